@@ -0,0 +1,142 @@
+use allocator as _;
+use criterion::{Criterion, Throughput};
+use easy_ext::ext;
+use itertools::Itertools as _;
+use ssz::SszReadDefault as _;
+use typenum::Unsigned as _;
+use types::{
+    deneb::primitives::{Blob, KzgCommitment, KzgProof},
+    preset::{Mainnet, Preset},
+};
+
+// The `unused_crate_dependencies` lint checks every crate in a package separately.
+// These are only used by other benchmarks in this package.
+// See <https://github.com/rust-lang/rust/issues/57274>.
+use anyhow as _;
+use binary_utils as _;
+use bls as _;
+use clock as _;
+use eth2_cache_utils as _;
+use eth2_libp2p as _;
+use execution_engine as _;
+use fork_choice_control as _;
+use fork_choice_store as _;
+use genesis as _;
+use hashing as _;
+use helper_functions as _;
+use im as _;
+use integer_sqrt as _;
+use interop as _;
+use num_integer as _;
+use once_cell as _;
+use openssl as _;
+use operation_pools as _;
+use serde_json as _;
+use serde_utils as _;
+use sha2 as _;
+use shuffling as _;
+use std_ext as _;
+use tap as _;
+use transition_functions as _;
+use tynm as _;
+
+// Criterion macros only add confusion.
+fn main() {
+    Criterion::default()
+        .configure_from_args()
+        .benchmark_trusted_setup()
+        .benchmark_verification::<Mainnet>()
+        .final_summary();
+}
+
+#[ext]
+impl Criterion {
+    fn benchmark_trusted_setup(&mut self) -> &mut Self {
+        // Only the first call loads the trusted setup. This measures the cost of later calls.
+        self.benchmark_group("trusted setup")
+            .throughput(Throughput::Elements(1))
+            .bench_function("kzg_utils::settings", |bencher| {
+                bencher.iter(kzg_utils::settings)
+            });
+
+        self
+    }
+
+    fn benchmark_verification<P: Preset>(&mut self) -> &mut Self {
+        let blobs_per_block = P::MaxBlobsPerBlock::USIZE;
+
+        let blobs = (0..).take(blobs_per_block).map(blob::<P>).collect_vec();
+
+        let commitments = blobs
+            .iter()
+            .map(|blob| {
+                kzg_utils::eip_4844::blob_to_kzg_commitment::<P>(blob)
+                    .expect("blob should be valid")
+            })
+            .collect_vec();
+
+        let proofs = blobs
+            .iter()
+            .zip(commitments.iter().copied())
+            .map(|(blob, commitment)| {
+                kzg_utils::eip_4844::compute_blob_kzg_proof::<P>(blob, commitment)
+                    .expect("blob and commitment should be valid")
+            })
+            .collect_vec();
+
+        self.benchmark_group(format!("{blobs_per_block} blobs"))
+            .throughput(Throughput::Elements(P::MaxBlobsPerBlock::U64))
+            .bench_function("kzg_utils::eip_4844::verify_blob_kzg_proof", |bencher| {
+                bencher.iter(|| verify_individually::<P>(&blobs, &commitments, &proofs))
+            })
+            .bench_function(
+                "kzg_utils::eip_4844::verify_blob_kzg_proof_batch",
+                |bencher| bencher.iter(|| verify_batch::<P>(&blobs, &commitments, &proofs)),
+            );
+
+        self
+    }
+}
+
+fn verify_individually<P: Preset>(
+    blobs: &[Blob<P>],
+    commitments: &[KzgCommitment],
+    proofs: &[KzgProof],
+) -> bool {
+    itertools::izip!(blobs, commitments, proofs).all(|(blob, commitment, proof)| {
+        kzg_utils::eip_4844::verify_blob_kzg_proof::<P>(blob, *commitment, *proof)
+            .expect("verification should not fail")
+    })
+}
+
+fn verify_batch<P: Preset>(
+    blobs: &[Blob<P>],
+    commitments: &[KzgCommitment],
+    proofs: &[KzgProof],
+) -> bool {
+    kzg_utils::eip_4844::verify_blob_kzg_proof_batch::<P>(
+        blobs,
+        commitments.iter().copied(),
+        proofs.iter().copied(),
+    )
+    .expect("verification should not fail")
+}
+
+fn blob<P: Preset>(seed: u8) -> Blob<P> {
+    let bytes = (0..=u8::MAX)
+        .cycle()
+        .take(P::BytesPerBlob::USIZE)
+        .enumerate()
+        .map(|(index, byte)| {
+            // Field elements are big-endian. Zeroing the most significant byte of each one keeps
+            // them below the modulus of the scalar field.
+            if index % 32 == 0 {
+                0
+            } else {
+                byte.wrapping_add(seed)
+            }
+        })
+        .collect_vec();
+
+    Blob::<P>::from_ssz_default(bytes).expect("bytes should have the size of a blob")
+}
@@ -10,7 +10,7 @@ use easy_ext::ext;
 use eth2_cache_utils::{goerli, holesky, LazyBeaconState};
 use helper_functions::accessors;
 use once_cell::unsync::Lazy;
-use operation_pools::AttestationPacker;
+use operation_pools::{AttestationPacker, DEFAULT_ATTESTATION_PACKING_TIME_BUDGET};
 use std_ext::ArcExt as _;
 use types::{config::Config, phase0::containers::Attestation, preset::Preset};
 
@@ -60,7 +60,7 @@ impl Criterion {
             let state = state.force().clone_arc();
             let latest_block_root = accessors::latest_block_root(&state);
 
-            AttestationPacker::new(config, latest_block_root, state, true)
+            AttestationPacker::new(config, latest_block_root, state, None)
                 .expect("AttestationPacker should be constructed successfully")
         });
 
@@ -77,6 +77,7 @@ impl Criterion {
                         packer.pack_proposable_attestations_greedily(
                             previous_aggregates,
                             current_aggregates,
+                            DEFAULT_ATTESTATION_PACKING_TIME_BUDGET,
                         )
                     })
                 },
@@ -98,7 +99,7 @@ impl Criterion {
             let state = state.force().clone_arc();
             let latest_block_root = accessors::latest_block_root(&state);
 
-            AttestationPacker::new(config, latest_block_root, state, true)
+            AttestationPacker::new(config, latest_block_root, state, None)
                 .expect("AttestationPacker should be constructed successfully")
         });
 
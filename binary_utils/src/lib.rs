@@ -1,11 +1,30 @@
-use std::io::Write as _;
+use core::future::Future;
+use std::io::{Result as IoResult, Write};
 
 use anyhow::Result;
 use chrono::{Local, SecondsFormat};
 use env_logger::{Builder, Env, Target, WriteStyle};
-use log::LevelFilter;
+use log::{LevelFilter, Record};
 use rayon::ThreadPoolBuilder;
 
+tokio::task_local! {
+    // Set by the HTTP APIs for the duration of each request.
+    // `log` has no spans, so this is the only way to get the ID into messages logged by handlers.
+    static REQUEST_ID: Box<str>;
+}
+
+/// Runs `future` with `request_id` included in every message it logs.
+///
+/// Work moved to other tasks or threads does not inherit the ID.
+pub async fn with_request_id<F: Future>(request_id: impl Into<Box<str>>, future: F) -> F::Output {
+    REQUEST_ID.scope(request_id.into(), future).await
+}
+
+#[must_use]
+pub fn current_request_id() -> Option<Box<str>> {
+    REQUEST_ID.try_with(Clone::clone).ok()
+}
+
 pub fn initialize_logger(
     module_path: &str,
     always_write_style: bool,
@@ -42,20 +61,7 @@ pub fn initialize_logger(
         .filter_module("web3", LevelFilter::Debug)
         .filter_module(module_path!(), LevelFilter::Info)
         .filter_module(module_path, LevelFilter::Info)
-        .format(|formatter, record| {
-            writeln!(
-                formatter,
-                "[{}] [{}] [{}] {}",
-                // This allocates a `String` only to write it to `formatter`, but that has a
-                // negligible effect on performance. `DateTime::format_with_items` with the same
-                // format is slower. Manual formatting with `core::fmt` is faster, however.
-                Local::now().to_rfc3339_opts(SecondsFormat::Millis, false),
-                //formatter.default_level_style(record.level()),
-                record.level(),
-                record.target(),
-                record.args(),
-            )
-        })
+        .format(|formatter, record| write_record(formatter, record))
         .target(Target::Stdout);
 
     if always_write_style {
@@ -73,6 +79,26 @@ pub fn initialize_logger(
     builder.try_init().map_err(Into::into)
 }
 
+fn write_record(writer: &mut impl Write, record: &Record) -> IoResult<()> {
+    // This allocates a `String` only to write it to `writer`, but that has a negligible effect on
+    // performance. `DateTime::format_with_items` with the same format is slower. Manual formatting
+    // with `core::fmt` is faster, however.
+    let timestamp = Local::now().to_rfc3339_opts(SecondsFormat::Millis, false);
+    let level = record.level();
+    let target = record.target();
+    let args = record.args();
+
+    match REQUEST_ID.try_with(|request_id| {
+        writeln!(
+            writer,
+            "[{timestamp}] [{level}] [{target}] [request_id: {request_id}] {args}",
+        )
+    }) {
+        Ok(result) => result,
+        Err(_) => writeln!(writer, "[{timestamp}] [{level}] [{target}] {args}"),
+    }
+}
+
 pub fn initialize_rayon() -> Result<()> {
     ThreadPoolBuilder::new()
         .thread_name(|index| format!("rayon-{index}"))
@@ -83,8 +109,42 @@ pub fn initialize_rayon() -> Result<()> {
 
 #[cfg(test)]
 mod tests {
+    use log::Level;
+
     use super::*;
 
+    #[tokio::test]
+    async fn request_id_is_included_in_messages_logged_in_scope() -> Result<()> {
+        let log = |message| -> Result<String> {
+            let mut output = vec![];
+
+            write_record(
+                &mut output,
+                &Record::builder()
+                    .args(format_args!("{message}"))
+                    .level(Level::Info)
+                    .target("http_api")
+                    .build(),
+            )?;
+
+            Ok(String::from_utf8(output)?)
+        };
+
+        let in_scope = with_request_id("7", async {
+            assert_eq!(current_request_id().as_deref(), Some("7"));
+            log("in scope")
+        })
+        .await?;
+
+        let out_of_scope = log("out of scope")?;
+
+        assert!(in_scope.ends_with("[INFO] [http_api] [request_id: 7] in scope\n"));
+        assert!(out_of_scope.ends_with("[INFO] [http_api] out of scope\n"));
+        assert_eq!(current_request_id(), None);
+
+        Ok(())
+    }
+
     // The error message will typically not show up in the output even with `--nocapture`.
     // That is because the main thread exits before the Rayon panic handler can log it.
     #[test]
@@ -7,6 +7,9 @@ use thiserror::Error;
 pub enum Error {
     #[error("decompression failed: {0:?}")]
     DecompressionFailed(BLST_ERROR),
+    #[error("key derivation failed: {0:?}")]
+    #[from(ignore)]
+    KeyDerivationFailed(BLST_ERROR),
     #[error("no public keys to aggregate")]
     NoPublicKeysToAggregate,
 }
@@ -71,6 +71,21 @@ impl Hash for SecretKey {
 }
 
 impl SecretKey {
+    /// [`derive_master_SK`](https://eips.ethereum.org/EIPS/eip-2333#derive_master_sk)
+    #[inline]
+    pub fn derive_master(seed: &[u8]) -> Result<Self, Error> {
+        RawSecretKey::derive_master_eip2333(seed)
+            .map(Self)
+            .map_err(Error::KeyDerivationFailed)
+    }
+
+    /// [`derive_child_SK`](https://eips.ethereum.org/EIPS/eip-2333#derive_child_sk)
+    #[inline]
+    #[must_use]
+    pub fn derive_child(&self, index: u32) -> Self {
+        Self(self.as_raw().derive_child_eip2333(index))
+    }
+
     #[inline]
     #[must_use]
     pub fn to_public_key(&self) -> PublicKey {
@@ -96,3 +111,33 @@ impl SecretKey {
         &self.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test case 0 from <https://eips.ethereum.org/EIPS/eip-2333#test-case-0>.
+    #[test]
+    fn secret_key_derivation_matches_eip_2333_test_vector() -> Result<(), Error> {
+        let seed = hex::decode(
+            "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e5349553\
+             1f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04",
+        )
+        .expect("seed should be valid hexadecimal");
+
+        let master = SecretKey::derive_master(seed.as_slice())?;
+        let child = master.derive_child(0);
+
+        assert_eq!(
+            hex::encode(master.to_bytes().as_ref()),
+            "0d7359d57963ab8fbbde1852dcf553fedbc31f464d80ee7d40ae683122b45070",
+        );
+
+        assert_eq!(
+            hex::encode(child.to_bytes().as_ref()),
+            "2d18bd6c14e6d15bf8b5085c9b74f3daae3b03cc2014770a599d8c1539e50f8e",
+        );
+
+        Ok(())
+    }
+}
@@ -1,18 +1,24 @@
-use core::time::Duration;
-use std::sync::Arc;
+use core::{num::NonZeroU64, time::Duration};
+use std::{
+    collections::BTreeMap,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
-use anyhow::{bail, ensure, Result};
+use anyhow::{bail, ensure, Error as AnyhowError, Result};
 use bls::PublicKeyBytes;
-use derive_more::Constructor;
+use futures::future::join_all;
 use helper_functions::signing::SignForAllForks;
 use itertools::Itertools as _;
-use log::{debug, info};
+use log::{debug, info, warn};
+use parking_lot::Mutex;
 use prometheus_metrics::Metrics;
 use reqwest::{Client, Response, StatusCode, Url};
 use ssz::SszHash as _;
 use thiserror::Error;
 use typenum::Unsigned as _;
 use types::{
+    bellatrix::primitives::Wei,
     combined::{ExecutionPayload, SignedBlindedBeaconBlock},
     config::Config as ChainConfig,
     nonstandard::{Phase, WithBlobsAndMev},
@@ -25,10 +31,12 @@ use types::{
 };
 
 use crate::{
+    bid_traces::{BidTrace, BidTraces},
     combined::{ExecutionPayloadAndBlobsBundle, SignedBuilderBid},
+    config,
     consts::BUILDER_PROPOSAL_DELAY_TOLERANCE,
     unphased::containers::SignedValidatorRegistrationV1,
-    BuilderConfig,
+    BuilderConfig, DEFAULT_BUILDER_BOOST_FACTOR,
 };
 
 const REQUEST_TIMEOUT: Duration = Duration::from_secs(BUILDER_PROPOSAL_DELAY_TOLERANCE);
@@ -38,10 +46,19 @@ const REQUEST_TIMEOUT: Duration = Duration::from_secs(BUILDER_PROPOSAL_DELAY_TOL
 pub enum BuilderApiError {
     #[error("bad request to Builder API (builder node response: {message})")]
     BadRequest { message: String },
+    #[error("builder bid ({bid}) is lower than the minimum accepted bid ({min_bid})")]
+    BidBelowMinimum { bid: Wei, min_bid: Wei },
     #[error("builder node internal error (builder node response: {message})")]
     BuilderNodeInternalError { message: String },
     #[error("{missing_blocks} consecutive missing blocks since head")]
     ConsecutiveMissingBlocks { missing_blocks: u64 },
+    #[error(
+        "local payload value ({local_value}) is not lower than \
+         builder bid adjusted by boost factor ({boosted_bid})"
+    )]
+    LocalPayloadMoreValuable { local_value: Wei, boosted_bid: Wei },
+    #[error("no builder relays configured")]
+    NoRelays,
     #[error("{missing_blocks} missing blocks in the last rolling epoch")]
     RollingEpochMissingBlocks { missing_blocks: u64 },
     #[error(
@@ -56,16 +73,36 @@ pub enum BuilderApiError {
          (computed: {computed}, response: {in_response})"
     )]
     VersionMismatch { computed: Phase, in_response: Phase },
+    #[error("builder boost factor is 0 and no local execution payload is available")]
+    ZeroBoostFactorWithoutLocalPayload,
 }
 
-#[derive(Constructor)]
 pub struct Api {
     config: BuilderConfig,
     client: Client,
     metrics: Option<Arc<Metrics>>,
+    bid_traces: BidTraces,
+    bid_relays: Mutex<BTreeMap<Slot, Vec<(ExecutionBlockHash, Url)>>>,
 }
 
 impl Api {
+    #[must_use]
+    pub fn new(config: BuilderConfig, client: Client, metrics: Option<Arc<Metrics>>) -> Self {
+        Self {
+            config,
+            client,
+            metrics,
+            bid_traces: BidTraces::default(),
+            bid_relays: Mutex::default(),
+        }
+    }
+
+    /// Returns traces of bids received from relays, optionally only those for `slot`.
+    #[must_use]
+    pub fn bid_traces(&self, slot: Option<Slot>) -> Vec<BidTrace> {
+        self.bid_traces.traces(slot)
+    }
+
     pub fn can_use_builder_api<P: Preset>(
         &self,
         slot: Slot,
@@ -106,6 +143,43 @@ impl Api {
         Ok(())
     }
 
+    /// Decides whether a builder bid should be used instead of a locally built payload.
+    ///
+    /// `local_value` is [`None`] when the execution engine failed to produce a payload.
+    /// Ties are resolved in favor of the local payload.
+    /// A boost factor of 0 rejects all bids, even when there is no local payload to fall back to.
+    pub fn can_use_builder_bid(
+        &self,
+        local_value: Option<Wei>,
+        bid: Wei,
+    ) -> Result<(), BuilderApiError> {
+        if let Some(min_bid) = self.config.min_builder_bid {
+            if bid < min_bid {
+                return Err(BuilderApiError::BidBelowMinimum { bid, min_bid });
+            }
+        }
+
+        let Some(local_value) = local_value else {
+            // A boost factor of 0 means builder bids must never be used.
+            if self.config.builder_boost_factor == 0 {
+                return Err(BuilderApiError::ZeroBoostFactorWithoutLocalPayload);
+            }
+
+            return Ok(());
+        };
+
+        let boosted_bid = boost_bid(bid, self.config.builder_boost_factor);
+
+        if boosted_bid <= local_value {
+            return Err(BuilderApiError::LocalPayloadMoreValuable {
+                local_value,
+                boosted_bid,
+            });
+        }
+
+        Ok(())
+    }
+
     pub async fn register_validators(
         &self,
         validator_registrations: &[SignedValidatorRegistrationV1],
@@ -117,7 +191,47 @@ impl Api {
 
         debug!("registering validators: {validator_registrations:?}");
 
-        let url = self.url("/eth/v1/builder/validators")?;
+        let results = join_all(self.config.relays.iter().map(|relay| async move {
+            let registrations = validator_registrations
+                .iter()
+                .filter(|registration| relay.is_enabled_for(registration.message.pubkey))
+                .collect_vec();
+
+            if registrations.is_empty() {
+                return Ok(());
+            }
+
+            self.request_validator_registration(&relay.url, &registrations)
+                .await
+                .map_err(|error| {
+                    warn!(
+                        "failed to register validators with relay {}: {error:?}",
+                        relay_name(&relay.url),
+                    );
+
+                    error
+                })
+        }))
+        .await;
+
+        let (registered, errors): (Vec<_>, Vec<_>) = results.into_iter().partition_result();
+
+        // Registering with any relay is enough to receive bids for the registered validators.
+        if registered.is_empty() {
+            if let Some(error) = errors.into_iter().next() {
+                return Err(error);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn request_validator_registration(
+        &self,
+        relay_url: &Url,
+        validator_registrations: &[&SignedValidatorRegistrationV1],
+    ) -> Result<()> {
+        let url = relay_url.join("/eth/v1/builder/validators")?;
         let response = self
             .client
             .post(url)
@@ -132,6 +246,10 @@ impl Api {
         Ok(())
     }
 
+    /// Queries all relays enabled for `pubkey` concurrently and returns the most valuable bid.
+    ///
+    /// Returns [`None`] if no relay is enabled for `pubkey` or none of them has a bid.
+    /// Errors are only returned if every enabled relay failed.
     pub async fn get_execution_payload_header<P: Preset>(
         &self,
         chain_config: &ChainConfig,
@@ -145,11 +263,149 @@ impl Api {
                 .start_timer()
         });
 
-        let url = self.url(&format!(
+        let relays = self
+            .config
+            .relays
+            .iter()
+            .filter(|relay| relay.is_enabled_for(pubkey))
+            .collect_vec();
+
+        let results = join_all(relays.iter().map(|relay| {
+            self.relay_execution_payload_header(&relay.url, chain_config, slot, parent_hash, pubkey)
+        }))
+        .await;
+
+        let mut bids = vec![];
+        let mut any_relay_without_bid = false;
+        let mut first_error = None;
+
+        for (relay, result) in relays.into_iter().zip(results) {
+            match result {
+                Ok(Some(builder_bid)) => bids.push((&relay.url, builder_bid)),
+                Ok(None) => any_relay_without_bid = true,
+                Err(error) => {
+                    first_error.get_or_insert(error);
+                }
+            }
+        }
+
+        let Some(block_hash) = bids
+            .iter()
+            .max_by_key(|(_, builder_bid)| builder_bid.mev())
+            .map(|(_, builder_bid)| builder_bid.block_hash())
+        else {
+            return match first_error {
+                Some(error) if !any_relay_without_bid => Err(error),
+                _ => Ok(None),
+            };
+        };
+
+        // The same block may be offered by multiple relays.
+        // Any of them should be able to reveal the payload for it.
+        let (offering_relays, mut best_bids): (Vec<_>, Vec<_>) = bids
+            .into_iter()
+            .filter(|(_, builder_bid)| builder_bid.block_hash() == block_hash)
+            .map(|(relay_url, builder_bid)| (relay_url.clone(), builder_bid))
+            .unzip();
+
+        if offering_relays.len() > 1 {
+            info!(
+                "best bid for slot {slot} (block hash: {block_hash:?}) offered by {} relays",
+                offering_relays.len(),
+            );
+        }
+
+        {
+            let mut bid_relays = self.bid_relays.lock();
+            let recent_bid_relays =
+                bid_relays.split_off(&slot.saturating_sub(P::SlotsPerEpoch::U64));
+
+            *bid_relays = recent_bid_relays;
+
+            bid_relays
+                .entry(slot)
+                .or_default()
+                .extend(offering_relays.into_iter().map(|url| (block_hash, url)));
+        }
+
+        Ok(best_bids.pop())
+    }
+
+    async fn relay_execution_payload_header<P: Preset>(
+        &self,
+        relay_url: &Url,
+        chain_config: &ChainConfig,
+        slot: Slot,
+        parent_hash: ExecutionBlockHash,
+        pubkey: PublicKeyBytes,
+    ) -> Result<Option<SignedBuilderBid<P>>> {
+        let relay = relay_name(relay_url);
+
+        let relay_timer = self.metrics.as_ref().and_then(|metrics| {
+            prometheus_metrics::start_timer_vec(&metrics.builder_relay_get_header_times, &relay)
+        });
+
+        let result = self
+            .request_execution_payload_header(relay_url, chain_config, slot, parent_hash, pubkey)
+            .await;
+
+        drop(relay_timer);
+
+        match &result {
+            Ok(Some(builder_bid)) => {
+                let trace = BidTrace {
+                    relay,
+                    slot,
+                    parent_hash,
+                    block_hash: builder_bid.block_hash(),
+                    builder_pubkey: builder_bid.pubkey(),
+                    proposer_pubkey: pubkey,
+                    value: builder_bid.mev(),
+                    received_at: SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .unwrap_or_default()
+                        .as_secs(),
+                };
+
+                info!(
+                    "received bid from {} for slot {slot} \
+                     (parent hash: {parent_hash:?}, block hash: {:?}, value: {}, builder: {:?})",
+                    trace.relay, trace.block_hash, trace.value, trace.builder_pubkey,
+                );
+
+                self.bid_traces.record(trace);
+            }
+            Ok(None) => {}
+            Err(error) => {
+                let fault = fault_type(error);
+
+                debug!("relay {relay} failed to provide bid for slot {slot} ({fault}): {error:?}");
+
+                if let Some(metrics) = self.metrics.as_ref() {
+                    metrics.register_builder_relay_fault(&relay, fault);
+                }
+            }
+        }
+
+        result
+    }
+
+    async fn request_execution_payload_header<P: Preset>(
+        &self,
+        relay_url: &Url,
+        chain_config: &ChainConfig,
+        slot: Slot,
+        parent_hash: ExecutionBlockHash,
+        pubkey: PublicKeyBytes,
+    ) -> Result<Option<SignedBuilderBid<P>>> {
+        let url = relay_url.join(&format!(
             "/eth/v1/builder/header/{slot}/{parent_hash:?}/{pubkey:?}"
         ))?;
 
-        debug!("getting execution payload header from {url}");
+        debug!(
+            "getting execution payload header from {}",
+            config::redact_url(&url),
+        );
 
         let response = self.client.get(url).timeout(REQUEST_TIMEOUT).send().await?;
         let response = handle_error(response).await?;
@@ -186,11 +442,12 @@ impl Api {
             }
         }
 
-        info!("received execution payload header from builder for slot {slot}");
-
         Ok(Some(builder_bid))
     }
 
+    /// Submits `block` to the relays that offered its header, then to the remaining ones.
+    ///
+    /// Relays are tried one at a time until one of them reveals the payload.
     pub async fn post_blinded_block<P: Preset>(
         &self,
         chain_config: &ChainConfig,
@@ -202,14 +459,97 @@ impl Api {
             .as_ref()
             .map(|metrics| metrics.builder_post_blinded_block_times.start_timer());
 
-        let url = self.url("/eth/v1/builder/blinded_blocks")?;
+        let slot = block.message().slot();
+        let block_hash = block.execution_payload_header().block_hash();
+
+        let offering_relays = self
+            .bid_relays
+            .lock()
+            .get(&slot)
+            .into_iter()
+            .flatten()
+            .filter(|(offered_hash, _)| *offered_hash == block_hash)
+            .map(|(_, relay_url)| relay_url.clone())
+            .collect_vec();
+
+        let fallback_relays = self
+            .config
+            .relays
+            .iter()
+            .map(|relay| &relay.url)
+            .filter(|relay_url| !offering_relays.contains(relay_url))
+            .cloned()
+            .collect_vec();
+
+        let mut last_error = None;
+
+        for relay_url in offering_relays.into_iter().chain(fallback_relays) {
+            match self
+                .relay_blinded_block(&relay_url, chain_config, genesis_time, block)
+                .await
+            {
+                Ok(response) => return Ok(response),
+                Err(error) => {
+                    warn!(
+                        "relay {} failed to reveal execution payload for slot {slot}: {error:?}",
+                        relay_name(&relay_url),
+                    );
+
+                    last_error = Some(error);
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| BuilderApiError::NoRelays.into()))
+    }
+
+    async fn relay_blinded_block<P: Preset>(
+        &self,
+        relay_url: &Url,
+        chain_config: &ChainConfig,
+        genesis_time: UnixSeconds,
+        block: &SignedBlindedBeaconBlock<P>,
+    ) -> Result<WithBlobsAndMev<ExecutionPayload<P>, P>> {
+        let relay = relay_name(relay_url);
+
+        let relay_timer = self.metrics.as_ref().and_then(|metrics| {
+            prometheus_metrics::start_timer_vec(
+                &metrics.builder_relay_post_blinded_block_times,
+                &relay,
+            )
+        });
+
+        let result = self
+            .request_execution_payload(relay_url, chain_config, genesis_time, block)
+            .await;
+
+        drop(relay_timer);
+
+        if let Err(error) = &result {
+            if let Some(metrics) = self.metrics.as_ref() {
+                metrics.register_builder_relay_fault(&relay, fault_type(error));
+            }
+        }
+
+        result
+    }
+
+    async fn request_execution_payload<P: Preset>(
+        &self,
+        relay_url: &Url,
+        chain_config: &ChainConfig,
+        genesis_time: UnixSeconds,
+        block: &SignedBlindedBeaconBlock<P>,
+    ) -> Result<WithBlobsAndMev<ExecutionPayload<P>, P>> {
+        let url = relay_url.join("/eth/v1/builder/blinded_blocks")?;
 
         let (next_interval, remaining_time) =
             clock::next_interval_with_remaining_time(chain_config, genesis_time)?;
 
         debug!(
-            "posting blinded block to {url} with timeout of {remaining_time:?} \
+            "posting blinded block to {} with timeout of {remaining_time:?} \
              before next interval {next_interval:?}",
+            config::redact_url(&url),
         );
 
         let block_root = block.message().hash_tree_root();
@@ -250,9 +590,29 @@ impl Api {
 
         Ok(response)
     }
+}
 
-    fn url(&self, path: &str) -> Result<Url> {
-        self.config.builder_api_url.join(path).map_err(Into::into)
+// Relay URLs may contain credentials, so only the origin is used in logs, traces and metrics.
+fn relay_name(relay_url: &Url) -> String {
+    relay_url.origin().ascii_serialization()
+}
+
+fn fault_type(error: &AnyhowError) -> &'static str {
+    if let Some(error) = error.downcast_ref::<BuilderApiError>() {
+        return match error {
+            BuilderApiError::BadRequest { .. } => "bad_request",
+            BuilderApiError::BuilderNodeInternalError { .. } => "internal_error",
+            BuilderApiError::RootMismatch { .. } => "root_mismatch",
+            BuilderApiError::VersionMismatch { .. } => "version_mismatch",
+            _ => "other",
+        };
+    }
+
+    match error.downcast_ref::<reqwest::Error>() {
+        Some(error) if error.is_timeout() => "timeout",
+        Some(error) if error.is_decode() => "invalid_response",
+        Some(_) => "request_failed",
+        None => "invalid_bid",
     }
 }
 
@@ -270,6 +630,19 @@ async fn handle_error(response: Response) -> Result<Response> {
     Ok(response)
 }
 
+// Dividing first loses less than `boost_factor` Wei of precision, which is negligible for bids.
+// The product can still exceed `Uint256::MAX` for very large boost factors, so it saturates.
+fn boost_bid(bid: Wei, boost_factor: u64) -> Wei {
+    if boost_factor == DEFAULT_BUILDER_BOOST_FACTOR {
+        return bid;
+    }
+
+    let divisor = NonZeroU64::new(DEFAULT_BUILDER_BOOST_FACTOR)
+        .expect("DEFAULT_BUILDER_BOOST_FACTOR is nonzero");
+
+    (bid / divisor).saturating_mul(Wei::from_u64(boost_factor))
+}
+
 fn validate_phase(computed: Phase, in_response: Phase) -> Result<()> {
     ensure!(
         computed == in_response,
@@ -284,14 +657,23 @@ fn validate_phase(computed: Phase, in_response: Phase) -> Result<()> {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashSet;
+
+    use bls::{SecretKey, SignatureBytes};
     use eth2_cache_utils::mainnet;
+    use httpmock::{Method, Mock, MockServer};
     use reqwest::{Client, Url};
+    use serde_json::{json, Value};
     use test_case::test_case;
-    use types::{preset::Mainnet, traits::SignedBeaconBlock as _};
+    use types::{
+        bellatrix::containers::ExecutionPayloadHeader, preset::Mainnet,
+        traits::SignedBeaconBlock as _,
+    };
 
     use crate::{
+        bellatrix::containers::BuilderBid as BellatrixBuilderBid,
         config::{DEFAULT_BUILDER_MAX_SKIPPED_SLOTS, DEFAULT_BUILDER_MAX_SKIPPED_SLOTS_PER_EPOCH},
-        BuilderApi, BuilderConfig,
+        BuilderApi, BuilderConfig, RelayConfig,
     };
 
     use super::*;
@@ -327,11 +709,14 @@ mod tests {
     ) -> Result<(), BuilderApiError> {
         let api = BuilderApi::new(
             BuilderConfig {
-                builder_api_url: Url::parse("http://localhost")
-                    .expect("http://localhost should be a valid URL"),
+                relays: vec![RelayConfig::for_all_validators(
+                    Url::parse("http://localhost").expect("http://localhost should be a valid URL"),
+                )],
+                builder_boost_factor: DEFAULT_BUILDER_BOOST_FACTOR,
                 builder_disable_checks: false,
                 builder_max_skipped_slots_per_epoch: DEFAULT_BUILDER_MAX_SKIPPED_SLOTS_PER_EPOCH,
                 builder_max_skipped_slots: DEFAULT_BUILDER_MAX_SKIPPED_SLOTS,
+                min_builder_bid: None,
             },
             Client::new(),
             None,
@@ -340,6 +725,267 @@ mod tests {
         api.can_use_builder_api::<Mainnet>(slot, nonempty_slots)
     }
 
+    #[test_case(100, None, Some(10), 11 => Ok(()); "higher bid")]
+    #[test_case(100, None, Some(10), 10 => Err(BuilderApiError::LocalPayloadMoreValuable {
+        local_value: Wei::from_u64(10),
+        boosted_bid: Wei::from_u64(10),
+    }); "equal values prefer local payload")]
+    #[test_case(100, None, None, 1 => Ok(()); "no local payload")]
+    #[test_case(0, None, Some(0), 1000 => Err(BuilderApiError::LocalPayloadMoreValuable {
+        local_value: Wei::ZERO,
+        boosted_bid: Wei::ZERO,
+    }); "zero boost factor always prefers local payload")]
+    #[test_case(50, None, Some(600), 1000 => Err(BuilderApiError::LocalPayloadMoreValuable {
+        local_value: Wei::from_u64(600),
+        boosted_bid: Wei::from_u64(500),
+    }); "boost factor below 100 penalizes builder")]
+    #[test_case(0, None, None, 1000 => Err(BuilderApiError::ZeroBoostFactorWithoutLocalPayload);
+        "zero boost factor without local payload")]
+    #[test_case(200, None, Some(1500), 1000 => Ok(()); "boost factor above 100 favors builder")]
+    #[test_case(100, Some(100), None, 99 => Err(BuilderApiError::BidBelowMinimum {
+        bid: Wei::from_u64(99),
+        min_bid: Wei::from_u64(100),
+    }); "bid below minimum")]
+    fn builder_bid_selection(
+        builder_boost_factor: u64,
+        min_builder_bid: Option<u64>,
+        local_value: Option<u64>,
+        bid: u64,
+    ) -> Result<(), BuilderApiError> {
+        let api = BuilderApi::new(
+            BuilderConfig {
+                relays: vec![RelayConfig::for_all_validators(
+                    Url::parse("http://localhost").expect("http://localhost should be a valid URL"),
+                )],
+                builder_boost_factor,
+                builder_disable_checks: false,
+                builder_max_skipped_slots_per_epoch: DEFAULT_BUILDER_MAX_SKIPPED_SLOTS_PER_EPOCH,
+                builder_max_skipped_slots: DEFAULT_BUILDER_MAX_SKIPPED_SLOTS,
+                min_builder_bid: min_builder_bid.map(Wei::from_u64),
+            },
+            Client::new(),
+            None,
+        );
+
+        api.can_use_builder_bid(local_value.map(Wei::from_u64), Wei::from_u64(bid))
+    }
+
+    #[test]
+    fn boost_bid_saturates_on_overflow() {
+        assert_eq!(boost_bid(Wei::MAX, u64::MAX), Wei::MAX);
+        assert_eq!(
+            boost_bid(Wei::from_u64(1000), u64::MAX),
+            Wei::from_u64(10) * Wei::from_u64(u64::MAX)
+        );
+    }
+
+    #[tokio::test]
+    async fn most_valuable_valid_bid_is_selected() -> Result<()> {
+        let chain_config = ChainConfig::mainnet().start_and_stay_in(Phase::Bellatrix);
+        let best_hash = ExecutionBlockHash::repeat_byte(3);
+
+        let low_bid = MockServer::start();
+        let best_bid = MockServer::start();
+        let same_best_bid = MockServer::start();
+        let invalid_bid = MockServer::start();
+        let no_bid = MockServer::start();
+        let failing = MockServer::start();
+
+        serve_header(&low_bid, 200, Some(bid_json(&chain_config, 1, 10, true)));
+        serve_header(&best_bid, 200, Some(bid_json(&chain_config, 3, 30, true)));
+        serve_header(
+            &same_best_bid,
+            200,
+            Some(bid_json(&chain_config, 3, 30, true)),
+        );
+        serve_header(
+            &invalid_bid,
+            200,
+            Some(bid_json(&chain_config, 4, 100, false)),
+        );
+        serve_header(&no_bid, 204, None);
+        serve_header(&failing, 500, None);
+
+        let servers = [
+            &low_bid,
+            &best_bid,
+            &same_best_bid,
+            &invalid_bid,
+            &no_bid,
+            &failing,
+        ];
+
+        let api = api_with_relays(servers.map(relay_url).map(RelayConfig::for_all_validators));
+
+        let builder_bid = api
+            .get_execution_payload_header::<Mainnet>(
+                &chain_config,
+                SLOT,
+                ExecutionBlockHash::zero(),
+                PublicKeyBytes::default(),
+            )
+            .await?
+            .expect("a relay should have provided a valid bid");
+
+        assert_eq!(builder_bid.block_hash(), best_hash);
+        assert_eq!(builder_bid.mev(), Wei::from_u64(30));
+
+        // Both relays that offered the chosen block are tried first when revealing the payload.
+        assert_eq!(
+            api.bid_relays.lock()[&SLOT],
+            [
+                (best_hash, relay_url(&best_bid)),
+                (best_hash, relay_url(&same_best_bid)),
+            ],
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn relays_not_enabled_for_proposer_are_not_queried() -> Result<()> {
+        let chain_config = ChainConfig::mainnet().start_and_stay_in(Phase::Bellatrix);
+
+        let enabled = MockServer::start();
+        let disabled = MockServer::start();
+
+        let enabled_mock = serve_header(&enabled, 200, Some(bid_json(&chain_config, 1, 10, true)));
+        let disabled_mock =
+            serve_header(&disabled, 200, Some(bid_json(&chain_config, 2, 20, true)));
+
+        let api = api_with_relays([
+            RelayConfig::for_all_validators(relay_url(&enabled)),
+            RelayConfig {
+                url: relay_url(&disabled),
+                enabled_validators: Some(HashSet::new()),
+            },
+        ]);
+
+        let builder_bid = api
+            .get_execution_payload_header::<Mainnet>(
+                &chain_config,
+                SLOT,
+                ExecutionBlockHash::zero(),
+                PublicKeyBytes::default(),
+            )
+            .await?
+            .expect("the enabled relay should have provided a bid");
+
+        assert_eq!(builder_bid.mev(), Wei::from_u64(10));
+        assert_eq!(enabled_mock.hits(), 1);
+        assert_eq!(disabled_mock.hits(), 0);
+
+        Ok(())
+    }
+
+    #[test_case(&[500, 500] => false; "all relays failed")]
+    #[test_case(&[500, 204] => true; "one relay failed and one had no bid")]
+    #[test_case(&[204, 204] => true; "no relay had a bid")]
+    #[tokio::test]
+    async fn missing_bids_are_not_errors_unless_every_relay_failed(statuses: &[u16]) -> bool {
+        let chain_config = ChainConfig::mainnet().start_and_stay_in(Phase::Bellatrix);
+
+        let servers = statuses
+            .iter()
+            .map(|status| {
+                let server = MockServer::start();
+                serve_header(&server, *status, None);
+                server
+            })
+            .collect_vec();
+
+        let api = api_with_relays(
+            servers
+                .iter()
+                .map(relay_url)
+                .map(RelayConfig::for_all_validators),
+        );
+
+        api.get_execution_payload_header::<Mainnet>(
+            &chain_config,
+            SLOT,
+            ExecutionBlockHash::zero(),
+            PublicKeyBytes::default(),
+        )
+        .await
+        .is_ok_and(|builder_bid| builder_bid.is_none())
+    }
+
+    const SLOT: Slot = 1;
+
+    fn api_with_relays(relays: impl IntoIterator<Item = RelayConfig>) -> BuilderApi {
+        BuilderApi::new(
+            BuilderConfig {
+                relays: relays.into_iter().collect(),
+                builder_boost_factor: DEFAULT_BUILDER_BOOST_FACTOR,
+                builder_disable_checks: false,
+                builder_max_skipped_slots_per_epoch: DEFAULT_BUILDER_MAX_SKIPPED_SLOTS_PER_EPOCH,
+                builder_max_skipped_slots: DEFAULT_BUILDER_MAX_SKIPPED_SLOTS,
+                min_builder_bid: None,
+            },
+            Client::new(),
+            None,
+        )
+    }
+
+    fn relay_url(server: &MockServer) -> Url {
+        Url::parse(&server.base_url()).expect("mock server URL should be valid")
+    }
+
+    fn serve_header<'server>(
+        server: &'server MockServer,
+        status: u16,
+        body: Option<Value>,
+    ) -> Mock<'server> {
+        server.mock(|when, then| {
+            when.method(Method::GET)
+                .path_contains("/eth/v1/builder/header/");
+
+            let then = then.status(status);
+
+            if let Some(body) = body {
+                then.json_body(body);
+            }
+        })
+    }
+
+    fn bid_json(
+        chain_config: &ChainConfig,
+        block_hash_byte: u8,
+        value: u64,
+        valid_signature: bool,
+    ) -> Value {
+        let secret_key = SecretKey::derive_master(&[block_hash_byte; 32])
+            .expect("seed should be long enough to derive a key");
+
+        let message = BellatrixBuilderBid::<Mainnet> {
+            header: Box::new(ExecutionPayloadHeader {
+                block_hash: ExecutionBlockHash::repeat_byte(block_hash_byte),
+                ..ExecutionPayloadHeader::default()
+            }),
+            value: Wei::from_u64(value),
+            pubkey: secret_key.to_public_key().into(),
+        };
+
+        let signature = if valid_signature {
+            message.sign(chain_config, &secret_key).into()
+        } else {
+            SignatureBytes::empty()
+        };
+
+        json!({
+            "version": "bellatrix",
+            "data": {
+                "message": {
+                    "header": message.header,
+                    "value": message.value,
+                    "pubkey": message.pubkey,
+                },
+                "signature": signature,
+            },
+        })
+    }
+
     fn nonempty_slots_in_mainnet() -> impl Iterator<Item = Slot> {
         mainnet::BEACON_BLOCKS_UP_TO_SLOT_128
             .force()
@@ -0,0 +1,103 @@
+use std::collections::VecDeque;
+
+use bls::PublicKeyBytes;
+use parking_lot::Mutex;
+use serde::Serialize;
+use types::{
+    bellatrix::primitives::Wei,
+    phase0::primitives::{ExecutionBlockHash, Slot, UnixSeconds},
+};
+
+// Validators propose rarely, so this covers a long period even for large setups.
+const MAX_BID_TRACES: usize = 1 << 12;
+
+/// A summary of a builder bid that passed validation.
+#[derive(Clone, Debug, Serialize)]
+pub struct BidTrace {
+    pub relay: String,
+    #[serde(with = "serde_utils::string_or_native")]
+    pub slot: Slot,
+    pub parent_hash: ExecutionBlockHash,
+    pub block_hash: ExecutionBlockHash,
+    pub builder_pubkey: PublicKeyBytes,
+    pub proposer_pubkey: PublicKeyBytes,
+    pub value: Wei,
+    #[serde(with = "serde_utils::string_or_native")]
+    pub received_at: UnixSeconds,
+}
+
+/// Most recent bid traces in the order they were received.
+#[derive(Default)]
+pub struct BidTraces {
+    traces: Mutex<VecDeque<BidTrace>>,
+}
+
+impl BidTraces {
+    pub fn record(&self, trace: BidTrace) {
+        let mut traces = self.traces.lock();
+
+        if traces.len() == MAX_BID_TRACES {
+            traces.pop_front();
+        }
+
+        traces.push_back(trace);
+    }
+
+    #[must_use]
+    pub fn traces(&self, slot: Option<Slot>) -> Vec<BidTrace> {
+        self.traces
+            .lock()
+            .iter()
+            .filter(|trace| slot.map_or(true, |slot| trace.slot == slot))
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trace(slot: Slot) -> BidTrace {
+        BidTrace {
+            relay: "http://localhost".to_owned(),
+            slot,
+            parent_hash: ExecutionBlockHash::zero(),
+            block_hash: ExecutionBlockHash::zero(),
+            builder_pubkey: PublicKeyBytes::zero(),
+            proposer_pubkey: PublicKeyBytes::zero(),
+            value: Wei::from_u64(slot),
+            received_at: 0,
+        }
+    }
+
+    #[test]
+    fn bid_traces_keeps_most_recent_traces() {
+        let bid_traces = BidTraces::default();
+
+        for slot in 0..=MAX_BID_TRACES as Slot {
+            bid_traces.record(trace(slot));
+        }
+
+        let traces = bid_traces.traces(None);
+
+        assert_eq!(traces.len(), MAX_BID_TRACES);
+        assert_eq!(traces.first().map(|trace| trace.slot), Some(1));
+        assert_eq!(
+            traces.last().map(|trace| trace.slot),
+            Some(MAX_BID_TRACES as Slot),
+        );
+    }
+
+    #[test]
+    fn bid_traces_filters_by_slot() {
+        let bid_traces = BidTraces::default();
+
+        bid_traces.record(trace(1));
+        bid_traces.record(trace(2));
+        bid_traces.record(trace(2));
+
+        assert_eq!(bid_traces.traces(Some(2)).len(), 2);
+        assert!(bid_traces.traces(Some(3)).is_empty());
+    }
+}
@@ -1,8 +1,9 @@
 pub use crate::{
-    api::Api as BuilderApi,
+    api::{Api as BuilderApi, BuilderApiError},
+    bid_traces::BidTrace,
     config::{
-        Config as BuilderConfig, DEFAULT_BUILDER_MAX_SKIPPED_SLOTS,
-        DEFAULT_BUILDER_MAX_SKIPPED_SLOTS_PER_EPOCH,
+        Config as BuilderConfig, RelayConfig, DEFAULT_BUILDER_BOOST_FACTOR,
+        DEFAULT_BUILDER_MAX_SKIPPED_SLOTS, DEFAULT_BUILDER_MAX_SKIPPED_SLOTS_PER_EPOCH,
     },
 };
 
@@ -26,5 +27,6 @@ mod deneb {
 }
 
 mod api;
+mod bid_traces;
 mod config;
 mod signing;
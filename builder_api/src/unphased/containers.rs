@@ -20,7 +20,7 @@ pub struct ValidatorRegistrationV1 {
     pub pubkey: PublicKeyBytes,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, Ssz)]
 pub struct SignedValidatorRegistrationV1 {
     pub message: ValidatorRegistrationV1,
     pub signature: SignatureBytes,
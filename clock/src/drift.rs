@@ -0,0 +1,229 @@
+use core::time::Duration;
+use std::{
+    collections::VecDeque,
+    time::{Instant, SystemTime},
+};
+
+use anyhow::Result;
+use parking_lot::Mutex;
+use types::{
+    config::Config,
+    phase0::{consts::INTERVALS_PER_SLOT, primitives::UnixSeconds},
+};
+
+// Blocks from a single slow or malicious proposer should not be enough to trigger warnings.
+const MAX_SAMPLES: usize = 32;
+const MIN_SAMPLES: usize = 8;
+
+// `MAXIMUM_GOSSIP_CLOCK_DISPARITY` from the networking specification.
+const MAXIMUM_GOSSIP_CLOCK_DISPARITY_MILLIS: i64 = 500;
+
+// Skew is estimated again for every block. Warning about it every time would flood the log.
+const WARNING_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ClockSkew {
+    Behind(Duration),
+    Ahead(Duration),
+}
+
+impl ClockSkew {
+    #[must_use]
+    pub const fn duration(self) -> Duration {
+        match self {
+            Self::Behind(duration) | Self::Ahead(duration) => duration,
+        }
+    }
+
+    /// Signed skew in milliseconds. Positive values mean the local clock is ahead.
+    #[must_use]
+    pub fn as_millis(self) -> i64 {
+        let millis = i64::try_from(self.duration().as_millis()).unwrap_or(i64::MAX);
+
+        match self {
+            Self::Behind(_) => millis.saturating_neg(),
+            Self::Ahead(_) => millis,
+        }
+    }
+}
+
+/// Estimates how far the local clock is from the rest of the network.
+///
+/// Honest proposers publish blocks at the start of their slot and most blocks reach us well
+/// before the attestation deadline. Blocks that consistently appear to arrive before their slot
+/// starts mean the local clock is behind. Blocks that consistently appear to arrive after the
+/// attestation deadline mean it is ahead. Only the median arrival time is considered, so a few
+/// late or early blocks have no effect.
+#[derive(Default)]
+pub struct ClockDrift {
+    arrival_offsets_millis: Mutex<VecDeque<i64>>,
+    last_warning: Mutex<Option<Instant>>,
+}
+
+impl ClockDrift {
+    pub fn observe_block_arrival(&self, slot_timestamp: UnixSeconds) -> Result<()> {
+        let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)?;
+        let now_millis = i64::try_from(now.as_millis())?;
+        let slot_start_millis = i64::try_from(slot_timestamp)?.saturating_mul(1000);
+
+        self.observe_arrival_offset(now_millis.saturating_sub(slot_start_millis));
+
+        Ok(())
+    }
+
+    /// Returns [`None`] if the clock appears to be accurate or there are too few samples to tell.
+    #[must_use]
+    pub fn estimated_skew(&self, config: &Config) -> Option<ClockSkew> {
+        let median = self.median_arrival_offset_millis()?;
+        let attestation_deadline = attestation_deadline_millis(config);
+
+        if median < -MAXIMUM_GOSSIP_CLOCK_DISPARITY_MILLIS {
+            return Some(ClockSkew::Behind(duration_from_millis(median)));
+        }
+
+        if median > attestation_deadline {
+            return Some(ClockSkew::Ahead(duration_from_millis(
+                median - attestation_deadline,
+            )));
+        }
+
+        None
+    }
+
+    /// Whether the clock is so far off that blocks produced with it would be rejected or orphaned.
+    ///
+    /// Blocks from the future are ignored by peers and blocks published after the attestation
+    /// deadline are unlikely to become canonical.
+    #[must_use]
+    pub fn is_grossly_skewed(&self, config: &Config) -> bool {
+        self.estimated_skew(config).is_some_and(|skew| {
+            skew.duration() >= duration_from_millis(attestation_deadline_millis(config))
+        })
+    }
+
+    /// Returns `true` if a warning about skew should be logged at `now`.
+    ///
+    /// Returns `true` at most once per [`WARNING_INTERVAL`].
+    pub fn should_warn(&self, now: Instant) -> bool {
+        let mut last_warning = self.last_warning.lock();
+
+        if last_warning.is_some_and(|last_warning| {
+            now.saturating_duration_since(last_warning) < WARNING_INTERVAL
+        }) {
+            return false;
+        }
+
+        *last_warning = Some(now);
+
+        true
+    }
+
+    fn observe_arrival_offset(&self, offset_millis: i64) {
+        let mut offsets = self.arrival_offsets_millis.lock();
+
+        if offsets.len() == MAX_SAMPLES {
+            offsets.pop_front();
+        }
+
+        offsets.push_back(offset_millis);
+    }
+
+    fn median_arrival_offset_millis(&self) -> Option<i64> {
+        let mut offsets = self
+            .arrival_offsets_millis
+            .lock()
+            .iter()
+            .copied()
+            .collect::<Vec<_>>();
+
+        if offsets.len() < MIN_SAMPLES {
+            return None;
+        }
+
+        let middle = offsets.len() / 2;
+        let (_, median, _) = offsets.select_nth_unstable(middle);
+
+        Some(*median)
+    }
+}
+
+fn attestation_deadline_millis(config: &Config) -> i64 {
+    let slot_millis = config.seconds_per_slot.get().saturating_mul(1000);
+    let intervals_per_slot =
+        u64::try_from(INTERVALS_PER_SLOT.get()).expect("number of intervals per slot fits in u64");
+
+    i64::try_from(slot_millis / intervals_per_slot).unwrap_or(i64::MAX)
+}
+
+fn duration_from_millis(millis: i64) -> Duration {
+    Duration::from_millis(millis.unsigned_abs())
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    #[test_case(&[], None)]
+    #[test_case(&[100; MIN_SAMPLES - 1], None)]
+    #[test_case(&[100; MIN_SAMPLES], None)]
+    #[test_case(&[-400; MIN_SAMPLES], None)]
+    #[test_case(&[-2000; MIN_SAMPLES], Some(ClockSkew::Behind(Duration::from_secs(2))))]
+    #[test_case(&[7000; MIN_SAMPLES], Some(ClockSkew::Ahead(Duration::from_secs(3))))]
+    #[test_case(
+        &[-2000, -2000, 100, 200, 300, 400, 500, 600, 30_000],
+        None;
+        "outliers are ignored"
+    )]
+    fn estimated_skew_is_based_on_median_arrival_time(
+        offsets: &[i64],
+        expected_skew: Option<ClockSkew>,
+    ) {
+        let config = Config::mainnet();
+        let clock_drift = ClockDrift::default();
+
+        for offset in offsets {
+            clock_drift.observe_arrival_offset(*offset);
+        }
+
+        assert_eq!(clock_drift.estimated_skew(&config), expected_skew);
+    }
+
+    #[test_case(ClockSkew::Behind(Duration::from_millis(1500)) => -1500)]
+    #[test_case(ClockSkew::Ahead(Duration::from_millis(1500)) => 1500)]
+    fn skew_in_millis_is_negative_when_behind(skew: ClockSkew) -> i64 {
+        skew.as_millis()
+    }
+
+    #[test]
+    fn only_recent_samples_are_considered() {
+        let config = Config::mainnet();
+        let clock_drift = ClockDrift::default();
+
+        for _ in 0..MAX_SAMPLES {
+            clock_drift.observe_arrival_offset(-10_000);
+        }
+
+        assert!(clock_drift.is_grossly_skewed(&config));
+
+        for _ in 0..MAX_SAMPLES {
+            clock_drift.observe_arrival_offset(1000);
+        }
+
+        assert_eq!(clock_drift.estimated_skew(&config), None);
+        assert!(!clock_drift.is_grossly_skewed(&config));
+    }
+
+    #[test]
+    fn warnings_are_rate_limited() {
+        let clock_drift = ClockDrift::default();
+        let start = Instant::now();
+
+        assert!(clock_drift.should_warn(start));
+        assert!(!clock_drift.should_warn(start + Duration::from_secs(1)));
+        assert!(!clock_drift.should_warn(start + WARNING_INTERVAL - Duration::from_secs(1)));
+        assert!(clock_drift.should_warn(start + WARNING_INTERVAL));
+        assert!(!clock_drift.should_warn(start + WARNING_INTERVAL + Duration::from_secs(1)));
+    }
+}
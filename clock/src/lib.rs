@@ -66,7 +66,7 @@ use std::time::{Instant, SystemTime};
 use anyhow::Result;
 use arithmetic::U128Ext as _;
 use enum_iterator::Sequence;
-use futures::stream::{Stream, StreamExt as _, TryStreamExt as _};
+use futures::stream::{BoxStream, Stream, StreamExt, TryStreamExt as _};
 use helper_functions::misc;
 use serde::Deserialize;
 use thiserror::Error;
@@ -81,9 +81,46 @@ use types::{
     traits::{BeaconBlock as _, SignedBeaconBlock},
 };
 
+pub use crate::{
+    drift::{ClockDrift, ClockSkew},
+    manual_clock::ManualClock,
+};
+
 use crate::fake_time::{InstantLike, SystemTimeLike};
 
+mod drift;
 mod fake_time;
+mod manual_clock;
+
+/// Source of [`Tick`]s that drive the application.
+///
+/// [`SystemClock`] follows wall-clock time. [`ManualClock`] lets tests advance time explicitly.
+pub trait Clock: Send + Sync {
+    fn current_tick(&self, config: &Config, genesis_time: UnixSeconds) -> Result<Tick>;
+
+    fn ticks(
+        &self,
+        config: &Config,
+        genesis_time: UnixSeconds,
+    ) -> Result<BoxStream<'static, Result<Tick>>>;
+}
+
+#[derive(Clone, Copy, Default, Debug)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn current_tick(&self, config: &Config, genesis_time: UnixSeconds) -> Result<Tick> {
+        Tick::current(config, genesis_time)
+    }
+
+    fn ticks(
+        &self,
+        config: &Config,
+        genesis_time: UnixSeconds,
+    ) -> Result<BoxStream<'static, Result<Tick>>> {
+        ticks(config, genesis_time).map(StreamExt::boxed)
+    }
+}
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Deserialize)]
 pub struct Tick {
@@ -154,6 +191,17 @@ impl Tick {
         )
     }
 
+    /// Time from the start of this tick to the start of the next slot.
+    pub fn duration_until_next_slot(self, config: &Config) -> Result<Duration> {
+        let ticks_since_slot = enum_iterator::all::<TickKind>()
+            .position(|kind| kind == self.kind)
+            .expect("every TickKind is yielded by enum_iterator::all");
+
+        let remaining_ticks = u32::try_from(TickKind::CARDINALITY - ticks_since_slot)?;
+
+        Ok(tick_duration(config)? * remaining_ticks)
+    }
+
     fn from_duration(
         config: &Config,
         duration_since_unix_epoch: Duration,
@@ -370,6 +418,16 @@ mod tests {
         assert!(TickKind::CARDINALITY.is_multiple_of(INTERVALS_PER_SLOT));
     }
 
+    #[test_case(TickKind::Propose => Duration::from_secs(12))]
+    #[test_case(TickKind::ProposeFourth => Duration::from_secs(9))]
+    #[test_case(TickKind::Attest => Duration::from_secs(8))]
+    #[test_case(TickKind::AggregateFourth => Duration::from_secs(1))]
+    fn duration_until_next_slot_with_mainnet_config(kind: TickKind) -> Duration {
+        Tick::new(0, kind)
+            .duration_until_next_slot(&Config::mainnet())
+            .expect("mainnet slots are evenly divisible into ticks")
+    }
+
     #[tokio::test(start_paused = true)]
     async fn ticks_with_mainnet_config_produces_a_tick_every_second() -> Result<()> {
         let genesis_time = SystemTime::now()
@@ -0,0 +1,136 @@
+use anyhow::Result;
+use futures::{
+    channel::mpsc::{self, UnboundedSender},
+    stream::{BoxStream, StreamExt as _},
+};
+use parking_lot::Mutex;
+use types::{
+    config::Config,
+    phase0::primitives::{Slot, UnixSeconds},
+};
+
+use crate::{Clock, Tick};
+
+/// A [`Clock`] that only advances when told to.
+///
+/// Ticks are emitted in the same order and with the same filtering as [`SystemClock`],
+/// but without waiting. This makes tests that depend on the passage of time deterministic.
+///
+/// [`SystemClock`]: crate::SystemClock
+pub struct ManualClock {
+    state: Mutex<State>,
+}
+
+struct State {
+    tick: Tick,
+    subscribers: Vec<UnboundedSender<Result<Tick>>>,
+}
+
+impl ManualClock {
+    #[must_use]
+    pub const fn new(tick: Tick) -> Self {
+        Self {
+            state: Mutex::new(State {
+                tick,
+                subscribers: Vec::new(),
+            }),
+        }
+    }
+
+    #[must_use]
+    pub fn tick(&self) -> Tick {
+        self.state.lock().tick
+    }
+
+    /// Advances to the next tick the application uses and emits it.
+    pub fn advance_tick(&self) -> Result<Tick> {
+        let mut state = self.state.lock();
+
+        let mut tick = state.tick.next()?;
+
+        while !(tick.is_start_of_interval() || tick.is_end_of_interval()) {
+            tick = tick.next()?;
+        }
+
+        state.tick = tick;
+
+        state
+            .subscribers
+            .retain(|subscriber| subscriber.unbounded_send(Ok(tick)).is_ok());
+
+        Ok(tick)
+    }
+
+    /// Advances to the start of `slot`, emitting every tick in between.
+    ///
+    /// Does nothing if `slot` has already started.
+    pub fn advance_to_slot(&self, slot: Slot) -> Result<Tick> {
+        let mut tick = self.tick();
+
+        while tick < Tick::start_of_slot(slot) {
+            tick = self.advance_tick()?;
+        }
+
+        Ok(tick)
+    }
+
+    /// Advances to the start of the next slot, emitting every tick in between.
+    pub fn advance_slot(&self) -> Result<Tick> {
+        self.advance_to_slot(self.tick().slot + 1)
+    }
+}
+
+impl Clock for ManualClock {
+    fn current_tick(&self, _config: &Config, _genesis_time: UnixSeconds) -> Result<Tick> {
+        Ok(self.tick())
+    }
+
+    fn ticks(
+        &self,
+        _config: &Config,
+        _genesis_time: UnixSeconds,
+    ) -> Result<BoxStream<'static, Result<Tick>>> {
+        let (sender, receiver) = mpsc::unbounded();
+        self.state.lock().subscribers.push(sender);
+        Ok(receiver.boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::{future::FutureExt as _, stream::TryStreamExt as _};
+
+    use crate::TickKind;
+
+    use super::*;
+
+    #[test]
+    fn manual_clock_emits_interval_ticks_only_when_advanced() -> Result<()> {
+        let config = Config::minimal();
+        let clock = ManualClock::new(Tick::start_of_slot(0));
+        let mut ticks = clock.ticks(&config, 0)?;
+        let mut next_tick = || {
+            ticks
+                .try_next()
+                .now_or_never()
+                .transpose()
+                .map(Option::flatten)
+        };
+
+        assert_eq!(next_tick()?, None);
+
+        clock.advance_slot()?;
+
+        assert_eq!(next_tick()?, Some(Tick::new(0, TickKind::ProposeFourth)));
+        assert_eq!(next_tick()?, Some(Tick::new(0, TickKind::Attest)));
+        assert_eq!(next_tick()?, Some(Tick::new(0, TickKind::AttestFourth)));
+        assert_eq!(next_tick()?, Some(Tick::new(0, TickKind::Aggregate)));
+        assert_eq!(next_tick()?, Some(Tick::new(0, TickKind::AggregateFourth)));
+        assert_eq!(next_tick()?, Some(Tick::new(1, TickKind::Propose)));
+        assert_eq!(next_tick()?, None);
+
+        assert_eq!(clock.current_tick(&config, 0)?, Tick::start_of_slot(1));
+
+        Ok(())
+    }
+}
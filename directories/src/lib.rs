@@ -54,14 +54,24 @@ impl Directories {
         self
     }
 
-    // TODO: This does not include validator_dir and other files outside store and network directories
-    pub fn disk_usage(&self) -> Result<u64> {
-        let dir_usage =
-            |dir: Option<&PathBuf>| dir.as_ref().map(fs_extra::dir::get_size).transpose();
+    pub fn store_disk_usage(&self) -> Result<u64> {
+        dir_usage(self.store_directory.as_ref())
+    }
+
+    pub fn network_disk_usage(&self) -> Result<u64> {
+        dir_usage(self.network_dir.as_ref())
+    }
 
-        let store_usage = dir_usage(self.store_directory.as_ref())?.unwrap_or_default();
-        let network_usage = dir_usage(self.network_dir.as_ref())?.unwrap_or_default();
+    pub fn validator_disk_usage(&self) -> Result<u64> {
+        dir_usage(self.validator_dir.as_ref())
+    }
 
-        Ok(store_usage + network_usage)
+    pub fn disk_usage(&self) -> Result<u64> {
+        Ok(self.store_disk_usage()? + self.network_disk_usage()? + self.validator_disk_usage()?)
     }
 }
+
+fn dir_usage(dir: Option<&PathBuf>) -> Result<u64> {
+    let usage = dir.map(fs_extra::dir::get_size).transpose()?;
+    Ok(usage.unwrap_or_default())
+}
@@ -36,10 +36,11 @@ type DerivedKey = Zeroizing<[u8; DERIVED_KEY_LENGTH]>;
 type ScryptCost = u64;
 
 #[allow(dead_code)]
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct Keystore {
     crypto: Crypto<SecretKeyBytes>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     description: Option<String>,
     // Without the attribute this would use the `Deserialize` impl of `PublicKeyBytes`,
     // which accepts strings prefixed with `0x`.
@@ -51,11 +52,39 @@ pub struct Keystore {
 }
 
 impl Keystore {
+    /// Encrypts a secret key derived at `m/12381/{coin_type}/{account}/{use_levels}`.
+    pub fn encrypt(
+        secret_key: SecretKeyBytes,
+        normalized_password: &str,
+        pubkey: PublicKeyBytes,
+        coin_type: usize,
+        account: usize,
+        use_levels: Vec<usize>,
+    ) -> Result<Self> {
+        Ok(Self {
+            crypto: Crypto::encrypt(secret_key, normalized_password)?,
+            description: None,
+            pubkey: Some(pubkey),
+            path: Eip2334Path::Known {
+                coin_type,
+                account,
+                use_levels,
+            },
+            uuid: Uuid::new_v4(),
+            version: Version,
+        })
+    }
+
     #[must_use]
     pub const fn uuid(&self) -> Uuid {
         self.uuid
     }
 
+    #[must_use]
+    pub const fn pubkey(&self) -> Option<PublicKeyBytes> {
+        self.pubkey
+    }
+
     pub fn decrypt(self, normalized_password: &str) -> Result<SecretKeyBytes> {
         self.crypto.decrypt(normalized_password)
     }
@@ -343,7 +372,6 @@ impl FromHex for ChecksumMessage {
 /// BLS12-381 key path as defined by [EIP-2334](https://eips.ethereum.org/EIPS/eip-2334).
 enum Eip2334Path {
     UnknownOrIrrelevant,
-    #[allow(dead_code)]
     Known {
         coin_type: usize,
         account: usize,
@@ -418,8 +446,39 @@ impl<'de> Deserialize<'de> for Eip2334Path {
     }
 }
 
+impl Serialize for Eip2334Path {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::UnknownOrIrrelevant => serializer.serialize_str(""),
+            Self::Known {
+                coin_type,
+                account,
+                use_levels,
+            } => {
+                let path = core::iter::once("m".to_owned())
+                    .chain(
+                        [PURPOSE, *coin_type, *account]
+                            .into_iter()
+                            .chain(use_levels.iter().copied())
+                            .map(|level| level.to_string()),
+                    )
+                    .collect::<Vec<_>>()
+                    .join("/");
+
+                serializer.serialize_str(&path)
+            }
+        }
+    }
+}
+
 struct Version;
 
+impl Serialize for Version {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        VERSION.serialize(serializer)
+    }
+}
+
 impl<'de> Deserialize<'de> for Version {
     fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
         let actual_version = usize::deserialize(deserializer)?;
@@ -563,4 +622,36 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn encrypted_keystore_round_trips_through_json() -> Result<()> {
+        let secret_key = hex!("000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f")
+            .conv::<SecretKeyBytes>()
+            .try_conv::<SecretKey>()?;
+
+        let pubkey = secret_key.to_public_key().into();
+        let normalized_password = normalize_password("testpassword")?;
+
+        let keystore = Keystore::encrypt(
+            secret_key.to_bytes(),
+            normalized_password.as_str(),
+            pubkey,
+            3600,
+            0,
+            vec![0, 0],
+        )?;
+
+        let json = serde_json::to_value(&keystore)?;
+
+        assert_eq!(json["path"], "m/12381/3600/0/0/0");
+        assert_eq!(json["version"], 4);
+
+        let decrypted_secret_key = serde_json::from_value::<Keystore>(json)?
+            .decrypt(normalized_password.as_str())?
+            .try_conv::<SecretKey>()?;
+
+        assert_eq!(decrypted_secret_key, secret_key);
+
+        Ok(())
+    }
 }
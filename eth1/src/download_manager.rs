@@ -1,8 +1,11 @@
 use std::sync::Arc;
 
-use anyhow::{Context as _, Result};
+use anyhow::{Context as _, Error as AnyhowError, Result};
 use eth1_api::{Eth1Api, Eth1ApiToMetrics, Eth1Block};
-use futures::channel::mpsc::UnboundedSender;
+use futures::{
+    channel::mpsc::UnboundedSender,
+    stream::{StreamExt as _, TryStreamExt as _},
+};
 use log::{info, warn};
 use prometheus_metrics::Metrics;
 use reqwest::Client;
@@ -14,6 +17,8 @@ use crate::{Eth1Cache, Eth1Config};
 
 const BLOCK_BATCH_SIZE: u64 = 1000;
 const DEPOSIT_BATCH_SIZE: u64 = 1_000;
+const DEPOSIT_REPLAY_BATCH_SIZE: u64 = 100_000;
+const MAX_CONCURRENT_DEPOSIT_REQUESTS: usize = 4;
 const DOWNLOAD_DISTANCE_FROM_HEAD: u64 = 5000;
 
 #[derive(Debug, Error)]
@@ -147,6 +152,39 @@ impl DownloadManager {
 
         let mut from_block = starting_block_number + 1;
 
+        // Deposit events downloaded earlier may be ahead of the deposit tree if the tree was
+        // replaced (for example, with one from a checkpoint). Replay them without contacting
+        // Eth1 nodes. Events can only be replayed if they were stored starting from a block no
+        // later than the one the tree needs next.
+        let stored_block_number = match self.cache.get_deposit_events_block_range()? {
+            Some(block_range) if *block_range.start() <= from_block => {
+                (*block_range.end()).min(latest_block_number)
+            }
+            _ => 0,
+        };
+
+        if from_block <= stored_block_number {
+            info!(
+                "replaying stored Eth1 deposits from block {from_block} to block {stored_block_number}",
+            );
+        }
+
+        while from_block <= stored_block_number {
+            let to_block = stored_block_number.min(from_block + DEPOSIT_REPLAY_BATCH_SIZE - 1);
+            let deposit_events = self.cache.get_deposit_events(from_block..=to_block)?;
+
+            // Download the remaining deposits from Eth1 nodes if the stored ones are unusable.
+            if let Err(error) = self
+                .cache
+                .add_deposits(deposit_events.iter().collect(), to_block)
+            {
+                warn!("unable to replay stored Eth1 deposits: {error:?}");
+                break;
+            }
+
+            from_block = to_block + 1;
+        }
+
         if from_block <= latest_block_number {
             info!(
                 "will download Eth1 deposits from block {} to block {}",
@@ -154,12 +192,35 @@ impl DownloadManager {
             );
         }
 
-        while from_block <= latest_block_number {
-            let to_block = latest_block_number.min(from_block + DEPOSIT_BATCH_SIZE - 1);
+        let mut batches = vec![];
+        let mut batch_start = from_block;
+
+        while batch_start <= latest_block_number {
+            let batch_end = latest_block_number.min(batch_start + DEPOSIT_BATCH_SIZE - 1);
+            batches.push(batch_start..=batch_end);
+            batch_start = batch_end + 1;
+        }
+
+        // Logs are requested for several batches at once, but batches are still added to the
+        // deposit tree in order.
+        let mut downloaded_batches = futures::stream::iter(batches)
+            .map(|block_numbers| async move {
+                let (from_block, to_block) = block_numbers.clone().into_inner();
+
+                info!("downloading Eth1 deposits from block {from_block} to block {to_block}");
+
+                let deposit_event_map = self.api.get_deposit_events(block_numbers).await?;
+
+                Ok::<_, AnyhowError>((from_block..=to_block, deposit_event_map))
+            })
+            .buffered(MAX_CONCURRENT_DEPOSIT_REQUESTS);
+
+        while let Some((block_numbers, deposit_event_map)) = downloaded_batches.try_next().await? {
+            let to_block = *block_numbers.end();
 
-            info!("downloading Eth1 deposits from block {from_block} to block {to_block}");
+            self.cache
+                .put_deposit_events(&deposit_event_map, block_numbers)?;
 
-            let deposit_event_map = self.api.get_deposit_events(from_block..=to_block).await?;
             let deposit_events = deposit_event_map.values().flatten().collect();
 
             if let Err(error) = self.cache.add_deposits(deposit_events, to_block) {
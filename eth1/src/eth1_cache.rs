@@ -1,4 +1,5 @@
-use std::sync::Mutex;
+use core::ops::RangeInclusive;
+use std::{collections::BTreeMap, sync::Mutex};
 
 use anyhow::Result;
 use database::Database;
@@ -6,9 +7,12 @@ use deposit_tree::DepositTree;
 use eth1_api::{DepositEvent, Eth1Block};
 use itertools::Itertools as _;
 use ssz::{SszReadDefault, SszWrite as _};
-use types::phase0::primitives::ExecutionBlockNumber;
+use types::phase0::primitives::{DepositIndex, ExecutionBlockNumber};
 
 const BLOCK_KEY_PREFIX: &str = "bk";
+const DEPOSIT_EVENT_KEY_PREFIX: &str = "dl";
+const DEPOSIT_EVENTS_FIRST_BLOCK_NUMBER_KEY: &str = "deposit_events_first_block_number";
+const DEPOSIT_EVENTS_BLOCK_NUMBER_KEY: &str = "deposit_events_block_number";
 const DEPOSIT_TREE_KEY: &str = "deposit_tree";
 
 pub struct Eth1Cache {
@@ -75,6 +79,41 @@ impl Eth1Cache {
         })?
     }
 
+    /// Returns deposit events stored by [`Self::put_deposit_events`] in ascending order.
+    pub fn get_deposit_events(
+        &self,
+        block_numbers: RangeInclusive<ExecutionBlockNumber>,
+    ) -> Result<Vec<DepositEvent>> {
+        let (start, end) = block_numbers.into_inner();
+        let end_key = deposit_event_key(end, DepositIndex::MAX);
+
+        let results = self
+            .database
+            .iterator_ascending(deposit_event_key(start, 0)..)?;
+
+        itertools::process_results(results, |pairs| {
+            pairs
+                .take_while(|(key_bytes, _)| {
+                    valid_deposit_event_key_bytes(key_bytes) && **key_bytes <= *end_key.as_bytes()
+                })
+                .map(|(_, value_bytes)| DepositEvent::from_ssz_default(value_bytes))
+                .try_collect()
+                .map_err(Into::into)
+        })?
+    }
+
+    /// Returns the range of blocks that deposit events have been stored for without gaps.
+    pub fn get_deposit_events_block_range(
+        &self,
+    ) -> Result<Option<RangeInclusive<ExecutionBlockNumber>>> {
+        let first_block_number = get(&self.database, DEPOSIT_EVENTS_FIRST_BLOCK_NUMBER_KEY)?;
+        let last_block_number = get(&self.database, DEPOSIT_EVENTS_BLOCK_NUMBER_KEY)?;
+
+        Ok(first_block_number
+            .zip(last_block_number)
+            .map(|(first, last)| first..=last))
+    }
+
     pub fn get_deposit_tree(&self) -> Result<Option<DepositTree>> {
         get(&self.database, DEPOSIT_TREE_KEY)
     }
@@ -99,6 +138,50 @@ impl Eth1Cache {
             .and_then(core::convert::identity)
     }
 
+    /// Stores deposit events from all blocks in `block_numbers`.
+    ///
+    /// This allows the deposit tree to be rebuilt without requesting logs from Eth1 nodes again.
+    /// The stored range is extended if `block_numbers` continues it. Otherwise it is replaced,
+    /// so that events from before a gap are never replayed as if the gap did not exist.
+    pub fn put_deposit_events(
+        &self,
+        deposit_events: &BTreeMap<ExecutionBlockNumber, Vec<DepositEvent>>,
+        block_numbers: RangeInclusive<ExecutionBlockNumber>,
+    ) -> Result<()> {
+        let (start, end) = block_numbers.into_inner();
+
+        let (first_block_number, last_block_number) = match self.get_deposit_events_block_range()? {
+            Some(stored) if *stored.start() <= start && start <= stored.end().saturating_add(1) => {
+                (*stored.start(), end.max(*stored.end()))
+            }
+            _ => (start, end),
+        };
+
+        let events = deposit_events
+            .iter()
+            .flat_map(|(block_number, events)| events.iter().map(|event| (*block_number, event)))
+            .map(|(block_number, event)| {
+                let key_string = deposit_event_key(block_number, event.index);
+                let value_bytes = event.to_ssz()?;
+                Ok((key_string, value_bytes))
+            });
+
+        let block_range = [
+            (DEPOSIT_EVENTS_FIRST_BLOCK_NUMBER_KEY, first_block_number),
+            (DEPOSIT_EVENTS_BLOCK_NUMBER_KEY, last_block_number),
+        ]
+        .into_iter()
+        .map(|(key, block_number)| {
+            let value_bytes = block_number.to_ssz()?;
+            Ok((key.to_owned(), value_bytes))
+        });
+
+        let results = events.chain(block_range);
+
+        itertools::process_results(results, |pairs| self.database.put_batch(pairs))
+            .and_then(core::convert::identity)
+    }
+
     pub fn put_deposit_tree(&self, deposit_tree: &DepositTree) -> Result<()> {
         put_deposit_tree(&self.database, deposit_tree)
     }
@@ -108,6 +191,10 @@ fn block_key(block_number: ExecutionBlockNumber) -> String {
     format!("{BLOCK_KEY_PREFIX}{block_number:020}")
 }
 
+fn deposit_event_key(block_number: ExecutionBlockNumber, deposit_index: DepositIndex) -> String {
+    format!("{DEPOSIT_EVENT_KEY_PREFIX}{block_number:020}{deposit_index:020}")
+}
+
 fn get<V: SszReadDefault>(database: &Database, key: impl AsRef<[u8]>) -> Result<Option<V>> {
     let value = match database.get(key)? {
         Some(bytes) => V::from_ssz_default(bytes.as_slice())?,
@@ -124,3 +211,82 @@ fn put_deposit_tree(database: &Database, deposit_tree: &DepositTree) -> Result<(
 fn valid_block_key_bytes(key_bytes: &[u8]) -> bool {
     key_bytes.starts_with(BLOCK_KEY_PREFIX.as_bytes())
 }
+
+fn valid_deposit_event_key_bytes(key_bytes: &[u8]) -> bool {
+    key_bytes.starts_with(DEPOSIT_EVENT_KEY_PREFIX.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use types::phase0::containers::DepositData;
+
+    use super::*;
+
+    #[test]
+    fn deposit_events_are_returned_in_block_and_index_order() -> Result<()> {
+        let cache = Eth1Cache::new(Database::in_memory(), None)?;
+
+        // Block numbers and indices with different digit counts would be misordered if keys
+        // were not padded.
+        let deposit_events = BTreeMap::from([
+            (9, vec![deposit_event(0), deposit_event(1)]),
+            (10, vec![deposit_event(2)]),
+            (100, vec![deposit_event(9), deposit_event(10)]),
+            (1000, vec![deposit_event(11)]),
+        ]);
+
+        cache.put_deposit_events(&deposit_events, 1..=1000)?;
+
+        assert_eq!(deposit_indices(&cache, 0..=1000)?, [0, 1, 2, 9, 10, 11]);
+        assert_eq!(deposit_indices(&cache, 10..=100)?, [2, 9, 10]);
+        assert!(deposit_indices(&cache, 11..=99)?.is_empty());
+        assert_eq!(deposit_indices(&cache, 1000..=1000)?, [11]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn deposit_events_block_range_is_extended_only_without_gaps() -> Result<()> {
+        let cache = Eth1Cache::new(Database::in_memory(), None)?;
+
+        assert_eq!(cache.get_deposit_events_block_range()?, None);
+
+        cache.put_deposit_events(&BTreeMap::new(), 10..=19)?;
+        cache.put_deposit_events(&BTreeMap::new(), 20..=29)?;
+
+        assert_eq!(cache.get_deposit_events_block_range()?, Some(10..=29));
+
+        // Downloading a range again does not shrink the stored one.
+        cache.put_deposit_events(&BTreeMap::new(), 15..=24)?;
+
+        assert_eq!(cache.get_deposit_events_block_range()?, Some(10..=29));
+
+        cache.put_deposit_events(&BTreeMap::new(), 40..=49)?;
+
+        assert_eq!(cache.get_deposit_events_block_range()?, Some(40..=49));
+
+        cache.put_deposit_events(&BTreeMap::new(), 0..=9)?;
+
+        assert_eq!(cache.get_deposit_events_block_range()?, Some(0..=9));
+
+        Ok(())
+    }
+
+    fn deposit_event(index: DepositIndex) -> DepositEvent {
+        DepositEvent {
+            data: DepositData::default(),
+            index,
+        }
+    }
+
+    fn deposit_indices(
+        cache: &Eth1Cache,
+        block_numbers: RangeInclusive<ExecutionBlockNumber>,
+    ) -> Result<Vec<DepositIndex>> {
+        let deposit_events = cache.get_deposit_events(block_numbers)?;
+        Ok(deposit_events
+            .into_iter()
+            .map(|event| event.index)
+            .collect())
+    }
+}
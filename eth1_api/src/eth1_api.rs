@@ -1,4 +1,7 @@
-use core::ops::RangeInclusive;
+use core::{
+    ops::RangeInclusive,
+    sync::atomic::{AtomicBool, Ordering},
+};
 use std::{collections::BTreeMap, sync::Arc, vec::IntoIter};
 
 use anyhow::{bail, ensure, Result};
@@ -46,6 +49,8 @@ pub struct Eth1Api {
     auth: Arc<Auth>,
     original: Vec<Url>,
     endpoints: Mutex<IntoIter<Url>>,
+    // Set when the last request failed on all endpoints and cleared when one succeeds.
+    el_offline: AtomicBool,
     eth1_api_to_metrics_tx: Option<UnboundedSender<Eth1ApiToMetrics>>,
     metrics: Option<Arc<Metrics>>,
 }
@@ -66,11 +71,18 @@ impl Eth1Api {
             auth,
             original: eth1_rpc_urls.clone(),
             endpoints: Mutex::new(eth1_rpc_urls.into_iter()),
+            el_offline: AtomicBool::new(false),
             eth1_api_to_metrics_tx,
             metrics,
         }
     }
 
+    /// Returns `true` if no endpoint could be reached the last time a request was made.
+    #[must_use]
+    pub fn el_offline(&self) -> bool {
+        self.el_offline.load(Ordering::Relaxed)
+    }
+
     pub async fn current_head_number(&self) -> Result<ExecutionBlockNumber> {
         Ok(self
             .request_with_fallback(|(api, headers)| Ok(api.block_number(headers)))
@@ -368,6 +380,8 @@ impl Eth1Api {
 
             match query {
                 Ok(result) => {
+                    self.el_offline.store(false, Ordering::Relaxed);
+
                     if let Some(metrics_tx) = self.eth1_api_to_metrics_tx.as_ref() {
                         Eth1ApiToMetrics::Eth1Connection(Eth1ConnectionData {
                             sync_eth1_connected: true,
@@ -404,6 +418,7 @@ impl Eth1Api {
         }
 
         self.reset_endpoints().await;
+        self.el_offline.store(true, Ordering::Relaxed);
 
         if let Some(metrics) = self.metrics.as_ref() {
             metrics.eth1_api_reset_count.inc();
@@ -9,6 +9,9 @@ pub use crate::{
     misc::{ApiController, RealController},
 };
 
+#[cfg(any(test, feature = "mock-execution-service"))]
+pub use crate::mock_execution_service::MockExecutionService;
+
 mod auth;
 mod deposit_event;
 mod eth1_api;
@@ -17,3 +20,6 @@ mod eth1_execution_engine;
 mod execution_service;
 mod messages;
 mod misc;
+
+#[cfg(any(test, feature = "mock-execution-service"))]
+mod mock_execution_service;
@@ -0,0 +1,380 @@
+use core::time::Duration;
+use std::collections::HashMap;
+
+use anyhow::Result;
+use execution_engine::{PayloadStatusV1, PayloadValidationStatus};
+use fork_choice_control::Wait;
+use futures::{channel::mpsc::UnboundedReceiver, StreamExt as _};
+use log::warn;
+use types::{phase0::primitives::ExecutionBlockHash, preset::Preset};
+
+use crate::{messages::ExecutionServiceMessage, misc::ApiController};
+
+/// A replacement for [`ExecutionService`] that answers messages from a script instead of
+/// calling an execution engine.
+///
+/// Payloads are reported with the status scripted for their block hash or the default status.
+/// The default is [`PayloadValidationStatus::Syncing`], which leaves blocks optimistic.
+/// Payloads are never built, so `engine_forkchoiceUpdated` calls always return no payload ID.
+///
+/// [`ExecutionService`]: crate::ExecutionService
+pub struct MockExecutionService<P: Preset, W: Wait> {
+    controller: ApiController<P, W>,
+    rx: UnboundedReceiver<ExecutionServiceMessage<P>>,
+    statuses: HashMap<ExecutionBlockHash, PayloadValidationStatus>,
+    default_status: PayloadValidationStatus,
+    latency: Duration,
+}
+
+impl<P: Preset, W: Wait> MockExecutionService<P, W> {
+    #[must_use]
+    pub fn new(
+        controller: ApiController<P, W>,
+        rx: UnboundedReceiver<ExecutionServiceMessage<P>>,
+    ) -> Self {
+        Self {
+            controller,
+            rx,
+            statuses: HashMap::new(),
+            default_status: PayloadValidationStatus::Syncing,
+            latency: Duration::ZERO,
+        }
+    }
+
+    #[must_use]
+    pub fn with_statuses(
+        mut self,
+        statuses: impl IntoIterator<Item = (ExecutionBlockHash, PayloadValidationStatus)>,
+    ) -> Self {
+        self.statuses.extend(statuses);
+        self
+    }
+
+    #[must_use]
+    pub const fn with_default_status(mut self, default_status: PayloadValidationStatus) -> Self {
+        self.default_status = default_status;
+        self
+    }
+
+    /// Delays every response by `latency` to simulate a slow execution engine.
+    #[must_use]
+    pub const fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    /// Reports all scripted statuses to fork choice without waiting for payloads to be submitted.
+    ///
+    /// Statuses for blocks that have not been imported yet are delayed until the blocks arrive.
+    /// This is how fork choice sees an execution engine that is ahead of the beacon node.
+    pub fn notify_scripted_statuses(&self) {
+        for (execution_block_hash, status) in &self.statuses {
+            self.controller.on_notified_new_payload(
+                *execution_block_hash,
+                Self::payload_status(*execution_block_hash, *status),
+            );
+        }
+    }
+
+    pub async fn run(mut self) -> Result<()> {
+        while let Some(message) = self.rx.next().await {
+            if !self.latency.is_zero() {
+                tokio::time::sleep(self.latency).await;
+            }
+
+            match message {
+                ExecutionServiceMessage::NotifyForkchoiceUpdated {
+                    head_eth1_block_hash,
+                    sender,
+                    ..
+                } => {
+                    let status = self.scripted_status(head_eth1_block_hash);
+
+                    self.controller
+                        .on_notified_fork_choice_update(Self::payload_status(
+                            head_eth1_block_hash,
+                            status,
+                        ));
+
+                    if let Some(sender) = sender {
+                        if let Err(message) = sender.send(None) {
+                            warn!(
+                                "sending mocked engine_forkchoiceUpdated result \
+                                 failed because the receiver was dropped: {message:?}"
+                            );
+                        }
+                    }
+                }
+                ExecutionServiceMessage::NotifyNewPayload {
+                    payload, sender, ..
+                } => {
+                    let execution_block_hash = payload.block_hash();
+                    let status = self.scripted_status(execution_block_hash);
+                    let payload_status = Self::payload_status(execution_block_hash, status);
+
+                    self.controller
+                        .on_notified_new_payload(execution_block_hash, payload_status.clone());
+
+                    if let Some(sender) = sender {
+                        if let Err(message) = sender.send(Ok(payload_status)) {
+                            warn!(
+                                "sending mocked engine_newPayload result \
+                                 failed because the receiver was dropped: {message:?}"
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn scripted_status(&self, execution_block_hash: ExecutionBlockHash) -> PayloadValidationStatus {
+        self.statuses
+            .get(&execution_block_hash)
+            .copied()
+            .unwrap_or(self.default_status)
+    }
+
+    // Only `VALID` responses are required to contain `latest_valid_hash`.
+    // Omitting it for other statuses makes fork choice affect only the block itself.
+    fn payload_status(
+        execution_block_hash: ExecutionBlockHash,
+        status: PayloadValidationStatus,
+    ) -> PayloadStatusV1 {
+        PayloadStatusV1 {
+            status,
+            latest_valid_hash: status.is_valid().then_some(execution_block_hash),
+            validation_error: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use clock::Tick;
+    use crossbeam_utils::sync::WaitGroup;
+    use database::Database;
+    use fork_choice_control::{Controller, Storage, DEFAULT_ARCHIVAL_EPOCH_INTERVAL};
+    use fork_choice_store::{PayloadStatus, StoreConfig};
+    use futures::channel::mpsc::UnboundedReceiver;
+    use reqwest::Client;
+    use std_ext::ArcExt as _;
+    use test_case::test_case;
+    use types::{
+        combined::SignedBeaconBlock, config::Config, nonstandard::Phase, phase0::primitives::H256,
+        preset::Minimal, traits::SignedBeaconBlock as _,
+    };
+
+    use crate::{Eth1Api, Eth1ExecutionEngine};
+
+    use super::*;
+
+    type TestController = ApiController<Minimal, WaitGroup>;
+
+    const EXECUTION_BLOCK_HASH: ExecutionBlockHash = H256::repeat_byte(1);
+
+    #[test_case(None, PayloadValidationStatus::Syncing => Some(PayloadStatus::Optimistic))]
+    #[test_case(None, PayloadValidationStatus::Valid => Some(PayloadStatus::Valid))]
+    #[test_case(None, PayloadValidationStatus::Invalid => None)]
+    #[test_case(
+        Some(PayloadValidationStatus::Valid),
+        PayloadValidationStatus::Syncing
+        => Some(PayloadStatus::Valid)
+    )]
+    #[test_case(
+        Some(PayloadValidationStatus::Accepted),
+        PayloadValidationStatus::Valid
+        => Some(PayloadStatus::Optimistic)
+    )]
+    fn submitted_payloads_are_answered_with_scripted_statuses(
+        scripted_status: Option<PayloadValidationStatus>,
+        default_status: PayloadValidationStatus,
+    ) -> Option<PayloadStatus> {
+        with_imported_block(|controller, execution_service_rx, block_root| {
+            // Blocks are imported optimistically until the execution engine responds.
+            assert_eq!(
+                head_payload_status(controller, block_root),
+                Some(PayloadStatus::Optimistic),
+            );
+
+            // Closing the channel lets `MockExecutionService::run` finish
+            // after answering the messages sent while importing the block.
+            let mut execution_service_rx = execution_service_rx;
+            execution_service_rx.close();
+
+            let execution_service =
+                MockExecutionService::new(controller.clone_arc(), execution_service_rx)
+                    .with_statuses(scripted_status.map(|status| (EXECUTION_BLOCK_HASH, status)))
+                    .with_default_status(default_status);
+
+            futures::executor::block_on(execution_service.run())
+                .expect("MockExecutionService::run should not fail");
+
+            controller.wait_for_tasks();
+            head_payload_status(controller, block_root)
+        })
+    }
+
+    #[test]
+    fn scripted_statuses_notified_before_import_are_applied_to_blocks_when_they_arrive() {
+        with_controller(|controller, execution_service_rx, block| {
+            MockExecutionService::new(controller.clone_arc(), execution_service_rx)
+                .with_statuses([(EXECUTION_BLOCK_HASH, PayloadValidationStatus::Valid)])
+                .notify_scripted_statuses();
+
+            controller.on_requested_block(block.clone_arc(), None);
+            controller.wait_for_tasks();
+
+            assert_eq!(
+                head_payload_status(controller, block.message().hash_tree_root()),
+                Some(PayloadStatus::Valid),
+            );
+        })
+    }
+
+    #[test]
+    fn payload_submitters_receive_scripted_statuses() -> Result<()> {
+        with_controller(|controller, _, block| {
+            let (tx, rx) = futures::channel::mpsc::unbounded();
+            let (sender, mut receiver) = futures::channel::oneshot::channel();
+
+            let payload = SignedBeaconBlock::clone(block)
+                .execution_payload()
+                .expect("block should contain an execution payload");
+
+            ExecutionServiceMessage::NotifyNewPayload {
+                beacon_block_root: block.message().hash_tree_root(),
+                payload,
+                params: None,
+                sender: Some(sender),
+            }
+            .send(&tx);
+
+            drop(tx);
+
+            futures::executor::block_on(
+                MockExecutionService::new(controller.clone_arc(), rx)
+                    .with_statuses([(EXECUTION_BLOCK_HASH, PayloadValidationStatus::Invalid)])
+                    .run(),
+            )?;
+
+            let payload_status = receiver
+                .try_recv()?
+                .expect("MockExecutionService should respond to engine_newPayload")?;
+
+            assert_eq!(
+                payload_status,
+                PayloadStatusV1 {
+                    status: PayloadValidationStatus::Invalid,
+                    latest_valid_hash: None,
+                    validation_error: None,
+                },
+            );
+
+            Ok(())
+        })
+    }
+
+    // A block with an invalid payload stops being the head, in which case this returns `None`.
+    fn head_payload_status(controller: &TestController, block_root: H256) -> Option<PayloadStatus> {
+        let head = controller.head().value;
+        (head.block_root == block_root).then_some(head.payload_status)
+    }
+
+    fn with_imported_block<T>(
+        test: impl FnOnce(
+            &TestController,
+            UnboundedReceiver<ExecutionServiceMessage<Minimal>>,
+            H256,
+        ) -> T,
+    ) -> T {
+        with_controller(|controller, execution_service_rx, block| {
+            controller.on_requested_block(block.clone_arc(), None);
+            controller.wait_for_tasks();
+
+            test(
+                controller,
+                execution_service_rx,
+                block.message().hash_tree_root(),
+            )
+        })
+    }
+
+    // The controller is passed to a closure because `MutatorHandle` cannot be named here.
+    // Dropping the handle at the end of this function joins the mutator thread.
+    fn with_controller<T>(
+        test: impl FnOnce(
+            &TestController,
+            UnboundedReceiver<ExecutionServiceMessage<Minimal>>,
+            &Arc<SignedBeaconBlock<Minimal>>,
+        ) -> T,
+    ) -> T {
+        let config = Arc::new(Config::minimal().start_and_stay_in(Phase::Bellatrix));
+
+        let (genesis_state, _) =
+            factory::min_genesis_state(&config).expect("genesis state should be valid");
+
+        let genesis_block = Arc::new(genesis::beacon_block(&genesis_state));
+
+        let execution_payload =
+            factory::execution_payload(&config, &genesis_state, 1, EXECUTION_BLOCK_HASH)
+                .expect("execution payload should be constructed successfully");
+
+        let (block, _) = factory::block_with_payload(
+            &config,
+            genesis_state.clone_arc(),
+            1,
+            H256::zero(),
+            execution_payload,
+        )
+        .expect("block should be constructed successfully");
+
+        let (execution_service_tx, execution_service_rx) = futures::channel::mpsc::unbounded();
+
+        let eth1_api = Arc::new(Eth1Api::new(
+            config.clone_arc(),
+            Client::new(),
+            Arc::default(),
+            vec![],
+            None,
+            None,
+        ));
+
+        let execution_engine = Arc::new(Eth1ExecutionEngine::new(
+            config.clone_arc(),
+            eth1_api,
+            execution_service_tx,
+        ));
+
+        let storage = Arc::new(Storage::new(
+            config.clone_arc(),
+            Database::in_memory(),
+            DEFAULT_ARCHIVAL_EPOCH_INTERVAL,
+            false,
+        ));
+
+        let (controller, _mutator_handle) = Controller::new(
+            config.clone_arc(),
+            StoreConfig::minimal(&config),
+            genesis_block,
+            genesis_state,
+            Tick::block_proposal(&block),
+            execution_engine,
+            None,
+            futures::sink::drain(),
+            futures::sink::drain(),
+            futures::sink::drain(),
+            futures::sink::drain(),
+            futures::sink::drain(),
+            storage,
+            core::iter::empty(),
+        )
+        .expect("controller should be constructed successfully");
+
+        test(&controller, execution_service_rx, &block)
+    }
+}
@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use derive_more::From;
 use ethereum_types::H64;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, Serializer};
 use ssz::{ByteList, ByteVector, ContiguousList};
 use types::{
     bellatrix::{
@@ -387,6 +387,13 @@ pub struct PayloadAttributesV1 {
     pub timestamp: UnixSeconds,
     pub prev_randao: H256,
     pub suggested_fee_recipient: ExecutionAddress,
+    /// Gas limit preferred by the proposer.
+    /// Not part of the Engine API, so it is only sent if enabled in configuration.
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_gas_limit"
+    )]
+    pub gas_limit: Option<Gas>,
 }
 
 /// [`PayloadAttributesV2`](https://github.com/ethereum/execution-apis/blob/b7c5d3420e00648f456744d121ffbd929862924d/src/engine/shanghai.md#payloadattributesv2)
@@ -398,6 +405,13 @@ pub struct PayloadAttributesV2<P: Preset> {
     pub prev_randao: H256,
     pub suggested_fee_recipient: ExecutionAddress,
     pub withdrawals: ContiguousList<WithdrawalV1, P::MaxWithdrawalsPerPayload>,
+    /// Gas limit preferred by the proposer.
+    /// Not part of the Engine API, so it is only sent if enabled in configuration.
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_gas_limit"
+    )]
+    pub gas_limit: Option<Gas>,
 }
 
 /// [`PayloadAttributesV3`](https://github.com/ethereum/execution-apis/blob/fe8e13c288c592ec154ce25c534e26cb7ce0530d/src/engine/cancun.md#payloadattributesv3)
@@ -410,6 +424,13 @@ pub struct PayloadAttributesV3<P: Preset> {
     pub suggested_fee_recipient: ExecutionAddress,
     pub withdrawals: ContiguousList<WithdrawalV1, P::MaxWithdrawalsPerPayload>,
     pub parent_beacon_block_root: H256,
+    /// Gas limit preferred by the proposer.
+    /// Not part of the Engine API, so it is only sent if enabled in configuration.
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_gas_limit"
+    )]
+    pub gas_limit: Option<Gas>,
 }
 
 /// [`engine_getPayloadV1` response](https://github.com/ethereum/execution-apis/blob/b7c5d3420e00648f456744d121ffbd929862924d/src/engine/paris.md#response-2).
@@ -610,6 +631,16 @@ pub enum PayloadId {
     Deneb(H64),
 }
 
+fn serialize_gas_limit<S: Serializer>(
+    gas_limit: &Option<Gas>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match gas_limit {
+        Some(gas_limit) => serde_utils::prefixed_hex_quantity::serialize(gas_limit, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use anyhow::Result;
@@ -630,6 +661,80 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_payload_attributes_v1_gas_limit_serialization() -> Result<()> {
+        let mut payload_attributes = PayloadAttributesV1 {
+            timestamp: 1,
+            prev_randao: H256::zero(),
+            suggested_fee_recipient: ExecutionAddress::zero(),
+            gas_limit: None,
+        };
+
+        let json = serde_json::to_value(&payload_attributes)?;
+
+        assert_eq!(json.get("gasLimit"), None);
+
+        payload_attributes.gas_limit = Some(30_000_000);
+
+        let json = serde_json::to_value(&payload_attributes)?;
+
+        assert_eq!(json.get("gasLimit"), Some(&json!("0x1c9c380")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_payload_attributes_v2_gas_limit_serialization() -> Result<()> {
+        let mut payload_attributes = PayloadAttributesV2::<Mainnet> {
+            timestamp: 1,
+            prev_randao: H256::zero(),
+            suggested_fee_recipient: ExecutionAddress::zero(),
+            withdrawals: ContiguousList::default(),
+            gas_limit: None,
+        };
+
+        let json = serde_json::to_value(&payload_attributes)?;
+
+        assert_eq!(json.get("gasLimit"), None);
+        assert_eq!(json.get("withdrawals"), Some(&json!([])));
+
+        payload_attributes.gas_limit = Some(30_000_000);
+
+        let json = serde_json::to_value(&payload_attributes)?;
+
+        assert_eq!(json.get("gasLimit"), Some(&json!("0x1c9c380")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_payload_attributes_v3_gas_limit_serialization() -> Result<()> {
+        let mut payload_attributes = PayloadAttributesV3::<Mainnet> {
+            timestamp: 1,
+            prev_randao: H256::zero(),
+            suggested_fee_recipient: ExecutionAddress::zero(),
+            withdrawals: ContiguousList::default(),
+            parent_beacon_block_root: H256::zero(),
+            gas_limit: None,
+        };
+
+        let json = serde_json::to_value(&payload_attributes)?;
+
+        assert_eq!(json.get("gasLimit"), None);
+        assert_eq!(
+            json.get("parentBeaconBlockRoot"),
+            Some(&json!(H256::zero())),
+        );
+
+        payload_attributes.gas_limit = Some(30_000_000);
+
+        let json = serde_json::to_value(&payload_attributes)?;
+
+        assert_eq!(json.get("gasLimit"), Some(&json!("0x1c9c380")));
+
+        Ok(())
+    }
+
     #[test]
     fn test_capella_execution_payload_conversion_and_serialization() -> Result<()> {
         let payload_v2 = ExecutionPayloadV2::from(sample_capella_payload().value);
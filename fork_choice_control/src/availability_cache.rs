@@ -0,0 +1,272 @@
+use core::time::Duration;
+use std::{
+    collections::{BTreeSet, HashMap},
+    time::Instant,
+};
+
+use parking_lot::Mutex;
+use types::{
+    deneb::primitives::BlobIndex,
+    phase0::primitives::{Slot, H256},
+    preset::Preset,
+    traits::SignedBeaconBlock as _,
+};
+
+use crate::misc::PendingBlock;
+
+/// Blocks that passed validation but cannot be imported until their blobs are available.
+///
+/// Blob sidecars are accepted into the fork choice store regardless of where they come from
+/// (gossip, requests to peers, the HTTP API), so the cache only needs to track which indices are
+/// still missing. A block is handed back for import once the last of them arrives, rather than
+/// being retried after every blob sidecar.
+///
+/// The cache is shared with [`Controller`] to report the availability of pending blocks.
+///
+/// [`Controller`]: crate::Controller
+pub struct AvailabilityCache<P: Preset> {
+    pending: Mutex<HashMap<H256, PendingAvailability<P>>>,
+}
+
+impl<P: Preset> Default for AvailabilityCache<P> {
+    fn default() -> Self {
+        Self {
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+struct PendingAvailability<P: Preset> {
+    pending_block: PendingBlock<P>,
+    missing_blob_indices: BTreeSet<BlobIndex>,
+    delayed_at: Instant,
+}
+
+impl<P: Preset> AvailabilityCache<P> {
+    /// Caches `pending_block` until the blob sidecars with `missing_blob_indices` are available.
+    ///
+    /// `missing_blob_indices` may be out of date if blob sidecars were accepted after the block
+    /// was validated, so indices in `received_blob_indices` are not considered missing.
+    /// The block is returned instead of being cached if none of its blob sidecars are missing.
+    pub fn insert(
+        &self,
+        block_root: H256,
+        pending_block: PendingBlock<P>,
+        missing_blob_indices: impl IntoIterator<Item = BlobIndex>,
+        received_blob_indices: impl IntoIterator<Item = BlobIndex>,
+    ) -> Availability<P> {
+        let mut missing_blob_indices = missing_blob_indices.into_iter().collect::<BTreeSet<_>>();
+
+        for index in received_blob_indices {
+            missing_blob_indices.remove(&index);
+        }
+
+        // Keep the original delay time if the block was delayed again after a retry.
+        let mut pending = self.pending.lock();
+
+        if missing_blob_indices.is_empty() {
+            pending.remove(&block_root);
+            return Availability::Available(pending_block);
+        }
+
+        let delayed_at = pending
+            .get(&block_root)
+            .map_or_else(Instant::now, |pending| pending.delayed_at);
+
+        let missing = missing_blob_indices.iter().copied().collect();
+
+        pending.insert(
+            block_root,
+            PendingAvailability {
+                pending_block,
+                missing_blob_indices,
+                delayed_at,
+            },
+        );
+
+        Availability::Missing(missing)
+    }
+
+    /// Records that the blob sidecar with `index` for the block with `block_root` is available.
+    ///
+    /// Returns the block along with the time it spent waiting if it became fully available.
+    /// The block is kept in the cache until it is imported or pruned.
+    pub fn on_blob_sidecar(
+        &self,
+        block_root: H256,
+        index: BlobIndex,
+    ) -> Option<(PendingBlock<P>, Duration)> {
+        let mut pending = self.pending.lock();
+        let pending = pending.get_mut(&block_root)?;
+
+        if !pending.missing_blob_indices.remove(&index) || !pending.missing_blob_indices.is_empty()
+        {
+            return None;
+        }
+
+        Some((pending.pending_block.clone(), pending.delayed_at.elapsed()))
+    }
+
+    pub fn pending_blobs(&self, block_root: H256) -> Option<PendingBlobs> {
+        let pending = self.pending.lock();
+        let pending = pending.get(&block_root)?;
+        let block = pending.pending_block.block.message();
+
+        let blob_count = block
+            .body()
+            .post_deneb()
+            .map_or(0, |body| body.blob_kzg_commitments().len());
+
+        Some(PendingBlobs {
+            slot: block.slot(),
+            blob_count,
+            missing_blob_indices: pending.missing_blob_indices.iter().copied().collect(),
+            waiting_time: pending.delayed_at.elapsed(),
+        })
+    }
+
+    pub fn remove(&self, block_root: H256) {
+        self.pending.lock().remove(&block_root);
+    }
+
+    pub fn prune(&self, finalized_slot: Slot) {
+        self.pending
+            .lock()
+            .retain(|_, pending| pending.pending_block.block.message().slot() > finalized_slot);
+    }
+}
+
+pub enum Availability<P: Preset> {
+    Available(PendingBlock<P>),
+    Missing(Vec<BlobIndex>),
+}
+
+pub struct PendingBlobs {
+    pub slot: Slot,
+    pub blob_count: usize,
+    pub missing_blob_indices: Vec<BlobIndex>,
+    pub waiting_time: Duration,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use bls::SignatureBytes;
+    use fork_choice_store::BlockOrigin;
+    use types::{
+        combined::SignedBeaconBlock,
+        deneb::{
+            containers::{
+                BeaconBlock, BeaconBlockBody, SignedBeaconBlock as DenebSignedBeaconBlock,
+            },
+            primitives::KzgCommitment,
+        },
+        preset::Minimal,
+    };
+
+    use super::*;
+
+    const BLOCK_ROOT: H256 = H256::repeat_byte(1);
+
+    #[test]
+    fn insert_ignores_indices_of_received_blob_sidecars() {
+        let cache = AvailabilityCache::default();
+
+        let Availability::Missing(missing_blob_indices) =
+            cache.insert(BLOCK_ROOT, pending_block(3), [0, 1, 2], [0, 2])
+        else {
+            panic!("block should be missing blob sidecars");
+        };
+
+        assert_eq!(missing_blob_indices, [1]);
+        assert!(cache.on_blob_sidecar(BLOCK_ROOT, 0).is_none());
+        assert!(cache.on_blob_sidecar(BLOCK_ROOT, 1).is_some());
+    }
+
+    #[test]
+    fn insert_returns_block_if_all_blob_sidecars_were_received() {
+        let cache = AvailabilityCache::default();
+
+        assert!(matches!(
+            cache.insert(BLOCK_ROOT, pending_block(2), [0, 1], [0]),
+            Availability::Missing(_),
+        ));
+
+        // The block is validated again after the missing blob sidecar is accepted.
+        assert!(matches!(
+            cache.insert(BLOCK_ROOT, pending_block(2), [1], [0, 1]),
+            Availability::Available(_),
+        ));
+
+        assert!(cache.pending_blobs(BLOCK_ROOT).is_none());
+    }
+
+    #[test]
+    fn pending_blobs_reports_missing_indices_of_cached_block() {
+        let cache = AvailabilityCache::default();
+
+        assert!(cache.pending_blobs(BLOCK_ROOT).is_none());
+
+        cache.insert(BLOCK_ROOT, pending_block(3), [0, 1, 2], [1]);
+
+        let pending_blobs = cache
+            .pending_blobs(BLOCK_ROOT)
+            .expect("block should be cached");
+
+        assert_eq!(pending_blobs.slot, 5);
+        assert_eq!(pending_blobs.blob_count, 3);
+        assert_eq!(pending_blobs.missing_blob_indices, [0, 2]);
+
+        cache.on_blob_sidecar(BLOCK_ROOT, 2);
+
+        let pending_blobs = cache
+            .pending_blobs(BLOCK_ROOT)
+            .expect("block should be cached");
+
+        assert_eq!(pending_blobs.missing_blob_indices, [0]);
+
+        cache.remove(BLOCK_ROOT);
+
+        assert!(cache.pending_blobs(BLOCK_ROOT).is_none());
+    }
+
+    #[test]
+    fn prune_removes_blocks_not_newer_than_finalized_slot() {
+        let cache = AvailabilityCache::default();
+
+        cache.insert(BLOCK_ROOT, pending_block(1), [0], []);
+
+        cache.prune(4);
+
+        assert!(cache.pending_blobs(BLOCK_ROOT).is_some());
+
+        cache.prune(5);
+
+        assert!(cache.pending_blobs(BLOCK_ROOT).is_none());
+    }
+
+    fn pending_block(blob_count: usize) -> PendingBlock<Minimal> {
+        let blob_kzg_commitments = vec![KzgCommitment::default(); blob_count]
+            .try_into()
+            .expect("blob count should be within the limit");
+
+        let block = DenebSignedBeaconBlock {
+            message: BeaconBlock {
+                slot: 5,
+                body: BeaconBlockBody {
+                    blob_kzg_commitments,
+                    ..BeaconBlockBody::default()
+                },
+                ..BeaconBlock::default()
+            },
+            signature: SignatureBytes::default(),
+        };
+
+        PendingBlock {
+            block: Arc::new(SignedBeaconBlock::from(block)),
+            origin: BlockOrigin::Own,
+            submission_time: Instant::now(),
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use core::{
+    cell::RefCell,
+    sync::atomic::{AtomicBool, Ordering},
+};
+use std::sync::Arc;
+
+use anyhow::{ensure, Result};
+use thiserror::Error;
+
+thread_local! {
+    static CURRENT_TOKEN: RefCell<Option<CancellationToken>> = const { RefCell::new(None) };
+}
+
+/// Lets work done on behalf of a caller stop early once the caller is no longer interested in it.
+///
+/// Cancellation is cooperative. Only loops that replay blocks from storage check for it,
+/// since they are the only ones that take long enough for it to matter.
+#[derive(Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Runs `function` on the current thread with cancellation checks observing this token.
+    pub fn run<T>(&self, function: impl FnOnce() -> T) -> T {
+        let previous = CURRENT_TOKEN.with(|current| current.replace(Some(self.clone())));
+
+        // Restore the previous token even if `function` panics.
+        let _guard = RestoreOnDrop(previous);
+
+        function()
+    }
+}
+
+struct RestoreOnDrop(Option<CancellationToken>);
+
+impl Drop for RestoreOnDrop {
+    fn drop(&mut self) {
+        CURRENT_TOKEN.with(|current| *current.borrow_mut() = self.0.take());
+    }
+}
+
+pub fn ensure_not_cancelled() -> Result<()> {
+    let cancelled = CURRENT_TOKEN.with(|current| {
+        current
+            .borrow()
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+    });
+
+    ensure!(!cancelled, Error::Canceled);
+
+    Ok(())
+}
+
+#[derive(Debug, Error)]
+enum Error {
+    #[error("operation was canceled")]
+    Canceled,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cancellation_is_only_observed_inside_run() {
+        let token = CancellationToken::default();
+
+        token.cancel();
+
+        assert!(ensure_not_cancelled().is_ok());
+        assert!(token.run(ensure_not_cancelled).is_err());
+        assert!(ensure_not_cancelled().is_ok());
+    }
+
+    #[test]
+    fn nested_run_restores_outer_token() {
+        let outer = CancellationToken::default();
+        let inner = CancellationToken::default();
+
+        inner.cancel();
+
+        outer.run(|| {
+            assert!(inner.run(ensure_not_cancelled).is_err());
+            assert!(ensure_not_cancelled().is_ok());
+
+            outer.cancel();
+
+            assert!(ensure_not_cancelled().is_err());
+        });
+    }
+}
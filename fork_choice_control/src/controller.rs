@@ -15,7 +15,7 @@ use std::{
     time::Instant,
 };
 
-use anyhow::{Context as _, Result};
+use anyhow::{ensure, Context as _, Result};
 use arc_swap::{ArcSwap, Guard};
 use clock::Tick;
 use eth2_libp2p::{GossipId, PeerId};
@@ -26,6 +26,9 @@ use fork_choice_store::{
 };
 use futures::channel::{mpsc::Sender as MultiSender, oneshot::Sender as OneshotSender};
 use genesis::GenesisProvider;
+use helper_functions::misc;
+use log::{error, info};
+use parking_lot::Mutex;
 use prometheus_metrics::Metrics;
 use std_ext::ArcExt as _;
 use tap::TapFallible as _;
@@ -36,23 +39,27 @@ use types::{
     deneb::containers::BlobSidecar,
     nonstandard::ValidationOutcome,
     phase0::{
-        containers::{Attestation, AttesterSlashing, SignedAggregateAndProof},
-        primitives::{ExecutionBlockHash, Slot, SubnetId},
+        containers::{Attestation, AttesterSlashing, Checkpoint, SignedAggregateAndProof},
+        primitives::{Epoch, ExecutionBlockHash, Slot, SubnetId, H256},
     },
     preset::Preset,
     traits::SignedBeaconBlock as _,
 };
 
 use crate::{
+    availability_cache::AvailabilityCache,
     messages::{
         ApiMessage, MutatorMessage, P2pMessage, SubnetMessage, SyncMessage, ValidatorMessage,
     },
-    misc::{VerifyAggregateAndProofResult, VerifyAttestationResult},
+    misc::{PendingBlobSidecar, VerifyAggregateAndProofResult, VerifyAttestationResult},
     mutator::Mutator,
+    proposer_cache::ProposerCache,
     state_cache::StateCache,
     storage::Storage,
+    storage_health::StorageHealth,
     tasks::{
-        AggregateAndProofTask, AttestationTask, AttesterSlashingTask, BlobSidecarTask, BlockTask,
+        AggregateAndProofTask, AttestationTask, AttesterSlashingTask, BlobSidecarsTask, BlockTask,
+        GossipBlobSidecarsTask,
     },
     thread_pool::{Spawn, ThreadPool},
     unbounded_sink::UnboundedSink,
@@ -64,11 +71,15 @@ pub struct Controller<P: Preset, E, W: Wait> {
     store_snapshot: Arc<ArcSwap<Store<P>>>,
     execution_engine: E,
     state_cache: Arc<StateCache<P, W>>,
+    proposer_cache: Arc<ProposerCache>,
+    availability_cache: Arc<AvailabilityCache<P>>,
     storage: Arc<Storage<P>>,
     thread_pool: ThreadPool<P, E, W>,
     wait_group: W::Swappable,
     metrics: Option<Arc<Metrics>>,
     mutator_tx: Sender<MutatorMessage<P, W>>,
+    // Gossip sidecars waiting for a `GossipBlobSidecarsTask` to verify them in a batch.
+    gossip_blob_sidecars: Arc<Mutex<Vec<(PendingBlobSidecar<P>, W)>>>,
 }
 
 impl<P: Preset, E, W: Wait> Drop for Controller<P, E, W> {
@@ -121,9 +132,14 @@ where
             mutator_tx.clone(),
         ));
 
+        let proposer_cache = Arc::new(ProposerCache::default());
+        let availability_cache = Arc::new(AvailabilityCache::default());
+
         let mut mutator = Mutator::new(
             store_snapshot.clone_arc(),
             state_cache.clone_arc(),
+            proposer_cache.clone_arc(),
+            availability_cache.clone_arc(),
             execution_engine.clone(),
             storage.clone_arc(),
             thread_pool.clone(),
@@ -145,11 +161,14 @@ where
             store_snapshot,
             execution_engine,
             state_cache,
+            proposer_cache,
+            availability_cache,
             storage,
             thread_pool,
             wait_group: wait_group.clone(),
             metrics,
             mutator_tx: mutator_tx.clone(),
+            gossip_blob_sidecars: Arc::default(),
         });
 
         let thread_name = "store-mutator".to_owned();
@@ -181,6 +200,20 @@ where
         self.storage().config()
     }
 
+    pub fn storage_health(&self) -> &StorageHealth {
+        self.storage().health()
+    }
+
+    // Used for graceful shutdown. Other tasks may hold `Controller`s until the runtime exits.
+    // The current chain is saved to storage before the mutator thread exits.
+    // Messages sent to the mutator after this are ignored.
+    pub fn stop(&self) {
+        MutatorMessage::Stop {
+            save_to_storage: true,
+        }
+        .send(&self.mutator_tx);
+    }
+
     // This should be called at the start of every tick.
     // More or less frequent calls are allowed but may worsen performance and quality of the head.
     // According to the Fork Choice specification, `on_tick` should be called every second,
@@ -212,17 +245,31 @@ where
         self.spawn_block_task_with_wait_group(wait_group, block, BlockOrigin::Own)
     }
 
-    pub fn on_own_blob_sidecar(&self, wait_group: W, blob_sidecar: Arc<BlobSidecar<P>>) {
-        self.spawn_blob_sidecar_task_with_wait_group(
-            wait_group,
-            blob_sidecar,
-            true,
+    pub fn on_own_blob_sidecars(
+        &self,
+        wait_group: &W,
+        blob_sidecars: impl IntoIterator<Item = Arc<BlobSidecar<P>>>,
+    ) {
+        self.spawn_blob_sidecars_task(
+            blob_sidecars
+                .into_iter()
+                .map(|blob_sidecar| (blob_sidecar, true, wait_group.clone())),
             BlobSidecarOrigin::Own,
         )
     }
 
-    pub fn on_api_blob_sidecar(&self, blob_sidecar: Arc<BlobSidecar<P>>) {
-        self.spawn_blob_sidecar_task(blob_sidecar, true, BlobSidecarOrigin::Api)
+    pub fn on_api_blob_sidecars(
+        &self,
+        blob_sidecars: impl IntoIterator<Item = Arc<BlobSidecar<P>>>,
+    ) {
+        let wait_group = self.owned_wait_group();
+
+        self.spawn_blob_sidecars_task(
+            blob_sidecars
+                .into_iter()
+                .map(|blob_sidecar| (blob_sidecar, true, wait_group.clone())),
+            BlobSidecarOrigin::Api,
+        )
     }
 
     pub fn on_api_block(
@@ -254,6 +301,128 @@ where
         .send(&self.mutator_tx);
     }
 
+    /// Marks the block and its descendants as invalid regardless of what the execution engine
+    /// reported about them. Meant for manual intervention during consensus incidents.
+    pub fn invalidate_block(&self, block_root: H256) -> Result<()> {
+        self.ensure_unfinalized_with_payload(block_root)?;
+
+        MutatorMessage::InvalidateBlock {
+            wait_group: self.owned_wait_group(),
+            block_root,
+        }
+        .send(&self.mutator_tx);
+
+        Ok(())
+    }
+
+    /// Invalidates all unfinalized branches that do not contain the block in `checkpoint`,
+    /// forcing fork choice to select a head that descends from it.
+    pub fn reanchor(&self, checkpoint: Checkpoint) -> Result<()> {
+        let Checkpoint { epoch, root } = checkpoint;
+
+        let store = self.store_snapshot();
+        let chain_link = store
+            .chain_link(root)
+            .ok_or(Error::BlockNotFound { block_root: root })?;
+
+        ensure!(
+            chain_link.slot() <= misc::compute_start_slot_at_epoch::<P>(epoch),
+            Error::CheckpointBlockAfterEpoch { checkpoint },
+        );
+
+        ensure!(
+            store.last_finalized().slot() < chain_link.slot(),
+            Error::BlockFinalized { block_root: root },
+        );
+
+        MutatorMessage::Reanchor {
+            wait_group: self.owned_wait_group(),
+            block_root: root,
+        }
+        .send(&self.mutator_tx);
+
+        Ok(())
+    }
+
+    /// Submits the execution payload of the block to the execution engine again.
+    /// The block and its descendants are treated as optimistic until the response arrives,
+    /// even if they were previously found to be invalid.
+    pub fn reverify_payload(&self, block_root: H256) -> Result<()> {
+        self.ensure_unfinalized_with_payload(block_root)?;
+
+        MutatorMessage::ReverifyPayload {
+            wait_group: self.owned_wait_group(),
+            block_root,
+        }
+        .send(&self.mutator_tx);
+
+        Ok(())
+    }
+
+    /// Saves finalized blocks and the anchor state to storage without waiting for an epoch that
+    /// is a multiple of `Storage.archival_epoch_interval`.
+    pub fn force_archival(&self) -> Result<()> {
+        self.storage().ensure_archival_allowed()?;
+
+        MutatorMessage::ForceArchival {
+            wait_group: self.owned_wait_group(),
+        }
+        .send(&self.mutator_tx);
+
+        Ok(())
+    }
+
+    /// Deletes archival states from before `epoch` in a background thread.
+    ///
+    /// Blob sidecars are only deleted if peers are no longer allowed to request them.
+    pub fn prune_storage(&self, epoch: Epoch) -> Result<()> {
+        let store = self.store_snapshot();
+        let anchor_epoch = store.anchor_epoch();
+
+        ensure!(
+            epoch <= anchor_epoch,
+            Error::PruningPastAnchor {
+                epoch,
+                anchor_epoch,
+            },
+        );
+
+        let blob_retention_epoch = store
+            .current_epoch()
+            .saturating_sub(store.chain_config().min_epochs_for_blob_sidecars_requests);
+
+        let blob_sidecars_slot =
+            misc::compute_start_slot_at_epoch::<P>(epoch.min(blob_retention_epoch));
+
+        self.storage().start_pruning(epoch)?;
+
+        let storage = self.storage.clone_arc();
+        let wait_group = self.owned_wait_group();
+
+        let spawn_result = Builder::new()
+            .name("storage-pruner".to_owned())
+            .spawn(move || {
+                info!("pruning storage up to epoch {epoch}…");
+
+                match storage.prune_to_epoch(epoch, blob_sidecars_slot) {
+                    Ok(pruned_states) => info!(
+                        "pruned storage up to epoch {epoch} \
+                         (deleted {pruned_states} archival states)",
+                    ),
+                    Err(error) => error!("pruning storage up to epoch {epoch} failed: {error:?}"),
+                }
+
+                drop(wait_group);
+            });
+
+        if let Err(error) = spawn_result {
+            self.storage().abort_pruning();
+            return Err(error.into());
+        }
+
+        Ok(())
+    }
+
     pub fn on_api_aggregate_and_proof(
         &self,
         aggregate_and_proof: Box<SignedAggregateAndProof<P>>,
@@ -388,29 +557,45 @@ where
         gossip_id: GossipId,
         block_seen: bool,
     ) {
-        self.spawn_blob_sidecar_task(
+        let pending_blob_sidecar = PendingBlobSidecar {
             blob_sidecar,
             block_seen,
-            BlobSidecarOrigin::Gossip(subnet_id, gossip_id),
-        )
+            origin: BlobSidecarOrigin::Gossip(subnet_id, gossip_id),
+            submission_time: Instant::now(),
+        };
+
+        let task_pending = {
+            let mut queue = self.gossip_blob_sidecars.lock();
+            let task_pending = !queue.is_empty();
+            queue.push((pending_blob_sidecar, self.owned_wait_group()));
+            task_pending
+        };
+
+        if !task_pending {
+            self.spawn(GossipBlobSidecarsTask {
+                store_snapshot: self.store_snapshot.clone_arc(),
+                mutator_tx: self.owned_mutator_tx(),
+                queue: self.gossip_blob_sidecars.clone_arc(),
+                metrics: self.metrics.clone(),
+            })
+        }
     }
 
-    pub fn on_requested_blob_sidecar(
+    /// Accepts sidecars received from `peer_id` in a single `BlobsByRange` or `BlobsByRoot`
+    /// response, each paired with whether its block has been seen.
+    pub fn on_requested_blob_sidecars(
         &self,
-        blob_sidecar: Arc<BlobSidecar<P>>,
-        block_seen: bool,
+        blob_sidecars: impl IntoIterator<Item = (Arc<BlobSidecar<P>>, bool)>,
         peer_id: PeerId,
     ) {
-        self.spawn(BlobSidecarTask {
-            store_snapshot: self.owned_store_snapshot(),
-            mutator_tx: self.owned_mutator_tx(),
-            wait_group: self.owned_wait_group(),
-            blob_sidecar,
-            block_seen,
-            origin: BlobSidecarOrigin::Requested(peer_id),
-            submission_time: Instant::now(),
-            metrics: self.metrics.clone(),
-        })
+        let wait_group = self.owned_wait_group();
+
+        self.spawn_blob_sidecars_task(
+            blob_sidecars
+                .into_iter()
+                .map(|(blob_sidecar, block_seen)| (blob_sidecar, block_seen, wait_group.clone())),
+            BlobSidecarOrigin::Requested(peer_id),
+        )
     }
 
     pub fn store_back_sync_blocks(
@@ -430,35 +615,35 @@ where
             .archive_back_sync_states(start_slot, end_slot, genesis_provider)
     }
 
-    fn spawn_blob_sidecar_task(
+    fn spawn_blob_sidecars_task(
         &self,
-        blob_sidecar: Arc<BlobSidecar<P>>,
-        block_seen: bool,
+        blob_sidecars: impl IntoIterator<Item = (Arc<BlobSidecar<P>>, bool, W)>,
         origin: BlobSidecarOrigin,
     ) {
-        self.spawn_blob_sidecar_task_with_wait_group(
-            self.owned_wait_group(),
-            blob_sidecar,
-            block_seen,
-            origin,
-        )
-    }
+        let submission_time = Instant::now();
+
+        let blob_sidecars = blob_sidecars
+            .into_iter()
+            .map(|(blob_sidecar, block_seen, wait_group)| {
+                let pending_blob_sidecar = PendingBlobSidecar {
+                    blob_sidecar,
+                    block_seen,
+                    origin: origin.clone(),
+                    submission_time,
+                };
+
+                (pending_blob_sidecar, wait_group)
+            })
+            .collect::<Vec<_>>();
+
+        if blob_sidecars.is_empty() {
+            return;
+        }
 
-    fn spawn_blob_sidecar_task_with_wait_group(
-        &self,
-        wait_group: W,
-        blob_sidecar: Arc<BlobSidecar<P>>,
-        block_seen: bool,
-        origin: BlobSidecarOrigin,
-    ) {
-        self.spawn(BlobSidecarTask {
+        self.spawn(BlobSidecarsTask {
             store_snapshot: self.owned_store_snapshot(),
             mutator_tx: self.owned_mutator_tx(),
-            wait_group,
-            blob_sidecar,
-            block_seen,
-            origin,
-            submission_time: Instant::now(),
+            blob_sidecars,
             metrics: self.metrics.clone(),
         })
     }
@@ -475,6 +660,7 @@ where
     ) {
         self.spawn(BlockTask {
             store_snapshot: self.owned_store_snapshot(),
+            proposer_cache: self.proposer_cache.clone_arc(),
             execution_engine: self.execution_engine.clone(),
             mutator_tx: self.owned_mutator_tx(),
             wait_group,
@@ -493,6 +679,14 @@ where
         &self.state_cache
     }
 
+    pub(crate) const fn proposer_cache(&self) -> &Arc<ProposerCache> {
+        &self.proposer_cache
+    }
+
+    pub(crate) const fn availability_cache(&self) -> &Arc<AvailabilityCache<P>> {
+        &self.availability_cache
+    }
+
     pub(crate) fn store_snapshot(&self) -> Guard<Arc<Store<P>>> {
         self.store_snapshot.load()
     }
@@ -509,6 +703,26 @@ where
         &self.wait_group
     }
 
+    fn ensure_unfinalized_with_payload(&self, block_root: H256) -> Result<()> {
+        let store = self.store_snapshot();
+
+        let chain_link = store
+            .chain_link(block_root)
+            .ok_or(Error::BlockNotFound { block_root })?;
+
+        ensure!(
+            store.last_finalized().slot() < chain_link.slot(),
+            Error::BlockFinalized { block_root },
+        );
+
+        ensure!(
+            chain_link.execution_block_hash().is_some(),
+            Error::BlockWithoutExecutionPayload { block_root },
+        );
+
+        Ok(())
+    }
+
     pub(crate) fn owned_wait_group(&self) -> W {
         Wait::load_and_clone(&self.wait_group)
     }
@@ -582,8 +796,18 @@ impl<P: Preset, W> MutatorHandle<P, W> {
 
 #[derive(Debug, Error)]
 enum Error {
+    #[error("block is already finalized: {block_root:?}")]
+    BlockFinalized { block_root: H256 },
+    #[error("block not found in fork choice store: {block_root:?}")]
+    BlockNotFound { block_root: H256 },
+    #[error("block has no execution payload: {block_root:?}")]
+    BlockWithoutExecutionPayload { block_root: H256 },
+    #[error("checkpoint block is later than the start of its epoch: {checkpoint:?}")]
+    CheckpointBlockAfterEpoch { checkpoint: Checkpoint },
     #[error("mutator panicked")]
     MutatorPanicked,
     #[error("mutator failed")]
     MutatorFailed,
+    #[error("cannot prune storage up to epoch {epoch} past anchor epoch {anchor_epoch}")]
+    PruningPastAnchor { epoch: Epoch, anchor_epoch: Epoch },
 }
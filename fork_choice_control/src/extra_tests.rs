@@ -15,16 +15,18 @@ use core::ops::Not as _;
 use std::{path::PathBuf, sync::Arc};
 
 use anyhow::Result;
+use clock::{Tick, TickKind};
 use eth2_cache_utils::{mainnet, medalla, withdrawal_devnet_3};
 use eth2_libp2p::GossipId;
 use execution_engine::PayloadStatusV1;
 use fork_choice_store::PayloadStatus;
-use helper_functions::misc;
+use helper_functions::{accessors, misc};
+use serde_json::json;
 use std_ext::ArcExt as _;
 use types::{
     combined::SignedBeaconBlock,
     config::Config,
-    phase0::{consts::GENESIS_SLOT, primitives::H256},
+    phase0::{consts::GENESIS_SLOT, containers::Checkpoint, primitives::H256},
     preset::{Medalla, Minimal},
     traits::SignedBeaconBlock as _,
 };
@@ -401,6 +403,88 @@ fn handles_happy_path_with_3_blocks_and_height_difference_of_1() {
     });
 }
 
+#[test]
+fn ignores_repeated_gossip_block_from_same_proposer_and_slot() {
+    let mut context = Context::minimal();
+
+    let (_, state_0) = context.genesis();
+    let (block_1, _) = context.empty_block(&state_0, 1, H256::repeat_byte(1));
+    let (block_2, _) = context.empty_block(&state_0, 1, H256::repeat_byte(2));
+
+    context.on_slot(1);
+
+    context.on_acceptable_block(&block_1);
+    context.on_ignorable_block(&block_2);
+
+    context.assert_status(Status {
+        head: &block_1,
+        attesting_validators: Some(0),
+        store_justified_epoch: 0,
+        store_finalized_epoch: 0,
+        fork_count_viable: 1,
+        fork_count_total: 1,
+        finalized_block_count: 1,
+        unfinalized_block_count_in_fork: 1,
+        unfinalized_block_count_total: 1,
+    });
+
+    context.on_requested_block(&block_2);
+
+    context.assert_status(Status {
+        head: &block_1,
+        attesting_validators: Some(0),
+        store_justified_epoch: 0,
+        store_finalized_epoch: 0,
+        fork_count_viable: 2,
+        fork_count_total: 2,
+        finalized_block_count: 1,
+        unfinalized_block_count_in_fork: 1,
+        unfinalized_block_count_total: 2,
+    });
+}
+
+#[test]
+fn competing_blocks_from_same_proposer_and_slot_are_reported_as_equivocating() {
+    let mut context = Context::minimal();
+
+    let (_, state_0) = context.genesis();
+    let (block_1, _) = context.empty_block(&state_0, 1, H256::repeat_byte(1));
+    let (block_2, _) = context.empty_block(&state_0, 1, H256::repeat_byte(2));
+
+    let proposer_index = block_1.message().proposer_index();
+    let block_root_1 = block_1.message().hash_tree_root();
+    let block_root_2 = block_2.message().hash_tree_root();
+
+    context.on_slot(1);
+
+    assert_eq!(context.proposed_block_root(1, proposer_index), None);
+
+    context.on_acceptable_block(&block_1);
+
+    assert_eq!(
+        context.proposed_block_root(1, proposer_index),
+        Some(block_root_1),
+    );
+    assert_eq!(context.proposed_block_root(1, proposer_index + 1), None);
+    assert_eq!(context.proposed_block_root(2, proposer_index), None);
+    assert_eq!(context.equivocating_block_root(&block_1), None);
+    assert_eq!(
+        context.equivocating_block_root(&block_2),
+        Some(block_root_1)
+    );
+
+    context.on_requested_block(&block_2);
+
+    assert_eq!(
+        context.equivocating_block_root(&block_1),
+        Some(block_root_2)
+    );
+    assert_eq!(
+        context.equivocating_block_root(&block_2),
+        Some(block_root_1)
+    );
+}
+
 #[test]
 fn best_child_is_updated_when_it_falls_behind_the_2nd_best_one() {
     let mut context = Context::minimal();
@@ -585,7 +669,7 @@ fn justified_checkpoint_always_switches_to_later_one() {
         unfinalized_block_count_total: 3,
     });
 
-    context.on_acceptable_block(&block_4);
+    context.on_requested_block(&block_4);
 
     context.assert_status(Status {
         head: &block_3,
@@ -846,14 +930,14 @@ fn finalizes_and_prunes_many_segments_correctly() {
 
     context.on_acceptable_block(&block_1);
     context.on_acceptable_block(&block_2);
-    context.on_acceptable_block(&block_3);
+    context.on_requested_block(&block_3);
     context.on_acceptable_block(&block_4);
-    context.on_acceptable_block(&block_5);
+    context.on_requested_block(&block_5);
     context.on_acceptable_block(&block_6);
     context.on_acceptable_block(&block_7);
-    context.on_acceptable_block(&block_8);
+    context.on_requested_block(&block_8);
     context.on_acceptable_block(&block_9);
-    context.on_acceptable_block(&block_10);
+    context.on_requested_block(&block_10);
     context.on_acceptable_block(&block_11);
     context.on_acceptable_block(&block_12);
     context.on_acceptable_block(&block_13);
@@ -1369,7 +1453,7 @@ fn lighthouse_ffg_updates_two_branches_starting_with_lower_in_a_future_slot() {
         unfinalized_block_count_total: 1,
     });
 
-    context.on_acceptable_block(&block_2);
+    context.on_requested_block(&block_2);
 
     context.assert_status(Status {
         head: &block_2,
@@ -1397,7 +1481,7 @@ fn lighthouse_ffg_updates_two_branches_starting_with_lower_in_a_future_slot() {
         unfinalized_block_count_total: 3,
     });
 
-    context.on_acceptable_block(&block_8);
+    context.on_requested_block(&block_8);
 
     context.assert_status(Status {
         head: &block_7,
@@ -1425,7 +1509,7 @@ fn lighthouse_ffg_updates_two_branches_starting_with_lower_in_a_future_slot() {
         unfinalized_block_count_total: 5,
     });
 
-    context.on_acceptable_block(&block_10);
+    context.on_requested_block(&block_10);
 
     context.assert_status(Status {
         head: &block_9,
@@ -1499,7 +1583,7 @@ fn lighthouse_ffg_updates_two_branches_starting_with_higher_in_a_future_slot() {
         unfinalized_block_count_total: 1,
     });
 
-    context.on_acceptable_block(&block_1);
+    context.on_requested_block(&block_1);
 
     context.assert_status(Status {
         head: &block_2,
@@ -1527,7 +1611,7 @@ fn lighthouse_ffg_updates_two_branches_starting_with_higher_in_a_future_slot() {
         unfinalized_block_count_total: 3,
     });
 
-    context.on_acceptable_block(&block_7);
+    context.on_requested_block(&block_7);
 
     context.assert_status(Status {
         head: &block_8,
@@ -1555,7 +1639,7 @@ fn lighthouse_ffg_updates_two_branches_starting_with_higher_in_a_future_slot() {
         unfinalized_block_count_total: 5,
     });
 
-    context.on_acceptable_block(&block_9);
+    context.on_requested_block(&block_9);
 
     context.assert_status(Status {
         head: &block_10,
@@ -1629,7 +1713,7 @@ fn lighthouse_ffg_updates_two_branches_starting_with_lower_in_real_time() {
         unfinalized_block_count_total: 1,
     });
 
-    context.on_acceptable_block(&block_2);
+    context.on_requested_block(&block_2);
 
     context.assert_status(Status {
         head: &block_1,
@@ -1659,7 +1743,7 @@ fn lighthouse_ffg_updates_two_branches_starting_with_lower_in_real_time() {
         unfinalized_block_count_total: 3,
     });
 
-    context.on_acceptable_block(&block_8);
+    context.on_requested_block(&block_8);
 
     context.assert_status(Status {
         head: &block_7,
@@ -1689,7 +1773,7 @@ fn lighthouse_ffg_updates_two_branches_starting_with_lower_in_real_time() {
         unfinalized_block_count_total: 5,
     });
 
-    context.on_acceptable_block(&block_10);
+    context.on_requested_block(&block_10);
 
     context.assert_status(Status {
         head: &block_9,
@@ -2057,7 +2141,7 @@ fn lighthouse_votes() {
         unfinalized_block_count_total: 6,
     });
 
-    context.on_acceptable_block(&block_7);
+    context.on_requested_block(&block_7);
 
     context.assert_status(Status {
         head: &block_7,
@@ -2103,7 +2187,7 @@ fn lighthouse_votes() {
         unfinalized_block_count_total: 9,
     });
 
-    context.on_acceptable_block(&block_10);
+    context.on_requested_block(&block_10);
 
     context.assert_status(Status {
         head: &block_9,
@@ -2199,6 +2283,54 @@ fn controller_blocks_by_range_can_access_all_blocks_in_a_segment() {
     itertools::assert_equal(actual_blocks, expected_blocks);
 }
 
+#[test]
+fn blob_availability_reports_imported_and_unknown_blocks() -> Result<()> {
+    let mut context = Context::minimal();
+
+    let (_, state_0) = context.genesis();
+    let (block_1, _) = context.empty_block(&state_0, 1, H256::default());
+    let block_root = block_1.message().hash_tree_root();
+    let unknown_root = H256::repeat_byte(1);
+
+    assert_eq!(
+        serde_json::to_value(context.blob_availability(block_root))?,
+        json!({
+            "block_root": block_root,
+            "status": "unknown",
+            "missing_blob_indices": [],
+            "received": [],
+        }),
+    );
+
+    context.on_slot(block_1.message().slot());
+    context.on_acceptable_block(&block_1);
+
+    // Blocks before Deneb have no blobs.
+    assert_eq!(
+        serde_json::to_value(context.blob_availability(block_root))?,
+        json!({
+            "block_root": block_root,
+            "status": "imported",
+            "slot": 1,
+            "blob_count": 0,
+            "missing_blob_indices": [],
+            "received": [],
+        }),
+    );
+
+    assert_eq!(
+        serde_json::to_value(context.blob_availability(unknown_root))?,
+        json!({
+            "block_root": unknown_root,
+            "status": "unknown",
+            "missing_blob_indices": [],
+            "received": [],
+        }),
+    );
+
+    Ok(())
+}
+
 #[test]
 fn head_falls_back_to_previous_block_if_last_block_of_single_fork_is_invalidated() {
     let mut context = Context::bellatrix_minimal();
@@ -2387,6 +2519,44 @@ fn invalidating_a_block_invalidates_its_descendants() {
     context.assert_payload_status(&block_3, Some(PayloadStatus::Invalid));
 }
 
+#[test]
+fn reverifying_an_invalidated_payload_restores_the_block_and_its_descendants() {
+    let mut context = Context::bellatrix_minimal();
+
+    let (_, state_0) = context.genesis();
+    let (block_1, state_1) =
+        context.block_with_payload(&state_0, 1, H256::default(), H256::repeat_byte(1));
+    let (block_2, state_2) =
+        context.block_with_payload(&state_1, 2, H256::default(), H256::repeat_byte(2));
+    let (block_3, _) =
+        context.block_with_payload(&state_2, 3, H256::default(), H256::repeat_byte(3));
+
+    context.on_slot(block_3.message().slot());
+
+    context.on_acceptable_block(&block_1);
+    context.on_acceptable_block(&block_2);
+    context.on_acceptable_block(&block_3);
+
+    context.on_notified_invalid_payload(&block_2, None);
+
+    context.assert_head(block_1.message().slot(), block_1.message().hash_tree_root());
+    context.assert_payload_status(&block_2, Some(PayloadStatus::Invalid));
+    context.assert_payload_status(&block_3, Some(PayloadStatus::Invalid));
+
+    context.on_reverify_payload(&block_2);
+
+    context.assert_head(block_3.message().slot(), block_3.message().hash_tree_root());
+    context.assert_payload_status(&block_1, Some(PayloadStatus::Optimistic));
+    context.assert_payload_status(&block_2, Some(PayloadStatus::Optimistic));
+    context.assert_payload_status(&block_3, Some(PayloadStatus::Optimistic));
+
+    context.on_notified_valid_payload(&block_3);
+
+    context.assert_payload_status(&block_1, Some(PayloadStatus::Valid));
+    context.assert_payload_status(&block_2, Some(PayloadStatus::Valid));
+    context.assert_payload_status(&block_3, Some(PayloadStatus::Valid));
+}
+
 // ```text
 // 0
 //  \
@@ -2450,6 +2620,54 @@ fn all_descendants_of_first_invalid_block_are_invalidated() {
     context.assert_payload_status(&block_5, Some(PayloadStatus::Optimistic));
 }
 
+// ```text
+// 0
+//  \
+//   1
+//   |\
+//   2 \
+//   |\ \
+//   3 | |  block in a conflicting fork
+//     | |
+//     4 |  checkpoint block chosen manually
+//       |
+//       5  block in a conflicting fork
+// ```
+#[test]
+fn reanchoring_invalidates_branches_conflicting_with_checkpoint() {
+    let mut context = Context::bellatrix_minimal();
+
+    let (block_0, state_0) = context.genesis();
+    let (block_1, state_1) =
+        context.block_with_payload(&state_0, 1, H256::default(), H256::repeat_byte(1));
+    let (block_2, state_2) =
+        context.block_with_payload(&state_1, 2, H256::default(), H256::repeat_byte(2));
+    let (block_3, _) =
+        context.block_with_payload(&state_2, 3, H256::default(), H256::repeat_byte(3));
+    let (block_4, _) =
+        context.block_with_payload(&state_2, 4, H256::default(), H256::repeat_byte(4));
+    let (block_5, _) =
+        context.block_with_payload(&state_1, 5, H256::default(), H256::repeat_byte(5));
+
+    context.on_slot(block_5.message().slot());
+
+    context.on_acceptable_block(&block_1);
+    context.on_acceptable_block(&block_2);
+    context.on_acceptable_block(&block_3);
+    context.on_acceptable_block(&block_4);
+    context.on_acceptable_block(&block_5);
+
+    context.on_reanchor(&block_4, 1);
+
+    context.assert_head(block_4.message().slot(), block_4.message().hash_tree_root());
+    context.assert_payload_status(&block_0, Some(PayloadStatus::Valid));
+    context.assert_payload_status(&block_1, Some(PayloadStatus::Optimistic));
+    context.assert_payload_status(&block_2, Some(PayloadStatus::Optimistic));
+    context.assert_payload_status(&block_3, Some(PayloadStatus::Invalid));
+    context.assert_payload_status(&block_4, Some(PayloadStatus::Optimistic));
+    context.assert_payload_status(&block_5, Some(PayloadStatus::Invalid));
+}
+
 // ```text
 // 0
 //  \
@@ -2645,6 +2863,155 @@ fn blocks_after_the_merge_are_optimistic_until_confirmation() {
     context.assert_optimistic(&block_2, false);
 }
 
+#[test]
+fn head_state_is_preprocessed_for_next_slot_at_attest_tick() {
+    let mut context = Context::minimal();
+
+    context.on_tick(Tick {
+        slot: 1,
+        kind: TickKind::Propose,
+    });
+
+    assert!(context.cached_head_state_at_slot(2).is_none());
+
+    context.on_tick(Tick {
+        slot: 1,
+        kind: TickKind::Attest,
+    });
+
+    let cached_state = context
+        .cached_head_state_at_slot(2)
+        .expect("head state should be preprocessed for slot 2");
+
+    // Duties and gossip validation in the next slot get their states through the state cache.
+    context.on_slot(2);
+
+    assert!(Arc::ptr_eq(
+        &context.preprocessed_state_at_current_slot(),
+        &cached_state,
+    ));
+}
+
+// Preprocessing is retried at the last tick of the slot in case it failed or `Attest` was skipped.
+#[test]
+fn head_state_is_preprocessed_for_next_slot_if_attest_tick_is_missed() {
+    let mut context = Context::minimal();
+
+    context.on_tick(Tick {
+        slot: 1,
+        kind: TickKind::AggregateFourth,
+    });
+
+    let cached_state = context
+        .cached_head_state_at_slot(2)
+        .expect("head state should be preprocessed for slot 2");
+
+    assert!(Arc::ptr_eq(
+        &context.preprocessed_state_at_next_slot(),
+        &cached_state,
+    ));
+}
+
+// The safe block is exposed to applications through the `safe` block tag of the execution client,
+// so it should not point to a payload the execution client has not verified yet.
+#[test]
+fn safe_execution_payload_hash_follows_verified_ancestors_of_justified_block() {
+    let mut context = Context::bellatrix_minimal();
+
+    let (_, state_0) = context.genesis();
+    let (block_1, state_1) =
+        context.block_with_payload(&state_0, 1, H256::default(), H256::repeat_byte(1));
+    let (block_2, state_2) = context.block_with_payload(
+        &state_1,
+        start_of_epoch(2),
+        H256::default(),
+        H256::repeat_byte(2),
+    );
+    let (block_3, state_3) = context.block_with_payload_justifying_current_epoch(
+        &state_2,
+        2,
+        H256::default(),
+        H256::repeat_byte(3),
+    );
+    let (block_4, _) = context.block_with_payload(
+        &state_3,
+        start_of_epoch(3),
+        H256::default(),
+        H256::repeat_byte(4),
+    );
+
+    context.on_slot(block_4.message().slot());
+
+    context.on_acceptable_block(&block_1);
+    context.on_acceptable_block(&block_2);
+    context.on_acceptable_block(&block_3);
+    context.on_acceptable_block(&block_4);
+
+    context.assert_justified_checkpoint(Checkpoint {
+        epoch: 2,
+        root: block_2.message().hash_tree_root(),
+    });
+
+    context.on_notified_valid_payload(&block_1);
+
+    context.assert_optimistic(&block_2, true);
+    context.assert_optimistic(&block_4, true);
+    context.assert_safe_execution_payload_hash(H256::repeat_byte(1));
+
+    context.on_notified_valid_payload(&block_2);
+
+    context.assert_optimistic(&block_2, false);
+    context.assert_optimistic(&block_4, true);
+    context.assert_safe_execution_payload_hash(H256::repeat_byte(2));
+
+    // The safe block never moves past the justified block,
+    // even once the execution engine has verified its descendants.
+    context.on_notified_valid_payload(&block_4);
+
+    context.assert_optimistic(&block_4, false);
+    context.assert_safe_execution_payload_hash(H256::repeat_byte(2));
+}
+
+#[test]
+fn safe_execution_payload_hash_falls_back_when_justified_block_is_invalidated() {
+    let mut context = Context::bellatrix_minimal();
+
+    let (_, state_0) = context.genesis();
+    let (block_1, state_1) =
+        context.block_with_payload(&state_0, 1, H256::default(), H256::repeat_byte(1));
+    let (block_2, state_2) = context.block_with_payload(
+        &state_1,
+        start_of_epoch(2),
+        H256::default(),
+        H256::repeat_byte(2),
+    );
+    let (block_3, state_3) = context.block_with_payload_justifying_current_epoch(
+        &state_2,
+        2,
+        H256::default(),
+        H256::repeat_byte(3),
+    );
+    let (block_4, _) = context.block_with_payload(
+        &state_3,
+        start_of_epoch(3),
+        H256::default(),
+        H256::repeat_byte(4),
+    );
+
+    context.on_slot(block_4.message().slot());
+
+    context.on_acceptable_block(&block_1);
+    context.on_acceptable_block(&block_2);
+    context.on_acceptable_block(&block_3);
+    context.on_acceptable_block(&block_4);
+
+    context.on_notified_valid_payload(&block_1);
+    context.on_notified_invalid_payload(&block_2, None);
+
+    context.assert_payload_status(&block_2, Some(PayloadStatus::Invalid));
+    context.assert_safe_execution_payload_hash(H256::repeat_byte(1));
+}
+
 #[test]
 fn reorganizing_due_to_invalidation_sends_notifications_if_common_ancestor_is_finalized() {
     let graffiti = H256::repeat_byte(0);
@@ -2774,3 +3141,87 @@ fn reorganizing_due_to_invalidation_sends_notifications_if_common_ancestor_is_un
         unfinalized_block_count_total: 1,
     });
 }
+
+// Proposers are cached by dependent root. A reorganization that replaces the block at the end of
+// the previous epoch must not reuse proposers computed for the old head.
+//
+// Proposers for epoch 3 are shuffled using the RANDAO mix from the end of epoch 1,
+// so the forks have to diverge in epoch 1 for their proposers to differ.
+#[test]
+fn reorganization_does_not_reuse_proposers_cached_for_old_head() -> Result<()> {
+    let mut context = Context::bellatrix_minimal();
+
+    let last_slot_of_epoch_2 = start_of_epoch(3) - 1;
+
+    let (_, state_0) = context.genesis();
+    let (block_a1, state_a1) = context.block_with_payload(
+        &state_0,
+        start_of_epoch(1),
+        H256::default(),
+        H256::repeat_byte(1),
+    );
+    let (block_a2, _) = context.block_with_payload(
+        &state_a1,
+        last_slot_of_epoch_2,
+        H256::default(),
+        H256::repeat_byte(2),
+    );
+    let (block_b1, state_b1) = context.block_with_payload(
+        &state_0,
+        start_of_epoch(1) + 1,
+        H256::default(),
+        H256::repeat_byte(3),
+    );
+    let (block_b2, state_b2) = context.block_with_payload(
+        &state_b1,
+        start_of_epoch(1) + 2,
+        H256::default(),
+        H256::repeat_byte(4),
+    );
+    let (block_b3, _) = context.block_with_payload(
+        &state_b2,
+        last_slot_of_epoch_2,
+        H256::default(),
+        H256::repeat_byte(5),
+    );
+
+    let root_a2 = block_a2.message().hash_tree_root();
+    let root_b3 = block_b3.message().hash_tree_root();
+
+    context.on_slot(start_of_epoch(3));
+
+    for block in [&block_a1, &block_a2, &block_b1, &block_b2, &block_b3] {
+        context.on_requested_block(block);
+    }
+
+    context.assert_head(last_slot_of_epoch_2, root_a2);
+
+    let old_state = context.preprocessed_state_at_current_slot();
+    let old_proposers = context.proposer_indices(&old_state);
+
+    assert_eq!(context.dependent_root(&old_state, 3), root_a2);
+
+    // Invalidating `block_a1` invalidates `block_a2` as well.
+    context.on_notified_invalid_payload(&block_a1, None);
+
+    context.assert_head(last_slot_of_epoch_2, root_b3);
+
+    let new_state = context.preprocessed_state_at_current_slot();
+    let new_proposers = context.proposer_indices(&new_state);
+
+    let expected_proposers = misc::slots_in_epoch::<Minimal>(3)
+        .map(|slot| accessors::get_beacon_proposer_index_at_slot(&new_state, slot))
+        .collect::<Result<Vec<_>>>()?;
+
+    assert_eq!(context.dependent_root(&new_state, 3), root_b3);
+    assert_eq!(*new_proposers, expected_proposers);
+    assert_ne!(new_proposers, old_proposers);
+
+    // The entry for the old head is kept until finalization but cannot be reached from the new one.
+    assert_eq!(
+        context.cached_proposer_index(start_of_epoch(3), root_a2),
+        Some(old_proposers[0]),
+    );
+
+    Ok(())
+}
@@ -20,14 +20,14 @@ use types::{
         primitives::{Epoch, ExecutionBlockHash, Slot, UnixSeconds, ValidatorIndex, H256},
     },
     preset::{Minimal, Preset},
-    traits::SignedBeaconBlock as _,
+    traits::{BeaconState as _, SignedBeaconBlock as _},
 };
 use unwrap_none::UnwrapNone as _;
 
 use crate::{
     controller::MutatorHandle,
     messages::P2pMessage,
-    queries::BlockWithRoot,
+    queries::{BlobAvailability, BlockWithRoot},
     specialized::{TestController, TestExecutionEngine},
 };
 
@@ -270,6 +270,20 @@ impl<P: Preset> Context<P> {
         assert!(matches!(self.on_block(block), Some(P2pMessage::Accept(_))));
     }
 
+    // Gossip rules ignore repeated proposals, so competing blocks from the same proposer and slot
+    // have to be delivered as if they were requested.
+    pub fn on_requested_block(&mut self, block: &Arc<SignedBeaconBlock<P>>) {
+        self.controller()
+            .on_requested_block(block.clone_arc(), None);
+
+        self.controller().wait_for_tasks();
+
+        assert!(self
+            .controller()
+            .store_snapshot()
+            .contains_block(block.message().hash_tree_root()));
+    }
+
     pub fn on_ignorable_block(&mut self, block: &Arc<SignedBeaconBlock<P>>) {
         assert!(matches!(self.on_block(block), Some(P2pMessage::Ignore(_))));
     }
@@ -391,10 +405,100 @@ impl<P: Preset> Context<P> {
         self.controller().wait_for_tasks();
     }
 
+    pub fn on_reanchor(&self, block: &SignedBeaconBlock<P>, epoch: Epoch) {
+        let checkpoint = Checkpoint {
+            epoch,
+            root: block.message().hash_tree_root(),
+        };
+
+        self.controller()
+            .reanchor(checkpoint)
+            .expect("checkpoint should be in fork choice store and not finalized");
+
+        self.controller().wait_for_tasks();
+    }
+
+    pub fn on_reverify_payload(&self, block: &SignedBeaconBlock<P>) {
+        self.controller()
+            .reverify_payload(block.message().hash_tree_root())
+            .expect("block should be unfinalized and have an execution payload");
+
+        self.controller().wait_for_tasks();
+    }
+
+    #[must_use]
+    pub fn blob_availability(&self, block_root: H256) -> BlobAvailability {
+        self.controller().blob_availability(block_root)
+    }
+
     pub fn blocks_by_range(&self, range: Range<Slot>) -> Result<Vec<BlockWithRoot<P>>> {
         self.controller().blocks_by_range(range)
     }
 
+    #[must_use]
+    pub fn preprocessed_state_at_current_slot(&self) -> Arc<BeaconState<P>> {
+        self.controller()
+            .preprocessed_state_at_current_slot()
+            .expect("head state should be available")
+    }
+
+    #[must_use]
+    pub fn preprocessed_state_at_next_slot(&self) -> Arc<BeaconState<P>> {
+        self.controller()
+            .preprocessed_state_at_next_slot()
+            .expect("head state should be available")
+    }
+
+    // This only looks at states already in the `Store`, unlike the methods of `Controller`.
+    #[must_use]
+    pub fn cached_head_state_at_slot(&self, slot: Slot) -> Option<Arc<BeaconState<P>>> {
+        let store = self.controller().store_snapshot();
+
+        store
+            .preprocessed_state_before_or_at_slot(store.head().block_root, slot)
+            .filter(|state| state.slot() == slot)
+            .cloned()
+    }
+
+    #[must_use]
+    pub fn dependent_root(&self, state: &BeaconState<P>, epoch: Epoch) -> H256 {
+        self.controller()
+            .dependent_root(state, epoch)
+            .expect("dependent root should be available")
+    }
+
+    #[must_use]
+    pub fn proposer_indices(&self, state: &BeaconState<P>) -> Arc<[ValidatorIndex]> {
+        self.controller()
+            .proposer_indices(state)
+            .expect("proposer indices should be computed successfully")
+    }
+
+    #[must_use]
+    pub fn cached_proposer_index(
+        &self,
+        slot: Slot,
+        dependent_root: H256,
+    ) -> Option<ValidatorIndex> {
+        self.controller()
+            .proposer_cache()
+            .proposer_index::<P>(slot, dependent_root)
+    }
+
+    #[must_use]
+    pub fn proposed_block_root(&self, slot: Slot, proposer_index: ValidatorIndex) -> Option<H256> {
+        self.controller().proposed_block_root(slot, proposer_index)
+    }
+
+    #[must_use]
+    pub fn equivocating_block_root(&self, block: &SignedBeaconBlock<P>) -> Option<H256> {
+        self.controller().equivocating_block_root(
+            block.message().slot(),
+            block.message().proposer_index(),
+            block.message().hash_tree_root(),
+        )
+    }
+
     pub fn assert_genesis_time(&self, expected_time: UnixSeconds) {
         assert_eq!(self.controller().genesis_time(), expected_time);
     }
@@ -498,6 +602,13 @@ impl<P: Preset> Context<P> {
         );
     }
 
+    pub fn assert_safe_execution_payload_hash(&self, expected_hash: ExecutionBlockHash) {
+        assert_eq!(
+            self.controller().snapshot().safe_execution_payload_hash(),
+            expected_hash,
+        );
+    }
+
     pub fn assert_forward_synced(&self, expected_forward_synced: bool) {
         assert_eq!(
             self.controller().is_forward_synced(),
@@ -16,31 +16,39 @@
 //! [`storage`]: ::storage
 
 pub use crate::{
+    cancellation::CancellationToken,
     controller::Controller,
     messages::{
         ApiMessage, BlockEvent, ChainReorgEvent, FinalizedCheckpointEvent, HeadEvent, P2pMessage,
         SubnetMessage, SyncMessage, ValidatorMessage,
     },
     misc::{MutatorRejectionReason, VerifyAggregateAndProofResult, VerifyAttestationResult},
-    queries::{BlockWithRoot, ForkChoiceContext, ForkTip, Snapshot},
+    queries::{
+        ArchivalProgress, BlobAvailability, BlockWithRoot, ForkChoiceContext, ForkTip, Snapshot,
+    },
     specialized::{AdHocBenchController, BenchController},
     state_cache::Error as StateCacheError,
     storage::{StateLoadStrategy, Storage, DEFAULT_ARCHIVAL_EPOCH_INTERVAL},
+    storage_health::StorageHealth,
     storage_tool::{export_state_and_blocks, replay_blocks},
     wait::Wait,
 };
 
 pub mod checkpoint_sync;
 
+mod availability_cache;
+mod cancellation;
 mod controller;
 mod messages;
 mod misc;
 mod mutator;
+mod proposer_cache;
 mod queries;
 mod specialized;
 mod state_cache;
 mod storage;
 mod storage_back_sync;
+mod storage_health;
 mod storage_tool;
 mod tasks;
 mod thread_pool;
@@ -99,6 +99,7 @@ pub enum MutatorMessage<P: Preset, W> {
         persisted_blob_ids: Vec<BlobIdentifier>,
     },
     PreprocessedBeaconState {
+        wait_group: Option<W>,
         block_root: H256,
         state: Arc<BeaconState<P>>,
     },
@@ -111,6 +112,21 @@ pub enum MutatorMessage<P: Preset, W> {
         execution_block_hash: ExecutionBlockHash,
         payload_status: PayloadStatusV1,
     },
+    InvalidateBlock {
+        wait_group: W,
+        block_root: H256,
+    },
+    Reanchor {
+        wait_group: W,
+        block_root: H256,
+    },
+    ReverifyPayload {
+        wait_group: W,
+        block_root: H256,
+    },
+    ForceArchival {
+        wait_group: W,
+    },
     // Dropping `Controller.mutator_tx` is not enough to stop the mutator thread because `Mutator`
     // itself keeps a sender in `Mutator.mutator_tx` for spawning tasks.
     //
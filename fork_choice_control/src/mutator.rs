@@ -32,13 +32,13 @@ use eth2_libp2p::GossipId;
 use execution_engine::{ExecutionEngine, PayloadStatusV1};
 use fork_choice_store::{
     AggregateAndProofAction, ApplyBlockChanges, ApplyTickChanges, AttestationAction,
-    AttestationOrigin, AttesterSlashingOrigin, BlobSidecarAction, BlobSidecarOrigin, BlockAction,
-    BlockOrigin, ChainLink, PayloadAction, Store, ValidAttestation,
+    AttestationOrigin, AttesterSlashingOrigin, BlobSidecarAction, BlobSidecarOrigin,
+    BlobSidecarSource, BlockAction, BlockOrigin, ChainLink, PayloadAction, Store, ValidAttestation,
 };
 use futures::channel::{mpsc::Sender as MultiSender, oneshot::Sender as OneshotSender};
 use helper_functions::{accessors, misc, predicates, verifier::NullVerifier};
 use itertools::{Either, Itertools as _};
-use log::{debug, error, info, warn};
+use log::{debug, error, info, warn, Level};
 use prometheus_metrics::Metrics;
 use ssz::SszHash as _;
 use std_ext::ArcExt as _;
@@ -55,12 +55,14 @@ use types::{
 };
 
 use crate::{
+    availability_cache::{Availability, AvailabilityCache},
     messages::{MutatorMessage, P2pMessage, SubnetMessage, SyncMessage, ValidatorMessage},
     misc::{
         Delayed, MutatorRejectionReason, PendingAggregateAndProof, PendingAttestation,
         PendingBlobSidecar, PendingBlock, PendingChainLink, VerifyAggregateAndProofResult,
         VerifyAttestationResult, WaitingForCheckpointState,
     },
+    proposer_cache::ProposerCache,
     state_cache::StateCache,
     storage::Storage,
     tasks::{
@@ -78,8 +80,9 @@ pub struct Mutator<P: Preset, E, W, AS, PS, NS, SS, VS> {
     store: Arc<Store<P>>,
     store_snapshot: Arc<ArcSwap<Store<P>>>,
     state_cache: Arc<StateCache<P, W>>,
+    proposer_cache: Arc<ProposerCache>,
     execution_engine: E,
-    delayed_until_blobs: HashMap<H256, PendingBlock<P>>,
+    availability_cache: Arc<AvailabilityCache<P>>,
     delayed_until_block: HashMap<H256, Delayed<P>>,
     // We previously ignored objects that would have to be delayed more than one slot. This was
     // based on the assumption that one slot is enough to account for clock differences between
@@ -129,6 +132,8 @@ where
     pub fn new(
         store_snapshot: Arc<ArcSwap<Store<P>>>,
         state_cache: Arc<StateCache<P, W>>,
+        proposer_cache: Arc<ProposerCache>,
+        availability_cache: Arc<AvailabilityCache<P>>,
         execution_engine: E,
         storage: Arc<Storage<P>>,
         thread_pool: ThreadPool<P, E, W>,
@@ -145,8 +150,9 @@ where
             store: store_snapshot.load_full(),
             store_snapshot,
             state_cache,
+            proposer_cache,
             execution_engine,
-            delayed_until_blobs: HashMap::new(),
+            availability_cache,
             delayed_until_block: HashMap::new(),
             delayed_until_slot: BTreeMap::new(),
             delayed_until_payload: HashMap::new(),
@@ -232,8 +238,13 @@ where
                 } => {
                     self.handle_finish_persisting_blob_sidecars(wait_group, persisted_blob_ids);
                 }
-                MutatorMessage::PreprocessedBeaconState { block_root, state } => {
+                MutatorMessage::PreprocessedBeaconState {
+                    wait_group,
+                    block_root,
+                    state,
+                } => {
                     self.handle_preprocessed_beacon_state(block_root, &state);
+                    drop(wait_group);
                 }
                 MutatorMessage::NotifiedForkChoiceUpdate {
                     wait_group,
@@ -248,6 +259,25 @@ where
                     execution_block_hash,
                     payload_status,
                 ),
+                MutatorMessage::InvalidateBlock {
+                    wait_group,
+                    block_root,
+                } => self.handle_invalidate_block(&wait_group, block_root),
+                MutatorMessage::Reanchor {
+                    wait_group,
+                    block_root,
+                } => self.handle_reanchor(&wait_group, block_root),
+                MutatorMessage::ReverifyPayload {
+                    wait_group,
+                    block_root,
+                } => self.handle_reverify_payload(&wait_group, block_root),
+                MutatorMessage::ForceArchival { wait_group } => {
+                    // Forced archival is requested through the HTTP API.
+                    // A failed request should not stop the mutator.
+                    if let Err(error) = self.handle_force_archival(&wait_group) {
+                        error!("forced archival failed: {error:?}");
+                    }
+                }
                 MutatorMessage::Stop { save_to_storage } => {
                     break self.handle_stop(save_to_storage);
                 }
@@ -334,30 +364,7 @@ where
             let head = self.store.head();
 
             if head.is_optimistic() {
-                if let Some(execution_payload) = head.block.as_ref().clone().execution_payload() {
-                    let mut params = None;
-
-                    if let Some(body) = head.block.message().body().post_deneb() {
-                        let versioned_hashes = body
-                            .blob_kzg_commitments()
-                            .iter()
-                            .copied()
-                            .map(helper_functions::misc::kzg_commitment_to_versioned_hash)
-                            .collect();
-
-                        params = Some(ExecutionPayloadParams::Deneb {
-                            versioned_hashes,
-                            parent_beacon_block_root: head.block.message().parent_root(),
-                        });
-                    }
-
-                    self.execution_engine.notify_new_payload(
-                        head.block_root,
-                        execution_payload,
-                        params,
-                        None,
-                    )?;
-                }
+                self.notify_new_payload(head)?;
             }
         }
 
@@ -368,6 +375,8 @@ where
         if changes.is_finalized_checkpoint_updated() {
             self.archive_finalized(wait_group)?;
             self.prune_delayed_until_payload();
+            self.availability_cache.prune(self.store.finalized_slot());
+            self.proposer_cache.prune(self.store.finalized_epoch());
         }
 
         self.update_store_snapshot();
@@ -395,9 +404,17 @@ where
 
         if let ApplyTickChanges::Reorganized { old_head, .. } = changes {
             self.notify_about_reorganization(wait_group.clone(), &old_head);
-            self.spawn_preprocess_head_state_for_next_slot_task();
+            self.spawn_preprocess_head_state_for_next_slot_task(wait_group.clone());
         } else if self.store.tick().kind == TickKind::Attest {
-            self.spawn_preprocess_head_state_for_next_slot_task();
+            self.spawn_preprocess_head_state_for_next_slot_task(wait_group.clone());
+        } else if self.store.tick().kind == TickKind::AggregateFourth
+            && !self.is_head_state_preprocessed_for_next_slot()
+        {
+            // The state preprocessed earlier may be missing if the task failed or if the
+            // application lagged enough to miss the `Attest` tick. Make one more attempt shortly
+            // before the slot starts so that duties and gossip validation do not have to process
+            // slots themselves.
+            self.spawn_preprocess_head_state_for_next_slot_task(wait_group.clone());
         }
 
         if tick.kind == TickKind::AttestFourth
@@ -465,25 +482,41 @@ where
                 let missing_blob_indices =
                     self.store.indices_of_missing_blobs(&pending_block.block);
 
-                if missing_blob_indices.is_empty() {
-                    self.retry_block(wait_group, pending_block);
-                } else {
-                    debug!("block delayed until blobs: {pending_block:?}");
-
-                    if let Some(gossip_id) = pending_block.origin.gossip_id() {
-                        P2pMessage::Accept(gossip_id).send(&self.p2p_tx);
-                    }
+                let received_blob_indices = self
+                    .store
+                    .received_blob_sidecars(block_root)
+                    .map(|(index, _)| index);
 
-                    let blob_ids = missing_blob_indices
-                        .into_iter()
-                        .map(|index| BlobIdentifier { block_root, index })
-                        .collect_vec();
+                let gossip_id = pending_block.origin.gossip_id();
+                let peer_id = pending_block.origin.peer_id();
 
-                    let peer_id = pending_block.origin.peer_id();
+                match self.availability_cache.insert(
+                    block_root,
+                    pending_block,
+                    missing_blob_indices,
+                    received_blob_indices,
+                ) {
+                    Availability::Available(pending_block) => {
+                        self.retry_block(wait_group, pending_block);
+                    }
+                    Availability::Missing(missing_blob_indices) => {
+                        debug!(
+                            "block delayed until blobs \
+                             (block_root: {block_root:?}, slot: {slot}, \
+                             missing_blob_indices: {missing_blob_indices:?})",
+                        );
+
+                        if let Some(gossip_id) = gossip_id {
+                            P2pMessage::Accept(gossip_id).send(&self.p2p_tx);
+                        }
 
-                    P2pMessage::BlobsNeeded(blob_ids, slot, peer_id).send(&self.p2p_tx);
+                        let blob_ids = missing_blob_indices
+                            .into_iter()
+                            .map(|index| BlobIdentifier { block_root, index })
+                            .collect_vec();
 
-                    self.delay_block_until_blobs(block_root, pending_block);
+                        P2pMessage::BlobsNeeded(blob_ids, slot, peer_id).send(&self.p2p_tx);
+                    }
                 }
             }
             Ok(BlockAction::DelayUntilParent(block)) => {
@@ -612,10 +645,15 @@ where
                         .send(&self.validator_tx);
                 }
 
+                let is_first_from_aggregator = self.store.register_aggregator(
+                    aggregate_and_proof.message.aggregate.data.target.epoch,
+                    aggregate_and_proof.message.aggregator_index,
+                );
+
                 let (gossip_id, sender) = origin.split();
 
                 if let Some(gossip_id) = gossip_id {
-                    if is_superset {
+                    if is_superset && is_first_from_aggregator {
                         P2pMessage::Accept(gossip_id).send(&self.p2p_tx);
                     } else {
                         P2pMessage::Ignore(gossip_id).send(&self.p2p_tx);
@@ -636,7 +674,7 @@ where
 
                 if let Some(old_head) = old_head {
                     self.notify_about_reorganization(wait_group.clone(), &old_head);
-                    self.spawn_preprocess_head_state_for_next_slot_task();
+                    self.spawn_preprocess_head_state_for_next_slot_task(wait_group.clone());
                 }
             }
             Ok(AggregateAndProofAction::Ignore) => {
@@ -773,10 +811,22 @@ where
                 }
 
                 let is_from_block = origin.is_from_block();
+
+                // Attestations from blocks and aggregates do not count as seen on a subnet.
+                let is_first_on_subnet = origin.subnet_id().is_none()
+                    || self.store.register_subnet_attesters(
+                        attestation.data.target.epoch,
+                        attesting_indices.iter().copied(),
+                    );
+
                 let (gossip_id, sender) = origin.split();
 
                 if let Some(gossip_id) = gossip_id {
-                    P2pMessage::Accept(gossip_id).send(&self.p2p_tx);
+                    if is_first_on_subnet {
+                        P2pMessage::Accept(gossip_id).send(&self.p2p_tx);
+                    } else {
+                        P2pMessage::Ignore(gossip_id).send(&self.p2p_tx);
+                    }
                 }
 
                 reply_to_http_api(sender, Ok(ValidationOutcome::Accept));
@@ -793,7 +843,7 @@ where
 
                 if let Some(old_head) = old_head {
                     self.notify_about_reorganization(wait_group.clone(), &old_head);
-                    self.spawn_preprocess_head_state_for_next_slot_task();
+                    self.spawn_preprocess_head_state_for_next_slot_task(wait_group.clone());
                 }
             }
             Ok(AttestationAction::Ignore) => {
@@ -974,7 +1024,7 @@ where
 
         if let Some(old_head) = old_head {
             self.notify_about_reorganization(wait_group.clone(), &old_head);
-            self.spawn_preprocess_head_state_for_next_slot_task();
+            self.spawn_preprocess_head_state_for_next_slot_task(wait_group.clone());
         }
 
         Ok(())
@@ -996,7 +1046,7 @@ where
 
                 if let Some(old_head) = old_head {
                     self.notify_about_reorganization(wait_group.clone(), &old_head);
-                    self.spawn_preprocess_head_state_for_next_slot_task();
+                    self.spawn_preprocess_head_state_for_next_slot_task(wait_group.clone());
                 }
             }
             Err(error) => warn!("attester slashing rejected (error: {error}, origin: {origin:?})"),
@@ -1015,11 +1065,13 @@ where
     ) {
         match result {
             Ok(BlobSidecarAction::Accept(blob_sidecar)) => {
+                let source = origin.source();
+
                 if let Some(gossip_id) = origin.gossip_id() {
                     P2pMessage::Accept(gossip_id).send(&self.p2p_tx);
                 }
 
-                self.accept_blob_sidecar(&wait_group, blob_sidecar);
+                self.accept_blob_sidecar(&wait_group, blob_sidecar, source);
             }
             Ok(BlobSidecarAction::Ignore) => {
                 if let Some(gossip_id) = origin.gossip_id() {
@@ -1258,6 +1310,94 @@ where
         self.handle_potential_head_change(wait_group, &old_head, head_was_optimistic);
     }
 
+    fn handle_invalidate_block(&mut self, wait_group: &W, block_root: H256) {
+        let Some(execution_block_hash) = self
+            .store
+            .chain_link(block_root)
+            .and_then(ChainLink::execution_block_hash)
+        else {
+            warn!("cannot invalidate block without execution payload (block_root: {block_root:?})");
+            return;
+        };
+
+        let old_head = self.store.head().clone();
+        let head_was_optimistic = old_head.is_optimistic();
+
+        self.store_mut()
+            .invalidate_block_and_descendant_payload_statuses(execution_block_hash);
+        self.update_store_snapshot();
+
+        self.handle_potential_head_change(wait_group, &old_head, head_was_optimistic);
+    }
+
+    fn handle_reanchor(&mut self, wait_group: &W, block_root: H256) {
+        let old_head = self.store.head().clone();
+        let head_was_optimistic = old_head.is_optimistic();
+
+        self.store_mut()
+            .invalidate_branches_conflicting_with(block_root);
+        self.update_store_snapshot();
+
+        self.handle_potential_head_change(wait_group, &old_head, head_was_optimistic);
+    }
+
+    fn handle_reverify_payload(&mut self, wait_group: &W, block_root: H256) {
+        let Some(chain_link) = self.store.chain_link(block_root).cloned() else {
+            warn!("cannot reverify payload of unknown block (block_root: {block_root:?})");
+            return;
+        };
+
+        let Some(execution_block_hash) = chain_link.execution_block_hash() else {
+            warn!("cannot reverify block without execution payload (block_root: {block_root:?})");
+            return;
+        };
+
+        let old_head = self.store.head().clone();
+        let head_was_optimistic = old_head.is_optimistic();
+
+        self.store_mut()
+            .reset_payload_statuses_for_reverification(execution_block_hash);
+        self.update_store_snapshot();
+
+        self.handle_potential_head_change(wait_group, &old_head, head_was_optimistic);
+
+        // The response is handled like any other `engine_newPayload` response.
+        // The blocks stay optimistic if the payload cannot be submitted.
+        if let Err(error) = self.notify_new_payload(&chain_link) {
+            warn!(
+                "failed to submit payload for reverification \
+                 (block_root: {block_root:?}, error: {error:?})",
+            );
+        }
+    }
+
+    fn handle_force_archival(&mut self, wait_group: &W) -> Result<()> {
+        self.archive_finalized(wait_group)?;
+        self.update_store_snapshot();
+
+        let anchor = self.store.anchor().clone();
+        let state = anchor.state(&self.store);
+        let storage = self.storage.clone_arc();
+        let wait_group = wait_group.clone();
+
+        Builder::new()
+            .name("state-archiver".to_owned())
+            .spawn(move || {
+                let slot = anchor.slot();
+
+                info!("saving anchor state in slot {slot} on request…");
+
+                match storage.archive_state(anchor.block_root, &state) {
+                    Ok(()) => info!("saved anchor state in slot {slot}"),
+                    Err(error) => error!("saving anchor state failed: {error:?}"),
+                }
+
+                drop(wait_group);
+            })?;
+
+        Ok(())
+    }
+
     fn handle_potential_head_change(
         &self,
         wait_group: &W,
@@ -1284,7 +1424,7 @@ where
 
         if head_changed {
             self.notify_about_reorganization(wait_group.clone(), old_head);
-            self.spawn_preprocess_head_state_for_next_slot_task();
+            self.spawn_preprocess_head_state_for_next_slot_task(wait_group.clone());
         }
     }
 
@@ -1325,6 +1465,8 @@ where
         let block_root = chain_link.block_root;
         let block = &chain_link.block;
 
+        self.availability_cache.remove(block_root);
+
         // Check if the block is already present in the store.
         // This is done here primarily to avoid spawning redundant `BlockAttestationsTask`s.
         if self.store.contains_block(block_root) {
@@ -1463,6 +1605,8 @@ where
         if changes.is_finalized_checkpoint_updated() {
             self.archive_finalized(wait_group)?;
             self.prune_delayed_until_payload();
+            self.availability_cache.prune(self.store.finalized_slot());
+            self.proposer_cache.prune(self.store.finalized_epoch());
         }
 
         // Call `Store::apply_attester_slashing` after `Store::archive_finalized` to reduce the
@@ -1538,11 +1682,11 @@ where
                 }
 
                 self.notify_forkchoice_updated(&new_head);
-                self.spawn_preprocess_head_state_for_next_slot_task();
+                self.spawn_preprocess_head_state_for_next_slot_task(wait_group.clone());
             }
             ApplyBlockChanges::Reorganized { old_head, .. } => {
                 self.notify_about_reorganization(wait_group.clone(), &old_head);
-                self.spawn_preprocess_head_state_for_next_slot_task();
+                self.spawn_preprocess_head_state_for_next_slot_task(wait_group.clone());
             }
             ApplyBlockChanges::AlternateChainExtended { .. } => {}
         }
@@ -1563,17 +1707,33 @@ where
         Ok(())
     }
 
-    fn accept_blob_sidecar(&mut self, wait_group: &W, blob_sidecar: Arc<BlobSidecar<P>>) {
+    fn accept_blob_sidecar(
+        &mut self,
+        wait_group: &W,
+        blob_sidecar: Arc<BlobSidecar<P>>,
+        source: BlobSidecarSource,
+    ) {
         let old_head = self.store.head().clone();
         let head_was_optimistic = old_head.is_optimistic();
         let block_root = blob_sidecar.signed_block_header.message.hash_tree_root();
+        let index = blob_sidecar.index;
 
-        self.store_mut().apply_blob_sidecar(blob_sidecar);
+        self.store_mut().apply_blob_sidecar(blob_sidecar, source);
 
         self.update_store_snapshot();
 
-        if let Some(pending_block) = self.delayed_until_blobs.get(&block_root) {
-            self.retry_block(wait_group.clone(), pending_block.clone());
+        if let Some((pending_block, waiting_time)) =
+            self.availability_cache.on_blob_sidecar(block_root, index)
+        {
+            debug!("blobs for delayed block {block_root:?} available after {waiting_time:?}");
+
+            if let Some(metrics) = self.metrics.as_ref() {
+                metrics
+                    .block_blob_availability_times
+                    .observe(waiting_time.as_secs_f64());
+            }
+
+            self.retry_block(wait_group.clone(), pending_block);
         }
 
         self.spawn(PersistBlobSidecarsTask {
@@ -1628,16 +1788,32 @@ where
     fn notify_about_reorganization(&self, wait_group: W, old_head: &ChainLink<P>) {
         let new_head = self.store.head().clone();
         let event = ChainReorgEvent::new(&self.store, old_head);
+        let depth = event.depth;
+
+        // `ChainReorgEvent.depth` is the distance from the old head to the common ancestor.
+        let common_ancestor_slot = old_head.slot().saturating_sub(depth);
 
         ApiMessage::ChainReorgEvent(event).send(&self.api_tx);
 
         if let Some(metrics) = self.metrics.as_ref() {
             metrics.beacon_reorgs_total.inc();
+            metrics.observe_reorg_depth(depth);
         }
 
-        info!(
-            "chain reorganized (old head: {:?}, new head: {:?})",
-            old_head.block_root, new_head.block_root,
+        let level = if depth > self.store.store_config().reorg_warning_depth {
+            Level::Warn
+        } else {
+            Level::Info
+        };
+
+        log::log!(
+            level,
+            "chain reorganized (old head: {:?} at slot {}, new head: {:?} at slot {}, \
+             depth: {depth}, common ancestor slot: {common_ancestor_slot})",
+            old_head.block_root,
+            old_head.slot(),
+            new_head.block_root,
+            new_head.slot(),
         );
 
         let state = new_head.state(&self.store);
@@ -1655,6 +1831,35 @@ where
         self.notify_forkchoice_updated(&new_head);
     }
 
+    fn notify_new_payload(&self, chain_link: &ChainLink<P>) -> Result<()> {
+        let Some(execution_payload) = chain_link.block.as_ref().clone().execution_payload() else {
+            return Ok(());
+        };
+
+        let mut params = None;
+
+        if let Some(body) = chain_link.block.message().body().post_deneb() {
+            let versioned_hashes = body
+                .blob_kzg_commitments()
+                .iter()
+                .copied()
+                .map(misc::kzg_commitment_to_versioned_hash)
+                .collect();
+
+            params = Some(ExecutionPayloadParams::Deneb {
+                versioned_hashes,
+                parent_beacon_block_root: chain_link.block.message().parent_root(),
+            });
+        }
+
+        self.execution_engine.notify_new_payload(
+            chain_link.block_root,
+            execution_payload,
+            params,
+            None,
+        )
+    }
+
     fn notify_forkchoice_updated(&self, new_head: &ChainLink<P>) {
         let new_head_state = new_head.state(&self.store);
 
@@ -1682,11 +1887,6 @@ where
         }
     }
 
-    fn delay_block_until_blobs(&mut self, beacon_block_root: H256, pending_block: PendingBlock<P>) {
-        self.delayed_until_blobs
-            .insert(beacon_block_root, pending_block);
-    }
-
     fn delay_block_until_parent(&mut self, pending_block: PendingBlock<P>) {
         // Blocks produced by the application itself should never be delayed.
         assert!(!matches!(pending_block.origin, BlockOrigin::Own));
@@ -1909,6 +2109,7 @@ where
 
         self.spawn(BlockTask {
             store_snapshot: self.owned_store(),
+            proposer_cache: self.proposer_cache.clone_arc(),
             execution_engine: self.execution_engine.clone(),
             mutator_tx: self.owned_mutator_tx(),
             wait_group,
@@ -2175,19 +2376,28 @@ where
         });
     }
 
-    fn spawn_preprocess_head_state_for_next_slot_task(&self) {
+    fn spawn_preprocess_head_state_for_next_slot_task(&self, wait_group: W) {
         if !self.store.is_forward_synced() {
             return;
         }
 
         self.spawn(PreprocessStateTask {
             state_cache: self.state_cache.clone_arc(),
+            wait_group,
             head_block_root: self.store.head().block_root,
             next_slot: self.store.slot() + 1,
             metrics: self.metrics.clone(),
         })
     }
 
+    fn is_head_state_preprocessed_for_next_slot(&self) -> bool {
+        let next_slot = self.store.slot() + 1;
+
+        self.store
+            .preprocessed_state_before_or_at_slot(self.store.head().block_root, next_slot)
+            .is_some_and(|state| state.slot() == next_slot)
+    }
+
     fn archive_finalized(&mut self, wait_group: &W) -> Result<()> {
         if let Some(latest_archivable_index) = self.store.latest_archivable_index() {
             debug!("archiving finalized blocks and anchor state…");
@@ -0,0 +1,83 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+use anyhow::Result;
+use helper_functions::{accessors, misc};
+use parking_lot::Mutex;
+use std_ext::ArcExt;
+use types::{
+    combined::BeaconState,
+    phase0::primitives::{Epoch, Slot, ValidatorIndex, H256},
+    preset::Preset,
+};
+
+/// Proposer indices for whole epochs keyed by epoch and dependent root.
+///
+/// Proposers for an epoch are determined by the state at the end of the previous epoch, so all
+/// chains that contain the block at that point agree on them. Reorganizations that replace that
+/// block change the dependent root, which makes stale entries unreachable rather than incorrect.
+#[derive(Default)]
+pub struct ProposerCache {
+    proposers: Mutex<BTreeMap<Epoch, HashMap<H256, Arc<[ValidatorIndex]>>>>,
+}
+
+impl ProposerCache {
+    #[must_use]
+    pub fn proposer_index<P: Preset>(
+        &self,
+        slot: Slot,
+        dependent_root: H256,
+    ) -> Option<ValidatorIndex> {
+        let epoch = misc::compute_epoch_at_slot::<P>(slot);
+        let position = usize::try_from(misc::slots_since_epoch_start::<P>(slot)).ok()?;
+
+        self.proposers
+            .lock()
+            .get(&epoch)?
+            .get(&dependent_root)?
+            .get(position)
+            .copied()
+    }
+
+    /// Returns proposers for the current epoch of `state`, computing them if needed.
+    ///
+    /// `dependent_root` must be the dependent root of the current epoch of `state`.
+    pub fn proposer_indices<P: Preset>(
+        &self,
+        state: &BeaconState<P>,
+        dependent_root: H256,
+    ) -> Result<Arc<[ValidatorIndex]>> {
+        let epoch = accessors::get_current_epoch(state);
+
+        let cached = self
+            .proposers
+            .lock()
+            .get(&epoch)
+            .and_then(|proposers| proposers.get(&dependent_root))
+            .map(ArcExt::clone_arc);
+
+        if let Some(proposer_indices) = cached {
+            return Ok(proposer_indices);
+        }
+
+        let proposer_indices = misc::slots_in_epoch::<P>(epoch)
+            .map(|slot| accessors::get_beacon_proposer_index_at_slot(state, slot))
+            .collect::<Result<Arc<[_]>>>()?;
+
+        self.proposers
+            .lock()
+            .entry(epoch)
+            .or_default()
+            .insert(dependent_root, proposer_indices.clone_arc());
+
+        Ok(proposer_indices)
+    }
+
+    // Blocks in finalized epochs are never validated again, and neither are duties for them.
+    pub fn prune(&self, finalized_epoch: Epoch) {
+        let mut proposers = self.proposers.lock();
+        *proposers = proposers.split_off(&finalized_epoch);
+    }
+}
@@ -3,33 +3,41 @@ use std::sync::Arc;
 
 use anyhow::{bail, ensure, Result};
 use arc_swap::Guard;
+use bls::PublicKeyBytes;
 use eth2_libp2p::GossipId;
 use execution_engine::ExecutionEngine;
 use fork_choice_store::{
-    AggregateAndProofOrigin, AttestationOrigin, ChainLink, PayloadStatus, Segment, Store,
+    AggregateAndProofOrigin, AttestationOrigin, BlobSidecarSource, ChainLink, PayloadStatus,
+    Segment, Store,
 };
-use helper_functions::misc;
+use helper_functions::{accessors, misc};
 use itertools::Itertools as _;
 use serde::Serialize;
 use std_ext::ArcExt;
 use thiserror::Error;
 use types::{
     combined::{BeaconState, SignedBeaconBlock},
-    deneb::containers::{BlobIdentifier, BlobSidecar},
+    deneb::{
+        containers::{BlobIdentifier, BlobSidecar},
+        primitives::BlobIndex,
+    },
     nonstandard::{Phase, WithStatus},
     phase0::{
         containers::{Attestation, Checkpoint, SignedAggregateAndProof},
-        primitives::{Epoch, ExecutionBlockHash, Gwei, Slot, SubnetId, UnixSeconds, H256},
+        primitives::{
+            Epoch, ExecutionBlockHash, Gwei, Slot, SubnetId, UnixSeconds, ValidatorIndex, H256,
+        },
     },
     preset::Preset,
     traits::{BeaconState as _, SignedBeaconBlock as _},
 };
 
 use crate::{
+    availability_cache::PendingBlobs,
     controller::Controller,
     misc::{VerifyAggregateAndProofResult, VerifyAttestationResult},
     state_cache::StateCache,
-    storage::Storage,
+    storage::{ArchivalStatus, Storage},
     wait::Wait,
 };
 
@@ -93,18 +101,7 @@ where
     /// [Eth Beacon Node API]: https://ethereum.github.io/beacon-APIs/
     pub fn justified_state(&self) -> Result<WithStatus<Arc<BeaconState<P>>>> {
         let store = self.store_snapshot();
-
-        // `rustfmt` formats the method chain below in a surprising and counterproductive way.
-        // <https://github.com/rust-lang/rustfmt/issues/2961> describes a similar problem.
-        // <https://github.com/rust-lang/rustfmt/issues/3514> proposes adding an option.
-        // <https://github.com/rust-lang/rustfmt/pull/4886> implements the option.
-        #[rustfmt::skip]
-        let chain_link = store
-            .justified_chain_link()
-            .ok_or_else(|| Error::JustifiedBlockPruned {
-                justified_checkpoint: store.justified_checkpoint(),
-                finalized_checkpoint: store.finalized_checkpoint(),
-            })?;
+        let chain_link = justified_chain_link(&store)?;
 
         Ok(WithStatus {
             value: chain_link.state(&store),
@@ -113,6 +110,28 @@ where
         })
     }
 
+    pub fn justified_block_root(&self) -> Result<WithStatus<H256>> {
+        let store = self.store_snapshot();
+        let chain_link = justified_chain_link(&store)?;
+
+        Ok(WithStatus {
+            value: chain_link.block_root,
+            optimistic: chain_link.is_optimistic(),
+            finalized: store.is_slot_finalized(chain_link.slot()),
+        })
+    }
+
+    pub fn justified_block(&self) -> Result<WithStatus<Arc<SignedBeaconBlock<P>>>> {
+        let store = self.store_snapshot();
+        let chain_link = justified_chain_link(&store)?;
+
+        Ok(WithStatus {
+            value: chain_link.block.clone_arc(),
+            optimistic: chain_link.is_optimistic(),
+            finalized: store.is_slot_finalized(chain_link.slot()),
+        })
+    }
+
     #[must_use]
     pub fn last_finalized_block_root(&self) -> WithStatus<H256> {
         let store = self.store_snapshot();
@@ -193,6 +212,37 @@ where
             .collect_vec()
     }
 
+    // Unlike `block_by_slot`, this includes blocks outside the canonical chain.
+    // Invalid blocks are excluded because they were never meant to be served.
+    #[must_use]
+    pub fn unfinalized_blocks(
+        &self,
+        slot: Option<Slot>,
+        parent_root: Option<H256>,
+    ) -> Vec<WithStatus<BlockWithRoot<P>>> {
+        self.store_snapshot()
+            .unfinalized()
+            .values()
+            .flatten()
+            .filter(|unfinalized_block| !unfinalized_block.is_invalid())
+            .map(|unfinalized_block| &unfinalized_block.chain_link)
+            .filter(|chain_link| slot.map_or(true, |slot| chain_link.slot() == slot))
+            .filter(|chain_link| {
+                parent_root.map_or(true, |parent_root| {
+                    chain_link.block.message().parent_root() == parent_root
+                })
+            })
+            .map(|chain_link| WithStatus {
+                value: BlockWithRoot {
+                    block: chain_link.block.clone_arc(),
+                    root: chain_link.block_root,
+                },
+                optimistic: chain_link.is_optimistic(),
+                finalized: false,
+            })
+            .collect()
+    }
+
     #[must_use]
     pub fn fork_choice_context(&self) -> ForkChoiceContext {
         let store = self.store_snapshot();
@@ -225,6 +275,69 @@ where
         }
     }
 
+    /// Reports which blob sidecars have been received for the block with `block_root`.
+    ///
+    /// This is meant for debugging data availability issues. Blob sidecars are only kept for a
+    /// few slots, so the report is only meaningful for recent blocks.
+    #[must_use]
+    pub fn blob_availability(&self, block_root: H256) -> BlobAvailability {
+        let store = self.store_snapshot();
+
+        let received = store
+            .received_blob_sidecars(block_root)
+            .sorted_unstable_by_key(|(index, _)| *index)
+            .map(|(index, source)| ReceivedBlobSidecar { index, source })
+            .collect();
+
+        if let Some(pending_blobs) = self.availability_cache().pending_blobs(block_root) {
+            let PendingBlobs {
+                slot,
+                blob_count,
+                missing_blob_indices,
+                waiting_time,
+            } = pending_blobs;
+
+            return BlobAvailability {
+                block_root,
+                status: BlobAvailabilityStatus::Pending,
+                slot: Some(slot),
+                blob_count: Some(blob_count),
+                missing_blob_indices,
+                received,
+                waiting_time_ms: Some(waiting_time.as_millis()),
+            };
+        }
+
+        if let Some(chain_link) = store.chain_link(block_root) {
+            let blob_count = chain_link
+                .block
+                .message()
+                .body()
+                .post_deneb()
+                .map_or(0, |body| body.blob_kzg_commitments().len());
+
+            return BlobAvailability {
+                block_root,
+                status: BlobAvailabilityStatus::Imported,
+                slot: Some(chain_link.slot()),
+                blob_count: Some(blob_count),
+                missing_blob_indices: vec![],
+                received,
+                waiting_time_ms: None,
+            };
+        }
+
+        BlobAvailability {
+            block_root,
+            status: BlobAvailabilityStatus::Unknown,
+            slot: None,
+            blob_count: None,
+            missing_blob_indices: vec![],
+            received,
+            waiting_time_ms: None,
+        }
+    }
+
     #[must_use]
     pub fn head(&self) -> WithStatus<ChainLink<P>> {
         let store = self.store_snapshot();
@@ -379,17 +492,48 @@ where
             }
         }
 
+        // `Storage::block_by_slot` only looks up finalized blocks.
         if let Some((block, root)) = self.storage().block_by_slot(slot)? {
-            // TODO(feature/in-memory-db): The call to `Store::is_slot_finalized`
-            //                             is either pointless or incorrect.
-            let finalized = store.is_slot_finalized(block.message().slot());
             let block_with_root = BlockWithRoot { block, root };
-            return Ok(Some(WithStatus::valid(block_with_root, finalized)));
+            return Ok(Some(WithStatus::valid_and_finalized(block_with_root)));
         }
 
         Ok(None)
     }
 
+    #[must_use]
+    pub fn proposed_block_root(&self, slot: Slot, proposer_index: ValidatorIndex) -> Option<H256> {
+        self.store_snapshot()
+            .signed_block_roots(slot, proposer_index)
+            .next()
+    }
+
+    #[must_use]
+    pub fn subnet_attester_seen(
+        &self,
+        target_epoch: Epoch,
+        validator_index: ValidatorIndex,
+    ) -> bool {
+        self.store_snapshot()
+            .subnet_attester_seen(target_epoch, validator_index)
+    }
+
+    #[must_use]
+    pub fn equivocating_block_root(
+        &self,
+        slot: Slot,
+        proposer_index: ValidatorIndex,
+        block_root: H256,
+    ) -> Option<H256> {
+        self.store_snapshot()
+            .signed_block_roots(slot, proposer_index)
+            .find(|other_root| *other_root != block_root)
+    }
+
+    pub fn validate_block_for_gossip(&self, block: &Arc<SignedBeaconBlock<P>>) -> Result<()> {
+        self.store_snapshot().validate_block_for_gossip(block)
+    }
+
     pub fn block_root_by_slot(&self, slot: Slot) -> Result<Option<H256>> {
         self.storage()
             .block_root_by_slot_with_store(self.store_snapshot().as_ref(), slot)
@@ -399,6 +543,14 @@ where
         self.snapshot().blocks_by_range(range)
     }
 
+    // The database may be empty right after checkpoint sync,
+    // in which case the earliest block is the one in the store.
+    pub fn earliest_available_slot(&self) -> Result<Slot> {
+        let in_memory = self.store_snapshot().anchor().slot();
+        let in_database = self.storage().earliest_block_slot()?;
+        Ok(in_database.map_or(in_memory, |slot| slot.min(in_memory)))
+    }
+
     pub fn blob_sidecars_by_ids(
         &self,
         blob_ids: impl IntoIterator<Item = BlobIdentifier> + Send,
@@ -534,6 +686,71 @@ where
             .dependent_root(self.store_snapshot().as_ref(), state, epoch)
     }
 
+    /// Returns proposers for every slot in the current epoch of `state`.
+    ///
+    /// Proposers are cached by dependent root and shared by all states that agree on it.
+    pub fn proposer_indices(&self, state: &BeaconState<P>) -> Result<Arc<[ValidatorIndex]>> {
+        let epoch = accessors::get_current_epoch(state);
+        let dependent_root = self.dependent_root(state, epoch)?;
+        self.proposer_cache()
+            .proposer_indices(state, dependent_root)
+    }
+
+    pub fn proposer_index(&self, state: &BeaconState<P>) -> Result<ValidatorIndex> {
+        let proposer_indices = self.proposer_indices(state)?;
+        let position = usize::try_from(misc::slots_since_epoch_start::<P>(state.slot()))?;
+        Ok(proposer_indices[position])
+    }
+
+    /// Looks up the index of `public_key` in `state` using the validator indices of the last
+    /// finalized state.
+    ///
+    /// This avoids scanning the validator registry of `state` unless the validator was added after
+    /// the last finalized state or `state` has a different registry.
+    #[must_use]
+    pub fn validator_index(
+        &self,
+        state: &(impl types::traits::BeaconState<P> + ?Sized),
+        public_key: PublicKeyBytes,
+    ) -> Option<ValidatorIndex> {
+        accessors::index_of_public_key_with_cache(
+            state,
+            self.store_snapshot().finalized_validator_indices(),
+            public_key,
+        )
+    }
+
+    #[must_use]
+    pub fn archival_progress(&self) -> ArchivalProgress {
+        let store = self.store_snapshot();
+        let storage = self.storage();
+        let archival_epoch_interval = storage.archival_epoch_interval.get();
+        let prune_storage = storage.prunes_storage();
+
+        let ArchivalStatus {
+            last_archived_state_slot,
+            pruning_up_to_epoch,
+            pruned_up_to_epoch,
+        } = storage.archival_status();
+
+        // States are archived in the first finalized epoch that is a multiple of the interval.
+        let next_archival_epoch = (!prune_storage).then(|| {
+            (store.anchor_epoch() / archival_epoch_interval + 1) * archival_epoch_interval
+        });
+
+        ArchivalProgress {
+            archival_epoch_interval,
+            prune_storage,
+            storage_degraded: storage.health().is_degraded(),
+            anchor_slot: store.anchor().slot(),
+            finalized_epoch: store.finalized_epoch(),
+            last_archived_state_slot,
+            next_archival_epoch,
+            pruning_up_to_epoch,
+            pruned_up_to_epoch,
+        }
+    }
+
     #[must_use]
     pub fn snapshot(&self) -> Snapshot<P, W> {
         Snapshot {
@@ -644,6 +861,54 @@ where
     }
 }
 
+#[derive(Serialize)]
+pub struct ArchivalProgress {
+    #[serde(with = "serde_utils::string_or_native")]
+    archival_epoch_interval: u64,
+    prune_storage: bool,
+    storage_degraded: bool,
+    #[serde(with = "serde_utils::string_or_native")]
+    anchor_slot: Slot,
+    #[serde(with = "serde_utils::string_or_native")]
+    finalized_epoch: Epoch,
+    // Only states archived since the application was started are reported.
+    last_archived_state_slot: Option<Slot>,
+    next_archival_epoch: Option<Epoch>,
+    pruning_up_to_epoch: Option<Epoch>,
+    pruned_up_to_epoch: Option<Epoch>,
+}
+
+#[derive(Serialize)]
+pub struct BlobAvailability {
+    block_root: H256,
+    status: BlobAvailabilityStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    slot: Option<Slot>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    blob_count: Option<usize>,
+    missing_blob_indices: Vec<BlobIndex>,
+    received: Vec<ReceivedBlobSidecar>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    waiting_time_ms: Option<u128>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "lowercase")]
+enum BlobAvailabilityStatus {
+    // The block passed validation and is waiting for blob sidecars.
+    Pending,
+    // The block is in the fork choice store.
+    Imported,
+    // The block has not been seen or has been finalized.
+    Unknown,
+}
+
+#[derive(Serialize)]
+struct ReceivedBlobSidecar {
+    index: BlobIndex,
+    source: BlobSidecarSource,
+}
+
 #[derive(Serialize)]
 pub struct ForkTip {
     root: H256,
@@ -904,6 +1169,22 @@ impl<P: Preset, W> Snapshot<'_, P, W> {
     }
 }
 
+fn justified_chain_link<P: Preset>(store: &Store<P>) -> Result<&ChainLink<P>> {
+    // `rustfmt` formats the method chain below in a surprising and counterproductive way.
+    // <https://github.com/rust-lang/rustfmt/issues/2961> describes a similar problem.
+    // <https://github.com/rust-lang/rustfmt/issues/3514> proposes adding an option.
+    // <https://github.com/rust-lang/rustfmt/pull/4886> implements the option.
+    #[rustfmt::skip]
+    let chain_link = store
+        .justified_chain_link()
+        .ok_or_else(|| Error::JustifiedBlockPruned {
+            justified_checkpoint: store.justified_checkpoint(),
+            finalized_checkpoint: store.finalized_checkpoint(),
+        })?;
+
+    Ok(chain_link)
+}
+
 #[derive(Debug, Error)]
 enum Error {
     #[error(
@@ -42,6 +42,7 @@ impl<P: Preset, W> StateCache<P, W> {
                 block_root,
                 slot,
                 self.should_print_slot_processing_warning(),
+                None,
             )?)),
             None => Ok(None),
         }
@@ -57,15 +58,22 @@ impl<P: Preset, W> StateCache<P, W> {
             block_root,
             slot,
             self.should_print_slot_processing_warning(),
+            None,
         )
     }
 
-    pub fn state_at_slot_quiet(&self, block_root: H256, slot: Slot) -> Result<Arc<BeaconState<P>>> {
+    // `wait_group` is released once the processed state has been added to the `Store`.
+    pub fn state_at_slot_quiet(
+        &self,
+        wait_group: W,
+        block_root: H256,
+        slot: Slot,
+    ) -> Result<Arc<BeaconState<P>>> {
         let state = self
             .try_find_state(block_root, slot)
             .ok_or(Error::StateNotFound { block_root })?;
 
-        self.process_slots(state, block_root, slot, false)
+        self.process_slots(state, block_root, slot, false, Some(wait_group))
     }
 
     fn try_find_state(&self, block_root: H256, slot: Slot) -> Option<Arc<BeaconState<P>>> {
@@ -83,6 +91,7 @@ impl<P: Preset, W> StateCache<P, W> {
         block_root: H256,
         slot: Slot,
         warn_on_slot_processing: bool,
+        wait_group: Option<W>,
     ) -> Result<Arc<BeaconState<P>>> {
         if state.slot() < slot {
             let store = self.store_snapshot();
@@ -114,6 +123,7 @@ impl<P: Preset, W> StateCache<P, W> {
 
             if is_forward_synced {
                 MutatorMessage::PreprocessedBeaconState {
+                    wait_group,
                     block_root,
                     state: state.clone_arc(),
                 }
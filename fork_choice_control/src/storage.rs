@@ -1,5 +1,5 @@
 use core::{fmt::Display, marker::PhantomData, num::NonZeroU64};
-use std::{borrow::Cow, sync::Arc};
+use std::{borrow::Cow, sync::Arc, time::Instant};
 
 use anyhow::{bail, ensure, Context as _, Error as AnyhowError, Result};
 use arithmetic::U64Ext as _;
@@ -11,6 +11,7 @@ use helper_functions::{accessors, misc};
 use itertools::Itertools as _;
 use log::{debug, info, warn};
 use nonzero_ext::nonzero;
+use parking_lot::Mutex;
 use reqwest::{Client, Url};
 use ssz::{Ssz, SszRead, SszReadDefault as _, SszWrite};
 use std_ext::ArcExt as _;
@@ -32,7 +33,11 @@ use types::{
     traits::{BeaconState as _, SignedBeaconBlock as _},
 };
 
-use crate::checkpoint_sync::{self, FinalizedCheckpoint};
+use crate::{
+    cancellation,
+    checkpoint_sync::{self, FinalizedCheckpoint},
+    storage_health::StorageHealth,
+};
 
 pub const DEFAULT_ARCHIVAL_EPOCH_INTERVAL: NonZeroU64 = nonzero!(32_u64);
 
@@ -57,9 +62,19 @@ pub struct Storage<P> {
     pub(crate) database: Database,
     pub(crate) archival_epoch_interval: NonZeroU64,
     prune_storage: bool,
+    health: StorageHealth,
+    archival_status: Mutex<ArchivalStatus>,
     phantom: PhantomData<P>,
 }
 
+/// Archival activity since the application was started.
+#[derive(Clone, Copy, Default, Debug)]
+pub(crate) struct ArchivalStatus {
+    pub last_archived_state_slot: Option<Slot>,
+    pub pruning_up_to_epoch: Option<Epoch>,
+    pub pruned_up_to_epoch: Option<Epoch>,
+}
+
 impl<P: Preset> Storage<P> {
     pub fn new(
         config: Arc<Config>,
@@ -72,6 +87,8 @@ impl<P: Preset> Storage<P> {
             database,
             archival_epoch_interval,
             prune_storage,
+            health: StorageHealth::default(),
+            archival_status: Mutex::default(),
             phantom: PhantomData,
         }
     }
@@ -88,6 +105,8 @@ impl<P: Preset> Storage<P> {
             database: Database::in_memory(),
             archival_epoch_interval: DEFAULT_ARCHIVAL_EPOCH_INTERVAL,
             prune_storage: false,
+            health: StorageHealth::default(),
+            archival_status: Mutex::default(),
             phantom: PhantomData,
         }
     }
@@ -97,6 +116,27 @@ impl<P: Preset> Storage<P> {
         &self.config
     }
 
+    #[must_use]
+    pub const fn health(&self) -> &StorageHealth {
+        &self.health
+    }
+
+    #[must_use]
+    pub(crate) const fn prunes_storage(&self) -> bool {
+        self.prune_storage
+    }
+
+    pub(crate) fn ensure_archival_allowed(&self) -> Result<()> {
+        ensure!(!self.prune_storage, Error::ArchivalDisabled);
+        ensure!(!self.health.is_degraded(), Error::StorageDegraded);
+        Ok(())
+    }
+
+    #[must_use]
+    pub(crate) fn archival_status(&self) -> ArchivalStatus {
+        *self.archival_status.lock()
+    }
+
     pub async fn load(
         &self,
         client: &Client,
@@ -230,6 +270,7 @@ impl<P: Preset> Storage<P> {
         let mut store_head_slot = 0;
         let mut checkpoint_state_appended = false;
         let mut archival_state_appended = false;
+        let mut archived_state_slot = None;
         let mut batch = vec![];
 
         let unfinalized = unfinalized.zip(core::iter::repeat(false));
@@ -308,21 +349,138 @@ impl<P: Preset> Storage<P> {
                         && state_epoch.is_multiple_of(self.archival_epoch_interval);
 
                     if append_state {
-                        info!("saving state in slot {state_slot}");
-
-                        batch.push(serialize(StateByBlockRoot(block_root), state)?);
-
-                        archival_state_appended = true;
+                        if self.health.is_degraded() {
+                            warn!(
+                                "not saving state in slot {state_slot} because storage is degraded"
+                            );
+                        } else {
+                            info!("saving state in slot {state_slot}");
+
+                            batch.push(serialize(StateByBlockRoot(block_root), state)?);
+                            archived_state_slot = Some(state_slot);
+                            archival_state_appended = true;
+                        }
                     }
                 }
             }
         }
 
+        let write_started_at = Instant::now();
+
         self.database.put_batch(batch)?;
 
+        self.health
+            .observe_write_latency(write_started_at.elapsed());
+
+        if let Some(slot) = archived_state_slot {
+            self.archival_status.lock().last_archived_state_slot = Some(slot);
+        }
+
         Ok(slots)
     }
 
+    /// Saves `state` as an archival state regardless of `Storage.archival_epoch_interval`.
+    pub(crate) fn archive_state(&self, block_root: H256, state: &BeaconState<P>) -> Result<()> {
+        self.ensure_archival_allowed()?;
+
+        self.database
+            .put_batch([serialize(StateByBlockRoot(block_root), state)?])?;
+
+        self.archival_status.lock().last_archived_state_slot = Some(state.slot());
+
+        Ok(())
+    }
+
+    /// Marks pruning up to `epoch` as started.
+    ///
+    /// Fails if another pruning run has not finished yet.
+    /// [`Storage::prune_to_epoch`] must be called after this succeeds.
+    pub(crate) fn start_pruning(&self, epoch: Epoch) -> Result<()> {
+        let mut archival_status = self.archival_status.lock();
+
+        if let Some(in_progress_epoch) = archival_status.pruning_up_to_epoch {
+            bail!(Error::PruningInProgress {
+                epoch: in_progress_epoch,
+            });
+        }
+
+        archival_status.pruning_up_to_epoch = Some(epoch);
+
+        Ok(())
+    }
+
+    /// Marks pruning started with [`Storage::start_pruning`] as no longer in progress.
+    ///
+    /// Must be called if [`Storage::prune_to_epoch`] will not be called after all.
+    pub(crate) fn abort_pruning(&self) {
+        self.archival_status.lock().pruning_up_to_epoch = None;
+    }
+
+    /// Deletes archival states from before `epoch` and blob sidecars up to `blob_sidecars_slot`.
+    ///
+    /// Blocks are kept so that they can still be served to peers.
+    /// Returns the number of states deleted.
+    pub(crate) fn prune_to_epoch(&self, epoch: Epoch, blob_sidecars_slot: Slot) -> Result<usize> {
+        let up_to_slot = misc::compute_start_slot_at_epoch::<P>(epoch);
+
+        let result = self
+            .prune_archived_states(up_to_slot)
+            .and_then(|pruned_states| {
+                self.prune_old_blob_sidecars(blob_sidecars_slot)?;
+                Ok(pruned_states)
+            });
+
+        let mut archival_status = self.archival_status.lock();
+
+        archival_status.pruning_up_to_epoch = None;
+
+        if result.is_ok() {
+            archival_status.pruned_up_to_epoch = Some(epoch);
+        }
+
+        result
+    }
+
+    // Archival states are only stored for blocks in the first slot of an epoch.
+    // Only those need to be looked up.
+    fn prune_archived_states(&self, up_to_slot: Slot) -> Result<usize> {
+        let mut keys_to_remove = vec![];
+
+        let results = self
+            .database
+            .iterator_ascending(BlockRootBySlot(GENESIS_SLOT).to_string()..)?;
+
+        for result in results {
+            let (key_bytes, value_bytes) = result?;
+
+            if !BlockRootBySlot::has_prefix(&key_bytes) {
+                break;
+            }
+
+            let BlockRootBySlot(slot) = key_bytes.try_into()?;
+
+            if slot >= up_to_slot {
+                break;
+            }
+
+            if !misc::is_epoch_start::<P>(slot) {
+                continue;
+            }
+
+            let state_key = StateByBlockRoot(H256::from_ssz_default(value_bytes)?);
+
+            if self.contains_key(&state_key)? {
+                keys_to_remove.push(state_key.to_string());
+            }
+        }
+
+        for key in &keys_to_remove {
+            self.database.delete(key)?;
+        }
+
+        Ok(keys_to_remove.len())
+    }
+
     pub(crate) fn append_blob_sidecars(
         &self,
         blob_sidecars: impl IntoIterator<Item = BlobSidecarWithId<P>>,
@@ -404,6 +562,28 @@ impl<P: Preset> Storage<P> {
         Ok(None)
     }
 
+    // Finalized blocks are stored without gaps from the anchor onwards,
+    // so the lowest `BlockRootBySlot` key is the earliest block that can be served to peers.
+    pub(crate) fn earliest_block_slot(&self) -> Result<Option<Slot>> {
+        let results = self
+            .database
+            .iterator_ascending(BlockRootBySlot(GENESIS_SLOT).to_string()..)?;
+
+        for result in results {
+            let (key_bytes, _) = result?;
+
+            if !BlockRootBySlot::has_prefix(&key_bytes) {
+                break;
+            }
+
+            let BlockRootBySlot(slot) = key_bytes.try_into()?;
+
+            return Ok(Some(slot));
+        }
+
+        Ok(None)
+    }
+
     pub(crate) fn genesis_block_root(&self, store: &Store<P>) -> Result<H256> {
         self.block_root_by_slot_with_store(store, GENESIS_SLOT)?
             .ok_or(Error::GenesisBlockRootNotFound)
@@ -488,6 +668,7 @@ impl<P: Preset> Storage<P> {
         // `blocks` here are needed to transition state closer to `slot`.
         for result in blocks.rev() {
             let block = result?;
+            cancellation::ensure_not_cancelled()?;
             combined::trusted_state_transition(&self.config, state.make_mut(), &block)?;
         }
 
@@ -534,6 +715,7 @@ impl<P: Preset> Storage<P> {
         };
 
         for block in blocks.into_iter().rev() {
+            cancellation::ensure_not_cancelled()?;
             combined::trusted_state_transition(&self.config, state.make_mut(), &block)?;
         }
 
@@ -893,8 +1075,139 @@ pub enum Error {
     PersistedSlotCannotContainAnchor { slot: Slot },
     #[error("storage key has incorrect prefix: {bytes:?}")]
     IncorrectPrefix { bytes: Vec<u8> },
+    #[error("states are not archived while storage is degraded")]
+    StorageDegraded,
+    #[error("states are not archived when storage is pruned")]
+    ArchivalDisabled,
+    #[error("pruning up to epoch {epoch} is still in progress")]
+    PruningInProgress { epoch: Epoch },
 }
 
 pub fn serialize(key: impl Display, value: impl SszWrite) -> Result<(String, Vec<u8>)> {
     Ok((key.to_string(), value.to_ssz()?))
 }
+
+#[cfg(test)]
+mod tests {
+    use types::preset::Minimal;
+
+    use super::*;
+
+    #[test]
+    fn earliest_block_slot_is_lowest_slot_with_block_root() -> Result<()> {
+        let storage = Storage::<Minimal>::new(
+            Arc::new(Config::minimal()),
+            Database::in_memory(),
+            DEFAULT_ARCHIVAL_EPOCH_INTERVAL,
+            false,
+        );
+
+        assert_eq!(storage.earliest_block_slot()?, None);
+
+        // Keys with other prefixes sort both before and after `BlockRootBySlot` keys.
+        storage.database.put_batch([
+            serialize(FinalizedBlockByRoot(H256::zero()), H256::zero())?,
+            serialize(SlotBlobId(1, H256::zero(), 0), H256::zero())?,
+            serialize(StateByBlockRoot(H256::zero()), H256::zero())?,
+        ])?;
+
+        assert_eq!(storage.earliest_block_slot()?, None);
+
+        storage.database.put_batch([
+            serialize(BlockRootBySlot(100), H256::repeat_byte(3))?,
+            serialize(BlockRootBySlot(64), H256::repeat_byte(1))?,
+            serialize(BlockRootBySlot(65), H256::repeat_byte(2))?,
+        ])?;
+
+        // Slots are zero-padded, so the lexicographic order of keys matches the numeric one.
+        assert_eq!(storage.earliest_block_slot()?, Some(64));
+
+        storage
+            .database
+            .put(BlockRootBySlot(9).to_string(), H256::zero())?;
+
+        assert_eq!(storage.earliest_block_slot()?, Some(9));
+
+        Ok(())
+    }
+
+    #[test]
+    fn prune_archived_states_deletes_states_of_epoch_start_blocks_before_slot() -> Result<()> {
+        let storage = Storage::<Minimal>::new(
+            Arc::new(Config::minimal()),
+            Database::in_memory(),
+            DEFAULT_ARCHIVAL_EPOCH_INTERVAL,
+            false,
+        );
+
+        let root = H256::repeat_byte;
+
+        // Slots per epoch in `Minimal` is 8. The block in slot 16 has no archival state.
+        storage.database.put_batch([
+            serialize(BlockRootBySlot(0), root(0))?,
+            serialize(BlockRootBySlot(8), root(8))?,
+            serialize(BlockRootBySlot(9), root(9))?,
+            serialize(BlockRootBySlot(16), root(16))?,
+            serialize(BlockRootBySlot(24), root(24))?,
+        ])?;
+
+        storage.database.put_batch([
+            serialize(StateByBlockRoot(root(0)), H256::zero())?,
+            serialize(StateByBlockRoot(root(8)), H256::zero())?,
+            serialize(StateByBlockRoot(root(9)), H256::zero())?,
+            serialize(StateByBlockRoot(root(24)), H256::zero())?,
+        ])?;
+
+        assert_eq!(storage.prune_archived_states(24)?, 2);
+
+        assert!(!storage.contains_key(StateByBlockRoot(root(0)))?);
+        assert!(!storage.contains_key(StateByBlockRoot(root(8)))?);
+        assert!(storage.contains_key(StateByBlockRoot(root(9)))?);
+        assert!(storage.contains_key(StateByBlockRoot(root(24)))?);
+
+        // Blocks are kept.
+        assert!(storage.contains_key(BlockRootBySlot(0))?);
+        assert!(storage.contains_key(BlockRootBySlot(8))?);
+
+        assert_eq!(storage.prune_archived_states(24)?, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn start_pruning_fails_until_previous_run_finishes() -> Result<()> {
+        let storage = Storage::<Minimal>::new(
+            Arc::new(Config::minimal()),
+            Database::in_memory(),
+            DEFAULT_ARCHIVAL_EPOCH_INTERVAL,
+            false,
+        );
+
+        storage.start_pruning(2)?;
+
+        let error = storage
+            .start_pruning(3)
+            .expect_err("pruning up to epoch 2 should still be in progress");
+
+        assert!(matches!(
+            error.downcast_ref(),
+            Some(Error::PruningInProgress { epoch: 2 }),
+        ));
+
+        storage.abort_pruning();
+        storage.start_pruning(3)?;
+
+        assert_eq!(storage.archival_status().pruning_up_to_epoch, Some(3));
+
+        storage.prune_to_epoch(3, 0)?;
+
+        let archival_status = storage.archival_status();
+
+        assert_eq!(archival_status.pruning_up_to_epoch, None);
+        assert_eq!(archival_status.pruned_up_to_epoch, Some(3));
+
+        storage.start_pruning(4)?;
+
+        Ok(())
+    }
+}
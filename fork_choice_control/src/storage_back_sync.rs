@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use anyhow::Result;
+use anyhow::{ensure, Result};
 use arithmetic::U64Ext as _;
 use features::Feature;
 use genesis::GenesisProvider;
@@ -30,6 +30,8 @@ impl<P: Preset> Storage<P> {
         end_slot: Slot,
         genesis_provider: GenesisProvider<P>,
     ) -> Result<()> {
+        ensure!(!self.health().is_degraded(), Error::StorageDegraded);
+
         let genesis_root = genesis_provider.block_root();
 
         let mut state = if start_slot == GENESIS_SLOT {
@@ -0,0 +1,40 @@
+use core::{
+    sync::atomic::{AtomicBool, AtomicU64, Ordering},
+    time::Duration,
+};
+
+/// State shared between [`Storage`] and the watchdog that monitors the disk it is stored on.
+///
+/// [`Storage`]: crate::Storage
+#[derive(Default)]
+pub struct StorageHealth {
+    degraded: AtomicBool,
+    max_write_latency_micros: AtomicU64,
+}
+
+impl StorageHealth {
+    /// Whether storage is in degraded mode.
+    ///
+    /// Historical states are not archived in degraded mode. They make up most of the growth of the
+    /// database, so skipping them leaves room for the data needed to keep following the chain.
+    #[must_use]
+    pub fn is_degraded(&self) -> bool {
+        self.degraded.load(Ordering::Relaxed)
+    }
+
+    pub fn set_degraded(&self, degraded: bool) {
+        self.degraded.store(degraded, Ordering::Relaxed);
+    }
+
+    /// Returns the longest write observed since the last call and resets it.
+    #[must_use]
+    pub fn take_max_write_latency(&self) -> Duration {
+        Duration::from_micros(self.max_write_latency_micros.swap(0, Ordering::Relaxed))
+    }
+
+    pub(crate) fn observe_write_latency(&self, latency: Duration) {
+        let micros = u64::try_from(latency.as_micros()).unwrap_or(u64::MAX);
+        self.max_write_latency_micros
+            .fetch_max(micros, Ordering::Relaxed);
+    }
+}
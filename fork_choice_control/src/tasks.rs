@@ -4,28 +4,31 @@ use std::{
     time::Instant,
 };
 
-use anyhow::Result;
+use anyhow::{ensure, Result};
+use arc_swap::ArcSwap;
 use eth2_libp2p::GossipId;
 use execution_engine::{ExecutionEngine, NullExecutionEngine};
 use features::Feature;
 use fork_choice_store::{
     AggregateAndProofOrigin, AttestationOrigin, AttesterSlashingOrigin, BlobSidecarOrigin,
-    BlockOrigin, Store,
+    BlockAction, BlockOrigin, Store,
 };
 use helper_functions::{
     accessors, misc,
     verifier::{MultiVerifier, NullVerifier, VerifierOption},
 };
 use log::warn;
+use parking_lot::Mutex;
 use prometheus_metrics::Metrics;
 use std_ext::ArcExt as _;
+use thiserror::Error;
 use types::{
     combined::SignedBeaconBlock,
     deneb::containers::BlobSidecar,
     nonstandard::RelativeEpoch,
     phase0::{
         containers::{Attestation, AttesterSlashing, Checkpoint, SignedAggregateAndProof},
-        primitives::{Slot, H256},
+        primitives::{Slot, ValidatorIndex, H256},
     },
     preset::Preset,
     traits::{BeaconState, SignedBeaconBlock as _},
@@ -33,7 +36,8 @@ use types::{
 
 use crate::{
     messages::MutatorMessage,
-    misc::{VerifyAggregateAndProofResult, VerifyAttestationResult},
+    misc::{PendingBlobSidecar, VerifyAggregateAndProofResult, VerifyAttestationResult},
+    proposer_cache::ProposerCache,
     state_cache::StateCache,
     storage::Storage,
 };
@@ -56,6 +60,7 @@ pub trait Run {
 
 pub struct BlockTask<P: Preset, E, W> {
     pub store_snapshot: Arc<Store<P>>,
+    pub proposer_cache: Arc<ProposerCache>,
     pub execution_engine: E,
     pub mutator_tx: Sender<MutatorMessage<P, W>>,
     pub wait_group: W,
@@ -69,6 +74,7 @@ impl<P: Preset, E: ExecutionEngine<P> + Send, W> Run for BlockTask<P, E, W> {
     fn run(self) {
         let Self {
             store_snapshot,
+            proposer_cache,
             execution_engine,
             mutator_tx,
             wait_group,
@@ -84,8 +90,43 @@ impl<P: Preset, E: ExecutionEngine<P> + Send, W> Run for BlockTask<P, E, W> {
 
         let block_arc = block.clone_arc();
 
+        // > The block is the first block with valid signature received for the proposer for the
+        // > slot
+        //
+        // Only blocks already accepted into the store are considered, so blocks that fail
+        // validation cannot be used to suppress valid ones. Blocks from other origins are still
+        // processed so that the application can follow whichever fork becomes canonical.
+        if matches!(origin, BlockOrigin::Gossip(_)) {
+            let is_repeated_proposal = store_snapshot
+                .proposed_block_roots(block.message().slot(), block.message().proposer_index())
+                .next()
+                .is_some();
+
+            if is_repeated_proposal {
+                MutatorMessage::Block {
+                    wait_group,
+                    result: Ok(BlockAction::Ignore),
+                    origin,
+                    submission_time,
+                    rejected_block_root: None,
+                }
+                .send(&mutator_tx);
+
+                return;
+            }
+        }
+
+        // Blocks from unexpected proposers can be rejected without running the state transition
+        // if proposers for the epoch have already been computed for the same chain.
+        let proposer_check = match origin {
+            BlockOrigin::Gossip(_) => {
+                validate_cached_proposer(&store_snapshot, &proposer_cache, &block)
+            }
+            _ => Ok(()),
+        };
+
         // TODO(Grandine Team): Consider moving the `match` into `Store`.
-        let result = match origin {
+        let result = proposer_check.and_then(|()| match origin {
             BlockOrigin::Gossip(_) | BlockOrigin::Requested(_) | BlockOrigin::Api(_) => {
                 store_snapshot.validate_block(
                     block,
@@ -123,7 +164,7 @@ impl<P: Preset, E: ExecutionEngine<P> + Send, W> Run for BlockTask<P, E, W> {
                 NullExecutionEngine,
                 NullVerifier,
             ),
-        };
+        });
 
         let rejected_block_root = result
             .is_err()
@@ -316,6 +357,7 @@ impl<P: Preset, W> Run for BlobSidecarTask<P, W> {
             blob_sidecar,
             block_seen,
             &origin,
+            false,
             MultiVerifier::default(),
         );
 
@@ -330,6 +372,151 @@ impl<P: Preset, W> Run for BlobSidecarTask<P, W> {
     }
 }
 
+// Verifying KZG proofs of many blobs at once is considerably faster than verifying them one by one.
+// This is done for sidecars that arrive together: own and API ones along with their blocks and
+// requested ones in `BlobsByRange` and `BlobsByRoot` responses.
+// Gossip sidecars arrive individually on separate subnets.
+// They are collected into batches by `GossipBlobSidecarsTask`.
+pub struct BlobSidecarsTask<P: Preset, W> {
+    pub store_snapshot: Arc<Store<P>>,
+    pub mutator_tx: Sender<MutatorMessage<P, W>>,
+    pub blob_sidecars: Vec<(PendingBlobSidecar<P>, W)>,
+    pub metrics: Option<Arc<Metrics>>,
+}
+
+impl<P: Preset, W> Run for BlobSidecarsTask<P, W> {
+    fn run(self) {
+        let Self {
+            store_snapshot,
+            mutator_tx,
+            blob_sidecars,
+            metrics,
+        } = self;
+
+        validate_blob_sidecars(
+            &store_snapshot,
+            &mutator_tx,
+            blob_sidecars,
+            metrics.as_deref(),
+        );
+    }
+}
+
+// A task is only spawned for a gossip sidecar added to an empty queue. Sidecars that arrive while
+// the task is waiting to run are verified along with it, so batching them adds no latency.
+// The store snapshot is loaded when the task runs because the queue may contain sidecars that
+// arrived after the task was spawned.
+pub struct GossipBlobSidecarsTask<P: Preset, W> {
+    pub store_snapshot: Arc<ArcSwap<Store<P>>>,
+    pub mutator_tx: Sender<MutatorMessage<P, W>>,
+    pub queue: Arc<Mutex<Vec<(PendingBlobSidecar<P>, W)>>>,
+    pub metrics: Option<Arc<Metrics>>,
+}
+
+impl<P: Preset, W> Run for GossipBlobSidecarsTask<P, W> {
+    fn run(self) {
+        let Self {
+            store_snapshot,
+            mutator_tx,
+            queue,
+            metrics,
+        } = self;
+
+        let blob_sidecars = core::mem::take(&mut *queue.lock());
+        let store_snapshot = store_snapshot.load_full();
+
+        validate_blob_sidecars(
+            &store_snapshot,
+            &mutator_tx,
+            blob_sidecars,
+            metrics.as_deref(),
+        );
+    }
+}
+
+fn validate_blob_sidecars<P: Preset, W>(
+    store_snapshot: &Store<P>,
+    mutator_tx: &Sender<MutatorMessage<P, W>>,
+    blob_sidecars: Vec<(PendingBlobSidecar<P>, W)>,
+    metrics: Option<&Metrics>,
+) {
+    if blob_sidecars.is_empty() {
+        return;
+    }
+
+    let _timer = metrics.map(|metrics| metrics.fc_blob_sidecars_task_times.start_timer());
+
+    let kzg_proofs_verified = verify_kzg_proofs(
+        blob_sidecars
+            .iter()
+            .map(|(pending, _)| pending.blob_sidecar.as_ref()),
+    );
+
+    for ((pending, wait_group), kzg_proof_verified) in
+        blob_sidecars.into_iter().zip(kzg_proofs_verified)
+    {
+        let PendingBlobSidecar {
+            blob_sidecar,
+            block_seen,
+            origin,
+            submission_time,
+        } = pending;
+
+        // Invalid proofs are verified again, but that only happens when the batch fails.
+        let result = store_snapshot.validate_blob_sidecar(
+            blob_sidecar,
+            block_seen,
+            &origin,
+            kzg_proof_verified,
+            MultiVerifier::default(),
+        );
+
+        MutatorMessage::BlobSidecar {
+            wait_group,
+            result,
+            block_seen,
+            origin,
+            submission_time,
+        }
+        .send(mutator_tx);
+    }
+}
+
+// Returns whether the KZG proof of each sidecar is valid.
+// If the batch fails, proofs are verified one by one so that valid sidecars are still accepted.
+fn verify_kzg_proofs<'sidecar, P: Preset>(
+    blob_sidecars: impl IntoIterator<Item = &'sidecar BlobSidecar<P>>,
+) -> Vec<bool> {
+    let blob_sidecars = blob_sidecars.into_iter().collect::<Vec<_>>();
+
+    let batch_verified = kzg_utils::eip_4844::verify_blob_kzg_proof_batch::<P>(
+        blob_sidecars.iter().map(|blob_sidecar| &blob_sidecar.blob),
+        blob_sidecars
+            .iter()
+            .map(|blob_sidecar| blob_sidecar.kzg_commitment),
+        blob_sidecars
+            .iter()
+            .map(|blob_sidecar| blob_sidecar.kzg_proof),
+    )
+    .unwrap_or(false);
+
+    if batch_verified {
+        return vec![true; blob_sidecars.len()];
+    }
+
+    blob_sidecars
+        .into_iter()
+        .map(|blob_sidecar| {
+            kzg_utils::eip_4844::verify_blob_kzg_proof::<P>(
+                &blob_sidecar.blob,
+                blob_sidecar.kzg_commitment,
+                blob_sidecar.kzg_proof,
+            )
+            .unwrap_or(false)
+        })
+        .collect()
+}
+
 pub struct PersistBlobSidecarsTask<P: Preset, W> {
     pub store_snapshot: Arc<Store<P>>,
     pub storage: Arc<Storage<P>>,
@@ -413,6 +600,7 @@ impl<P: Preset, W> Run for CheckpointStateTask<P, W> {
 
 pub struct PreprocessStateTask<P: Preset, W> {
     pub state_cache: Arc<StateCache<P, W>>,
+    pub wait_group: W,
     pub head_block_root: H256,
     pub next_slot: Slot,
     pub metrics: Option<Arc<Metrics>>,
@@ -422,6 +610,7 @@ impl<P: Preset, W> Run for PreprocessStateTask<P, W> {
     fn run(self) {
         let Self {
             state_cache,
+            wait_group,
             head_block_root,
             next_slot,
             metrics,
@@ -431,7 +620,7 @@ impl<P: Preset, W> Run for PreprocessStateTask<P, W> {
             .as_ref()
             .map(|metrics| metrics.fc_preprocess_state_task_times.start_timer());
 
-        match state_cache.state_at_slot_quiet(head_block_root, next_slot) {
+        match state_cache.state_at_slot_quiet(wait_group, head_block_root, next_slot) {
             Ok(state) => {
                 if let Err(error) = initialize_preprocessed_state_cache(&state) {
                     warn!("failed to initialize preprocessed state's cache values: {error:?}");
@@ -453,3 +642,97 @@ fn initialize_preprocessed_state_cache<P: Preset>(state: &impl BeaconState<P>) -
 
     Ok(())
 }
+
+fn validate_cached_proposer<P: Preset>(
+    store: &Store<P>,
+    proposer_cache: &ProposerCache,
+    block: &SignedBeaconBlock<P>,
+) -> Result<()> {
+    let slot = block.message().slot();
+
+    let Some(dependent_root) = store.proposer_dependent_root(block.message().parent_root(), slot)
+    else {
+        return Ok(());
+    };
+
+    let Some(expected) = proposer_cache.proposer_index::<P>(slot, dependent_root) else {
+        return Ok(());
+    };
+
+    let in_block = block.message().proposer_index();
+
+    ensure!(
+        in_block == expected,
+        Error::ProposerIndexMismatch { expected, in_block },
+    );
+
+    Ok(())
+}
+
+#[derive(Debug, Error)]
+enum Error {
+    #[error("proposer index is incorrect (in_block: {in_block}, expected: {expected})")]
+    ProposerIndexMismatch {
+        expected: ValidatorIndex,
+        in_block: ValidatorIndex,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use ssz::SszReadDefault as _;
+    use types::{deneb::primitives::Blob, preset::Minimal};
+
+    use super::*;
+
+    #[test]
+    fn verify_kzg_proofs_verifies_batch() -> Result<()> {
+        let blob_sidecars = [blob_sidecar(1)?, blob_sidecar(2)?, blob_sidecar(3)?];
+
+        assert_eq!(verify_kzg_proofs(&blob_sidecars), [true, true, true]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn verify_kzg_proofs_falls_back_to_individual_proofs_if_batch_fails() -> Result<()> {
+        let mut blob_sidecars = [blob_sidecar(1)?, blob_sidecar(2)?, blob_sidecar(3)?];
+
+        blob_sidecars[1].kzg_proof = blob_sidecars[2].kzg_proof;
+
+        assert_eq!(verify_kzg_proofs(&blob_sidecars), [true, false, true]);
+
+        Ok(())
+    }
+
+    fn blob_sidecar(seed: u8) -> Result<BlobSidecar<Minimal>> {
+        let length = Blob::<Minimal>::default().as_bytes().len();
+
+        // Field elements are big-endian. Zeroing the most significant byte of each one keeps them
+        // below the modulus of the scalar field.
+        let bytes = (0..=u8::MAX)
+            .cycle()
+            .take(length)
+            .enumerate()
+            .map(|(index, byte)| {
+                if index % 32 == 0 {
+                    0
+                } else {
+                    byte.wrapping_add(seed)
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let blob = Blob::<Minimal>::from_ssz_default(bytes)?;
+        let kzg_commitment = kzg_utils::eip_4844::blob_to_kzg_commitment::<Minimal>(&blob)?;
+        let kzg_proof =
+            kzg_utils::eip_4844::compute_blob_kzg_proof::<Minimal>(&blob, kzg_commitment)?;
+
+        Ok(BlobSidecar {
+            blob,
+            kzg_commitment,
+            kzg_proof,
+            ..BlobSidecar::default()
+        })
+    }
+}
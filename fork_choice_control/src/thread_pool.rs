@@ -25,8 +25,8 @@ use types::preset::Preset;
 use crate::{
     tasks::{
         AggregateAndProofTask, AttestationTask, AttesterSlashingTask, BlobSidecarTask,
-        BlockAttestationsTask, BlockTask, CheckpointStateTask, PersistBlobSidecarsTask,
-        PreprocessStateTask, Run,
+        BlobSidecarsTask, BlockAttestationsTask, BlockTask, CheckpointStateTask,
+        GossipBlobSidecarsTask, PersistBlobSidecarsTask, PreprocessStateTask, Run,
     },
     wait::Wait,
 };
@@ -102,6 +102,8 @@ pub struct Critical<P: Preset, E, W> {
 enum HighPriorityTask<P: Preset, E, W> {
     Block(BlockTask<P, E, W>),
     BlobSidecar(BlobSidecarTask<P, W>),
+    BlobSidecars(BlobSidecarsTask<P, W>),
+    GossipBlobSidecars(GossipBlobSidecarsTask<P, W>),
     // `CheckpointStateTask` is a high priority task to prevent attestation tasks from delaying
     // processing of blocks that are waiting for checkpoint states. However, this may result in a
     // `CheckpointStateTask` being prioritized when it's only needed to verify attestations.
@@ -114,6 +116,8 @@ impl<P: Preset, E: ExecutionEngine<P> + Send, W> Run for HighPriorityTask<P, E,
         match self {
             Self::Block(task) => task.run(),
             Self::BlobSidecar(task) => task.run(),
+            Self::BlobSidecars(task) => task.run(),
+            Self::GossipBlobSidecars(task) => task.run(),
             Self::CheckpointState(task) => task.run(),
             Self::PreprocessState(task) => task.run(),
         }
@@ -157,6 +161,18 @@ impl<P: Preset, E, W> Spawn<P, E, W> for BlobSidecarTask<P, W> {
     }
 }
 
+impl<P: Preset, E, W> Spawn<P, E, W> for BlobSidecarsTask<P, W> {
+    fn spawn(self, critical: &mut Critical<P, E, W>) {
+        critical.high_priority_tasks.push_back(self.into())
+    }
+}
+
+impl<P: Preset, E, W> Spawn<P, E, W> for GossipBlobSidecarsTask<P, W> {
+    fn spawn(self, critical: &mut Critical<P, E, W>) {
+        critical.high_priority_tasks.push_back(self.into())
+    }
+}
+
 impl<P: Preset, E, W> Spawn<P, E, W> for CheckpointStateTask<P, W> {
     fn spawn(self, critical: &mut Critical<P, E, W>) {
         critical.high_priority_tasks.push_back(self.into())
@@ -2,17 +2,22 @@ use std::{collections::HashMap, sync::Arc};
 
 use std_ext::ArcExt as _;
 use types::{
-    deneb::containers::{BlobIdentifier, BlobSidecar},
+    deneb::{
+        containers::{BlobIdentifier, BlobSidecar},
+        primitives::BlobIndex,
+    },
     nonstandard::BlobSidecarWithId,
-    phase0::primitives::Slot,
+    phase0::primitives::{Slot, H256},
     preset::Preset,
 };
 
+use crate::misc::BlobSidecarSource;
+
 const BLOB_RETAIN_DURATION_IN_SLOTS: Slot = 2;
 
 #[derive(Clone, Default)]
 pub struct BlobCache<P: Preset> {
-    blobs: HashMap<BlobIdentifier, (Arc<BlobSidecar<P>>, Slot, bool)>,
+    blobs: HashMap<BlobIdentifier, (Arc<BlobSidecar<P>>, Slot, bool, BlobSidecarSource)>,
 }
 
 impl<P: Preset> BlobCache<P> {
@@ -21,15 +26,17 @@ impl<P: Preset> BlobCache<P> {
     }
 
     pub fn has_unpersisted_blob_sidecars(&self) -> bool {
-        self.blobs.iter().any(|(_, (_, _, persisted))| !persisted)
+        self.blobs
+            .iter()
+            .any(|(_, (_, _, persisted, _))| !persisted)
     }
 
-    pub fn insert(&mut self, blob_sidecar: Arc<BlobSidecar<P>>) {
+    pub fn insert(&mut self, blob_sidecar: Arc<BlobSidecar<P>>, source: BlobSidecarSource) {
         let slot = blob_sidecar.signed_block_header.message.slot;
         let blob_identifier = blob_sidecar.as_ref().into();
 
         self.blobs
-            .insert(blob_identifier, (blob_sidecar, slot, false));
+            .insert(blob_identifier, (blob_sidecar, slot, false, source));
     }
 
     pub fn mark_persisted_blobs(&mut self, persisted_blob_ids: Vec<BlobIdentifier>) {
@@ -40,7 +47,17 @@ impl<P: Preset> BlobCache<P> {
 
     pub fn on_slot(&mut self, slot: Slot) {
         self.blobs
-            .retain(|_, (_, blob_slot, _)| *blob_slot + BLOB_RETAIN_DURATION_IN_SLOTS >= slot);
+            .retain(|_, (_, blob_slot, _, _)| *blob_slot + BLOB_RETAIN_DURATION_IN_SLOTS >= slot);
+    }
+
+    pub fn sources(
+        &self,
+        block_root: H256,
+    ) -> impl Iterator<Item = (BlobIndex, BlobSidecarSource)> + '_ {
+        self.blobs
+            .iter()
+            .filter(move |(blob_id, _)| blob_id.block_root == block_root)
+            .map(|(blob_id, (_, _, _, source))| (blob_id.index, *source))
     }
 
     pub fn size(&self) -> usize {
@@ -50,10 +67,78 @@ impl<P: Preset> BlobCache<P> {
     pub fn unpersisted_blob_sidecars(&self) -> impl Iterator<Item = BlobSidecarWithId<P>> + '_ {
         self.blobs
             .iter()
-            .filter(|(_, (_, _, persisted))| !persisted)
-            .map(|(blob_id, (blob_sidecar, _, _))| BlobSidecarWithId {
+            .filter(|(_, (_, _, persisted, _))| !persisted)
+            .map(|(blob_id, (blob_sidecar, _, _, _))| BlobSidecarWithId {
                 blob_sidecar: blob_sidecar.clone_arc(),
                 blob_id: *blob_id,
             })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools as _;
+    use ssz::SszHash as _;
+    use types::{
+        phase0::containers::{BeaconBlockHeader, SignedBeaconBlockHeader},
+        preset::Minimal,
+    };
+
+    use super::*;
+
+    #[test]
+    fn sources_reports_indices_and_sources_of_sidecars_for_block() {
+        let mut cache = BlobCache::default();
+
+        let sidecar_0 = blob_sidecar(1, 0);
+        let sidecar_1 = blob_sidecar(1, 1);
+        let other_sidecar = blob_sidecar(2, 0);
+        let block_root = sidecar_0.signed_block_header.message.hash_tree_root();
+        let other_block_root = other_sidecar.signed_block_header.message.hash_tree_root();
+
+        assert_eq!(cache.sources(block_root).count(), 0);
+
+        cache.insert(sidecar_0, BlobSidecarSource::Gossip);
+        cache.insert(sidecar_1, BlobSidecarSource::Requested);
+        cache.insert(other_sidecar, BlobSidecarSource::Own);
+
+        assert_eq!(
+            cache
+                .sources(block_root)
+                .sorted_by_key(|(index, _)| *index)
+                .collect_vec(),
+            [
+                (0, BlobSidecarSource::Gossip),
+                (1, BlobSidecarSource::Requested),
+            ],
+        );
+
+        assert_eq!(
+            cache.sources(other_block_root).collect_vec(),
+            [(0, BlobSidecarSource::Own)],
+        );
+
+        cache.on_slot(1 + BLOB_RETAIN_DURATION_IN_SLOTS + 1);
+
+        assert_eq!(cache.sources(block_root).count(), 0);
+
+        assert_eq!(
+            cache.sources(other_block_root).collect_vec(),
+            [(0, BlobSidecarSource::Own)],
+        );
+    }
+
+    fn blob_sidecar(slot: Slot, index: BlobIndex) -> Arc<BlobSidecar<Minimal>> {
+        Arc::new(BlobSidecar {
+            index,
+            signed_block_header: SignedBeaconBlockHeader {
+                message: BeaconBlockHeader {
+                    slot,
+                    ..BeaconBlockHeader::default()
+                },
+                ..SignedBeaconBlockHeader::default()
+            },
+            ..BlobSidecar::default()
+        })
+    }
+}
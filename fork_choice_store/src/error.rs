@@ -74,6 +74,24 @@ pub enum Error<P: Preset> {
         blob_sidecar: Arc<BlobSidecar<P>>,
         computed: ValidatorIndex,
     },
+    #[error("block is not a descendant of the finalized block: {block:?}")]
+    BlockNotADescendantOfFinalized { block: Arc<SignedBeaconBlock<P>> },
+    #[error("block is not newer than the finalized block: {block:?}")]
+    BlockNotNewerThanFinalized { block: Arc<SignedBeaconBlock<P>> },
+    #[error("block is not newer than its parent (block: {block:?}, parent_slot: {parent_slot})")]
+    BlockNotNewerThanParent {
+        block: Arc<SignedBeaconBlock<P>>,
+        parent_slot: Slot,
+    },
+    #[error("block's parent is invalid: {block:?}")]
+    BlockParentInvalid { block: Arc<SignedBeaconBlock<P>> },
+    #[error("block's parent is unknown: {block:?}")]
+    BlockParentUnknown { block: Arc<SignedBeaconBlock<P>> },
+    #[error("block has incorrect proposer index (block: {block:?}, computed: {computed})")]
+    BlockProposerIndexMismatch {
+        block: Arc<SignedBeaconBlock<P>>,
+        computed: ValidatorIndex,
+    },
     #[error("aggregate and proof has invalid signature: {aggregate_and_proof:?}")]
     InvalidAggregateAndProofSignature {
         aggregate_and_proof: Box<SignedAggregateAndProof<P>>,
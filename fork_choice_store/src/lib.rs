@@ -74,8 +74,8 @@ pub use crate::{
     misc::{
         AggregateAndProofAction, AggregateAndProofOrigin, ApplyBlockChanges, ApplyTickChanges,
         AttestationAction, AttestationOrigin, AttesterSlashingOrigin, BlobSidecarAction,
-        BlobSidecarOrigin, BlockAction, BlockOrigin, ChainLink, PayloadAction, PayloadStatus,
-        ValidAttestation,
+        BlobSidecarOrigin, BlobSidecarSource, BlockAction, BlockOrigin, ChainLink, PayloadAction,
+        PayloadStatus, ValidAttestation,
     },
     segment::Segment,
     store::Store,
@@ -85,6 +85,7 @@ pub use crate::{
 mod blob_cache;
 mod error;
 mod misc;
+mod seen_validators;
 mod segment;
 mod state_cache;
 mod store;
@@ -481,7 +481,7 @@ impl AttesterSlashingOrigin {
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum BlobSidecarOrigin {
     Api,
     Gossip(SubnetId, GossipId),
@@ -514,6 +514,27 @@ impl BlobSidecarOrigin {
             Self::Api | Self::Own | Self::Requested(_) => None,
         }
     }
+
+    #[must_use]
+    pub const fn source(&self) -> BlobSidecarSource {
+        match self {
+            Self::Api => BlobSidecarSource::Api,
+            Self::Gossip(_, _) => BlobSidecarSource::Gossip,
+            Self::Requested(_) => BlobSidecarSource::Requested,
+            Self::Own => BlobSidecarSource::Own,
+        }
+    }
+}
+
+/// [`BlobSidecarOrigin`] without the data needed to respond to peers.
+/// Kept along with accepted blob sidecars for debugging data availability.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BlobSidecarSource {
+    Api,
+    Gossip,
+    Requested,
+    Own,
 }
 
 pub enum BlockAction<P: Preset> {
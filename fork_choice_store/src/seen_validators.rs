@@ -0,0 +1,67 @@
+use crossbeam_skiplist::{SkipMap, SkipSet};
+use types::phase0::primitives::{Epoch, ValidatorIndex};
+
+/// Validators that already had a valid message of some kind accepted for a target epoch.
+///
+/// Used to enforce the gossip conditions that only allow the first valid aggregate per aggregator
+/// and the first valid subnet attestation per validator in each epoch to be propagated.
+#[derive(Default)]
+pub struct SeenValidators {
+    validators: SkipMap<Epoch, SkipSet<ValidatorIndex>>,
+}
+
+impl SeenValidators {
+    pub fn contains(&self, epoch: Epoch, validator_index: ValidatorIndex) -> bool {
+        self.validators
+            .get(&epoch)
+            .is_some_and(|entry| entry.value().contains(&validator_index))
+    }
+
+    /// Returns `true` if the validator had not been seen in `epoch` before.
+    pub fn insert(&self, epoch: Epoch, validator_index: ValidatorIndex) -> bool {
+        let entry = self.validators.get_or_insert_with(epoch, SkipSet::new);
+        let validators = entry.value();
+
+        if validators.contains(&validator_index) {
+            return false;
+        }
+
+        validators.insert(validator_index);
+
+        true
+    }
+
+    pub fn prune(&self, finalized_epoch: Epoch) {
+        for entry in self.validators.range(..=finalized_epoch) {
+            entry.remove();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validators_are_only_inserted_once_per_epoch() {
+        let seen = SeenValidators::default();
+
+        assert!(!seen.contains(3, 7));
+        assert!(seen.insert(3, 7));
+        assert!(seen.contains(3, 7));
+        assert!(!seen.insert(3, 7));
+        assert!(seen.insert(4, 7));
+    }
+
+    #[test]
+    fn prune_removes_finalized_epochs() {
+        let seen = SeenValidators::default();
+
+        seen.insert(3, 7);
+        seen.insert(4, 7);
+        seen.prune(3);
+
+        assert!(!seen.contains(3, 7));
+        assert!(seen.contains(4, 7));
+    }
+}
@@ -7,6 +7,7 @@ use std::{
 
 use anyhow::{anyhow, bail, ensure, Result};
 use arithmetic::NonZeroExt as _;
+use bls::PublicKeyBytes;
 use clock::Tick;
 use execution_engine::ExecutionEngine;
 use features::Feature;
@@ -63,10 +64,12 @@ use crate::{
     misc::{
         AggregateAndProofAction, AggregateAndProofOrigin, ApplyBlockChanges, ApplyTickChanges,
         AttestationAction, AttestationOrigin, AttesterSlashingOrigin, BlobSidecarAction,
-        BlobSidecarOrigin, BlockAction, BranchPoint, ChainLink, Difference, DifferenceAtLocation,
-        DissolvedDifference, LatestMessage, Location, PartialAttestationAction, PartialBlockAction,
-        PayloadAction, PayloadStatus, Score, SegmentId, UnfinalizedBlock, ValidAttestation,
+        BlobSidecarOrigin, BlobSidecarSource, BlockAction, BranchPoint, ChainLink, Difference,
+        DifferenceAtLocation, DissolvedDifference, LatestMessage, Location,
+        PartialAttestationAction, PartialBlockAction, PayloadAction, PayloadStatus, Score,
+        SegmentId, UnfinalizedBlock, ValidAttestation,
     },
+    seen_validators::SeenValidators,
     segment::{Position, Segment},
     state_cache::StateCache,
     store_config::StoreConfig,
@@ -117,6 +120,10 @@ pub struct Store<P: Preset> {
     justified_active_balances: Arc<[Gwei]>,
     // Cached timely proposer score derived from `Store.justified_active_balances`.
     timely_proposer_score: OnceLock<Gwei>,
+    // Indices of validators in the last finalized state. Validators can only be appended to the
+    // registry, so most lookups in other states can be answered with this instead of scanning
+    // their registries. See `accessors::index_of_public_key_with_cache`.
+    finalized_validator_indices: HashMap<PublicKeyBytes, ValidatorIndex>,
     // Long-lived forks can theoretically have different validator registries.
     // That makes validator indices ambiguous, but the fork choice store is unaffected.
     // The fork choice store only deals with active validator indices, which cannot diverge.
@@ -205,6 +212,8 @@ pub struct Store<P: Preset> {
     preprocessed_states: StateCache<P>,
     execution_payload_locations: HashMap<ExecutionBlockHash, Location>,
     aggregate_and_proof_supersets: Arc<AggregateAndProofSupersets<P>>,
+    seen_aggregators: Arc<SeenValidators>,
+    seen_subnet_attesters: Arc<SeenValidators>,
     accepted_blob_sidecars:
         HashMap<(Slot, ValidatorIndex, BlobIndex), HashMap<H256, KzgCommitment>>,
     blob_cache: BlobCache<P>,
@@ -250,6 +259,8 @@ impl<P: Preset> Store<P> {
 
         let validator_count = anchor_state.validators().len_usize();
         let latest_messages = itertools::repeat_n(None, validator_count).collect();
+        let finalized_validator_indices =
+            accessors::get_or_init_validator_indices(&anchor_state, false).clone();
 
         Self {
             chain_config,
@@ -268,12 +279,15 @@ impl<P: Preset> Store<P> {
             head_segment_id: None,
             justified_active_balances: Self::active_balances(&anchor_state),
             timely_proposer_score: OnceLock::new(),
+            finalized_validator_indices,
             latest_messages,
             checkpoint_states: HashMap::unit(checkpoint, anchor_state),
             current_slot_attestations: vector![],
             preprocessed_states: StateCache::default(),
             execution_payload_locations: hashmap! {},
             aggregate_and_proof_supersets: Arc::new(AggregateAndProofSupersets::new()),
+            seen_aggregators: Arc::default(),
+            seen_subnet_attesters: Arc::default(),
             accepted_blob_sidecars: HashMap::default(),
             blob_cache: BlobCache::default(),
             rejected_block_roots: HashSet::default(),
@@ -323,6 +337,17 @@ impl<P: Preset> Store<P> {
         Self::epoch_at_slot(self.anchor().slot())
     }
 
+    /// Returns indices and sources of cached blob sidecars for the block with `block_root`.
+    ///
+    /// The block itself does not have to be in the store.
+    /// Blob sidecars are often received before the block they belong to.
+    pub fn received_blob_sidecars(
+        &self,
+        block_root: H256,
+    ) -> impl Iterator<Item = (BlobIndex, BlobSidecarSource)> + '_ {
+        self.blob_cache.sources(block_root)
+    }
+
     #[must_use]
     pub fn cached_blob_sidecar_by_id(
         &self,
@@ -446,6 +471,11 @@ impl<P: Preset> Store<P> {
             .map(|chain_link| chain_link.state(self))
     }
 
+    #[must_use]
+    pub const fn finalized_validator_indices(&self) -> &HashMap<PublicKeyBytes, ValidatorIndex> {
+        &self.finalized_validator_indices
+    }
+
     #[must_use]
     pub fn anchor(&self) -> &ChainLink<P> {
         self.finalized
@@ -471,6 +501,49 @@ impl<P: Preset> Store<P> {
             .or_else(|| self.finalized_before_or_at(slot))
     }
 
+    /// Returns roots of blocks from `proposer_index` in `slot`.
+    ///
+    /// Only blocks that have been accepted into the `Store` are considered.
+    /// More than one root means the proposer has equivocated.
+    pub fn proposed_block_roots(
+        &self,
+        slot: Slot,
+        proposer_index: ValidatorIndex,
+    ) -> impl Iterator<Item = H256> + '_ {
+        // Each segment is a single chain, so it can contain at most one block per slot.
+        self.unfinalized
+            .values()
+            .filter_map(move |segment| segment.block_before_or_at(slot, segment.last_position()))
+            .map(|unfinalized_block| &unfinalized_block.chain_link)
+            .chain(self.finalized_before_or_at(slot))
+            .filter(move |chain_link| chain_link.slot() == slot)
+            .filter(move |chain_link| chain_link.block.message().proposer_index() == proposer_index)
+            .map(|chain_link| chain_link.block_root)
+    }
+
+    /// Returns roots of blocks from `proposer_index` in `slot` known to be signed by the proposer.
+    ///
+    /// In addition to blocks returned by [`Store::proposed_block_roots`], this includes blocks
+    /// that have not been accepted yet but whose blob sidecars have been.
+    /// Blob sidecars contain signed headers of their blocks.
+    /// The same root may be returned more than once.
+    pub fn signed_block_roots(
+        &self,
+        slot: Slot,
+        proposer_index: ValidatorIndex,
+    ) -> impl Iterator<Item = H256> + '_ {
+        let blob_sidecar_block_roots = (0..P::MaxBlobsPerBlock::U64)
+            .filter_map(move |index| {
+                self.accepted_blob_sidecars
+                    .get(&(slot, proposer_index, index))
+            })
+            .flat_map(HashMap::keys)
+            .copied();
+
+        self.proposed_block_roots(slot, proposer_index)
+            .chain(blob_sidecar_block_roots)
+    }
+
     #[must_use]
     pub fn finalized_before_or_at(&self, slot: Slot) -> Option<&ChainLink<P>> {
         let index = match self.finalized.binary_search_by_key(&slot, ChainLink::slot) {
@@ -865,6 +938,22 @@ impl<P: Preset> Store<P> {
             .map(|chain_link| chain_link.block_root)
     }
 
+    /// Returns the root of the block that proposers in the epoch of `slot` depend on
+    /// in the chain ending with the block with root `block_root`.
+    ///
+    /// Returns `None` in the genesis epoch and if the block is not in the store.
+    #[must_use]
+    pub fn proposer_dependent_root(&self, block_root: H256, slot: Slot) -> Option<H256> {
+        if !self.contains_block(block_root) {
+            return None;
+        }
+
+        let epoch = misc::compute_epoch_at_slot::<P>(slot);
+        let dependent_slot = misc::compute_start_slot_at_epoch::<P>(epoch).checked_sub(1)?;
+
+        self.ancestor(block_root, dependent_slot)
+    }
+
     #[must_use]
     pub fn common_ancestor(&self, a_root: H256, b_root: H256) -> Option<&ChainLink<P>> {
         itertools::merge_join_by(
@@ -901,13 +990,54 @@ impl<P: Preset> Store<P> {
             .expect_none(
                 "the state corresponding to a particular checkpoint should only be inserted once; \
                  the mutator should only spawn one CheckpointStateTask per checkpoint",
-            )
+            );
+
+        self.evict_checkpoint_states(checkpoint);
+    }
+
+    // Checkpoint states for competing forks can pile up during long periods of non-finality.
+    // Evicted states are recomputed by the mutator if attestations or blocks need them again.
+    fn evict_checkpoint_states(&mut self, inserted: Checkpoint) {
+        let max_checkpoint_states = self.store_config.max_checkpoint_states;
+
+        if self.checkpoint_states.len() <= max_checkpoint_states {
+            return;
+        }
+
+        let retained = [
+            inserted,
+            self.justified_checkpoint,
+            self.unrealized_justified_checkpoint,
+        ];
+
+        let evicted = self
+            .checkpoint_states
+            .keys()
+            .copied()
+            .filter(|checkpoint| !retained.contains(checkpoint))
+            .sorted_by_key(|checkpoint| checkpoint.epoch)
+            .take(self.checkpoint_states.len() - max_checkpoint_states)
+            .collect_vec();
+
+        for checkpoint in evicted {
+            self.checkpoint_states.remove(&checkpoint);
+        }
     }
 
     /// [`get_safe_execution_payload_hash`](https://github.com/ethereum/consensus-specs/blob/v1.3.0/fork_choice/safe-block.md#get_safe_execution_payload_hash)
+    ///
+    /// This deviates from the specification when the justified block was imported optimistically.
+    /// Execution clients expose the safe block to applications through the `safe` block tag,
+    /// so we use its latest ancestor with a verified payload instead. The search stops at the
+    /// last finalized block, which is passed to `engine_forkchoiceUpdated` regardless.
     #[must_use]
     pub fn safe_execution_payload_hash(&self) -> ExecutionBlockHash {
-        self.justified_chain_link()
+        let finalized_block_root = self.last_finalized().block_root;
+
+        self.chain_ending_with(self.justified_checkpoint.root)
+            .find(|chain_link| {
+                chain_link.is_valid() || chain_link.block_root == finalized_block_root
+            })
             .and_then(ChainLink::execution_block_hash)
             .unwrap_or_default()
     }
@@ -1096,6 +1226,108 @@ impl<P: Preset> Store<P> {
         Ok(BlockAction::Accept(chain_link, attester_slashing_results))
     }
 
+    /// Performs the [`beacon_block` gossip validations] that do not require the state transition.
+    ///
+    /// Meant for blocks submitted through the HTTP API, which must pass gossip validation before
+    /// being broadcast. Conditions that make the Networking specification ignore a block are
+    /// treated as errors, except for blocks from future slots, which are left for
+    /// [`Store::validate_block`] to delay. The check for repeated proposals is left to callers.
+    ///
+    /// [`beacon_block` gossip validations]: https://github.com/ethereum/consensus-specs/blob/v1.4.0/specs/phase0/p2p-interface.md#beacon_block
+    pub fn validate_block_for_gossip(&self, block: &Arc<SignedBeaconBlock<P>>) -> Result<()> {
+        let message = block.message();
+        let slot = message.slot();
+        let parent_root = message.parent_root();
+
+        // > [IGNORE] The block is from a slot greater than the latest finalized slot
+        ensure!(
+            slot > self.finalized_slot(),
+            Error::BlockNotNewerThanFinalized {
+                block: block.clone_arc(),
+            },
+        );
+
+        // > [REJECT] The block's parent (defined by `block.parent_root`) passes validation.
+        //
+        // Invalid blocks are not kept in the store, so this has to be checked first.
+        ensure!(
+            !self.rejected_block_roots.contains(&parent_root),
+            Error::BlockParentInvalid {
+                block: block.clone_arc(),
+            },
+        );
+
+        // > [IGNORE] The block's parent (defined by `block.parent_root`) has been seen
+        let Some(parent) = self.chain_link(parent_root) else {
+            bail!(Error::BlockParentUnknown {
+                block: block.clone_arc(),
+            });
+        };
+
+        ensure!(
+            !parent.is_invalid(),
+            Error::BlockParentInvalid {
+                block: block.clone_arc(),
+            },
+        );
+
+        // > [REJECT] The block is from a higher slot than its parent.
+        let parent_slot = parent.slot();
+
+        ensure!(
+            slot > parent_slot,
+            Error::BlockNotNewerThanParent {
+                block: block.clone_arc(),
+                parent_slot,
+            },
+        );
+
+        // > [REJECT] The current `finalized_checkpoint` is an ancestor of `block`
+        let ancestor_at_finalized_slot = self
+            .ancestor(parent_root, self.finalized_slot())
+            .expect("every block in the store should have an ancestor at the last finalized slot");
+
+        ensure!(
+            ancestor_at_finalized_slot == self.finalized_checkpoint.root,
+            Error::BlockNotADescendantOfFinalized {
+                block: block.clone_arc(),
+            },
+        );
+
+        let mut state = self
+            .preprocessed_states
+            .before_or_at_slot(parent_root, slot)
+            .cloned()
+            .unwrap_or_else(|| parent.state(self));
+
+        if state.slot() < slot {
+            combined::process_slots(&self.chain_config, state.make_mut(), slot)?;
+        }
+
+        // > [REJECT] The proposer signature, `signed_beacon_block.signature`, is valid with respect
+        // > to the `proposer_index` pubkey.
+        SingleVerifier.verify_singular(
+            message.to_header().signing_root(&self.chain_config, &state),
+            block.signature(),
+            accessors::public_key(&state, message.proposer_index())?,
+            SignatureKind::Block,
+        )?;
+
+        // > [REJECT] The block is proposed by the expected `proposer_index` for the block's slot
+        // > in the context of the current shuffling
+        let computed = accessors::get_beacon_proposer_index(&state)?;
+
+        ensure!(
+            message.proposer_index() == computed,
+            Error::BlockProposerIndexMismatch {
+                block: block.clone_arc(),
+                computed,
+            },
+        );
+
+        Ok(())
+    }
+
     /// [`validate_merge_block`](https://github.com/ethereum/consensus-specs/blob/v1.3.0/specs/bellatrix/fork-choice.md#validate_merge_block)
     ///
     /// > Check the parent PoW block of execution payload is a valid terminal PoW block.
@@ -1227,6 +1459,18 @@ impl<P: Preset> Store<P> {
             ..
         } = aggregate.data;
 
+        // > The aggregate is the first valid aggregate received for the aggregator with index
+        // > `aggregate_and_proof.aggregator_index` for the epoch `aggregate.data.target.epoch`
+        //
+        // Aggregators are only recorded by `Store::register_aggregator` once an aggregate passes
+        // all validations. Invalid aggregates cannot be used to suppress valid ones.
+        if self
+            .seen_aggregators
+            .contains(target.epoch, aggregator_index)
+        {
+            return Ok(AggregateAndProofAction::Ignore);
+        }
+
         // TODO(feature/deneb): Figure out why this validation is split over 2 methods.
         // TODO(feature/deneb): This appears to be unfinished.
         //                      Deneb replaces the old validation with 2 new ones.
@@ -1445,6 +1689,16 @@ impl<P: Preset> Store<P> {
         let attesting_indices =
             self.attesting_indices(&target_state, &attestation, origin.validate_indexed())?;
 
+        // > There has been no other valid attestation seen on an attestation subnet that has an
+        // > identical `attestation.data.target.epoch` and participating validator index
+        if origin.subnet_id().is_some()
+            && attesting_indices
+                .iter()
+                .all(|index| self.seen_subnet_attesters.contains(target.epoch, *index))
+        {
+            return Ok(AttestationAction::Ignore);
+        }
+
         Ok(AttestationAction::Accept {
             attestation,
             attesting_indices,
@@ -1616,6 +1870,7 @@ impl<P: Preset> Store<P> {
         blob_sidecar: Arc<BlobSidecar<P>>,
         block_seen: bool,
         origin: &BlobSidecarOrigin,
+        kzg_proof_verified: bool,
         mut verifier: impl Verifier + Send,
     ) -> Result<BlobSidecarAction<P>> {
         let block_header = blob_sidecar.signed_block_header.message;
@@ -1735,13 +1990,15 @@ impl<P: Preset> Store<P> {
         );
 
         // [REJECT] The sidecar's blob is valid as verified by verify_blob_kzg_proof(blob_sidecar.blob, blob_sidecar.kzg_commitment, blob_sidecar.kzg_proof).
+        // `kzg_proof_verified` is set when the proof has already been verified as part of a batch.
         ensure!(
-            kzg_utils::eip_4844::verify_blob_kzg_proof::<P>(
-                &blob_sidecar.blob,
-                blob_sidecar.kzg_commitment,
-                blob_sidecar.kzg_proof,
-            )
-            .unwrap_or(false),
+            kzg_proof_verified
+                || kzg_utils::eip_4844::verify_blob_kzg_proof::<P>(
+                    &blob_sidecar.blob,
+                    blob_sidecar.kzg_commitment,
+                    blob_sidecar.kzg_proof,
+                )
+                .unwrap_or(false),
             Error::BlobSidecarInvalid { blob_sidecar }
         );
 
@@ -2003,6 +2260,44 @@ impl<P: Preset> Store<P> {
         }
     }
 
+    /// Records the aggregator of an aggregate accepted by [`Self::validate_aggregate_and_proof`].
+    ///
+    /// Returns `false` if another aggregate by the same aggregator was accepted for the same target
+    /// epoch. This can happen if both were validated concurrently.
+    pub fn register_aggregator(
+        &self,
+        target_epoch: Epoch,
+        aggregator_index: ValidatorIndex,
+    ) -> bool {
+        self.seen_aggregators.insert(target_epoch, aggregator_index)
+    }
+
+    /// Returns `true` if a subnet attestation from `validator_index` for `target_epoch` has been
+    /// accepted, either from gossip or from this node.
+    #[must_use]
+    pub fn subnet_attester_seen(
+        &self,
+        target_epoch: Epoch,
+        validator_index: ValidatorIndex,
+    ) -> bool {
+        self.seen_subnet_attesters
+            .contains(target_epoch, validator_index)
+    }
+
+    /// Records the attesters of a subnet attestation accepted by [`Self::validate_attestation`].
+    ///
+    /// Returns `false` if all of them already had a subnet attestation accepted for the same target
+    /// epoch.
+    pub fn register_subnet_attesters(
+        &self,
+        target_epoch: Epoch,
+        attesting_indices: impl IntoIterator<Item = ValidatorIndex>,
+    ) -> bool {
+        attesting_indices.into_iter().fold(false, |any_new, index| {
+            self.seen_subnet_attesters.insert(target_epoch, index) || any_new
+        })
+    }
+
     /// Applies an attestation previously validated using [`Self::validate_attestation`] or
     /// [`Self::validate_aggregate_and_proof`].
     ///
@@ -2072,7 +2367,11 @@ impl<P: Preset> Store<P> {
             .pipe(Ok)
     }
 
-    pub fn apply_blob_sidecar(&mut self, blob_sidecar: Arc<BlobSidecar<P>>) {
+    pub fn apply_blob_sidecar(
+        &mut self,
+        blob_sidecar: Arc<BlobSidecar<P>>,
+        source: BlobSidecarSource,
+    ) {
         let block_header = blob_sidecar.signed_block_header.message;
         let block_root = block_header.hash_tree_root();
 
@@ -2087,7 +2386,7 @@ impl<P: Preset> Store<P> {
 
         commitments.insert(block_root, blob_sidecar.kzg_commitment);
 
-        self.blob_cache.insert(blob_sidecar);
+        self.blob_cache.insert(blob_sidecar, source);
     }
 
     fn insert_block(&mut self, chain_link: ChainLink<P>) -> Result<()> {
@@ -2397,6 +2696,10 @@ impl<P: Preset> Store<P> {
             self.prune_orphans(partially_finalized_location);
         }
 
+        self.finalized_validator_indices =
+            accessors::get_or_init_validator_indices(&self.last_finalized().state(self), false)
+                .clone();
+
         let finalized_slot = self.finalized_slot();
 
         self.accepted_blob_sidecars
@@ -2405,6 +2708,8 @@ impl<P: Preset> Store<P> {
         self.preprocessed_states.prune(finalized_slot);
         self.aggregate_and_proof_supersets
             .prune(self.finalized_epoch());
+        self.seen_aggregators.prune(self.finalized_epoch());
+        self.seen_subnet_attesters.prune(self.finalized_epoch());
     }
 
     /// Applies changes to [`Store.latest_messages`] and computes changes to attesting balances.
@@ -2902,6 +3207,82 @@ impl<P: Preset> Store<P> {
         PayloadAction::DelayUntilBlock(block_hash)
     }
 
+    /// Marks every unfinalized block that is neither an ancestor nor a descendant of `block_root`
+    /// as invalid, forcing fork choice to select a head that descends from `block_root`.
+    ///
+    /// This is meant for manual intervention when the network finalizes or justifies
+    /// a checkpoint that the node considers non-viable. Only blocks with execution payloads
+    /// can be invalidated this way.
+    pub fn invalidate_branches_conflicting_with(&mut self, block_root: H256) {
+        // All unfinalized blocks descend from finalized ones.
+        if !self.unfinalized_locations.contains_key(&block_root) {
+            return;
+        }
+
+        let ancestors = self
+            .chain_ending_with(block_root)
+            .map(|chain_link| chain_link.block_root)
+            .collect::<HashSet<H256>>();
+
+        let conflicting_hashes = self
+            .unfinalized
+            .values()
+            .filter(|segment| {
+                !self
+                    .unfinalized_chain_ending_with(segment, segment.last_position())
+                    .any(|chain_link| chain_link.block_root == block_root)
+            })
+            .flat_map(|segment| segment.iter_up_to(..=segment.last_position()))
+            .map(|unfinalized_block| &unfinalized_block.chain_link)
+            .filter(|chain_link| !ancestors.contains(&chain_link.block_root))
+            .filter_map(ChainLink::execution_block_hash)
+            .collect_vec();
+
+        for hash in conflicting_hashes {
+            self.set_block_payload_status(hash, PayloadStatus::Invalid);
+        }
+
+        self.update_head_segment_id();
+    }
+
+    /// Returns the block with `block_hash` and its invalid descendants to `PayloadStatus::Optimistic`
+    /// so that they can be verified again.
+    ///
+    /// Descendants of an invalid block are invalid too, so the whole branch has to be reset.
+    pub fn reset_payload_statuses_for_reverification(&mut self, block_hash: ExecutionBlockHash) {
+        let descendant_hashes = self
+            .unfinalized
+            .values()
+            .flat_map(|segment| {
+                self.unfinalized_execution_chain_hashes(
+                    block_hash,
+                    segment,
+                    segment.last_position(),
+                )
+            })
+            .collect::<HashSet<_>>();
+
+        for hash in descendant_hashes
+            .into_iter()
+            .chain(core::iter::once(block_hash))
+        {
+            let is_invalid = self
+                .execution_payload_locations
+                .get(&hash)
+                .is_some_and(|location| {
+                    self.unfinalized[&location.segment_id][location.position]
+                        .chain_link
+                        .is_invalid()
+                });
+
+            if is_invalid {
+                self.set_block_payload_status(hash, PayloadStatus::Optimistic);
+            }
+        }
+
+        self.update_head_segment_id();
+    }
+
     pub fn update_chain_payload_statuses(
         &mut self,
         latest_valid_hash: ExecutionBlockHash,
@@ -3038,3 +3419,201 @@ impl<P: Preset> Store<P> {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use types::preset::Minimal;
+
+    use super::*;
+
+    #[test]
+    fn checkpoint_states_from_oldest_epochs_are_evicted_beyond_limit() {
+        let (mut store, state) = store_with_max_checkpoint_states(3);
+        let anchor_checkpoint = store.justified_checkpoint;
+
+        for epoch in 1..=4 {
+            store.insert_checkpoint_state(checkpoint(epoch), state.clone_arc());
+        }
+
+        assert_eq!(store.checkpoint_states.len(), 3);
+        assert!(store.contains_checkpoint_state(anchor_checkpoint));
+        assert!(!store.contains_checkpoint_state(checkpoint(1)));
+        assert!(!store.contains_checkpoint_state(checkpoint(2)));
+        assert!(store.contains_checkpoint_state(checkpoint(3)));
+        assert!(store.contains_checkpoint_state(checkpoint(4)));
+    }
+
+    #[test]
+    fn inserted_checkpoint_state_is_not_evicted_even_if_oldest() {
+        let (mut store, state) = store_with_max_checkpoint_states(3);
+        let anchor_checkpoint = store.justified_checkpoint;
+
+        store.insert_checkpoint_state(checkpoint(3), state.clone_arc());
+        store.insert_checkpoint_state(checkpoint(4), state.clone_arc());
+        store.insert_checkpoint_state(checkpoint(2), state);
+
+        assert_eq!(store.checkpoint_states.len(), 3);
+        assert!(store.contains_checkpoint_state(anchor_checkpoint));
+        assert!(store.contains_checkpoint_state(checkpoint(2)));
+        assert!(!store.contains_checkpoint_state(checkpoint(3)));
+        assert!(store.contains_checkpoint_state(checkpoint(4)));
+    }
+
+    #[test]
+    fn checkpoint_states_within_limit_are_kept() {
+        let (mut store, state) = store_with_max_checkpoint_states(16);
+
+        for epoch in 1..=4 {
+            store.insert_checkpoint_state(checkpoint(epoch), state.clone_arc());
+        }
+
+        assert_eq!(store.checkpoint_states.len(), 5);
+    }
+
+    #[test]
+    fn valid_blocks_pass_gossip_validation_even_if_they_are_repeated_proposals() -> Result<()> {
+        let (store, genesis_state) = genesis_store();
+
+        let (block_1, _) = factory::empty_block(
+            &store.chain_config,
+            genesis_state.clone_arc(),
+            1,
+            H256::repeat_byte(1),
+        )?;
+
+        let (block_2, _) =
+            factory::empty_block(&store.chain_config, genesis_state, 1, H256::repeat_byte(2))?;
+
+        store.validate_block_for_gossip(&block_1)?;
+        store.validate_block_for_gossip(&block_2)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn block_with_invalid_proposer_signature_fails_gossip_validation() -> Result<()> {
+        let (store, genesis_state) = genesis_store();
+
+        let (block_1, _) = factory::empty_block(
+            &store.chain_config,
+            genesis_state.clone_arc(),
+            1,
+            H256::repeat_byte(1),
+        )?;
+
+        let (block_2, _) =
+            factory::empty_block(&store.chain_config, genesis_state, 1, H256::repeat_byte(2))?;
+
+        let (message, _) = block_1.as_ref().clone().split();
+        let (_, signature) = block_2.as_ref().clone().split();
+        let forged_block = Arc::new(message.with_signature(signature));
+
+        assert!(store.validate_block_for_gossip(&forged_block).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn block_with_unknown_parent_fails_gossip_validation() -> Result<()> {
+        let (store, genesis_state) = genesis_store();
+
+        let (_, state_1) =
+            factory::empty_block(&store.chain_config, genesis_state, 1, H256::repeat_byte(1))?;
+
+        let (block_2, _) =
+            factory::empty_block(&store.chain_config, state_1, 2, H256::repeat_byte(2))?;
+
+        let error = store
+            .validate_block_for_gossip(&block_2)
+            .expect_err("parent of block_2 is not in the store");
+
+        assert!(matches!(
+            error.downcast_ref::<Error<Minimal>>(),
+            Some(Error::BlockParentUnknown { .. }),
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn signed_block_roots_include_blocks_of_accepted_blob_sidecars() {
+        let (mut store, _) = genesis_store();
+
+        let blob_sidecar = |proposer_index, body_root, index| {
+            let mut blob_sidecar = BlobSidecar::<Minimal>::default();
+            blob_sidecar.index = index;
+            blob_sidecar.signed_block_header.message.slot = 1;
+            blob_sidecar.signed_block_header.message.proposer_index = proposer_index;
+            blob_sidecar.signed_block_header.message.body_root = body_root;
+            Arc::new(blob_sidecar)
+        };
+
+        let blob_sidecar_1 = blob_sidecar(1, H256::repeat_byte(1), 0);
+        let blob_sidecar_2 = blob_sidecar(1, H256::repeat_byte(2), 1);
+        let blob_sidecar_3 = blob_sidecar(2, H256::repeat_byte(3), 0);
+
+        let block_root_1 = blob_sidecar_1.signed_block_header.message.hash_tree_root();
+        let block_root_2 = blob_sidecar_2.signed_block_header.message.hash_tree_root();
+
+        assert_eq!(store.signed_block_roots(1, 1).next(), None);
+
+        store.apply_blob_sidecar(blob_sidecar_1, BlobSidecarSource::Gossip);
+        store.apply_blob_sidecar(blob_sidecar_2, BlobSidecarSource::Gossip);
+        store.apply_blob_sidecar(blob_sidecar_3, BlobSidecarSource::Gossip);
+
+        assert_eq!(
+            store.signed_block_roots(1, 1).collect_vec(),
+            [block_root_1, block_root_2],
+        );
+
+        assert_eq!(store.proposed_block_roots(1, 1).next(), None);
+        assert_eq!(store.signed_block_roots(1, 3).next(), None);
+        assert_eq!(store.signed_block_roots(2, 1).next(), None);
+    }
+
+    fn genesis_store() -> (Store<Minimal>, Arc<BeaconState<Minimal>>) {
+        let config = Arc::new(ChainConfig::minimal());
+        let store_config = StoreConfig::minimal(&config);
+        new_store(config, store_config)
+    }
+
+    fn store_with_max_checkpoint_states(
+        max_checkpoint_states: usize,
+    ) -> (Store<Minimal>, Arc<BeaconState<Minimal>>) {
+        let config = Arc::new(ChainConfig::minimal());
+
+        let store_config = StoreConfig {
+            max_checkpoint_states,
+            ..StoreConfig::minimal(&config)
+        };
+
+        new_store(config, store_config)
+    }
+
+    fn new_store(
+        config: Arc<ChainConfig>,
+        store_config: StoreConfig,
+    ) -> (Store<Minimal>, Arc<BeaconState<Minimal>>) {
+        let (genesis_state, _) =
+            factory::min_genesis_state(&config).expect("genesis state should be valid");
+
+        let genesis_block = Arc::new(genesis::beacon_block(&genesis_state));
+
+        let store = Store::new(
+            config,
+            store_config,
+            genesis_block,
+            genesis_state.clone_arc(),
+            true,
+        );
+
+        (store, genesis_state)
+    }
+
+    fn checkpoint(epoch: Epoch) -> Checkpoint {
+        Checkpoint {
+            epoch,
+            root: H256::repeat_byte(1),
+        }
+    }
+}
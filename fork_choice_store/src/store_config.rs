@@ -10,6 +10,11 @@ pub struct StoreConfig {
     pub max_empty_slots: u64,
     #[educe(Default = 128)]
     pub unfinalized_states_in_memory: u64,
+    #[educe(Default = 16)]
+    pub max_checkpoint_states: usize,
+    /// Reorganizations deeper than this many slots are logged as warnings.
+    #[educe(Default = 2)]
+    pub reorg_warning_depth: u64,
 }
 
 impl StoreConfig {
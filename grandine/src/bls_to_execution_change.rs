@@ -0,0 +1,126 @@
+use anyhow::{ensure, Result};
+use bls::PublicKeyBytes;
+use helper_functions::{misc, signing::SignForAllForksWithGenesis as _};
+use log::info;
+use reqwest::{Client, Url};
+use serde::Deserialize;
+use thiserror::Error;
+use types::{
+    capella::containers::{BlsToExecutionChange, SignedBlsToExecutionChange},
+    config::Config as ChainConfig,
+    phase0::{
+        containers::Validator,
+        primitives::{ExecutionAddress, ValidatorIndex, H256},
+    },
+    preset::Preset,
+    traits::BeaconState,
+};
+
+use crate::key_derivation;
+
+#[derive(Deserialize)]
+struct ValidatorResponse {
+    data: ValidatorData,
+}
+
+#[derive(Deserialize)]
+struct ValidatorData {
+    validator: Validator,
+}
+
+/// Signs [`BlsToExecutionChange`]s with withdrawal keys derived from a mnemonic and submits them.
+///
+/// The withdrawal credentials of each validator are fetched from the beacon node and compared
+/// with the derived key before anything is signed. This catches wrong account indices and
+/// mnemonics that have a valid checksum but are not the ones used for the deposit.
+pub async fn change_withdrawal_credentials<P: Preset>(
+    chain_config: &ChainConfig,
+    genesis_state: &impl BeaconState<P>,
+    client: &Client,
+    validator_indices: Vec<ValidatorIndex>,
+    account_indices: Vec<u32>,
+    to_execution_address: ExecutionAddress,
+    beacon_node_url: Url,
+) -> Result<()> {
+    ensure!(
+        validator_indices.len() == account_indices.len(),
+        Error::IndexCountMismatch,
+    );
+
+    let seed = key_derivation::prompt_seed()?;
+    let mut changes = Vec::with_capacity(validator_indices.len());
+
+    for (validator_index, account_index) in validator_indices.into_iter().zip(account_indices) {
+        let secret_key = key_derivation::withdrawal_key(seed.as_slice(), account_index)?;
+        let from_bls_pubkey = secret_key.to_public_key().into();
+
+        let url = beacon_node_url.join(&format!(
+            "/eth/v1/beacon/states/head/validators/{validator_index}"
+        ))?;
+
+        let ValidatorResponse { data } = client
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let withdrawal_credentials = data.validator.withdrawal_credentials;
+
+        ensure!(
+            withdrawal_credentials == misc::bls_withdrawal_credentials(from_bls_pubkey),
+            Error::WithdrawalCredentialsMismatch {
+                validator_index,
+                account_index,
+                from_bls_pubkey,
+                withdrawal_credentials,
+            },
+        );
+
+        let message = BlsToExecutionChange {
+            validator_index,
+            from_bls_pubkey,
+            to_execution_address,
+        };
+
+        let signature = message
+            .sign(chain_config, genesis_state, &secret_key)
+            .into();
+
+        changes.push(SignedBlsToExecutionChange { message, signature });
+    }
+
+    let url = beacon_node_url.join("/eth/v1/beacon/pool/bls_to_execution_changes")?;
+
+    client
+        .post(url)
+        .json(&changes)
+        .send()
+        .await?
+        .error_for_status()?;
+
+    info!(
+        "submitted {} BLS to execution changes to {beacon_node_url} \
+         (withdrawals will be sent to {to_execution_address:?})",
+        changes.len(),
+    );
+
+    Ok(())
+}
+
+#[derive(Debug, Error)]
+enum Error {
+    #[error("--validator-indices and --account-indices must have the same number of elements")]
+    IndexCountMismatch,
+    #[error(
+        "withdrawal key for account {account_index} ({from_bls_pubkey:?}) does not match \
+         withdrawal credentials of validator {validator_index} ({withdrawal_credentials:?})"
+    )]
+    WithdrawalCredentialsMismatch {
+        validator_index: ValidatorIndex,
+        account_index: u32,
+        from_bls_pubkey: PublicKeyBytes,
+        withdrawal_credentials: H256,
+    },
+}
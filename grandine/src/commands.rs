@@ -1,7 +1,10 @@
+use core::num::NonZeroU64;
 use std::path::PathBuf;
 
+use bls::PublicKeyBytes;
 use clap::Subcommand;
-use types::phase0::primitives::Slot;
+use reqwest::Url;
+use types::phase0::primitives::{Epoch, ExecutionAddress, Slot, UnixSeconds, ValidatorIndex};
 
 #[derive(Clone, Subcommand)]
 #[cfg_attr(test, derive(PartialEq, Eq, Debug))]
@@ -40,6 +43,28 @@ pub enum GrandineCommand {
     /// (example: grandine interchange import file.json)
     #[clap(subcommand)]
     Interchange(InterchangeCommand),
+
+    /// Generate genesis state with deterministic interop validator keys
+    /// and write it along with the configuration used to generate it
+    /// (example: grandine --network minimal interop-genesis --genesis-time 1700000000 --validator-count 64)
+    InteropGenesis {
+        /// Genesis time of the generated state
+        #[clap(long, value_name = "UNIX_SECONDS")]
+        genesis_time: UnixSeconds,
+
+        /// Number of validators with interop keys
+        #[clap(long, value_name = "COUNT")]
+        validator_count: NonZeroU64,
+
+        /// Output directory (defaults to current directory)
+        #[clap(short, long)]
+        output_dir: Option<PathBuf>,
+    },
+
+    /// Manage validators
+    /// (example: grandine validator exit --public-key 0x… --validator-index 1234)
+    #[clap(subcommand)]
+    Validator(ValidatorCommand),
 }
 
 #[derive(Clone, Subcommand)]
@@ -52,3 +77,77 @@ pub enum InterchangeCommand {
     /// (example: grandine interchange export file.json)
     Export { file_path: PathBuf },
 }
+
+#[derive(Clone, Subcommand)]
+#[cfg_attr(test, derive(PartialEq, Eq, Debug))]
+pub enum ValidatorCommand {
+    /// Sign a voluntary exit with a key loaded from keystores or Web3Signer
+    /// and optionally submit it to a beacon node
+    /// (example: grandine --keystore-dir keys --keystore-password-file password.txt
+    /// validator exit --public-key 0x… --validator-index 1234 --beacon-node-url http://localhost:5052)
+    Exit {
+        /// Public key of the validator to exit
+        #[clap(long)]
+        public_key: PublicKeyBytes,
+
+        /// Index of the validator to exit
+        #[clap(long)]
+        validator_index: ValidatorIndex,
+
+        /// Epoch to sign the exit for (defaults to current epoch)
+        #[clap(long)]
+        epoch: Option<Epoch>,
+
+        /// Beacon Node API URL to submit the exit to (exit is only printed if omitted)
+        #[clap(long)]
+        beacon_node_url: Option<Url>,
+    },
+
+    /// Change BLS withdrawal credentials to an execution address
+    /// using withdrawal keys derived from a mnemonic (the mnemonic is read from a prompt)
+    /// (example: grandine validator bls-to-execution-change --validator-indices 1234,1235
+    /// --account-indices 0,1 --to-execution-address 0x… --beacon-node-url http://localhost:5052)
+    BlsToExecutionChange {
+        /// Indices of validators to change withdrawal credentials of
+        #[clap(long, value_delimiter = ',', required = true)]
+        validator_indices: Vec<ValidatorIndex>,
+
+        /// EIP-2334 account indices of withdrawal keys in the same order as --validator-indices
+        #[clap(long, value_delimiter = ',', required = true)]
+        account_indices: Vec<u32>,
+
+        /// Execution address to send withdrawals to
+        #[clap(long)]
+        to_execution_address: ExecutionAddress,
+
+        /// Beacon Node API URL to verify withdrawal credentials with and submit changes to
+        #[clap(long)]
+        beacon_node_url: Url,
+    },
+
+    /// Generate validator keystores and deposit data for them from a new or existing mnemonic
+    /// (example: grandine --network holesky validator generate-keys --count 4
+    /// --withdrawal-address 0x…)
+    GenerateKeys {
+        /// Number of validators to generate keys for
+        #[clap(long, value_name = "COUNT")]
+        count: u32,
+
+        /// EIP-2334 account index of the first validator
+        #[clap(long, default_value_t = 0)]
+        first_account_index: u32,
+
+        /// Execution address to set in withdrawal credentials
+        /// (BLS withdrawal credentials derived from the mnemonic are used if omitted)
+        #[clap(long)]
+        withdrawal_address: Option<ExecutionAddress>,
+
+        /// Derive keys from an existing mnemonic instead of generating a new one
+        #[clap(long)]
+        existing_mnemonic: bool,
+
+        /// Output directory (defaults to current directory)
+        #[clap(short, long)]
+        output_dir: Option<PathBuf>,
+    },
+}
@@ -1,11 +1,34 @@
+use std::path::{Path, PathBuf};
+
 use anyhow::{Error, Result};
 use itertools::Itertools as _;
 use p2p::Enr;
 
 pub const CONFIG_FILE: &str = "config.yaml";
 pub const DEPOSIT_CONTRACT_BLOCK_FILE: &str = "deposit_contract_block.txt";
+pub const DEPLOY_BLOCK_FILE: &str = "deploy_block.txt";
 pub const GENESIS_STATE_FILE: &str = "genesis.ssz";
 pub const PLAIN_BOOTNODES_FILE: &str = "bootstrap_nodes.txt";
+pub const BOOT_ENR_FILE: &str = "boot_enr.yaml";
+
+// Network configuration directories come in two layouts.
+// `eth-clients` repositories use `deposit_contract_block.txt` and `bootstrap_nodes.txt`.
+// Devnet tooling usually emits `deploy_block.txt` and `boot_enr.yaml` instead.
+pub fn deposit_contract_block_file(directory: &Path) -> Option<PathBuf> {
+    first_existing_file(directory, [DEPOSIT_CONTRACT_BLOCK_FILE, DEPLOY_BLOCK_FILE])
+}
+
+pub fn bootnodes_file(directory: &Path) -> Option<PathBuf> {
+    first_existing_file(directory, [PLAIN_BOOTNODES_FILE, BOOT_ENR_FILE])
+}
+
+pub fn parse_bootnodes_file(path: &Path, string: &str) -> Result<Vec<Enr>> {
+    if path.ends_with(BOOT_ENR_FILE) {
+        parse_yaml_bootnodes(string)
+    } else {
+        parse_plain_bootnodes(string)
+    }
+}
 
 pub fn parse_plain_bootnodes(string: &str) -> Result<Vec<Enr>> {
     string
@@ -22,6 +45,22 @@ pub fn parse_plain_bootnodes(string: &str) -> Result<Vec<Enr>> {
         .map_err(Error::msg)
 }
 
+pub fn parse_yaml_bootnodes(string: &str) -> Result<Vec<Enr>> {
+    serde_yaml::from_str::<Option<Vec<String>>>(string)?
+        .unwrap_or_default()
+        .iter()
+        .map(|enr| enr.trim().parse())
+        .try_collect()
+        .map_err(Error::msg)
+}
+
+fn first_existing_file<const N: usize>(directory: &Path, names: [&str; N]) -> Option<PathBuf> {
+    names
+        .into_iter()
+        .map(|name| directory.join(name))
+        .find(|path| path.is_file())
+}
+
 #[allow(clippy::needless_pass_by_value)]
 #[cfg(test)]
 mod tests {
@@ -56,4 +95,33 @@ mod tests {
 
         Ok(())
     }
+
+    #[test_case(format!("- {ENR_1}\n- {ENR_2}"); "minimal")]
+    #[test_case(
+        format!("
+            # bootnode 1
+            - {ENR_1}
+            - \"{ENR_2}\" # bootnode 2
+        ");
+        "comments, quotes, leading whitespace"
+    )]
+    fn parse_yaml_bootnodes_successfully_parses(string: String) -> Result<()> {
+        let expected = [
+            ENR_1.parse().map_err(Error::msg)?,
+            ENR_2.parse().map_err(Error::msg)?,
+        ];
+
+        let actual = parse_yaml_bootnodes(string.as_str())?;
+
+        assert_eq!(actual, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_yaml_bootnodes_accepts_empty_file() -> Result<()> {
+        assert!(parse_yaml_bootnodes("")?.is_empty());
+
+        Ok(())
+    }
 }
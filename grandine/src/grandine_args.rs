@@ -1,19 +1,21 @@
 use core::{
     fmt::Display,
-    num::{NonZeroU16, NonZeroU64},
+    num::{NonZeroU16, NonZeroU32, NonZeroU64, NonZeroUsize},
     ops::Not as _,
     time::Duration,
 };
 use std::{
+    collections::{BTreeMap, HashSet},
     net::{IpAddr, Ipv4Addr, Ipv6Addr},
     path::PathBuf,
     sync::Arc,
 };
 
-use anyhow::{ensure, Result};
+use anyhow::{ensure, Error as AnyhowError, Result};
 use bls::PublicKeyBytes;
 use builder_api::{
-    BuilderConfig, DEFAULT_BUILDER_MAX_SKIPPED_SLOTS, DEFAULT_BUILDER_MAX_SKIPPED_SLOTS_PER_EPOCH,
+    BuilderConfig, RelayConfig, DEFAULT_BUILDER_BOOST_FACTOR, DEFAULT_BUILDER_MAX_SKIPPED_SLOTS,
+    DEFAULT_BUILDER_MAX_SKIPPED_SLOTS_PER_EPOCH,
 };
 use bytesize::ByteSize;
 use clap::{error::ErrorKind, Args, CommandFactory as _, Error as ClapError, Parser, ValueEnum};
@@ -27,33 +29,41 @@ use features::Feature;
 use fork_choice_control::DEFAULT_ARCHIVAL_EPOCH_INTERVAL;
 use fork_choice_store::StoreConfig;
 use grandine_version::{APPLICATION_NAME, APPLICATION_VERSION};
-use http_api::HttpApiConfig;
+use http_api::{
+    AdminToken, HttpApiConfig, RateLimitConfig, RequestQueueConfig, TlsConfig, UnixSocketConfig,
+};
 use itertools::{EitherOrBoth, Itertools as _};
 use log::warn;
 use metrics::{MetricsServerConfig, MetricsServiceConfig};
+use operation_pools::{
+    PoolConfig, DEFAULT_ATTESTATION_PACKING_TIME_BUDGET,
+    DEFAULT_MAX_AGGREGATES_PER_ATTESTATION_DATA, DEFAULT_MAX_AGGREGATES_PER_CONTRIBUTION_DATA,
+    DEFAULT_MAX_BLS_TO_EXECUTION_CHANGES,
+};
 use p2p::{Enr, Multiaddr, NetworkConfig};
 use prometheus_metrics::Metrics;
-use reqwest::{header::HeaderValue, Url};
+use reqwest::{header::HeaderValue, Method, Url};
 use runtime::{
     MetricsConfig, StorageConfig, DEFAULT_ETH1_DB_SIZE, DEFAULT_ETH2_DB_SIZE,
     DEFAULT_LIBP2P_IPV4_PORT, DEFAULT_LIBP2P_IPV6_PORT, DEFAULT_LIBP2P_QUIC_IPV4_PORT,
     DEFAULT_LIBP2P_QUIC_IPV6_PORT, DEFAULT_METRICS_PORT, DEFAULT_REQUEST_TIMEOUT,
-    DEFAULT_TARGET_PEERS, DEFAULT_TIMEOUT,
+    DEFAULT_SUBNET_PEER_DISCOVERY_DELAY, DEFAULT_TARGET_PEERS, DEFAULT_TIMEOUT,
 };
 use serde::{de::DeserializeOwned, Serialize};
 use serde_json::Value;
-use signer::Web3SignerConfig;
+use signer::{Web3SignerClientIdentity, Web3SignerConfig, Web3SignerTlsConfig};
 use slasher::SlasherConfig;
 use slashing_protection::DEFAULT_SLASHING_PROTECTION_HISTORY_LIMIT;
 use std_ext::ArcExt as _;
 use thiserror::Error;
-use tower_http::cors::AllowOrigin;
+use tower_http::cors::{AllowMethods, AllowOrigin};
 use types::{
-    bellatrix::primitives::Difficulty,
+    bellatrix::primitives::{Difficulty, Wei},
     config::Config as ChainConfig,
     nonstandard::Phase,
     phase0::primitives::{
-        Epoch, ExecutionAddress, ExecutionBlockHash, ExecutionBlockNumber, Slot, H256,
+        Epoch, ExecutionAddress, ExecutionBlockHash, ExecutionBlockNumber, Slot, ValidatorIndex,
+        H256,
     },
     preset::PresetName,
 };
@@ -61,11 +71,10 @@ use validator::ValidatorConfig;
 
 use crate::{
     commands::GrandineCommand,
-    config_dir::{
-        self, CONFIG_FILE, DEPOSIT_CONTRACT_BLOCK_FILE, GENESIS_STATE_FILE, PLAIN_BOOTNODES_FILE,
-    },
+    config_dir::{self, CONFIG_FILE, GENESIS_STATE_FILE},
     consts::GRANDINE_DONATION_ADDRESS,
     grandine_config::GrandineConfig,
+    options_file,
     predefined_network::PredefinedNetwork,
     validators::Validators,
 };
@@ -102,6 +111,12 @@ pub struct GrandineArgs {
     #[clap(long)]
     features: Vec<Feature>,
 
+    /// Load command line options from YAML or TOML FILE.
+    /// Options passed on the command line or through GRANDINE_* environment variables
+    /// take precedence over ones in FILE
+    #[clap(long, value_name = "FILE")]
+    config_file: Option<PathBuf>,
+
     #[clap(subcommand)]
     command: Option<GrandineCommand>,
 }
@@ -116,8 +131,9 @@ struct ChainOptions {
     #[clap(long, value_name = "YAML_FILE")]
     configuration_file: Option<PathBuf>,
 
-    /// Load configuration from directory
-    #[clap(long, value_name = "DIRECTORY")]
+    /// Load network configuration (config.yaml, genesis.ssz, deploy block and bootnodes)
+    /// from DIRECTORY to run networks that are not predefined
+    #[clap(long, alias = "testnet-dir", value_name = "DIRECTORY")]
     configuration_directory: Option<PathBuf>,
 
     /// Verify that Phase 0 variables in preset match YAML_FILE
@@ -165,8 +181,12 @@ struct ChainOptions {
     genesis_state_file: Option<PathBuf>,
 
     /// Download genesis state from specified URL
-    #[clap(long, value_name = "URL")]
+    #[clap(long, alias = "genesis-state-url", value_name = "URL")]
     genesis_state_download_url: Option<Url>,
+
+    /// Verify that the hash tree root of the genesis state is ROOT
+    #[clap(long, value_name = "ROOT")]
+    genesis_state_root: Option<H256>,
 }
 
 #[derive(Args)]
@@ -184,48 +204,205 @@ struct HttpApiOptions {
     #[clap(long)]
     http_allowed_origins: Vec<HeaderValue>,
 
+    /// List of methods allowed in CORS preflight responses of the HTTP API server.
+    /// Needed for browser-based clients that make requests other than simple GET requests.
+    /// [default: none]
+    #[clap(long, value_name = "METHOD")]
+    http_allowed_methods: Vec<Method>,
+
     /// Max number of events stored in a single channel for HTTP API /events api call
     #[clap(long, default_value_t = HttpApiConfig::default().max_events)]
     max_events: usize,
 
+    /// Number of recent events of each topic kept for /eth/v1/events clients reconnecting with
+    /// a Last-Event-ID header. Missed events are replayed to them before new ones
+    #[clap(long, default_value_t = HttpApiConfig::default().event_replay_buffer_size)]
+    http_event_replay_buffer_size: usize,
+
     /// HTTP API timeout in milliseconds
     #[clap(long, default_value_t = HttpApiOptions::default_timeout())]
     timeout: u64,
+
+    /// Timeout in milliseconds for costly HTTP API endpoints (states, validators, rewards).
+    /// Loading states for requests that time out is canceled.
+    /// [default: value of --timeout]
+    #[clap(long, value_name = "MILLISECONDS")]
+    http_costly_timeout: Option<u64>,
+
+    /// Maximum number of requests to costly HTTP API endpoints (states, validators, rewards)
+    /// handled at once. Further requests wait in a queue
+    #[clap(
+        long,
+        value_name = "REQUESTS",
+        default_value_t = RequestQueueConfig::default().max_concurrent_requests,
+    )]
+    http_costly_max_concurrent_requests: NonZeroUsize,
+
+    /// Maximum number of requests to costly HTTP API endpoints waiting to be handled.
+    /// Requests that do not fit in the queue are rejected with 503 Service Unavailable
+    #[clap(
+        long,
+        value_name = "REQUESTS",
+        default_value_t = RequestQueueConfig::default().max_queued_requests,
+    )]
+    http_costly_max_queued_requests: usize,
+
+    /// Path to a file containing a token for HTTP API admin endpoints.
+    /// Requests to them must include the header `Authorization: Bearer <token>`.
+    /// Admin endpoints are disabled if this is not specified.
+    #[clap(long, value_name = "PATH")]
+    http_admin_token_file: Option<PathBuf>,
+
+    /// Minimum size in bytes of HTTP API responses to compress
+    #[clap(
+        long,
+        value_name = "BYTES",
+        default_value_t = HttpApiConfig::default().compression_threshold.unwrap_or_default(),
+    )]
+    http_compression_threshold: u16,
+
+    /// Disable gzip and zstd compression of HTTP API responses
+    #[clap(long)]
+    disable_http_compression: bool,
+
+    /// Maximum size in bytes of HTTP API request bodies
+    #[clap(long, value_name = "BYTES", default_value_t = HttpApiConfig::default().max_body_size)]
+    http_max_body_size: usize,
+
+    /// Maximum size in bytes of HTTP API request bodies when publishing blocks
+    #[clap(
+        long,
+        value_name = "BYTES",
+        default_value_t = HttpApiConfig::default().max_block_body_size,
+    )]
+    http_max_block_body_size: usize,
+
+    /// Maximum number of requests per second to costly HTTP API endpoints
+    /// (states, validators, rewards) per client.
+    /// Rate limiting is disabled if this is not specified.
+    #[clap(long, value_name = "RPS")]
+    http_rate_limit: Option<NonZeroU32>,
+
+    /// Maximum number of requests to costly HTTP API endpoints a client can make at once
+    /// [default: value of --http-rate-limit]
+    #[clap(long, value_name = "REQUESTS", requires = "http_rate_limit")]
+    http_rate_limit_burst: Option<NonZeroU32>,
+
+    /// IP addresses exempt from HTTP API rate limiting
+    #[clap(long, default_values_t = RateLimitConfig::default_exempt_addresses())]
+    http_rate_limit_exempt: Vec<IpAddr>,
+
+    /// Rate limit HTTP API requests per bearer token instead of per IP address.
+    /// Tokens are not validated, so this should only be used behind a proxy that authenticates them.
+    #[clap(long, requires = "http_rate_limit")]
+    http_rate_limit_per_token: bool,
+
+    /// Path to a PEM file containing the certificate chain for serving the HTTP API over HTTPS.
+    /// The certificate is reloaded when the file changes.
+    #[clap(long, value_name = "PATH", requires = "http_tls_key")]
+    http_tls_cert: Option<PathBuf>,
+
+    /// Path to a PEM file containing the private key for --http-tls-cert
+    #[clap(long, value_name = "PATH", requires = "http_tls_cert")]
+    http_tls_key: Option<PathBuf>,
+
+    /// Path to a Unix socket to serve the HTTP API on in addition to the TCP address
+    #[clap(long, value_name = "PATH")]
+    http_unix_socket: Option<PathBuf>,
+
+    /// File mode of the HTTP API Unix socket in octal
+    #[clap(long, value_name = "MODE", default_value = "660", value_parser = parse_file_mode)]
+    http_unix_socket_mode: u32,
+
+    /// Serve the HTTP API only on the Unix socket, not on the TCP address
+    #[clap(long, requires = "http_unix_socket")]
+    http_unix_socket_only: bool,
 }
 
-impl From<HttpApiOptions> for HttpApiConfig {
-    fn from(http_api_options: HttpApiOptions) -> Self {
+impl TryFrom<HttpApiOptions> for HttpApiConfig {
+    type Error = AnyhowError;
+
+    fn try_from(http_api_options: HttpApiOptions) -> Result<Self> {
         let HttpApiOptions {
             http_address,
             http_port,
             http_allowed_origins,
+            http_allowed_methods,
             max_events,
+            http_event_replay_buffer_size,
             timeout,
+            http_costly_timeout,
+            http_costly_max_concurrent_requests,
+            http_costly_max_queued_requests,
+            http_admin_token_file,
+            http_compression_threshold,
+            disable_http_compression,
+            http_max_body_size,
+            http_max_block_body_size,
+            http_rate_limit,
+            http_rate_limit_burst,
+            http_rate_limit_exempt,
+            http_rate_limit_per_token,
+            http_tls_cert,
+            http_tls_key,
+            http_unix_socket,
+            http_unix_socket_mode,
+            http_unix_socket_only,
         } = http_api_options;
 
+        let admin_token = http_admin_token_file
+            .map(|path| -> Result<_> {
+                let contents = fs_err::read_to_string(path)?;
+                let admin_token =
+                    AdminToken::new(contents.as_str()).ok_or(Error::EmptyHttpAdminToken)?;
+                Ok(admin_token)
+            })
+            .transpose()?;
+
         let mut http_api_config = Self {
             max_events,
+            event_replay_buffer_size: http_event_replay_buffer_size,
             timeout: Some(Duration::from_millis(timeout)),
+            costly_timeout: http_costly_timeout.map(Duration::from_millis),
+            costly_request_queue: RequestQueueConfig {
+                max_concurrent_requests: http_costly_max_concurrent_requests,
+                max_queued_requests: http_costly_max_queued_requests,
+            },
+            admin_token,
+            compression_threshold: disable_http_compression
+                .not()
+                .then_some(http_compression_threshold),
+            max_body_size: http_max_body_size,
+            max_block_body_size: http_max_block_body_size,
+            rate_limit: http_rate_limit.map(|requests_per_second| RateLimitConfig {
+                requests_per_second,
+                burst: http_rate_limit_burst.unwrap_or(requests_per_second),
+                exempt_addresses: http_rate_limit_exempt,
+                limit_per_token: http_rate_limit_per_token,
+            }),
+            tls: http_tls_cert
+                .zip(http_tls_key)
+                .map(|(certificate_path, private_key_path)| TlsConfig {
+                    certificate_path,
+                    private_key_path,
+                }),
+            listen_on_tcp: http_unix_socket_only.not(),
+            unix_socket: http_unix_socket.map(|path| UnixSocketConfig {
+                path,
+                mode: http_unix_socket_mode,
+            }),
             ..Self::with_address(http_address, http_port)
         };
 
-        if !http_allowed_origins.is_empty() {
-            // `tower_http::cors::AllowOrigin::list` panics if a wildcard is passed to it.
-            if http_allowed_origins.contains(&HeaderValue::from_static("*")) {
-                if http_allowed_origins.len() > 1 {
-                    warn!(
-                        "extra values of Access-Control-Allow-Origin specified along with a wildcard; \
-                        only the wildcard will be used",
-                    );
-                }
+        if !http_allowed_methods.is_empty() {
+            http_api_config.allow_methods = AllowMethods::list(http_allowed_methods);
+        }
 
-                http_api_config.allow_origin = AllowOrigin::any();
-            } else {
-                http_api_config.allow_origin = AllowOrigin::list(http_allowed_origins);
-            }
+        if !http_allowed_origins.is_empty() {
+            http_api_config.allow_origin = allow_origin(http_allowed_origins);
         }
 
-        http_api_config
+        Ok(http_api_config)
     }
 }
 
@@ -288,6 +465,14 @@ struct BeaconNodeOptions {
     #[clap(long, default_value_t = StoreConfig::default().unfinalized_states_in_memory)]
     unfinalized_states_in_memory: u64,
 
+    /// Number of checkpoint states to keep in memory for attestation verification.
+    #[clap(long, default_value_t = StoreConfig::default().max_checkpoint_states)]
+    max_checkpoint_states: usize,
+
+    /// Log chain reorganizations deeper than this many slots as warnings
+    #[clap(long, default_value_t = StoreConfig::default().reorg_warning_depth)]
+    reorg_warning_depth: u64,
+
     /// Max size of the Eth2 database
     #[clap(long, default_value_t = DEFAULT_ETH2_DB_SIZE)]
     database_size: ByteSize,
@@ -314,6 +499,11 @@ struct BeaconNodeOptions {
     #[clap(long)]
     subscribe_all_subnets: bool,
 
+    /// Time in milliseconds a subscribed subnet may have too few peers
+    /// before more peers are discovered for it
+    #[clap(long, default_value_t = DEFAULT_SUBNET_PEER_DISCOVERY_DELAY)]
+    subnet_peer_discovery_delay: u64,
+
     /// Suggested value for the feeRecipient field of the new payload
     #[clap(long, value_name = "EXECUTION_ADDRESS")]
     suggested_fee_recipient: Option<ExecutionAddress>,
@@ -349,6 +539,11 @@ struct BeaconNodeOptions {
     #[clap(long, default_value_t = DEFAULT_METRICS_PORT)]
     metrics_port: u16,
 
+    /// List of Access-Control-Allow-Origin header values for the metrics server
+    /// [default: *]
+    #[clap(long, alias = "metrics-allow-origin")]
+    metrics_allowed_origins: Vec<HeaderValue>,
+
     /// Optional remote metrics URL that Grandine will periodically send metrics to
     #[clap(long)]
     remote_metrics_url: Option<Url>,
@@ -358,6 +553,23 @@ struct BeaconNodeOptions {
     #[clap(long)]
     track_liveness: bool,
 
+    /// Time in milliseconds attestation packing may spend optimizing
+    /// the attestations included in a block
+    #[clap(long, default_value_t = BeaconNodeOptions::default_attestation_packing_time_budget())]
+    attestation_packing_time_budget: u64,
+
+    /// Maximum number of aggregates kept in the attestation pool per attestation data
+    #[clap(long, default_value_t = DEFAULT_MAX_AGGREGATES_PER_ATTESTATION_DATA)]
+    max_aggregates_per_attestation_data: usize,
+
+    /// Maximum number of aggregates kept in the sync committee pool per subcommittee and block
+    #[clap(long, default_value_t = DEFAULT_MAX_AGGREGATES_PER_CONTRIBUTION_DATA)]
+    max_aggregates_per_contribution_data: usize,
+
+    /// Maximum number of BLS to execution changes kept in the pool
+    #[clap(long, default_value_t = DEFAULT_MAX_BLS_TO_EXECUTION_CHANGES)]
+    max_bls_to_execution_changes: usize,
+
     /// Enable in-memory mode.
     /// No data will be stored in data-dir.
     /// [default: disabled]
@@ -365,6 +577,16 @@ struct BeaconNodeOptions {
     in_memory: bool,
 }
 
+impl BeaconNodeOptions {
+    // See `HttpApiOptions::default_timeout`.
+    fn default_attestation_packing_time_budget() -> u64 {
+        DEFAULT_ATTESTATION_PACKING_TIME_BUDGET
+            .as_millis()
+            .try_into()
+            .expect("default attestation packing time budget in milliseconds should fit in u64")
+    }
+}
+
 // False positive. The `bool`s are independent.
 #[allow(clippy::struct_excessive_bools)]
 #[derive(Args)]
@@ -665,6 +887,14 @@ struct ValidatorOptions {
     )]
     keystore_password_file: Option<PathBuf>,
 
+    /// Prompt for a password for keystore files at startup
+    #[clap(
+        long,
+        requires("keystore_dir"),
+        conflicts_with_all(["keystore_password_file", "keystore_password_dir"])
+    )]
+    keystore_password_prompt: bool,
+
     /// Path to a file containing password for decrypting imported keystores from API
     #[clap(long)]
     keystore_storage_password_file: Option<PathBuf>,
@@ -673,9 +903,20 @@ struct ValidatorOptions {
     #[clap(long)]
     builder_api_url: Option<Url>,
 
-    /// External block builder URL
+    /// List of external block builder URLs.
+    /// Bids are requested from all of them and the most valuable one is used
+    #[clap(long, num_args = 1..)]
+    builder_url: Vec<Url>,
+
+    /// Path to a YAML file mapping external block builder URLs to public keys of validators
+    /// allowed to use them. Builders passed with --builder-url are used for all validators
     #[clap(long)]
-    builder_url: Option<Url>,
+    builder_relays_file: Option<PathBuf>,
+
+    /// Percentage to multiply external block builder bids by before comparing them to local payload values.
+    /// 0 always uses local payloads, 100 compares values as they are
+    #[clap(long, default_value_t = DEFAULT_BUILDER_BOOST_FACTOR)]
+    builder_boost_factor: u64,
 
     /// Always use specified external block builder without checking for circuit breaker conditions
     #[clap(long)]
@@ -689,6 +930,10 @@ struct ValidatorOptions {
     #[clap(long, default_value_t = DEFAULT_BUILDER_MAX_SKIPPED_SLOTS_PER_EPOCH)]
     builder_max_skipped_slots_per_epoch: u64,
 
+    /// Minimum external block builder bid in Wei to use instead of a local payload
+    #[clap(long)]
+    min_builder_bid: Option<Wei>,
+
     /// List of public keys to use from Web3Signer
     #[clap(long, num_args = 1..)]
     web3signer_public_keys: Vec<PublicKeyBytes>,
@@ -701,13 +946,106 @@ struct ValidatorOptions {
     #[clap(long, num_args = 1..)]
     web3signer_urls: Vec<Url>,
 
+    /// Path to a PEM file with CA certificates to trust when connecting to Web3Signer
+    #[clap(long)]
+    web3signer_ca_certificate_file: Option<PathBuf>,
+
+    /// Path to a PKCS#12 file with a client certificate and key for Web3Signer mutual TLS
+    #[clap(
+        long,
+        conflicts_with_all(["web3signer_client_certificate_file", "web3signer_client_key_file"])
+    )]
+    web3signer_client_identity_file: Option<PathBuf>,
+
+    /// Path to a file containing password for the Web3Signer PKCS#12 client identity file
+    #[clap(long, requires("web3signer_client_identity_file"))]
+    web3signer_client_identity_password_file: Option<PathBuf>,
+
+    /// Path to a PEM file with a client certificate for Web3Signer mutual TLS
+    #[clap(long, requires("web3signer_client_key_file"))]
+    web3signer_client_certificate_file: Option<PathBuf>,
+
+    /// Path to a PEM file with a PKCS#8 client key for Web3Signer mutual TLS
+    #[clap(long, requires("web3signer_client_certificate_file"))]
+    web3signer_client_key_file: Option<PathBuf>,
+
+    /// Maximum number of signing requests sent to Web3Signer at once
+    #[clap(long, default_value_t = Web3SignerConfig::default().max_concurrent_requests)]
+    web3signer_max_concurrent_requests: NonZeroUsize,
+
+    /// Forward keystore operations of the Keymanager API to the keymanager APIs of the Web3Signer
+    /// instances passed with --web3signer-urls. Listings are merged and imported keystores are
+    /// spread across the instances
+    #[clap(long, requires("web3signer_urls"))]
+    keymanager_web3signer_proxy: bool,
+
     /// Use validator key cache for faster startup
     #[clap(long)]
     use_validator_key_cache: bool,
 
+    /// URL of distributed validator middleware (Charon, SSV, Diva) to use with key shares.
+    /// Aggregation duties are decided using selection proofs combined by the middleware
+    #[clap(long)]
+    distributed_middleware_url: Option<Url>,
+
     /// Number of epochs to keep slashing protection data for
     #[clap(long, default_value_t = DEFAULT_SLASHING_PROTECTION_HISTORY_LIMIT)]
     slashing_protection_history_limit: u64,
+
+    /// How long in milliseconds before a local proposal slot to start building an execution payload.
+    /// Values longer than a slot are treated as one slot
+    #[clap(long, default_value_t = ValidatorOptions::default_prepare_payload_lookahead())]
+    prepare_payload_lookahead: u64,
+
+    /// Refuse to propose blocks with execution payloads paying a fee recipient other than
+    /// the configured one instead of only warning about them.
+    /// Builders may pay proposers with a transaction and set their own fee recipient in payloads,
+    /// so this makes most builder bids unusable
+    #[clap(long)]
+    strict_fee_recipient: bool,
+
+    /// Send validator gas limits to the execution client in a nonstandard `gasLimit` field of
+    /// payload attributes. Only enable this if the execution client accepts the field
+    #[clap(long)]
+    payload_attributes_gas_limit: bool,
+
+    /// Time in milliseconds after aggregates are due (2/3 of a slot) to publish own attestations
+    /// again if they have not been seen in any aggregate by then.
+    /// Attestations are not published again if this is not set
+    #[clap(long)]
+    attestation_rebroadcast_delay: Option<u64>,
+
+    /// Publish blocks even if another block from the same proposer in the same slot has already
+    /// been seen. Applies to both own blocks and blocks submitted through the HTTP API
+    #[clap(long)]
+    disable_block_equivocation_check: bool,
+
+    /// Sign attestations even if an attestation from the same validator for the same target epoch
+    /// has already been seen on an attestation subnet
+    #[clap(long)]
+    disable_attestation_equivocation_check: bool,
+
+    /// Refuse to propose blocks while arrival times of recent blocks show that the local clock
+    /// is off by more than a third of a slot. Blocks proposed then are likely to be ignored or
+    /// orphaned, but may still become canonical if the estimate is wrong
+    #[clap(long)]
+    refuse_proposals_on_clock_skew: bool,
+
+    /// List of validator indices whose positions in the withdrawal sweep are tracked in metrics
+    /// and reported by /grandine/validator/withdrawal_sweep
+    #[clap(long, num_args = 1..)]
+    withdrawal_sweep_validator_indices: Vec<ValidatorIndex>,
+}
+
+impl ValidatorOptions {
+    // See `HttpApiOptions::default_timeout`.
+    fn default_prepare_payload_lookahead() -> u64 {
+        ValidatorConfig::default()
+            .prepare_payload_lookahead
+            .as_millis()
+            .try_into()
+            .expect("default payload preparation lookahead in milliseconds should fit in u64")
+    }
 }
 
 #[derive(Clone, Copy, Sequence, ValueEnum)]
@@ -778,6 +1116,7 @@ impl GrandineArgs {
             mut deposit_contract_starting_block,
             mut genesis_state_file,
             genesis_state_download_url,
+            genesis_state_root,
         } = chain_options;
 
         let BeaconNodeOptions {
@@ -793,10 +1132,13 @@ impl GrandineArgs {
             archival_epoch_interval,
             prune_storage,
             unfinalized_states_in_memory,
+            max_checkpoint_states,
+            reorg_warning_depth,
             request_timeout,
             state_slot,
             disable_block_verification_pool,
             subscribe_all_subnets,
+            subnet_peer_discovery_delay,
             suggested_fee_recipient,
             jwt_id,
             jwt_secret,
@@ -805,8 +1147,13 @@ impl GrandineArgs {
             metrics,
             metrics_address,
             metrics_port,
+            metrics_allowed_origins,
             remote_metrics_url,
             track_liveness,
+            attestation_packing_time_budget,
+            max_aggregates_per_attestation_data,
+            max_aggregates_per_contribution_data,
+            max_bls_to_execution_changes,
             in_memory,
         } = beacon_node_options;
 
@@ -822,17 +1169,37 @@ impl GrandineArgs {
             keystore_dir,
             keystore_password_dir,
             keystore_password_file,
+            keystore_password_prompt,
             keystore_storage_password_file,
             builder_api_url,
             builder_url,
+            builder_relays_file,
+            builder_boost_factor,
             builder_disable_checks,
             builder_max_skipped_slots,
             builder_max_skipped_slots_per_epoch,
+            min_builder_bid,
             use_validator_key_cache,
             web3signer_public_keys,
             web3signer_api_urls,
             web3signer_urls,
+            web3signer_ca_certificate_file,
+            web3signer_client_identity_file,
+            web3signer_client_identity_password_file,
+            web3signer_client_certificate_file,
+            web3signer_client_key_file,
+            web3signer_max_concurrent_requests,
+            keymanager_web3signer_proxy,
+            distributed_middleware_url,
             slashing_protection_history_limit,
+            prepare_payload_lookahead,
+            strict_fee_recipient,
+            payload_attributes_gas_limit,
+            attestation_rebroadcast_delay,
+            disable_block_equivocation_check,
+            disable_attestation_equivocation_check,
+            refuse_proposals_on_clock_skew,
+            withdrawal_sweep_validator_indices,
         } = validator_options;
 
         if in_memory {
@@ -872,26 +1239,43 @@ impl GrandineArgs {
                     );
                     Some(number)
                 }
-                None => {
-                    let bytes = fs_err::read(directory.join(DEPOSIT_CONTRACT_BLOCK_FILE))?;
-                    Some(serde_yaml::from_slice(bytes.as_slice())?)
-                }
+                None => match config_dir::deposit_contract_block_file(&directory) {
+                    Some(path) => {
+                        let bytes = fs_err::read(path)?;
+                        Some(serde_yaml::from_slice(bytes.as_slice())?)
+                    }
+                    None => None,
+                },
             };
 
-            genesis_state_file = genesis_state_file
-                .inspect(|_| {
-                    warn!(
-                        "both --configuration-directory and --genesis-state-file specified; \
-                         --genesis-state-file will take precedence",
-                    );
-                })
-                .or_else(|| Some(directory.join(GENESIS_STATE_FILE)));
+            if genesis_state_file.is_some() {
+                warn!(
+                    "both --configuration-directory and --genesis-state-file specified; \
+                     --genesis-state-file will take precedence",
+                );
+            } else if genesis_state_download_url.is_none() {
+                let path = directory.join(GENESIS_STATE_FILE);
+
+                ensure!(
+                    path.is_file(),
+                    Error::MissingGenesisStateInConfigurationDirectory { path },
+                );
+
+                genesis_state_file = Some(path);
+            }
 
             if network_config_options.boot_nodes.is_empty() {
-                let bytes = fs_err::read_to_string(directory.join(PLAIN_BOOTNODES_FILE))?;
+                if let Some(path) = config_dir::bootnodes_file(&directory) {
+                    let string = fs_err::read_to_string(&path)?;
 
-                network_config_options.boot_nodes =
-                    config_dir::parse_plain_bootnodes(bytes.as_str())?;
+                    network_config_options.boot_nodes =
+                        config_dir::parse_bootnodes_file(&path, string.as_str())?;
+                } else {
+                    warn!(
+                        "no bootnodes file found in {}; specify --boot-nodes to discover peers",
+                        directory.display(),
+                    );
+                }
             } else {
                 warn!(
                     "both --configuration-directory and --boot-nodes specified; \
@@ -904,7 +1288,7 @@ impl GrandineArgs {
 
         if predefined_network.is_none() && eth1_rpc_urls.is_empty() {
             ensure!(
-                genesis_state_file.is_some(),
+                genesis_state_file.is_some() || genesis_state_download_url.is_some(),
                 Error::MissingEth1RpcUrlsForCustomWithoutGenesisState,
             );
         }
@@ -1013,14 +1397,20 @@ impl GrandineArgs {
         let metrics_server_config = metrics.then_some(MetricsServerConfig {
             metrics_address,
             metrics_port,
+            allow_origin: if metrics_allowed_origins.is_empty() {
+                AllowOrigin::any()
+            } else {
+                allow_origin(metrics_allowed_origins)
+            },
             timeout: request_timeout,
             directories: directories.clone_arc(),
         });
 
-        let http_api_config = HttpApiConfig::from(http_api_options);
+        let http_api_config = HttpApiConfig::try_from(http_api_options)?;
         if let Some(metrics_server_config) = metrics_server_config.as_ref() {
             ensure!(
-                http_api_config.address != metrics_server_config.into(),
+                http_api_config.listen_on_tcp.not()
+                    || http_api_config.address != metrics_server_config.into(),
                 Error::IdenticalHttpApiAndMetricsUrl,
             );
         }
@@ -1040,15 +1430,19 @@ impl GrandineArgs {
             metrics_service_config,
         };
 
-        let validators = keystore_dir
-            .zip(keystore_password_file.or(keystore_password_dir))
-            .map(
-                |(keystore_dir, keystore_password_file)| Validators::KeystoreDirectory {
-                    keystore_dir,
-                    keystore_password_file,
-                },
-            )
-            .unwrap_or_default();
+        let validators = match (
+            keystore_dir,
+            keystore_password_file.or(keystore_password_dir),
+        ) {
+            (Some(keystore_dir), _) if keystore_password_prompt => {
+                Validators::KeystoreDirectoryWithPasswordPrompt { keystore_dir }
+            }
+            (Some(keystore_dir), Some(keystore_password_file)) => Validators::KeystoreDirectory {
+                keystore_dir,
+                keystore_password_file,
+            },
+            _ => Validators::default(),
+        };
 
         let minimum = StoreConfig::min_unfinalized_states_in_memory(&chain_config);
 
@@ -1070,18 +1464,42 @@ impl GrandineArgs {
             version: jwt_version,
         };
 
-        let builder_url = if builder_url.is_none() && builder_api_url.is_some() {
+        let builder_urls = if builder_url.is_empty() && builder_api_url.is_some() {
             warn!("--builder-api-url option is deprecated. Use --builder-url instead.");
-            builder_api_url
+            builder_api_url.into_iter().collect()
         } else {
             builder_url
         };
 
-        let builder_config = builder_url.map(|url| BuilderConfig {
-            builder_api_url: url,
+        let mut relays = builder_urls
+            .into_iter()
+            .map(RelayConfig::for_all_validators)
+            .collect_vec();
+
+        if let Some(path) = builder_relays_file {
+            let bytes = fs_err::read(path)?;
+            let enabled_validators =
+                serde_yaml::from_slice::<BTreeMap<String, HashSet<PublicKeyBytes>>>(&bytes)?;
+
+            for (url, validators) in enabled_validators {
+                let url = Url::parse(&url)?;
+
+                relays.retain(|relay| relay.url != url);
+
+                relays.push(RelayConfig {
+                    url,
+                    enabled_validators: Some(validators),
+                });
+            }
+        }
+
+        let builder_config = (!relays.is_empty()).then_some(BuilderConfig {
+            relays,
+            builder_boost_factor,
             builder_disable_checks,
             builder_max_skipped_slots,
             builder_max_skipped_slots_per_epoch,
+            min_builder_bid,
         });
 
         let web3signer_urls = if web3signer_urls.is_empty() && !web3signer_api_urls.is_empty() {
@@ -1091,9 +1509,30 @@ impl GrandineArgs {
             web3signer_urls
         };
 
+        let client_identity = match (
+            web3signer_client_identity_file,
+            web3signer_client_certificate_file,
+            web3signer_client_key_file,
+        ) {
+            (Some(identity_file), _, _) => Some(Web3SignerClientIdentity::Pkcs12 {
+                identity_file,
+                password_file: web3signer_client_identity_password_file,
+            }),
+            (None, Some(certificate_file), Some(key_file)) => Some(Web3SignerClientIdentity::Pem {
+                certificate_file,
+                key_file,
+            }),
+            _ => None,
+        };
+
         let web3signer_config = Web3SignerConfig {
             public_keys: web3signer_public_keys.into_iter().collect(),
             urls: web3signer_urls,
+            tls: Web3SignerTlsConfig {
+                ca_certificate_file: web3signer_ca_certificate_file,
+                client_identity,
+            },
+            max_concurrent_requests: web3signer_max_concurrent_requests,
         };
 
         let storage_config = StorageConfig {
@@ -1113,6 +1552,7 @@ impl GrandineArgs {
             deposit_contract_starting_block,
             genesis_state_file,
             genesis_state_download_url,
+            genesis_state_root,
             checkpoint_sync_url,
             force_checkpoint_sync,
             back_sync,
@@ -1131,6 +1571,8 @@ impl GrandineArgs {
             ),
             storage_config,
             unfinalized_states_in_memory,
+            max_checkpoint_states,
+            reorg_warning_depth,
             request_timeout: Duration::from_millis(request_timeout),
             command,
             slashing_enabled,
@@ -1143,12 +1585,48 @@ impl GrandineArgs {
             http_api_config,
             metrics_config,
             track_liveness,
+            subnet_peer_discovery_delay: Duration::from_millis(subnet_peer_discovery_delay),
+            pool_config: PoolConfig {
+                attestation_packing_time_budget: Duration::from_millis(
+                    attestation_packing_time_budget,
+                ),
+                max_aggregates_per_attestation_data,
+                max_aggregates_per_contribution_data,
+                max_bls_to_execution_changes,
+            },
             use_validator_key_cache,
+            distributed_middleware_url,
             slashing_protection_history_limit,
+            prepare_payload_lookahead: Duration::from_millis(prepare_payload_lookahead),
+            attestation_rebroadcast_delay: attestation_rebroadcast_delay.map(Duration::from_millis),
+            strict_fee_recipient,
+            payload_attributes_gas_limit,
+            block_equivocation_check: !disable_block_equivocation_check,
+            attestation_equivocation_check: !disable_attestation_equivocation_check,
+            refuse_proposals_on_clock_skew,
+            withdrawal_sweep_validator_indices,
+            keymanager_web3signer_proxy,
             in_memory,
         })
     }
 
+    // Options from `--config-file` and environment variables are merged into the arguments.
+    // See the `options_file` module for the order of precedence.
+    pub fn try_parse_with_options_file() -> Result<Self> {
+        let environment_variables = std::env::vars_os().filter_map(|(variable, value)| {
+            Some((variable.into_string().ok()?, value.into_string().ok()?))
+        });
+
+        let arguments = options_file::merge_arguments(
+            &Self::command(),
+            std::env::args_os(),
+            environment_variables,
+        )
+        .map_err(Self::clap_error)?;
+
+        Self::try_parse_from(arguments).map_err(Into::into)
+    }
+
     #[must_use]
     pub fn clap_error(message: impl Display) -> ClapError {
         Self::command().error(ErrorKind::ValueValidation, message)
@@ -1171,10 +1649,16 @@ enum Error {
     #[error("--configuration-file must be specified when connecting to custom network")]
     MissingConfigurationFileForCustom,
     #[error(
-        "--eth1-rpc-urls must be specified when connecting \
-         to custom network without --genesis-state-file"
+        "--eth1-rpc-urls must be specified when connecting to custom network \
+         without --genesis-state-file or --genesis-state-download-url"
     )]
     MissingEth1RpcUrlsForCustomWithoutGenesisState,
+    #[error(
+        "genesis state not found at {}; \
+         specify --genesis-state-file or --genesis-state-download-url instead",
+        path.display(),
+    )]
+    MissingGenesisStateInConfigurationDirectory { path: PathBuf },
     #[error(
         "{phase} variables in {preset_name} preset do not match file ({})",
         differences.iter().format(", "),
@@ -1193,6 +1677,26 @@ enum Error {
     UnfinalizedStatesInMemoryTooLow { minimum: u64 },
     #[error("identical addresses specified for metrics server and HTTP API server")]
     IdenticalHttpApiAndMetricsUrl,
+    #[error("--http-admin-token-file must not be empty")]
+    EmptyHttpAdminToken,
+    #[error("file mode must be an octal number no greater than 777")]
+    InvalidFileMode,
+}
+
+fn allow_origin(allowed_origins: Vec<HeaderValue>) -> AllowOrigin {
+    // `tower_http::cors::AllowOrigin::list` panics if a wildcard is passed to it.
+    if allowed_origins.contains(&HeaderValue::from_static("*")) {
+        if allowed_origins.len() > 1 {
+            warn!(
+                "extra values of Access-Control-Allow-Origin specified along with a wildcard; \
+                 only the wildcard will be used",
+            );
+        }
+
+        AllowOrigin::any()
+    } else {
+        AllowOrigin::list(allowed_origins)
+    }
 }
 
 fn parse_graffiti(string: &str) -> Result<H256> {
@@ -1204,6 +1708,12 @@ fn parse_graffiti(string: &str) -> Result<H256> {
     Ok(graffiti)
 }
 
+fn parse_file_mode(string: &str) -> Result<u32> {
+    let mode = u32::from_str_radix(string, 8).map_err(|_| Error::InvalidFileMode)?;
+    ensure!(mode <= 0o777, Error::InvalidFileMode);
+    Ok(mode)
+}
+
 fn verify_preset<T: DeserializeOwned + Serialize>(
     chain_config: &ChainConfig,
     preset: &T,
@@ -1303,11 +1813,28 @@ fn compare_with_file<T: DeserializeOwned + Serialize>(
 
 #[cfg(test)]
 mod tests {
-    use std::net::{Ipv4Addr, SocketAddr};
+    use std::{
+        io::Write as _,
+        net::{Ipv4Addr, SocketAddr},
+    };
 
+    use axum::{
+        body::Body,
+        http::{
+            header::{
+                ACCESS_CONTROL_ALLOW_METHODS, ACCESS_CONTROL_ALLOW_ORIGIN,
+                ACCESS_CONTROL_REQUEST_METHOD, ORIGIN,
+            },
+            Request,
+        },
+        response::Response,
+        routing::get,
+        Router,
+    };
     use tempfile::NamedTempFile;
+    use tower::Service as _;
 
-    use crate::commands::InterchangeCommand;
+    use crate::commands::{InterchangeCommand, ValidatorCommand};
 
     use super::*;
 
@@ -1478,6 +2005,70 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn http_allowed_methods_option() -> Result<()> {
+        let config = config_from_args([
+            "--http-allowed-origins",
+            "http://localhost:3000",
+            "--http-allowed-methods",
+            "GET",
+            "--http-allowed-methods",
+            "POST",
+        ]);
+
+        let response = preflight_response(
+            config.http_api_config.allow_origin,
+            config.http_api_config.allow_methods,
+            "http://localhost:3000",
+        )
+        .await?;
+
+        assert_eq!(
+            response.headers()[ACCESS_CONTROL_ALLOW_ORIGIN],
+            "http://localhost:3000",
+        );
+        assert_eq!(response.headers()[ACCESS_CONTROL_ALLOW_METHODS], "GET,POST");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn metrics_allowed_origins_option() -> Result<()> {
+        let config = config_from_args([
+            "--metrics",
+            "--metrics-allowed-origins",
+            "http://localhost:3000",
+        ]);
+
+        let metrics_server_config = config
+            .metrics_config
+            .metrics_server_config
+            .expect("metrics server should be enabled");
+
+        let response = preflight_response(
+            metrics_server_config.allow_origin.clone(),
+            AllowMethods::default(),
+            "http://localhost:3000",
+        )
+        .await?;
+
+        assert_eq!(
+            response.headers()[ACCESS_CONTROL_ALLOW_ORIGIN],
+            "http://localhost:3000",
+        );
+
+        let response = preflight_response(
+            metrics_server_config.allow_origin,
+            AllowMethods::default(),
+            "http://example.com",
+        )
+        .await?;
+
+        assert!(!response.headers().contains_key(ACCESS_CONTROL_ALLOW_ORIGIN));
+
+        Ok(())
+    }
+
     #[test]
     fn http_allowed_origins_option_multiple_occurences_including_wildcard() {
         let config = config_from_args([
@@ -1496,6 +2087,219 @@ mod tests {
         );
     }
 
+    #[test]
+    fn http_compression_options() {
+        assert_eq!(
+            config_from_args([]).http_api_config.compression_threshold,
+            Some(1024),
+        );
+
+        assert_eq!(
+            config_from_args(["--http-compression-threshold", "256"])
+                .http_api_config
+                .compression_threshold,
+            Some(256),
+        );
+
+        assert_eq!(
+            config_from_args(["--disable-http-compression"])
+                .http_api_config
+                .compression_threshold,
+            None,
+        );
+    }
+
+    #[test]
+    fn http_max_body_size_options() {
+        let config = config_from_args([]);
+
+        assert_eq!(config.http_api_config.max_body_size, 2 * 1024 * 1024);
+        assert_eq!(config.http_api_config.max_block_body_size, 32 * 1024 * 1024);
+
+        let config = config_from_args([
+            "--http-max-body-size",
+            "1000",
+            "--http-max-block-body-size",
+            "2000",
+        ]);
+
+        assert_eq!(config.http_api_config.max_body_size, 1000);
+        assert_eq!(config.http_api_config.max_block_body_size, 2000);
+    }
+
+    #[test]
+    fn http_event_replay_buffer_size_option() {
+        assert_eq!(
+            config_from_args([])
+                .http_api_config
+                .event_replay_buffer_size,
+            64,
+        );
+
+        assert_eq!(
+            config_from_args(["--http-event-replay-buffer-size", "0"])
+                .http_api_config
+                .event_replay_buffer_size,
+            0,
+        );
+    }
+
+    #[test]
+    fn http_costly_request_queue_options() {
+        let request_queue = config_from_args([]).http_api_config.costly_request_queue;
+
+        assert_eq!(request_queue.max_concurrent_requests.get(), 4);
+        assert_eq!(request_queue.max_queued_requests, 128);
+
+        let request_queue = config_from_args([
+            "--http-costly-max-concurrent-requests",
+            "2",
+            "--http-costly-max-queued-requests",
+            "0",
+        ])
+        .http_api_config
+        .costly_request_queue;
+
+        assert_eq!(request_queue.max_concurrent_requests.get(), 2);
+        assert_eq!(request_queue.max_queued_requests, 0);
+    }
+
+    #[test]
+    fn http_rate_limit_options() {
+        assert!(config_from_args([]).http_api_config.rate_limit.is_none());
+
+        let rate_limit = config_from_args(["--http-rate-limit", "10"])
+            .http_api_config
+            .rate_limit
+            .expect("--http-rate-limit should enable rate limiting");
+
+        assert_eq!(rate_limit.requests_per_second.get(), 10);
+        assert_eq!(rate_limit.burst.get(), 10);
+        assert_eq!(
+            rate_limit.exempt_addresses,
+            RateLimitConfig::default_exempt_addresses(),
+        );
+        assert!(!rate_limit.limit_per_token);
+
+        let rate_limit = config_from_args([
+            "--http-rate-limit",
+            "10",
+            "--http-rate-limit-burst",
+            "50",
+            "--http-rate-limit-exempt",
+            "10.0.0.1",
+            "--http-rate-limit-exempt",
+            "10.0.0.2",
+            "--http-rate-limit-per-token",
+        ])
+        .http_api_config
+        .rate_limit
+        .expect("--http-rate-limit should enable rate limiting");
+
+        assert_eq!(rate_limit.burst.get(), 50);
+        assert_eq!(
+            rate_limit.exempt_addresses,
+            [
+                IpAddr::from(Ipv4Addr::new(10, 0, 0, 1)),
+                IpAddr::from(Ipv4Addr::new(10, 0, 0, 2)),
+            ],
+        );
+        assert!(rate_limit.limit_per_token);
+    }
+
+    #[test]
+    fn http_tls_options() {
+        assert!(config_from_args([]).http_api_config.tls.is_none());
+
+        let config = config_from_args(["--http-tls-cert", "cert.pem", "--http-tls-key", "key.pem"]);
+
+        let tls = config
+            .http_api_config
+            .tls
+            .expect("TLS should be enabled when both options are specified");
+
+        assert_eq!(tls.certificate_path, PathBuf::from("cert.pem"));
+        assert_eq!(tls.private_key_path, PathBuf::from("key.pem"));
+
+        // Enabling TLS should not change the allowed origins.
+        // `Debug` is the only way to inspect the contents of `AllowOrigin`.
+        assert_eq!(
+            format!("{:?}", config.http_api_config.allow_origin),
+            "List([\"http://127.0.0.1:5052\"])",
+        );
+
+        try_config_from_args(["--http-tls-cert", "cert.pem"])
+            .expect_err("--http-tls-cert should require --http-tls-key");
+    }
+
+    #[test]
+    fn http_unix_socket_options() {
+        let config = config_from_args([]);
+
+        assert!(config.http_api_config.listen_on_tcp);
+        assert!(config.http_api_config.unix_socket.is_none());
+
+        let config = config_from_args([
+            "--http-unix-socket",
+            "grandine.sock",
+            "--http-unix-socket-mode",
+            "600",
+            "--http-unix-socket-only",
+        ]);
+
+        let unix_socket = config
+            .http_api_config
+            .unix_socket
+            .expect("--http-unix-socket should enable the Unix socket");
+
+        assert!(!config.http_api_config.listen_on_tcp);
+        assert_eq!(unix_socket.path, PathBuf::from("grandine.sock"));
+        assert_eq!(unix_socket.mode, 0o600);
+
+        assert_eq!(
+            config_from_args(["--http-unix-socket", "grandine.sock"])
+                .http_api_config
+                .unix_socket
+                .map(|unix_socket| unix_socket.mode),
+            Some(0o660),
+        );
+
+        try_config_from_args(["--http-unix-socket-only"])
+            .expect_err("--http-unix-socket-only should require --http-unix-socket");
+
+        try_config_from_args([
+            "--http-unix-socket",
+            "grandine.sock",
+            "--http-unix-socket-mode",
+            "888",
+        ])
+        .expect_err("file mode should be octal");
+    }
+
+    #[test]
+    fn http_admin_token_file_option() -> Result<()> {
+        assert!(config_from_args([]).http_api_config.admin_token.is_none());
+
+        let mut token_file = NamedTempFile::new()?;
+
+        let token_path = token_file
+            .path()
+            .to_str()
+            .expect("temporary file path should be a valid UTF-8 string")
+            .to_owned();
+
+        try_config_from_args(["--http-admin-token-file", token_path.as_str()])
+            .expect_err("empty admin token should be rejected");
+
+        writeln!(token_file, "secret")?;
+
+        let config = config_from_args(["--http-admin-token-file", token_path.as_str()]);
+
+        assert!(config.http_api_config.admin_token.is_some());
+
+        Ok(())
+    }
+
     #[test]
     fn validators_from_keystore_password_file() {
         let config = config_from_args([
@@ -1532,6 +2336,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn validators_from_keystore_password_prompt() {
+        let config =
+            config_from_args(["--keystore-dir", "dir_value", "--keystore-password-prompt"]);
+
+        assert_eq!(
+            config.validators,
+            Validators::KeystoreDirectoryWithPasswordPrompt {
+                keystore_dir: PathBuf::from("dir_value"),
+            },
+        );
+    }
+
     #[test]
     fn validators_from_keystore_password_dir_and_file() {
         try_config_from_args([
@@ -1593,6 +2410,33 @@ mod tests {
         .expect_err("GrandineArgs::try_into_config should fail");
     }
 
+    #[test]
+    fn configuration_directory_without_genesis_state() -> Result<()> {
+        let directory = tempfile::tempdir()?;
+
+        fs_err::write(directory.path().join(CONFIG_FILE), "")?;
+
+        let directory = directory
+            .path()
+            .to_str()
+            .expect("temporary directory path should be a valid UTF-8 string");
+
+        let error = try_config_from_args([
+            "--network",
+            "custom",
+            "--configuration-directory",
+            directory,
+        ])
+        .expect_err("GrandineArgs::try_into_config should fail");
+
+        assert!(matches!(
+            error.downcast_ref::<Error>(),
+            Some(Error::MissingGenesisStateInConfigurationDirectory { .. }),
+        ));
+
+        Ok(())
+    }
+
     #[test]
     fn graffiti_option_single_value() {
         let config = config_from_args(["--graffiti", "**test-graffiti**"]);
@@ -1633,6 +2477,214 @@ mod tests {
         .expect_err("parse_graffiti should fail");
     }
 
+    #[test]
+    fn reorg_warning_depth_option() {
+        assert_eq!(
+            config_from_args([]).reorg_warning_depth,
+            StoreConfig::default().reorg_warning_depth,
+        );
+
+        assert_eq!(
+            config_from_args(["--reorg-warning-depth", "5"]).reorg_warning_depth,
+            5,
+        );
+    }
+
+    #[test]
+    fn strict_fee_recipient_option() {
+        assert!(!config_from_args([]).strict_fee_recipient);
+        assert!(config_from_args(["--strict-fee-recipient"]).strict_fee_recipient);
+    }
+
+    #[test]
+    fn attestation_rebroadcast_delay_option() {
+        assert_eq!(config_from_args([]).attestation_rebroadcast_delay, None);
+
+        assert_eq!(
+            config_from_args(["--attestation-rebroadcast-delay", "2000"])
+                .attestation_rebroadcast_delay,
+            Some(Duration::from_secs(2)),
+        );
+    }
+
+    #[test]
+    fn disable_block_equivocation_check_option() {
+        assert!(config_from_args([]).block_equivocation_check);
+
+        assert!(!config_from_args(["--disable-block-equivocation-check"]).block_equivocation_check);
+    }
+
+    #[test]
+    fn disable_attestation_equivocation_check_option() {
+        assert!(config_from_args([]).attestation_equivocation_check);
+
+        assert!(
+            !config_from_args(["--disable-attestation-equivocation-check"])
+                .attestation_equivocation_check,
+        );
+    }
+
+    #[test]
+    fn withdrawal_sweep_validator_indices_option() {
+        assert!(config_from_args([])
+            .withdrawal_sweep_validator_indices
+            .is_empty());
+
+        assert_eq!(
+            config_from_args(["--withdrawal-sweep-validator-indices", "3", "14", "159"])
+                .withdrawal_sweep_validator_indices,
+            [3, 14, 159],
+        );
+    }
+
+    #[test]
+    fn distributed_middleware_url_option() {
+        assert_eq!(config_from_args([]).distributed_middleware_url, None);
+
+        let config = config_from_args(["--distributed-middleware-url", "http://localhost:3600"]);
+
+        assert_eq!(
+            config.distributed_middleware_url.as_ref().map(Url::as_str),
+            Some("http://localhost:3600/"),
+        );
+    }
+
+    #[test]
+    fn web3signer_max_concurrent_requests_option() {
+        assert_eq!(
+            config_from_args([])
+                .web3signer_config
+                .max_concurrent_requests,
+            Web3SignerConfig::default().max_concurrent_requests,
+        );
+
+        assert_eq!(
+            config_from_args(["--web3signer-max-concurrent-requests", "8"])
+                .web3signer_config
+                .max_concurrent_requests
+                .get(),
+            8,
+        );
+    }
+
+    #[test]
+    fn keymanager_web3signer_proxy_option() {
+        assert!(!config_from_args([]).keymanager_web3signer_proxy);
+
+        assert!(
+            config_from_args([
+                "--web3signer-urls",
+                "http://localhost:9000",
+                "--keymanager-web3signer-proxy",
+            ])
+            .keymanager_web3signer_proxy
+        );
+
+        try_config_from_args(["--keymanager-web3signer-proxy"])
+            .expect_err("--keymanager-web3signer-proxy should require --web3signer-urls");
+    }
+
+    #[test]
+    fn refuse_proposals_on_clock_skew_option() {
+        assert!(!config_from_args([]).refuse_proposals_on_clock_skew);
+        assert!(
+            config_from_args(["--refuse-proposals-on-clock-skew"]).refuse_proposals_on_clock_skew
+        );
+    }
+
+    #[test]
+    fn payload_attributes_gas_limit_option() {
+        assert!(!config_from_args([]).payload_attributes_gas_limit);
+        assert!(config_from_args(["--payload-attributes-gas-limit"]).payload_attributes_gas_limit);
+    }
+
+    #[test]
+    fn multiple_builder_urls() {
+        let config = config_from_args([
+            "--builder-url",
+            "http://relay-1.test",
+            "http://relay-2.test",
+        ]);
+
+        let relay_urls = config
+            .builder_config
+            .expect("builder config should be present when builder URLs are passed")
+            .relays
+            .into_iter()
+            .map(|relay| relay.url.to_string())
+            .collect_vec();
+
+        assert_eq!(relay_urls, ["http://relay-1.test/", "http://relay-2.test/"]);
+    }
+
+    #[test]
+    fn builder_relays_file_option() -> Result<()> {
+        let pubkey = PublicKeyBytes::repeat_byte(1);
+        let mut relays_file = NamedTempFile::new()?;
+
+        writeln!(relays_file, "http://relay-2.test/:\n- '{pubkey:?}'")?;
+
+        let relays_path = relays_file
+            .path()
+            .to_str()
+            .expect("temporary file path should be a valid UTF-8 string")
+            .to_owned();
+
+        let relays = config_from_args([
+            "--builder-url",
+            "http://relay-1.test",
+            "http://relay-2.test",
+            "--builder-relays-file",
+            relays_path.as_str(),
+        ])
+        .builder_config
+        .expect("builder config should be present when builder URLs are passed")
+        .relays;
+
+        assert_eq!(relays.len(), 2);
+        assert!(relays
+            .iter()
+            .any(|relay| relay.url.as_str() == "http://relay-1.test/"
+                && relay.is_enabled_for(PublicKeyBytes::zero())));
+        assert!(relays
+            .iter()
+            .any(|relay| relay.url.as_str() == "http://relay-2.test/"
+                && relay.is_enabled_for(pubkey)
+                && !relay.is_enabled_for(PublicKeyBytes::zero())));
+
+        Ok(())
+    }
+
+    #[test]
+    fn prepare_payload_lookahead_option() {
+        assert_eq!(
+            config_from_args([]).prepare_payload_lookahead,
+            Duration::from_secs(12),
+        );
+
+        assert_eq!(
+            config_from_args(["--prepare-payload-lookahead", "4000"]).prepare_payload_lookahead,
+            Duration::from_secs(4),
+        );
+    }
+
+    #[test]
+    fn attestation_packing_time_budget_option() {
+        assert_eq!(
+            config_from_args([])
+                .pool_config
+                .attestation_packing_time_budget,
+            Duration::from_millis(250),
+        );
+
+        assert_eq!(
+            config_from_args(["--attestation-packing-time-budget", "100"])
+                .pool_config
+                .attestation_packing_time_budget,
+            Duration::from_millis(100),
+        );
+    }
+
     #[test]
     fn interchange_import_subcommand() {
         let config = config_from_args(["interchange", "import", "test.json"]);
@@ -1694,6 +2746,112 @@ mod tests {
         );
     }
 
+    #[test]
+    fn interop_genesis_subcommand() {
+        let config = config_from_args([
+            "interop-genesis",
+            "--genesis-time",
+            "1700000000",
+            "--validator-count",
+            "64",
+            "--output-dir",
+            "testnet",
+        ]);
+
+        assert_eq!(
+            config.command,
+            Some(GrandineCommand::InteropGenesis {
+                genesis_time: 1_700_000_000,
+                validator_count: NonZeroU64::new(64).expect("64 is not zero"),
+                output_dir: Some(PathBuf::from("testnet")),
+            }),
+        );
+    }
+
+    #[test]
+    fn validator_exit_subcommand() {
+        let public_key = PublicKeyBytes::repeat_byte(1);
+        let public_key_string = format!("{public_key:?}");
+
+        let config = config_from_args([
+            "validator",
+            "exit",
+            "--public-key",
+            public_key_string.as_str(),
+            "--validator-index",
+            "1234",
+            "--beacon-node-url",
+            "http://localhost:5052",
+        ]);
+
+        assert_eq!(
+            config.command,
+            Some(GrandineCommand::Validator(ValidatorCommand::Exit {
+                public_key,
+                validator_index: 1234,
+                epoch: None,
+                beacon_node_url: Some(
+                    "http://localhost:5052"
+                        .parse()
+                        .expect("URL should be valid"),
+                ),
+            })),
+        );
+    }
+
+    #[test]
+    fn validator_bls_to_execution_change_subcommand() {
+        let config = config_from_args([
+            "validator",
+            "bls-to-execution-change",
+            "--validator-indices",
+            "1234,1235",
+            "--account-indices",
+            "0,1",
+            "--to-execution-address",
+            "0x0101010101010101010101010101010101010101",
+            "--beacon-node-url",
+            "http://localhost:5052",
+        ]);
+
+        assert_eq!(
+            config.command,
+            Some(GrandineCommand::Validator(
+                ValidatorCommand::BlsToExecutionChange {
+                    validator_indices: vec![1234, 1235],
+                    account_indices: vec![0, 1],
+                    to_execution_address: ExecutionAddress::repeat_byte(1),
+                    beacon_node_url: "http://localhost:5052"
+                        .parse()
+                        .expect("URL should be valid"),
+                },
+            )),
+        );
+    }
+
+    #[test]
+    fn validator_generate_keys_subcommand() {
+        let config = config_from_args([
+            "validator",
+            "generate-keys",
+            "--count",
+            "4",
+            "--withdrawal-address",
+            "0x0101010101010101010101010101010101010101",
+        ]);
+
+        assert_eq!(
+            config.command,
+            Some(GrandineCommand::Validator(ValidatorCommand::GenerateKeys {
+                count: 4,
+                first_account_index: 0,
+                withdrawal_address: Some(ExecutionAddress::repeat_byte(1)),
+                existing_mnemonic: false,
+                output_dir: None,
+            })),
+        );
+    }
+
     fn config_from_args<'a>(arguments: impl IntoIterator<Item = &'a str>) -> GrandineConfig {
         try_config_from_args(arguments)
             .expect("GrandineArgs should be successfully parsed from arguments")
@@ -1705,4 +2863,29 @@ mod tests {
         GrandineArgs::try_parse_from(core::iter::once(APPLICATION_NAME).chain(arguments))?
             .try_into_config()
     }
+
+    // Send a preflight request through the same CORS middleware the servers use.
+    async fn preflight_response(
+        allowed_origins: AllowOrigin,
+        allowed_methods: AllowMethods,
+        origin: &'static str,
+    ) -> Result<Response> {
+        let router = Router::new().route("/", get(|| async {}).post(|| async {}));
+
+        let mut router = http_api_utils::extend_router_with_middleware(
+            router,
+            None,
+            allowed_origins,
+            allowed_methods,
+            None,
+            None,
+        );
+
+        let request = Request::options("/")
+            .header(ORIGIN, origin)
+            .header(ACCESS_CONTROL_REQUEST_METHOD, "POST")
+            .body(Body::empty())?;
+
+        router.call(request).await.map_err(Into::into)
+    }
 }
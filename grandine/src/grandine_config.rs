@@ -1,19 +1,20 @@
 use core::time::Duration;
 use std::{net::SocketAddr, path::PathBuf, sync::Arc};
 
-use builder_api::BuilderConfig;
+use builder_api::{BuilderConfig, RelayConfig};
 use eth1_api::AuthOptions;
 use features::Feature;
 use http_api::HttpApiConfig;
 use itertools::Itertools as _;
-use log::info;
+use log::{info, warn};
+use operation_pools::PoolConfig;
 use p2p::NetworkConfig;
 use reqwest::Url;
 use runtime::{MetricsConfig, StorageConfig};
 use signer::Web3SignerConfig;
 use types::{
     config::Config as ChainConfig,
-    phase0::primitives::{ExecutionAddress, ExecutionBlockNumber, Slot, H256},
+    phase0::primitives::{ExecutionAddress, ExecutionBlockNumber, Slot, ValidatorIndex, H256},
 };
 
 use crate::{
@@ -29,6 +30,7 @@ pub struct GrandineConfig {
     pub deposit_contract_starting_block: Option<ExecutionBlockNumber>,
     pub genesis_state_file: Option<PathBuf>,
     pub genesis_state_download_url: Option<Url>,
+    pub genesis_state_root: Option<H256>,
     pub checkpoint_sync_url: Option<Url>,
     pub force_checkpoint_sync: bool,
     pub back_sync: bool,
@@ -42,6 +44,8 @@ pub struct GrandineConfig {
     pub network_config: NetworkConfig,
     pub storage_config: StorageConfig,
     pub unfinalized_states_in_memory: u64,
+    pub max_checkpoint_states: usize,
+    pub reorg_warning_depth: u64,
     pub request_timeout: Duration,
     pub command: Option<GrandineCommand>,
     pub slashing_enabled: bool,
@@ -54,8 +58,20 @@ pub struct GrandineConfig {
     pub http_api_config: HttpApiConfig,
     pub metrics_config: MetricsConfig,
     pub track_liveness: bool,
+    pub subnet_peer_discovery_delay: Duration,
+    pub pool_config: PoolConfig,
     pub use_validator_key_cache: bool,
+    pub distributed_middleware_url: Option<Url>,
     pub slashing_protection_history_limit: u64,
+    pub prepare_payload_lookahead: Duration,
+    pub strict_fee_recipient: bool,
+    pub payload_attributes_gas_limit: bool,
+    pub attestation_rebroadcast_delay: Option<Duration>,
+    pub block_equivocation_check: bool,
+    pub attestation_equivocation_check: bool,
+    pub refuse_proposals_on_clock_skew: bool,
+    pub withdrawal_sweep_validator_indices: Vec<ValidatorIndex>,
+    pub keymanager_web3signer_proxy: bool,
     pub in_memory: bool,
 }
 
@@ -81,6 +97,15 @@ impl GrandineConfig {
             metrics_config,
             checkpoint_sync_url,
             use_validator_key_cache,
+            distributed_middleware_url,
+            strict_fee_recipient,
+            payload_attributes_gas_limit,
+            attestation_rebroadcast_delay,
+            block_equivocation_check,
+            attestation_equivocation_check,
+            refuse_proposals_on_clock_skew,
+            withdrawal_sweep_validator_indices,
+            keymanager_web3signer_proxy,
             ..
         } = self;
 
@@ -108,7 +133,13 @@ impl GrandineConfig {
 
         info!("Eth1 RPC URLs: [{}]", eth1_rpc_urls.iter().format(", "));
         info!("graffiti: {graffiti:?}");
-        info!("HTTP API address: {}", http_api_config.address);
+        if http_api_config.listen_on_tcp {
+            info!("HTTP API address: {}", http_api_config.address);
+        }
+
+        if let Some(unix_socket) = &http_api_config.unix_socket {
+            info!("HTTP API Unix socket: {}", unix_socket.path.display());
+        }
 
         if let Some(metrics_server_config) = &metrics_config.metrics_server_config {
             info!(
@@ -130,9 +161,18 @@ impl GrandineConfig {
 
         if let Some(builder_config) = builder_config {
             info!(
-                "using external block builder (API URL: {})",
-                builder_config.builder_api_url,
+                "using external block builders (URLs: [{}], boost factor: {})",
+                builder_config
+                    .relays
+                    .iter()
+                    .map(RelayConfig::redacted_url)
+                    .format(", "),
+                builder_config.builder_boost_factor,
             );
+
+            if let Some(min_builder_bid) = builder_config.min_builder_bid {
+                info!("minimum external block builder bid: {min_builder_bid} Wei");
+            }
         }
 
         if let Some(checkpoint_sync_url) = checkpoint_sync_url {
@@ -144,6 +184,10 @@ impl GrandineConfig {
                 "using Web3Signer API to sign validator messages (API URLs: [{}])",
                 web3signer_config.urls.iter().format(", "),
             );
+
+            if web3signer_config.tls.client_identity.is_some() {
+                info!("using client certificate for Web3Signer mutual TLS");
+            }
         }
 
         if *slashing_enabled {
@@ -151,8 +195,58 @@ impl GrandineConfig {
         }
 
         info!("suggested fee recipient: {suggested_fee_recipient}");
+
+        if *strict_fee_recipient {
+            info!("proposals with execution payloads paying other fee recipients will be refused");
+        }
+
+        if *keymanager_web3signer_proxy {
+            info!("keystore operations of the Keymanager API will be forwarded to Web3Signer");
+        }
+
+        if !block_equivocation_check {
+            warn!(
+                "blocks will be published even if another block from the same proposer \
+                 in the same slot has been seen",
+            );
+        }
+
+        if !attestation_equivocation_check {
+            warn!(
+                "attestations will be signed even if another attestation from the same validator \
+                 for the same target epoch has been seen",
+            );
+        }
+
+        if *refuse_proposals_on_clock_skew {
+            info!("proposals will be refused while the local clock appears to be grossly skewed");
+        }
+
+        if !withdrawal_sweep_validator_indices.is_empty() {
+            info!(
+                "tracking withdrawal sweep positions of validators: \
+                 {withdrawal_sweep_validator_indices:?}",
+            );
+        }
+
+        if let Some(delay) = attestation_rebroadcast_delay {
+            info!(
+                "own attestations not seen in aggregates will be published again \
+                 {delay:?} after aggregates are due",
+            );
+        }
+
+        if *payload_attributes_gas_limit {
+            info!(
+                "validator gas limits will be sent to the execution client in payload attributes"
+            );
+        }
         info!("back sync enabled: {back_sync}");
 
+        if let Some(distributed_middleware_url) = distributed_middleware_url {
+            info!("distributed validator middleware url: {distributed_middleware_url}");
+        }
+
         if *use_validator_key_cache {
             info!("using validator key cache");
         }
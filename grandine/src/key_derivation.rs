@@ -0,0 +1,113 @@
+use std::io::Write as _;
+
+use anyhow::{ensure, Result};
+use bip39::Mnemonic;
+use bls::SecretKey;
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+// <https://eips.ethereum.org/EIPS/eip-2334#path>
+const PURPOSE: u32 = 12381;
+pub const COIN_TYPE: u32 = 3600;
+const WITHDRAWAL_KEY_USE: u32 = 0;
+pub const SIGNING_KEY_USE: [u32; 2] = [WITHDRAWAL_KEY_USE, 0];
+
+const MNEMONIC_WORD_COUNT: usize = 24;
+
+/// Prompts for a BIP-39 mnemonic and passphrase and returns the seed derived from them.
+///
+/// Input is not echoed because the mnemonic controls both signing and withdrawal keys.
+pub fn prompt_seed() -> Result<Zeroizing<[u8; 64]>> {
+    let phrase = Zeroizing::new(rpassword::prompt_password("Enter mnemonic: ")?);
+    let mnemonic = Mnemonic::parse(phrase.as_str())?;
+
+    let passphrase = Zeroizing::new(rpassword::prompt_password(
+        "Enter mnemonic passphrase (leave empty if none): ",
+    )?);
+
+    Ok(Zeroizing::new(mnemonic.to_seed(passphrase.as_str())))
+}
+
+/// Generates a new mnemonic and has the user write it down and enter it back.
+///
+/// New mnemonics are not protected with a passphrase, as is the case in `staking-deposit-cli`.
+pub fn generate_seed() -> Result<Zeroizing<[u8; 64]>> {
+    let mnemonic = Mnemonic::generate(MNEMONIC_WORD_COUNT)?;
+    let phrase = Zeroizing::new(mnemonic.to_string());
+
+    let mut stdout = std::io::stdout();
+
+    writeln!(
+        stdout,
+        "This is your mnemonic. Write it down and store it somewhere safe. \
+         It is the only way to recover your validator keys and withdraw your funds.\n\n\
+         {}\n",
+        phrase.as_str(),
+    )?;
+
+    stdout.flush()?;
+
+    let confirmation = Zeroizing::new(rpassword::prompt_password(
+        "Enter the mnemonic to confirm you have written it down: ",
+    )?);
+
+    ensure!(
+        Mnemonic::parse(confirmation.as_str()).ok().as_ref() == Some(&mnemonic),
+        Error::MnemonicMismatch,
+    );
+
+    Ok(Zeroizing::new(mnemonic.to_seed("")))
+}
+
+/// Derives the signing key at `m/12381/3600/{account}/0/0`.
+pub fn signing_key(seed: &[u8], account: u32) -> Result<SecretKey> {
+    let [first_use, second_use] = SIGNING_KEY_USE;
+    derive_path(seed, &[PURPOSE, COIN_TYPE, account, first_use, second_use])
+}
+
+/// Derives the withdrawal key at `m/12381/3600/{account}/0`.
+pub fn withdrawal_key(seed: &[u8], account: u32) -> Result<SecretKey> {
+    derive_path(seed, &[PURPOSE, COIN_TYPE, account, WITHDRAWAL_KEY_USE])
+}
+
+fn derive_path(seed: &[u8], path: &[u32]) -> Result<SecretKey> {
+    let master = SecretKey::derive_master(seed)?;
+
+    let secret_key = path
+        .iter()
+        .fold(master, |parent, index| parent.derive_child(*index));
+
+    Ok(secret_key)
+}
+
+#[derive(Debug, Error)]
+enum Error {
+    #[error("entered mnemonic does not match the generated one")]
+    MnemonicMismatch,
+}
+
+#[cfg(test)]
+mod tests {
+    use hex_literal::hex;
+
+    use super::*;
+
+    // The seed in <https://eips.ethereum.org/EIPS/eip-2333#test-case-0> is derived from this
+    // mnemonic, which is the first test vector in the reference implementation of BIP-39.
+    #[test]
+    fn master_key_derivation_matches_eip_2333_test_vector() -> Result<()> {
+        let mnemonic = Mnemonic::parse(
+            "abandon abandon abandon abandon abandon abandon \
+             abandon abandon abandon abandon abandon about",
+        )?;
+
+        let seed = mnemonic.to_seed("TREZOR");
+
+        assert_eq!(
+            derive_path(&seed, &[])?.to_bytes().as_ref(),
+            &hex!("0d7359d57963ab8fbbde1852dcf553fedbc31f464d80ee7d40ae683122b45070"),
+        );
+
+        Ok(())
+    }
+}
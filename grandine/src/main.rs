@@ -1,4 +1,4 @@
-use core::{future::Future, panic::AssertUnwindSafe, pin::pin};
+use core::{future::Future, num::NonZeroU64, panic::AssertUnwindSafe, pin::pin, time::Duration};
 use std::{
     net::{SocketAddr, TcpListener, UdpSocket},
     path::PathBuf,
@@ -10,7 +10,9 @@ use allocator as _;
 use anyhow::{bail, ensure, Context as _, Result};
 use builder_api::BuilderConfig;
 use clap::{Error as ClapError, Parser as _};
+use clock::SystemClock;
 use database::Database;
+use dedicated_executor::DedicatedExecutor;
 use eth1::{Eth1Chain, Eth1Config};
 use eth1_api::Auth;
 use features::Feature;
@@ -20,19 +22,20 @@ use genesis::GenesisProvider;
 use http_api::HttpApiConfig;
 use log::{error, info, warn};
 use metrics::MetricsServerConfig;
+use operation_pools::PoolConfig;
 use p2p::{ListenAddr, NetworkConfig};
 use reqwest::{Client, ClientBuilder, Url};
 use runtime::{MetricsConfig, StorageConfig};
 use signer::Signer;
 use slasher::SlasherConfig;
 use slashing_protection::SlashingProtector;
-use ssz::SszRead as _;
+use ssz::{SszRead as _, SszWrite as _};
 use std_ext::ArcExt as _;
 use thiserror::Error;
 use tokio::runtime::Builder;
 use types::{
     config::Config as ChainConfig,
-    phase0::primitives::{ExecutionBlockNumber, Slot},
+    phase0::primitives::{ExecutionBlockNumber, Slot, UnixSeconds, H256},
     preset::{Preset, PresetName},
     traits::BeaconState as _,
 };
@@ -40,10 +43,11 @@ use validator::ValidatorConfig;
 use validator_key_cache::ValidatorKeyCache;
 
 use crate::{
-    commands::{GrandineCommand, InterchangeCommand},
+    commands::{GrandineCommand, InterchangeCommand, ValidatorCommand},
+    config_dir::{CONFIG_FILE, GENESIS_STATE_FILE},
     grandine_args::GrandineArgs,
     grandine_config::GrandineConfig,
-    predefined_network::PredefinedNetwork,
+    predefined_network::{self, PredefinedNetwork},
 };
 
 #[cfg(any(feature = "preset-mainnet", test))]
@@ -53,13 +57,18 @@ use types::preset::Medalla;
 #[cfg(any(feature = "preset-minimal", test))]
 use types::preset::Minimal;
 
+mod bls_to_execution_change;
 mod commands;
 mod config_dir;
 mod consts;
 mod grandine_args;
 mod grandine_config;
+mod key_derivation;
+mod options_file;
 mod predefined_network;
+mod validator_keys;
 mod validators;
+mod voluntary_exit;
 
 #[cfg(not(any(feature = "preset-any", test, doc)))]
 compile_error! {
@@ -78,6 +87,7 @@ struct Context {
     deposit_contract_starting_block: Option<ExecutionBlockNumber>,
     genesis_state_file: Option<PathBuf>,
     genesis_state_download_url: Option<Url>,
+    genesis_state_root: Option<H256>,
     validator_config: Arc<ValidatorConfig>,
     checkpoint_sync_url: Option<Url>,
     force_checkpoint_sync: bool,
@@ -94,6 +104,8 @@ struct Context {
     http_api_config: HttpApiConfig,
     metrics_config: MetricsConfig,
     track_liveness: bool,
+    subnet_peer_discovery_delay: Duration,
+    pool_config: PoolConfig,
     slashing_protection_history_limit: u64,
 }
 
@@ -131,6 +143,7 @@ impl Context {
             mut deposit_contract_starting_block,
             genesis_state_file,
             genesis_state_download_url,
+            genesis_state_root,
             validator_config,
             checkpoint_sync_url,
             force_checkpoint_sync,
@@ -147,13 +160,50 @@ impl Context {
             http_api_config,
             metrics_config,
             track_liveness,
+            subnet_peer_discovery_delay,
+            pool_config,
             slashing_protection_history_limit,
         } = self;
 
+        // Interop genesis generation does not depend on an existing genesis state.
+        if let Some(GrandineCommand::InteropGenesis {
+            genesis_time,
+            validator_count,
+            output_dir,
+        }) = command
+        {
+            return generate_interop_genesis::<P>(
+                &chain_config,
+                genesis_time,
+                validator_count,
+                output_dir,
+            );
+        }
+
+        // Deposits are signed with the genesis fork version, so no genesis state is needed either.
+        if let Some(GrandineCommand::Validator(ValidatorCommand::GenerateKeys {
+            count,
+            first_account_index,
+            withdrawal_address,
+            existing_mnemonic,
+            output_dir,
+        })) = command
+        {
+            return validator_keys::generate_validator_keys::<P>(
+                &chain_config,
+                count,
+                first_account_index,
+                withdrawal_address,
+                existing_mnemonic,
+                output_dir,
+            );
+        }
+
         // Load keys early so we can validate `eth1_rpc_urls`.
         signer.load_keys_from_web3signer().await?;
 
-        if eth1_rpc_urls.is_empty() {
+        // Commands do not run a beacon node, so they never need an Eth1 endpoint.
+        if eth1_rpc_urls.is_empty() && command.is_none() {
             ensure!(signer.no_keys(), Error::MissingEth1RpcUrlsWithValidators);
         }
 
@@ -212,10 +262,49 @@ impl Context {
                 .unwrap_or_default(),
             checkpoint_sync_url.clone(),
             genesis_state_download_url,
+            genesis_state_root,
             &eth1_chain,
         )
         .await?;
 
+        if let Some(GrandineCommand::Validator(ValidatorCommand::Exit {
+            public_key,
+            validator_index,
+            epoch,
+            beacon_node_url,
+        })) = command
+        {
+            return voluntary_exit::exit_validator(
+                &chain_config,
+                genesis_provider.state().as_ref(),
+                &signer,
+                public_key,
+                validator_index,
+                epoch,
+                beacon_node_url,
+            )
+            .await;
+        }
+
+        if let Some(GrandineCommand::Validator(ValidatorCommand::BlsToExecutionChange {
+            validator_indices,
+            account_indices,
+            to_execution_address,
+            beacon_node_url,
+        })) = command
+        {
+            return bls_to_execution_change::change_withdrawal_credentials(
+                &chain_config,
+                genesis_provider.state().as_ref(),
+                signer.client(),
+                validator_indices,
+                account_indices,
+                to_execution_address,
+                beacon_node_url,
+            )
+            .await;
+        }
+
         if let Some(command) = command {
             return handle_command(
                 chain_config,
@@ -258,9 +347,12 @@ impl Context {
             back_sync,
             metrics_config,
             track_liveness,
+            subnet_peer_discovery_delay,
             eth1_api_to_metrics_tx,
             eth1_api_to_metrics_rx,
             slashing_protection_history_limit,
+            pool_config,
+            Arc::new(SystemClock),
         )
         .await
     }
@@ -303,7 +395,7 @@ fn try_main() -> Result<()> {
     )?;
     binary_utils::initialize_rayon()?;
 
-    let config = GrandineArgs::try_parse()?
+    let config = GrandineArgs::try_parse_with_options_file()?
         .try_into_config()
         .map_err(GrandineArgs::clap_error)?;
 
@@ -316,6 +408,7 @@ fn try_main() -> Result<()> {
         deposit_contract_starting_block,
         genesis_state_file,
         genesis_state_download_url,
+        genesis_state_root,
         checkpoint_sync_url,
         force_checkpoint_sync,
         back_sync,
@@ -330,6 +423,8 @@ fn try_main() -> Result<()> {
         storage_config,
         request_timeout,
         unfinalized_states_in_memory,
+        max_checkpoint_states,
+        reorg_warning_depth,
         command,
         slashing_enabled,
         slashing_history_limit,
@@ -341,8 +436,20 @@ fn try_main() -> Result<()> {
         http_api_config,
         metrics_config,
         track_liveness,
+        subnet_peer_discovery_delay,
+        pool_config,
         use_validator_key_cache,
+        distributed_middleware_url,
         slashing_protection_history_limit,
+        prepare_payload_lookahead,
+        strict_fee_recipient,
+        payload_attributes_gas_limit,
+        attestation_rebroadcast_delay,
+        block_equivocation_check,
+        attestation_equivocation_check,
+        refuse_proposals_on_clock_skew,
+        withdrawal_sweep_validator_indices,
+        keymanager_web3signer_proxy,
         in_memory,
     } = config;
 
@@ -359,7 +466,9 @@ fn try_main() -> Result<()> {
     // The ports could in theory be freed or taken between restarts, but it's not likely.
     if command.is_none() {
         ensure_ports_not_in_use(
-            http_api_config.address,
+            http_api_config
+                .listen_on_tcp
+                .then_some(http_api_config.address),
             &network_config,
             metrics_server_config.as_ref(),
         )
@@ -371,15 +480,27 @@ fn try_main() -> Result<()> {
     }
 
     let validator_config = Arc::new(ValidatorConfig {
+        distributed_middleware_url,
         graffiti,
         max_empty_slots,
+        prepare_payload_lookahead,
+        attestation_rebroadcast_delay,
         suggested_fee_recipient,
+        strict_fee_recipient,
+        payload_attributes_gas_limit,
+        block_equivocation_check,
+        attestation_equivocation_check,
+        refuse_proposals_on_clock_skew,
         keystore_storage_password_file,
+        keymanager_web3signer_proxy,
+        withdrawal_sweep_validator_indices,
     });
 
     let store_config = StoreConfig {
         max_empty_slots,
         unfinalized_states_in_memory,
+        max_checkpoint_states,
+        reorg_warning_depth,
     };
 
     let eth1_auth = Arc::new(Auth::new(auth_options)?);
@@ -387,10 +508,21 @@ fn try_main() -> Result<()> {
     // Creating multiple `reqwest::Client`s seems to leak memory.
     // See <https://github.com/seanmonstar/reqwest/issues?q=is%3Aissue+memory>.
     // Create a single one for the whole application and reuse it through `Signer::client`.
-    let client = ClientBuilder::new()
-        .timeout(request_timeout)
-        .user_agent(grandine_version::version_with_platform())
-        .build()?;
+    let client_builder = || {
+        ClientBuilder::new()
+            .timeout(request_timeout)
+            .user_agent(grandine_version::version_with_platform())
+    };
+
+    let client = client_builder().build()?;
+
+    // Web3Signer TLS settings get a client of their own so that the client identity and
+    // additional root certificates are not used for connections to other servers.
+    let web3signer_client = if web3signer_config.tls.is_empty() {
+        client.clone()
+    } else {
+        web3signer_config.tls.configure(client_builder())?.build()?
+    };
 
     let mut cache = use_validator_key_cache.then(|| {
         ValidatorKeyCache::new(
@@ -418,9 +550,17 @@ fn try_main() -> Result<()> {
         None => ValidatorKeyCache::default(),
     };
 
+    let keystore_decryption_executor =
+        DedicatedExecutor::new("de-keystore", num_cpus::get(), None, metrics.clone());
+
     let signer = Signer::new(
-        validators.normalize(cache.as_mut(), &keystore_storage)?,
+        validators.normalize(
+            cache.as_mut(),
+            &keystore_storage,
+            &keystore_decryption_executor,
+        )?,
         client,
+        web3signer_client,
         web3signer_config,
         metrics.clone(),
     );
@@ -442,6 +582,7 @@ fn try_main() -> Result<()> {
         deposit_contract_starting_block,
         genesis_state_file,
         genesis_state_download_url,
+        genesis_state_root,
         validator_config,
         checkpoint_sync_url,
         force_checkpoint_sync,
@@ -458,6 +599,8 @@ fn try_main() -> Result<()> {
         http_api_config,
         metrics_config,
         track_liveness,
+        subnet_peer_discovery_delay,
+        pool_config,
         slashing_protection_history_limit,
     };
 
@@ -476,15 +619,17 @@ fn try_main() -> Result<()> {
 // Ports are checked before binding them for actual use.
 // This is a TOCTOU race condition, but the only consequence of it is slightly worse error messages.
 fn ensure_ports_not_in_use(
-    http_address: SocketAddr,
+    http_address: Option<SocketAddr>,
     network_config: &NetworkConfig,
     metrics_server_config: Option<&MetricsServerConfig>,
 ) -> Result<()> {
-    TcpListener::bind(http_address).context(Error::PortInUse {
-        port: http_address.port(),
-        service: "HTTP API",
-        option: "--http-port",
-    })?;
+    if let Some(http_address) = http_address {
+        TcpListener::bind(http_address).context(Error::PortInUse {
+            port: http_address.port(),
+            service: "HTTP API",
+            option: "--http-port",
+        })?;
+    }
 
     if let Some(listen_addr) = network_config.listen_addrs().v4() {
         let ListenAddr {
@@ -620,6 +765,12 @@ fn handle_command<P: Preset>(
             let input_dir = input_dir.unwrap_or(std::env::current_dir()?);
             fork_choice_control::replay_blocks::<P>(&chain_config, &input_dir, from, to)?;
         }
+        GrandineCommand::InteropGenesis { .. } => {
+            unreachable!("interop genesis is generated before the genesis state is loaded")
+        }
+        GrandineCommand::Validator(_) => {
+            unreachable!("validator commands are handled before the command handler is called")
+        }
         GrandineCommand::Interchange(interchange_command) => {
             let genesis_validators_root = genesis_provider.state().genesis_validators_root();
 
@@ -657,6 +808,34 @@ fn handle_command<P: Preset>(
     Ok(())
 }
 
+fn generate_interop_genesis<P: Preset>(
+    chain_config: &ChainConfig,
+    genesis_time: UnixSeconds,
+    validator_count: NonZeroU64,
+    output_dir: Option<PathBuf>,
+) -> Result<()> {
+    let output_dir = output_dir.unwrap_or(std::env::current_dir()?);
+
+    let (genesis_state, _) =
+        interop::quick_start_beacon_state::<P>(chain_config, genesis_time, validator_count)?;
+
+    fs_err::create_dir_all(&output_dir)?;
+    fs_err::write(output_dir.join(GENESIS_STATE_FILE), genesis_state.to_ssz()?)?;
+    fs_err::write(
+        output_dir.join(CONFIG_FILE),
+        serde_yaml::to_string(chain_config)?,
+    )?;
+
+    info!(
+        "interop genesis state with {validator_count} validators written to {output_dir:?} \
+         (genesis time: {genesis_time}, genesis validators root: {:?})",
+        genesis_state.genesis_validators_root(),
+    );
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn genesis_provider<P: Preset>(
     chain_config: &ChainConfig,
     genesis_state_file: Option<PathBuf>,
@@ -665,11 +844,13 @@ async fn genesis_provider<P: Preset>(
     store_directory: PathBuf,
     checkpoint_sync_url: Option<Url>,
     genesis_state_download_url: Option<Url>,
+    genesis_state_root: Option<H256>,
     eth1_chain: &Eth1Chain,
 ) -> Result<GenesisProvider<P>> {
     if let Some(file_path) = genesis_state_file {
         let bytes = fs_err::read(file_path)?;
         let genesis_state = Arc::from_ssz(chain_config, bytes)?;
+        predefined_network::verify_genesis_state(&genesis_state, genesis_state_root, None)?;
         return Ok(GenesisProvider::Custom(genesis_state));
     }
 
@@ -680,10 +861,25 @@ async fn genesis_provider<P: Preset>(
                 store_directory.as_path(),
                 checkpoint_sync_url,
                 genesis_state_download_url,
+                genesis_state_root,
             )
             .await;
     }
 
+    if let Some(download_url) = genesis_state_download_url {
+        return predefined_network::load_or_download_genesis_state(
+            chain_config,
+            client,
+            store_directory,
+            download_url,
+            checkpoint_sync_url,
+            genesis_state_root,
+            None,
+        )
+        .await
+        .map(GenesisProvider::Custom);
+    }
+
     let eth1_block_stream = pin!(eth1_chain.stream_blocks()?);
 
     let genesis_state =
@@ -0,0 +1,383 @@
+// Command line options can also be provided through a file passed with `--config-file` and through
+// environment variables. They are merged in the following order of precedence (highest first):
+//
+// 1. Command line arguments.
+// 2. Environment variables named after options with a `GRANDINE_` prefix
+//    (`GRANDINE_HTTP_PORT=5052` is equivalent to `--http-port 5052`).
+//    Multiple values are separated with commas.
+//    Variables that do not correspond to any option are ignored with a warning.
+// 3. The options file in YAML or TOML format. Keys are option names without leading dashes
+//    (`http-port: 5052` or `http_port = 5052`). Multiple values are given as sequences.
+// 4. Default values.
+//
+// Options from lower precedence sources are converted to command line arguments and placed
+// before the actual ones, so `clap` performs all the parsing and value validation.
+
+use core::fmt::{Display, Formatter, Result as FmtResult};
+use std::{
+    collections::BTreeMap,
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, ensure, Result};
+use clap::Command;
+use itertools::Itertools as _;
+use log::warn;
+use serde::Deserialize;
+use thiserror::Error;
+
+const OPTIONS_FILE_OPTION: &str = "config-file";
+const ENVIRONMENT_VARIABLE_PREFIX: &str = "GRANDINE_";
+
+// Used to configure logging. See `binary_utils::initialize_logger`.
+const NON_OPTION_ENVIRONMENT_VARIABLES: &[&str] = &["GRANDINE_LOG", "GRANDINE_LOG_STYLE"];
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum FileValue {
+    Single(ScalarValue),
+    Multiple(Vec<ScalarValue>),
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ScalarValue {
+    Bool(bool),
+    Unsigned(u64),
+    Signed(i64),
+    Float(f64),
+    String(String),
+}
+
+impl Display for ScalarValue {
+    fn fmt(&self, formatter: &mut Formatter) -> FmtResult {
+        match self {
+            Self::Bool(value) => value.fmt(formatter),
+            Self::Unsigned(value) => value.fmt(formatter),
+            Self::Signed(value) => value.fmt(formatter),
+            Self::Float(value) => value.fmt(formatter),
+            Self::String(value) => value.fmt(formatter),
+        }
+    }
+}
+
+pub fn merge_arguments(
+    command: &Command,
+    arguments: impl IntoIterator<Item = OsString>,
+    environment_variables: impl IntoIterator<Item = (String, String)>,
+) -> Result<Vec<OsString>> {
+    let mut arguments = arguments.into_iter();
+    let binary = arguments.next();
+    let arguments = arguments.collect_vec();
+
+    let environment_options = environment_variables
+        .into_iter()
+        .filter(|(variable, _)| !NON_OPTION_ENVIRONMENT_VARIABLES.contains(&variable.as_str()))
+        .filter_map(|(variable, value)| {
+            let name = variable
+                .strip_prefix(ENVIRONMENT_VARIABLE_PREFIX)?
+                .to_lowercase()
+                .replace('_', "-");
+
+            Some((name, variable, value))
+        })
+        .collect_vec();
+
+    let options_file = command_line_value(&arguments, OPTIONS_FILE_OPTION)
+        .map(PathBuf::from)
+        .or_else(|| {
+            environment_options
+                .iter()
+                .find(|(name, _, _)| name == OPTIONS_FILE_OPTION)
+                .map(|(_, _, value)| PathBuf::from(value))
+        });
+
+    let mut options = BTreeMap::new();
+
+    if let Some(path) = options_file {
+        for (name, values) in load_options_file(path.as_path())? {
+            validate_option(command, &name, || {
+                format!("options file {}", path.display())
+            })?;
+            options.insert(name, values);
+        }
+    }
+
+    for (name, variable, value) in environment_options {
+        if name == OPTIONS_FILE_OPTION {
+            continue;
+        }
+
+        // Unlike the options file, the environment is shared with other programs.
+        // A stray variable with the prefix should not prevent the node from starting.
+        if !is_known_option(command, &name) {
+            warn!(
+                "ignoring environment variable {variable}; \
+                 it does not correspond to any command line option",
+            );
+            continue;
+        }
+
+        let values = value
+            .split(',')
+            .map(str::trim)
+            .map(ToOwned::to_owned)
+            .collect();
+
+        options.insert(name, values);
+    }
+
+    let command_line_options = arguments
+        .iter()
+        .filter_map(|argument| argument.to_str())
+        .take_while(|argument| *argument != "--")
+        .filter_map(|argument| argument.strip_prefix("--"))
+        .map(|argument| argument.split_once('=').map_or(argument, |(name, _)| name))
+        .collect_vec();
+
+    let mut merged = binary.into_iter().collect_vec();
+
+    for (name, values) in options {
+        if command_line_options.contains(&name.as_str()) {
+            continue;
+        }
+
+        let takes_values = command
+            .get_arguments()
+            .find(|argument| argument.get_long() == Some(name.as_str()))
+            .is_some_and(|argument| argument.get_action().takes_values());
+
+        if takes_values {
+            merged.extend(
+                values
+                    .into_iter()
+                    .map(|value| OsString::from(format!("--{name}={value}"))),
+            );
+        } else {
+            let enabled = values
+                .iter()
+                .exactly_one()
+                .ok()
+                .and_then(|value| value.parse::<bool>().ok());
+
+            match enabled {
+                Some(true) => merged.push(OsString::from(format!("--{name}"))),
+                Some(false) => {}
+                None => bail!(Error::InvalidFlagValue { name, values }),
+            }
+        }
+    }
+
+    merged.extend(arguments);
+
+    Ok(merged)
+}
+
+fn load_options_file(path: &Path) -> Result<Vec<(String, Vec<String>)>> {
+    let contents = fs_err::read_to_string(path)?;
+
+    let is_toml = path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("toml"));
+
+    let options: BTreeMap<String, FileValue> = if is_toml {
+        toml::from_str(contents.as_str())?
+    } else {
+        serde_yaml::from_str::<Option<_>>(contents.as_str())?.unwrap_or_default()
+    };
+
+    let options = options
+        .into_iter()
+        .map(|(key, value)| {
+            let name = key.trim_start_matches('-').replace('_', "-");
+
+            let values = match value {
+                FileValue::Single(value) => vec![value.to_string()],
+                FileValue::Multiple(values) => values.iter().map(ToString::to_string).collect(),
+            };
+
+            (name, values)
+        })
+        .collect();
+
+    Ok(options)
+}
+
+fn validate_option(command: &Command, name: &str, origin: impl Fn() -> String) -> Result<()> {
+    ensure!(
+        name != OPTIONS_FILE_OPTION,
+        Error::NestedOptionsFile { origin: origin() },
+    );
+
+    ensure!(
+        is_known_option(command, name),
+        Error::UnknownOption {
+            name: name.to_owned(),
+            origin: origin(),
+        },
+    );
+
+    Ok(())
+}
+
+fn is_known_option(command: &Command, name: &str) -> bool {
+    command
+        .get_arguments()
+        .any(|argument| argument.get_long() == Some(name))
+}
+
+fn command_line_value<'arguments>(
+    arguments: &'arguments [OsString],
+    name: &str,
+) -> Option<&'arguments str> {
+    let flag = format!("--{name}");
+    let prefix = format!("--{name}=");
+
+    arguments
+        .iter()
+        .filter_map(|argument| argument.to_str())
+        .tuple_windows()
+        .find_map(|(argument, next)| (argument == flag).then_some(next))
+        .or_else(|| {
+            arguments
+                .iter()
+                .filter_map(|argument| argument.to_str())
+                .find_map(|argument| argument.strip_prefix(prefix.as_str()))
+        })
+}
+
+#[derive(Debug, Error)]
+enum Error {
+    #[error(
+        "unknown option {name:?} in {origin}; \
+         use option names as shown by --help without leading dashes (for example, http-port)"
+    )]
+    UnknownOption { name: String, origin: String },
+    #[error("--{OPTIONS_FILE_OPTION} cannot be set in {origin}")]
+    NestedOptionsFile { origin: String },
+    #[error("option {name:?} is a flag and must be set to true or false (found: {values:?})")]
+    InvalidFlagValue { name: String, values: Vec<String> },
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write as _;
+
+    use clap::CommandFactory as _;
+    use tempfile::Builder;
+
+    use crate::grandine_args::GrandineArgs;
+
+    use super::*;
+
+    #[test]
+    fn command_line_takes_precedence_over_environment_and_options_file() -> Result<()> {
+        let mut file = Builder::new().suffix(".yaml").tempfile()?;
+
+        writeln!(
+            file,
+            "http-port: 5053\nlibp2p_port: 9001\ndisable-upnp: true\nboot-nodes: []",
+        )?;
+
+        let merged = merge_arguments(
+            &GrandineArgs::command(),
+            arguments([
+                "grandine",
+                "--config-file",
+                path(&file),
+                "--http-port",
+                "5054",
+            ]),
+            [("GRANDINE_LIBP2P_PORT".to_owned(), "9002".to_owned())],
+        )?;
+
+        assert_eq!(
+            merged,
+            arguments([
+                "grandine",
+                "--disable-upnp",
+                "--libp2p-port=9002",
+                "--config-file",
+                path(&file),
+                "--http-port",
+                "5054",
+            ]),
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn options_file_in_toml_format_is_supported() -> Result<()> {
+        let mut file = Builder::new().suffix(".toml").tempfile()?;
+
+        writeln!(
+            file,
+            "eth1_rpc_urls = ['http://a', 'http://b']\nhttp_port = 5053"
+        )?;
+
+        let merged = merge_arguments(
+            &GrandineArgs::command(),
+            arguments(["grandine", "--config-file", path(&file)]),
+            [],
+        )?;
+
+        assert_eq!(
+            merged,
+            arguments([
+                "grandine",
+                "--eth1-rpc-urls=http://a",
+                "--eth1-rpc-urls=http://b",
+                "--http-port=5053",
+                "--config-file",
+                path(&file),
+            ]),
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_options_are_rejected() -> Result<()> {
+        let mut file = Builder::new().suffix(".yaml").tempfile()?;
+
+        writeln!(file, "no-such-option: 1")?;
+
+        merge_arguments(
+            &GrandineArgs::command(),
+            arguments(["grandine", "--config-file", path(&file)]),
+            [],
+        )
+        .expect_err("unknown option in options file should be rejected");
+
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_environment_variables_are_ignored() -> Result<()> {
+        let merged = merge_arguments(
+            &GrandineArgs::command(),
+            arguments(["grandine"]),
+            [
+                ("GRANDINE_NO_SUCH_OPTION".to_owned(), "1".to_owned()),
+                ("GRANDINE_LOG".to_owned(), "debug".to_owned()),
+                ("GRANDINE_HTTP_PORT".to_owned(), "5053".to_owned()),
+            ],
+        )?;
+
+        assert_eq!(merged, arguments(["grandine", "--http-port=5053"]));
+
+        Ok(())
+    }
+
+    fn path(file: &tempfile::NamedTempFile) -> &str {
+        file.path()
+            .to_str()
+            .expect("temporary file paths should be valid UTF-8")
+    }
+
+    fn arguments<'a>(arguments: impl IntoIterator<Item = &'a str>) -> Vec<OsString> {
+        arguments.into_iter().map(OsString::from).collect()
+    }
+}
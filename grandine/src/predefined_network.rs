@@ -1,20 +1,22 @@
 use core::time::Duration;
 use std::{io::ErrorKind, path::Path, sync::Arc};
 
-use anyhow::{bail, Context as _, Result};
+use anyhow::{bail, ensure, Context as _, Result};
 use deposit_tree::DepositTree;
 use fork_choice_control::checkpoint_sync;
 use genesis::GenesisProvider;
+use hex_literal::hex;
 use log::info;
 use p2p::{Enr, NetworkConfig};
 use reqwest::{Client, Url};
-use ssz::SszRead as _;
+use ssz::{SszHash as _, SszRead as _};
 use strum::Display;
 use tap::Pipe as _;
-use types::{combined::BeaconState, config::Config as ChainConfig, preset::Preset};
-
-#[cfg(any(feature = "network-mainnet", test))]
-use ::{hex_literal::hex, types::phase0::primitives::H256};
+use thiserror::Error;
+use types::{
+    combined::BeaconState, config::Config as ChainConfig, phase0::primitives::H256, preset::Preset,
+    traits::BeaconState as _,
+};
 
 #[derive(Clone, Copy, Display)]
 #[strum(serialize_all = "lowercase")]
@@ -121,12 +123,35 @@ impl PredefinedNetwork {
         }
     }
 
+    #[must_use]
+    pub const fn genesis_validators_root(self) -> H256 {
+        match self {
+            #[cfg(any(feature = "network-mainnet", test))]
+            Self::Mainnet => H256(hex!(
+                "4b363db94e286120d76eb905340fdd4e54bfe9f06bf33ff6cf5ad27f511bfe95"
+            )),
+            #[cfg(any(feature = "network-goerli", test))]
+            Self::Goerli => H256(hex!(
+                "043db0d9a83813551ee2f33450d23797757d430911a9320530ad8a0eabc43efb"
+            )),
+            #[cfg(any(feature = "network-sepolia", test))]
+            Self::Sepolia => H256(hex!(
+                "d8ea171f3c94aea21ebc42a1ed61052acf3f9209c00e4efbaaddac09ed9b8078"
+            )),
+            #[cfg(any(feature = "network-holesky", test))]
+            Self::Holesky => H256(hex!(
+                "9143aa7c615a7f7115e2b6aac319c03529df8242ae705fba9df39b79c59fa8b1"
+            )),
+        }
+    }
+
     pub async fn genesis_provider<P: Preset>(
         self,
         client: &Client,
         store_directory: impl AsRef<Path> + Send,
         checkpoint_sync_url: Option<Url>,
         genesis_download_url: Option<Url>,
+        genesis_state_root: Option<H256>,
     ) -> Result<GenesisProvider<P>> {
         let config = &self.chain_config();
         let load_genesis_state = |default_download_url: &str| {
@@ -140,12 +165,27 @@ impl PredefinedNetwork {
                         .expect("hard-coded genesis state download URL should be valid")
                 }),
                 checkpoint_sync_url,
+                genesis_state_root,
+                Some(self.genesis_validators_root()),
             )
         };
 
         match self {
             #[cfg(any(feature = "network-mainnet", test))]
-            Self::Mainnet => predefined_chains::mainnet::<P>(),
+            Self::Mainnet => {
+                let genesis_provider = predefined_chains::mainnet::<P>();
+
+                if let Some(expected) = genesis_state_root {
+                    let actual = genesis_provider.state_root();
+
+                    ensure!(
+                        actual == expected,
+                        Error::GenesisStateRootMismatch { expected, actual },
+                    );
+                }
+
+                genesis_provider
+            }
             #[cfg(any(feature = "network-goerli", test))]
             Self::Goerli => load_genesis_state(
                 "https://github.com/eth-clients/goerli/raw/397ecd128e8162fa9b352cd28cdea77d64502629/prater/genesis.ssz",
@@ -264,22 +304,40 @@ impl PredefinedNetwork {
     }
 }
 
-async fn load_or_download_genesis_state<P: Preset>(
+// Downloaded genesis states are cached in the store directory only after they pass verification.
+pub async fn load_or_download_genesis_state<P: Preset>(
     config: &ChainConfig,
     client: &Client,
     store_directory: impl AsRef<Path> + Send,
     download_url: Url,
     checkpoint_sync_url: Option<Url>,
+    expected_state_root: Option<H256>,
+    expected_genesis_validators_root: Option<H256>,
 ) -> Result<Arc<BeaconState<P>>> {
     let genesis_state_path = store_directory.as_ref().join("genesis_state.ssz");
 
-    let ssz_bytes = match fs_err::tokio::read(genesis_state_path.as_path()).await {
+    match fs_err::tokio::read(genesis_state_path.as_path()).await {
         Ok(bytes) => {
             info!(
                 "loading genesis state from file: {}…",
                 genesis_state_path.display()
             );
-            bytes.into()
+
+            let genesis_state = Arc::from_ssz(config, bytes)?;
+
+            verify_genesis_state(
+                &genesis_state,
+                expected_state_root,
+                expected_genesis_validators_root,
+            )
+            .with_context(|| {
+                format!(
+                    "cached genesis state in {} failed verification",
+                    genesis_state_path.display(),
+                )
+            })?;
+
+            Ok(genesis_state)
         }
         Err(error) if error.kind() == ErrorKind::NotFound => {
             if let Some(url) = checkpoint_sync_url {
@@ -296,17 +354,58 @@ async fn load_or_download_genesis_state<P: Preset>(
                 .timeout(Duration::from_secs(600))
                 .send()
                 .await?
+                .error_for_status()?
                 .bytes()
                 .await?;
 
+            let genesis_state = Arc::from_ssz(config, &bytes)?;
+
+            verify_genesis_state(
+                &genesis_state,
+                expected_state_root,
+                expected_genesis_validators_root,
+            )?;
+
             fs_err::tokio::write(genesis_state_path, &bytes).await?;
 
-            bytes
+            Ok(genesis_state)
         }
         Err(error) => bail!(error),
-    };
+    }
+}
+
+pub fn verify_genesis_state<P: Preset>(
+    genesis_state: &BeaconState<P>,
+    expected_state_root: Option<H256>,
+    expected_genesis_validators_root: Option<H256>,
+) -> Result<()> {
+    if let Some(expected) = expected_state_root {
+        let actual = genesis_state.hash_tree_root();
+
+        ensure!(
+            actual == expected,
+            Error::GenesisStateRootMismatch { expected, actual },
+        );
+    }
+
+    if let Some(expected) = expected_genesis_validators_root {
+        let actual = genesis_state.genesis_validators_root();
+
+        ensure!(
+            actual == expected,
+            Error::GenesisValidatorsRootMismatch { expected, actual },
+        );
+    }
+
+    Ok(())
+}
 
-    Arc::from_ssz(config, ssz_bytes).map_err(Into::into)
+#[derive(Debug, Error)]
+enum Error {
+    #[error("genesis state root does not match (expected: {expected:?}, actual: {actual:?})")]
+    GenesisStateRootMismatch { expected: H256, actual: H256 },
+    #[error("genesis validators root does not match (expected: {expected:?}, actual: {actual:?})")]
+    GenesisValidatorsRootMismatch { expected: H256, actual: H256 },
 }
 
 #[cfg(test)]
@@ -323,7 +422,7 @@ mod tests {
 
     fn assert_deposit_tree_valid<P: Preset>(predefined_network: PredefinedNetwork) {
         let genesis_provider = predefined_network
-            .genesis_provider::<P>(&Client::new(), "", None, None)
+            .genesis_provider::<P>(&Client::new(), "", None, None, None)
             .pipe(futures::executor::block_on)
             .expect("this test should not load files or access the network");
 
@@ -332,5 +431,9 @@ mod tests {
 
         assert_eq!(state.eth1_data().deposit_count, deposit_tree.deposit_count);
         assert_eq!(state.eth1_deposit_index(), deposit_tree.deposit_count);
+        assert_eq!(
+            state.genesis_validators_root(),
+            predefined_network.genesis_validators_root(),
+        );
     }
 }
@@ -0,0 +1,285 @@
+use std::{
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{ensure, Result};
+use bls::{PublicKeyBytes, SecretKey, SignatureBytes};
+use eip_2335::Keystore;
+use helper_functions::{misc, signing::SignForAllForks as _};
+use log::info;
+use serde::Serialize;
+use serde_utils::FromHexTrait;
+use serde_with::As;
+use ssz::SszHash as _;
+use thiserror::Error;
+use types::{
+    config::Config as ChainConfig,
+    phase0::{
+        containers::{DepositData, DepositMessage},
+        primitives::{ExecutionAddress, Gwei, Version, H256},
+    },
+    preset::Preset,
+};
+use zeroize::Zeroizing;
+
+use crate::key_derivation;
+
+const KEYS_DIRECTORY: &str = "validator_keys";
+const MIN_PASSWORD_LENGTH: usize = 8;
+
+// Version of `staking-deposit-cli` whose output format is reproduced here.
+const DEPOSIT_CLI_VERSION: &str = "2.7.0";
+
+/// An entry in `deposit_data-*.json` in the format produced by `staking-deposit-cli`.
+#[derive(Serialize)]
+struct DepositDataJson<'config> {
+    #[serde(with = "As::<FromHexTrait>")]
+    pubkey: PublicKeyBytes,
+    #[serde(with = "As::<FromHexTrait>")]
+    withdrawal_credentials: H256,
+    amount: Gwei,
+    #[serde(with = "As::<FromHexTrait>")]
+    signature: SignatureBytes,
+    #[serde(with = "As::<FromHexTrait>")]
+    deposit_message_root: H256,
+    #[serde(with = "As::<FromHexTrait>")]
+    deposit_data_root: H256,
+    #[serde(with = "As::<FromHexTrait>")]
+    fork_version: Version,
+    network_name: &'config str,
+    deposit_cli_version: &'static str,
+}
+
+/// Derives `count` validator keys starting at `first_account_index` and writes EIP-2335 keystores
+/// along with deposit data for them.
+///
+/// Deposits are signed with `GENESIS_FORK_VERSION` from `chain_config` as required by the
+/// deposit contract, so the output is only valid for the network `chain_config` describes.
+pub fn generate_validator_keys<P: Preset>(
+    chain_config: &ChainConfig,
+    count: u32,
+    first_account_index: u32,
+    withdrawal_address: Option<ExecutionAddress>,
+    existing_mnemonic: bool,
+    output_dir: Option<PathBuf>,
+) -> Result<()> {
+    let output_dir = output_dir
+        .unwrap_or(std::env::current_dir()?)
+        .join(KEYS_DIRECTORY);
+
+    let last_account_index = first_account_index
+        .checked_add(count)
+        .ok_or(Error::AccountIndexOverflow)?;
+
+    let seed = if existing_mnemonic {
+        key_derivation::prompt_seed()?
+    } else {
+        key_derivation::generate_seed()?
+    };
+
+    let password = prompt_keystore_password()?;
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let amount = P::MAX_EFFECTIVE_BALANCE;
+    let [first_use, second_use] = key_derivation::SIGNING_KEY_USE;
+
+    fs_err::create_dir_all(&output_dir)?;
+
+    let mut deposit_data = Vec::with_capacity(count.try_into()?);
+
+    for account in first_account_index..last_account_index {
+        let secret_key = key_derivation::signing_key(seed.as_slice(), account)?;
+        let pubkey = secret_key.to_public_key().into();
+
+        let withdrawal_credentials = match withdrawal_address {
+            Some(address) => misc::eth1_address_withdrawal_credentials(address),
+            None => {
+                let withdrawal_key = key_derivation::withdrawal_key(seed.as_slice(), account)?;
+                misc::bls_withdrawal_credentials(withdrawal_key.to_public_key().into())
+            }
+        };
+
+        deposit_data.push(deposit_data_json(
+            chain_config,
+            &secret_key,
+            withdrawal_credentials,
+            amount,
+        ));
+
+        let keystore = Keystore::encrypt(
+            secret_key.to_bytes(),
+            password.as_str(),
+            pubkey,
+            key_derivation::COIN_TYPE.try_into()?,
+            account.try_into()?,
+            vec![first_use.try_into()?, second_use.try_into()?],
+        )?;
+
+        let keystore_file = format!(
+            "keystore-m_12381_{}_{account}_{first_use}_{second_use}-{timestamp}.json",
+            key_derivation::COIN_TYPE,
+        );
+
+        fs_err::write(
+            output_dir.join(keystore_file),
+            serde_json::to_string(&keystore)?,
+        )?;
+    }
+
+    let deposit_data_file = output_dir.join(format!("deposit_data-{timestamp}.json"));
+
+    fs_err::write(&deposit_data_file, serde_json::to_string(&deposit_data)?)?;
+
+    info!(
+        "{count} validator keys (accounts {first_account_index}..{last_account_index}) \
+         and deposit data for {} written to {output_dir:?}; \
+         upload {deposit_data_file:?} to the Staking Launchpad to make the deposits",
+        chain_config.config_name,
+    );
+
+    Ok(())
+}
+
+fn deposit_data_json<'config>(
+    chain_config: &'config ChainConfig,
+    secret_key: &SecretKey,
+    withdrawal_credentials: H256,
+    amount: Gwei,
+) -> DepositDataJson<'config> {
+    let pubkey = secret_key.to_public_key().into();
+
+    let deposit_message = DepositMessage {
+        pubkey,
+        withdrawal_credentials,
+        amount,
+    };
+
+    let signature = deposit_message.sign(chain_config, secret_key).into();
+
+    let deposit_data = DepositData {
+        pubkey,
+        withdrawal_credentials,
+        amount,
+        signature,
+    };
+
+    DepositDataJson {
+        pubkey,
+        withdrawal_credentials,
+        amount,
+        signature,
+        deposit_message_root: deposit_message.hash_tree_root(),
+        deposit_data_root: deposit_data.hash_tree_root(),
+        fork_version: chain_config.genesis_fork_version,
+        network_name: &chain_config.config_name,
+        deposit_cli_version: DEPOSIT_CLI_VERSION,
+    }
+}
+
+fn prompt_keystore_password() -> Result<Zeroizing<String>> {
+    let password = Zeroizing::new(rpassword::prompt_password(
+        "Enter a password to encrypt the keystores with: ",
+    )?);
+
+    let confirmation = Zeroizing::new(rpassword::prompt_password("Repeat the password: ")?);
+
+    ensure!(password == confirmation, Error::PasswordMismatch);
+
+    let normalized_password = eip_2335::normalize_password(password.as_bytes())?;
+
+    ensure!(
+        normalized_password.chars().count() >= MIN_PASSWORD_LENGTH,
+        Error::PasswordTooShort,
+    );
+
+    Ok(normalized_password)
+}
+
+#[derive(Debug, Error)]
+enum Error {
+    #[error("account indices do not fit in 32 bits")]
+    AccountIndexOverflow,
+    #[error("passwords do not match")]
+    PasswordMismatch,
+    #[error("password must be at least {MIN_PASSWORD_LENGTH} characters long")]
+    PasswordTooShort,
+}
+
+#[cfg(test)]
+mod tests {
+    use bls::CachedPublicKey;
+    use itertools::Itertools as _;
+    use serde_json::Value;
+    use types::preset::Mainnet;
+
+    use super::*;
+
+    // `staking-deposit-cli` and the Staking Launchpad accept a `deposit_data-*.json` file only if
+    // every entry passes the checks in `staking_deposit.utils.validation.validate_deposit`.
+    // The interop keys and deposits are the test vectors from
+    // <https://github.com/ethereum/eth2.0-pm/tree/b7c76e7a9d036ce73ca6aa0b7065db92f7728f41/interop/mocked_start>.
+    #[test]
+    fn deposit_data_json_matches_interop_deposits_and_passes_validation() -> Result<()> {
+        let chain_config = ChainConfig::mainnet();
+
+        for validator_index in 0..4 {
+            let secret_key = interop::secret_key(validator_index);
+            let expected = interop::quick_start_deposit_data::<Mainnet>(&chain_config, &secret_key);
+
+            let json = deposit_data_json(
+                &chain_config,
+                &secret_key,
+                expected.withdrawal_credentials,
+                expected.amount,
+            );
+
+            assert_eq!(json.pubkey, expected.pubkey);
+            assert_eq!(json.signature, expected.signature);
+            assert_eq!(json.deposit_data_root, expected.hash_tree_root());
+            assert_eq!(json.fork_version, chain_config.genesis_fork_version);
+
+            let deposit_message = DepositMessage {
+                pubkey: json.pubkey,
+                withdrawal_credentials: json.withdrawal_credentials,
+                amount: json.amount,
+            };
+
+            assert_eq!(json.deposit_message_root, deposit_message.hash_tree_root());
+
+            deposit_message.verify(
+                &chain_config,
+                json.signature,
+                &CachedPublicKey::from(json.pubkey),
+            )?;
+
+            let Value::Object(fields) = serde_json::to_value(&json)? else {
+                panic!("deposit data should be serialized as an object");
+            };
+
+            itertools::assert_equal(
+                fields.keys().map(String::as_str).sorted(),
+                [
+                    "amount",
+                    "deposit_cli_version",
+                    "deposit_data_root",
+                    "deposit_message_root",
+                    "fork_version",
+                    "network_name",
+                    "pubkey",
+                    "signature",
+                    "withdrawal_credentials",
+                ],
+            );
+
+            // `staking-deposit-cli` writes hex strings without the `0x` prefix.
+            assert!(fields["pubkey"]
+                .as_str()
+                .is_some_and(|pubkey| pubkey.len() == 96 && !pubkey.starts_with("0x")));
+            assert_eq!(fields["fork_version"], "00000000");
+            assert_eq!(fields["amount"], 32_000_000_000_u64);
+            assert_eq!(fields["network_name"], "mainnet");
+        }
+
+        Ok(())
+    }
+}
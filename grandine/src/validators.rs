@@ -2,13 +2,17 @@ use std::{
     collections::HashMap,
     path::{Path, PathBuf},
     sync::Arc,
+    time::Instant,
 };
 
-use anyhow::{Error, Result};
+use anyhow::{Context as _, Error, Result};
 use bls::{PublicKeyBytes, SecretKey};
+use dedicated_executor::DedicatedExecutor;
 use educe::Educe;
 use eip_2335::Keystore;
-use log::warn;
+use futures::stream::{FuturesOrdered, TryStreamExt as _};
+use itertools::Itertools as _;
+use log::{info, warn};
 use rayon::iter::{IntoParallelIterator as _, ParallelIterator as _};
 use signer::KeyOrigin;
 use std_ext::ArcExt;
@@ -28,16 +32,14 @@ pub enum Validators {
         keystore_dir: PathBuf,
         keystore_password_file: PathBuf,
     },
+    KeystoreDirectoryWithPasswordPrompt {
+        keystore_dir: PathBuf,
+    },
 }
 
 impl Validators {
-    fn keymap_from_paths(
-        keystore_dir: impl AsRef<Path>,
-        keystore_password_file: impl AsRef<Path>,
-    ) -> Result<HashMap<PathBuf, PathBuf>> {
+    fn keystore_paths(keystore_dir: impl AsRef<Path>) -> Result<Vec<PathBuf>> {
         let keystore_dir = keystore_dir.as_ref();
-        let keystore_password_file = keystore_password_file.as_ref();
-        let individual_passwords = keystore_password_file.is_dir();
         let keystore_glob = "*.json";
 
         let old_working_directory = std::env::current_dir()?;
@@ -47,6 +49,23 @@ impl Validators {
         let keystores = glob::glob(keystore_glob)
             .expect("glob pattern should be valid")
             .flatten()
+            .collect_vec();
+
+        std::env::set_current_dir(old_working_directory)?;
+
+        Ok(keystores)
+    }
+
+    fn keymap_from_paths(
+        keystore_dir: impl AsRef<Path>,
+        keystore_password_file: impl AsRef<Path>,
+    ) -> Result<HashMap<PathBuf, PathBuf>> {
+        let keystore_dir = keystore_dir.as_ref();
+        let keystore_password_file = keystore_password_file.as_ref();
+        let individual_passwords = keystore_password_file.is_dir();
+
+        let keystores = Self::keystore_paths(keystore_dir)?
+            .into_iter()
             .map(|path| {
                 let keystore_file = keystore_dir.join(path.as_path());
 
@@ -64,37 +83,79 @@ impl Validators {
             })
             .collect();
 
-        std::env::set_current_dir(old_working_directory)?;
-
         Ok(keystores)
     }
 
+    fn load_keystores_with_passwords(self) -> Result<Vec<(Keystore, Zeroizing<String>)>> {
+        let keystore_and_password_paths = match self {
+            Self::Keystores {
+                keystore_and_password_paths,
+            } => keystore_and_password_paths,
+            Self::KeystoreDirectory {
+                keystore_dir,
+                keystore_password_file,
+            } => Self::keymap_from_paths(keystore_dir, keystore_password_file)?,
+            Self::KeystoreDirectoryWithPasswordPrompt { keystore_dir } => {
+                let keystore_paths = Self::keystore_paths(&keystore_dir)?;
+
+                if keystore_paths.is_empty() {
+                    return Ok(vec![]);
+                }
+
+                let password = Zeroizing::new(rpassword::prompt_password(format!(
+                    "Enter password for {} keystore(s) in {}: ",
+                    keystore_paths.len(),
+                    keystore_dir.display(),
+                ))?);
+
+                let normalized_password = eip_2335::normalize_password(password.as_bytes())?;
+
+                return keystore_paths
+                    .into_par_iter()
+                    .map(|path| {
+                        let keystore = read_keystore(keystore_dir.join(path))?;
+                        Ok((keystore, normalized_password.clone()))
+                    })
+                    .collect();
+            }
+        };
+
+        // Keystores commonly share a password file.
+        // Read each file once instead of once per keystore.
+        let passwords = keystore_and_password_paths
+            .values()
+            .unique()
+            .collect_vec()
+            .into_par_iter()
+            .map(|password_path| {
+                let password = Zeroizing::new(fs_err::read(password_path)?);
+                let normalized_password = eip_2335::normalize_password(password.as_slice())?;
+                Ok((password_path.clone(), normalized_password))
+            })
+            .collect::<Result<HashMap<_, _>>>()?;
+
+        keystore_and_password_paths
+            .into_par_iter()
+            .map(|(keystore_path, password_path)| {
+                let keystore = read_keystore(keystore_path)?;
+                let normalized_password = passwords[&password_path].clone();
+                Ok((keystore, normalized_password))
+            })
+            .collect()
+    }
+
     pub fn normalize(
         self,
         mut validator_key_cache: Option<&mut ValidatorKeyCache>,
         keystore_storage: &ValidatorKeyCache,
+        dedicated_executor: &DedicatedExecutor,
     ) -> Result<Vec<(PublicKeyBytes, Arc<SecretKey>, KeyOrigin)>> {
         // Collect all passwords and keystores first.
         // They may be used to load secret keys from the cache.
         // Secret keys are decrypted later.
-        let keystores_with_passwords = match self {
-            Self::Keystores {
-                keystore_and_password_paths,
-            } => keystore_and_password_paths,
-            Self::KeystoreDirectory {
-                keystore_dir,
-                keystore_password_file,
-            } => Self::keymap_from_paths(keystore_dir, keystore_password_file)?,
-        }
-        .into_par_iter()
-        .map(|(keystore_path, password_path)| {
-            let password = Zeroizing::new(fs_err::read(password_path)?);
-            let normalized_password = eip_2335::normalize_password(password.as_slice())?;
-            let keystore_bytes = Zeroizing::new(fs_err::read(keystore_path)?);
-            let keystore = serde_json::from_slice::<Keystore>(keystore_bytes.as_slice())?;
-            Ok((keystore, normalized_password))
-        })
-        .collect::<Result<Vec<_>>>()?;
+        let keystores_with_passwords = self.load_keystores_with_passwords()?;
+        let keystore_count = keystores_with_passwords.len();
+        let decryption_start = Instant::now();
 
         // Collect all passwords for decrypting the cache.
         let passwords = keystores_with_passwords
@@ -111,30 +172,39 @@ impl Validators {
             }
         }
 
+        // Decrypt keystores missing from the cache on the dedicated executor.
+        // Key derivation in EIP-2335 keystores is deliberately slow, so each keystore gets a task.
         let keypairs =
             keystores_with_passwords
-                .into_par_iter()
+                .into_iter()
                 .map(|(keystore, normalized_password)| {
                     let uuid = keystore.uuid();
 
-                    let keypair = validator_key_cache
+                    let cached_keypair = validator_key_cache
                         .as_ref()
-                        .and_then(|cache| cache.get(uuid))
-                        .map(Ok::<_, Error>)
-                        .unwrap_or_else(|| {
-                            let secret_key = keystore
-                                .decrypt(normalized_password.as_str())?
-                                .try_conv::<SecretKey>()?
-                                .pipe(Arc::new);
-
-                            let public_key = secret_key.to_public_key().into();
-
-                            Ok((public_key, secret_key))
-                        })?;
-
-                    Ok((uuid, normalized_password, keypair))
+                        .and_then(|cache| cache.get(uuid));
+
+                    async move {
+                        let keypair = match cached_keypair {
+                            Some(keypair) => keypair,
+                            None => {
+                                let decryption =
+                                    decrypt_keystore(keystore, normalized_password.clone());
+
+                                dedicated_executor
+                                    .spawn(decryption)
+                                    .await
+                                    .map_err(Error::msg)
+                                    .context("keystore decryption task failed")??
+                            }
+                        };
+
+                        Ok::<_, Error>((uuid, normalized_password, keypair))
+                    }
                 })
-                .collect::<Result<Vec<_>>>()?
+                .collect::<FuturesOrdered<_>>()
+                .try_collect::<Vec<_>>()
+                .pipe(futures::executor::block_on)?
                 .into_iter()
                 .map(|(uuid, normalized_password, (public_key, secret_key))| {
                     if let Some(cache) = validator_key_cache.as_mut() {
@@ -153,6 +223,32 @@ impl Validators {
                 }))
                 .collect();
 
+        if keystore_count > 0 {
+            info!(
+                "loaded {keystore_count} keystore(s) in {:?}",
+                decryption_start.elapsed(),
+            );
+        }
+
         Ok(keypairs)
     }
 }
+
+async fn decrypt_keystore(
+    keystore: Keystore,
+    normalized_password: Zeroizing<String>,
+) -> Result<(PublicKeyBytes, Arc<SecretKey>)> {
+    let secret_key = keystore
+        .decrypt(normalized_password.as_str())?
+        .try_conv::<SecretKey>()?
+        .pipe(Arc::new);
+
+    let public_key = secret_key.to_public_key().into();
+
+    Ok((public_key, secret_key))
+}
+
+fn read_keystore(keystore_path: impl AsRef<Path>) -> Result<Keystore> {
+    let keystore_bytes = Zeroizing::new(fs_err::read(keystore_path.as_ref())?);
+    serde_json::from_slice(keystore_bytes.as_slice()).map_err(Into::into)
+}
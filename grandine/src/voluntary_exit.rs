@@ -0,0 +1,186 @@
+use std::io::{BufRead as _, Write as _};
+
+use anyhow::{ensure, Result};
+use bls::PublicKeyBytes;
+use clock::Tick;
+use helper_functions::{misc, signing_domains::SigningDomains};
+use log::info;
+use reqwest::Url;
+use signer::{ForkInfo, Signer, SigningMessage};
+use thiserror::Error;
+use types::{
+    config::Config as ChainConfig,
+    phase0::{
+        containers::{Fork, SignedVoluntaryExit, VoluntaryExit},
+        primitives::{Epoch, ValidatorIndex},
+    },
+    preset::Preset,
+    traits::BeaconState,
+};
+
+const CONFIRMATION_PHRASE: &str = "Exit my validator";
+
+/// Signs a [`VoluntaryExit`] and optionally submits it to a beacon node.
+///
+/// The key is looked up in `signer`, so it can come from keystores or from Web3Signer.
+pub async fn exit_validator<P: Preset>(
+    chain_config: &ChainConfig,
+    genesis_state: &impl BeaconState<P>,
+    signer: &Signer,
+    public_key: PublicKeyBytes,
+    validator_index: ValidatorIndex,
+    epoch: Option<Epoch>,
+    beacon_node_url: Option<Url>,
+) -> Result<()> {
+    ensure!(
+        signer.has_key(public_key),
+        Error::KeyNotFound { public_key }
+    );
+
+    let genesis_validators_root = genesis_state.genesis_validators_root();
+    let current_slot = Tick::current(chain_config, genesis_state.genesis_time())?.slot;
+    let current_phase = chain_config.phase_at_slot::<P>(current_slot);
+    let current_epoch = misc::compute_epoch_at_slot::<P>(current_slot);
+    let epoch = epoch.unwrap_or(current_epoch);
+
+    let voluntary_exit = VoluntaryExit {
+        epoch,
+        validator_index,
+    };
+
+    let signing_domains = SigningDomains::<P>::new(chain_config, genesis_validators_root);
+    let fork_version = signing_domains.voluntary_exit_fork_version(current_phase, epoch);
+    let signing_root = signing_domains.voluntary_exit_signing_root(&voluntary_exit, current_phase);
+
+    // Web3Signer computes the domain from `fork_info` itself.
+    // Using the same version on both sides of the fork makes it pick `fork_version`.
+    let fork_info = ForkInfo::new(
+        Fork {
+            previous_version: fork_version,
+            current_version: fork_version,
+            epoch,
+        },
+        genesis_validators_root,
+    );
+
+    // The exit queue may delay the exit further, so these are only lower bounds.
+    let exit_epoch = misc::compute_activation_exit_epoch::<P>(current_epoch.max(epoch));
+    let withdrawable_epoch = exit_epoch + chain_config.min_validator_withdrawability_delay;
+
+    info!(
+        "about to exit validator {validator_index} ({public_key:?}) on {} \
+         (exit message epoch: {epoch}, earliest exit epoch: {exit_epoch}, \
+         earliest withdrawable epoch: {withdrawable_epoch}); \
+         exits are irreversible and the validator will not be able to rejoin",
+        chain_config.config_name,
+    );
+
+    confirm()?;
+
+    let signature = signer
+        .sign(
+            SigningMessage::<P>::VoluntaryExit(voluntary_exit),
+            signing_root,
+            Some(fork_info),
+            public_key,
+        )
+        .await?
+        .into();
+
+    let signed_voluntary_exit = SignedVoluntaryExit {
+        message: voluntary_exit,
+        signature,
+    };
+
+    info!(
+        "signed voluntary exit: {}",
+        serde_json::to_string(&signed_voluntary_exit)?,
+    );
+
+    if let Some(beacon_node_url) = beacon_node_url {
+        let url = voluntary_exits_url(&beacon_node_url)?;
+
+        signer
+            .client()
+            .post(url)
+            .json(&signed_voluntary_exit)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        info!("voluntary exit submitted to {beacon_node_url}");
+    }
+
+    Ok(())
+}
+
+// `Url::join` replaces the last path segment unless the base ends with a slash and discards the
+// whole path if the joined path is absolute, which would break beacon nodes behind a path prefix.
+fn voluntary_exits_url(beacon_node_url: &Url) -> Result<Url> {
+    let mut base_url = beacon_node_url.clone();
+
+    if !base_url.path().ends_with('/') {
+        base_url.set_path(&format!("{}/", base_url.path()));
+    }
+
+    base_url
+        .join("eth/v1/beacon/pool/voluntary_exits")
+        .map_err(Into::into)
+}
+
+fn confirm() -> Result<()> {
+    let mut stdout = std::io::stdout();
+
+    write!(stdout, "Type \"{CONFIRMATION_PHRASE}\" to confirm: ")?;
+    stdout.flush()?;
+
+    let mut input = String::new();
+    std::io::stdin().lock().read_line(&mut input)?;
+
+    ensure!(input.trim() == CONFIRMATION_PHRASE, Error::NotConfirmed);
+
+    Ok(())
+}
+
+#[derive(Debug, Error)]
+enum Error {
+    #[error("no key found for {public_key:?}; load it from a keystore or Web3Signer")]
+    KeyNotFound { public_key: PublicKeyBytes },
+    #[error("voluntary exit was not confirmed")]
+    NotConfirmed,
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    #[test_case(
+        "http://localhost:5052",
+        "http://localhost:5052/eth/v1/beacon/pool/voluntary_exits";
+        "root"
+    )]
+    #[test_case(
+        "http://localhost:5052/",
+        "http://localhost:5052/eth/v1/beacon/pool/voluntary_exits";
+        "root with trailing slash"
+    )]
+    #[test_case(
+        "https://example.com/beacon",
+        "https://example.com/beacon/eth/v1/beacon/pool/voluntary_exits";
+        "path prefix"
+    )]
+    #[test_case(
+        "https://example.com/nodes/beacon/",
+        "https://example.com/nodes/beacon/eth/v1/beacon/pool/voluntary_exits";
+        "path prefix with trailing slash"
+    )]
+    fn voluntary_exits_url_keeps_base_path(beacon_node_url: &str, expected: &str) -> Result<()> {
+        let url = voluntary_exits_url(&beacon_node_url.parse()?)?;
+
+        assert_eq!(url.as_str(), expected);
+
+        Ok(())
+    }
+}
@@ -186,6 +186,34 @@ pub fn index_of_public_key<P: Preset>(
         .copied()
 }
 
+/// Like [`index_of_public_key`], but looks up `public_key` in `validator_indices` first.
+///
+/// `validator_indices` may have been built from any state. An index found in it is only returned
+/// if the validator at that index in `state` has the same public key. Public keys are unique
+/// within a validator registry, so the result is correct even if `state` does not descend from the
+/// state `validator_indices` was built from. The cache of `state` is only initialized on a miss.
+#[must_use]
+pub fn index_of_public_key_with_cache<P: Preset>(
+    state: &(impl BeaconState<P> + ?Sized),
+    validator_indices: &HashMap<PublicKeyBytes, ValidatorIndex>,
+    public_key: PublicKeyBytes,
+) -> Option<ValidatorIndex> {
+    let cached_index = validator_indices.get(&public_key).copied();
+
+    if let Some(validator_index) = cached_index {
+        let in_state = state
+            .validators()
+            .get(validator_index)
+            .is_ok_and(|validator| validator.pubkey.to_bytes() == public_key);
+
+        if in_state {
+            return Some(validator_index);
+        }
+    }
+
+    index_of_public_key(state, public_key)
+}
+
 pub fn get_or_init_validator_indices<P: Preset>(
     state: &(impl BeaconState<P> + ?Sized),
     _report_cache_miss: bool,
@@ -808,6 +836,8 @@ pub fn initialize_shuffled_indices<'attestations, P: Preset>(
 
 #[cfg(test)]
 mod tests {
+    use ssz::PersistentList;
+    use try_from_iterator::TryFromIterator as _;
     use types::{
         phase0::{beacon_state::BeaconState as Phase0BeaconState, containers::Validator},
         preset::Minimal,
@@ -930,4 +960,40 @@ mod tests {
 
         itertools::assert_equal(indices, [0, 2]);
     }
+
+    #[test]
+    fn test_index_of_public_key_with_cache_verifies_cached_indices() {
+        let state_with_public_keys = |bytes: &[u8]| Phase0BeaconState::<Minimal> {
+            validators: bytes
+                .iter()
+                .map(|byte| Validator {
+                    pubkey: PublicKeyBytes::repeat_byte(*byte).into(),
+                    ..Validator::default()
+                })
+                .pipe(PersistentList::try_from_iter)
+                .expect("length is under maximum"),
+            ..Phase0BeaconState::default()
+        };
+
+        let cached_state = state_with_public_keys(&[1, 2]);
+        let validator_indices = get_or_init_validator_indices(&cached_state, false);
+
+        // The first validator is deliberately different from the one in the cached state.
+        // This would never happen in a descendant, but the cache should not be trusted blindly.
+        let state = state_with_public_keys(&[3, 2, 4]);
+
+        let index_of = |byte| {
+            index_of_public_key_with_cache(
+                &state,
+                validator_indices,
+                PublicKeyBytes::repeat_byte(byte),
+            )
+        };
+
+        assert_eq!(index_of(1), None);
+        assert_eq!(index_of(2), Some(1));
+        assert_eq!(index_of(3), Some(0));
+        assert_eq!(index_of(4), Some(2));
+        assert_eq!(index_of(5), None);
+    }
 }
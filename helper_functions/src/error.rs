@@ -23,6 +23,8 @@ pub(crate) enum Error {
     EpochOverflow,
     #[error("failed to select proposer")]
     FailedToSelectProposer,
+    #[error("generalized index does not refer to a node supported by proof construction")]
+    GeneralizedIndexOutOfRange,
     #[error("no validators are active")]
     NoActiveValidators,
     #[error("permutated prefix maximum overflowed")]
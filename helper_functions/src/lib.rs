@@ -8,6 +8,7 @@ pub mod mutators;
 pub mod phase0;
 pub mod predicates;
 pub mod signing;
+pub mod signing_domains;
 pub mod slot_report;
 pub mod verifier;
 
@@ -10,11 +10,11 @@ use hashing::ZERO_HASHES;
 use itertools::{izip, Itertools as _};
 use ssz::{BitVector, ContiguousVector, MerkleElements, MerkleTree, SszHash};
 use tap::{Pipe as _, TryConv as _};
-use typenum::Unsigned as _;
+use typenum::{Unsigned as _, U5};
 use types::{
     altair::{consts::SyncCommitteeSubnetCount, primitives::SyncCommitteePeriod},
     cache::PackedIndices,
-    combined::SignedBeaconBlock,
+    combined::{BeaconState as CombinedBeaconState, SignedBeaconBlock},
     config::Config,
     deneb::{
         consts::{BlobSidecarSubnetCount, VERSIONED_HASH_VERSION_KZG},
@@ -34,7 +34,8 @@ use types::{
     },
     preset::Preset,
     traits::{
-        BeaconState, PostAltairBeaconState, PostDenebBeaconBlockBody, SignedBeaconBlock as _,
+        BeaconState, PostAltairBeaconState, PostCapellaBeaconState, PostDenebBeaconBlockBody,
+        SignedBeaconBlock as _,
     },
 };
 
@@ -409,6 +410,214 @@ pub fn kzg_commitment_inclusion_proof<P: Preset>(
     Ok(proof)
 }
 
+// Capella and Deneb states have 28 fields, so their field roots form a tree of depth 5.
+// `historical_summaries` is the last field, which makes its index 27.
+pub fn historical_summaries_proof<P: Preset>(
+    state: &(impl PostCapellaBeaconState<P> + ?Sized),
+) -> ContiguousVector<H256, U5> {
+    let chunks = [
+        state.genesis_time().hash_tree_root(),
+        state.genesis_validators_root(),
+        state.slot().hash_tree_root(),
+        state.fork().hash_tree_root(),
+        state.latest_block_header().hash_tree_root(),
+        state.block_roots().hash_tree_root(),
+        state.state_roots().hash_tree_root(),
+        state.historical_roots().hash_tree_root(),
+        state.eth1_data().hash_tree_root(),
+        state.eth1_data_votes().hash_tree_root(),
+        state.eth1_deposit_index().hash_tree_root(),
+        state.validators().hash_tree_root(),
+        state.balances().hash_tree_root(),
+        state.randao_mixes().hash_tree_root(),
+        state.slashings().hash_tree_root(),
+        state.previous_epoch_participation().hash_tree_root(),
+        state.current_epoch_participation().hash_tree_root(),
+        state.justification_bits().hash_tree_root(),
+        state.previous_justified_checkpoint().hash_tree_root(),
+        state.current_justified_checkpoint().hash_tree_root(),
+        state.finalized_checkpoint().hash_tree_root(),
+        state.inactivity_scores().hash_tree_root(),
+        state.current_sync_committee().hash_tree_root(),
+        state.next_sync_committee().hash_tree_root(),
+        state.latest_execution_payload_header().hash_tree_root(),
+        state.next_withdrawal_index().hash_tree_root(),
+        state.next_withdrawal_validator_index().hash_tree_root(),
+        state.historical_summaries().hash_tree_root(),
+    ];
+
+    let field_count = chunks.len();
+
+    let proof_with_length = MerkleTree::<U5>::default()
+        .extend_and_construct_proofs(chunks, 0..field_count, field_count - 1..field_count)
+        .exactly_one()
+        .ok()
+        .expect("exactly one proof is requested");
+
+    // The last node mixes in the number of chunks, which is not part of a container proof.
+    let mut proof = ContiguousVector::default();
+    proof.copy_from_slice(&proof_with_length[..U5::USIZE]);
+    proof
+}
+
+/// Returns the node at `generalized_index` in the Merkle tree of `state` along with its branch.
+///
+/// Supported nodes are those at or above the roots of individual fields and those in the trees
+/// of the fixed-length root vectors (`block_roots`, `state_roots` and `randao_mixes`).
+/// States of all phases have at most 32 fields, so the generalized index of a field is
+/// `32 + field_index` and the branch of a field is 5 nodes long.
+/// Nodes inside lists and other composite fields are not supported.
+pub fn beacon_state_proof<P: Preset>(
+    state: &CombinedBeaconState<P>,
+    generalized_index: u64,
+) -> Result<(H256, Vec<H256>)> {
+    let tree_depth = U5::USIZE;
+
+    ensure!(generalized_index > 0, Error::GeneralizedIndexOutOfRange);
+
+    let depth = generalized_index.ilog2().try_conv::<usize>()?;
+
+    if depth <= tree_depth {
+        return merkle_proof(
+            beacon_state_field_roots(state),
+            tree_depth,
+            generalized_index,
+        );
+    }
+
+    let subtree_depth = depth - tree_depth;
+    let field_generalized_index = generalized_index >> subtree_depth;
+
+    let elements = match field_generalized_index - (1 << tree_depth) {
+        5 => state.block_roots().into_iter().copied().collect_vec(),
+        6 => state.state_roots().into_iter().copied().collect_vec(),
+        13 => state.randao_mixes().into_iter().copied().collect_vec(),
+        _ => return Err(Error::GeneralizedIndexOutOfRange.into()),
+    };
+
+    // Root vectors have lengths that are powers of 2 and store one element per chunk.
+    let vector_depth = elements.len().ilog2().try_conv::<usize>()?;
+
+    ensure!(
+        subtree_depth <= vector_depth,
+        Error::GeneralizedIndexOutOfRange,
+    );
+
+    let subtree_generalized_index =
+        (1 << subtree_depth) | (generalized_index & ((1 << subtree_depth) - 1));
+
+    let (node, mut branch) = merkle_proof(elements, vector_depth, subtree_generalized_index)?;
+    let (_, field_branch) = merkle_proof(
+        beacon_state_field_roots(state),
+        tree_depth,
+        field_generalized_index,
+    )?;
+
+    branch.extend(field_branch);
+
+    Ok((node, branch))
+}
+
+fn merkle_proof(
+    mut leaves: Vec<H256>,
+    tree_depth: usize,
+    generalized_index: u64,
+) -> Result<(H256, Vec<H256>)> {
+    ensure!(
+        (1..1 << (tree_depth + 1)).contains(&generalized_index),
+        Error::GeneralizedIndexOutOfRange,
+    );
+
+    leaves.resize(1 << tree_depth, H256::zero());
+
+    let mut layers = vec![leaves];
+
+    while let Some(layer) = layers.last().filter(|layer| layer.len() > 1) {
+        let parents = layer
+            .iter()
+            .tuples()
+            .map(|(left, right)| hashing::hash_256_256(*left, *right))
+            .collect();
+
+        layers.push(parents);
+    }
+
+    let depth = generalized_index.ilog2().try_conv::<usize>()?;
+    let mut position = (generalized_index - (1 << depth)).try_conv::<usize>()?;
+    let node = layers[tree_depth - depth][position];
+    let mut branch = Vec::with_capacity(depth);
+
+    for layer in &layers[tree_depth - depth..tree_depth] {
+        branch.push(layer[position ^ 1]);
+        position /= 2;
+    }
+
+    Ok((node, branch))
+}
+
+fn beacon_state_field_roots<P: Preset>(state: &CombinedBeaconState<P>) -> Vec<H256> {
+    let mut roots = vec![
+        state.genesis_time().hash_tree_root(),
+        state.genesis_validators_root(),
+        state.slot().hash_tree_root(),
+        state.fork().hash_tree_root(),
+        state.latest_block_header().hash_tree_root(),
+        state.block_roots().hash_tree_root(),
+        state.state_roots().hash_tree_root(),
+        state.historical_roots().hash_tree_root(),
+        state.eth1_data().hash_tree_root(),
+        state.eth1_data_votes().hash_tree_root(),
+        state.eth1_deposit_index().hash_tree_root(),
+        state.validators().hash_tree_root(),
+        state.balances().hash_tree_root(),
+        state.randao_mixes().hash_tree_root(),
+        state.slashings().hash_tree_root(),
+    ];
+
+    if let CombinedBeaconState::Phase0(state) = state {
+        roots.extend([
+            state.previous_epoch_attestations.hash_tree_root(),
+            state.current_epoch_attestations.hash_tree_root(),
+        ]);
+    }
+
+    if let Some(state) = state.post_altair() {
+        roots.extend([
+            state.previous_epoch_participation().hash_tree_root(),
+            state.current_epoch_participation().hash_tree_root(),
+        ]);
+    }
+
+    roots.extend([
+        state.justification_bits().hash_tree_root(),
+        state.previous_justified_checkpoint().hash_tree_root(),
+        state.current_justified_checkpoint().hash_tree_root(),
+        state.finalized_checkpoint().hash_tree_root(),
+    ]);
+
+    if let Some(state) = state.post_altair() {
+        roots.extend([
+            state.inactivity_scores().hash_tree_root(),
+            state.current_sync_committee().hash_tree_root(),
+            state.next_sync_committee().hash_tree_root(),
+        ]);
+    }
+
+    if let Some(state) = state.post_bellatrix() {
+        roots.push(state.latest_execution_payload_header().hash_tree_root());
+    }
+
+    if let Some(state) = state.post_capella() {
+        roots.extend([
+            state.next_withdrawal_index().hash_tree_root(),
+            state.next_withdrawal_validator_index().hash_tree_root(),
+            state.historical_summaries().hash_tree_root(),
+        ]);
+    }
+
+    roots
+}
+
 #[must_use]
 pub fn blob_serve_range_slot<P: Preset>(config: &Config, current_slot: Slot) -> Slot {
     let current_epoch = compute_epoch_at_slot::<P>(current_slot);
@@ -458,6 +667,8 @@ mod tests {
     use itertools::iproduct;
     use nonzero_ext::nonzero;
     use types::{
+        capella::beacon_state::BeaconState as CapellaBeaconState,
+        deneb::beacon_state::BeaconState as DenebBeaconState,
         nonstandard::RelativeEpoch,
         phase0::{
             beacon_state::BeaconState as Phase0BeaconState,
@@ -577,4 +788,99 @@ mod tests {
             compute_subscribed_subnets::<Minimal>(node_id, &config, epoch).ok();
         }
     }
+
+    #[test]
+    fn historical_summaries_proof_is_valid_against_state_root() {
+        let state = CapellaBeaconState::<Minimal> {
+            slot: 1234,
+            next_withdrawal_index: 5,
+            ..CapellaBeaconState::default()
+        };
+
+        let proof = historical_summaries_proof(&state);
+
+        assert!(crate::predicates::is_valid_merkle_branch(
+            state.historical_summaries.hash_tree_root(),
+            proof.iter().copied(),
+            27,
+            state.hash_tree_root(),
+        ));
+    }
+
+    #[test]
+    fn beacon_state_proof_is_valid_against_state_root_for_all_nodes() -> Result<()> {
+        let states = [
+            CombinedBeaconState::<Minimal>::Phase0(Phase0BeaconState::default().into()),
+            CombinedBeaconState::Deneb(
+                DenebBeaconState {
+                    slot: 1234,
+                    next_withdrawal_index: 5,
+                    ..DenebBeaconState::default()
+                }
+                .into(),
+            ),
+        ];
+
+        for state in states {
+            for generalized_index in 1..64 {
+                let (node, branch) = beacon_state_proof(&state, generalized_index)?;
+                let index = generalized_index - (1 << generalized_index.ilog2());
+
+                assert!(crate::predicates::is_valid_merkle_branch(
+                    node,
+                    branch,
+                    index,
+                    state.hash_tree_root(),
+                ));
+            }
+
+            assert!(beacon_state_proof(&state, 0).is_err());
+            assert!(beacon_state_proof(&state, 64).is_err());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn beacon_state_proof_is_valid_against_state_root_for_root_vector_nodes() -> Result<()> {
+        let mut state = DenebBeaconState::<Minimal>::default();
+
+        let vector_length = <Minimal as Preset>::SlotsPerHistoricalRoot::U64;
+
+        for index in 0..vector_length {
+            *state.block_roots.mod_index_mut(index) = H256::from_low_u64_be(index + 1);
+            *state.randao_mixes.mod_index_mut(index) = H256::from_low_u64_be(index + 1000);
+        }
+
+        let state = CombinedBeaconState::Deneb(state.into());
+
+        for field_generalized_index in [37, 38, 45] {
+            for depth in 1..=vector_length.ilog2() {
+                for index in 0..1 << depth {
+                    let generalized_index = (field_generalized_index << depth) | index;
+                    let (node, branch) = beacon_state_proof(&state, generalized_index)?;
+
+                    assert_eq!(branch.len(), 5 + depth as usize);
+
+                    assert!(crate::predicates::is_valid_merkle_branch(
+                        node,
+                        branch,
+                        generalized_index - (1 << generalized_index.ilog2()),
+                        state.hash_tree_root(),
+                    ));
+                }
+            }
+        }
+
+        let element_generalized_index = 37 * vector_length + 3;
+        let (node, _) = beacon_state_proof(&state, element_generalized_index)?;
+
+        assert_eq!(node, H256::from_low_u64_be(4));
+
+        // Nodes below the leaves of a vector and nodes inside other fields are not supported.
+        assert!(beacon_state_proof(&state, 37 * vector_length * 2).is_err());
+        assert!(beacon_state_proof(&state, 43 * 2).is_err());
+
+        Ok(())
+    }
 }
@@ -0,0 +1,248 @@
+use core::marker::PhantomData;
+
+use enum_iterator::Sequence as _;
+use types::{
+    config::Config,
+    nonstandard::Phase,
+    phase0::{
+        consts::{DOMAIN_VOLUNTARY_EXIT, GENESIS_EPOCH},
+        containers::{Fork, VoluntaryExit},
+        primitives::{Domain, DomainType, Epoch, Slot, Version, H256},
+    },
+    preset::Preset,
+    traits::BeaconState,
+};
+
+use crate::{
+    misc,
+    signing::{SignForSingleFork, SignForSingleForkAtSlot},
+};
+
+/// Computes signing domains from the fork schedule in [`Config`] rather than from the fork of a
+/// state.
+///
+/// [`accessors::get_domain`] takes fork versions from `state.fork()`, which only knows about forks
+/// the state has been processed through. Signing messages for later epochs with such a state
+/// (e.g., selection proofs for the next epoch signed using the head state in the last epoch before
+/// a fork) produces domains of the previous fork, which are rejected once the fork happens.
+///
+/// All messages signed by validators should compute signing roots with this.
+/// Verification of messages from other nodes (in gossip validation, API submissions and state
+/// transitions) keeps using [`SignForSingleFork::signing_root`] with a state advanced to the slot
+/// of the message. The fork of such a state is the one the spec requires, so the two agree.
+///
+/// [`accessors::get_domain`]: crate::accessors::get_domain
+#[derive(Clone, Copy)]
+pub struct SigningDomains<'config, P: Preset> {
+    config: &'config Config,
+    genesis_validators_root: H256,
+    phantom: PhantomData<P>,
+}
+
+impl<'config, P: Preset> SigningDomains<'config, P> {
+    #[must_use]
+    pub const fn new(config: &'config Config, genesis_validators_root: H256) -> Self {
+        Self {
+            config,
+            genesis_validators_root,
+            phantom: PhantomData,
+        }
+    }
+
+    #[must_use]
+    pub fn for_state(config: &'config Config, state: &(impl BeaconState<P> + ?Sized)) -> Self {
+        Self::new(config, state.genesis_validators_root())
+    }
+
+    #[must_use]
+    pub const fn genesis_validators_root(&self) -> H256 {
+        self.genesis_validators_root
+    }
+
+    #[must_use]
+    pub fn phase_at_epoch(&self, epoch: Epoch) -> Phase {
+        let slot = misc::compute_start_slot_at_epoch::<P>(epoch);
+        self.config.phase_at_slot::<P>(slot)
+    }
+
+    /// Returns the fork that a state in `epoch` would have according to the fork schedule.
+    ///
+    /// Remote signers compute domains from this, so it must match the fork version used locally.
+    #[must_use]
+    pub fn fork(&self, epoch: Epoch) -> Fork {
+        let current_phase = self.phase_at_epoch(epoch);
+        let fork_epoch = self.config.fork_epoch(current_phase);
+
+        // Genesis states start with both versions set to the version of the genesis phase.
+        let previous_phase = current_phase
+            .previous()
+            .filter(|_| fork_epoch > GENESIS_EPOCH)
+            .unwrap_or(current_phase);
+
+        Fork {
+            previous_version: self.config.version(previous_phase),
+            current_version: self.config.version(current_phase),
+            epoch: fork_epoch,
+        }
+    }
+
+    #[must_use]
+    pub fn fork_version(&self, epoch: Epoch) -> Version {
+        self.config.version(self.phase_at_epoch(epoch))
+    }
+
+    #[must_use]
+    pub fn domain(&self, domain_type: DomainType, epoch: Epoch) -> Domain {
+        misc::compute_domain(
+            self.config,
+            domain_type,
+            Some(self.fork_version(epoch)),
+            Some(self.genesis_validators_root),
+        )
+    }
+
+    /// Like [`SignForSingleFork::signing_root`], but with the domain of the fork scheduled for
+    /// the epoch of `message`.
+    ///
+    /// Voluntary exits are signed with a fixed fork version starting with Deneb.
+    /// Use [`Self::voluntary_exit_signing_root`] for them.
+    #[must_use]
+    pub fn signing_root<M: SignForSingleFork<P> + ?Sized>(&self, message: &M) -> H256 {
+        let domain = self.domain(M::DOMAIN_TYPE, message.epoch());
+        misc::compute_signing_root(message, domain)
+    }
+
+    /// Like [`SignForSingleForkAtSlot::signing_root`], but with the domain of the fork scheduled
+    /// for `slot`.
+    #[must_use]
+    pub fn signing_root_at_slot<M: SignForSingleForkAtSlot<P> + ?Sized>(
+        &self,
+        message: &M,
+        slot: Slot,
+    ) -> H256 {
+        let epoch = misc::compute_epoch_at_slot::<P>(slot);
+        let domain = self.domain(M::DOMAIN_TYPE, epoch);
+        misc::compute_signing_root(message, domain)
+    }
+
+    /// Returns the fork version used to sign a [`VoluntaryExit`] on a chain in `current_phase`.
+    ///
+    /// Starting with Deneb, exits are always signed with the Capella fork version (see EIP-7044).
+    #[must_use]
+    pub fn voluntary_exit_fork_version(&self, current_phase: Phase, epoch: Epoch) -> Version {
+        if current_phase >= Phase::Deneb {
+            self.config.capella_fork_version
+        } else {
+            self.fork_version(epoch)
+        }
+    }
+
+    #[must_use]
+    pub fn voluntary_exit_signing_root(
+        &self,
+        voluntary_exit: &VoluntaryExit,
+        current_phase: Phase,
+    ) -> H256 {
+        let fork_version = self.voluntary_exit_fork_version(current_phase, voluntary_exit.epoch);
+
+        let domain = misc::compute_domain(
+            self.config,
+            DOMAIN_VOLUNTARY_EXIT,
+            Some(fork_version),
+            Some(self.genesis_validators_root),
+        );
+
+        misc::compute_signing_root(voluntary_exit, domain)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use types::{
+        phase0::{beacon_state::BeaconState as Phase0BeaconState, consts::DOMAIN_SELECTION_PROOF},
+        preset::Minimal,
+    };
+
+    use crate::accessors;
+
+    use super::*;
+
+    fn config() -> Config {
+        Config {
+            altair_fork_epoch: 2,
+            bellatrix_fork_epoch: 4,
+            ..Config::minimal()
+        }
+    }
+
+    #[test]
+    fn fork_follows_fork_schedule() {
+        let config = config();
+        let domains = SigningDomains::<Minimal>::new(&config, H256::repeat_byte(1));
+
+        assert_eq!(
+            domains.fork(1),
+            Fork {
+                previous_version: config.genesis_fork_version,
+                current_version: config.genesis_fork_version,
+                epoch: GENESIS_EPOCH,
+            },
+        );
+
+        assert_eq!(
+            domains.fork(3),
+            Fork {
+                previous_version: config.genesis_fork_version,
+                current_version: config.altair_fork_version,
+                epoch: 2,
+            },
+        );
+
+        assert_eq!(
+            domains.fork(10),
+            Fork {
+                previous_version: config.altair_fork_version,
+                current_version: config.bellatrix_fork_version,
+                epoch: 4,
+            },
+        );
+    }
+
+    #[test]
+    fn domain_uses_scheduled_fork_even_if_state_has_not_reached_it() {
+        let config = config();
+
+        // A Phase 0 state in the epoch before the Altair fork.
+        let state = Phase0BeaconState::<Minimal> {
+            slot: misc::compute_start_slot_at_epoch::<Minimal>(1),
+            fork: Fork {
+                previous_version: config.genesis_fork_version,
+                current_version: config.genesis_fork_version,
+                epoch: GENESIS_EPOCH,
+            },
+            ..Phase0BeaconState::default()
+        };
+
+        let domains = SigningDomains::for_state(&config, &state);
+
+        assert_eq!(
+            domains.domain(DOMAIN_SELECTION_PROOF, 1),
+            accessors::get_domain(&config, &state, DOMAIN_SELECTION_PROOF, Some(1)),
+        );
+
+        assert_ne!(
+            domains.domain(DOMAIN_SELECTION_PROOF, 2),
+            accessors::get_domain(&config, &state, DOMAIN_SELECTION_PROOF, Some(2)),
+        );
+
+        assert_eq!(
+            domains.domain(DOMAIN_SELECTION_PROOF, 2),
+            misc::compute_domain(
+                &config,
+                DOMAIN_SELECTION_PROOF,
+                Some(config.altair_fork_version),
+                Some(state.genesis_validators_root),
+            ),
+        );
+    }
+}
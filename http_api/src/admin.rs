@@ -0,0 +1,141 @@
+//! Operations for manual intervention during consensus incidents.
+//!
+//! These bypass the normal flow of payload statuses reported by the execution engine.
+//! Every successful call is logged along with the address of the client that made it.
+//! Diagnostics collected for postmortems of such incidents are served here as well.
+//! So are storage maintenance operations, which operators may need to run outside of the usual
+//! archival schedule.
+
+use std::{net::SocketAddr, sync::Arc};
+
+use axum::{
+    extract::{ConnectInfo, State},
+    Json,
+};
+use eth1_api::ApiController;
+use fork_choice_control::{ArchivalProgress, Wait};
+use log::warn;
+use serde::Deserialize;
+use types::{
+    phase0::{
+        containers::Checkpoint,
+        primitives::{Epoch, H256},
+    },
+    preset::Preset,
+};
+use validator::{ProposalReport, ProposalReports};
+
+use crate::{error::Error, extractors::EthJson};
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BlockRootBody {
+    block_root: H256,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EpochBody {
+    #[serde(with = "serde_utils::string_or_native")]
+    epoch: Epoch,
+}
+
+/// `POST /admin/invalidate_block`
+pub async fn invalidate_block<P: Preset, W: Wait>(
+    State(controller): State<ApiController<P, W>>,
+    ConnectInfo(remote): ConnectInfo<SocketAddr>,
+    EthJson(body): EthJson<BlockRootBody>,
+) -> Result<(), Error> {
+    let BlockRootBody { block_root } = body;
+
+    controller
+        .invalidate_block(block_root)
+        .map_err(Error::AdminOperationRejected)?;
+
+    warn!("block {block_root:?} and its descendants invalidated by {remote}");
+
+    Ok(())
+}
+
+/// `POST /admin/reanchor`
+pub async fn reanchor<P: Preset, W: Wait>(
+    State(controller): State<ApiController<P, W>>,
+    ConnectInfo(remote): ConnectInfo<SocketAddr>,
+    EthJson(checkpoint): EthJson<Checkpoint>,
+) -> Result<(), Error> {
+    controller
+        .reanchor(checkpoint)
+        .map_err(Error::AdminOperationRejected)?;
+
+    warn!("fork choice re-anchored on checkpoint {checkpoint:?} by {remote}");
+
+    Ok(())
+}
+
+/// `POST /admin/reverify_payload`
+pub async fn reverify_payload<P: Preset, W: Wait>(
+    State(controller): State<ApiController<P, W>>,
+    ConnectInfo(remote): ConnectInfo<SocketAddr>,
+    EthJson(body): EthJson<BlockRootBody>,
+) -> Result<(), Error> {
+    let BlockRootBody { block_root } = body;
+
+    controller
+        .reverify_payload(block_root)
+        .map_err(Error::AdminOperationRejected)?;
+
+    warn!("payload of block {block_root:?} submitted for reverification by {remote}");
+
+    Ok(())
+}
+
+/// `GET /admin/missed_proposals`
+///
+/// Returns reports of the most recent proposals own validators failed to publish, oldest first.
+pub async fn missed_proposals(
+    State(proposal_reports): State<Arc<ProposalReports>>,
+) -> Json<Vec<ProposalReport>> {
+    Json(proposal_reports.recent())
+}
+
+/// `POST /admin/archive`
+///
+/// Saves finalized blocks and the anchor state without waiting for the next archival epoch.
+pub async fn archive<P: Preset, W: Wait>(
+    State(controller): State<ApiController<P, W>>,
+    ConnectInfo(remote): ConnectInfo<SocketAddr>,
+) -> Result<(), Error> {
+    controller
+        .force_archival()
+        .map_err(Error::AdminOperationRejected)?;
+
+    warn!("archival cycle forced by {remote}");
+
+    Ok(())
+}
+
+/// `POST /admin/prune`
+///
+/// Deletes archival states from before the epoch in the request body.
+pub async fn prune<P: Preset, W: Wait>(
+    State(controller): State<ApiController<P, W>>,
+    ConnectInfo(remote): ConnectInfo<SocketAddr>,
+    EthJson(body): EthJson<EpochBody>,
+) -> Result<(), Error> {
+    let EpochBody { epoch } = body;
+
+    controller
+        .prune_storage(epoch)
+        .map_err(Error::AdminOperationRejected)?;
+
+    warn!("pruning of storage up to epoch {epoch} started by {remote}");
+
+    Ok(())
+}
+
+/// `GET /admin/archival_progress`
+pub async fn archival_progress<P: Preset, W: Wait>(
+    State(controller): State<ApiController<P, W>>,
+) -> Json<ArchivalProgress> {
+    Json(controller.archival_progress())
+}
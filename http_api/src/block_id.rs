@@ -19,6 +19,7 @@ pub fn block<P: Preset, W: Wait>(
         BlockId::Head => Some(controller.head_block()),
         BlockId::Genesis => Some(WithStatus::valid_and_finalized(genesis_provider.block())),
         BlockId::Finalized => Some(controller.last_finalized_block()),
+        BlockId::Justified => Some(controller.justified_block()?),
         BlockId::Slot(slot) => controller
             .block_by_slot(slot)?
             .map(|with_status| with_status.map(|block_with_root| block_with_root.block)),
@@ -38,6 +39,7 @@ pub fn block_root<P: Preset, W: Wait>(
             genesis_provider.block_root(),
         )),
         BlockId::Finalized => Some(controller.last_finalized_block_root()),
+        BlockId::Justified => Some(controller.justified_block_root()?),
         BlockId::Slot(slot) => controller
             .block_by_slot(slot)?
             .map(|with_status| with_status.map(|with_status| with_status.root)),
@@ -1,26 +1,32 @@
 use core::future::Future;
-use std::{net::Ipv4Addr, sync::Arc};
+use std::{
+    net::{Ipv4Addr, SocketAddr},
+    sync::Arc,
+};
 
 use anyhow::Result;
 use bls::{PublicKeyBytes, SecretKey};
-use clock::Tick;
+use clock::{ClockDrift, SystemClock, Tick};
 use database::Database;
 use dedicated_executor::DedicatedExecutor;
 use deposit_tree::DepositTree;
 use enum_iterator::Sequence as _;
 use eth1::{Eth1Chain, Eth1Config};
-use eth1_api::{Eth1Api, Eth1ExecutionEngine, ExecutionService};
+use eth1_api::{Eth1Api, Eth1ExecutionEngine, MockExecutionService};
 use eth2_cache_utils::mainnet;
+use execution_engine::PayloadValidationStatus;
 use features::Feature;
 use fork_choice_control::{
     Controller, StateLoadStrategy, Storage, DEFAULT_ARCHIVAL_EPOCH_INTERVAL,
 };
-use fork_choice_store::{PayloadStatus, StoreConfig};
+use fork_choice_store::StoreConfig;
 use futures::{future::FutureExt as _, lock::Mutex, select_biased};
 use genesis::GenesisProvider;
 use keymanager::KeyManager;
 use liveness_tracker::LivenessTracker;
-use operation_pools::{AttestationAggPool, BlsToExecutionChangePool, SyncCommitteeAggPool};
+use operation_pools::{
+    AttestationAggPool, BlsToExecutionChangePool, PoolConfig, SyncCommitteeAggPool,
+};
 use p2p::{NetworkConfig, SubnetService, SyncToApi};
 use reqwest::Client;
 use signer::{KeyOrigin, Signer, Web3SignerConfig};
@@ -37,7 +43,7 @@ use types::{
     preset::{Mainnet, Minimal, Preset},
     traits::BeaconState as _,
 };
-use validator::{Validator, ValidatorChannels, ValidatorConfig};
+use validator::{DutiesCache, ProposalReports, Validator, ValidatorChannels, ValidatorConfig};
 
 use crate::{
     http_api_config::HttpApiConfig,
@@ -56,7 +62,7 @@ pub struct Context<P: Preset> {
     anchor_state: Arc<BeaconState<P>>,
     deposit_tree: Option<DepositTree>,
     extra_blocks: Vec<Arc<SignedBeaconBlock<P>>>,
-    payload_statuses: Vec<(ExecutionBlockHash, PayloadStatus)>,
+    payload_statuses: Vec<(ExecutionBlockHash, PayloadValidationStatus)>,
     validator_keys: Vec<(PublicKeyBytes, Arc<SecretKey>, KeyOrigin)>,
 }
 
@@ -66,6 +72,18 @@ impl<P: Preset> Context<P> {
             .unwrap_or_else(|error| panic!("{error:?}"))
     }
 
+    /// Records responses for `case` from a node synced to a real network at `live_address`.
+    ///
+    /// The recorded responses are then checked against the node started from `self`
+    /// to ensure the test case can be replayed without the live node.
+    pub fn record_case(self, case: Case, live_address: SocketAddr) {
+        block_on(async {
+            case.record(live_address).await?;
+            self.try_run_case(case, false).await
+        })
+        .unwrap_or_else(|error| panic!("{error:?}"))
+    }
+
     #[allow(clippy::too_many_lines)]
     async fn try_run_case(self, case: Case<'_>, update_responses: bool) -> Result<()> {
         Feature::ServeCostlyEndpoints.enable();
@@ -92,7 +110,9 @@ impl<P: Preset> Context<P> {
         let (fc_to_subnet_tx, fc_to_subnet_rx) = futures::channel::mpsc::unbounded();
         let (fc_to_sync_tx, fc_to_sync_rx) = futures::channel::mpsc::unbounded();
         let (fc_to_validator_tx, fc_to_validator_rx) = futures::channel::mpsc::unbounded();
+        let (_, http_api_shutdown_rx) = futures::channel::oneshot::channel();
         let (_, p2p_to_validator_rx) = futures::channel::mpsc::unbounded();
+        let (_, validator_shutdown_rx) = futures::channel::oneshot::channel();
         let (pool_to_api_tx, pool_to_api_rx) = futures::channel::mpsc::unbounded();
         let (pool_to_liveness_tx, pool_to_liveness_rx) = futures::channel::mpsc::unbounded();
         let (pool_to_p2p_tx, pool_to_p2p_rx) = futures::channel::mpsc::unbounded();
@@ -187,6 +207,12 @@ impl<P: Preset> Context<P> {
             core::iter::empty(),
         )?;
 
+        // Payloads without a scripted status are reported as `SYNCING`, leaving their blocks
+        // optimistic like they would be with an execution engine that is still syncing.
+        let execution_service =
+            MockExecutionService::new(controller.clone_arc(), execution_service_rx)
+                .with_statuses(payload_statuses);
+
         // TODO(feature/in-memory-db): Rephrase comment.
         // Payload statuses have to be submitted before blocks to ensure that blocks get saved to
         // the database when archiving. That is because the fork choice store does not attempt to
@@ -194,15 +220,7 @@ impl<P: Preset> Context<P> {
         // `mainnet/mainnet/epoch-244816` also works, but only due to a convenient race condition.
         // Payload statuses in that get delayed because blocks take so long to process.
         // Submitting payload statuses first ensures that they get delayed.
-        for (execution_block_hash, payload_status) in payload_statuses {
-            match payload_status {
-                PayloadStatus::Valid => controller.on_notified_valid_payload(execution_block_hash),
-                PayloadStatus::Invalid => {
-                    controller.on_notified_invalid_payload(execution_block_hash, None)
-                }
-                PayloadStatus::Optimistic => {}
-            }
-        }
+        execution_service.notify_scripted_statuses();
 
         for block in extra_blocks {
             // Strictly speaking the blocks are not requested from anywhere, but we want them to be
@@ -210,10 +228,13 @@ impl<P: Preset> Context<P> {
             controller.on_requested_block(block, None);
         }
 
-        let execution_service =
-            ExecutionService::new(eth1_api, controller.clone_arc(), execution_service_rx);
-
-        let signer = Signer::new(validator_keys, client, Web3SignerConfig::default(), None);
+        let signer = Signer::new(
+            validator_keys,
+            client.clone(),
+            client,
+            Web3SignerConfig::default(),
+            None,
+        );
         let validator_keys = Arc::new(signer.keys().copied().collect());
 
         let mut slashing_protector =
@@ -233,6 +254,7 @@ impl<P: Preset> Context<P> {
             anchor_state.genesis_validators_root(),
             validator_config.suggested_fee_recipient,
             H256::default(),
+            false,
         ));
 
         let dedicated_executor = Arc::new(DedicatedExecutor::new(
@@ -242,8 +264,13 @@ impl<P: Preset> Context<P> {
             None,
         ));
 
-        let attestation_agg_pool =
-            AttestationAggPool::new(controller.clone_arc(), dedicated_executor.clone_arc(), None);
+        let attestation_agg_pool = AttestationAggPool::new(
+            controller.clone_arc(),
+            Arc::new(SystemClock),
+            dedicated_executor.clone_arc(),
+            None,
+            PoolConfig::default(),
+        );
 
         let sync_committee_agg_pool = SyncCommitteeAggPool::new(
             dedicated_executor,
@@ -251,6 +278,7 @@ impl<P: Preset> Context<P> {
             Some(pool_to_liveness_tx),
             pool_to_p2p_tx.clone(),
             None,
+            PoolConfig::default(),
         );
 
         let (bls_to_execution_change_pool, bls_to_execution_change_pool_service) =
@@ -259,6 +287,7 @@ impl<P: Preset> Context<P> {
                 pool_to_api_tx,
                 pool_to_p2p_tx,
                 None,
+                PoolConfig::default(),
             );
 
         let liveness_tracker = LivenessTracker::new(
@@ -272,6 +301,7 @@ impl<P: Preset> Context<P> {
         let validator_channels = ValidatorChannels {
             api_to_validator_rx,
             fork_choice_rx: fc_to_validator_rx,
+            graceful_shutdown_rx: validator_shutdown_rx,
             p2p_tx: validator_to_p2p_tx,
             p2p_to_validator_rx,
             slasher_to_validator_rx: None,
@@ -281,19 +311,27 @@ impl<P: Preset> Context<P> {
             validator_to_slasher_tx: None,
         };
 
+        let duties_cache = Arc::new(DutiesCache::default());
+        let proposal_reports = Arc::new(ProposalReports::default());
+
         let validator = Validator::new(
             eth1_chain,
             validator_config.clone_arc(),
             controller.clone_arc(),
             execution_engine,
             attestation_agg_pool.clone_arc(),
+            duties_cache.clone_arc(),
+            None,
             None,
             keymanager.proposer_configs().clone_arc(),
             signer,
             slashing_protector,
             sync_committee_agg_pool.clone_arc(),
             bls_to_execution_change_pool.clone_arc(),
+            Database::in_memory(),
             None,
+            Arc::new(ClockDrift::default()),
+            proposal_reports.clone_arc(),
             validator_channels,
         );
 
@@ -319,6 +357,7 @@ impl<P: Preset> Context<P> {
             api_to_p2p_tx,
             api_to_validator_tx,
             fc_to_api_rx,
+            graceful_shutdown_rx: http_api_shutdown_rx,
             pool_to_api_rx,
             subnet_service_tx,
             sync_to_api_rx,
@@ -328,6 +367,7 @@ impl<P: Preset> Context<P> {
         let http_api = HttpApi {
             controller,
             genesis_provider,
+            eth1_api,
             keymanager,
             validator_keys,
             validator_config,
@@ -336,8 +376,11 @@ impl<P: Preset> Context<P> {
             attestation_agg_pool,
             sync_committee_agg_pool,
             bls_to_execution_change_pool,
+            duties_cache,
+            proposal_reports,
             channels,
             metrics: None,
+            builder_api: None,
         };
 
         let test_state = TestState {
@@ -360,7 +403,7 @@ impl<P: Preset> Context<P> {
                         middleware::wait_for_tasks,
                     ))
             },
-            incoming,
+            Some(incoming),
         );
 
         let join_mutator = async { tokio::task::spawn_blocking(|| mutator_handle.join()).await? };
@@ -453,7 +496,7 @@ impl Context<Mainnet> {
         let payload_statuses = extra_blocks
             .iter()
             .filter_map(|block| block.execution_block_hash())
-            .map(|execution_block_hash| (execution_block_hash, PayloadStatus::Valid))
+            .map(|execution_block_hash| (execution_block_hash, PayloadValidationStatus::Valid))
             .collect();
 
         Self {
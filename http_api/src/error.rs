@@ -1,5 +1,5 @@
 use core::fmt::Display;
-use std::error::Error as StdError;
+use std::{backtrace::BacktraceStatus, error::Error as StdError};
 
 use anyhow::Error as AnyhowError;
 use axum::{
@@ -8,17 +8,33 @@ use axum::{
     Extension, Json,
 };
 use bls::SignatureBytes;
+use features::Feature;
 use futures::channel::oneshot::Canceled;
 use itertools::Itertools as _;
 use serde::{Serialize, Serializer};
 use thiserror::Error;
 use tokio::task::JoinError;
-use types::{deneb::primitives::BlobIndex, phase0::primitives::Slot};
+use types::{
+    deneb::primitives::BlobIndex,
+    nonstandard::Phase,
+    phase0::primitives::{Epoch, Slot, H256},
+};
 
 #[derive(Debug, Error)]
 pub enum Error {
+    #[error("admin endpoints are disabled")]
+    AdminEndpointsDisabled,
+    #[error("admin operation rejected")]
+    AdminOperationRejected(#[source] AnyhowError),
     #[error("attestation cannot be found")]
     AttestationNotFound,
+    #[error(
+        "block equivocates with another block from the same proposer in the same slot \
+         (equivocating block root: {equivocating_block_root:?})"
+    )]
+    BlockEquivocates { equivocating_block_root: H256 },
+    #[error("block not broadcast because it did not pass the requested validation")]
+    BlockNotBroadcast,
     #[error("block not found")]
     BlockNotFound,
     #[error(transparent)]
@@ -28,10 +44,13 @@ pub enum Error {
          the expected number of committees ({computed})"
     )]
     CommitteesAtSlotMismatch { requested: u64, computed: u64 },
+    #[error(
+        "Eth-Consensus-Version header ({header}) does not match \
+         the phase at the slot of the object ({computed})"
+    )]
+    ConsensusVersionMismatch { header: Phase, computed: Phase },
     #[error("current slot has no sync committee")]
     CurrentSlotHasNoSyncCommittee,
-    #[error("endpoint not implemented")]
-    EndpointNotImplemented,
     #[error("epoch is before previous one relative to head")]
     EpochBeforePrevious,
     #[error("requested epoch is neither in current nor next sync committee period")]
@@ -42,6 +61,8 @@ pub enum Error {
     ExecutionPayloadNotAvailable,
     #[error("no event topics specified")]
     EventTopicsEmpty,
+    #[error("feature {0} is not enabled")]
+    FeatureNotEnabled(Feature),
     #[error("too many empty slots after head: {head_slot} + {max_empty_slots} < {slot}")]
     HeadFarBehind {
         head_slot: Slot,
@@ -56,8 +77,12 @@ pub enum Error {
     InvalidAggregatesAndProofs(Vec<IndexedError>),
     #[error("invalid attestations")]
     InvalidAttestations(Vec<IndexedError>),
+    #[error("missing or invalid admin token")]
+    InvalidAdminToken,
     #[error("invalid attester slashing, it will never pass validation so it's rejected")]
     InvalidAttesterSlashing(#[source] AnyhowError),
+    #[error("invalid beacon committee subscriptions")]
+    InvalidBeaconCommitteeSubscriptions(Vec<IndexedError>),
     #[error("invalid blob index {0}")]
     InvalidBlobIndex(BlobIndex),
     #[error("invalid block ID")]
@@ -66,8 +91,12 @@ pub enum Error {
     InvalidBlock(#[source] AnyhowError),
     #[error("invalid contribution and proofs")]
     InvalidContributionAndProofs(Vec<IndexedError>),
+    #[error("invalid Eth-Consensus-Version header")]
+    InvalidConsensusVersion(#[source] AnyhowError),
     #[error("invalid epoch")]
     InvalidEpoch(#[source] AnyhowError),
+    #[error("invalid generalized index")]
+    InvalidGeneralizedIndex(#[source] AnyhowError),
     #[error("invalid JSON body")]
     InvalidJsonBody(#[source] AnyhowError),
     #[error("invalid peer ID")]
@@ -87,10 +116,14 @@ pub enum Error {
     InvalidPublicKey(#[source] AnyhowError),
     #[error("invalid query string")]
     InvalidQuery(#[source] AnyhowError),
+    #[error("invalid selection proofs")]
+    InvalidSelectionProofs(Vec<IndexedError>),
     #[error("invalid voluntary exit, it will never pass validation so it's rejected")]
     InvalidSignedVoluntaryExit(#[source] AnyhowError),
     #[error("invalid sync committee messages")]
     InvalidSyncCommitteeMessages(Vec<IndexedError>),
+    #[error("invalid sync committee subscriptions")]
+    InvalidSyncCommitteeSubscriptions(Vec<IndexedError>),
     #[error("invalid validator index")]
     InvalidValidatorIndex(#[source] AnyhowError),
     #[error("invalid validator signatures")]
@@ -115,6 +148,8 @@ pub enum Error {
     SlotHeadNotAvailable,
     #[error("state is pre-Capella")]
     StatePreCapella,
+    #[error("sync committee index {sync_committee_index} is out of bounds")]
+    SyncCommitteeIndexOutOfBounds { sync_committee_index: usize },
     #[error("target state not found")]
     TargetStateNotFound,
     #[error(transparent)]
@@ -125,6 +160,11 @@ pub enum Error {
     UnableToProduceBeaconBlock,
     #[error("unable to produce blinded block")]
     UnableToProduceBlindedBlock,
+    #[error("until_epoch ({until_epoch}) is not after current epoch ({current_epoch})")]
+    UntilEpochNotInFuture {
+        until_epoch: Epoch,
+        current_epoch: Epoch,
+    },
     #[error("validator not found")]
     ValidatorNotFound,
     // TODO(Grandine Team): Some API clients do not set `validator_index`.
@@ -177,7 +217,11 @@ impl Error {
             | Self::StateNotFound
             | Self::TargetStateNotFound
             | Self::ValidatorNotFound => StatusCode::NOT_FOUND,
-            Self::CommitteesAtSlotMismatch { .. }
+            Self::AdminOperationRejected(_)
+            | Self::BlockEquivocates { .. }
+            | Self::BlockNotBroadcast
+            | Self::CommitteesAtSlotMismatch { .. }
+            | Self::ConsensusVersionMismatch { .. }
             | Self::CurrentSlotHasNoSyncCommittee
             | Self::EpochBeforePrevious { .. }
             | Self::EpochNotInSyncCommitteePeriod
@@ -186,27 +230,34 @@ impl Error {
             | Self::InvalidAggregatesAndProofs(_)
             | Self::InvalidAttestations(_)
             | Self::InvalidAttesterSlashing(_)
+            | Self::InvalidBeaconCommitteeSubscriptions(_)
             | Self::InvalidBlock(_)
             | Self::InvalidBlobIndex(_)
             | Self::InvalidBlockId(_)
+            | Self::InvalidConsensusVersion(_)
             | Self::InvalidContributionAndProofs(_)
             | Self::InvalidEpoch(_)
+            | Self::InvalidGeneralizedIndex(_)
             | Self::InvalidJsonBody(_)
             | Self::InvalidQuery(_)
             | Self::InvalidPeerId(_)
             | Self::InvalidProposerSlashing(_)
             | Self::InvalidPublicKey(_)
+            | Self::InvalidSelectionProofs(_)
             | Self::InvalidSignedVoluntaryExit(_)
             | Self::InvalidStateId(_)
             | Self::InvalidSignedBlsToExecutionChanges(_)
             | Self::InvalidSyncCommitteeMessages(_)
+            | Self::InvalidSyncCommitteeSubscriptions(_)
             | Self::InvalidRandaoReveal
             | Self::InvalidValidatorId(_)
             | Self::InvalidValidatorIndex(_)
             | Self::InvalidValidatorSignatures(_)
             | Self::ProposalSlotNotLaterThanStateSlot
             | Self::SlotNotInEpoch
-            | Self::StatePreCapella => StatusCode::BAD_REQUEST,
+            | Self::StatePreCapella
+            | Self::SyncCommitteeIndexOutOfBounds { .. }
+            | Self::UntilEpochNotInFuture { .. } => StatusCode::BAD_REQUEST,
             // | Self::ValidatorNotInCommittee { .. }
             Self::Internal(_)
             | Self::Canceled(_)
@@ -217,7 +268,8 @@ impl Error {
             | Self::UnableToProduceAttestation { .. }
             | Self::UnableToProduceBeaconBlock
             | Self::UnableToProduceBlindedBlock => StatusCode::INTERNAL_SERVER_ERROR,
-            Self::EndpointNotImplemented => StatusCode::NOT_IMPLEMENTED,
+            Self::InvalidAdminToken => StatusCode::UNAUTHORIZED,
+            Self::AdminEndpointsDisabled | Self::FeatureNotEnabled(_) => StatusCode::FORBIDDEN,
             Self::HeadFarBehind { .. } | Self::HeadIsOptimistic | Self::NodeIsSyncing => {
                 StatusCode::SERVICE_UNAVAILABLE
             }
@@ -228,16 +280,30 @@ impl Error {
         EthErrorResponse {
             code: self.status_code().as_u16(),
             message: self,
+            stacktraces: self.stacktraces(),
             failures: self.failures(),
         }
     }
 
+    // `anyhow` only captures backtraces if `RUST_BACKTRACE` or `RUST_LIB_BACKTRACE` is set.
+    fn stacktraces(&self) -> Vec<String> {
+        match self {
+            Self::Internal(error) if error.backtrace().status() == BacktraceStatus::Captured => {
+                vec![error.backtrace().to_string()]
+            }
+            _ => vec![],
+        }
+    }
+
     fn failures(&self) -> &[IndexedError] {
         match self {
             Self::InvalidAggregatesAndProofs(failures)
             | Self::InvalidAttestations(failures)
+            | Self::InvalidBeaconCommitteeSubscriptions(failures)
             | Self::InvalidContributionAndProofs(failures)
+            | Self::InvalidSelectionProofs(failures)
             | Self::InvalidSyncCommitteeMessages(failures)
+            | Self::InvalidSyncCommitteeSubscriptions(failures)
             | Self::InvalidValidatorSignatures(failures)
             | Self::InvalidSignedBlsToExecutionChanges(failures) => failures,
             _ => &[],
@@ -259,6 +325,8 @@ struct EthErrorResponse<'error> {
     // The `code` field is supposed to contain a number.
     code: u16,
     message: &'error Error,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    stacktraces: Vec<String>,
     #[serde(skip_serializing_if = "<[_]>::is_empty")]
     failures: &'error [IndexedError],
 }
@@ -271,6 +339,17 @@ mod tests {
 
     use super::*;
 
+    #[test_case(
+        Error::BlockEquivocates {
+            equivocating_block_root: H256::zero(),
+        },
+        json!({
+            "code": 400,
+            "message": "block equivocates with another block from the same proposer in the same slot \
+                        (equivocating block root: \
+                        0x0000000000000000000000000000000000000000000000000000000000000000)",
+        })
+    )]
     #[test_case(
         Error::BlockNotFound,
         json!({
@@ -278,6 +357,13 @@ mod tests {
             "message": "block not found",
         })
     )]
+    #[test_case(
+        Error::InvalidAdminToken,
+        json!({
+            "code": 401,
+            "message": "missing or invalid admin token",
+        })
+    )]
     #[test_case(
         Error::InvalidAttestations(vec![IndexedError {
             index: 0,
@@ -294,6 +380,13 @@ mod tests {
             ],
         })
     )]
+    #[test_case(
+        Error::InvalidBlock(AnyhowError::msg("block's parent is unknown")),
+        json!({
+            "code": 400,
+            "message": "invalid block: block's parent is unknown",
+        })
+    )]
     fn error_is_serialized_correctly(error: Error, expected_json: Value) -> Result<()> {
         let actual_json = serde_json::to_value(error.body())?;
         assert_eq!(actual_json, expected_json);
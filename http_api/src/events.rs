@@ -1,11 +1,15 @@
+use core::sync::atomic::{AtomicU64, Ordering};
+use std::collections::VecDeque;
+
 use axum::response::sse::Event;
+use parking_lot::Mutex;
 use serde::Serialize;
 use serde_json::Result;
 use serde_with::DeserializeFromStr;
 use strum::{AsRefStr, EnumString};
 use tokio::sync::broadcast::{self, Receiver, Sender};
 
-#[derive(Clone, Copy, AsRefStr, EnumString, DeserializeFromStr)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, AsRefStr, EnumString, DeserializeFromStr)]
 #[strum(serialize_all = "snake_case")]
 pub enum Topic {
     Attestation,
@@ -19,37 +23,113 @@ pub enum Topic {
 }
 
 impl Topic {
-    pub fn build(self, data: impl Serialize) -> Result<Event> {
+    fn build(self, data: impl Serialize) -> Result<Event> {
         Event::default().event(self).json_data(data)
     }
 }
 
 pub struct EventChannels {
-    pub attestations: Sender<Event>,
-    pub blocks: Sender<Event>,
-    pub bls_to_execution_changes: Sender<Event>,
-    pub chain_reorgs: Sender<Event>,
-    pub contribution_and_proofs: Sender<Event>,
-    pub finalized_checkpoints: Sender<Event>,
-    pub heads: Sender<Event>,
-    pub voluntary_exits: Sender<Event>,
+    attestations: TopicChannel,
+    blocks: TopicChannel,
+    bls_to_execution_changes: TopicChannel,
+    chain_reorgs: TopicChannel,
+    contribution_and_proofs: TopicChannel,
+    finalized_checkpoints: TopicChannel,
+    heads: TopicChannel,
+    voluntary_exits: TopicChannel,
+    next_id: AtomicU64,
+    replay_enabled: bool,
 }
 
 impl EventChannels {
-    pub fn new(max_events: usize) -> Self {
+    pub fn new(max_events: usize, replay_buffer_size: usize) -> Self {
         Self {
-            attestations: broadcast::channel(max_events).0,
-            blocks: broadcast::channel(max_events).0,
-            bls_to_execution_changes: broadcast::channel(max_events).0,
-            chain_reorgs: broadcast::channel(max_events).0,
-            contribution_and_proofs: broadcast::channel(max_events).0,
-            finalized_checkpoints: broadcast::channel(max_events).0,
-            heads: broadcast::channel(max_events).0,
-            voluntary_exits: broadcast::channel(max_events).0,
+            attestations: TopicChannel::new(max_events, replay_buffer_size),
+            blocks: TopicChannel::new(max_events, replay_buffer_size),
+            bls_to_execution_changes: TopicChannel::new(max_events, replay_buffer_size),
+            chain_reorgs: TopicChannel::new(max_events, replay_buffer_size),
+            contribution_and_proofs: TopicChannel::new(max_events, replay_buffer_size),
+            finalized_checkpoints: TopicChannel::new(max_events, replay_buffer_size),
+            heads: TopicChannel::new(max_events, replay_buffer_size),
+            voluntary_exits: TopicChannel::new(max_events, replay_buffer_size),
+            next_id: AtomicU64::new(0),
+            replay_enabled: replay_buffer_size > 0,
         }
     }
 
-    pub fn receiver_for(&self, topic: Topic) -> Receiver<Event> {
+    // Returns the number of receivers the event was sent to.
+    pub fn send(&self, topic: Topic, data: impl Serialize) -> Result<usize> {
+        let channel = self.channel_for(topic);
+
+        if !self.replay_enabled {
+            // Events are only built for subscribers when there is no buffer to keep them in.
+            if channel.sender.receiver_count() == 0 {
+                return Ok(0);
+            }
+
+            let event = topic.build(data)?.id(self.next_id().to_string());
+
+            return Ok(channel.sender.send(event).unwrap_or_default());
+        }
+
+        let mut replay_buffer = channel.replay_buffer.lock();
+
+        // IDs are assigned while holding the lock to keep events in each buffer ordered by ID.
+        let id = self.next_id();
+        let event = topic.build(data)?.id(id.to_string());
+
+        replay_buffer.push(id, event.clone());
+
+        Ok(channel.sender.send(event).unwrap_or_default())
+    }
+
+    /// Subscribes to `topics` and returns events emitted after the one with `last_event_id`.
+    ///
+    /// Event IDs are only unique within a single run of the application. Only events still in the
+    /// replay buffer can be returned, so clients that stay disconnected for long may miss some.
+    pub fn subscribe(
+        &self,
+        topics: &[Topic],
+        last_event_id: Option<u64>,
+    ) -> (Vec<Event>, Vec<Receiver<Event>>) {
+        let (missed_events, receivers) = self.subscribe_with_ids(topics, last_event_id);
+
+        let missed_events = missed_events.into_iter().map(|(_, event)| event).collect();
+
+        (missed_events, receivers)
+    }
+
+    fn subscribe_with_ids(
+        &self,
+        topics: &[Topic],
+        last_event_id: Option<u64>,
+    ) -> (Vec<(u64, Event)>, Vec<Receiver<Event>>) {
+        let mut missed_events = vec![];
+        let mut receivers = vec![];
+
+        for topic in topics {
+            let channel = self.channel_for(*topic);
+            let replay_buffer = channel.replay_buffer.lock();
+
+            if let Some(last_event_id) = last_event_id {
+                missed_events.extend(replay_buffer.events_after(last_event_id));
+            }
+
+            receivers.push(channel.sender.subscribe());
+        }
+
+        // Topics may be repeated. Their events should only be replayed once.
+        missed_events.sort_unstable_by_key(|(id, _)| *id);
+        missed_events.dedup_by_key(|(id, _)| *id);
+
+        (missed_events, receivers)
+    }
+
+    fn next_id(&self) -> u64 {
+        self.next_id.fetch_add(1, Ordering::Relaxed)
+    }
+
+    const fn channel_for(&self, topic: Topic) -> &TopicChannel {
         match topic {
             Topic::Attestation => &self.attestations,
             Topic::Block => &self.blocks,
@@ -60,6 +140,117 @@ impl EventChannels {
             Topic::Head => &self.heads,
             Topic::VoluntaryExit => &self.voluntary_exits,
         }
-        .subscribe()
+    }
+}
+
+// Recent events are kept separately for each topic.
+// Otherwise attestations would push events of all other topics out of the buffer within a slot.
+// Separate locks also keep senders of different topics from waiting on each other.
+struct TopicChannel {
+    sender: Sender<Event>,
+    // Events are sent while holding the lock so that subscribers see each event exactly once,
+    // either replayed from the buffer or through their receivers.
+    replay_buffer: Mutex<ReplayBuffer>,
+}
+
+impl TopicChannel {
+    fn new(max_events: usize, replay_buffer_size: usize) -> Self {
+        Self {
+            sender: broadcast::channel(max_events).0,
+            replay_buffer: Mutex::new(ReplayBuffer::new(replay_buffer_size)),
+        }
+    }
+}
+
+struct ReplayBuffer {
+    size: usize,
+    events: VecDeque<(u64, Event)>,
+}
+
+impl ReplayBuffer {
+    const fn new(size: usize) -> Self {
+        Self {
+            size,
+            events: VecDeque::new(),
+        }
+    }
+
+    fn push(&mut self, id: u64, event: Event) {
+        if self.size == 0 {
+            return;
+        }
+
+        if self.events.len() == self.size {
+            self.events.pop_front();
+        }
+
+        self.events.push_back((id, event));
+    }
+
+    fn events_after(&self, last_event_id: u64) -> impl Iterator<Item = (u64, Event)> + '_ {
+        self.events
+            .iter()
+            .filter(move |(id, _)| *id > last_event_id)
+            .cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools as _;
+
+    use super::*;
+
+    #[test]
+    fn subscribe_returns_missed_events_of_requested_topics_in_order() -> Result<()> {
+        let event_channels = EventChannels::new(16, 2);
+
+        for topic in [
+            Topic::Head,
+            Topic::Block,
+            Topic::Head,
+            Topic::Attestation,
+            Topic::Head,
+            Topic::Block,
+        ] {
+            event_channels.send(topic, ())?;
+        }
+
+        let missed_ids = |topics: &[Topic], last_event_id| {
+            event_channels
+                .subscribe_with_ids(topics, Some(last_event_id))
+                .0
+                .into_iter()
+                .map(|(id, _)| id)
+                .collect_vec()
+        };
+
+        // The first head event has already been pushed out of the buffer.
+        assert_eq!(missed_ids(&[Topic::Head, Topic::Block], 0), [1, 2, 4, 5]);
+        assert_eq!(missed_ids(&[Topic::Block, Topic::Head], 2), [4, 5]);
+        assert!(missed_ids(&[Topic::Attestation], 5).is_empty());
+        assert_eq!(missed_ids(&[Topic::Head, Topic::Head], 3), [4]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn events_are_neither_buffered_nor_built_if_replay_is_disabled() -> Result<()> {
+        let event_channels = EventChannels::new(16, 0);
+
+        assert_eq!(event_channels.send(Topic::Head, ())?, 0);
+
+        let (missed_events, mut receivers) =
+            event_channels.subscribe_with_ids(&[Topic::Head], Some(0));
+
+        assert!(missed_events.is_empty());
+
+        assert_eq!(event_channels.send(Topic::Head, ())?, 1);
+        assert!(receivers[0].try_recv().is_ok());
+
+        // IDs are only used up by events that were sent to subscribers.
+        assert_eq!(event_channels.next_id(), 1);
+
+        Ok(())
     }
 }
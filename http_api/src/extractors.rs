@@ -7,7 +7,7 @@
 
 use std::sync::Arc;
 
-use anyhow::{Error as AnyhowError, Result};
+use anyhow::{anyhow, Error as AnyhowError, Result};
 use axum::{
     async_trait,
     body::Body,
@@ -29,22 +29,25 @@ use ssz::SszRead;
 use types::{
     altair::containers::SignedContributionAndProof,
     config::Config,
+    nonstandard::Phase,
     phase0::{
         containers::{
-            Attestation, AttesterSlashing, ProposerSlashing, SignedAggregateAndProof,
+            Attestation, AttesterSlashing, Checkpoint, ProposerSlashing, SignedAggregateAndProof,
             SignedVoluntaryExit,
         },
-        primitives::{Epoch, ValidatorIndex},
+        primitives::{Epoch, ValidatorIndex, H256},
     },
     preset::Preset,
 };
-use validator::ValidatorProposerData;
+use validator::{BeaconCommitteeSelection, SyncCommitteeSelection, ValidatorProposerData};
 
 use crate::{
+    admin::BlockRootBody,
     error::Error,
+    response::ETH_CONSENSUS_VERSION,
     standard::{
         KeystoreDeleteQuery, KeystoreImportQuery, RemoteKeysDeleteQuery, RemoteKeysImportQuery,
-        SetFeeRecipientQuery, SetGasLimitQuery, SetGraffitiQuery,
+        SetFeeRecipientQuery, SetGasLimitQuery, SetGraffitiQuery, StateValidatorsBody,
     },
     state_id::StateId,
     validator_status::ValidatorId,
@@ -158,6 +161,22 @@ impl<S> FromRequestParts<S> for EthPath<(StateId, ValidatorId)> {
     }
 }
 
+#[async_trait]
+impl<S> FromRequestParts<S> for EthPath<H256> {
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extract::<Path<String>>()
+            .await
+            .map_err(AnyhowError::new)?
+            .parse()
+            .map(Self)
+            .map_err(AnyhowError::new)
+            .map_err(Error::InvalidBlockId)
+    }
+}
+
 #[async_trait]
 impl<S> FromRequestParts<S> for EthPath<PublicKeyBytes> {
     type Rejection = Error;
@@ -174,6 +193,28 @@ impl<S> FromRequestParts<S> for EthPath<PublicKeyBytes> {
     }
 }
 
+/// Extracts the phase from the `Eth-Consensus-Version` header.
+/// Endpoints that accept objects that change with phases require it.
+pub struct EthConsensusVersion(pub Phase);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for EthConsensusVersion {
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let parse = || -> Result<_> {
+            let header = parts
+                .headers
+                .get(ETH_CONSENSUS_VERSION)
+                .ok_or_else(|| anyhow!("{ETH_CONSENSUS_VERSION} header is missing"))?;
+
+            Ok(header.to_str()?.parse()?)
+        };
+
+        parse().map(Self).map_err(Error::InvalidConsensusVersion)
+    }
+}
+
 pub struct EthQuery<T>(pub T);
 
 #[async_trait]
@@ -294,6 +335,76 @@ impl<S> FromRequest<S, Body> for EthJson<Vec<ValidatorId>> {
     }
 }
 
+#[async_trait]
+impl<S> FromRequest<S, Body> for EthJson<StateValidatorsBody> {
+    type Rejection = Error;
+
+    async fn from_request(request: Request<Body>, _state: &S) -> Result<Self, Self::Rejection> {
+        request
+            .extract()
+            .await
+            .map(|Json(body)| Self(body))
+            .map_err(AnyhowError::new)
+            .map_err(Error::InvalidJsonBody)
+    }
+}
+
+#[async_trait]
+impl<S> FromRequest<S, Body> for EthJson<BlockRootBody> {
+    type Rejection = Error;
+
+    async fn from_request(request: Request<Body>, _state: &S) -> Result<Self, Self::Rejection> {
+        request
+            .extract()
+            .await
+            .map(|Json(body)| Self(body))
+            .map_err(AnyhowError::new)
+            .map_err(Error::InvalidJsonBody)
+    }
+}
+
+#[async_trait]
+impl<S> FromRequest<S, Body> for EthJson<Checkpoint> {
+    type Rejection = Error;
+
+    async fn from_request(request: Request<Body>, _state: &S) -> Result<Self, Self::Rejection> {
+        request
+            .extract()
+            .await
+            .map(|Json(checkpoint)| Self(checkpoint))
+            .map_err(AnyhowError::new)
+            .map_err(Error::InvalidJsonBody)
+    }
+}
+
+#[async_trait]
+impl<S> FromRequest<S, Body> for EthJson<Vec<BeaconCommitteeSelection>> {
+    type Rejection = Error;
+
+    async fn from_request(request: Request<Body>, _state: &S) -> Result<Self, Self::Rejection> {
+        request
+            .extract()
+            .await
+            .map(|Json(selections)| Self(selections))
+            .map_err(AnyhowError::new)
+            .map_err(Error::InvalidJsonBody)
+    }
+}
+
+#[async_trait]
+impl<S> FromRequest<S, Body> for EthJson<Vec<SyncCommitteeSelection>> {
+    type Rejection = Error;
+
+    async fn from_request(request: Request<Body>, _state: &S) -> Result<Self, Self::Rejection> {
+        request
+            .extract()
+            .await
+            .map(|Json(selections)| Self(selections))
+            .map_err(AnyhowError::new)
+            .map_err(Error::InvalidJsonBody)
+    }
+}
+
 #[async_trait]
 impl<S> FromRequest<S, Body> for EthJson<Vec<SyncCommitteeSubscription>> {
     type Rejection = Error;
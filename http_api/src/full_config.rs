@@ -22,7 +22,7 @@ use types::{
     bellatrix::primitives::Gas,
     capella::consts::DOMAIN_BLS_TO_EXECUTION_CHANGE,
     config::Config,
-    deneb::consts::DOMAIN_BLOB_SIDECAR,
+    deneb::consts::{BytesPerFieldElement, DOMAIN_BLOB_SIDECAR, VERSIONED_HASH_VERSION_KZG},
     phase0::{
         consts::{
             AttestationSubnetCount, DepositContractTreeDepth, JustificationBitsLength,
@@ -144,11 +144,17 @@ pub struct FullConfig {
     // Capella domain types
     domain_bls_to_execution_change: DomainType,
 
-    // TODO(feature/deneb): Add constants from the Polynomial Commitments specification if needed.
-
     // Deneb domain types
     domain_blob_sidecar: DomainType,
 
+    // Deneb blob constants
+    #[serde(with = "serde_utils::prefixed_hex_or_bytes_slice")]
+    versioned_hash_version_kzg: &'static [u8],
+
+    // Deneb polynomial commitment constants
+    #[serde(with = "serde_utils::string_or_native")]
+    bytes_per_field_element: usize,
+
     // Builder constants
     #[serde(with = "serde_utils::string_or_native")]
     builder_proposal_delay_tolerance: u64,
@@ -235,6 +241,12 @@ impl FullConfig {
             // Deneb domain types
             domain_blob_sidecar: DOMAIN_BLOB_SIDECAR,
 
+            // Deneb blob constants
+            versioned_hash_version_kzg: VERSIONED_HASH_VERSION_KZG,
+
+            // Deneb polynomial commitment constants
+            bytes_per_field_element: BytesPerFieldElement::USIZE,
+
             // Builder constants
             builder_proposal_delay_tolerance: BUILDER_PROPOSAL_DELAY_TOLERANCE,
             domain_application_builder: DOMAIN_APPLICATION_BUILDER,
@@ -247,6 +259,7 @@ impl FullConfig {
 
 #[cfg(test)]
 mod tests {
+    use serde_json::Result;
     use types::preset::Mainnet;
 
     use super::*;
@@ -257,4 +270,30 @@ mod tests {
 
         serde_utils::assert_json_contains_no_numbers(full_config);
     }
+
+    #[test]
+    fn full_config_json_contains_values_from_all_phases() -> Result<()> {
+        let full_config = FullConfig::new::<Mainnet>(Arc::new(Config::mainnet()));
+        let json = serde_json::to_value(full_config)?;
+
+        for key in [
+            "SLOTS_PER_EPOCH",
+            "GENESIS_FORK_VERSION",
+            "DOMAIN_BEACON_PROPOSER",
+            "SYNC_COMMITTEE_SIZE",
+            "ALTAIR_FORK_EPOCH",
+            "MAX_TRANSACTIONS_PER_PAYLOAD",
+            "MAX_WITHDRAWALS_PER_PAYLOAD",
+            "DOMAIN_BLS_TO_EXECUTION_CHANGE",
+            "MAX_BLOBS_PER_BLOCK",
+            "DENEB_FORK_EPOCH",
+            "MAX_REQUEST_BLOB_SIDECARS",
+            "VERSIONED_HASH_VERSION_KZG",
+            "BYTES_PER_FIELD_ELEMENT",
+        ] {
+            assert!(json.get(key).is_some(), "{key} is missing");
+        }
+
+        Ok(())
+    }
 }
@@ -1,4 +1,4 @@
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, net::SocketAddr};
 
 use anyhow::{anyhow, Result};
 use features::Feature;
@@ -28,11 +28,21 @@ pub fn get_features() -> BTreeMap<Feature, bool> {
 }
 
 /// `PATCH /features`
-pub fn patch_features(features: BTreeMap<Feature, bool>) {
+/// `PATCH /admin/features`
+pub fn patch_features(features: BTreeMap<Feature, bool>, remote: SocketAddr) {
     for (feature, enabled) in features {
+        let previously_enabled = feature.is_enabled();
+
         feature.set_enabled(enabled);
 
+        // This serves as an audit log of changes made at runtime.
         let verb = if enabled { "enabled" } else { "disabled" };
-        info!("feature {feature} {verb}");
+        let previous_state = if previously_enabled {
+            "enabled"
+        } else {
+            "disabled"
+        };
+
+        info!("feature {feature} {verb} by {remote} (previously {previous_state})");
     }
 }
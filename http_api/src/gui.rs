@@ -885,7 +885,7 @@ pub fn get_validator_owned<P: Preset, W: Wait>(
         .iter()
         .copied()
         .filter_map(|pubkey| {
-            let validator_index = accessors::index_of_public_key(&head_state, pubkey)?;
+            let validator_index = controller.validator_index(&head_state, pubkey)?;
             Some((pubkey, validator_index))
         })
         .collect()
@@ -905,7 +905,7 @@ pub async fn get_validator_registered<P: Preset, W: Wait>(
         .await?
         .into_iter()
         .filter_map(|pubkey| {
-            let validator_index = accessors::index_of_public_key(&head_state, pubkey)?;
+            let validator_index = controller.validator_index(&head_state, pubkey)?;
             Some((pubkey, validator_index))
         })
         .collect();
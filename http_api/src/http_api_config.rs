@@ -1,18 +1,50 @@
-use core::time::Duration;
-use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use core::{
+    fmt::{Debug, Formatter, Result as FmtResult},
+    time::Duration,
+};
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    path::PathBuf,
+    sync::Arc,
+};
 
 use educe::Educe;
+use http_api_utils::{RateLimitConfig, RequestQueueConfig};
 use hyper::{server::conn::AddrIncoming, Result};
-use tower_http::cors::AllowOrigin;
+use tower_http::cors::{AllowMethods, AllowOrigin};
 
 #[derive(Clone, Debug, Educe)]
 #[educe(Default(expression = "Self::with_address(Ipv4Addr::LOCALHOST, 5052)"))]
 pub struct HttpApiConfig {
     pub address: SocketAddr,
     pub allow_origin: AllowOrigin,
+    // Methods allowed in CORS preflight responses. Only simple requests are allowed by default.
+    pub allow_methods: AllowMethods,
     pub max_events: usize,
+    // Number of recent events of each topic kept for clients reconnecting with `Last-Event-ID`.
+    pub event_replay_buffer_size: usize,
     // `HttpApiConfig.timeout` is optional to prevent timeouts in tests.
     pub timeout: Option<Duration>,
+    // Shorter timeout for costly endpoints. Only `HttpApiConfig.timeout` applies if this is `None`.
+    pub costly_timeout: Option<Duration>,
+    // Limits how many requests to costly endpoints are handled at once and how many can wait.
+    pub costly_request_queue: RequestQueueConfig,
+    // Admin endpoints are disabled if this is `None`.
+    pub admin_token: Option<AdminToken>,
+    // Minimum size of response bodies to compress in bytes. Compression is disabled if `None`.
+    pub compression_threshold: Option<u16>,
+    // Maximum size of request bodies in bytes. Larger requests are rejected with 413.
+    pub max_body_size: usize,
+    // Same as `HttpApiConfig.max_body_size` but for block publishing endpoints.
+    pub max_block_body_size: usize,
+    // Costly endpoints are not rate limited if this is `None`.
+    pub rate_limit: Option<RateLimitConfig>,
+    // The API is served over plain HTTP if this is `None`.
+    pub tls: Option<TlsConfig>,
+    // Whether to listen on `HttpApiConfig.address`.
+    // This can only be disabled if `HttpApiConfig.unix_socket` is specified.
+    pub listen_on_tcp: bool,
+    pub unix_socket: Option<UnixSocketConfig>,
 }
 
 impl HttpApiConfig {
@@ -27,8 +59,20 @@ impl HttpApiConfig {
         Self {
             address,
             allow_origin: AllowOrigin::list([allowed_origin]),
+            allow_methods: AllowMethods::default(),
             max_events: 100,
+            event_replay_buffer_size: 64,
             timeout: None,
+            costly_timeout: None,
+            costly_request_queue: RequestQueueConfig::default(),
+            admin_token: None,
+            compression_threshold: Some(1024),
+            max_body_size: 2 * 1024 * 1024,
+            max_block_body_size: 32 * 1024 * 1024,
+            rate_limit: None,
+            tls: None,
+            listen_on_tcp: true,
+            unix_socket: None,
         }
     }
 
@@ -36,3 +80,80 @@ impl HttpApiConfig {
         AddrIncoming::bind(&self.address)
     }
 }
+
+/// Paths to PEM files containing the certificate chain and private key for serving HTTPS.
+/// The files are reloaded when they change.
+#[derive(Clone, Debug)]
+pub struct TlsConfig {
+    pub certificate_path: PathBuf,
+    pub private_key_path: PathBuf,
+}
+
+/// Path to a Unix socket to serve the API on along with the file mode to create it with.
+/// Connections through it are always made over plain HTTP.
+#[derive(Clone, Debug)]
+pub struct UnixSocketConfig {
+    pub path: PathBuf,
+    pub mode: u32,
+}
+
+/// Token that must be passed in an `Authorization: Bearer` header to access admin endpoints.
+#[derive(Clone)]
+pub struct AdminToken(Arc<str>);
+
+// Keep the token out of logs.
+impl Debug for AdminToken {
+    fn fmt(&self, formatter: &mut Formatter) -> FmtResult {
+        formatter.write_str("AdminToken(..)")
+    }
+}
+
+impl AdminToken {
+    #[must_use]
+    pub fn new(token: &str) -> Option<Self> {
+        let token = token.trim();
+        (!token.is_empty()).then(|| Self(token.into()))
+    }
+
+    // Compare in constant time to avoid leaking the token through response timing.
+    pub(crate) fn matches(&self, candidate: &str) -> bool {
+        let expected = self.0.as_bytes();
+        let candidate = candidate.as_bytes();
+
+        expected.len() == candidate.len()
+            && expected
+                .iter()
+                .zip(candidate)
+                .fold(0, |difference, (expected, candidate)| {
+                    difference | (expected ^ candidate)
+                })
+                == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn admin_token_matches_only_identical_tokens() {
+        let token = AdminToken::new(" secret\n").expect("token is not empty");
+
+        assert!(token.matches("secret"));
+        assert!(!token.matches("secreT"));
+        assert!(!token.matches("secret "));
+        assert!(!token.matches(""));
+    }
+
+    #[test]
+    fn admin_token_must_not_be_empty() {
+        assert!(AdminToken::new(" \n").is_none());
+    }
+
+    #[test]
+    fn admin_token_is_not_printed() {
+        let token = AdminToken::new("secret").expect("token is not empty");
+
+        assert!(!format!("{token:?}").contains("secret"));
+    }
+}
@@ -1,8 +1,11 @@
+pub use http_api_utils::{RateLimitConfig, RequestQueueConfig};
+
 pub use crate::{
-    http_api_config::HttpApiConfig,
+    http_api_config::{AdminToken, HttpApiConfig, TlsConfig, UnixSocketConfig},
     task::{Channels, HttpApi},
 };
 
+mod admin;
 mod block_id;
 mod error;
 mod events;
@@ -18,6 +21,8 @@ mod routing;
 mod standard;
 mod state_id;
 mod task;
+mod tls;
+mod unix_socket;
 mod validator_status;
 
 #[cfg(test)]
@@ -6,11 +6,11 @@ use std::sync::Arc;
 use axum::{
     body::Body,
     extract::State,
-    http::{Request, StatusCode},
+    http::{header::AUTHORIZATION, Request},
 };
 use features::Feature;
 
-use crate::{error::Error, misc::SyncedStatus};
+use crate::{error::Error, http_api_config::AdminToken, misc::SyncedStatus};
 
 #[cfg(test)]
 use types::preset::Preset;
@@ -21,11 +21,29 @@ use crate::misc::TestApiController;
 pub async fn feature_is_enabled(
     State(feature): State<Feature>,
     request: Request<Body>,
-) -> Result<Request<Body>, StatusCode> {
+) -> Result<Request<Body>, Error> {
     feature
         .is_enabled()
         .then_some(request)
-        .ok_or(StatusCode::FORBIDDEN)
+        .ok_or(Error::FeatureNotEnabled(feature))
+}
+
+pub async fn is_admin(
+    State(admin_token): State<Option<AdminToken>>,
+    request: Request<Body>,
+) -> Result<Request<Body>, Error> {
+    let Some(admin_token) = admin_token else {
+        return Err(Error::AdminEndpointsDisabled);
+    };
+
+    request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|token| admin_token.matches(token))
+        .then_some(request)
+        .ok_or(Error::InvalidAdminToken)
 }
 
 pub async fn is_synced(
@@ -1,21 +1,37 @@
+use core::iter::Peekable;
+
 use anyhow::Result;
 use axum::{
-    http::{header::ACCEPT, HeaderMap, HeaderValue},
+    body::{Body, Bytes},
+    http::{
+        header::{ACCEPT, CACHE_CONTROL, CONTENT_TYPE, ETAG, VARY},
+        HeaderMap, HeaderValue,
+    },
     response::{IntoResponse, Response},
     Json,
 };
-use mime::APPLICATION_OCTET_STREAM;
+use futures::stream;
+use mime::{APPLICATION_JSON, APPLICATION_OCTET_STREAM};
 use serde::Serialize;
 use ssz::SszWrite;
+use tap::Pipe as _;
 use types::{bellatrix::primitives::Wei, nonstandard::Phase, phase0::primitives::H256};
 
 use crate::error::Error;
 
-const ETH_CONSENSUS_VERSION: &str = "eth-consensus-version";
+pub const ETH_CONSENSUS_VERSION: &str = "eth-consensus-version";
 const ETH_CONSENSUS_BLOCK_VALUE: &str = "eth-consensus-block-value";
 const ETH_EXECUTION_PAYLOAD_BLINDED: &str = "eth-execution-payload-blinded";
 const ETH_EXECUTION_PAYLOAD_VALUE: &str = "eth-execution-payload-value";
 
+// One year is the conventional maximum. See <https://www.rfc-editor.org/rfc/rfc9111#section-5.2.2.1>.
+const CACHE_CONTROL_IMMUTABLE: &str = "public, max-age=31536000, immutable";
+const CACHE_CONTROL_REVALIDATE: &str = "no-cache";
+
+// Large enough to make the overhead of sending chunks negligible,
+// small enough to keep at most a few hundred kilobytes of a response in memory.
+const STREAMED_ITEMS_PER_CHUNK: usize = 1024;
+
 pub struct AlwaysJson;
 
 pub enum JsonOrSsz {
@@ -50,6 +66,12 @@ pub struct EthResponse<T, M = (), F = AlwaysJson> {
     #[serde(skip_serializing_if = "Option::is_none")]
     slashing_protection: Option<String>,
 
+    // These are returned only in headers.
+    #[serde(skip)]
+    etag_root: Option<H256>,
+    #[serde(skip)]
+    immutable: bool,
+
     #[serde(skip)]
     format: F,
 }
@@ -57,7 +79,7 @@ pub struct EthResponse<T, M = (), F = AlwaysJson> {
 impl<T: Serialize, M: Serialize> IntoResponse for EthResponse<T, M, AlwaysJson> {
     fn into_response(self) -> Response {
         let run = || {
-            let response_headers = self.response_headers()?;
+            let response_headers = self.response_headers("json")?;
             let response_body = self.into_json();
             Ok((response_headers, response_body))
         };
@@ -69,7 +91,12 @@ impl<T: Serialize, M: Serialize> IntoResponse for EthResponse<T, M, AlwaysJson>
 impl<T: SszWrite + Serialize, M: Serialize> IntoResponse for EthResponse<T, M, JsonOrSsz> {
     fn into_response(self) -> Response {
         let run = || {
-            let response_headers = self.response_headers()?;
+            let representation = match self.format {
+                JsonOrSsz::Json => "json",
+                JsonOrSsz::Ssz => "ssz",
+            };
+
+            let response_headers = self.response_headers(representation)?;
 
             let response_body = match self.format {
                 JsonOrSsz::Json => self.into_json().into_response(),
@@ -96,6 +123,8 @@ impl<T, M, F> EthResponse<T, M, F> {
             execution_optimistic: None,
             finalized: None,
             slashing_protection: None,
+            etag_root: None,
+            immutable: false,
             format,
         }
     }
@@ -140,7 +169,18 @@ impl<T, M, F> EthResponse<T, M, F> {
         self
     }
 
-    fn response_headers(&self) -> Result<HeaderMap> {
+    // Finalized resources never change, so responses for them can be cached.
+    // `root` should uniquely identify the resource (a block root or a state root).
+    // `immutable` should be `false` if the same URL may refer to a different resource later
+    // (e.g., `/eth/v2/beacon/blocks/finalized`). Clients then have to revalidate using the `ETag`.
+    // No caching headers are added unless the response is marked as finalized.
+    pub const fn etag(mut self, root: H256, immutable: bool) -> Self {
+        self.etag_root = Some(root);
+        self.immutable = immutable;
+        self
+    }
+
+    fn response_headers(&self, representation: &str) -> Result<HeaderMap> {
         let mut response_headers = HeaderMap::new();
 
         if let Some(phase) = self.version {
@@ -163,6 +203,23 @@ impl<T, M, F> EthResponse<T, M, F> {
             response_headers.insert(ETH_EXECUTION_PAYLOAD_VALUE, header_value);
         }
 
+        if let (Some(root), Some(true)) = (self.etag_root, self.finalized) {
+            // The representation is included because JSON and SSZ responses differ.
+            let header_value = format!("\"{root:?}-{representation}\"").try_into()?;
+            response_headers.insert(ETAG, header_value);
+
+            let cache_control = if self.immutable {
+                CACHE_CONTROL_IMMUTABLE
+            } else {
+                CACHE_CONTROL_REVALIDATE
+            };
+
+            response_headers.insert(CACHE_CONTROL, HeaderValue::from_static(cache_control));
+            // The compression layer does not add `Vary` itself,
+            // but compressed and uncompressed responses share the same `ETag`.
+            response_headers.insert(VARY, HeaderValue::from_static("accept, accept-encoding"));
+        }
+
         Ok(response_headers)
     }
 
@@ -178,6 +235,8 @@ impl<T, M, F> EthResponse<T, M, F> {
             execution_optimistic,
             finalized,
             slashing_protection,
+            etag_root: _,
+            immutable: _,
             format: _,
         } = self;
 
@@ -192,6 +251,8 @@ impl<T, M, F> EthResponse<T, M, F> {
             execution_optimistic,
             finalized,
             slashing_protection,
+            etag_root: None,
+            immutable: false,
             format: AlwaysJson,
         };
 
@@ -212,6 +273,8 @@ impl<T, F> EthResponse<T, (), F> {
             execution_optimistic,
             finalized,
             slashing_protection,
+            etag_root,
+            immutable,
             format,
         } = self;
 
@@ -226,6 +289,8 @@ impl<T, F> EthResponse<T, (), F> {
             execution_optimistic,
             finalized,
             slashing_protection,
+            etag_root,
+            immutable,
             format,
         }
     }
@@ -250,3 +315,132 @@ impl<T> EthResponse<T, (), JsonOrSsz> {
         Self::new(data, format)
     }
 }
+
+/// A response with a potentially huge list in the `data` field.
+/// Items are serialized lazily as the body is sent instead of all at once.
+/// Errors that occur while serializing items abort the response.
+#[allow(clippy::module_name_repetitions)]
+pub struct EthStreamingResponse<I> {
+    data: I,
+    metadata: StreamingMetadata,
+}
+
+#[derive(Default, Serialize)]
+struct StreamingMetadata {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    execution_optimistic: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    finalized: Option<bool>,
+}
+
+impl<I> IntoResponse for EthStreamingResponse<I>
+where
+    I: IntoIterator + Send + 'static,
+    I::IntoIter: Send + 'static,
+    I::Item: Serialize + Send + 'static,
+{
+    fn into_response(self) -> Response {
+        let Self { data, metadata } = self;
+
+        // Fields are written in the same order as in `EthResponse` with `data` first.
+        let metadata = match serde_json::to_string(&metadata) {
+            Ok(metadata) => metadata,
+            Err(error) => return Error::Internal(error.into()).into_response(),
+        };
+
+        let suffix = match metadata.strip_prefix('{') {
+            Some("}") | None => "]}".to_owned(),
+            Some(fields) => format!("],{fields}"),
+        };
+
+        let chunks = StreamedChunks {
+            items: data.into_iter().peekable(),
+            first: true,
+        };
+
+        let body = core::iter::once(Ok(Bytes::from_static(b"{\"data\":[")))
+            .chain(chunks)
+            .chain(core::iter::once(Ok(Bytes::from(suffix))))
+            .pipe(stream::iter)
+            .pipe(Body::wrap_stream);
+
+        let content_type = HeaderValue::from_static(APPLICATION_JSON.as_ref());
+
+        ([(CONTENT_TYPE, content_type)], body).into_response()
+    }
+}
+
+impl<I> EthStreamingResponse<I> {
+    pub fn json(data: I) -> Self {
+        Self {
+            data,
+            metadata: StreamingMetadata::default(),
+        }
+    }
+
+    pub const fn execution_optimistic(mut self, execution_optimistic: bool) -> Self {
+        self.metadata.execution_optimistic = Some(execution_optimistic);
+        self
+    }
+
+    pub const fn finalized(mut self, finalized: bool) -> Self {
+        self.metadata.finalized = Some(finalized);
+        self
+    }
+}
+
+struct StreamedChunks<I: Iterator> {
+    items: Peekable<I>,
+    first: bool,
+}
+
+impl<I: Iterator<Item = impl Serialize>> Iterator for StreamedChunks<I> {
+    type Item = Result<Bytes, serde_json::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.items.peek()?;
+
+        let mut chunk = vec![];
+
+        for item in self.items.by_ref().take(STREAMED_ITEMS_PER_CHUNK) {
+            if !core::mem::take(&mut self.first) {
+                chunk.push(b',');
+            }
+
+            if let Err(error) = serde_json::to_writer(&mut chunk, &item) {
+                return Some(Err(error));
+            }
+        }
+
+        Some(Ok(chunk.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finalized_responses_vary_by_representation_and_encoding() -> Result<()> {
+        let root = H256::repeat_byte(1);
+
+        let headers = EthResponse::json(())
+            .finalized(true)
+            .etag(root, true)
+            .response_headers("json")?;
+
+        assert_eq!(headers[ETAG], format!("\"{root:?}-json\""));
+        assert_eq!(headers[CACHE_CONTROL], CACHE_CONTROL_IMMUTABLE);
+        assert_eq!(headers[VARY], "accept, accept-encoding");
+
+        let headers = EthResponse::json(())
+            .finalized(false)
+            .etag(root, true)
+            .response_headers("json")?;
+
+        assert!(headers.get(ETAG).is_none());
+        assert!(headers.get(VARY).is_none());
+
+        Ok(())
+    }
+}
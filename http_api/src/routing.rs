@@ -1,16 +1,19 @@
-use std::{collections::HashSet, sync::Arc};
+use core::time::Duration;
+use std::{collections::HashSet, net::SocketAddr, sync::Arc};
 
 use axum::{
-    extract::{FromRef, State},
+    extract::{ConnectInfo, FromRef, State},
     routing::{delete, get, patch, post},
     Json, Router,
 };
 use bls::PublicKeyBytes;
-use eth1_api::ApiController;
+use builder_api::BuilderApi;
+use eth1_api::{ApiController, Eth1Api};
 use features::Feature;
 use fork_choice_control::Wait;
 use futures::channel::mpsc::UnboundedSender;
 use genesis::GenesisProvider;
+use http_api_utils::{RateLimiter, RequestQueue};
 use keymanager::KeyManager;
 use liveness_tracker::ApiToLiveness;
 use metrics::ApiToMetrics;
@@ -20,39 +23,45 @@ use prometheus_metrics::Metrics;
 use serde_qs::axum::QsQuery;
 use std_ext::ArcExt as _;
 use types::{config::Config as ChainConfig, preset::Preset};
-use validator::{ApiToValidator, ValidatorConfig};
+use validator::{ApiToValidator, DutiesCache, ProposalReports, ValidatorConfig};
 
 use crate::{
+    admin,
     error::Error,
     events::EventChannels,
     global::{self},
-    gui, middleware,
+    gui,
+    http_api_config::AdminToken,
+    middleware,
     misc::{BackSyncedStatus, SyncedStatus},
     standard::{
-        beacon_events, beacon_heads, beacon_state, blob_sidecars, block, block_attestations,
-        block_headers, block_id_headers, block_rewards, block_root, config_spec, debug_fork_choice,
-        deposit_contract, expected_withdrawals, fork_schedule, genesis,
-        keymanager_delete_fee_recipient, keymanager_delete_gas_limit, keymanager_delete_graffiti,
-        keymanager_delete_keystores, keymanager_delete_remote_keys, keymanager_get_gas_limit,
-        keymanager_get_graffiti, keymanager_import_keystores, keymanager_import_remote_keys,
-        keymanager_list_fee_recipient, keymanager_list_remote_keys,
+        beacon_events, beacon_heads, beacon_state, bid_traces, blob_availability, blob_sidecars,
+        block, block_attestations, block_headers, block_id_headers, block_rewards, block_root,
+        config_spec, debug_fork_choice, deposit_contract, expected_withdrawals, fork_schedule,
+        genesis, keymanager_delete_fee_recipient, keymanager_delete_gas_limit,
+        keymanager_delete_graffiti, keymanager_delete_keystores, keymanager_delete_remote_keys,
+        keymanager_get_gas_limit, keymanager_get_graffiti, keymanager_import_keystores,
+        keymanager_import_remote_keys, keymanager_list_fee_recipient, keymanager_list_remote_keys,
         keymanager_list_validating_pubkeys, keymanager_set_fee_recipient, keymanager_set_gas_limit,
         keymanager_set_graffiti, node_health, node_identity, node_peer, node_peer_count,
         node_peers, node_syncing_status, node_version, pool_attestations, pool_attester_slashings,
         pool_bls_to_execution_changes, pool_proposer_slashings, pool_voluntary_exits,
-        publish_blinded_block, publish_block, state_committees, state_finality_checkpoints,
-        state_fork, state_randao, state_root, state_sync_committees, state_validator,
-        state_validator_balances, state_validators, submit_pool_attestations,
-        submit_pool_attester_slashing, submit_pool_bls_to_execution_change,
-        submit_pool_proposer_slashing, submit_pool_sync_committees, submit_pool_voluntary_exit,
-        sync_committee_rewards, validator_aggregate_attestation, validator_attestation_data,
-        validator_attester_duties, validator_beacon_committee_selections, validator_blinded_block,
-        validator_block, validator_block_v3, validator_liveness, validator_prepare_beacon_proposer,
+        post_state_validators, publish_blinded_block, publish_blinded_block_v2, publish_block,
+        publish_block_v2, state_committees, state_finality_checkpoints, state_fork,
+        state_historical_summaries, state_proof, state_randao, state_root, state_sync_committees,
+        state_validator, state_validator_balances, state_validator_identities, state_validators,
+        submit_pool_attestations, submit_pool_attester_slashing,
+        submit_pool_bls_to_execution_change, submit_pool_proposer_slashing,
+        submit_pool_sync_committees, submit_pool_voluntary_exit, sync_committee_rewards,
+        validator_aggregate_attestation, validator_aggregate_attestation_v2,
+        validator_attestation_data, validator_attester_duties,
+        validator_beacon_committee_selections, validator_blinded_block, validator_block,
+        validator_block_v3, validator_liveness, validator_prepare_beacon_proposer,
         validator_proposer_duties, validator_publish_aggregate_and_proofs,
-        validator_publish_contributions_and_proofs, validator_register_validator,
-        validator_subscribe_to_beacon_committee, validator_subscribe_to_sync_committees,
-        validator_sync_committee_contribution, validator_sync_committee_duties,
-        validator_sync_committee_selections,
+        validator_publish_aggregate_and_proofs_v2, validator_publish_contributions_and_proofs,
+        validator_register_validator, validator_subscribe_to_beacon_committee,
+        validator_subscribe_to_sync_committees, validator_sync_committee_contribution,
+        validator_sync_committee_duties, validator_sync_committee_selections, withdrawal_sweep,
     },
 };
 
@@ -72,10 +81,12 @@ pub struct NormalState<P: Preset, W: Wait> {
     pub chain_config: Arc<ChainConfig>,
     pub controller: ApiController<P, W>,
     pub genesis_provider: GenesisProvider<P>,
+    pub eth1_api: Arc<Eth1Api>,
     pub keymanager: Arc<KeyManager>,
     pub validator_keys: Arc<HashSet<PublicKeyBytes>>,
     pub validator_config: Arc<ValidatorConfig>,
     pub metrics: Option<Arc<Metrics>>,
+    pub builder_api: Option<Arc<BuilderApi>>,
     pub network_config: Arc<NetworkConfig>,
     pub attestation_agg_pool: Arc<AttestationAggPool<P, W>>,
     pub sync_committee_agg_pool: Arc<SyncCommitteeAggPool<P, W>>,
@@ -83,6 +94,8 @@ pub struct NormalState<P: Preset, W: Wait> {
     pub is_synced: Arc<SyncedStatus>,
     pub is_back_synced: Arc<BackSyncedStatus>,
     pub event_channels: Arc<EventChannels>,
+    pub duties_cache: Arc<DutiesCache>,
+    pub proposal_reports: Arc<ProposalReports>,
     pub api_to_liveness_tx: Option<UnboundedSender<ApiToLiveness>>,
     pub api_to_metrics_tx: Option<UnboundedSender<ApiToMetrics>>,
     pub api_to_p2p_tx: UnboundedSender<ApiToP2p<P>>,
@@ -110,6 +123,12 @@ impl<P: Preset, W: Wait> FromRef<NormalState<P, W>> for GenesisProvider<P> {
     }
 }
 
+impl<P: Preset, W: Wait> FromRef<NormalState<P, W>> for Arc<Eth1Api> {
+    fn from_ref(state: &NormalState<P, W>) -> Self {
+        state.eth1_api.clone_arc()
+    }
+}
+
 impl<P: Preset, W: Wait> FromRef<NormalState<P, W>> for Arc<KeyManager> {
     fn from_ref(state: &NormalState<P, W>) -> Self {
         state.keymanager.clone_arc()
@@ -170,6 +189,18 @@ impl<P: Preset, W: Wait> FromRef<NormalState<P, W>> for Arc<EventChannels> {
     }
 }
 
+impl<P: Preset, W: Wait> FromRef<NormalState<P, W>> for Arc<DutiesCache> {
+    fn from_ref(state: &NormalState<P, W>) -> Self {
+        state.duties_cache.clone_arc()
+    }
+}
+
+impl<P: Preset, W: Wait> FromRef<NormalState<P, W>> for Arc<ProposalReports> {
+    fn from_ref(state: &NormalState<P, W>) -> Self {
+        state.proposal_reports.clone_arc()
+    }
+}
+
 impl<P: Preset, W: Wait> FromRef<NormalState<P, W>> for Option<UnboundedSender<ApiToLiveness>> {
     fn from_ref(state: &NormalState<P, W>) -> Self {
         state.api_to_liveness_tx.clone()
@@ -206,6 +237,12 @@ impl<P: Preset, W: Wait> FromRef<NormalState<P, W>> for Option<Arc<Metrics>> {
     }
 }
 
+impl<P: Preset, W: Wait> FromRef<NormalState<P, W>> for Option<Arc<BuilderApi>> {
+    fn from_ref(state: &NormalState<P, W>) -> Self {
+        state.builder_api.clone()
+    }
+}
+
 #[allow(clippy::struct_field_names)]
 #[cfg(test)]
 #[derive(Clone)]
@@ -218,20 +255,55 @@ pub struct TestState<P: Preset> {
     pub validator_to_p2p_rx: SpyReceiver<ValidatorToP2p<P>>,
 }
 
-pub fn normal_routes<P: Preset, W: Wait>(state: NormalState<P, W>) -> Router {
-    gui_routes()
-        .merge(eth_v1_beacon_routes(state.clone()))
+/// Limits applied to routes that load states or iterate over validators.
+#[derive(Clone, Default)]
+pub struct CostlyRouteLimits {
+    pub rate_limiter: Option<Arc<RateLimiter>>,
+    // Shared by all costly routes so that the concurrency limit applies to them together.
+    pub request_queue: Option<Arc<RequestQueue>>,
+    pub timeout: Option<Duration>,
+}
+
+impl CostlyRouteLimits {
+    fn apply<S: Clone + Send + Sync + 'static>(&self, router: Router<S>) -> Router<S> {
+        // Rate limiting is applied last to reject requests before any time is spent on them.
+        // Queuing is applied outside the timeout so that only time spent handling requests counts.
+        let router = http_api_utils::limit_request_duration(router, self.timeout);
+        let router = http_api_utils::queue_requests(router, self.request_queue.clone());
+        http_api_utils::limit_request_rate(router, self.rate_limiter.clone())
+    }
+}
+
+pub fn normal_routes<P: Preset, W: Wait>(
+    state: NormalState<P, W>,
+    admin_token: Option<AdminToken>,
+    max_body_size: usize,
+    max_block_body_size: usize,
+    costly_route_limits: &CostlyRouteLimits,
+) -> Router {
+    let routes = gui_routes()
+        .merge(admin_routes(admin_token))
+        .merge(eth_v1_beacon_routes(state.clone(), costly_route_limits))
         .merge(eth_v2_beacon_routes())
         .merge(eth_v1_builder_routes())
         .merge(eth_v1_config_routes())
         .merge(eth_v1_debug_routes())
-        .merge(eth_v2_debug_routes())
+        .merge(eth_v2_debug_routes(costly_route_limits))
         .route("/eth/v1/events", get(beacon_events))
         .merge(eth_v1_node_routes())
         .merge(eth_v1_validator_routes(state.clone()))
         .merge(eth_v2_validator_routes(state.clone()))
         .merge(eth_v3_validator_routes(state.clone()))
         .merge(eth_v1_keymanager_routes())
+        .merge(grandine_routes());
+
+    let block_publishing_routes = http_api_utils::limit_request_body_size(
+        block_publishing_routes(state.clone()),
+        max_block_body_size,
+    );
+
+    http_api_utils::limit_request_body_size(routes, max_body_size)
+        .merge(block_publishing_routes)
         .with_state(state)
 }
 
@@ -309,12 +381,14 @@ fn gui_routes<P: Preset, W: Wait>() -> Router<NormalState<P, W>> {
         )
         .route(
             "/features",
-            patch(|Json(features)| async { global::patch_features(features) }).route_layer(
-                axum::middleware::map_request_with_state(
-                    Feature::ServeEffectfulEndpoints,
-                    middleware::feature_is_enabled,
-                ),
-            ),
+            patch(|extracted| async {
+                let (ConnectInfo::<SocketAddr>(remote), Json(features)) = extracted;
+                global::patch_features(features, remote)
+            })
+            .route_layer(axum::middleware::map_request_with_state(
+                Feature::ServeEffectfulEndpoints,
+                middleware::feature_is_enabled,
+            )),
         )
         .route(
             "/system/stats",
@@ -333,12 +407,41 @@ fn gui_routes<P: Preset, W: Wait>() -> Router<NormalState<P, W>> {
         )
 }
 
+// Unlike `gui_routes`, these do not depend on `Feature`s and are instead protected by a token.
+// This allows `Feature`s to be toggled without enabling `Feature::ServeEffectfulEndpoints`.
+fn admin_routes<P: Preset, W: Wait>(admin_token: Option<AdminToken>) -> Router<NormalState<P, W>> {
+    Router::new()
+        .route("/admin/invalidate_block", post(admin::invalidate_block))
+        .route("/admin/reanchor", post(admin::reanchor))
+        .route("/admin/reverify_payload", post(admin::reverify_payload))
+        .route("/admin/missed_proposals", get(admin::missed_proposals))
+        .route("/admin/archive", post(admin::archive))
+        .route("/admin/prune", post(admin::prune))
+        .route("/admin/archival_progress", get(admin::archival_progress))
+        .route(
+            "/admin/features",
+            get(|| async { Json(global::get_features()) }).patch(|extracted| async {
+                let (ConnectInfo::<SocketAddr>(remote), Json(features)) = extracted;
+                global::patch_features(features, remote);
+                Json(global::get_features())
+            }),
+        )
+        .route_layer(axum::middleware::map_request_with_state(
+            admin_token,
+            middleware::is_admin,
+        ))
+}
+
 // TODO(Grandine Team): The standard routes should be restricted with `Feature`s too. The easiest way
 //                      to do this would be to add `Feature`s corresponding to groups of endpoints
 //                      (`beacon`, `config`, `debug`, etc.). The same could be done with `gui`, but
 //                      `PATCH /features` requires special attention because it's more dangerous.
 
-fn eth_v1_beacon_routes<P: Preset, W: Wait>(state: NormalState<P, W>) -> Router<NormalState<P, W>> {
+// Routes that load states or iterate over validators are rate limited because they are costly.
+fn eth_v1_beacon_routes<P: Preset, W: Wait>(
+    state: NormalState<P, W>,
+    costly_route_limits: &CostlyRouteLimits,
+) -> Router<NormalState<P, W>> {
     let state_routes = Router::new()
         .route("/eth/v1/beacon/states/:state_id/root", get(state_root))
         .route("/eth/v1/beacon/states/:state_id/fork", get(state_fork))
@@ -348,12 +451,16 @@ fn eth_v1_beacon_routes<P: Preset, W: Wait>(state: NormalState<P, W>) -> Router<
         )
         .route(
             "/eth/v1/beacon/states/:state_id/validators",
-            get(state_validators),
+            get(state_validators).post(post_state_validators),
         )
         .route(
             "/eth/v1/beacon/states/:state_id/validators/:validator_id",
             get(state_validator),
         )
+        .route(
+            "/eth/v1/beacon/states/:state_id/validator_identities",
+            post(state_validator_identities),
+        )
         .route(
             "/eth/v1/beacon/states/:state_id/validator_balances",
             get(state_validator_balances),
@@ -366,7 +473,14 @@ fn eth_v1_beacon_routes<P: Preset, W: Wait>(state: NormalState<P, W>) -> Router<
             "/eth/v1/beacon/states/:state_id/sync_committees",
             get(state_sync_committees),
         )
-        .route("/eth/v1/beacon/states/:state_id/randao", get(state_randao));
+        .route(
+            "/eth/v1/beacon/states/:state_id/historical_summaries",
+            get(state_historical_summaries),
+        )
+        .route("/eth/v1/beacon/states/:state_id/randao", get(state_randao))
+        .route("/eth/v0/beacon/states/:state_id/proof", get(state_proof));
+
+    let state_routes = costly_route_limits.apply(state_routes);
 
     let header_routes = Router::new()
         .route("/eth/v1/beacon/headers", get(block_headers))
@@ -377,13 +491,6 @@ fn eth_v1_beacon_routes<P: Preset, W: Wait>(state: NormalState<P, W>) -> Router<
         .route(
             "/eth/v1/beacon/blocks/:block_id/attestations",
             get(block_attestations),
-        )
-        .route(
-            "/eth/v1/beacon/blocks",
-            post(publish_block).route_layer(axum::middleware::map_request_with_state(
-                state.clone(),
-                middleware::is_synced,
-            )),
         );
 
     let pool_routes = Router::new()
@@ -422,14 +529,9 @@ fn eth_v1_beacon_routes<P: Preset, W: Wait>(state: NormalState<P, W>) -> Router<
             post(sync_committee_rewards),
         );
 
+    let reward_routes = costly_route_limits.apply(reward_routes);
+
     Router::new()
-        .route(
-            "/eth/v1/beacon/blinded_blocks",
-            post(publish_blinded_block).route_layer(axum::middleware::map_request_with_state(
-                state,
-                middleware::is_synced,
-            )),
-        )
         .route("/eth/v1/beacon/blob_sidecars/:block_id", get(blob_sidecars))
         .route("/eth/v1/beacon/genesis", get(genesis))
         .merge(state_routes)
@@ -439,6 +541,41 @@ fn eth_v1_beacon_routes<P: Preset, W: Wait>(state: NormalState<P, W>) -> Router<
         .merge(reward_routes)
 }
 
+// These are separate from `eth_v1_beacon_routes` because they accept much larger bodies.
+fn block_publishing_routes<P: Preset, W: Wait>(
+    state: NormalState<P, W>,
+) -> Router<NormalState<P, W>> {
+    Router::new()
+        .route(
+            "/eth/v1/beacon/blocks",
+            post(publish_block).route_layer(axum::middleware::map_request_with_state(
+                state.clone(),
+                middleware::is_synced,
+            )),
+        )
+        .route(
+            "/eth/v1/beacon/blinded_blocks",
+            post(publish_blinded_block).route_layer(axum::middleware::map_request_with_state(
+                state.clone(),
+                middleware::is_synced,
+            )),
+        )
+        .route(
+            "/eth/v2/beacon/blocks",
+            post(publish_block_v2).route_layer(axum::middleware::map_request_with_state(
+                state.clone(),
+                middleware::is_synced,
+            )),
+        )
+        .route(
+            "/eth/v2/beacon/blinded_blocks",
+            post(publish_blinded_block_v2).route_layer(axum::middleware::map_request_with_state(
+                state,
+                middleware::is_synced,
+            )),
+        )
+}
+
 fn eth_v2_beacon_routes<P: Preset, W: Wait>() -> Router<NormalState<P, W>> {
     Router::new().route("/eth/v2/beacon/blocks/:block_id", get(block))
 }
@@ -450,6 +587,19 @@ fn eth_v1_builder_routes<P: Preset, W: Wait>() -> Router<NormalState<P, W>> {
     )
 }
 
+fn grandine_routes<P: Preset, W: Wait>() -> Router<NormalState<P, W>> {
+    Router::new()
+        .route(
+            "/grandine/beacon/blob_availability/:block_root",
+            get(blob_availability),
+        )
+        .route("/grandine/validator/bid_traces", get(bid_traces))
+        .route(
+            "/grandine/validator/withdrawal_sweep",
+            get(withdrawal_sweep),
+        )
+}
+
 fn eth_v1_config_routes<P: Preset, W: Wait>() -> Router<NormalState<P, W>> {
     Router::new()
         .route("/eth/v1/config/fork_schedule", get(fork_schedule::<P>))
@@ -461,9 +611,14 @@ fn eth_v1_debug_routes<P: Preset, W: Wait>() -> Router<NormalState<P, W>> {
     Router::new().route("/eth/v1/debug/fork_choice", get(debug_fork_choice))
 }
 
-fn eth_v2_debug_routes<P: Preset, W: Wait>() -> Router<NormalState<P, W>> {
+fn eth_v2_debug_routes<P: Preset, W: Wait>(
+    costly_route_limits: &CostlyRouteLimits,
+) -> Router<NormalState<P, W>> {
+    let state_routes =
+        Router::new().route("/eth/v2/debug/beacon/states/:state_id", get(beacon_state));
+
     Router::new()
-        .route("/eth/v2/debug/beacon/states/:state_id", get(beacon_state))
+        .merge(costly_route_limits.apply(state_routes))
         .route("/eth/v2/debug/beacon/heads", get(beacon_heads))
 }
 
@@ -510,6 +665,14 @@ fn eth_v1_validator_routes<P: Preset, W: Wait>(
             "/eth/v1/validator/aggregate_and_proofs",
             post(validator_publish_aggregate_and_proofs),
         )
+        .route(
+            "/eth/v2/validator/aggregate_attestation",
+            get(validator_aggregate_attestation_v2),
+        )
+        .route(
+            "/eth/v2/validator/aggregate_and_proofs",
+            post(validator_publish_aggregate_and_proofs_v2),
+        )
         .route(
             "/eth/v1/validator/beacon_committee_subscriptions",
             post(validator_subscribe_to_beacon_committee),
@@ -1,5 +1,6 @@
 use snapshot_test_utils::Case;
 use test_generator::test_resources;
+use types::preset::Preset;
 
 use crate::context::Context;
 
@@ -8,11 +9,20 @@ use crate::context::Context;
 // TODO(feature/deneb): Update snapshot tests just like for Capella.
 const UPDATE_RESPONSES: bool = false;
 
+// Set this to the HTTP API address of a synced mainnet node (like `"127.0.0.1:5052"`)
+// to record responses for test cases with names ending with `recorded` from it.
+const RECORD_FROM: Option<&str> = None;
+
 #[test]
 fn update_responses_should_be_false_when_committing() {
     assert!(!UPDATE_RESPONSES);
 }
 
+#[test]
+fn record_from_should_be_none_when_committing() {
+    assert!(RECORD_FROM.is_none());
+}
+
 #[test_resources("grandine-snapshot-tests/mainnet/mainnet/genesis/none/*")]
 fn mainnet_genesis_none(case: Case) {
     Context::mainnet_genesis_none().run_case(case, UPDATE_RESPONSES)
@@ -21,19 +31,19 @@ fn mainnet_genesis_none(case: Case) {
 #[test_resources("grandine-snapshot-tests/mainnet/mainnet/genesis/128-slots/*")]
 #[cfg(feature = "eth2-cache")]
 fn mainnet_genesis_128_slots(case: Case) {
-    Context::mainnet_genesis_128_slots().run_case(case, UPDATE_RESPONSES)
+    run_or_record_case(Context::mainnet_genesis_128_slots(), case)
 }
 
 #[test_resources("grandine-snapshot-tests/mainnet/mainnet/epoch-96214/128-slots/*")]
 #[cfg(feature = "eth2-cache")]
 fn mainnet_epoch_96214_128_slots(case: Case) {
-    Context::mainnet_epoch_96214_128_slots().run_case(case, UPDATE_RESPONSES)
+    run_or_record_case(Context::mainnet_epoch_96214_128_slots(), case)
 }
 
 #[test_resources("grandine-snapshot-tests/mainnet/mainnet/epoch-244816/128-slots/*")]
 #[cfg(feature = "eth2-cache")]
 fn mainnet_epoch_244816_128_slots(case: Case) {
-    Context::mainnet_epoch_244816_128_slots().run_case(case, UPDATE_RESPONSES)
+    run_or_record_case(Context::mainnet_epoch_244816_128_slots(), case)
 }
 
 #[test_resources("grandine-snapshot-tests/minimal/minimal/quick-start/all-keys/*")]
@@ -60,3 +70,16 @@ fn minimal_rapid_upgrade_all_keys(case: Case) {
 fn minimal_rapid_upgrade_all_phases_all_keys(case: Case) {
     Context::minimal_rapid_upgrade_all_phases_all_keys().run_case(case, UPDATE_RESPONSES)
 }
+
+fn run_or_record_case<P: Preset>(context: Context<P>, case: Case) {
+    match RECORD_FROM {
+        Some(address) if case.is_recorded() => {
+            let live_address = address
+                .parse()
+                .expect("RECORD_FROM should be a valid socket address");
+
+            context.record_case(case, live_address)
+        }
+        _ => context.run_case(case, UPDATE_RESPONSES),
+    }
+}
@@ -5,9 +5,13 @@
 // This makes `http_api::routing` less messy at the cost of coupling to `axum` even more.
 #![allow(clippy::unused_async)]
 
-use std::{collections::HashSet, sync::Arc};
+use core::hash::Hash;
+use std::{
+    collections::{hash_map::Entry, BTreeSet, HashMap},
+    sync::Arc,
+};
 
-use anyhow::{ensure, Error as AnyhowError, Result};
+use anyhow::{bail, ensure, Error as AnyhowError, Result};
 use axum::{
     extract::State,
     http::{HeaderMap, StatusCode},
@@ -17,18 +21,20 @@ use axum::{
     },
     Json,
 };
-use bls::{PublicKeyBytes, SignatureBytes};
-use builder_api::unphased::containers::SignedValidatorRegistrationV1;
+use bls::{PublicKeyBytes, Signature, SignatureBytes};
+use builder_api::{unphased::containers::SignedValidatorRegistrationV1, BidTrace, BuilderApi};
 use enum_iterator::Sequence as _;
-use eth1_api::ApiController;
+use eth1_api::{ApiController, Eth1Api};
 use eth2_libp2p::PeerId;
-use fork_choice_control::{ForkChoiceContext, ForkTip, Wait};
+use fork_choice_control::{BlobAvailability, ForkChoiceContext, ForkTip, Wait};
 use futures::{
     channel::mpsc::UnboundedSender,
     stream::{FuturesOrdered, Stream, StreamExt as _},
 };
 use genesis::GenesisProvider;
-use helper_functions::{accessors, misc, slot_report::SyncAggregateRewards};
+use helper_functions::{
+    accessors, misc, signing::SignForSingleFork as _, slot_report::SyncAggregateRewards,
+};
 use http_api_utils::BlockId;
 use itertools::{izip, Either, Itertools as _};
 use keymanager::{KeyManager, KeymanagerOperationStatus, RemoteKey, ValidatingPubkey};
@@ -45,28 +51,30 @@ use prometheus_metrics::Metrics;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use serde_with::{As, DisplayFromStr};
-use ssz::{ContiguousList, SszHash as _};
+use ssz::{ContiguousList, ContiguousVector, SszHash as _};
 use std_ext::ArcExt as _;
 use tap::Pipe as _;
 use tokio_stream::wrappers::{errors::BroadcastStreamRecvError, BroadcastStream};
 use try_from_iterator::TryFromIterator as _;
-use typenum::Unsigned as _;
+use typenum::{Unsigned as _, U5};
 use types::{
     altair::{
-        containers::{SignedContributionAndProof, SyncCommitteeContribution, SyncCommitteeMessage},
+        containers::{
+            SignedContributionAndProof, SyncAggregatorSelectionData, SyncCommitteeContribution,
+            SyncCommitteeMessage,
+        },
         primitives::SubcommitteeIndex,
     },
     bellatrix::primitives::{Gas, Wei},
     capella::containers::{SignedBlsToExecutionChange, Withdrawal},
+    collections::HistoricalSummaries,
     combined::{BeaconBlock, BeaconState, SignedBeaconBlock, SignedBlindedBeaconBlock},
     config::Config as ChainConfig,
     deneb::{
         containers::{BlobIdentifier, BlobSidecar},
         primitives::BlobIndex,
     },
-    nonstandard::{
-        Phase, RelativeEpoch, SlashingKind, ValidationOutcome, WithBlobsAndMev, WithStatus,
-    },
+    nonstandard::{Phase, SlashingKind, ValidationOutcome, WithBlobsAndMev, WithStatus},
     phase0::{
         consts::{GENESIS_EPOCH, GENESIS_SLOT},
         containers::{
@@ -79,23 +87,31 @@ use types::{
         },
     },
     preset::Preset,
-    traits::{BeaconState as _, SignedBeaconBlock as _},
+    traits::{BeaconState as _, PostCapellaBeaconState as _, SignedBeaconBlock as _},
+};
+use validator::{
+    ApiToValidator, AttesterDuty, BeaconCommitteeSelection, DutiesCache, ProposerDuty,
+    SyncCommitteeSelection, ValidatorBlindedBlock, ValidatorConfig, ValidatorProposerData,
+    WithdrawalSweepPosition,
 };
-use validator::{ApiToValidator, ValidatorBlindedBlock, ValidatorConfig, ValidatorProposerData};
 use zeroize::Zeroizing;
 
 use crate::{
     block_id,
     error::{Error, IndexedError},
     events::{EventChannels, Topic},
-    extractors::{EthJson, EthJsonOrSsz, EthPath, EthQuery},
+    extractors::{EthConsensusVersion, EthJson, EthJsonOrSsz, EthPath, EthQuery},
     full_config::FullConfig,
     misc::{APIBlock, BackSyncedStatus, SignedAPIBlock, SyncedStatus},
-    response::{EthResponse, JsonOrSsz},
+    response::{EthResponse, EthStreamingResponse, JsonOrSsz},
     state_id::StateId,
     validator_status::{ValidatorId, ValidatorStatus},
 };
 
+// Sent by clients reconnecting to event streams. See
+// <https://html.spec.whatwg.org/multipage/server-sent-events.html#the-last-event-id-header>.
+const LAST_EVENT_ID: &str = "last-event-id";
+
 #[derive(Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct BlobSidecarsQuery {
@@ -128,6 +144,15 @@ pub struct ValidatorIdOrStatusQuery {
     status: Vec<ValidatorStatus>,
 }
 
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StateValidatorsBody {
+    #[serde(default)]
+    ids: Vec<ValidatorId>,
+    #[serde(default)]
+    statuses: Vec<ValidatorStatus>,
+}
+
 #[derive(Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct StateCommitteesQuery {
@@ -142,6 +167,12 @@ pub struct StateSyncCommitteesQuery {
     epoch: Option<Epoch>,
 }
 
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StateProofQuery {
+    gindex: u64,
+}
+
 #[derive(Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct StateRandaoQuery {
@@ -155,6 +186,26 @@ pub struct BlockHeadersQuery {
     parent_root: Option<H256>,
 }
 
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PublishBlockQuery {
+    #[serde(default)]
+    broadcast_validation: BroadcastValidation,
+}
+
+/// Level of validation a block must pass before it is broadcast.
+///
+/// See the `broadcast_validation` parameter of
+/// [`publishBlockV2`](https://ethereum.github.io/beacon-APIs/#/Beacon/publishBlockV2).
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BroadcastValidation {
+    #[default]
+    Gossip,
+    Consensus,
+    ConsensusAndEquivocation,
+}
+
 #[derive(Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct PoolAttestationQuery {
@@ -186,6 +237,14 @@ pub struct AggregateAttestationQuery {
     slot: Slot,
 }
 
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AggregateAttestationV2Query {
+    attestation_data_root: H256,
+    slot: Slot,
+    committee_index: CommitteeIndex,
+}
+
 #[derive(Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct AttestationDataQuery {
@@ -208,6 +267,12 @@ pub struct ExpectedWithdrawalsQuery {
     proposal_slot: Option<Slot>,
 }
 
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BidTracesQuery {
+    slot: Option<Slot>,
+}
+
 #[allow(clippy::struct_field_names)]
 #[derive(Serialize)]
 pub struct GetGenesisResponse {
@@ -253,6 +318,24 @@ pub struct StateFinalityCheckpointsResponse {
     finalized: Checkpoint,
 }
 
+#[derive(Serialize)]
+#[serde(bound = "")]
+pub struct StateHistoricalSummariesResponse<P: Preset> {
+    historical_summaries: HistoricalSummaries<P>,
+    proof: ContiguousVector<H256, U5>,
+    #[serde(with = "serde_utils::string_or_native")]
+    slot: Slot,
+}
+
+#[derive(Serialize)]
+pub struct StateProofResponse {
+    #[serde(with = "serde_utils::string_or_native")]
+    gindex: u64,
+    leaf: H256,
+    proof: Vec<H256>,
+    state_root: H256,
+}
+
 #[derive(Serialize)]
 pub struct StateRandaoResponse {
     randao: H256,
@@ -268,6 +351,15 @@ pub struct StateValidatorResponse {
     validator: Validator,
 }
 
+#[derive(Serialize)]
+pub struct ValidatorIdentityResponse {
+    #[serde(with = "serde_utils::string_or_native")]
+    index: ValidatorIndex,
+    pubkey: PublicKeyBytes,
+    #[serde(with = "serde_utils::string_or_native")]
+    activation_epoch: Epoch,
+}
+
 #[derive(Serialize)]
 pub struct StateCommitteeResponse {
     #[serde(with = "serde_utils::string_or_native")]
@@ -320,9 +412,6 @@ struct NodeVersionResponse<'version> {
     version: Option<&'version str>,
 }
 
-// TODO(Grandine Team): `NodeSyncingResponse` should have an `el_offline` field.
-//                      It was added in Eth Beacon Node API version 2.4.0.
-//                      See <https://ethereum.github.io/beacon-APIs/#/Node/getSyncingStatus>.
 #[derive(Serialize)]
 pub struct NodeSyncingResponse {
     #[serde(with = "serde_utils::string_or_native")]
@@ -331,6 +420,7 @@ pub struct NodeSyncingResponse {
     sync_distance: Slot,
     is_syncing: bool,
     is_optimistic: bool,
+    el_offline: bool,
 }
 
 #[derive(Serialize)]
@@ -439,6 +529,64 @@ pub async fn genesis<P: Preset>(
     Ok(EthResponse::json(response))
 }
 
+/// `GET /grandine/validator/bid_traces`
+///
+/// This is not part of the Eth Beacon Node API. Returns recent bids received from relays.
+/// The response is empty if no builder is configured.
+pub async fn bid_traces(
+    State(builder_api): State<Option<Arc<BuilderApi>>>,
+    EthQuery(query): EthQuery<BidTracesQuery>,
+) -> EthResponse<Vec<BidTrace>> {
+    let BidTracesQuery { slot } = query;
+
+    let traces = builder_api
+        .map(|builder_api| builder_api.bid_traces(slot))
+        .unwrap_or_default();
+
+    EthResponse::json(traces)
+}
+
+/// `GET /grandine/beacon/blob_availability/{block_root}`
+///
+/// This is not part of the Eth Beacon Node API. Reports which blob sidecars have been received
+/// for a block and where they came from. Meant for debugging data availability issues.
+pub async fn blob_availability<P: Preset, W: Wait>(
+    State(controller): State<ApiController<P, W>>,
+    EthPath(block_root): EthPath<H256>,
+) -> EthResponse<BlobAvailability> {
+    EthResponse::json(controller.blob_availability(block_root))
+}
+
+/// `GET /grandine/validator/withdrawal_sweep`
+///
+/// This is not part of the Eth Beacon Node API. Reports where the validators passed to
+/// `--withdrawal-sweep-validator-indices` are in the withdrawal sweep of the head state and
+/// when the sweep is expected to reach them.
+pub async fn withdrawal_sweep<P: Preset, W: Wait>(
+    State(controller): State<ApiController<P, W>>,
+    State(chain_config): State<Arc<ChainConfig>>,
+    State(validator_config): State<Arc<ValidatorConfig>>,
+) -> Result<EthResponse<Vec<WithdrawalSweepPosition>>, Error> {
+    let WithStatus {
+        value: state,
+        optimistic,
+        finalized,
+    } = controller.head_state();
+
+    let positions = tokio::task::spawn_blocking(move || {
+        validator::withdrawal_sweep_positions(
+            &chain_config,
+            &state,
+            &validator_config.withdrawal_sweep_validator_indices,
+        )
+    })
+    .await?;
+
+    Ok(EthResponse::json(positions)
+        .execution_optimistic(optimistic)
+        .finalized(finalized))
+}
+
 /// `GET /eth/v1/builder/states/{state_id}/expected_withdrawals`
 pub async fn expected_withdrawals<P: Preset, W: Wait>(
     State(controller): State<ApiController<P, W>>,
@@ -450,7 +598,7 @@ pub async fn expected_withdrawals<P: Preset, W: Wait>(
         value: state,
         optimistic,
         finalized,
-    } = state_id.state(&controller, genesis_provider)?;
+    } = state_id.state(&controller, genesis_provider).await?;
 
     let proposal_slot = query.proposal_slot.unwrap_or_else(|| state.slot() + 1);
 
@@ -488,7 +636,7 @@ pub async fn state_root<P: Preset, W: Wait>(
         value: state,
         optimistic,
         finalized,
-    } = state_id.state(&controller, genesis_provider)?;
+    } = state_id.state(&controller, genesis_provider).await?;
 
     let root = state.hash_tree_root();
 
@@ -507,7 +655,7 @@ pub async fn state_fork<P: Preset, W: Wait>(
         value: state,
         optimistic,
         finalized,
-    } = state_id.state(&controller, genesis_provider)?;
+    } = state_id.state(&controller, genesis_provider).await?;
 
     Ok(EthResponse::json(state.fork())
         .execution_optimistic(optimistic)
@@ -524,7 +672,7 @@ pub async fn state_finality_checkpoints<P: Preset, W: Wait>(
         value: state,
         optimistic,
         finalized,
-    } = state_id.state(&controller, genesis_provider)?;
+    } = state_id.state(&controller, genesis_provider).await?;
 
     let response = StateFinalityCheckpointsResponse {
         previous_justified: state.previous_justified_checkpoint(),
@@ -543,44 +691,72 @@ pub async fn state_validators<P: Preset, W: Wait>(
     State(genesis_provider): State<GenesisProvider<P>>,
     EthPath(state_id): EthPath<StateId>,
     EthQuery(query): EthQuery<ValidatorIdOrStatusQuery>,
-) -> Result<EthResponse<Vec<StateValidatorResponse>>, Error> {
+) -> Result<EthStreamingResponse<impl Iterator<Item = StateValidatorResponse> + Send>, Error> {
+    let ValidatorIdOrStatusQuery { id, status } = query;
+
     let WithStatus {
         value: state,
         optimistic,
         finalized,
-    } = state_id.state(&controller, genesis_provider)?;
+    } = state_id.state(&controller, genesis_provider).await?;
 
-    let validators = izip!(
-        0..,
-        state.validators(),
-        state.balances().into_iter().copied(),
+    Ok(
+        EthStreamingResponse::json(matching_validators(&controller, state, &id, status))
+            .execution_optimistic(optimistic)
+            .finalized(finalized),
     )
-    .filter(|(index, validator, _)| {
-        let validator_status = ValidatorStatus::new(validator, &state);
+}
 
-        let allowed_by_id = query.id.is_empty()
-            || query.id.iter().any(|validator_id| match validator_id {
-                ValidatorId::ValidatorIndex(validator_index) => index == validator_index,
-                ValidatorId::PublicKey(pubkey) => validator.pubkey.as_bytes() == pubkey,
-            });
+/// `POST /eth/v1/beacon/states/{state_id}/validators`
+pub async fn post_state_validators<P: Preset, W: Wait>(
+    State(controller): State<ApiController<P, W>>,
+    State(genesis_provider): State<GenesisProvider<P>>,
+    EthPath(state_id): EthPath<StateId>,
+    EthJson(body): EthJson<StateValidatorsBody>,
+) -> Result<EthStreamingResponse<impl Iterator<Item = StateValidatorResponse> + Send>, Error> {
+    let StateValidatorsBody { ids, statuses } = body;
 
-        let allowed_by_status = query.status.is_empty()
-            || query
-                .status
-                .iter()
-                .any(|status| status.matches(validator_status));
+    let WithStatus {
+        value: state,
+        optimistic,
+        finalized,
+    } = state_id.state(&controller, genesis_provider).await?;
 
-        allowed_by_id && allowed_by_status
-    })
-    .map(|(index, validator, balance)| StateValidatorResponse {
-        index,
-        balance,
-        status: ValidatorStatus::new(validator, &state),
-        validator: validator.clone(),
-    })
-    .collect();
+    Ok(
+        EthStreamingResponse::json(matching_validators(&controller, state, &ids, statuses))
+            .execution_optimistic(optimistic)
+            .finalized(finalized),
+    )
+}
+
+/// `POST /eth/v1/beacon/states/{state_id}/validator_identities`
+pub async fn state_validator_identities<P: Preset, W: Wait>(
+    State(controller): State<ApiController<P, W>>,
+    State(genesis_provider): State<GenesisProvider<P>>,
+    EthPath(state_id): EthPath<StateId>,
+    EthJson(validator_ids): EthJson<Vec<ValidatorId>>,
+) -> Result<EthStreamingResponse<impl Iterator<Item = ValidatorIdentityResponse> + Send>, Error> {
+    let WithStatus {
+        value: state,
+        optimistic,
+        finalized,
+    } = state_id.state(&controller, genesis_provider).await?;
+
+    let identities =
+        selected_validator_indices(&controller, &state, &validator_ids).map(move |index| {
+            let validator = state
+                .validators()
+                .get(index)
+                .expect("selected_validator_indices only returns indices of existing validators");
+
+            ValidatorIdentityResponse {
+                index,
+                pubkey: validator.pubkey.to_bytes(),
+                activation_epoch: validator.activation_epoch,
+            }
+        });
 
-    Ok(EthResponse::json(validators)
+    Ok(EthStreamingResponse::json(identities)
         .execution_optimistic(optimistic)
         .finalized(finalized))
 }
@@ -595,10 +771,10 @@ pub async fn state_validator<P: Preset, W: Wait>(
         value: state,
         optimistic,
         finalized,
-    } = state_id.state(&controller, genesis_provider)?;
+    } = state_id.state(&controller, genesis_provider).await?;
 
     let validator_index = validator_id
-        .validator_index(&state)
+        .validator_index(&controller, &state)
         .ok_or(Error::ValidatorNotFound)?;
 
     let validator = state
@@ -635,7 +811,7 @@ pub async fn state_validator_balances<P: Preset, W: Wait>(
         value: state,
         optimistic,
         finalized,
-    } = state_id.state(&controller, genesis_provider)?;
+    } = state_id.state(&controller, genesis_provider).await?;
 
     let balances = izip!(
         0..,
@@ -668,7 +844,7 @@ pub async fn state_committees<P: Preset, W: Wait>(
         value: mut state,
         optimistic,
         finalized,
-    } = state_id.state(&controller, genesis_provider)?;
+    } = state_id.state(&controller, genesis_provider).await?;
 
     let state_epoch = misc::compute_epoch_at_slot::<P>(state.slot());
     let epoch = query.epoch.unwrap_or(state_epoch);
@@ -736,7 +912,7 @@ pub async fn state_sync_committees<P: Preset, W: Wait>(
         value: state,
         optimistic,
         finalized,
-    } = state_id.state(&controller, genesis_provider)?;
+    } = state_id.state(&controller, genesis_provider).await?;
 
     let Some(state) = state.post_altair() else {
         return Ok(EthResponse::json(StateSyncCommitteeResponse::default())
@@ -761,7 +937,7 @@ pub async fn state_sync_committees<P: Preset, W: Wait>(
     let validator_indices = committee
         .pubkeys
         .iter()
-        .filter_map(|pubkey| accessors::index_of_public_key(state, pubkey.to_bytes()))
+        .filter_map(|pubkey| controller.validator_index(state, pubkey.to_bytes()))
         .collect_vec();
 
     let validators = validator_indices.clone();
@@ -781,6 +957,73 @@ pub async fn state_sync_committees<P: Preset, W: Wait>(
         .into_response())
 }
 
+/// `GET /eth/v1/beacon/states/{state_id}/historical_summaries`
+///
+/// The proof is a Merkle branch of `historical_summaries` against the root of the state.
+/// It lets clients verify summaries without downloading the whole state.
+pub async fn state_historical_summaries<P: Preset, W: Wait>(
+    State(controller): State<ApiController<P, W>>,
+    State(genesis_provider): State<GenesisProvider<P>>,
+    EthPath(state_id): EthPath<StateId>,
+) -> Result<EthResponse<StateHistoricalSummariesResponse<P>>, Error> {
+    let WithStatus {
+        value: state,
+        optimistic,
+        finalized,
+    } = state_id.state(&controller, genesis_provider).await?;
+
+    let version = state.phase();
+    let state = state.post_capella().ok_or(Error::StatePreCapella)?;
+
+    let response = StateHistoricalSummariesResponse {
+        historical_summaries: state.historical_summaries().clone(),
+        proof: misc::historical_summaries_proof(state),
+        slot: state.slot(),
+    };
+
+    Ok(EthResponse::json(response)
+        .version(version)
+        .execution_optimistic(optimistic)
+        .finalized(finalized))
+}
+
+/// `GET /eth/v0/beacon/states/{state_id}/proof`
+///
+/// This is not part of the Eth Beacon Node API. `gindex` may refer to any node at or above the
+/// roots of individual state fields or to any node of `block_roots`, `state_roots` and
+/// `randao_mixes`, including individual elements. Nodes inside lists are rejected with 400.
+/// Proofs of block roots can be checked against roots exposed to the execution layer by EIP-4788
+/// after verifying the state root against the block header.
+pub async fn state_proof<P: Preset, W: Wait>(
+    State(controller): State<ApiController<P, W>>,
+    State(genesis_provider): State<GenesisProvider<P>>,
+    EthPath(state_id): EthPath<StateId>,
+    EthQuery(query): EthQuery<StateProofQuery>,
+) -> Result<EthResponse<StateProofResponse>, Error> {
+    let StateProofQuery { gindex } = query;
+
+    let WithStatus {
+        value: state,
+        optimistic,
+        finalized,
+    } = state_id.state(&controller, genesis_provider).await?;
+
+    let (leaf, proof) =
+        misc::beacon_state_proof(&state, gindex).map_err(Error::InvalidGeneralizedIndex)?;
+
+    let response = StateProofResponse {
+        gindex,
+        leaf,
+        proof,
+        state_root: state.hash_tree_root(),
+    };
+
+    Ok(EthResponse::json(response)
+        .version(state.phase())
+        .execution_optimistic(optimistic)
+        .finalized(finalized))
+}
+
 /// `GET /eth/v1/beacon/states/{state_id}/randao`
 pub async fn state_randao<P: Preset, W: Wait>(
     State(controller): State<ApiController<P, W>>,
@@ -792,7 +1035,7 @@ pub async fn state_randao<P: Preset, W: Wait>(
         value: state,
         optimistic,
         finalized,
-    } = state_id.state(&controller, genesis_provider)?;
+    } = state_id.state(&controller, genesis_provider).await?;
 
     // If `epoch` is in the future, return the RANDAO mix for the current epoch.
     // This matches how RANDAO mixes are updated during epoch transitions.
@@ -801,7 +1044,9 @@ pub async fn state_randao<P: Preset, W: Wait>(
     let epoch = query.epoch.unwrap_or(state_epoch).min(state_epoch);
     let difference = state_epoch - epoch;
 
-    if difference > P::EpochsPerHistoricalVector::U64 {
+    // Mixes are stored in a ring buffer, so the one for `epoch` is overwritten
+    // once `state_epoch` is `EpochsPerHistoricalVector` epochs ahead of it.
+    if difference >= P::EpochsPerHistoricalVector::U64 {
         return Err(Error::EpochOutOfRangeForStateRandao);
     };
 
@@ -826,7 +1071,7 @@ pub async fn state_randao<P: Preset, W: Wait>(
 pub async fn block_headers<P: Preset, W: Wait>(
     State(controller): State<ApiController<P, W>>,
     EthQuery(query): EthQuery<BlockHeadersQuery>,
-) -> Result<EthResponse<[BlockHeadersResponse; 1]>, Error> {
+) -> Result<EthResponse<Vec<BlockHeadersResponse>>, Error> {
     let opt_block_by_slot = |slot| -> Result<_> {
         if let Some(root) = controller.block_root_by_slot(slot)? {
             if let Some(with_status) = controller.block_by_root(root)? {
@@ -837,53 +1082,78 @@ pub async fn block_headers<P: Preset, W: Wait>(
         Ok(None)
     };
 
-    let block_result = match query {
-        // Default to blocks at the same slot as the head rather than the head directly.
-        // [The specification] refers to the "head slot" and "blocks" (plural).
-        // Lighthouse looks up the head directly, but the other 4 implementations use its slot.
-        // This currently makes no difference because we never return multiple headers.
-        //
-        // [The specification]: https://ethereum.github.io/beacon-APIs/#/Beacon/getBlockHeaders
-        BlockHeadersQuery {
-            slot: None,
-            parent_root: None,
-        } => opt_block_by_slot(controller.head_slot())?,
-        BlockHeadersQuery {
-            slot: Some(slot),
-            parent_root: None,
-        } => opt_block_by_slot(slot)?,
-        BlockHeadersQuery {
-            slot: None,
-            parent_root: Some(parent_root),
-        } => controller
+    let BlockHeadersQuery { slot, parent_root } = query;
+
+    // Default to blocks at the same slot as the head rather than the head directly.
+    // [The specification] refers to the "head slot" and "blocks" (plural).
+    // Lighthouse looks up the head directly, but the other 4 implementations use its slot.
+    //
+    // [The specification]: https://ethereum.github.io/beacon-APIs/#/Beacon/getBlockHeaders
+    let slot = match (slot, parent_root) {
+        (None, None) => Some(controller.head_slot()),
+        _ => slot,
+    };
+
+    // Look up the canonical block separately because it may already be finalized.
+    let canonical_block = match (slot, parent_root) {
+        (Some(slot), _) => opt_block_by_slot(slot)?,
+        (None, Some(parent_root)) => controller
             .block_by_root(parent_root)?
             .and_then(|parent_block| parent_block.value.message().slot().checked_add(1))
             .map(opt_block_by_slot)
             .transpose()?
-            .flatten()
-            .filter(|(_, with_status)| with_status.value.message().parent_root() == parent_root),
-        BlockHeadersQuery {
-            slot: Some(slot),
-            parent_root: Some(parent_root),
-        } => opt_block_by_slot(slot)?
-            .filter(|(_, with_status)| with_status.value.message().parent_root() == parent_root),
-    };
+            .flatten(),
+        (None, None) => None,
+    }
+    .filter(|(_, with_status)| {
+        parent_root.map_or(true, |parent_root| {
+            with_status.value.message().parent_root() == parent_root
+        })
+    });
+
+    // Blocks that are not finalized may have been orphaned.
+    // Explorers rely on this endpoint to find them.
+    let other_blocks = controller
+        .unfinalized_blocks(slot, parent_root)
+        .into_iter()
+        .map(|with_status| {
+            let root = with_status.value.root;
+            (
+                root,
+                with_status.map(|block_with_root| block_with_root.block),
+            )
+        })
+        .filter(|(root, _)| {
+            canonical_block
+                .as_ref()
+                .map_or(true, |(canonical_root, _)| root != canonical_root)
+        })
+        .collect_vec();
 
-    let (root, with_status) = block_result.ok_or(Error::BlockNotFound)?;
+    let mut optimistic = false;
+    let mut finalized = true;
+    let mut headers = vec![];
 
-    let WithStatus {
-        value: block,
-        optimistic,
-        finalized,
-    } = with_status;
+    for (root, with_status) in canonical_block.into_iter().chain(other_blocks) {
+        let block_slot = with_status.value.message().slot();
 
-    let response = BlockHeadersResponse {
-        root,
-        canonical: true,
-        header: block.to_header(),
-    };
+        let canonical = controller.block_root_by_slot(block_slot)? == Some(root);
+
+        optimistic |= with_status.optimistic;
+        finalized &= with_status.finalized;
+
+        headers.push(BlockHeadersResponse {
+            root,
+            canonical,
+            header: with_status.value.to_header(),
+        });
+    }
 
-    Ok(EthResponse::json([response])
+    if headers.is_empty() {
+        return Err(Error::BlockNotFound);
+    }
+
+    Ok(EthResponse::json(headers)
         .execution_optimistic(optimistic)
         .finalized(finalized))
 }
@@ -904,16 +1174,19 @@ pub async fn block_id_headers<P: Preset, W: Wait>(
         .block_by_root(root)?
         .ok_or(Error::BlockNotFound)?;
 
+    // The block may be non-canonical if `block_id` is `BlockId::Root(_)`.
+    let canonical = controller.block_root_by_slot(block.message().slot())? == Some(root);
+
     let response = BlockHeadersResponse {
         root,
-        // TODO(Grandine Team): The block may be non-canonical if `block_id` is `BlockId::Root(_)`.
-        canonical: true,
+        canonical,
         header: block.to_header(),
     };
 
     Ok(EthResponse::json(response)
         .execution_optimistic(optimistic)
-        .finalized(finalized))
+        .finalized(finalized)
+        .etag(root, block_id.is_fixed()))
 }
 
 /// `GET /eth/v2/beacon/blocks/{block_id}`
@@ -930,10 +1203,12 @@ pub async fn block<P: Preset, W: Wait>(
     } = block_id::block(block_id, &controller, &genesis_provider)?;
 
     let version = block.phase();
+    let block_root = block.message().hash_tree_root();
 
     Ok(EthResponse::json_or_ssz(block, &headers)
         .execution_optimistic(optimistic)
         .finalized(finalized)
+        .etag(block_root, block_id.is_fixed())
         .version(version))
 }
 
@@ -951,7 +1226,8 @@ pub async fn block_root<P: Preset, W: Wait>(
 
     Ok(EthResponse::json(RootResponse { root })
         .execution_optimistic(optimistic)
-        .finalized(finalized))
+        .finalized(finalized)
+        .etag(root, block_id.is_fixed()))
 }
 
 /// `GET /eth/v1/beacon/blocks/{block_id}/attestations`
@@ -988,7 +1264,12 @@ pub async fn blob_sidecars<P: Preset, W: Wait>(
     EthResponse<ContiguousList<Arc<BlobSidecar<P>>, P::MaxBlobsPerBlock>, (), JsonOrSsz>,
     Error,
 > {
-    let block_root = block_id::block_root(block_id, &controller, &genesis_provider)?.value;
+    let WithStatus {
+        value: block_root,
+        optimistic,
+        finalized,
+    } = block_id::block_root(block_id, &controller, &genesis_provider)?;
+
     let blob_identifiers = query
         .indices
         .unwrap_or_else(|| (0..P::MaxBlobsPerBlock::U64).collect())
@@ -1007,25 +1288,42 @@ pub async fn blob_sidecars<P: Preset, W: Wait>(
     let blob_sidecars =
         ContiguousList::try_from_iter(blob_sidecars.into_iter()).map_err(AnyhowError::new)?;
 
-    Ok(EthResponse::json_or_ssz(blob_sidecars, &headers))
+    Ok(EthResponse::json_or_ssz(blob_sidecars, &headers)
+        .execution_optimistic(optimistic)
+        .finalized(finalized))
 }
 
 /// `POST /eth/v1/beacon/blocks`
 pub async fn publish_block<P: Preset, W: Wait>(
     State(controller): State<ApiController<P, W>>,
+    State(validator_config): State<Arc<ValidatorConfig>>,
     State(api_to_p2p_tx): State<UnboundedSender<ApiToP2p<P>>>,
     EthJsonOrSsz(signed_api_block): EthJsonOrSsz<Box<SignedAPIBlock<P>>>,
 ) -> Result<StatusCode, Error> {
-    let (signed_beacon_block, proofs, blobs) = signed_api_block.split();
-
-    let blob_sidecars =
-        misc::construct_blob_sidecars(&signed_beacon_block, blobs.into_iter(), proofs.into_iter())?;
+    publish_api_block(
+        signed_api_block,
+        controller,
+        &validator_config,
+        api_to_p2p_tx,
+        BroadcastValidation::Gossip,
+    )
+    .await
+}
 
-    publish_signed_block(
-        Arc::new(signed_beacon_block),
-        blob_sidecars,
+/// `POST /eth/v2/beacon/blocks`
+pub async fn publish_block_v2<P: Preset, W: Wait>(
+    State(controller): State<ApiController<P, W>>,
+    State(validator_config): State<Arc<ValidatorConfig>>,
+    State(api_to_p2p_tx): State<UnboundedSender<ApiToP2p<P>>>,
+    EthQuery(query): EthQuery<PublishBlockQuery>,
+    EthJsonOrSsz(signed_api_block): EthJsonOrSsz<Box<SignedAPIBlock<P>>>,
+) -> Result<StatusCode, Error> {
+    publish_api_block(
+        signed_api_block,
         controller,
+        &validator_config,
         api_to_p2p_tx,
+        query.broadcast_validation,
     )
     .await
 }
@@ -1033,40 +1331,38 @@ pub async fn publish_block<P: Preset, W: Wait>(
 /// `POST /eth/v1/beacon/blinded_blocks`
 pub async fn publish_blinded_block<P: Preset, W: Wait>(
     State(controller): State<ApiController<P, W>>,
+    State(validator_config): State<Arc<ValidatorConfig>>,
     State(api_to_p2p_tx): State<UnboundedSender<ApiToP2p<P>>>,
     State(api_to_validator_tx): State<UnboundedSender<ApiToValidator<P>>>,
     EthJsonOrSsz(signed_blinded_block): EthJsonOrSsz<Box<SignedBlindedBeaconBlock<P>>>,
 ) -> Result<StatusCode, Error> {
-    let (message, signature) = signed_blinded_block.as_ref().clone().split();
-    let (sender, receiver) = futures::channel::oneshot::channel();
-
-    ApiToValidator::PublishSignedBlindedBlock(sender, signed_blinded_block)
-        .send(&api_to_validator_tx);
-
-    let WithBlobsAndMev {
-        value: execution_payload,
-        proofs,
-        blobs,
-        ..
-    } = receiver.await?.ok_or(Error::ExecutionPayloadNotAvailable)?;
-
-    let signed_beacon_block = message
-        .with_execution_payload(execution_payload)
-        .map_err(AnyhowError::new)?
-        .with_signature(signature)
-        .pipe(Arc::new);
-
-    let blob_sidecars = misc::construct_blob_sidecars(
-        &signed_beacon_block,
-        blobs.unwrap_or_default().into_iter(),
-        proofs.unwrap_or_default().into_iter(),
-    )?;
+    publish_unblinded_block(
+        signed_blinded_block,
+        controller,
+        &validator_config,
+        api_to_p2p_tx,
+        api_to_validator_tx,
+        BroadcastValidation::Gossip,
+    )
+    .await
+}
 
-    publish_signed_block(
-        signed_beacon_block,
-        blob_sidecars,
+/// `POST /eth/v2/beacon/blinded_blocks`
+pub async fn publish_blinded_block_v2<P: Preset, W: Wait>(
+    State(controller): State<ApiController<P, W>>,
+    State(validator_config): State<Arc<ValidatorConfig>>,
+    State(api_to_p2p_tx): State<UnboundedSender<ApiToP2p<P>>>,
+    State(api_to_validator_tx): State<UnboundedSender<ApiToValidator<P>>>,
+    EthQuery(query): EthQuery<PublishBlockQuery>,
+    EthJsonOrSsz(signed_blinded_block): EthJsonOrSsz<Box<SignedBlindedBeaconBlock<P>>>,
+) -> Result<StatusCode, Error> {
+    publish_unblinded_block(
+        signed_blinded_block,
         controller,
+        &validator_config,
         api_to_p2p_tx,
+        api_to_validator_tx,
+        query.broadcast_validation,
     )
     .await
 }
@@ -1130,7 +1426,7 @@ pub async fn sync_committee_rewards<P: Preset, W: Wait>(
         validator_ids
             .into_iter()
             .filter_map(|validator_id| {
-                let validator_index = validator_id.validator_index(&state)?;
+                let validator_index = validator_id.validator_index(&controller, &state)?;
                 let delta = *sync_committee_deltas.get(&validator_index)?;
                 Some((validator_index, delta))
             })
@@ -1163,11 +1459,11 @@ pub async fn pool_attestations<P: Preset, W: Wait>(
 
     let aggregates = attestation_agg_pool
         .aggregate_attestations_by_epoch(epoch)
-        .await;
+        .await?;
 
     let singular_attestations = attestation_agg_pool
         .singular_attestations_by_epoch(epoch)
-        .await;
+        .await?;
 
     let attestations = aggregates
         .iter()
@@ -1491,17 +1787,21 @@ pub async fn beacon_state<P: Preset, W: Wait>(
     EthPath(state_id): EthPath<StateId>,
     headers: HeaderMap,
 ) -> Result<EthResponse<Arc<BeaconState<P>>, (), JsonOrSsz>, Error> {
+    let immutable = state_id.is_fixed();
+
     let WithStatus {
         value: state,
         optimistic,
         finalized,
-    } = state_id.state(&controller, genesis_provider)?;
+    } = state_id.state(&controller, genesis_provider).await?;
 
     let version = state.phase();
+    let state_root = state.hash_tree_root();
 
     Ok(EthResponse::json_or_ssz(state, &headers)
         .execution_optimistic(optimistic)
         .finalized(finalized)
+        .etag(state_root, immutable)
         .version(version))
 }
 
@@ -1513,9 +1813,13 @@ pub async fn beacon_heads<P: Preset, W: Wait>(
 }
 
 /// `GET /eth/v1/events`
+///
+/// Clients that reconnect with a `Last-Event-ID` header first receive the events of the requested
+/// topics they missed, as long as those are still in the replay buffer.
 pub async fn beacon_events(
     State(event_channels): State<Arc<EventChannels>>,
     EthQuery(events): EthQuery<EventsQuery>,
+    headers: HeaderMap,
 ) -> Result<Sse<impl Stream<Item = Result<Event, BroadcastStreamRecvError>>>, Error> {
     let EventsQuery { topics } = events;
 
@@ -1523,11 +1827,22 @@ pub async fn beacon_events(
         return Err(Error::EventTopicsEmpty);
     }
 
-    topics
+    // IDs we did not assign cannot be used to find missed events, so they are ignored.
+    let last_event_id = headers
+        .get(LAST_EVENT_ID)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse().ok());
+
+    let (missed_events, receivers) = event_channels.subscribe(&topics, last_event_id);
+
+    let live_events = receivers
         .into_iter()
-        .map(|topic| event_channels.receiver_for(topic))
         .map(BroadcastStream::new)
-        .pipe(futures::stream::select_all)
+        .pipe(futures::stream::select_all);
+
+    futures::stream::iter(missed_events)
+        .map(Ok)
+        .chain(live_events)
         .pipe(Sse::new)
         .keep_alive(KeepAlive::default())
         .pipe(Ok)
@@ -1600,6 +1915,7 @@ pub async fn node_version(State(network_config): State<Arc<NetworkConfig>>) -> R
 /// `GET /eth/v1/node/syncing`
 pub async fn node_syncing_status<P: Preset, W: Wait>(
     State(controller): State<ApiController<P, W>>,
+    State(eth1_api): State<Arc<Eth1Api>>,
     State(is_synced): State<Arc<SyncedStatus>>,
     State(is_back_synced): State<Arc<BackSyncedStatus>>,
 ) -> EthResponse<NodeSyncingResponse> {
@@ -1615,15 +1931,21 @@ pub async fn node_syncing_status<P: Preset, W: Wait>(
             .unwrap_or_else(|| controller.slot() - head_slot),
         is_syncing: !(is_synced && is_back_synced),
         is_optimistic: snapshot.is_optimistic(),
+        el_offline: eth1_api.el_offline(),
     })
 }
 
 /// `GET /eth/v1/node/health`
-pub async fn node_health(
+pub async fn node_health<P: Preset, W: Wait>(
+    State(controller): State<ApiController<P, W>>,
     State(is_synced): State<Arc<SyncedStatus>>,
     State(is_back_synced): State<Arc<BackSyncedStatus>>,
 ) -> StatusCode {
-    if is_synced.get() && is_back_synced.get() {
+    // The specification uses this status for nodes with issues that prevent them from working.
+    // A node that is about to run out of disk space should not be relied on by load balancers.
+    if controller.storage_health().is_degraded() {
+        StatusCode::SERVICE_UNAVAILABLE
+    } else if is_synced.get() && is_back_synced.get() {
         StatusCode::OK
     } else {
         StatusCode::PARTIAL_CONTENT
@@ -1633,103 +1955,92 @@ pub async fn node_health(
 /// `POST /eth/v1/validator/duties/attester/{epoch}`
 pub async fn validator_attester_duties<P: Preset, W: Wait>(
     State(controller): State<ApiController<P, W>>,
+    State(duties_cache): State<Arc<DutiesCache>>,
     EthPath(epoch): EthPath<Epoch>,
     EthJson(validator_indices): EthJson<Vec<ValidatorIndex>>,
 ) -> Result<EthResponse<Vec<ValidatorAttesterDutyResponse>>, Error> {
-    let head_state = controller.head_state();
-
-    let (state, relative_epoch) = match accessors::relative_epoch(&head_state.value, epoch) {
-        Ok(relative_epoch) => (head_state, relative_epoch),
-        Err(_) => (
-            controller.preprocessed_state_at_epoch(epoch)?,
-            RelativeEpoch::Current,
-        ),
-    };
-
     let WithStatus {
-        value: state,
+        value: attester_duties,
         optimistic,
         // `duties` responses are not supposed to contain a `finalized` field.
         finalized: _,
-    } = state;
+    } = duties_cache.attester_duties(&controller, epoch)?;
 
-    // Unlike `GET /eth/v1/validator/duties/proposer/{epoch}`,
-    // this endpoint is supposed to return the dependent root for the previous epoch.
-    let previous_epoch = epoch.saturating_sub(1).max(GENESIS_EPOCH);
-    let dependent_root = controller.dependent_root(&state, previous_epoch)?;
+    // Validators in committees are active, so their public keys are present in the head state.
+    let head_state = controller.head_state().value;
 
-    let indices = validator_indices
+    let response = validator_indices
         .into_iter()
-        .collect::<HashSet<ValidatorIndex>>();
-
-    let committees_at_slot = accessors::get_committee_count_per_slot(&state, relative_epoch);
-
-    let response = misc::slots_in_epoch::<P>(epoch)
-        .map(|slot| {
-            accessors::beacon_committees(&state, slot)?
-                .zip(0..)
-                .flat_map(|(committee, committee_index)| {
-                    let state = &state;
-
-                    committee
-                        .into_iter()
-                        .enumerate()
-                        .filter(|(_, validator_index)| indices.contains(validator_index))
-                        .map(move |(validator_committee_index, validator_index)| {
-                            let pubkey = accessors::public_key(state, validator_index)?.to_bytes();
-
-                            Ok(ValidatorAttesterDutyResponse {
-                                committee_index,
-                                committee_length: committee.len(),
-                                committees_at_slot,
-                                pubkey,
-                                slot,
-                                validator_committee_index,
-                                validator_index,
-                            })
-                        })
-                })
-                .collect::<Result<Vec<_>>>()
+        .collect::<BTreeSet<ValidatorIndex>>()
+        .into_iter()
+        .filter_map(|validator_index| {
+            let duty = attester_duties.duties.get(&validator_index).copied()?;
+            Some((validator_index, duty))
+        })
+        .map(|(validator_index, duty)| {
+            let AttesterDuty {
+                committee_index,
+                committee_length,
+                committees_at_slot,
+                slot,
+                validator_committee_index,
+            } = duty;
+
+            let pubkey = accessors::public_key(&head_state, validator_index)?.to_bytes();
+
+            Ok(ValidatorAttesterDutyResponse {
+                committee_index,
+                committee_length,
+                committees_at_slot,
+                pubkey,
+                slot,
+                validator_committee_index,
+                validator_index,
+            })
         })
-        .flatten_ok()
-        .try_collect()
-        .map_err(Error::Internal)?;
+        .collect::<Result<Vec<_>>>()?;
 
+    // Unlike `GET /eth/v1/validator/duties/proposer/{epoch}`,
+    // this endpoint is supposed to return the dependent root for the previous epoch.
+    // `DutiesCache` takes care of that.
     Ok(EthResponse::json(response)
-        .dependent_root(dependent_root)
+        .dependent_root(attester_duties.dependent_root)
         .execution_optimistic(optimistic))
 }
 
 /// `GET /eth/v1/validator/duties/proposer/{epoch}`
 pub async fn validator_proposer_duties<P: Preset, W: Wait>(
     State(controller): State<ApiController<P, W>>,
+    State(duties_cache): State<Arc<DutiesCache>>,
     EthPath(epoch): EthPath<Epoch>,
 ) -> Result<EthResponse<Vec<ValidatorProposerDutyResponse>>, Error> {
     let WithStatus {
-        value: state,
+        value: proposer_duties,
         optimistic,
         // `duties` responses are not supposed to contain a `finalized` field.
         finalized: _,
-    } = controller.preprocessed_state_at_epoch(epoch)?;
-
-    let dependent_root = controller.dependent_root(&state, epoch)?;
+    } = duties_cache.proposer_duties(&controller, epoch)?;
 
-    let response = misc::slots_in_epoch::<P>(epoch)
-        .map(|slot| {
-            let validator_index = accessors::get_beacon_proposer_index_at_slot(&state, slot)?;
-            let pubkey = accessors::public_key(&state, validator_index)?.to_bytes();
+    let response = proposer_duties
+        .duties
+        .iter()
+        .map(|duty| {
+            let ProposerDuty {
+                pubkey,
+                validator_index,
+                slot,
+            } = *duty;
 
-            Ok(ValidatorProposerDutyResponse {
+            ValidatorProposerDutyResponse {
                 pubkey,
                 validator_index,
                 slot,
-            })
+            }
         })
-        .try_collect()
-        .map_err(Error::Internal)?;
+        .collect();
 
     Ok(EthResponse::json(response)
-        .dependent_root(dependent_root)
+        .dependent_root(proposer_duties.dependent_root)
         .execution_optimistic(optimistic))
 }
 
@@ -1743,6 +2054,7 @@ pub async fn validator_proposer_duties<P: Preset, W: Wait>(
 pub async fn validator_sync_committee_duties<P: Preset, W: Wait>(
     State(controller): State<ApiController<P, W>>,
     State(genesis_provider): State<GenesisProvider<P>>,
+    State(duties_cache): State<Arc<DutiesCache>>,
     EthPath(epoch): EthPath<Epoch>,
     EthJson(validator_indices): EthJson<Vec<ValidatorIndex>>,
 ) -> Result<EthResponse<Vec<ValidatorSyncDutyResponse>>, Error> {
@@ -1753,44 +2065,35 @@ pub async fn validator_sync_committee_duties<P: Preset, W: Wait>(
         optimistic,
         // `duties` responses are not supposed to contain a `finalized` field.
         finalized: _,
-    } = StateId::Slot(start_slot).state(&controller, genesis_provider)?;
+    } = StateId::Slot(start_slot)
+        .state(&controller, genesis_provider)
+        .await?;
 
     let Some(state) = state.post_altair() else {
         return Ok(EthResponse::json(vec![]).execution_optimistic(optimistic));
     };
 
     let requested_period = misc::sync_committee_period::<P>(epoch);
-    let state_epoch = misc::compute_epoch_at_slot::<P>(state.slot());
-    let state_period = misc::sync_committee_period::<P>(state_epoch);
 
-    let committee = if requested_period == state_period {
-        state.current_sync_committee()
-    } else if requested_period == state_period + 1 {
-        state.next_sync_committee()
-    } else {
-        return Err(Error::EpochNotInSyncCommitteePeriod);
-    };
+    let sync_duties = duties_cache
+        .sync_duties(&controller, state, requested_period)
+        .ok_or(Error::EpochNotInSyncCommitteePeriod)?;
 
     let duties = validator_indices
         .into_iter()
         .map(|validator_index| {
-            let validator_pubkey = accessors::public_key(state, validator_index)?;
-
-            let validator_sync_committee_indices = committee
-                .pubkeys
-                .iter()
-                .enumerate()
-                .filter_map(|(index, pubkey)| (pubkey == validator_pubkey).then_some(index))
-                .collect_vec();
+            let pubkey = accessors::public_key(state, validator_index)?.to_bytes();
 
-            if validator_sync_committee_indices.is_empty() {
+            let Some(validator_sync_committee_indices) =
+                sync_duties.sync_committee_indices(validator_index)
+            else {
                 return Ok(None);
-            }
+            };
 
             Ok(Some(ValidatorSyncDutyResponse {
-                pubkey: validator_pubkey.to_bytes(),
+                pubkey,
                 validator_index,
-                validator_sync_committee_indices,
+                validator_sync_committee_indices: validator_sync_committee_indices.to_vec(),
             }))
         })
         .filter_map(Result::transpose)
@@ -1819,8 +2122,37 @@ pub async fn validator_aggregate_attestation<P: Preset, W: Wait>(
     Ok(EthResponse::json(attestation))
 }
 
+/// `GET /eth/v2/validator/aggregate_attestation`
+pub async fn validator_aggregate_attestation_v2<P: Preset, W: Wait>(
+    State(chain_config): State<Arc<ChainConfig>>,
+    State(attestation_agg_pool): State<Arc<AttestationAggPool<P, W>>>,
+    EthQuery(query): EthQuery<AggregateAttestationV2Query>,
+) -> Result<EthResponse<Attestation<P>>, Error> {
+    let AggregateAttestationV2Query {
+        attestation_data_root,
+        slot,
+        committee_index,
+    } = query;
+
+    let epoch = misc::compute_epoch_at_slot::<P>(slot);
+
+    // Before Electra the committee index is part of the attestation data,
+    // so the data root alone identifies the committee.
+    // From Electra onward it will have to be checked against the committee bits instead.
+    let attestation = attestation_agg_pool
+        .best_aggregate_attestation_by_data_root(attestation_data_root, epoch)
+        .await
+        .filter(|attestation| attestation.data.index == committee_index)
+        .ok_or(Error::AttestationNotFound)?;
+
+    let version = chain_config.phase_at_slot::<P>(slot);
+
+    Ok(EthResponse::json(attestation).version(version))
+}
+
 /// `GET /eth/v1/validator/blinded_blocks/{slot}`
-pub async fn validator_blinded_block<P: Preset>(
+pub async fn validator_blinded_block<P: Preset, W: Wait>(
+    State(controller): State<ApiController<P, W>>,
     State(api_to_validator_tx): State<UnboundedSender<ApiToValidator<P>>>,
     EthPath(slot): EthPath<Slot>,
     EthQuery(query): EthQuery<ValidatorBlockQuery>,
@@ -1835,6 +2167,11 @@ pub async fn validator_blinded_block<P: Preset>(
         return Err(Error::InvalidRandaoReveal);
     }
 
+    // Blocks built on top of an optimistic head may extend an invalid chain.
+    if controller.is_optimistic() {
+        return Err(Error::HeadIsOptimistic);
+    }
+
     let graffiti = graffiti.unwrap_or_default();
     let (sender, receiver) = futures::channel::oneshot::channel();
 
@@ -1863,7 +2200,8 @@ pub async fn validator_blinded_block<P: Preset>(
 }
 
 /// `GET /eth/v2/validator/blocks/{slot}`
-pub async fn validator_block<P: Preset>(
+pub async fn validator_block<P: Preset, W: Wait>(
+    State(controller): State<ApiController<P, W>>,
     State(api_to_validator_tx): State<UnboundedSender<ApiToValidator<P>>>,
     EthPath(slot): EthPath<Slot>,
     EthQuery(query): EthQuery<ValidatorBlockQuery>,
@@ -1879,6 +2217,10 @@ pub async fn validator_block<P: Preset>(
         return Err(Error::InvalidRandaoReveal);
     }
 
+    if controller.is_optimistic() {
+        return Err(Error::HeadIsOptimistic);
+    }
+
     let graffiti = graffiti.unwrap_or_default();
     let (sender, receiver) = futures::channel::oneshot::channel();
 
@@ -1916,6 +2258,10 @@ pub async fn validator_block_v3<P: Preset, W: Wait>(
         return Err(Error::InvalidRandaoReveal);
     }
 
+    if controller.is_optimistic() {
+        return Err(Error::HeadIsOptimistic);
+    }
+
     let graffiti = graffiti.unwrap_or_default();
     let (sender, receiver) = futures::channel::oneshot::channel();
 
@@ -2053,36 +2399,48 @@ pub async fn validator_subscribe_to_beacon_committee<P: Preset, W: Wait>(
     let state = controller.preprocessed_state_at_current_slot()?;
     let (sender, receiver) = futures::channel::oneshot::channel();
 
-    subscriptions.iter().try_for_each(|subscription| {
-        let BeaconCommitteeSubscription {
-            committees_at_slot,
-            slot,
-            ..
-        } = *subscription;
+    let failures = subscriptions
+        .iter()
+        .enumerate()
+        .filter_map(|(index, subscription)| {
+            let run = || {
+                let BeaconCommitteeSubscription {
+                    committees_at_slot,
+                    slot,
+                    ..
+                } = *subscription;
+
+                let epoch = misc::compute_epoch_at_slot::<P>(slot);
+                let relative_epoch = accessors::relative_epoch(&state, epoch)?;
+                let computed = accessors::get_committee_count_per_slot(&state, relative_epoch);
+                let requested = committees_at_slot;
+
+                ensure!(
+                    requested == computed,
+                    Error::CommitteesAtSlotMismatch {
+                        requested,
+                        computed,
+                    },
+                );
 
-        let epoch = misc::compute_epoch_at_slot::<P>(slot);
-        let relative_epoch = accessors::relative_epoch(&state, epoch)?;
-        let computed = accessors::get_committee_count_per_slot(&state, relative_epoch);
-        let requested = committees_at_slot;
+                // TODO(Grandine Team): Some API clients do not set `validator_index`.
+                //                      See <https://github.com/attestantio/vouch/issues/75>.
+                // let committee = accessors::beacon_committee(&state, slot, committee_index)?;
+                // ensure!(
+                //     committee.into_iter().contains(&validator_index),
+                //     Error::ValidatorNotInCommittee { validator_index },
+                // );
 
-        ensure!(
-            requested == computed,
-            Error::CommitteesAtSlotMismatch {
-                requested,
-                computed,
-            },
-        );
+                Ok(())
+            };
 
-        // TODO(Grandine Team): Some API clients do not set `validator_index`.
-        //                      See <https://github.com/attestantio/vouch/issues/75>.
-        // let committee = accessors::beacon_committee(&state, slot, committee_index)?;
-        // ensure!(
-        //     committee.into_iter().contains(&validator_index),
-        //     Error::ValidatorNotInCommittee { validator_index },
-        // );
+            run().err().map(|error| IndexedError { index, error })
+        })
+        .collect_vec();
 
-        Ok(())
-    })?;
+    if !failures.is_empty() {
+        return Err(Error::InvalidBeaconCommitteeSubscriptions(failures));
+    }
 
     ToSubnetService::UpdateBeaconCommitteeSubscriptions(controller.slot(), subscriptions, sender)
         .send(&subnet_service_tx);
@@ -2100,6 +2458,49 @@ pub async fn validator_subscribe_to_sync_committees<P: Preset, W: Wait>(
 ) -> Result<(), Error> {
     let current_epoch = misc::compute_epoch_at_slot::<P>(controller.slot());
 
+    let failures = subscriptions
+        .iter()
+        .enumerate()
+        .filter_map(|(index, subscription)| {
+            let run = || {
+                let SyncCommitteeSubscription {
+                    ref sync_committee_indices,
+                    until_epoch,
+                    ..
+                } = *subscription;
+
+                ensure!(
+                    current_epoch < until_epoch,
+                    Error::UntilEpochNotInFuture {
+                        until_epoch,
+                        current_epoch,
+                    },
+                );
+
+                if let Some(sync_committee_index) =
+                    sync_committee_indices
+                        .iter()
+                        .copied()
+                        .find(|sync_committee_index| {
+                            *sync_committee_index >= P::SyncCommitteeSize::USIZE
+                        })
+                {
+                    bail!(Error::SyncCommitteeIndexOutOfBounds {
+                        sync_committee_index,
+                    });
+                }
+
+                Ok(())
+            };
+
+            run().err().map(|error| IndexedError { index, error })
+        })
+        .collect_vec();
+
+    if !failures.is_empty() {
+        return Err(Error::InvalidSyncCommitteeSubscriptions(failures));
+    }
+
     ToSubnetService::UpdateSyncCommitteeSubscriptions(current_epoch, subscriptions)
         .send(&subnet_service_tx);
 
@@ -2108,6 +2509,7 @@ pub async fn validator_subscribe_to_sync_committees<P: Preset, W: Wait>(
 
 /// `GET /eth/v1/validator/sync_committee_contribution`
 pub async fn validator_sync_committee_contribution<P: Preset, W: Wait>(
+    State(controller): State<ApiController<P, W>>,
     State(sync_committee_agg_pool): State<Arc<SyncCommitteeAggPool<P, W>>>,
     EthQuery(query): EthQuery<SyncCommitteeContributionQuery>,
 ) -> Result<EthResponse<SyncCommitteeContribution<P>>, Error> {
@@ -2117,6 +2519,10 @@ pub async fn validator_sync_committee_contribution<P: Preset, W: Wait>(
         subcommittee_index,
     } = query;
 
+    if controller.is_optimistic() {
+        return Err(Error::HeadIsOptimistic);
+    }
+
     let data = sync_committee_agg_pool
         .best_subcommittee_contribution(slot, beacon_block_root, subcommittee_index)
         .await;
@@ -2138,45 +2544,45 @@ pub async fn validator_publish_aggregate_and_proofs<P: Preset, W: Wait>(
     State(api_to_p2p_tx): State<UnboundedSender<ApiToP2p<P>>>,
     EthJson(aggregate_and_proofs): EthJson<Vec<Box<SignedAggregateAndProof<P>>>>,
 ) -> Result<(), Error> {
-    let (successes, failures): (Vec<_>, Vec<_>) = aggregate_and_proofs
-        .into_iter()
-        .enumerate()
-        .map(|(index, aggregate_and_proof)| {
-            let (sender, receiver) = futures::channel::oneshot::channel();
-
-            controller.on_api_aggregate_and_proof(aggregate_and_proof.clone(), sender);
+    publish_aggregate_and_proofs(&controller, &api_to_p2p_tx, aggregate_and_proofs).await
+}
 
-            async move {
-                let run = async {
-                    let validation_outcome = receiver.await??;
-                    Ok((aggregate_and_proof, validation_outcome))
-                };
+/// `POST /eth/v2/validator/aggregate_and_proofs`
+///
+/// This deviates from [the specification] by returning errors as [`IndexedError`].
+/// Lighthouse does the same thing.
+///
+/// [the specification]: https://ethereum.github.io/beacon-APIs/
+#[allow(clippy::vec_box)]
+pub async fn validator_publish_aggregate_and_proofs_v2<P: Preset, W: Wait>(
+    State(controller): State<ApiController<P, W>>,
+    State(api_to_p2p_tx): State<UnboundedSender<ApiToP2p<P>>>,
+    EthConsensusVersion(version): EthConsensusVersion,
+    EthJson(aggregate_and_proofs): EthJson<Vec<Box<SignedAggregateAndProof<P>>>>,
+) -> Result<(), Error> {
+    let failures = aggregate_and_proofs
+        .iter()
+        .enumerate()
+        .filter_map(|(index, aggregate_and_proof)| {
+            let slot = aggregate_and_proof.message.aggregate.data.slot;
+            let computed = controller.chain_config().phase_at_slot::<P>(slot);
 
-                run.await.map_err(|error| IndexedError { index, error })
-            }
+            (version != computed).then(|| IndexedError {
+                index,
+                error: Error::ConsensusVersionMismatch {
+                    header: version,
+                    computed,
+                }
+                .into(),
+            })
         })
-        .collect::<FuturesOrdered<_>>()
-        .collect::<Vec<_>>()
-        .await
-        .into_iter()
-        .partition_result();
-
-    // Send messages after validating all aggregates to make their order deterministic.
-    // Doing it above wouldn't work because `FuturesOrdered` polls futures concurrently.
-    //
-    // Send messages before reporting failures to be consistent with `submit_pool_sync_committees`.
-    // By this point votes from accepted aggregates have already been included in fork choice.
-    for (aggregate_and_proof, validation_outcome) in successes {
-        if validation_outcome == ValidationOutcome::Accept {
-            ApiToP2p::PublishAggregateAndProof(aggregate_and_proof).send(&api_to_p2p_tx);
-        }
-    }
+        .collect_vec();
 
     if !failures.is_empty() {
         return Err(Error::InvalidAggregatesAndProofs(failures));
     }
 
-    Ok(())
+    publish_aggregate_and_proofs(&controller, &api_to_p2p_tx, aggregate_and_proofs).await
 }
 
 /// `POST /eth/v1/validator/contribution_and_proofs`
@@ -2274,13 +2680,131 @@ pub async fn validator_liveness<P: Preset, W: Wait>(
 }
 
 /// `POST /eth/v1/validator/beacon_committee_selections`
-pub async fn validator_beacon_committee_selections() -> Error {
-    Error::EndpointNotImplemented
+///
+/// Distributed validator middleware submits the partial selection proofs of all operators of a
+/// validator. Partial selection proofs for the same validator and slot are aggregated and the
+/// result is verified against the public key of the validator. A validator with a complete key
+/// submits a single selection proof, which is returned unchanged after verification.
+///
+/// This deviates from [the specification] by returning errors as [`IndexedError`].
+///
+/// [the specification]: https://ethereum.github.io/beacon-APIs/
+pub async fn validator_beacon_committee_selections<P: Preset, W: Wait>(
+    State(controller): State<ApiController<P, W>>,
+    EthJson(selections): EthJson<Vec<BeaconCommitteeSelection>>,
+) -> Result<EthResponse<Vec<BeaconCommitteeSelection>>, Error> {
+    let state = controller.preprocessed_state_at_current_slot()?;
+    let chain_config = controller.chain_config();
+
+    let selections = combine_selection_proofs(
+        selections,
+        |selection| (selection.validator_index, selection.slot),
+        |selection| &mut selection.selection_proof,
+        |selection| {
+            let public_key = &state.validators().get(selection.validator_index)?.pubkey;
+
+            selection
+                .slot
+                .verify(chain_config, &state, selection.selection_proof, public_key)
+        },
+    )?;
+
+    Ok(EthResponse::json(selections))
 }
 
 /// `POST /eth/v1/validator/sync_committee_selections`
-pub async fn validator_sync_committee_selections() -> Error {
-    Error::EndpointNotImplemented
+///
+/// See [`validator_beacon_committee_selections`].
+pub async fn validator_sync_committee_selections<P: Preset, W: Wait>(
+    State(controller): State<ApiController<P, W>>,
+    EthJson(selections): EthJson<Vec<SyncCommitteeSelection>>,
+) -> Result<EthResponse<Vec<SyncCommitteeSelection>>, Error> {
+    if controller.phase() < Phase::Altair {
+        return Err(Error::CurrentSlotHasNoSyncCommittee);
+    }
+
+    let state = controller.preprocessed_state_at_current_slot()?;
+    let chain_config = controller.chain_config();
+
+    let selections = combine_selection_proofs(
+        selections,
+        |selection| {
+            (
+                selection.validator_index,
+                selection.slot,
+                selection.subcommittee_index,
+            )
+        },
+        |selection| &mut selection.selection_proof,
+        |selection| {
+            let public_key = &state.validators().get(selection.validator_index)?.pubkey;
+
+            SyncAggregatorSelectionData {
+                slot: selection.slot,
+                subcommittee_index: selection.subcommittee_index,
+            }
+            .verify(chain_config, &state, selection.selection_proof, public_key)
+        },
+    )?;
+
+    Ok(EthResponse::json(selections))
+}
+
+// Selections are returned in the order of the first partial selection proof for each duty.
+// Failures are reported at the index of that first partial selection proof.
+fn combine_selection_proofs<S, K: Eq + Hash>(
+    selections: Vec<S>,
+    duty: impl Fn(&S) -> K,
+    selection_proof: impl Fn(&mut S) -> &mut SignatureBytes,
+    verify: impl Fn(&S) -> Result<()>,
+) -> Result<Vec<S>, Error> {
+    let mut positions = HashMap::<_, usize>::new();
+    let mut combined = Vec::<(usize, S, Signature)>::new();
+    let mut failures = vec![];
+
+    for (index, mut selection) in selections.into_iter().enumerate() {
+        let signature = match Signature::try_from(*selection_proof(&mut selection)) {
+            Ok(signature) => signature,
+            Err(error) => {
+                failures.push(IndexedError {
+                    index,
+                    error: error.into(),
+                });
+
+                continue;
+            }
+        };
+
+        match positions.entry(duty(&selection)) {
+            Entry::Occupied(occupied) => combined[*occupied.get()].2.aggregate_in_place(signature),
+            Entry::Vacant(vacant) => {
+                vacant.insert(combined.len());
+                combined.push((index, selection, signature));
+            }
+        }
+    }
+
+    let selections = combined
+        .into_iter()
+        .filter_map(|(index, mut selection, aggregate)| {
+            *selection_proof(&mut selection) = aggregate.into();
+
+            match verify(&selection) {
+                Ok(()) => Some(selection),
+                Err(error) => {
+                    failures.push(IndexedError { index, error });
+                    None
+                }
+            }
+        })
+        .collect_vec();
+
+    if !failures.is_empty() {
+        failures.sort_by_key(|failure| failure.index);
+        return Err(Error::InvalidSelectionProofs(failures));
+    }
+
+    Ok(selections)
 }
 
 /// `GET /eth/v1/validator/{pubkey}/feerecipient`
@@ -2413,7 +2937,10 @@ pub async fn keymanager_delete_graffiti(
 pub async fn keymanager_list_validating_pubkeys(
     State(keymanager): State<Arc<KeyManager>>,
 ) -> Result<EthResponse<Vec<ValidatingPubkey>>, Error> {
-    let pubkeys = keymanager.keystores().list_validating_pubkeys().await;
+    let pubkeys = match keymanager.web3signer_proxy() {
+        Some(proxy) => proxy.list().await,
+        None => keymanager.keystores().list_validating_pubkeys().await,
+    };
 
     Ok(EthResponse::json(pubkeys))
 }
@@ -2429,10 +2956,19 @@ pub async fn keymanager_import_keystores(
         slashing_protection,
     } = query;
 
-    let import_statuses = keymanager
-        .keystores()
-        .import(keystores, passwords, slashing_protection)
-        .await?;
+    let import_statuses = match keymanager.web3signer_proxy() {
+        Some(proxy) => {
+            proxy
+                .import(&keystores, &passwords, slashing_protection.as_deref())
+                .await?
+        }
+        None => {
+            keymanager
+                .keystores()
+                .import(keystores, passwords, slashing_protection)
+                .await?
+        }
+    };
 
     Ok(EthResponse::json(import_statuses))
 }
@@ -2444,7 +2980,10 @@ pub async fn keymanager_delete_keystores(
 ) -> Result<EthResponse<Vec<KeymanagerOperationStatus>>, Error> {
     let KeystoreDeleteQuery { pubkeys } = query;
 
-    let (delete_statuses, slashing_protection) = keymanager.keystores().delete(pubkeys).await?;
+    let (delete_statuses, slashing_protection) = match keymanager.web3signer_proxy() {
+        Some(proxy) => proxy.delete(&pubkeys).await?,
+        None => keymanager.keystores().delete(pubkeys).await?,
+    };
 
     Ok(EthResponse::json(delete_statuses).slashing_protection(slashing_protection))
 }
@@ -2529,45 +3068,338 @@ pub fn calculate_block_rewards<P: Preset, W: Wait>(
     })
 }
 
+// Responses are built lazily from the state because they may contain every validator.
+// The state is kept alive by the iterator until the response is fully sent.
+fn matching_validators<P: Preset, W: Wait>(
+    controller: &ApiController<P, W>,
+    state: Arc<BeaconState<P>>,
+    validator_ids: &[ValidatorId],
+    statuses: Vec<ValidatorStatus>,
+) -> impl Iterator<Item = StateValidatorResponse> + Send {
+    selected_validator_indices(controller, &state, validator_ids).filter_map(move |index| {
+        let validator = state
+            .validators()
+            .get(index)
+            .expect("selected_validator_indices only returns indices of existing validators");
+
+        let status = ValidatorStatus::new(validator, &state);
+
+        let allowed_by_status =
+            statuses.is_empty() || statuses.iter().any(|allowed| allowed.matches(status));
+
+        if !allowed_by_status {
+            return None;
+        }
+
+        let balance = state
+            .balances()
+            .get(index)
+            .copied()
+            .expect("list of validators and list of balances should have the same length");
+
+        Some(StateValidatorResponse {
+            balance,
+            index,
+            status,
+            validator: validator.clone(),
+        })
+    })
+}
+
+// Selects all validators if no IDs are specified.
+// Unknown validators are skipped. Indices are returned in ascending order without duplicates.
+fn selected_validator_indices<P: Preset, W: Wait>(
+    controller: &ApiController<P, W>,
+    state: &BeaconState<P>,
+    validator_ids: &[ValidatorId],
+) -> impl Iterator<Item = ValidatorIndex> + Send {
+    let validator_count = state.validators().len_u64();
+
+    if validator_ids.is_empty() {
+        return Either::Left(0..validator_count);
+    }
+
+    let indices = validator_ids
+        .iter()
+        .filter_map(|validator_id| validator_id.validator_index(controller, state))
+        .filter(|index| *index < validator_count)
+        .sorted_unstable()
+        .dedup();
+
+    Either::Right(indices.collect_vec().into_iter())
+}
+
+#[allow(clippy::vec_box)]
+async fn publish_aggregate_and_proofs<P: Preset, W: Wait>(
+    controller: &ApiController<P, W>,
+    api_to_p2p_tx: &UnboundedSender<ApiToP2p<P>>,
+    aggregate_and_proofs: Vec<Box<SignedAggregateAndProof<P>>>,
+) -> Result<(), Error> {
+    let (successes, failures): (Vec<_>, Vec<_>) = aggregate_and_proofs
+        .into_iter()
+        .enumerate()
+        .map(|(index, aggregate_and_proof)| {
+            let (sender, receiver) = futures::channel::oneshot::channel();
+
+            controller.on_api_aggregate_and_proof(aggregate_and_proof.clone(), sender);
+
+            async move {
+                let run = async {
+                    let validation_outcome = receiver.await??;
+                    Ok((aggregate_and_proof, validation_outcome))
+                };
+
+                run.await.map_err(|error| IndexedError { index, error })
+            }
+        })
+        .collect::<FuturesOrdered<_>>()
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .partition_result();
+
+    // Send messages after validating all aggregates to make their order deterministic.
+    // Doing it above wouldn't work because `FuturesOrdered` polls futures concurrently.
+    //
+    // Send messages before reporting failures to be consistent with `submit_pool_sync_committees`.
+    // By this point votes from accepted aggregates have already been included in fork choice.
+    for (aggregate_and_proof, validation_outcome) in successes {
+        if validation_outcome == ValidationOutcome::Accept {
+            ApiToP2p::PublishAggregateAndProof(aggregate_and_proof).send(api_to_p2p_tx);
+        }
+    }
+
+    if !failures.is_empty() {
+        return Err(Error::InvalidAggregatesAndProofs(failures));
+    }
+
+    Ok(())
+}
+
+async fn publish_api_block<P: Preset, W: Wait>(
+    signed_api_block: Box<SignedAPIBlock<P>>,
+    controller: ApiController<P, W>,
+    validator_config: &ValidatorConfig,
+    api_to_p2p_tx: UnboundedSender<ApiToP2p<P>>,
+    broadcast_validation: BroadcastValidation,
+) -> Result<StatusCode, Error> {
+    let (signed_beacon_block, proofs, blobs) = signed_api_block.split();
+
+    let blob_sidecars =
+        misc::construct_blob_sidecars(&signed_beacon_block, blobs.into_iter(), proofs.into_iter())?;
+
+    publish_signed_block(
+        Arc::new(signed_beacon_block),
+        blob_sidecars,
+        controller,
+        validator_config,
+        api_to_p2p_tx,
+        broadcast_validation,
+    )
+    .await
+}
+
+async fn publish_unblinded_block<P: Preset, W: Wait>(
+    signed_blinded_block: Box<SignedBlindedBeaconBlock<P>>,
+    controller: ApiController<P, W>,
+    validator_config: &ValidatorConfig,
+    api_to_p2p_tx: UnboundedSender<ApiToP2p<P>>,
+    api_to_validator_tx: UnboundedSender<ApiToValidator<P>>,
+    broadcast_validation: BroadcastValidation,
+) -> Result<StatusCode, Error> {
+    // Check before the block is sent to the builder because builders publish blocks themselves.
+    if check_equivocation(validator_config, broadcast_validation) {
+        let message = signed_blinded_block.message();
+
+        reject_equivocating_block(
+            &controller,
+            message.slot(),
+            message.proposer_index(),
+            message.hash_tree_root(),
+        )?;
+    }
+
+    let (message, signature) = signed_blinded_block.as_ref().clone().split();
+    let (sender, receiver) = futures::channel::oneshot::channel();
+
+    ApiToValidator::PublishSignedBlindedBlock(sender, signed_blinded_block)
+        .send(&api_to_validator_tx);
+
+    let WithBlobsAndMev {
+        value: execution_payload,
+        proofs,
+        blobs,
+        ..
+    } = receiver.await?.ok_or(Error::ExecutionPayloadNotAvailable)?;
+
+    let signed_beacon_block = message
+        .with_execution_payload(execution_payload)
+        .map_err(AnyhowError::new)?
+        .with_signature(signature)
+        .pipe(Arc::new);
+
+    let blob_sidecars = misc::construct_blob_sidecars(
+        &signed_beacon_block,
+        blobs.unwrap_or_default().into_iter(),
+        proofs.unwrap_or_default().into_iter(),
+    )?;
+
+    publish_signed_block(
+        signed_beacon_block,
+        blob_sidecars,
+        controller,
+        validator_config,
+        api_to_p2p_tx,
+        broadcast_validation,
+    )
+    .await
+}
+
 async fn publish_signed_block<P: Preset, W: Wait>(
     block: Arc<SignedBeaconBlock<P>>,
     blob_sidecars: Vec<BlobSidecar<P>>,
     controller: ApiController<P, W>,
+    validator_config: &ValidatorConfig,
     api_to_p2p_tx: UnboundedSender<ApiToP2p<P>>,
+    broadcast_validation: BroadcastValidation,
 ) -> Result<StatusCode, Error> {
-    for blob_sidecar in blob_sidecars {
-        let blob_sidecar = Arc::new(blob_sidecar);
-        controller.on_api_blob_sidecar(blob_sidecar.clone_arc());
-        ApiToP2p::PublishBlobSidecar(blob_sidecar).send(&api_to_p2p_tx);
-    }
+    let blob_sidecars = blob_sidecars.into_iter().map(Arc::new).collect_vec();
+
+    controller.on_api_blob_sidecars(blob_sidecars.iter().cloned());
 
-    ApiToP2p::PublishBeaconBlock(block.clone_arc()).send(&api_to_p2p_tx);
+    let slot = block.message().slot();
+    let proposer_index = block.message().proposer_index();
+    let block_root = block.message().hash_tree_root();
+    let check_equivocation = check_equivocation(validator_config, broadcast_validation);
 
     let (sender, mut receiver) = futures::channel::mpsc::channel(1);
 
+    // Blocks submitted with `broadcast_validation=gossip` are broadcast after gossip validation.
+    // Waiting for full validation would delay them by the time the state transition takes.
+    // Blocks that fail full validation after being broadcast are reported with 202.
+    if broadcast_validation == BroadcastValidation::Gossip {
+        if check_equivocation {
+            reject_equivocating_block(&controller, slot, proposer_index, block_root)?;
+        }
+
+        let gossip_controller = controller.clone_arc();
+        let gossip_block = block.clone_arc();
+
+        let gossip_result = tokio::task::spawn_blocking(move || {
+            gossip_controller.validate_block_for_gossip(&gossip_block)
+        })
+        .await?;
+
+        if let Err(error) = gossip_result {
+            warn!(
+                "block received through HTTP API failed gossip validation \
+                 and will not be broadcast (block: {block:?}, error: {error})",
+            );
+
+            return Err(Error::InvalidBlock(error));
+        }
+
+        broadcast_block(block.clone_arc(), blob_sidecars, &api_to_p2p_tx);
+
+        controller.on_api_block(block.clone_arc(), sender);
+
+        let status_code = match receiver.next().await.transpose() {
+            Ok(Some(ValidationOutcome::Accept)) => StatusCode::OK,
+            Ok(Some(ValidationOutcome::Ignore)) => {
+                // We log only the root with `info!` because this is not an exceptional case.
+                // Vouch submits blocks it constructs to all beacon nodes it is connected to.
+                // The blocks often reach our application through gossip faster than through the API.
+                info!("block received through HTTP API was ignored (block root: {block_root:?})");
+                StatusCode::ACCEPTED
+            }
+            Ok(None) => {
+                warn!("received no block validation response for HTTP API (block: {block:?})");
+                StatusCode::ACCEPTED
+            }
+            Err(error) => {
+                warn!("received invalid block through HTTP API (block: {block:?}, error: {error})");
+                StatusCode::ACCEPTED
+            }
+        };
+
+        return Ok(status_code);
+    }
+
     controller.on_api_block(block.clone_arc(), sender);
 
-    let status_code = match receiver.next().await.transpose() {
-        Ok(Some(ValidationOutcome::Accept)) => StatusCode::OK,
+    match receiver.next().await.transpose() {
+        Ok(Some(ValidationOutcome::Accept)) => {}
         Ok(Some(ValidationOutcome::Ignore)) => {
-            // We log only the root with `info!` because this is not an exceptional case.
-            // Vouch submits blocks it constructs to all beacon nodes it is connected to.
-            // The blocks often reach our application through gossip faster than through the API.
-            let block_root = block.message().hash_tree_root();
-            info!("block received through HTTP API was ignored (block root: {block_root:?})");
-            StatusCode::ACCEPTED
+            // Blocks that are already in fork choice are ignored but have passed validation.
+            // Blocks can also be ignored for being older than the finalized checkpoint.
+            if controller.block_by_root(block_root)?.is_none() {
+                warn!(
+                    "block received through HTTP API was ignored and will not be broadcast \
+                     (block root: {block_root:?})",
+                );
+
+                return Err(Error::BlockNotBroadcast);
+            }
         }
         Ok(None) => {
             warn!("received no block validation response for HTTP API (block: {block:?})");
-            StatusCode::ACCEPTED
+            return Err(Error::BlockNotBroadcast);
         }
         Err(error) => {
             warn!("received invalid block through HTTP API (block: {block:?}, error: {error})");
-            StatusCode::ACCEPTED
+            return Err(Error::InvalidBlock(error));
         }
-    };
+    }
+
+    if check_equivocation {
+        reject_equivocating_block(&controller, slot, proposer_index, block_root)?;
+    }
+
+    broadcast_block(block, blob_sidecars, &api_to_p2p_tx);
+
+    Ok(StatusCode::OK)
+}
 
-    Ok(status_code)
+fn check_equivocation(
+    validator_config: &ValidatorConfig,
+    broadcast_validation: BroadcastValidation,
+) -> bool {
+    validator_config.block_equivocation_check
+        || broadcast_validation == BroadcastValidation::ConsensusAndEquivocation
+}
+
+fn reject_equivocating_block<P: Preset, W: Wait>(
+    controller: &ApiController<P, W>,
+    slot: Slot,
+    proposer_index: ValidatorIndex,
+    block_root: H256,
+) -> Result<(), Error> {
+    if let Some(equivocating_block_root) =
+        controller.equivocating_block_root(slot, proposer_index, block_root)
+    {
+        warn!(
+            "block received through HTTP API equivocates and will not be broadcast \
+             (block root: {block_root:?}, equivocating block root: {equivocating_block_root:?})",
+        );
+
+        return Err(Error::BlockEquivocates {
+            equivocating_block_root,
+        });
+    }
+
+    Ok(())
+}
+
+fn broadcast_block<P: Preset>(
+    block: Arc<SignedBeaconBlock<P>>,
+    blob_sidecars: Vec<Arc<BlobSidecar<P>>>,
+    api_to_p2p_tx: &UnboundedSender<ApiToP2p<P>>,
+) {
+    for blob_sidecar in blob_sidecars {
+        ApiToP2p::PublishBlobSidecar(blob_sidecar).send(api_to_p2p_tx);
+    }
+
+    ApiToP2p::PublishBeaconBlock(block).send(api_to_p2p_tx);
 }
 
 async fn submit_attestation_to_pool<P: Preset, W: Wait>(
@@ -2715,6 +3547,36 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_deserialize_for_publish_block_query() -> Result<()> {
+        assert_eq!(
+            extract_query::<PublishBlockQuery>("")
+                .await?
+                .broadcast_validation,
+            BroadcastValidation::Gossip,
+        );
+
+        assert_eq!(
+            extract_query::<PublishBlockQuery>("broadcast_validation=consensus")
+                .await?
+                .broadcast_validation,
+            BroadcastValidation::Consensus,
+        );
+
+        assert_eq!(
+            extract_query::<PublishBlockQuery>("broadcast_validation=consensus_and_equivocation")
+                .await?
+                .broadcast_validation,
+            BroadcastValidation::ConsensusAndEquivocation,
+        );
+
+        extract_query::<PublishBlockQuery>("broadcast_validation=full")
+            .await
+            .expect_err("query should be invalid because full is not a validation level");
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_deserialize_for_sync_committee_subscription() -> Result<()> {
         let subscriptions = [SyncCommitteeSubscription {
@@ -2738,6 +3600,68 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_combine_selection_proofs_aggregates_partial_proofs_per_duty() -> Result<()> {
+        let message = H256::repeat_byte(1);
+        let partial_1 = interop::secret_key(0).sign(message);
+        let partial_2 = interop::secret_key(1).sign(message);
+        let complete = interop::secret_key(2).sign(message);
+
+        let selections = vec![
+            (1, SignatureBytes::from(partial_1)),
+            (2, complete.into()),
+            (1, partial_2.into()),
+        ];
+
+        let combined = combine_selection_proofs(
+            selections,
+            |(duty, _)| *duty,
+            |(_, selection_proof)| selection_proof,
+            |_| Ok(()),
+        )?;
+
+        assert_eq!(
+            combined,
+            [
+                (1, partial_1.aggregate(partial_2).into()),
+                (2, complete.into()),
+            ],
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_combine_selection_proofs_reports_failures_at_first_partial_proof() {
+        let signature = SignatureBytes::from(interop::secret_key(0).sign(H256::zero()));
+
+        let selections = vec![
+            (1, signature),
+            (2, SignatureBytes::zero()),
+            (1, signature),
+            (3, signature),
+        ];
+
+        let result = combine_selection_proofs(
+            selections,
+            |(duty, _)| *duty,
+            |(_, selection_proof)| selection_proof,
+            |(duty, _)| {
+                ensure!(*duty != 1, "combined selection proof is invalid");
+                Ok(())
+            },
+        );
+
+        let Err(Error::InvalidSelectionProofs(failures)) = result else {
+            panic!("selection proofs for duties 1 and 2 should be rejected");
+        };
+
+        assert_eq!(
+            failures.iter().map(|failure| failure.index).collect_vec(),
+            [0, 1],
+        );
+    }
+
     async fn extract_query<T: DeserializeOwned + 'static>(query: impl Display + Send) -> Result<T> {
         Request::get(format!("/?{query}"))
             .body(())?
@@ -1,9 +1,10 @@
 use std::sync::Arc;
 
 use eth1_api::ApiController;
-use fork_choice_control::Wait;
+use fork_choice_control::{CancellationToken, Wait};
 use genesis::GenesisProvider;
 use parse_display::FromStr;
+use std_ext::ArcExt as _;
 use types::{
     combined::BeaconState,
     nonstandard::WithStatus,
@@ -31,7 +32,35 @@ pub enum StateId {
 }
 
 impl StateId {
-    pub fn state<P: Preset, W: Wait>(
+    // See `BlockId::is_fixed`.
+    pub const fn is_fixed(&self) -> bool {
+        matches!(self, Self::Genesis | Self::Slot(_) | Self::Root(_))
+    }
+
+    // Loading a state by slot or root may require replaying blocks, which can take seconds.
+    // It is done on a blocking thread to let the request time out while the state is loading.
+    // Replaying stops if the request is dropped because the client disconnected or timed out.
+    // Other states are kept in memory and are returned directly.
+    pub async fn state<P: Preset, W: Wait>(
+        self,
+        controller: &ApiController<P, W>,
+        genesis_provider: GenesisProvider<P>,
+    ) -> Result<WithStatus<Arc<BeaconState<P>>>, Error> {
+        if !matches!(self, Self::Slot(_) | Self::Root(_)) {
+            return self.load_state(controller, genesis_provider);
+        }
+
+        let controller = controller.clone_arc();
+        let token = CancellationToken::default();
+        let _guard = CancelOnDrop(token.clone());
+
+        tokio::task::spawn_blocking(move || {
+            token.run(|| self.load_state(&controller, genesis_provider))
+        })
+        .await?
+    }
+
+    fn load_state<P: Preset, W: Wait>(
         self,
         controller: &ApiController<P, W>,
         genesis_provider: GenesisProvider<P>,
@@ -48,6 +77,14 @@ impl StateId {
     }
 }
 
+struct CancelOnDrop(CancellationToken);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.cancel();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use hex_literal::hex;
@@ -3,15 +3,20 @@ use std::{collections::HashSet, net::SocketAddr, sync::Arc};
 use anyhow::Result;
 use axum::{Router, Server};
 use bls::PublicKeyBytes;
-use eth1_api::ApiController;
+use builder_api::BuilderApi;
+use eth1_api::{ApiController, Eth1Api};
 use fork_choice_control::{ApiMessage, Wait};
 use futures::{
-    channel::mpsc::{UnboundedReceiver, UnboundedSender},
-    future::{FutureExt as _, TryFutureExt as _},
+    channel::{
+        mpsc::{UnboundedReceiver, UnboundedSender},
+        oneshot::Receiver as OneshotReceiver,
+    },
+    future::{self, FutureExt as _, TryFutureExt as _},
     select,
     stream::StreamExt as _,
 };
 use genesis::GenesisProvider;
+use http_api_utils::{RateLimiter, RequestQueue};
 use hyper::server::conn::AddrIncoming;
 use keymanager::KeyManager;
 use liveness_tracker::ApiToLiveness;
@@ -24,13 +29,15 @@ use p2p::{ApiToP2p, NetworkConfig, SyncToApi, ToSubnetService};
 use prometheus_metrics::Metrics;
 use std_ext::ArcExt as _;
 use types::preset::Preset;
-use validator::{ApiToValidator, ValidatorConfig, ValidatorToApi};
+use validator::{ApiToValidator, DutiesCache, ProposalReports, ValidatorConfig, ValidatorToApi};
 
 use crate::{
     events::{EventChannels, Topic},
     http_api_config::HttpApiConfig,
     misc::{BackSyncedStatus, SyncedStatus},
-    routing::{self, NormalState},
+    routing::{self, CostlyRouteLimits, NormalState},
+    tls::{CertificateResolver, TlsIncoming},
+    unix_socket,
 };
 
 pub struct Channels<P: Preset> {
@@ -39,6 +46,7 @@ pub struct Channels<P: Preset> {
     pub api_to_p2p_tx: UnboundedSender<ApiToP2p<P>>,
     pub api_to_validator_tx: UnboundedSender<ApiToValidator<P>>,
     pub fc_to_api_rx: UnboundedReceiver<ApiMessage<P>>,
+    pub graceful_shutdown_rx: OneshotReceiver<()>,
     pub pool_to_api_rx: UnboundedReceiver<PoolToApiMessage>,
     pub subnet_service_tx: UnboundedSender<ToSubnetService>,
     pub sync_to_api_rx: UnboundedReceiver<SyncToApi>,
@@ -49,6 +57,7 @@ pub struct Channels<P: Preset> {
 pub struct HttpApi<P: Preset, W: Wait> {
     pub controller: ApiController<P, W>,
     pub genesis_provider: GenesisProvider<P>,
+    pub eth1_api: Arc<Eth1Api>,
     pub keymanager: Arc<KeyManager>,
     pub validator_keys: Arc<HashSet<PublicKeyBytes>>,
     pub validator_config: Arc<ValidatorConfig>,
@@ -57,13 +66,21 @@ pub struct HttpApi<P: Preset, W: Wait> {
     pub attestation_agg_pool: Arc<AttestationAggPool<P, W>>,
     pub sync_committee_agg_pool: Arc<SyncCommitteeAggPool<P, W>>,
     pub bls_to_execution_change_pool: Arc<BlsToExecutionChangePool>,
+    pub duties_cache: Arc<DutiesCache>,
+    pub proposal_reports: Arc<ProposalReports>,
     pub channels: Channels<P>,
     pub metrics: Option<Arc<Metrics>>,
+    pub builder_api: Option<Arc<BuilderApi>>,
 }
 
 impl<P: Preset, W: Wait> HttpApi<P, W> {
     pub async fn run(self) -> Result<()> {
-        let incoming = self.http_api_config.incoming()?;
+        let incoming = self
+            .http_api_config
+            .listen_on_tcp
+            .then(|| self.http_api_config.incoming())
+            .transpose()?;
+
         self.run_internal(|_, router| router, incoming).await
     }
 
@@ -75,11 +92,12 @@ impl<P: Preset, W: Wait> HttpApi<P, W> {
     pub(crate) async fn run_internal(
         self,
         extend_router: impl FnOnce(NormalState<P, W>, Router) -> Router + Send,
-        incoming: AddrIncoming,
+        incoming: Option<AddrIncoming>,
     ) -> Result<()> {
         let Self {
             controller,
             genesis_provider,
+            eth1_api,
             keymanager,
             validator_keys,
             validator_config,
@@ -88,15 +106,30 @@ impl<P: Preset, W: Wait> HttpApi<P, W> {
             attestation_agg_pool,
             sync_committee_agg_pool,
             bls_to_execution_change_pool,
+            duties_cache,
+            proposal_reports,
             channels,
             metrics,
+            builder_api,
         } = self;
 
         let HttpApiConfig {
             address,
             allow_origin,
+            allow_methods,
             max_events,
+            event_replay_buffer_size,
             timeout,
+            costly_timeout,
+            costly_request_queue,
+            admin_token,
+            compression_threshold,
+            max_body_size,
+            max_block_body_size,
+            rate_limit,
+            tls,
+            listen_on_tcp: _,
+            unix_socket,
         } = http_api_config;
 
         let Channels {
@@ -105,6 +138,7 @@ impl<P: Preset, W: Wait> HttpApi<P, W> {
             api_to_p2p_tx,
             api_to_validator_tx,
             fc_to_api_rx,
+            graceful_shutdown_rx,
             pool_to_api_rx,
             subnet_service_tx,
             sync_to_api_rx,
@@ -113,16 +147,18 @@ impl<P: Preset, W: Wait> HttpApi<P, W> {
 
         let is_synced = Arc::new(SyncedStatus::new(controller.is_forward_synced()));
         let is_back_synced = Arc::new(BackSyncedStatus::default());
-        let event_channels = Arc::new(EventChannels::new(max_events));
+        let event_channels = Arc::new(EventChannels::new(max_events, event_replay_buffer_size));
 
         let state = NormalState {
             chain_config: controller.chain_config().clone_arc(),
             controller,
             genesis_provider,
+            eth1_api,
             keymanager,
             validator_keys,
             validator_config,
             metrics: metrics.clone(),
+            builder_api,
             network_config,
             attestation_agg_pool,
             sync_committee_agg_pool,
@@ -130,6 +166,8 @@ impl<P: Preset, W: Wait> HttpApi<P, W> {
             is_synced: is_synced.clone_arc(),
             is_back_synced: is_back_synced.clone_arc(),
             event_channels: event_channels.clone_arc(),
+            duties_cache,
+            proposal_reports,
             api_to_liveness_tx,
             api_to_metrics_tx,
             api_to_p2p_tx,
@@ -137,12 +175,76 @@ impl<P: Preset, W: Wait> HttpApi<P, W> {
             subnet_service_tx,
         };
 
-        let router = extend_router(state.clone(), routing::normal_routes(state));
-        let router =
-            http_api_utils::extend_router_with_middleware(router, timeout, allow_origin, metrics);
+        let routes = routing::normal_routes(
+            state.clone(),
+            admin_token,
+            max_body_size,
+            max_block_body_size,
+            &CostlyRouteLimits {
+                rate_limiter: rate_limit.map(RateLimiter::new).map(Arc::new),
+                request_queue: Some(Arc::new(RequestQueue::new(costly_request_queue))),
+                timeout: costly_timeout,
+            },
+        );
+
+        let router = extend_router(state, routes);
+        let router = http_api_utils::extend_router_with_middleware(
+            router,
+            timeout,
+            allow_origin,
+            allow_methods,
+            metrics,
+            compression_threshold,
+        );
+
+        // Stop accepting connections and wait for in-flight requests once shutdown is requested.
+        // The sender being dropped without sending means no shutdown was requested.
+        let graceful_shutdown = async {
+            if graceful_shutdown_rx.await.is_err() {
+                core::future::pending().await
+            }
+        }
+        .shared();
+
+        let serve_unix_socket = match unix_socket {
+            Some(unix_socket) => {
+                unix_socket::serve(unix_socket, router.clone(), graceful_shutdown.clone()).boxed()
+            }
+            None => future::ok(()).boxed(),
+        };
 
+        let certificate_resolver = tls.map(CertificateResolver::new).transpose()?.map(Arc::new);
         let service = router.into_make_service_with_connect_info::<SocketAddr>();
-        let serve_requests = Server::builder(incoming).serve(service).err_into();
+
+        if incoming.is_some() {
+            info!("HTTP server listening on {address}");
+        }
+
+        let serve_tcp = match (incoming, &certificate_resolver) {
+            (Some(incoming), Some(certificate_resolver)) => {
+                let incoming = TlsIncoming::new(incoming, certificate_resolver.acceptor());
+
+                Server::builder(incoming)
+                    .serve(service)
+                    .with_graceful_shutdown(graceful_shutdown)
+                    .err_into()
+                    .boxed()
+            }
+            (Some(incoming), None) => Server::builder(incoming)
+                .serve(service)
+                .with_graceful_shutdown(graceful_shutdown)
+                .err_into()
+                .boxed(),
+            (None, _) => future::ok(()).boxed(),
+        };
+
+        // Let both servers finish handling requests before returning.
+        let serve_requests = future::try_join(serve_tcp, serve_unix_socket).map_ok(|_| ());
+
+        let reload_certificates = match certificate_resolver {
+            Some(certificate_resolver) => certificate_resolver.reload_on_change().boxed(),
+            None => future::pending().boxed(),
+        };
 
         let handle_events = handle_events(
             is_synced,
@@ -154,11 +256,10 @@ impl<P: Preset, W: Wait> HttpApi<P, W> {
             validator_to_api_rx,
         );
 
-        info!("HTTP server listening on {address}");
-
         select! {
             result = serve_requests.fuse() => result,
             result = handle_events.fuse() => result,
+            result = reload_certificates.fuse() => result,
         }
     }
 }
@@ -172,17 +273,6 @@ async fn handle_events<P: Preset>(
     mut sync_to_api_rx: UnboundedReceiver<SyncToApi>,
     mut validator_to_api_rx: UnboundedReceiver<ValidatorToApi<P>>,
 ) -> Result<()> {
-    let EventChannels {
-        attestations,
-        blocks,
-        bls_to_execution_changes,
-        chain_reorgs,
-        contribution_and_proofs,
-        finalized_checkpoints,
-        heads,
-        voluntary_exits,
-    } = event_channels.as_ref();
-
     loop {
         select! {
             message = sync_to_api_rx.select_next_some() => {
@@ -195,13 +285,11 @@ async fn handle_events<P: Preset>(
             message = validator_to_api_rx.select_next_some() => {
                 let receivers = match message {
                     ValidatorToApi::ContributionAndProof(signed_contribution_and_proof) => {
-                        let event =
-                            Topic::ContributionAndProof.build(signed_contribution_and_proof)?;
-                        contribution_and_proofs.send(event).unwrap_or_default()
+                        event_channels
+                            .send(Topic::ContributionAndProof, signed_contribution_and_proof)?
                     }
                     ValidatorToApi::VoluntaryExit(signed_voluntary_exit) => {
-                        let event = Topic::VoluntaryExit.build(signed_voluntary_exit)?;
-                        voluntary_exits.send(event).unwrap_or_default()
+                        event_channels.send(Topic::VoluntaryExit, signed_voluntary_exit)?
                     }
                 };
 
@@ -211,24 +299,19 @@ async fn handle_events<P: Preset>(
             message = fc_to_api_rx.select_next_some() => {
                 let receivers = match message {
                     ApiMessage::AttestationEvent(attestation) => {
-                        let event = Topic::Attestation.build(attestation)?;
-                        attestations.send(event).unwrap_or_default()
+                        event_channels.send(Topic::Attestation, attestation)?
                     }
                     ApiMessage::BlockEvent(block_event) => {
-                        let event = Topic::Block.build(block_event)?;
-                        blocks.send(event).unwrap_or_default()
+                        event_channels.send(Topic::Block, block_event)?
                     }
                     ApiMessage::ChainReorgEvent(chain_reorg_event) => {
-                        let event = Topic::ChainReorg.build(chain_reorg_event)?;
-                        chain_reorgs.send(event).unwrap_or_default()
+                        event_channels.send(Topic::ChainReorg, chain_reorg_event)?
                     }
                     ApiMessage::FinalizedCheckpoint(finalized_checkpoint_event) => {
-                        let event = Topic::FinalizedCheckpoint.build(finalized_checkpoint_event)?;
-                        finalized_checkpoints.send(event).unwrap_or_default()
+                        event_channels.send(Topic::FinalizedCheckpoint, finalized_checkpoint_event)?
                     }
                     ApiMessage::Head(head_event) => {
-                        let event = Topic::Head.build(head_event)?;
-                        heads.send(event).unwrap_or_default()
+                        event_channels.send(Topic::Head, head_event)?
                     }
                 };
 
@@ -238,8 +321,8 @@ async fn handle_events<P: Preset>(
             message = pool_to_api_rx.select_next_some() => {
                 let receivers = match message {
                     PoolToApiMessage::SignedBlsToExecutionChange(signed_bls_to_execution_change) => {
-                        let event = Topic::BlsToExecutionChange.build(signed_bls_to_execution_change)?;
-                        bls_to_execution_changes.send(event).unwrap_or_default()
+                        event_channels
+                            .send(Topic::BlsToExecutionChange, signed_bls_to_execution_change)?
                     }
                 };
 
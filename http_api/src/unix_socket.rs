@@ -0,0 +1,77 @@
+use core::future::Future;
+
+use anyhow::Result;
+use axum::Router;
+
+use crate::http_api_config::UnixSocketConfig;
+
+#[cfg(unix)]
+use {
+    axum::{extract::ConnectInfo, Extension, Server},
+    hyper::server::accept,
+    log::info,
+    std::{
+        fs::Permissions,
+        net::{IpAddr, Ipv4Addr, SocketAddr},
+        os::unix::fs::{FileTypeExt as _, PermissionsExt as _},
+    },
+    tokio::net::UnixListener,
+};
+
+// Clients connected through the Unix socket are on the same machine as the server.
+// They are given a loopback address so that middleware relying on `ConnectInfo<SocketAddr>`
+// works with them and treats them as local (exempting them from rate limiting by default).
+#[cfg(unix)]
+const PEER_ADDRESS: SocketAddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0);
+
+#[cfg(unix)]
+pub async fn serve(
+    config: UnixSocketConfig,
+    router: Router,
+    graceful_shutdown: impl Future<Output = ()> + Send,
+) -> Result<()> {
+    let UnixSocketConfig { path, mode } = config;
+
+    // A socket left behind by a previous run that did not shut down cleanly would prevent binding.
+    // Other kinds of files are left alone in case the path was specified by mistake.
+    if let Ok(metadata) = fs_err::symlink_metadata(&path) {
+        if metadata.file_type().is_socket() {
+            fs_err::remove_file(&path)?;
+        }
+    }
+
+    let listener = UnixListener::bind(&path)?;
+
+    fs_err::set_permissions(&path, Permissions::from_mode(mode))?;
+
+    let incoming = accept::poll_fn(move |context| {
+        listener
+            .poll_accept(context)
+            .map(|result| Some(result.map(|(stream, _)| stream)))
+    });
+
+    let service = router
+        .layer(Extension(ConnectInfo(PEER_ADDRESS)))
+        .into_make_service();
+
+    info!("HTTP server listening on {}", path.display());
+
+    Server::builder(incoming)
+        .serve(service)
+        .with_graceful_shutdown(graceful_shutdown)
+        .await?;
+
+    fs_err::remove_file(path)?;
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+#[allow(clippy::unused_async)]
+pub async fn serve(
+    _config: UnixSocketConfig,
+    _router: Router,
+    _graceful_shutdown: impl Future<Output = ()> + Send,
+) -> Result<()> {
+    anyhow::bail!("Unix sockets are not supported on this platform")
+}
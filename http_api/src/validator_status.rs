@@ -1,5 +1,7 @@
 use bls::PublicKeyBytes;
-use helper_functions::{accessors, misc};
+use eth1_api::ApiController;
+use fork_choice_control::Wait;
+use helper_functions::misc;
 use parse_display::{Display, FromStr};
 use serde_with::{DeserializeFromStr, SerializeDisplay};
 use types::{
@@ -19,10 +21,14 @@ pub enum ValidatorId {
 }
 
 impl ValidatorId {
-    pub fn validator_index<P: Preset>(self, state: &BeaconState<P>) -> Option<ValidatorIndex> {
+    pub fn validator_index<P: Preset, W: Wait>(
+        self,
+        controller: &ApiController<P, W>,
+        state: &BeaconState<P>,
+    ) -> Option<ValidatorIndex> {
         match self {
             Self::ValidatorIndex(validator_index) => Some(validator_index),
-            Self::PublicKey(pubkey) => accessors::index_of_public_key(state, pubkey),
+            Self::PublicKey(pubkey) => controller.validator_index(state, pubkey),
         }
     }
 }
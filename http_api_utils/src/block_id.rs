@@ -8,12 +8,22 @@ pub enum BlockId {
     Head,
     Genesis,
     Finalized,
+    Justified,
     #[display("{0}")]
     Slot(Slot),
     #[display("{0:?}")]
     Root(H256),
 }
 
+impl BlockId {
+    // Identifiers like `head` refer to different blocks over time even if the current one is
+    // finalized. Responses to them must not be cached indefinitely.
+    #[must_use]
+    pub const fn is_fixed(self) -> bool {
+        matches!(self, Self::Genesis | Self::Slot(_) | Self::Root(_))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use test_case::test_case;
@@ -23,6 +33,7 @@ mod tests {
     #[test_case("head", BlockId::Head)]
     #[test_case("genesis", BlockId::Genesis)]
     #[test_case("finalized", BlockId::Finalized)]
+    #[test_case("justified", BlockId::Justified)]
     #[test_case("12", BlockId::Slot(12))]
     #[test_case(
         "0x0000000000000000000000000000000000000000000000000000000000000000",
@@ -1,12 +1,14 @@
-use core::fmt::Display;
+use core::{fmt::Display, time::Duration};
 use std::error::Error as StdError;
 
 use anyhow::Error as AnyhowError;
 use axum::{
-    http::{StatusCode, Uri},
+    http::{header::RETRY_AFTER, StatusCode, Uri},
     response::{IntoResponse, Response},
+    Json,
 };
 use itertools::Itertools as _;
+use serde::{Serialize, Serializer};
 use thiserror::Error;
 
 use crate::misc::Direction;
@@ -19,11 +21,34 @@ pub enum Error {
         uri: Uri,
         source: AnyhowError,
     },
+    #[error("request body for {uri} is larger than {max_size} bytes")]
+    BodyTooLarge { uri: Uri, max_size: usize },
+    #[error("too many requests; retry after {retry_after:?}")]
+    RateLimited { retry_after: Duration },
+    #[error("too many requests to costly endpoints are queued; retry after {retry_after:?}")]
+    Overloaded { retry_after: Duration },
+    #[error("request took longer than {timeout:?}")]
+    TimedOut { timeout: Duration },
+}
+
+impl Serialize for Error {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&self.format_sources())
+    }
 }
 
 impl IntoResponse for Error {
     fn into_response(self) -> Response {
-        self.status_code().into_response()
+        let status_code = self.status_code();
+        let body = Json(self.body()).into_response();
+
+        if let Self::RateLimited { retry_after } | Self::Overloaded { retry_after } = self {
+            // `Retry-After` can only be specified in whole seconds.
+            let seconds = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+            return (status_code, [(RETRY_AFTER, seconds.to_string())], body).into_response();
+        }
+
+        (status_code, body).into_response()
     }
 }
 
@@ -47,9 +72,53 @@ impl Error {
         })
     }
 
+    fn body(&self) -> EthErrorResponse {
+        EthErrorResponse {
+            code: self.status_code().as_u16(),
+            message: self,
+        }
+    }
+
     const fn status_code(&self) -> StatusCode {
         match self {
             Self::InvalidBody { .. } => StatusCode::BAD_REQUEST,
+            Self::BodyTooLarge { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+            Self::RateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
+            Self::Overloaded { .. } => StatusCode::SERVICE_UNAVAILABLE,
+            Self::TimedOut { .. } => StatusCode::REQUEST_TIMEOUT,
         }
     }
 }
+
+// This mirrors the error object in the Beacon API specification used by handlers in `http_api`.
+#[derive(Serialize)]
+struct EthErrorResponse<'error> {
+    code: u16,
+    message: &'error Error,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Result, Value};
+
+    use super::*;
+
+    #[test]
+    fn error_is_serialized_correctly() -> Result<()> {
+        let error = Error::BodyTooLarge {
+            uri: Uri::from_static("/eth/v1/beacon/pool/attestations"),
+            max_size: 1024,
+        };
+
+        assert_eq!(
+            serde_json::to_value(error.body())?,
+            json!({
+                "code": 413,
+                "message": "request body for /eth/v1/beacon/pool/attestations \
+                            is larger than 1024 bytes",
+            }),
+        );
+
+        Ok(())
+    }
+}
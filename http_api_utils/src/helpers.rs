@@ -1,34 +1,159 @@
 use core::time::Duration;
 use std::sync::Arc;
 
-use axum::{error_handling::HandleErrorLayer, http::StatusCode, Router};
+use axum::{
+    error_handling::HandleErrorLayer,
+    extract::DefaultBodyLimit,
+    http::{
+        header::{AUTHORIZATION, CONTENT_TYPE},
+        HeaderName,
+    },
+    Router,
+};
 use features::Feature;
 use prometheus_metrics::Metrics;
 use tower::ServiceBuilder;
 use tower_http::{
-    cors::{AllowOrigin, CorsLayer},
+    compression::{
+        predicate::{NotForContentType, Predicate as _, SizeAbove},
+        CompressionLayer,
+    },
+    cors::{AllowHeaders, AllowMethods, AllowOrigin, CorsLayer},
+    request_id::{PropagateRequestIdLayer, SetRequestIdLayer},
     trace::TraceLayer,
 };
 
-use crate::{logging, middleware};
+use crate::{
+    error::Error, logging, middleware, misc::SequentialRequestId, rate_limiter::RateLimiter,
+    request_queue::RequestQueue,
+};
+
+// Header names in `http` are lowercase.
+const ETH_CONSENSUS_VERSION: HeaderName = HeaderName::from_static("eth-consensus-version");
+const LAST_EVENT_ID: HeaderName = HeaderName::from_static("last-event-id");
+const X_REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+
+// This only applies to routes already added to `router`.
+// Routes added later can be given a different limit by calling this again.
+pub fn limit_request_body_size<S: Clone + Send + Sync + 'static>(
+    router: Router<S>,
+    max_size: usize,
+) -> Router<S> {
+    router
+        // `axum` extractors enforce a limit of their own that would otherwise apply on top of ours.
+        .route_layer(DefaultBodyLimit::disable())
+        .route_layer(axum::middleware::from_fn_with_state(
+            max_size,
+            middleware::limit_request_body_size,
+        ))
+}
+
+// Like `limit_request_body_size`, this only applies to routes already added to `router`.
+pub fn limit_request_rate<S: Clone + Send + Sync + 'static>(
+    router: Router<S>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+) -> Router<S> {
+    match rate_limiter {
+        Some(rate_limiter) => router.route_layer(axum::middleware::from_fn_with_state(
+            rate_limiter,
+            middleware::limit_request_rate,
+        )),
+        None => router,
+    }
+}
+
+// Like `limit_request_body_size`, this only applies to routes already added to `router`.
+// `request_queue` should be shared between all routers it is applied to.
+pub fn queue_requests<S: Clone + Send + Sync + 'static>(
+    router: Router<S>,
+    request_queue: Option<Arc<RequestQueue>>,
+) -> Router<S> {
+    match request_queue {
+        Some(request_queue) => router.route_layer(axum::middleware::from_fn_with_state(
+            request_queue,
+            middleware::queue_requests,
+        )),
+        None => router,
+    }
+}
+
+// Like `limit_request_body_size`, this only applies to routes already added to `router`.
+// Handlers are dropped when the deadline passes. Work they started in the background should be
+// canceled when that happens.
+pub fn limit_request_duration<S: Clone + Send + Sync + 'static>(
+    router: Router<S>,
+    timeout: Option<Duration>,
+) -> Router<S> {
+    match timeout {
+        Some(timeout) => router.route_layer(
+            ServiceBuilder::new()
+                .layer(HandleErrorLayer::new(move |_| async move {
+                    Error::TimedOut { timeout }
+                }))
+                .timeout(timeout),
+        ),
+        None => router,
+    }
+}
 
 pub fn extend_router_with_middleware(
     mut router: Router,
     timeout: Option<Duration>,
     allowed_origins: AllowOrigin,
+    allowed_methods: AllowMethods,
     metrics: Option<Arc<Metrics>>,
+    compression_threshold: Option<u16>,
 ) -> Router {
+    router = router.layer(axum::middleware::from_fn(
+        middleware::handle_conditional_requests,
+    ));
+
+    // Compressing small responses is not worth the CPU time.
+    // Event streams must not be compressed because compression buffers output.
+    if let Some(threshold) = compression_threshold {
+        let predicate = SizeAbove::new(threshold)
+            .and(NotForContentType::GRPC)
+            .and(NotForContentType::IMAGES)
+            .and(NotForContentType::const_new("text/event-stream"));
+
+        router = router.layer(
+            CompressionLayer::new()
+                .gzip(true)
+                .zstd(true)
+                .compress_when(predicate),
+        );
+    }
+
     if let Some(timeout) = timeout {
         router = router.layer(
             ServiceBuilder::new()
-                .layer(HandleErrorLayer::new(|_| async {
-                    StatusCode::REQUEST_TIMEOUT
+                .layer(HandleErrorLayer::new(move |_| async move {
+                    Error::TimedOut { timeout }
                 }))
                 .timeout(timeout),
         );
     }
 
-    router = router.layer(CorsLayer::new().allow_origin(allowed_origins).vary([]));
+    // Preflight requests are only made for methods and headers that are not CORS-safelisted.
+    // These are all the non-safelisted request headers our APIs read:
+    // - `Content-Type` when it is not one of the types allowed in simple requests.
+    // - `Authorization` for the Keymanager API.
+    // - `Eth-Consensus-Version` for block submissions.
+    // - `Last-Event-ID` for resuming event streams.
+    // - `X-Request-Id` for request IDs assigned by clients.
+    router = router.layer(
+        CorsLayer::new()
+            .allow_origin(allowed_origins)
+            .allow_methods(allowed_methods)
+            .allow_headers(AllowHeaders::list([
+                CONTENT_TYPE,
+                AUTHORIZATION,
+                ETH_CONSENSUS_VERSION,
+                LAST_EVENT_ID,
+                X_REQUEST_ID,
+            ]))
+            .vary([]),
+    );
 
     if Feature::LogHttpRequests.is_enabled() || metrics.is_some() {
         router = router.layer(axum::middleware::from_fn(
@@ -59,5 +184,174 @@ pub fn extend_router_with_middleware(
         ));
     }
 
+    // Request IDs are assigned outside of all other middleware so that everything logged for a
+    // request can be correlated. IDs sent by clients in `X-Request-Id` are kept, which lets
+    // operators match errors in validator clients with messages logged here.
+    router = router.layer(
+        ServiceBuilder::new()
+            .layer(SetRequestIdLayer::x_request_id(SequentialRequestId))
+            .layer(PropagateRequestIdLayer::x_request_id())
+            .layer(axum::middleware::from_fn(middleware::log_access)),
+    );
+
     router
 }
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use axum::{
+        body::Body,
+        http::{
+            header::{
+                ACCESS_CONTROL_ALLOW_HEADERS, ACCESS_CONTROL_ALLOW_METHODS,
+                ACCESS_CONTROL_ALLOW_ORIGIN, ACCESS_CONTROL_REQUEST_HEADERS,
+                ACCESS_CONTROL_REQUEST_METHOD, ORIGIN,
+            },
+            HeaderValue, Method, Request, StatusCode,
+        },
+        response::Response,
+        routing::get,
+    };
+    use tower::Service as _;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn limit_request_duration_only_applies_to_existing_routes() -> Result<()> {
+        let slow_handler = || async {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+        };
+
+        let router = Router::new().route("/limited", get(slow_handler));
+
+        let mut router = limit_request_duration(router, Some(Duration::from_millis(10)))
+            .route("/unlimited", get(|| async {}));
+
+        let request = Request::get("/limited").body(Body::empty())?;
+        let response = router.call(request).await?;
+
+        assert_eq!(response.status(), StatusCode::REQUEST_TIMEOUT);
+
+        let request = Request::get("/unlimited").body(Body::empty())?;
+        let response = router.call(request).await?;
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn preflight_requests_allow_configured_methods_and_headers_read_by_api() -> Result<()> {
+        let mut router = cors_router(
+            AllowOrigin::list([HeaderValue::from_static("http://localhost:3000")]),
+            AllowMethods::list([Method::GET, Method::POST]),
+        );
+
+        let request = Request::options("/")
+            .header(ORIGIN, "http://localhost:3000")
+            .header(ACCESS_CONTROL_REQUEST_METHOD, "POST")
+            .header(
+                ACCESS_CONTROL_REQUEST_HEADERS,
+                "content-type,eth-consensus-version,last-event-id",
+            )
+            .body(Body::empty())?;
+
+        let response = router.call(request).await?;
+        let headers = response.headers();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            headers[ACCESS_CONTROL_ALLOW_ORIGIN],
+            "http://localhost:3000"
+        );
+        assert_eq!(headers[ACCESS_CONTROL_ALLOW_METHODS], "GET,POST");
+        assert_eq!(
+            headers[ACCESS_CONTROL_ALLOW_HEADERS],
+            "content-type,authorization,eth-consensus-version,last-event-id,x-request-id",
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn preflight_requests_from_other_origins_are_not_allowed() -> Result<()> {
+        let mut router = cors_router(
+            AllowOrigin::list([HeaderValue::from_static("http://localhost:3000")]),
+            AllowMethods::list([Method::POST]),
+        );
+
+        let request = Request::options("/")
+            .header(ORIGIN, "http://example.com")
+            .header(ACCESS_CONTROL_REQUEST_METHOD, "POST")
+            .body(Body::empty())?;
+
+        let response = router.call(request).await?;
+
+        assert!(!response.headers().contains_key(ACCESS_CONTROL_ALLOW_ORIGIN));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn request_ids_are_assigned_and_visible_to_handlers() -> Result<()> {
+        let mut router = request_id_router();
+
+        let first = router.call(Request::get("/").body(Body::empty())?).await?;
+        let second = router.call(Request::get("/").body(Body::empty())?).await?;
+
+        let first_id = first.headers()[X_REQUEST_ID].clone();
+        let second_id = second.headers()[X_REQUEST_ID].clone();
+
+        assert_ne!(first_id, second_id);
+        assert_eq!(response_text(first).await?, first_id);
+        assert_eq!(response_text(second).await?, second_id);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn request_ids_sent_by_clients_are_kept() -> Result<()> {
+        let mut router = request_id_router();
+
+        let request = Request::get("/")
+            .header(X_REQUEST_ID, "vc-1234")
+            .body(Body::empty())?;
+
+        let response = router.call(request).await?;
+
+        assert_eq!(response.headers()[X_REQUEST_ID], "vc-1234");
+        assert_eq!(response_text(response).await?, "vc-1234");
+
+        Ok(())
+    }
+
+    fn request_id_router() -> Router {
+        let handler = || async {
+            binary_utils::current_request_id()
+                .map(String::from)
+                .unwrap_or_default()
+        };
+        let router = Router::new().route("/", get(handler));
+
+        extend_router_with_middleware(
+            router,
+            None,
+            AllowOrigin::any(),
+            AllowMethods::any(),
+            None,
+            None,
+        )
+    }
+
+    async fn response_text(response: Response) -> Result<String> {
+        let bytes = hyper::body::to_bytes(response.into_body()).await?;
+        Ok(String::from_utf8(bytes.to_vec())?)
+    }
+
+    fn cors_router(allowed_origins: AllowOrigin, allowed_methods: AllowMethods) -> Router {
+        let router = Router::new().route("/", get(|| async {}).post(|| async {}));
+
+        extend_router_with_middleware(router, None, allowed_origins, allowed_methods, None, None)
+    }
+}
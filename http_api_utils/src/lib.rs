@@ -1,6 +1,11 @@
 pub use block_id::BlockId;
-pub use helpers::extend_router_with_middleware;
+pub use helpers::{
+    extend_router_with_middleware, limit_request_body_size, limit_request_duration,
+    limit_request_rate, queue_requests,
+};
 pub use misc::Direction;
+pub use rate_limiter::{RateLimitConfig, RateLimiter};
+pub use request_queue::{RequestQueue, RequestQueueConfig};
 
 pub mod logging;
 pub mod middleware;
@@ -9,3 +14,5 @@ mod block_id;
 mod error;
 mod helpers;
 mod misc;
+mod rate_limiter;
+mod request_queue;
@@ -1,24 +1,44 @@
-use std::{error::Error as StdError, net::SocketAddr};
+use core::sync::atomic::{AtomicBool, Ordering};
+use std::{
+    error::Error as StdError,
+    net::SocketAddr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use axum::{
     body::{Body, Bytes, HttpBody},
-    extract::{ConnectInfo, MatchedPath, OriginalUri},
+    extract::{ConnectInfo, MatchedPath, OriginalUri, State},
     http::{
-        header::{HeaderValue, CONTENT_TYPE},
-        Request, Uri,
+        header::{
+            HeaderValue, AUTHORIZATION, CACHE_CONTROL, CONTENT_LENGTH, CONTENT_TYPE, ETAG,
+            IF_NONE_MATCH, VARY,
+        },
+        Method, Request, StatusCode, Uri,
     },
     middleware::Next,
     response::{IntoResponse as _, Response},
     Error as AxumError, Extension,
 };
+use futures::stream::StreamExt as _;
 use log::info;
 use mime::{APPLICATION_JSON, TEXT_EVENT_STREAM};
+use parse_display::Display;
+use std_ext::ArcExt as _;
+use tower_http::request_id::RequestId;
 
-use crate::{error::Error, misc::Direction};
+use crate::{
+    error::Error,
+    misc::Direction,
+    rate_limiter::{Client, RateLimiter},
+    request_queue::{self, RequestQueue},
+};
 
 // Don't log states when `Feature::LogHttpBodies` is enabled.
 const ENDPOINTS_WITH_IGNORED_BODIES: &[&str] = &["/eth/v2/debug/beacon/states/"];
 
+const ACCESS_LOG_TARGET: &str = "http_api_utils::access";
+
 async fn buffer_and_log<B>(direction: Direction, uri: &Uri, body: B) -> Result<Bytes, Error>
 where
     B: HttpBody<Data = Bytes> + Send,
@@ -68,6 +88,210 @@ pub async fn insert_response_extensions(request: Request<Body>, next: Next<Body>
         .into_response()
 }
 
+#[derive(Display)]
+#[display("{method} {path} (status: {status}, duration: {duration:?}, body_size: {body_size})")]
+struct AccessLogEntry<'path> {
+    method: Method,
+    path: &'path str,
+    status: StatusCode,
+    duration: Duration,
+    #[display("{}")]
+    body_size: BodySize,
+}
+
+#[derive(Display)]
+enum BodySize {
+    #[display("{0}")]
+    Exact(u64),
+    // Streamed bodies like event streams have no size known in advance.
+    #[display("unknown")]
+    Unknown,
+}
+
+// Access logs are always written. `Feature::LogHttpRequests` adds more detail on top of them.
+// Messages logged while handling the request are tagged with its ID.
+// The ID is assigned by `SetRequestIdLayer` and is always present.
+pub async fn log_access(request: Request<Body>, next: Next<Body>) -> Response {
+    let started_at = Instant::now();
+    let method = request.method().clone();
+
+    let path = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or_else(|| request.uri().path(), MatchedPath::as_str)
+        .to_owned();
+
+    let request_id = request
+        .extensions()
+        .get::<RequestId>()
+        .and_then(|request_id| request_id.header_value().to_str().ok())
+        .unwrap_or_default()
+        .to_owned();
+
+    binary_utils::with_request_id(request_id, async {
+        let response = next.run(request).await;
+
+        let entry = AccessLogEntry {
+            method,
+            path: &path,
+            status: response.status(),
+            duration: started_at.elapsed(),
+            body_size: response
+                .body()
+                .size_hint()
+                .exact()
+                .map_or(BodySize::Unknown, BodySize::Exact),
+        };
+
+        info!(target: ACCESS_LOG_TARGET, "{entry}");
+
+        response
+    })
+    .await
+}
+
+// Bodies that declare their size in `Content-Length` are rejected before any of them is read.
+// Other bodies are cut off as soon as the limit is exceeded, so they are never buffered in full.
+// Handlers report errors in reading the body in different ways, so the response is replaced.
+pub async fn limit_request_body_size(
+    State(max_size): State<usize>,
+    request: Request<Body>,
+    next: Next<Body>,
+) -> Result<Response, Error> {
+    let uri = request.uri().clone();
+
+    let content_length = request
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<usize>().ok());
+
+    if content_length.is_some_and(|length| length > max_size) {
+        return Err(Error::BodyTooLarge { uri, max_size });
+    }
+
+    let (parts, body) = request.into_parts();
+    let exceeded = Arc::new(AtomicBool::new(false));
+    let mut size = 0;
+
+    let body = body.map({
+        let uri = uri.clone();
+        let exceeded = exceeded.clone_arc();
+
+        move |result| -> Result<Bytes, Box<dyn StdError + Send + Sync>> {
+            let chunk = result?;
+
+            size += chunk.len();
+
+            if size > max_size {
+                exceeded.store(true, Ordering::Relaxed);
+                let uri = uri.clone();
+                return Err(Error::BodyTooLarge { uri, max_size }.into());
+            }
+
+            Ok(chunk)
+        }
+    });
+
+    let request = Request::from_parts(parts, Body::wrap_stream(body));
+    let response = next.run(request).await;
+
+    if exceeded.load(Ordering::Relaxed) {
+        return Err(Error::BodyTooLarge { uri, max_size });
+    }
+
+    Ok(response)
+}
+
+// Requests with a bearer token may be limited per token rather than per address.
+pub async fn limit_request_rate(
+    State(rate_limiter): State<Arc<RateLimiter>>,
+    ConnectInfo(remote): ConnectInfo<SocketAddr>,
+    request: Request<Body>,
+    next: Next<Body>,
+) -> Result<Response, Error> {
+    if rate_limiter.is_exempt(remote.ip()) {
+        return Ok(next.run(request).await);
+    }
+
+    let token = request
+        .headers()
+        .get(AUTHORIZATION)
+        .filter(|_| rate_limiter.limits_per_token())
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    let client = match token {
+        Some(token) => Client::Token(token.to_owned()),
+        None => Client::Address(remote.ip().to_canonical()),
+    };
+
+    rate_limiter
+        .check(client, Instant::now())
+        .map_err(|retry_after| Error::RateLimited { retry_after })?;
+
+    Ok(next.run(request).await)
+}
+
+pub async fn queue_requests(
+    State(request_queue): State<Arc<RequestQueue>>,
+    request: Request<Body>,
+    next: Next<Body>,
+) -> Result<Response, Error> {
+    let queued_request = request_queue.try_enter().ok_or(Error::Overloaded {
+        retry_after: request_queue::RETRY_AFTER,
+    })?;
+
+    let _permits = queued_request.wait_for_turn().await;
+
+    Ok(next.run(request).await)
+}
+
+// Handlers only add `ETag` headers to responses for resources that cannot change.
+// The response is still produced in full, but there is no need to send it if the client has it.
+// See <https://www.rfc-editor.org/rfc/rfc9110#section-13.1.2>.
+pub async fn handle_conditional_requests(request: Request<Body>, next: Next<Body>) -> Response {
+    let if_none_match = request.headers().get(IF_NONE_MATCH).cloned();
+    let response = next.run(request).await;
+
+    let Some(if_none_match) = if_none_match else {
+        return response;
+    };
+
+    let Some(etag) = response.headers().get(ETAG) else {
+        return response;
+    };
+
+    if !response.status().is_success() || !etag_matches(&if_none_match, etag) {
+        return response;
+    }
+
+    let mut not_modified = StatusCode::NOT_MODIFIED.into_response();
+
+    for name in [ETAG, CACHE_CONTROL, VARY] {
+        if let Some(value) = response.headers().get(&name) {
+            not_modified.headers_mut().insert(name, value.clone());
+        }
+    }
+
+    not_modified
+}
+
+// `If-None-Match` uses weak comparison and may contain a list of entity tags.
+fn etag_matches(if_none_match: &HeaderValue, etag: &HeaderValue) -> bool {
+    let (Ok(if_none_match), Ok(etag)) = (if_none_match.to_str(), etag.to_str()) else {
+        return false;
+    };
+
+    let strip_weakness = |tag: &str| tag.strip_prefix("W/").unwrap_or(tag).to_owned();
+    let etag = strip_weakness(etag);
+
+    if_none_match
+        .split(',')
+        .map(str::trim)
+        .any(|candidate| candidate == "*" || strip_weakness(candidate) == etag)
+}
+
 pub async fn log_request_and_response_bodies(
     request: Request<Body>,
     next: Next<Body>,
@@ -111,3 +335,45 @@ pub async fn patch_content_type(mut request: Request<Body>) -> Request<Body> {
 
     request
 }
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    #[test_case(Method::GET, "/eth/v1/node/health", StatusCode::OK, BodySize::Exact(0) =>
+        "GET /eth/v1/node/health (status: 200 OK, duration: 5ms, body_size: 0)"
+    )]
+    #[test_case(Method::GET, "/eth/v1/events", StatusCode::OK, BodySize::Unknown =>
+        "GET /eth/v1/events (status: 200 OK, duration: 5ms, body_size: unknown)"
+    )]
+    fn access_log_entry_format(
+        method: Method,
+        path: &str,
+        status: StatusCode,
+        body_size: BodySize,
+    ) -> String {
+        AccessLogEntry {
+            method,
+            path,
+            status,
+            duration: Duration::from_millis(5),
+            body_size,
+        }
+        .to_string()
+    }
+
+    #[test_case("\"0x01-json\"", "\"0x01-json\"" => true)]
+    #[test_case("W/\"0x01-json\"", "\"0x01-json\"" => true)]
+    #[test_case("\"0x02-json\", \"0x01-json\"", "\"0x01-json\"" => true)]
+    #[test_case("*", "\"0x01-json\"" => true)]
+    #[test_case("\"0x01-ssz\"", "\"0x01-json\"" => false)]
+    #[test_case("\"0x01\"", "\"0x01-json\"" => false)]
+    fn etag_matches_if_none_match(if_none_match: &'static str, etag: &'static str) -> bool {
+        etag_matches(
+            &HeaderValue::from_static(if_none_match),
+            &HeaderValue::from_static(etag),
+        )
+    }
+}
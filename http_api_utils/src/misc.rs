@@ -1,4 +1,10 @@
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use axum::http::{HeaderValue, Request};
 use parse_display::Display;
+use tower_http::request_id::{MakeRequestId, RequestId};
+
+static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(0);
 
 #[derive(Clone, Copy, Debug, Display)]
 #[display(style = "lowercase")]
@@ -6,3 +12,15 @@ pub enum Direction {
     Request,
     Response,
 }
+
+// IDs only need to be unique within a single run of the application.
+// They are shared between all servers so that IDs in logs are unambiguous.
+#[derive(Clone, Copy, Default)]
+pub struct SequentialRequestId;
+
+impl MakeRequestId for SequentialRequestId {
+    fn make_request_id<B>(&mut self, _request: &Request<B>) -> Option<RequestId> {
+        let id = NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed);
+        Some(RequestId::new(HeaderValue::from(id)))
+    }
+}
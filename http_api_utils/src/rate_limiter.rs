@@ -0,0 +1,165 @@
+use core::{num::NonZeroU32, time::Duration};
+use std::{
+    collections::HashMap,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    time::Instant,
+};
+
+use parking_lot::Mutex;
+
+// Clients whose theoretical arrival time has passed are indistinguishable from new ones.
+// They are removed once this many clients are being tracked to keep memory usage bounded.
+const MAX_TRACKED_CLIENTS: usize = 16384;
+
+#[derive(Clone, Debug)]
+pub struct RateLimitConfig {
+    pub requests_per_second: NonZeroU32,
+    pub burst: NonZeroU32,
+    // Tokens are not validated, so clients could evade limits by sending different tokens.
+    // This should only be enabled if a proxy in front of the API authenticates them.
+    pub limit_per_token: bool,
+    // Requests from these addresses are never limited.
+    pub exempt_addresses: Vec<IpAddr>,
+}
+
+impl RateLimitConfig {
+    #[must_use]
+    pub fn new(requests_per_second: NonZeroU32) -> Self {
+        Self {
+            requests_per_second,
+            burst: requests_per_second,
+            limit_per_token: false,
+            exempt_addresses: Self::default_exempt_addresses(),
+        }
+    }
+
+    #[must_use]
+    pub fn default_exempt_addresses() -> Vec<IpAddr> {
+        vec![Ipv4Addr::LOCALHOST.into(), Ipv6Addr::LOCALHOST.into()]
+    }
+}
+
+/// Identifies a client for rate limiting purposes.
+/// Clients that send the same token are limited together regardless of their address.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Client {
+    Token(String),
+    Address(IpAddr),
+}
+
+/// Rate limiter implementing the generic cell rate algorithm with a separate state for each
+/// [`Client`]. It behaves like a token bucket but only needs to store a single timestamp.
+/// See <https://en.wikipedia.org/wiki/Generic_cell_rate_algorithm>.
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    // Theoretical arrival times of the next request from each client.
+    arrival_times: Mutex<HashMap<Client, Instant>>,
+}
+
+impl RateLimiter {
+    #[must_use]
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config,
+            arrival_times: Mutex::default(),
+        }
+    }
+
+    pub(crate) const fn limits_per_token(&self) -> bool {
+        self.config.limit_per_token
+    }
+
+    pub(crate) fn is_exempt(&self, address: IpAddr) -> bool {
+        self.config
+            .exempt_addresses
+            .contains(&address.to_canonical())
+    }
+
+    // Returns how long the client should wait before retrying if the request is not allowed.
+    pub(crate) fn check(&self, client: Client, now: Instant) -> Result<(), Duration> {
+        let interval = Duration::from_secs(1) / self.config.requests_per_second.get();
+        let tolerance = interval * (self.config.burst.get() - 1);
+
+        let mut arrival_times = self.arrival_times.lock();
+
+        if arrival_times.len() >= MAX_TRACKED_CLIENTS {
+            arrival_times.retain(|_, arrival_time| *arrival_time > now);
+        }
+
+        let arrival_time = arrival_times.entry(client).or_insert(now);
+        let earliest_allowed = (*arrival_time).max(now);
+
+        let retry_after = earliest_allowed.saturating_duration_since(now + tolerance);
+
+        if !retry_after.is_zero() {
+            return Err(retry_after);
+        }
+
+        *arrival_time = earliest_allowed + interval;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rate_limiter_allows_bursts_and_refills_over_time() {
+        let rate_limiter = RateLimiter::new(RateLimitConfig {
+            burst: NonZeroU32::new(3).expect("3 is nonzero"),
+            ..RateLimitConfig::new(NonZeroU32::new(2).expect("2 is nonzero"))
+        });
+
+        let client = Client::Address(Ipv4Addr::new(192, 0, 2, 1).into());
+        let start = Instant::now();
+
+        for _ in 0..3 {
+            assert_eq!(rate_limiter.check(client.clone(), start), Ok(()));
+        }
+
+        assert_eq!(
+            rate_limiter.check(client.clone(), start),
+            Err(Duration::from_millis(500)),
+        );
+
+        let later = start + Duration::from_millis(500);
+
+        assert_eq!(rate_limiter.check(client.clone(), later), Ok(()));
+        assert!(rate_limiter.check(client, later).is_err());
+    }
+
+    #[test]
+    fn rate_limiter_tracks_clients_separately() {
+        let rate_limiter = RateLimiter::new(RateLimitConfig::new(
+            NonZeroU32::new(1).expect("1 is nonzero"),
+        ));
+
+        let now = Instant::now();
+
+        assert_eq!(
+            rate_limiter.check(Client::Token("a".to_owned()), now),
+            Ok(()),
+        );
+        assert_eq!(
+            rate_limiter.check(Client::Token("b".to_owned()), now),
+            Ok(()),
+        );
+        assert!(rate_limiter
+            .check(Client::Token("a".to_owned()), now)
+            .is_err());
+    }
+
+    #[test]
+    fn loopback_addresses_are_exempt_by_default() {
+        let rate_limiter = RateLimiter::new(RateLimitConfig::new(
+            NonZeroU32::new(1).expect("1 is nonzero"),
+        ));
+
+        assert!(rate_limiter.is_exempt(Ipv4Addr::LOCALHOST.into()));
+        assert!(rate_limiter.is_exempt(Ipv6Addr::LOCALHOST.into()));
+        assert!(rate_limiter.is_exempt(Ipv4Addr::LOCALHOST.to_ipv6_mapped().into()));
+        assert!(!rate_limiter.is_exempt(Ipv4Addr::new(192, 0, 2, 1).into()));
+    }
+}
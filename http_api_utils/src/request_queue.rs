@@ -0,0 +1,129 @@
+use core::{num::NonZeroUsize, time::Duration};
+use std::sync::Arc;
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+// Costly requests usually take well under a second once they are being handled.
+// Clients are told to retry after this long when the queue is full.
+pub const RETRY_AFTER: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Copy, Debug)]
+pub struct RequestQueueConfig {
+    pub max_concurrent_requests: NonZeroUsize,
+    // Requests over `max_concurrent_requests` wait for their turn until this many are waiting.
+    // Any more are rejected with 503.
+    pub max_queued_requests: usize,
+}
+
+impl Default for RequestQueueConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent_requests: NonZeroUsize::new(4).expect("4 is nonzero"),
+            max_queued_requests: 128,
+        }
+    }
+}
+
+/// Bounded queue for requests to costly endpoints.
+///
+/// Loading states and iterating over validators competes with block import for CPU time and
+/// memory. Limiting how many such requests are handled at once keeps bursts of them from starving
+/// the rest of the application. Requests that do not fit in the queue are shed immediately rather
+/// than left to pile up.
+pub struct RequestQueue {
+    // Permits for requests that are either waiting or being handled.
+    admission: Arc<Semaphore>,
+    // Permits for requests being handled.
+    concurrency: Arc<Semaphore>,
+}
+
+impl RequestQueue {
+    #[must_use]
+    pub fn new(config: RequestQueueConfig) -> Self {
+        let RequestQueueConfig {
+            max_concurrent_requests,
+            max_queued_requests,
+        } = config;
+
+        Self {
+            admission: Arc::new(Semaphore::new(
+                max_concurrent_requests.get() + max_queued_requests,
+            )),
+            concurrency: Arc::new(Semaphore::new(max_concurrent_requests.get())),
+        }
+    }
+
+    // Returns `None` if the queue is full.
+    pub(crate) fn try_enter(&self) -> Option<QueuedRequest> {
+        let admission = self.admission.clone().try_acquire_owned().ok()?;
+
+        Some(QueuedRequest {
+            admission,
+            concurrency: self.concurrency.clone(),
+        })
+    }
+}
+
+pub(crate) struct QueuedRequest {
+    admission: OwnedSemaphorePermit,
+    concurrency: Arc<Semaphore>,
+}
+
+impl QueuedRequest {
+    // The returned permits must be held until the request has been handled.
+    pub(crate) async fn wait_for_turn(self) -> (OwnedSemaphorePermit, OwnedSemaphorePermit) {
+        let Self {
+            admission,
+            concurrency,
+        } = self;
+
+        let permit = concurrency
+            .acquire_owned()
+            .await
+            .expect("RequestQueue never closes its semaphores");
+
+        (admission, permit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::FutureExt as _;
+
+    use super::*;
+
+    #[test]
+    fn request_queue_sheds_requests_once_full() {
+        let queue = RequestQueue::new(RequestQueueConfig {
+            max_concurrent_requests: NonZeroUsize::new(1).expect("1 is nonzero"),
+            max_queued_requests: 1,
+        });
+
+        let first = queue
+            .try_enter()
+            .expect("queue should be empty")
+            .wait_for_turn()
+            .now_or_never()
+            .expect("first request should be handled immediately");
+
+        let mut second = Box::pin(
+            queue
+                .try_enter()
+                .expect("second request should fit in the queue")
+                .wait_for_turn(),
+        );
+
+        assert!((&mut second).now_or_never().is_none());
+        assert!(queue.try_enter().is_none());
+
+        drop(first);
+
+        let second = second
+            .now_or_never()
+            .expect("second request should be handled after the first one");
+
+        assert!(queue.try_enter().is_some());
+
+        drop(second);
+    }
+}
@@ -108,35 +108,68 @@ impl KeystoreManager {
         self.persistence_config
             .validate_storage_password_presence()?;
 
-        let signer_keys = self
-            .signer
-            .read()
-            .await
-            .keys_with_origin()
-            .collect::<HashMap<_, _>>();
+        // Hold the write lock until slashing protection data has been exported.
+        // Signing takes a read lock, so this waits for in-flight signing to finish
+        // and prevents deleted keys from signing anything missing from the export.
+        let mut signer = self.signer.write().await;
+
+        let signer_keys = signer.keys_with_origin().collect::<HashMap<_, _>>();
         let mut deleted_keys = vec![];
+        let mut exported_keys = vec![];
 
-        let statuses = {
-            let mut signer = self.signer.write().await;
-
-            pubkeys
-                .iter()
-                .copied()
-                .map(|pubkey| match signer_keys.get(&pubkey) {
-                    Some(origin) => match origin {
-                        KeyOrigin::KeymanagerAPI => {
-                            signer.delete_key(pubkey);
-                            deleted_keys.push(pubkey);
-                            Status::Deleted.into()
-                        }
-                        KeyOrigin::LocalFileSystem | KeyOrigin::Web3Signer => {
-                            Error::ReadOnly.into()
-                        }
-                    },
-                    None => Error::NotFound.into(),
+        let statuses = pubkeys
+            .into_iter()
+            .map(|pubkey| {
+                let status = match signer_keys.get(&pubkey) {
+                    Some(KeyOrigin::KeymanagerAPI) => {
+                        signer.delete_key(pubkey);
+                        deleted_keys.push(pubkey);
+                        exported_keys.push(pubkey);
+                        Some(Status::Deleted.into())
+                    }
+                    Some(KeyOrigin::LocalFileSystem | KeyOrigin::Web3Signer) => {
+                        Some(Error::ReadOnly.into())
+                    }
+                    None => {
+                        exported_keys.push(pubkey);
+                        None
+                    }
+                };
+
+                (pubkey, status)
+            })
+            .collect_vec();
+
+        let slashing_protection = self
+            .slashing_protector
+            .lock()
+            .await
+            .build_interchange_data_for_validators(
+                self.genesis_validators_root,
+                exported_keys.iter().copied(),
+            )?;
+
+        drop(signer);
+
+        // Keys missing from the signer are reported as `not_active` if there is
+        // slashing protection data for them and `not_found` otherwise.
+        let statuses = statuses
+            .into_iter()
+            .map(|(pubkey, status)| {
+                status.unwrap_or_else(|| {
+                    let has_slashing_protection = slashing_protection
+                        .data
+                        .iter()
+                        .any(|data| data.pubkey == pubkey);
+
+                    if has_slashing_protection {
+                        Status::NotActive.into()
+                    } else {
+                        Status::NotFound.into()
+                    }
                 })
-                .collect()
-        };
+            })
+            .collect();
 
         if !deleted_keys.is_empty() {
             let mut key_storage = self.key_storage_mut().await?;
@@ -144,12 +177,6 @@ impl KeystoreManager {
             self.persist_key_storage(&key_storage).await?;
         }
 
-        let slashing_protection = self
-            .slashing_protector
-            .lock()
-            .await
-            .build_interchange_data_for_validators(self.genesis_validators_root, pubkeys)?;
-
         Ok((statuses, serde_json::to_string(&slashing_protection)?))
     }
 
@@ -547,6 +574,7 @@ mod tests {
         let signer = Arc::new(RwLock::new(Signer::new(
             vec![],
             Client::new(),
+            Client::new(),
             Web3SignerConfig::default(),
             None,
         )));
@@ -619,8 +647,9 @@ mod tests {
             }],
         );
 
+        // Imported keys are not used for signing until the validator activates them.
         assert_eq!(
-            signer.read().await.keys().copied().collect_vec(),
+            signer.read().await.pending_keys().copied().collect_vec(),
             vec![expected_pubkey],
         );
 
@@ -695,8 +724,8 @@ mod tests {
                     message: None,
                 },
                 OperationStatus {
-                    status: Status::Error,
-                    message: Some("key not found".into())
+                    status: Status::NotFound,
+                    message: None,
                 },
             ],
         );
@@ -755,8 +784,9 @@ mod tests {
             }],
         );
 
+        // Imported keys are not used for signing until the validator activates them.
         assert_eq!(
-            signer.read().await.keys().copied().collect_vec(),
+            signer.read().await.pending_keys().copied().collect_vec(),
             vec![expected_pubkey],
         );
 
@@ -822,8 +852,8 @@ mod tests {
                     message: None,
                 },
                 OperationStatus {
-                    status: Status::Error,
-                    message: Some("key not found".into())
+                    status: Status::NotFound,
+                    message: None,
                 },
             ],
         );
@@ -839,6 +869,26 @@ mod tests {
             expected_interchange.data.iter().sorted().collect_vec(),
         );
 
+        // Test repeated delete of a key with slashing protection data
+
+        let (delete_statuses, exported_interchange) = manager.delete(vec![expected_pubkey]).await?;
+
+        assert_eq!(
+            delete_statuses,
+            vec![OperationStatus {
+                status: Status::NotActive,
+                message: None,
+            }],
+        );
+
+        let exported_interchange =
+            serde_json::from_str::<InterchangeFormat>(&exported_interchange)?;
+
+        assert_eq!(
+            exported_interchange.data.iter().sorted().collect_vec(),
+            expected_interchange.data.iter().sorted().collect_vec(),
+        );
+
         Ok(())
     }
 }
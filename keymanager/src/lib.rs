@@ -17,17 +17,21 @@ use std_ext::ArcExt as _;
 use tokio::sync::RwLock;
 use types::phase0::primitives::{ExecutionAddress, H256};
 
-use crate::{keystores::KeystoreManager, remote_keys::RemoteKeyManager};
+use crate::{
+    keystores::KeystoreManager, remote_keys::RemoteKeyManager, web3signer_proxy::Web3SignerProxy,
+};
 
 mod keystores;
 mod misc;
 mod proposer_configs;
 mod remote_keys;
+mod web3signer_proxy;
 
 pub struct KeyManager {
     proposer_configs: Arc<ProposerConfigs>,
     keystores: KeystoreManager,
     remote_keys: RemoteKeyManager,
+    web3signer_proxy: Option<Web3SignerProxy>,
 }
 
 impl KeyManager {
@@ -37,6 +41,7 @@ impl KeyManager {
         genesis_validators_root: H256,
         default_fee_recipient: ExecutionAddress,
         default_graffiti: H256,
+        web3signer_proxy: bool,
     ) -> Self {
         let proposer_configs = Arc::new(ProposerConfigs::new_in_memory(
             default_fee_recipient,
@@ -49,12 +54,21 @@ impl KeyManager {
             genesis_validators_root,
         );
 
+        let web3signer_proxy = web3signer_proxy.then(|| {
+            Web3SignerProxy::new(
+                signer.clone_arc(),
+                slashing_protector.clone_arc(),
+                genesis_validators_root,
+            )
+        });
+
         let remote_keys_manager = RemoteKeyManager::new(signer, slashing_protector);
 
         Self {
             proposer_configs,
             keystores: keystore_manager,
             remote_keys: remote_keys_manager,
+            web3signer_proxy,
         }
     }
 
@@ -66,6 +80,7 @@ impl KeyManager {
         keystore_storage_password_path: Option<&Path>,
         default_fee_recipient: ExecutionAddress,
         default_graffiti: H256,
+        web3signer_proxy: bool,
     ) -> Result<Self> {
         let proposer_configs = Arc::new(ProposerConfigs::new_persistent(
             &validator_directory,
@@ -81,12 +96,21 @@ impl KeyManager {
             keystore_storage_password_path,
         )?;
 
+        let web3signer_proxy = web3signer_proxy.then(|| {
+            Web3SignerProxy::new(
+                signer.clone_arc(),
+                slashing_protector.clone_arc(),
+                genesis_validators_root,
+            )
+        });
+
         let remote_keys_manager = RemoteKeyManager::new(signer, slashing_protector);
 
         Ok(Self {
             proposer_configs,
             keystores: keystore_manager,
             remote_keys: remote_keys_manager,
+            web3signer_proxy,
         })
     }
 
@@ -104,4 +128,11 @@ impl KeyManager {
     pub const fn remote_keys(&self) -> &RemoteKeyManager {
         &self.remote_keys
     }
+
+    /// Present if keystore operations should be forwarded to Web3Signer instances
+    /// instead of being performed locally.
+    #[must_use]
+    pub const fn web3signer_proxy(&self) -> Option<&Web3SignerProxy> {
+        self.web3signer_proxy.as_ref()
+    }
 }
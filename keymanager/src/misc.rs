@@ -1,6 +1,6 @@
 use anyhow::Error as AnyhowError;
 use bls::PublicKeyBytes;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use types::phase0::primitives::H256;
 
@@ -18,21 +18,27 @@ pub enum Error {
     NotFound,
     #[error("number of passwords does not match number of keystores")]
     PasswordCountMismatch,
+    #[error("keystore does not contain a public key")]
+    MissingPubkey,
+    #[error("no Web3Signer instances are available")]
+    NoWeb3Signers,
     #[error("key is read-only")]
     ReadOnly,
     #[error("password for decrypting keystores is missing, run Grandine with --keystore-storage-password-file to provide it")]
     StoragePasswordNotProvided,
 }
 
-#[derive(Debug, PartialEq, Eq, Serialize)]
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize)]
 #[serde(rename_all = "snake_case")]
 pub enum Status {
     Deleted,
     Error,
     Imported,
+    NotActive,
+    NotFound,
 }
 
-#[derive(Debug, PartialEq, Eq, Serialize)]
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub struct OperationStatus {
     pub status: Status,
     pub message: Option<String>,
@@ -1,4 +1,7 @@
-use core::fmt::Display;
+use core::{
+    fmt::Display,
+    sync::atomic::{AtomicU64, Ordering},
+};
 use std::{path::Path, str};
 
 use anyhow::{ensure, Result};
@@ -21,6 +24,7 @@ pub struct ProposerConfigs {
     database: Database,
     default_fee_recipient: ExecutionAddress,
     default_graffiti: H256,
+    registration_version: AtomicU64,
 }
 
 impl ProposerConfigs {
@@ -32,6 +36,7 @@ impl ProposerConfigs {
             database,
             default_fee_recipient,
             default_graffiti,
+            registration_version: AtomicU64::default(),
         }
     }
 
@@ -46,9 +51,18 @@ impl ProposerConfigs {
             database,
             default_fee_recipient,
             default_graffiti,
+            registration_version: AtomicU64::default(),
         })
     }
 
+    /// Returns a counter incremented whenever a fee recipient or gas limit changes.
+    ///
+    /// Validator registrations contain both, so they need to be resubmitted when this changes.
+    #[must_use]
+    pub fn registration_version(&self) -> u64 {
+        self.registration_version.load(Ordering::Acquire)
+    }
+
     pub fn fee_recipient(&self, pubkey: PublicKeyBytes) -> Result<ExecutionAddress> {
         let fee_recipient = self.db_get(FeeRecipientByPubkey(pubkey))?;
 
@@ -60,13 +74,20 @@ impl ProposerConfigs {
         pubkey: PublicKeyBytes,
         fee_recipient: ExecutionAddress,
     ) -> Result<()> {
-        self.db_put(FeeRecipientByPubkey(pubkey), &fee_recipient)
+        self.db_put(FeeRecipientByPubkey(pubkey), &fee_recipient)?;
+        self.increment_registration_version();
+        Ok(())
     }
 
     pub fn delete_fee_recipient(&self, pubkey: PublicKeyBytes) -> Result<()> {
-        self.db_remove(FeeRecipientByPubkey(pubkey))
+        self.db_remove(FeeRecipientByPubkey(pubkey))?;
+        self.increment_registration_version();
+        Ok(())
     }
 
+    /// Returns the gas limit to use in validator registrations and payload attributes.
+    ///
+    /// Defaults to [`PREFERRED_EXECUTION_GAS_LIMIT`] for validators without one set.
     pub fn gas_limit(&self, pubkey: PublicKeyBytes) -> Result<Gas> {
         let gas_limit = self.db_get(GasLimitByPubkey(pubkey))?;
 
@@ -74,11 +95,15 @@ impl ProposerConfigs {
     }
 
     pub fn set_gas_limit(&self, pubkey: PublicKeyBytes, gas_limit: Gas) -> Result<()> {
-        self.db_put(GasLimitByPubkey(pubkey), &gas_limit)
+        self.db_put(GasLimitByPubkey(pubkey), &gas_limit)?;
+        self.increment_registration_version();
+        Ok(())
     }
 
     pub fn delete_gas_limit(&self, pubkey: PublicKeyBytes) -> Result<()> {
-        self.db_remove(GasLimitByPubkey(pubkey))
+        self.db_remove(GasLimitByPubkey(pubkey))?;
+        self.increment_registration_version();
+        Ok(())
     }
 
     pub fn graffiti_bytes(&self, pubkey: PublicKeyBytes) -> Result<Option<H256>> {
@@ -103,6 +128,10 @@ impl ProposerConfigs {
         self.db_remove(GraffitiByPubkey(pubkey))
     }
 
+    fn increment_registration_version(&self) {
+        self.registration_version.fetch_add(1, Ordering::AcqRel);
+    }
+
     fn db_get<V: DeserializeOwned>(&self, key: impl Display) -> Result<Option<V>> {
         let key_string = key.to_string();
 
@@ -258,6 +287,27 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_registration_version_changes_with_gas_limit() -> Result<()> {
+        let proposer_configs = build_proposer_configs(None)?;
+
+        assert_eq!(proposer_configs.registration_version(), 0);
+
+        proposer_configs.set_gas_limit(PUBKEY, 12345)?;
+
+        assert_eq!(proposer_configs.registration_version(), 1);
+
+        proposer_configs.delete_gas_limit(PUBKEY)?;
+
+        assert_eq!(proposer_configs.registration_version(), 2);
+
+        proposer_configs.set_graffiti(PUBKEY, "graffiti")?;
+
+        assert_eq!(proposer_configs.registration_version(), 2);
+
+        Ok(())
+    }
+
     #[test]
     fn test_get_graffiti_when_graffiti_is_not_set() -> Result<()> {
         let proposer_configs = build_proposer_configs(None)?;
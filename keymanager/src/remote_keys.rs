@@ -135,6 +135,7 @@ mod tests {
                 KeyOrigin::LocalFileSystem,
             )],
             Client::new(),
+            Client::new(),
             Web3SignerConfig::default(),
             None,
         )))
@@ -176,8 +177,14 @@ mod tests {
         );
 
         assert_eq!(
-            signer.read().await.keys().copied().sorted().collect_vec(),
-            [PUBKEY_REMOTE, PUBKEY_LOCAL],
+            signer.read().await.keys().copied().collect_vec(),
+            [PUBKEY_LOCAL],
+        );
+
+        // Imported keys are not used for signing until the validator activates them.
+        assert_eq!(
+            signer.read().await.pending_keys().copied().collect_vec(),
+            [PUBKEY_REMOTE],
         );
 
         assert_eq!(
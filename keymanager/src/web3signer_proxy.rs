@@ -0,0 +1,741 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+use anyhow::{anyhow, ensure, Error as AnyhowError, Result};
+use bls::PublicKeyBytes;
+use eip_2335::Keystore;
+use futures::{future, lock::Mutex};
+use itertools::Itertools as _;
+use log::{info, warn};
+use reqwest::{Client, Url};
+use serde::{Deserialize, Serialize};
+use signer::Signer;
+use slashing_protection::{
+    interchange_format::{InterchangeData, InterchangeFormat},
+    SlashingProtector,
+};
+use tokio::sync::RwLock;
+use types::phase0::primitives::H256;
+use zeroize::Zeroizing;
+
+use crate::misc::{Error, OperationStatus, Status, ValidatingPubkey};
+
+const KEYSTORES_PATH: &str = "/eth/v1/keystores";
+
+#[derive(Deserialize)]
+struct ListResponse {
+    data: Vec<ListedKeystore>,
+}
+
+#[derive(Deserialize)]
+struct ListedKeystore {
+    validating_pubkey: PublicKeyBytes,
+    readonly: bool,
+}
+
+#[derive(Serialize)]
+struct ImportRequest<'keystore> {
+    keystores: Vec<&'keystore str>,
+    passwords: Vec<&'keystore str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    slashing_protection: Option<&'keystore str>,
+}
+
+#[derive(Deserialize)]
+struct ImportResponse {
+    data: Vec<OperationStatus>,
+}
+
+#[derive(Serialize)]
+struct DeleteRequest {
+    pubkeys: Vec<PublicKeyBytes>,
+}
+
+#[derive(Deserialize)]
+struct DeleteResponse {
+    data: Vec<OperationStatus>,
+    slashing_protection: String,
+}
+
+/// Serves `/eth/v1/keystores` by forwarding requests to the keymanager APIs of the configured
+/// Web3Signer instances.
+///
+/// Listings from all instances are merged. Keys being deleted are routed to the instance that
+/// holds them. Keystores being imported are routed to the instance that already holds the key or
+/// the one with the fewest keys otherwise. Imported keys are used for signing through the instance
+/// they were imported into.
+///
+/// Slashing protection data returned for deleted keys combines the records of the Web3Signer
+/// instances with the ones in the local slashing protection database.
+pub struct Web3SignerProxy {
+    signer: Arc<RwLock<Signer>>,
+    slashing_protector: Arc<Mutex<SlashingProtector>>,
+    genesis_validators_root: H256,
+}
+
+impl Web3SignerProxy {
+    #[must_use]
+    pub const fn new(
+        signer: Arc<RwLock<Signer>>,
+        slashing_protector: Arc<Mutex<SlashingProtector>>,
+        genesis_validators_root: H256,
+    ) -> Self {
+        Self {
+            signer,
+            slashing_protector,
+            genesis_validators_root,
+        }
+    }
+
+    pub async fn list(&self) -> Vec<ValidatingPubkey> {
+        self.list_by_url()
+            .await
+            .into_iter()
+            .flat_map(|(url, keystores)| {
+                keystores.into_iter().map(move |keystore| ValidatingPubkey {
+                    validating_pubkey: keystore.validating_pubkey,
+                    url: Some(url.to_string()),
+                    readonly: keystore.readonly,
+                })
+            })
+            .collect()
+    }
+
+    pub async fn import(
+        &self,
+        keystores: &[String],
+        passwords: &[Zeroizing<String>],
+        slashing_protection: Option<&str>,
+    ) -> Result<Vec<OperationStatus>> {
+        ensure!(
+            keystores.len() == passwords.len(),
+            Error::PasswordCountMismatch,
+        );
+
+        if let Some(slashing_protection) = slashing_protection {
+            self.import_slashing_protection_data(serde_json::from_str(slashing_protection)?)
+                .await?;
+        }
+
+        let listings = self.list_by_url().await;
+
+        ensure!(!listings.is_empty(), Error::NoWeb3Signers);
+
+        let mut key_counts = listings
+            .iter()
+            .map(|(url, keystores)| (url.clone(), keystores.len()))
+            .collect::<HashMap<_, _>>();
+
+        let owners = owners(&listings);
+
+        let mut statuses = keystores.iter().map(|_| None).collect_vec();
+        let mut indices_by_url = HashMap::<Url, Vec<usize>>::new();
+        let mut pubkeys = HashMap::new();
+
+        for (index, keystore) in keystores.iter().enumerate() {
+            let pubkey = match serde_json::from_str::<Keystore>(keystore) {
+                Ok(keystore) => keystore.pubkey(),
+                Err(error) => {
+                    statuses[index] = Some(AnyhowError::new(error).into());
+                    continue;
+                }
+            };
+
+            let Some(pubkey) = pubkey else {
+                statuses[index] = Some(Error::MissingPubkey.into());
+                continue;
+            };
+
+            let url = match owners.get(&pubkey) {
+                Some(url) => (*url).clone(),
+                None => {
+                    let (url, count) = key_counts
+                        .iter_mut()
+                        .min_by_key(|(_, count)| **count)
+                        .expect("listings are not empty");
+
+                    *count += 1;
+                    url.clone()
+                }
+            };
+
+            pubkeys.insert(index, pubkey);
+            indices_by_url.entry(url).or_default().push(index);
+        }
+
+        let client = self.client().await;
+        let mut imported_keys = vec![];
+
+        for (url, indices) in indices_by_url {
+            let request = ImportRequest {
+                keystores: indices
+                    .iter()
+                    .map(|index| keystores[*index].as_str())
+                    .collect(),
+                passwords: indices
+                    .iter()
+                    .map(|index| passwords[*index].as_str())
+                    .collect(),
+                slashing_protection,
+            };
+
+            let result = async {
+                client
+                    .post(url.join(KEYSTORES_PATH)?)
+                    .json(&request)
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json::<ImportResponse>()
+                    .await
+                    .map_err(AnyhowError::from)
+            }
+            .await;
+
+            match result {
+                Ok(response) => {
+                    for (index, status) in indices.into_iter().zip(response.data) {
+                        if status.status == Status::Imported {
+                            imported_keys.push((pubkeys[&index], url.clone()));
+                        }
+
+                        statuses[index] = Some(status);
+                    }
+                }
+                Err(error) => {
+                    warn!("failed to import keystores into Web3Signer at {url}: {error:?}");
+
+                    for index in indices {
+                        statuses[index] = Some(anyhow!("{error}").into());
+                    }
+                }
+            }
+        }
+
+        if !imported_keys.is_empty() {
+            self.slashing_protector
+                .lock()
+                .await
+                .register_validators(imported_keys.iter().map(|(pubkey, _)| *pubkey))?;
+
+            let mut signer = self.signer.write().await;
+
+            for (pubkey, url) in imported_keys {
+                signer.append_remote_key(pubkey, url);
+            }
+        }
+
+        Ok(statuses
+            .into_iter()
+            .map(|status| status.unwrap_or_else(|| anyhow!("Web3Signer returned no status").into()))
+            .collect())
+    }
+
+    pub async fn delete(
+        &self,
+        pubkeys: &[PublicKeyBytes],
+    ) -> Result<(Vec<OperationStatus>, String)> {
+        let listings = self.list_by_url().await;
+        let owners = owners(&listings);
+
+        let mut statuses = pubkeys.iter().map(|_| None).collect_vec();
+        let mut indices_by_url = HashMap::<Url, Vec<usize>>::new();
+
+        for (index, pubkey) in pubkeys.iter().enumerate() {
+            if let Some(url) = owners.get(pubkey) {
+                indices_by_url
+                    .entry((*url).clone())
+                    .or_default()
+                    .push(index);
+            }
+        }
+
+        // Stop signing with the keys before slashing protection data is exported by Web3Signer.
+        {
+            let mut signer = self.signer.write().await;
+
+            for indices in indices_by_url.values() {
+                for index in indices {
+                    signer.delete_key(pubkeys[*index]);
+                }
+            }
+        }
+
+        // Own messages are recorded locally whether or not Web3Signer protects them too.
+        let local_slashing_protection = self
+            .slashing_protector
+            .lock()
+            .await
+            .build_interchange_data_for_validators(
+                self.genesis_validators_root,
+                pubkeys.iter().copied(),
+            )?;
+
+        // Keys missing from all instances are reported as `not_active` if there is
+        // slashing protection data for them and `not_found` otherwise.
+        for (index, pubkey) in pubkeys.iter().enumerate() {
+            if !owners.contains_key(pubkey) {
+                let has_slashing_protection = local_slashing_protection
+                    .data
+                    .iter()
+                    .any(|data| data.pubkey == *pubkey);
+
+                statuses[index] = Some(if has_slashing_protection {
+                    Status::NotActive.into()
+                } else {
+                    Status::NotFound.into()
+                });
+            }
+        }
+
+        let client = self.client().await;
+        let mut interchange_data = local_slashing_protection.data;
+
+        for (url, indices) in indices_by_url {
+            let request = DeleteRequest {
+                pubkeys: indices.iter().map(|index| pubkeys[*index]).collect(),
+            };
+
+            let result = async {
+                let response = client
+                    .delete(url.join(KEYSTORES_PATH)?)
+                    .json(&request)
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json::<DeleteResponse>()
+                    .await?;
+
+                let slashing_protection =
+                    serde_json::from_str::<InterchangeFormat>(&response.slashing_protection)?;
+
+                Ok::<_, AnyhowError>((response.data, slashing_protection))
+            }
+            .await;
+
+            match result {
+                Ok((response_statuses, slashing_protection)) => {
+                    for (index, status) in indices.into_iter().zip(response_statuses) {
+                        statuses[index] = Some(status);
+                    }
+
+                    interchange_data.extend(slashing_protection.data);
+                }
+                Err(error) => {
+                    warn!("failed to delete keystores from Web3Signer at {url}: {error:?}");
+
+                    for index in indices {
+                        statuses[index] = Some(anyhow!("{error}").into());
+                    }
+                }
+            }
+        }
+
+        let slashing_protection = InterchangeFormat::new(
+            self.genesis_validators_root,
+            merge_interchange_data(interchange_data),
+        );
+
+        let statuses = statuses
+            .into_iter()
+            .map(|status| status.unwrap_or_else(|| anyhow!("Web3Signer returned no status").into()))
+            .collect();
+
+        Ok((statuses, serde_json::to_string(&slashing_protection)?))
+    }
+
+    async fn client(&self) -> Client {
+        self.signer.read().await.web3signer().client().clone()
+    }
+
+    // Instances that cannot be reached are left out.
+    // Failing the whole request would make it impossible to manage the remaining ones.
+    async fn list_by_url(&self) -> Vec<(Url, Vec<ListedKeystore>)> {
+        let (client, urls) = {
+            let signer = self.signer.read().await;
+            let web3signer = signer.web3signer();
+            (web3signer.client().clone(), web3signer.urls().to_vec())
+        };
+
+        let results = future::join_all(urls.into_iter().map(|url| {
+            let client = &client;
+
+            async move {
+                let result = async {
+                    client
+                        .get(url.join(KEYSTORES_PATH)?)
+                        .send()
+                        .await?
+                        .error_for_status()?
+                        .json::<ListResponse>()
+                        .await
+                        .map_err(AnyhowError::from)
+                }
+                .await;
+
+                (url, result)
+            }
+        }))
+        .await;
+
+        results
+            .into_iter()
+            .filter_map(|(url, result)| match result {
+                Ok(response) => Some((url, response.data)),
+                Err(error) => {
+                    warn!("failed to list keystores in Web3Signer at {url}: {error:?}");
+                    None
+                }
+            })
+            .collect()
+    }
+
+    async fn import_slashing_protection_data(
+        &self,
+        slashing_protection: InterchangeFormat,
+    ) -> Result<()> {
+        slashing_protection.validate(self.genesis_validators_root)?;
+
+        let import_report = self
+            .slashing_protector
+            .lock()
+            .await
+            .import(slashing_protection)?;
+
+        info!(
+            "slashing protection data imported (imported records: {}, failed records: {})",
+            import_report.imported_records(),
+            import_report.failed_records(),
+        );
+
+        Ok(())
+    }
+}
+
+// Records for the same key from different sources are combined into one entry.
+fn merge_interchange_data(
+    interchange_data: impl IntoIterator<Item = InterchangeData>,
+) -> Vec<InterchangeData> {
+    let mut merged = BTreeMap::<PublicKeyBytes, InterchangeData>::new();
+
+    for data in interchange_data {
+        let merged_data = merged
+            .entry(data.pubkey)
+            .or_insert_with(|| InterchangeData {
+                pubkey: data.pubkey,
+                signed_blocks: vec![],
+                signed_attestations: vec![],
+            });
+
+        merged_data.signed_blocks.extend(data.signed_blocks);
+        merged_data
+            .signed_attestations
+            .extend(data.signed_attestations);
+    }
+
+    merged
+        .into_values()
+        .map(|mut data| {
+            data.signed_blocks.sort();
+            data.signed_blocks.dedup();
+            data.signed_attestations.sort();
+            data.signed_attestations.dedup();
+            data
+        })
+        .collect()
+}
+
+fn owners(listings: &[(Url, Vec<ListedKeystore>)]) -> HashMap<PublicKeyBytes, &Url> {
+    listings
+        .iter()
+        .flat_map(|(url, keystores)| {
+            keystores
+                .iter()
+                .map(move |keystore| (keystore.validating_pubkey, url))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+    use std::time::Instant;
+
+    use hex_literal::hex;
+    use httpmock::{Method, MockServer};
+    use serde_json::json;
+    use signer::Web3SignerConfig;
+    use slashing_protection::{
+        interchange_format::InterchangeBlock, DEFAULT_SLASHING_PROTECTION_HISTORY_LIMIT,
+    };
+    use std_ext::ArcExt as _;
+
+    use super::*;
+
+    const PUBKEY_1: PublicKeyBytes = PublicKeyBytes(hex!(
+        "93247f2209abcacf57b75a51dafae777f9dd38bc7053d1af526f220a7489a6d3a2753e5f3e8b1cfe39b56f43611df74a"
+    ));
+    const PUBKEY_2: PublicKeyBytes = PublicKeyBytes(hex!(
+        "b53d21a4cfd562c469cc81514d4ce5a6b577d8403d32a394dc265dd190b47fa9f829fdd7963afdf972e5e77854051f6f"
+    ));
+    const PUBKEY_MISSING: PublicKeyBytes = PublicKeyBytes(hex!(
+        "b301803f8b5ac4a1133581fc676dfedc60d891dd5fa99028805e5ea5b08d3491af75d0707adab3b70c6a6a580217bf81"
+    ));
+
+    type Proxy = (
+        Web3SignerProxy,
+        Arc<RwLock<Signer>>,
+        Arc<Mutex<SlashingProtector>>,
+    );
+
+    fn build_proxy(urls: Vec<Url>) -> Result<Proxy> {
+        let signer = Arc::new(RwLock::new(Signer::new(
+            core::iter::empty(),
+            Client::new(),
+            Client::new(),
+            Web3SignerConfig {
+                urls,
+                ..Web3SignerConfig::default()
+            },
+            None,
+        )));
+
+        let slashing_protector = Arc::new(Mutex::new(SlashingProtector::in_memory(
+            DEFAULT_SLASHING_PROTECTION_HISTORY_LIMIT,
+        )?));
+
+        let proxy = Web3SignerProxy::new(
+            signer.clone_arc(),
+            slashing_protector.clone_arc(),
+            H256::zero(),
+        );
+
+        Ok((proxy, signer, slashing_protector))
+    }
+
+    fn mock_listing(server: &MockServer, pubkey: PublicKeyBytes) {
+        server.mock(|when, then| {
+            when.method(Method::GET).path(KEYSTORES_PATH);
+            then.status(200).body(
+                json!({ "data": [{ "validating_pubkey": pubkey, "readonly": false }] }).to_string(),
+            );
+        });
+    }
+
+    #[tokio::test]
+    async fn test_list_merges_keys_from_all_signers() -> Result<()> {
+        let server_1 = MockServer::start();
+        let server_2 = MockServer::start();
+
+        mock_listing(&server_1, PUBKEY_1);
+        mock_listing(&server_2, PUBKEY_2);
+
+        let url_1 = Url::parse(&server_1.url("/"))?;
+        let url_2 = Url::parse(&server_2.url("/"))?;
+
+        let (proxy, _, _) = build_proxy(vec![url_1.clone(), url_2.clone()])?;
+
+        assert_eq!(
+            proxy.list().await,
+            [
+                ValidatingPubkey {
+                    validating_pubkey: PUBKEY_1,
+                    url: Some(url_1.to_string()),
+                    readonly: false,
+                },
+                ValidatingPubkey {
+                    validating_pubkey: PUBKEY_2,
+                    url: Some(url_2.to_string()),
+                    readonly: false,
+                },
+            ],
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_delete_routes_keys_to_their_signers() -> Result<()> {
+        let server_1 = MockServer::start();
+        let server_2 = MockServer::start();
+
+        mock_listing(&server_1, PUBKEY_1);
+        mock_listing(&server_2, PUBKEY_2);
+
+        let delete_response = |pubkey| {
+            json!({
+                "data": [{ "status": "deleted", "message": null }],
+                "slashing_protection": serde_json::to_string(&json!({
+                    "metadata": {
+                        "interchange_format_version": "5",
+                        "genesis_validators_root": H256::zero(),
+                    },
+                    "data": [{
+                        "pubkey": pubkey,
+                        "signed_blocks": [{ "slot": "2" }],
+                        "signed_attestations": [],
+                    }],
+                }))
+                .expect("slashing protection data should serialize"),
+            })
+            .to_string()
+        };
+
+        let delete_mock_1 = server_1.mock(|when, then| {
+            when.method(Method::DELETE)
+                .path(KEYSTORES_PATH)
+                .json_body(json!({ "pubkeys": [PUBKEY_1] }));
+            then.status(200).body(delete_response(PUBKEY_1));
+        });
+
+        let delete_mock_2 = server_2.mock(|when, then| {
+            when.method(Method::DELETE)
+                .path(KEYSTORES_PATH)
+                .json_body(json!({ "pubkeys": [PUBKEY_2] }));
+            then.status(200).body(delete_response(PUBKEY_2));
+        });
+
+        let url_1 = Url::parse(&server_1.url("/"))?;
+        let url_2 = Url::parse(&server_2.url("/"))?;
+
+        let (proxy, signer, slashing_protector) = build_proxy(vec![url_1.clone(), url_2])?;
+
+        signer.write().await.append_remote_key(PUBKEY_1, url_1);
+
+        // Blocks signed through Web3Signer are also recorded locally.
+        slashing_protector
+            .lock()
+            .await
+            .import(InterchangeFormat::new(
+                H256::zero(),
+                vec![InterchangeData {
+                    pubkey: PUBKEY_1,
+                    signed_blocks: vec![block(1), block(2)],
+                    signed_attestations: vec![],
+                }],
+            ))?;
+
+        let (statuses, slashing_protection) =
+            proxy.delete(&[PUBKEY_2, PUBKEY_MISSING, PUBKEY_1]).await?;
+
+        delete_mock_1.assert();
+        delete_mock_2.assert();
+
+        assert_eq!(
+            statuses,
+            [
+                Status::Deleted.into(),
+                Status::NotFound.into(),
+                Status::Deleted.into(),
+            ],
+        );
+
+        let slashing_protection = serde_json::from_str::<InterchangeFormat>(&slashing_protection)?;
+
+        assert_eq!(
+            slashing_protection
+                .data
+                .iter()
+                .map(|data| (data.pubkey, data.signed_blocks.clone()))
+                .collect_vec(),
+            [
+                (PUBKEY_1, vec![block(1), block(2)]),
+                (PUBKEY_2, vec![block(2)]),
+            ]
+            .into_iter()
+            .sorted()
+            .collect_vec(),
+        );
+
+        assert!(signer
+            .read()
+            .await
+            .keys_with_origin()
+            .all(|(pubkey, _)| pubkey != PUBKEY_1));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_delete_reports_keys_only_known_locally_as_not_active() -> Result<()> {
+        let server = MockServer::start();
+
+        mock_listing(&server, PUBKEY_2);
+
+        let (proxy, _, slashing_protector) = build_proxy(vec![Url::parse(&server.url("/"))?])?;
+
+        slashing_protector
+            .lock()
+            .await
+            .import(InterchangeFormat::new(
+                H256::zero(),
+                vec![InterchangeData {
+                    pubkey: PUBKEY_1,
+                    signed_blocks: vec![block(1)],
+                    signed_attestations: vec![],
+                }],
+            ))?;
+
+        let (statuses, slashing_protection) = proxy.delete(&[PUBKEY_1, PUBKEY_MISSING]).await?;
+
+        assert_eq!(
+            statuses,
+            [Status::NotActive.into(), Status::NotFound.into()],
+        );
+
+        let slashing_protection = serde_json::from_str::<InterchangeFormat>(&slashing_protection)?;
+
+        assert_eq!(
+            slashing_protection
+                .data
+                .iter()
+                .map(|data| data.pubkey)
+                .collect_vec(),
+            [PUBKEY_1],
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_queries_signers_concurrently() -> Result<()> {
+        let delay = Duration::from_secs(1);
+        let servers = [
+            MockServer::start(),
+            MockServer::start(),
+            MockServer::start(),
+        ];
+
+        for server in &servers {
+            server.mock(|when, then| {
+                when.method(Method::GET).path(KEYSTORES_PATH);
+                then.status(200)
+                    .delay(delay)
+                    .body(json!({ "data": [] }).to_string());
+            });
+        }
+
+        let urls = servers
+            .iter()
+            .map(|server| Url::parse(&server.url("/")))
+            .collect::<Result<_, _>>()?;
+
+        let (proxy, _, _) = build_proxy(urls)?;
+
+        let started_at = Instant::now();
+
+        assert_eq!(proxy.list_by_url().await.len(), servers.len());
+        assert!(started_at.elapsed() < delay * 2);
+
+        Ok(())
+    }
+
+    const fn block(slot: u64) -> InterchangeBlock {
+        InterchangeBlock {
+            slot,
+            signing_root: None,
+        }
+    }
+}
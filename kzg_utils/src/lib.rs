@@ -8,4 +8,17 @@ mod trusted_setup;
 #[cfg(test)]
 mod spec_tests;
 
+#[cfg(not(any(feature = "arkworks", feature = "blst")))]
+compile_error! {
+    "a KZG backend must be enabled; \
+     pass --features kzg_utils/arkworks or --features kzg_utils/blst to Cargo"
+}
+
+#[cfg(feature = "arkworks")]
+pub type KZGSettings = rust_kzg_arkworks::kzg_types::LKZGSettings;
+
+#[cfg(all(feature = "blst", not(feature = "arkworks")))]
 pub type KZGSettings = rust_kzg_blst::types::kzg_settings::FsKZGSettings;
+
+#[cfg(all(feature = "arkworks", feature = "blst"))]
+use rust_kzg_blst as _;
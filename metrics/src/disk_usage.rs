@@ -0,0 +1,115 @@
+use core::time::Duration;
+use std::{sync::Arc, time::Instant};
+
+use anyhow::Result;
+use directories::Directories;
+use std_ext::ArcExt as _;
+use tokio::sync::Mutex;
+
+// Directory sizes are computed by walking every file in them, which takes a while for a large
+// database. Scrapes are usually more frequent than sizes change noticeably.
+const MIN_TIME_BETWEEN_DISK_USAGE_REFRESH: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DiskUsage {
+    pub store: u64,
+    pub network: u64,
+    pub validator: u64,
+}
+
+impl DiskUsage {
+    pub const fn total(self) -> u64 {
+        self.store + self.network + self.validator
+    }
+}
+
+/// Disk usage of the data directories, refreshed at most once per
+/// [`MIN_TIME_BETWEEN_DISK_USAGE_REFRESH`].
+pub struct DiskUsageCache {
+    directories: Arc<Directories>,
+    // The lock is held during refreshes so that concurrent scrapes do not walk directories twice.
+    cached: Mutex<Option<(Instant, DiskUsage)>>,
+}
+
+impl DiskUsageCache {
+    pub const fn new(directories: Arc<Directories>) -> Self {
+        Self {
+            directories,
+            cached: Mutex::const_new(None),
+        }
+    }
+
+    pub async fn get(&self) -> Result<DiskUsage> {
+        let mut cached = self.cached.lock().await;
+
+        if let Some((refreshed_at, disk_usage)) = *cached {
+            if refreshed_at.elapsed() < MIN_TIME_BETWEEN_DISK_USAGE_REFRESH {
+                return Ok(disk_usage);
+            }
+        }
+
+        let directories = self.directories.clone_arc();
+
+        let disk_usage = tokio::task::spawn_blocking(move || {
+            anyhow::Ok(DiskUsage {
+                store: directories.store_disk_usage()?,
+                network: directories.network_disk_usage()?,
+                validator: directories.validator_disk_usage()?,
+            })
+        })
+        .await??;
+
+        *cached = Some((Instant::now(), disk_usage));
+
+        Ok(disk_usage)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn disk_usage_is_not_recomputed_before_refresh_interval() -> Result<()> {
+        let data_dir = TempDir::new()?;
+        let store_directory = data_dir.path().join("beacon");
+        let network_dir = data_dir.path().join("network");
+        let validator_dir = data_dir.path().join("validator");
+
+        write_file(&store_directory, "a", 100)?;
+        write_file(&network_dir, "a", 10)?;
+        write_file(&validator_dir, "a", 1)?;
+
+        let cache = DiskUsageCache::new(Arc::new(Directories {
+            data_dir: Some(data_dir.path().to_owned()),
+            store_directory: Some(store_directory.clone()),
+            network_dir: Some(network_dir),
+            validator_dir: Some(validator_dir),
+        }));
+
+        let expected = DiskUsage {
+            store: 100,
+            network: 10,
+            validator: 1,
+        };
+
+        assert_eq!(cache.get().await?, expected);
+        assert_eq!(expected.total(), 111);
+
+        write_file(&store_directory, "b", 1000)?;
+
+        assert_eq!(cache.get().await?, expected);
+
+        Ok(())
+    }
+
+    fn write_file(directory: &Path, name: &str, size: usize) -> Result<()> {
+        fs_err::create_dir_all(directory)?;
+        fs_err::write(directory.join(name), vec![0; size])?;
+        Ok(())
+    }
+}
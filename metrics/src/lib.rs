@@ -5,6 +5,7 @@ pub use crate::{
 };
 
 mod beaconchain;
+mod disk_usage;
 mod gui;
 mod helpers;
 mod messages;
@@ -4,6 +4,7 @@ use core::time::Duration;
 use std::{
     net::{IpAddr, SocketAddr},
     sync::Arc,
+    time::Instant,
 };
 
 use anyhow::{anyhow, Error as AnyhowError, Result};
@@ -26,7 +27,7 @@ use prometheus_client::registry::Registry;
 use prometheus_metrics::Metrics;
 use std_ext::ArcExt as _;
 use thiserror::Error;
-use tower_http::cors::AllowOrigin;
+use tower_http::cors::{AllowMethods, AllowOrigin};
 use transition_functions::combined::Statistics;
 use types::{
     combined::BeaconState,
@@ -35,12 +36,13 @@ use types::{
     traits::BeaconState as _,
 };
 
-use crate::messages::MetricsToMetrics;
+use crate::{disk_usage::DiskUsageCache, messages::MetricsToMetrics};
 
 #[derive(Clone, Debug)]
 pub struct MetricsServerConfig {
     pub metrics_address: IpAddr,
     pub metrics_port: u16,
+    pub allow_origin: AllowOrigin,
     pub timeout: u64,
     pub directories: Arc<Directories>,
 }
@@ -54,7 +56,7 @@ impl From<&MetricsServerConfig> for SocketAddr {
 #[derive(Clone)]
 pub struct MetricsState<P: Preset, W: Wait> {
     pub controller: ApiController<P, W>,
-    pub directories: Arc<Directories>,
+    pub disk_usage_cache: Arc<DiskUsageCache>,
     pub libp2p_registry: Option<Arc<Registry>>,
     pub metrics: Arc<Metrics>,
     pub metrics_to_metrics_tx: Option<UnboundedSender<MetricsToMetrics>>, // TODO: is still relevant, update naming if so
@@ -67,9 +69,9 @@ impl<P: Preset, W: Wait> FromRef<MetricsState<P, W>> for ApiController<P, W> {
     }
 }
 
-impl<P: Preset, W: Wait> FromRef<MetricsState<P, W>> for Arc<Directories> {
+impl<P: Preset, W: Wait> FromRef<MetricsState<P, W>> for Arc<DiskUsageCache> {
     fn from_ref(state: &MetricsState<P, W>) -> Self {
-        state.directories.clone_arc()
+        state.disk_usage_cache.clone_arc()
     }
 }
 
@@ -101,11 +103,21 @@ impl<P: Preset, W: Wait> FromRef<MetricsState<P, W>> for Arc<NetworkGlobals> {
 pub enum Error {
     #[error("internal error")]
     Internal(#[from] AnyhowError),
+    #[error(
+        "task dumps are not supported by this build; \
+         build with RUSTFLAGS='--cfg tokio_unstable --cfg tokio_taskdump' on Linux to enable them"
+    )]
+    TaskDumpsNotSupported,
 }
 
 impl IntoResponse for Error {
     fn into_response(self) -> Response {
-        StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        match self {
+            Self::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+            Self::TaskDumpsNotSupported => {
+                (StatusCode::NOT_IMPLEMENTED, self.to_string()).into_response()
+            }
+        }
     }
 }
 
@@ -122,10 +134,10 @@ pub async fn run_metrics_server<P: Preset, W: Wait>(
 
     info!("Metrics server is listening on {addr}");
 
-    let directories = config.directories.clone_arc();
+    let disk_usage_cache = Arc::new(DiskUsageCache::new(config.directories.clone_arc()));
     let state = MetricsState {
         controller,
-        directories,
+        disk_usage_cache,
         libp2p_registry: libp2p_registry.map(Arc::new),
         metrics,
         metrics_to_metrics_tx,
@@ -134,12 +146,15 @@ pub async fn run_metrics_server<P: Preset, W: Wait>(
 
     let router = Router::new()
         .route("/metrics", get(prometheus_metrics))
+        .route("/debug/tasks", get(task_dump))
         .with_state(state);
 
     let router = http_api_utils::extend_router_with_middleware(
         router,
         Some(Duration::from_millis(config.timeout)),
-        AllowOrigin::any(),
+        config.allow_origin,
+        AllowMethods::default(),
+        None,
         None,
     );
 
@@ -152,7 +167,7 @@ pub async fn run_metrics_server<P: Preset, W: Wait>(
 /// `GET /metrics`
 pub async fn prometheus_metrics<P: Preset, W: Wait>(
     State(controller): State<ApiController<P, W>>,
-    State(directories): State<Arc<Directories>>,
+    State(disk_usage_cache): State<Arc<DiskUsageCache>>,
     State(libp2p_registry): State<Option<Arc<Registry>>>,
     State(metrics): State<Arc<Metrics>>,
     State(metrics_to_metrics_tx): State<Option<UnboundedSender<MetricsToMetrics>>>,
@@ -179,16 +194,15 @@ pub async fn prometheus_metrics<P: Preset, W: Wait>(
         warn!("Unable to scrape jemalloc stats: {error:?}");
     }
 
-    // Scrape disk usage
-    metrics.set_disk_usage(
-        directories
-            .disk_usage()
-            .map_err(|error| {
-                warn!("Unable to fetch Grandine disk usage: {error:?}");
-                error
-            })
-            .unwrap_or_default(),
-    );
+    match disk_usage_cache.get().await {
+        Ok(disk_usage) => {
+            metrics.set_disk_usage(disk_usage.total());
+            metrics.set_database_disk_usage(disk_usage.store);
+        }
+        Err(error) => warn!("Unable to fetch Grandine disk usage: {error:?}"),
+    }
+
+    scrape_process_stats(&metrics).await;
 
     let epoch = misc::compute_epoch_at_slot::<P>(controller.head().value.slot());
     // Take state at last slot in epoch
@@ -204,6 +218,31 @@ pub async fn prometheus_metrics<P: Preset, W: Wait>(
     Ok(buffer)
 }
 
+/// `GET /debug/tasks`
+///
+/// Returns backtraces of all tasks in the async runtime to help diagnose stalls.
+/// Tokio only supports this when built with `--cfg tokio_unstable --cfg tokio_taskdump`.
+/// Dumping tasks waits for all of them to yield, so it may take a while if the runtime is stalled.
+#[cfg(all(tokio_unstable, tokio_taskdump))]
+pub async fn task_dump() -> Result<String, Error> {
+    use core::fmt::Write as _;
+
+    let dump = tokio::runtime::Handle::current().dump().await;
+    let mut output = String::new();
+
+    for (index, task) in dump.tasks().iter().enumerate() {
+        writeln!(output, "task {index}:\n{}\n", task.trace()).map_err(AnyhowError::new)?;
+    }
+
+    Ok(output)
+}
+
+/// `GET /debug/tasks`
+#[cfg(not(all(tokio_unstable, tokio_taskdump)))]
+pub async fn task_dump() -> Result<String, Error> {
+    Err(Error::TaskDumpsNotSupported)
+}
+
 pub fn scrape_epoch_statistics<P: Preset>(
     state: &Arc<BeaconState<P>>,
     metrics: &Arc<Metrics>,
@@ -264,6 +303,22 @@ async fn scrape_system_stats(
     Ok(())
 }
 
+async fn scrape_process_stats(metrics: &Metrics) {
+    #[cfg(target_os = "linux")]
+    match fs_err::read_dir("/proc/self/fd") {
+        // The count includes the descriptor opened by `read_dir` itself.
+        Ok(entries) => metrics.set_open_file_descriptors(entries.count().saturating_sub(1)),
+        Err(error) => warn!("Unable to count open file descriptors: {error:?}"),
+    }
+
+    // Tokio does not expose worker utilization without `tokio_unstable`.
+    // The time it takes for a yielding task to be polled again is a cheap substitute.
+    // It grows when all workers are busy or blocked by synchronous code.
+    let start = Instant::now();
+    tokio::task::yield_now().await;
+    metrics.observe_runtime_scheduling_delay(start.elapsed());
+}
+
 fn scrape_jemalloc_stats(metrics: &Arc<Metrics>) -> Result<()> {
     jemalloc_ctl::epoch::advance().map_err(AnyhowError::msg)?;
 
@@ -1,14 +1,15 @@
-use core::marker::PhantomData;
+use core::{cmp::Reverse, marker::PhantomData, time::Duration};
 use std::{
     cmp::min,
-    collections::{btree_map::BTreeMap, HashMap},
+    collections::{btree_map::BTreeMap, BinaryHeap, HashMap},
     sync::Arc,
     time::Instant,
 };
 
 use anyhow::{anyhow, bail, Context, Result};
 use bit_field::BitField as _;
-use clock::Tick;
+use bls::AggregateSignature;
+use clock::Clock;
 use good_lp::{
     default_solver, solvers::highs::highs, solvers::highs::HighsParallelType, variable, variables,
     Expression, Solution, SolverModel,
@@ -57,7 +58,9 @@ pub struct AttestationPacker<P: Preset> {
     state: Arc<BeaconState<P>>,
     previous_epoch_participation: Vec<ParticipationFlags>,
     current_epoch_participation: Vec<ParticipationFlags>,
-    ignore_deadline: bool,
+    // Packing stops at the start of the next slot according to this clock.
+    // There is no deadline if this is `None`.
+    deadline_clock: Option<Arc<dyn Clock>>,
     phantom: PhantomData<P>,
 }
 
@@ -66,7 +69,7 @@ impl<P: Preset> AttestationPacker<P> {
         config: Arc<Config>,
         head_block_root: H256,
         state: Arc<BeaconState<P>>,
-        ignore_deadline: bool,
+        deadline_clock: Option<Arc<dyn Clock>>,
     ) -> Result<Self> {
         let previous_epoch_participation =
             compute_epoch_participation(&state, AttestationEpoch::Previous)?;
@@ -80,128 +83,139 @@ impl<P: Preset> AttestationPacker<P> {
             state,
             previous_epoch_participation,
             current_epoch_participation,
-            ignore_deadline,
+            deadline_clock,
             phantom: PhantomData,
         })
     }
 
+    // Packing runs during block production, so it must never take long enough to delay the
+    // proposal. Once `time_budget` runs out, the remaining candidates are packed as they are.
     pub fn pack_proposable_attestations_greedily<'a>(
         &self,
         previous_epoch_aggregates: impl IntoIterator<Item = &'a Attestation<P>>,
         current_epoch_aggregates: impl IntoIterator<Item = &'a Attestation<P>>,
+        time_budget: Duration,
     ) -> PackOutcome<P> {
         let start_time = Instant::now();
 
+        let time_budget_exhausted =
+            || self.deadline_reached() || start_time.elapsed() >= time_budget;
+
         let mut previous_epoch_participation = self.previous_epoch_participation.clone();
         let mut current_epoch_participation = self.current_epoch_participation.clone();
 
-        // TODO(Grandine Team): Storing candidates in a map allows for quick lookups during
-        //                      aggregation without having to manage an index, but prevents the
-        //                      algorithm from packing multiple aggregates with the same
-        //                      `AttestationData`.
-
         // Use `BTreeMap` to make attestation packing deterministic for snapshot testing.
-        let mut candidates = BTreeMap::new();
+        let mut candidates_by_data = BTreeMap::<_, Vec<_>>::new();
 
-        // In general it may be possible to construct better aggregates out of smaller ones, but
-        // they must not overlap because aggregating a signature with itself is not idempotent and
-        // would require keeping track of aggregation counts rather than bits.
-        candidates.extend(
-            current_epoch_aggregates
-                .into_iter()
-                .chain(previous_epoch_aggregates)
-                .take_while(|_| !self.deadline_reached())
-                .filter(|aggregate| self.is_valid_for_inclusion(aggregate))
-                .map(|aggregate| {
-                    let added_weight = self
-                        .added_weight(
-                            aggregate,
-                            &previous_epoch_participation,
-                            &current_epoch_participation,
-                        )
-                        .unwrap_or_default();
-                    (aggregate, added_weight)
-                })
-                .filter(|(_, added_weight)| {
-                    // Filtering aggregates this way early should have no effect on rewards, but it
-                    // may speed up block processing by producing smaller aggregates later.
-                    *added_weight > 0
-                })
-                .into_grouping_map_by(|(aggregate, _)| aggregate.data)
-                .max_by_key(|_, (_, added_weight)| *added_weight)
-                .into_values()
-                .map(|(aggregate, _)| (aggregate.data, aggregate.clone())),
-        );
+        for aggregate in current_epoch_aggregates
+            .into_iter()
+            .chain(previous_epoch_aggregates)
+            .take_while(|_| !self.deadline_reached())
+            .filter(|aggregate| self.is_valid_for_inclusion(aggregate))
+        {
+            let added_weight = self
+                .added_weight(
+                    aggregate,
+                    &previous_epoch_participation,
+                    &current_epoch_participation,
+                )
+                .unwrap_or_default();
+
+            // Filtering aggregates this way early should have no effect on rewards, but it
+            // may speed up block processing by producing smaller aggregates later.
+            if added_weight > 0 {
+                candidates_by_data
+                    .entry(aggregate.data)
+                    .or_default()
+                    .push(aggregate);
+            }
+        }
 
-        let mut candidates = candidates.into_values().collect_vec();
+        let candidates = candidates_by_data
+            .into_values()
+            .flat_map(|aggregates| {
+                if time_budget_exhausted() {
+                    aggregates.into_iter().cloned().collect_vec()
+                } else {
+                    merge_compatible_aggregates(aggregates)
+                }
+            })
+            .collect_vec();
+
+        // Picking the best attestations is a variation of the maximum coverage problem, which is
+        // NP-hard. See <https://en.wikipedia.org/wiki/Maximum_coverage_problem>.
+        // Repeatedly picking the attestation that adds the most weight is a (1 - 1/e)
+        // approximation. Added weights can only decrease as attestations are packed, so weights
+        // computed earlier are upper bounds and only the top candidate has to be re-evaluated.
+        let mut queue = candidates
+            .iter()
+            .enumerate()
+            .map(|(index, candidate)| {
+                let added_weight = self
+                    .added_weight(
+                        candidate,
+                        &previous_epoch_participation,
+                        &current_epoch_participation,
+                    )
+                    .unwrap_or_default();
 
-        // Picking the best attestations is a variation of the set packing problem, which is
-        // NP-complete. See:
-        // - <https://en.wikipedia.org/wiki/Set_packing>
-        // - <https://cstheory.stackexchange.com/questions/21448/set-packing-with-maximum-coverage-objective>
-        // We use a greedy algorithm.
-        let attestations = core::iter::from_fn(move || {
-            let attestation = candidates.pop()?;
+                (added_weight, Reverse(index))
+            })
+            .collect::<BinaryHeap<_>>();
 
-            self.add_attestation(
-                &attestation,
-                &mut previous_epoch_participation,
-                &mut current_epoch_participation,
-            )
-            .unwrap_or_default()
-            .then_some(attestation)
-        })
-        .take(P::MaxAttestations::USIZE)
-        .pipe(ContiguousList::try_from_iter)
-        .expect(
-            "the call to Iterator::take limits the number \
-             of attestations to P::MaxAttestations::USIZE",
+        let mut attestations = vec![];
+
+        while attestations.len() < P::MaxAttestations::USIZE {
+            let Some((previous_weight, Reverse(index))) = queue.pop() else {
+                break;
+            };
+
+            let candidate = &candidates[index];
+
+            // If time runs out, pack the remaining candidates in order of their last known
+            // weights without re-evaluating them.
+            if !time_budget_exhausted() {
+                let added_weight = self
+                    .added_weight(
+                        candidate,
+                        &previous_epoch_participation,
+                        &current_epoch_participation,
+                    )
+                    .unwrap_or_default();
+
+                if added_weight == 0 {
+                    continue;
+                }
+
+                let outweighed = queue
+                    .peek()
+                    .is_some_and(|(next_weight, _)| *next_weight > added_weight);
+
+                if added_weight < previous_weight && outweighed {
+                    queue.push((added_weight, Reverse(index)));
+                    continue;
+                }
+            }
+
+            let added_participation = self
+                .add_attestation(
+                    candidate,
+                    &mut previous_epoch_participation,
+                    &mut current_epoch_participation,
+                )
+                .unwrap_or_default();
+
+            if added_participation {
+                attestations.push(candidate.clone());
+            }
+        }
+
+        let attestations = ContiguousList::try_from_iter(attestations).expect(
+            "the loop above limits the number of attestations to P::MaxAttestations::USIZE",
         );
 
-        // Picking the best attestations is a variation of the set packing problem, which is
-        // NP-complete. See:
-        // - <https://en.wikipedia.org/wiki/Set_packing>
-        // - <https://cstheory.stackexchange.com/questions/21448/set-packing-with-maximum-coverage-objective>
-        // We use a greedy algorithm.
-        // let attestations = core::iter::from_fn(move || {
-        //     // If time runs out, pack attestations as is,
-        //     // without trying to find the best ones anymore.
-        //     if !self.deadline_reached() {
-        //         candidates.sort_by_cached_key(|attestation| {
-        //             self.added_weight(
-        //                 attestation,
-        //                 &previous_epoch_participation,
-        //                 &current_epoch_participation,
-        //             )
-        //             .ok()
-        //         });
-        //     }
-
-        //     let attestation = candidates.pop()?;
-
-        //     self.add_attestation(
-        //         &attestation,
-        //         &mut previous_epoch_participation,
-        //         &mut current_epoch_participation,
-        //     )
-        //     .unwrap_or_default()
-        //     .then_some(attestation)
-        // })
-        // .take(P::MaxAttestations::USIZE)
-        // .pipe(ContiguousList::try_from_iter)
-        // .expect(
-        //     "the call to Iterator::take limits the number \
-        //      of attestations to P::MaxAttestations::USIZE",
-        // );
+        let elapsed_time = start_time.elapsed();
 
-        let end_time = Instant::now();
-        let elapsed_time = end_time.duration_since(start_time);
-        //println!(
-        //    "Greedy packing took: {}.{:03} seconds and deadline_reached() value is: {}",
-        //    elapsed_time.as_secs(),
-        //    elapsed_time.subsec_millis(),
-        //    self.deadline_reached()
-        //);
         info!(
             "Greedy packing took: {}.{:03} seconds and deadline_reached() value is: {}",
             elapsed_time.as_secs(),
@@ -748,11 +762,11 @@ impl<P: Preset> AttestationPacker<P> {
     }
 
     fn deadline_reached(&self) -> bool {
-        if self.ignore_deadline {
+        let Some(clock) = self.deadline_clock.as_ref() else {
             return false;
-        }
+        };
 
-        let result = Tick::current(&self.config, self.state.genesis_time());
+        let result = clock.current_tick(&self.config, self.state.genesis_time());
 
         let Ok(tick) = result else {
             return true;
@@ -807,6 +821,44 @@ fn compute_epoch_participation<P: Preset>(
     }
 }
 
+// Aggregates with the same `AttestationData` can be combined if their aggregation bits are
+// disjoint. Overlapping aggregates cannot be combined because aggregating a signature with itself
+// is not idempotent.
+fn merge_compatible_aggregates<P: Preset>(
+    mut aggregates: Vec<&Attestation<P>>,
+) -> Vec<Attestation<P>> {
+    aggregates.sort_by_key(|aggregate| Reverse(aggregate.aggregation_bits.count_ones()));
+
+    let mut merged = Vec::<Attestation<P>>::with_capacity(aggregates.len());
+
+    for aggregate in aggregates {
+        let merged_into_existing = merged
+            .iter_mut()
+            .filter(|merged| {
+                !merged
+                    .aggregation_bits
+                    .any_in_common(&aggregate.aggregation_bits)
+            })
+            .any(|merged| merge_aggregate(merged, aggregate).is_ok());
+
+        if !merged_into_existing {
+            merged.push(aggregate.clone());
+        }
+    }
+
+    merged
+}
+
+fn merge_aggregate<P: Preset>(target: &mut Attestation<P>, source: &Attestation<P>) -> Result<()> {
+    let mut signature = AggregateSignature::try_from(target.signature)?;
+    signature.aggregate_in_place(source.signature.try_into()?);
+
+    target.aggregation_bits |= &source.aggregation_bits;
+    target.signature = signature.into();
+
+    Ok(())
+}
+
 fn translate_participation<'attestations, P: Preset>(
     state: &Phase0BeaconState<P>,
     pending_attestations: impl IntoIterator<Item = &'attestations PendingAttestation<P>>,
@@ -842,7 +894,8 @@ fn translate_participation<'attestations, P: Preset>(
 mod tests {
     use std::collections::hash_map::{Entry as HashMapEntry, HashMap};
 
-    use eth2_cache_utils::{goerli, holesky};
+    use clock::{ManualClock, Tick};
+    use eth2_cache_utils::{goerli, holesky, mainnet};
     use ssz::BitList;
     use std_ext::ArcExt as _;
     use transition_functions::unphased;
@@ -926,11 +979,12 @@ mod tests {
             config.clone_arc(),
             latest_block_root,
             state.clone_arc(),
-            true,
+            None,
         )?;
         let pack_outcome = packer.pack_proposable_attestations_greedily(
             &previous_epoch_aggregates,
             &current_epoch_aggregates,
+            Duration::MAX,
         );
         //println!(
         //    "Greedy algorithm goerli reward: {}",
@@ -970,7 +1024,7 @@ mod tests {
             config.clone_arc(),
             latest_block_root,
             state.clone_arc(),
-            true,
+            None,
         )?;
         let pack_outcome = packer.pack_proposable_attestations_dynamically(
             &previous_epoch_aggregates,
@@ -1014,12 +1068,13 @@ mod tests {
             config.clone_arc(),
             latest_block_root,
             state.clone_arc(),
-            true,
+            None,
         )?;
 
         let pack_outcome = packer.pack_proposable_attestations_greedily(
             &previous_epoch_aggregates,
             &current_epoch_aggregates,
+            Duration::MAX,
         );
         let end_time = Instant::now();
         let elapsed_time = end_time.duration_since(start_time);
@@ -1047,6 +1102,43 @@ mod tests {
         assert_attestations_are_valid_and_add_new_bits(&config, &state, &proposable_attestations)
     }
 
+    #[test]
+    #[cfg(feature = "eth2-cache")]
+    fn test_holesky_aggregate_attestation_packing_without_time_budget() -> Result<()> {
+        let config = Arc::new(Config::holesky());
+        let slot = 50_015;
+        let epoch = misc::compute_epoch_at_slot::<Mainnet>(slot);
+        let state = holesky::beacon_state(slot, 8);
+        let latest_block_root = accessors::latest_block_root(&state);
+
+        let previous_epoch_aggregates = holesky::aggregate_attestations_by_epoch(epoch - 1);
+        let current_epoch_aggregates = holesky::aggregate_attestations_by_epoch(epoch);
+
+        let _unused = accessors::initialize_shuffled_indices(&state, &previous_epoch_aggregates);
+        let _unused = accessors::initialize_shuffled_indices(&state, &current_epoch_aggregates);
+
+        let packer = AttestationPacker::new(
+            config.clone_arc(),
+            latest_block_root,
+            state.clone_arc(),
+            None,
+        )?;
+
+        // With no time budget left, candidates are packed without merging or re-evaluating them.
+        let pack_outcome = packer.pack_proposable_attestations_greedily(
+            &previous_epoch_aggregates,
+            &current_epoch_aggregates,
+            Duration::ZERO,
+        );
+
+        let proposable_attestations = pack_outcome.attestations;
+
+        assert!(!pack_outcome.deadline_reached);
+        assert!(!proposable_attestations.is_empty());
+
+        assert_attestations_are_valid_and_add_new_bits(&config, &state, &proposable_attestations)
+    }
+
     #[test]
     #[cfg(feature = "eth2-cache")]
     fn test_holesky_dynamic_aggregate_attestation_packing() -> Result<()> {
@@ -1067,7 +1159,7 @@ mod tests {
             config.clone_arc(),
             latest_block_root,
             state.clone_arc(),
-            true,
+            None,
         )?;
 
         let pack_outcome = packer.pack_proposable_attestations_dynamically(
@@ -1132,4 +1224,36 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    #[cfg(feature = "eth2-cache")]
+    fn deadline_is_reached_at_start_of_slot_according_to_clock() -> Result<()> {
+        let config = Arc::new(Config::mainnet());
+        let state = mainnet::GENESIS_BEACON_STATE.force().clone_arc();
+        let clock = Arc::new(ManualClock::new(Tick::start_of_slot(1)));
+        let deadline_clock: Arc<dyn Clock> = clock.clone_arc();
+
+        let packer = AttestationPacker::new(
+            config.clone_arc(),
+            H256::zero(),
+            state.clone_arc(),
+            Some(deadline_clock),
+        )?;
+
+        assert!(packer.deadline_reached());
+
+        clock.advance_tick()?;
+
+        assert!(!packer.deadline_reached());
+
+        clock.advance_slot()?;
+
+        assert!(packer.deadline_reached());
+
+        let packer = AttestationPacker::new(config, H256::zero(), state, None)?;
+
+        assert!(!packer.deadline_reached());
+
+        Ok(())
+    }
 }
@@ -1,12 +1,15 @@
+use core::time::Duration;
 use std::sync::Arc;
 
 use anyhow::{Context, Error, Result};
 use bls::PublicKeyBytes;
-use clock::{Tick, TickKind};
+use clock::{Clock, Tick, TickKind};
 use dedicated_executor::DedicatedExecutor;
 use eth1_api::ApiController;
 use features::Feature;
 use fork_choice_control::Wait;
+use futures::stream::{FuturesOrdered, TryStreamExt as _};
+use log::warn;
 use prometheus_metrics::Metrics;
 use ssz::ContiguousList;
 use std_ext::ArcExt as _;
@@ -25,31 +28,39 @@ use crate::{
         pool::Pool,
         tasks::{
             BestProposableAttestationsTask, ComputeProposerIndicesTask, InsertAttestationTask,
-            PackProposableAttestationsTask, SetRegisteredValidatorsTask,
+            PackProposableAttestationsTask, PruneShardTask, SetRegisteredValidatorsTask,
+            ShardAggregatesByEpochTask, ShardSingularAttestationsByEpochTask,
         },
     },
+    config::PoolConfig,
     misc::PoolTask,
 };
 
 pub struct Manager<P: Preset, W: Wait> {
     controller: ApiController<P, W>,
+    clock: Arc<dyn Clock>,
     dedicated_executor: Arc<DedicatedExecutor>,
     metrics: Option<Arc<Metrics>>,
     pool: Arc<Pool<P>>,
+    packing_time_budget: Duration,
 }
 
 impl<P: Preset, W: Wait> Manager<P, W> {
     #[must_use]
     pub fn new(
         controller: ApiController<P, W>,
+        clock: Arc<dyn Clock>,
         dedicated_executor: Arc<DedicatedExecutor>,
         metrics: Option<Arc<Metrics>>,
+        pool_config: PoolConfig,
     ) -> Arc<Self> {
         Arc::new(Self {
             controller,
+            clock,
             dedicated_executor,
             metrics,
-            pool: Arc::new(Pool::default()),
+            pool: Arc::new(Pool::new(pool_config.max_aggregates_per_attestation_data)),
+            packing_time_budget: pool_config.attestation_packing_time_budget,
         })
     }
 
@@ -64,6 +75,23 @@ impl<P: Preset, W: Wait> Manager<P, W> {
         match kind {
             TickKind::Propose => {
                 self.pool.on_slot(slot).await;
+
+                let finalized_epoch = self.controller.finalized_epoch();
+
+                let result = self
+                    .spawn_per_shard(|pool, shard_index| PruneShardTask {
+                        pool,
+                        shard_index,
+                        slot,
+                        finalized_epoch,
+                    })
+                    .await;
+
+                if let Err(error) = result {
+                    warn!("failed to prune attestation aggregation pool: {error:?}");
+                }
+
+                self.track_collection_metrics().await;
             }
             TickKind::Attest => {
                 self.pool.clear_best_proposable_attestations().await;
@@ -84,8 +112,19 @@ impl<P: Preset, W: Wait> Manager<P, W> {
         }
     }
 
-    pub async fn aggregate_attestations_by_epoch(&self, epoch: Epoch) -> Vec<Attestation<P>> {
-        self.pool.aggregate_attestations_by_epoch(epoch).await
+    pub async fn aggregate_attestations_by_epoch(
+        &self,
+        epoch: Epoch,
+    ) -> Result<Vec<Attestation<P>>> {
+        let attestations = self
+            .spawn_per_shard(|pool, shard_index| ShardAggregatesByEpochTask {
+                pool,
+                shard_index,
+                epoch,
+            })
+            .await?;
+
+        Ok(attestations.into_iter().flatten().collect())
     }
 
     pub async fn best_aggregate_attestation(
@@ -113,6 +152,7 @@ impl<P: Preset, W: Wait> Manager<P, W> {
             controller: self.controller.clone_arc(),
             pool: self.pool.clone_arc(),
             beacon_state,
+            packing_time_budget: self.packing_time_budget,
         })
         .await
     }
@@ -137,6 +177,7 @@ impl<P: Preset, W: Wait> Manager<P, W> {
         self.spawn_detached(PackProposableAttestationsTask {
             pool: self.pool.clone_arc(),
             controller: self.controller.clone_arc(),
+            clock: self.clock.clone_arc(),
             metrics: self.metrics.clone(),
         });
     }
@@ -149,8 +190,33 @@ impl<P: Preset, W: Wait> Manager<P, W> {
         });
     }
 
-    pub async fn singular_attestations_by_epoch(&self, epoch: Epoch) -> Vec<Arc<Attestation<P>>> {
-        self.pool.singular_attestations_by_epoch(epoch).await
+    pub async fn singular_attestations_by_epoch(
+        &self,
+        epoch: Epoch,
+    ) -> Result<Vec<Arc<Attestation<P>>>> {
+        let attestations = self
+            .spawn_per_shard(|pool, shard_index| ShardSingularAttestationsByEpochTask {
+                pool,
+                shard_index,
+                epoch,
+            })
+            .await?;
+
+        Ok(attestations.into_iter().flatten().collect())
+    }
+
+    async fn track_collection_metrics(&self) {
+        if let Some(metrics) = self.metrics.as_ref() {
+            metrics.set_collection_length(
+                &["AttestationAggPool", "aggregates"],
+                self.pool.aggregate_count().await,
+            );
+
+            metrics.set_collection_length(
+                &["AttestationAggPool", "singular_attestations"],
+                self.pool.singular_attestation_count().await,
+            );
+        }
     }
 
     async fn spawn_task<T: PoolTask>(&self, task: T) -> Result<T::Output> {
@@ -161,6 +227,18 @@ impl<P: Preset, W: Wait> Manager<P, W> {
             .context("attestation aggregation pool task failed")?
     }
 
+    // Each shard is handled by a task of its own so that the work can run on multiple threads.
+    async fn spawn_per_shard<T: PoolTask>(
+        &self,
+        task: impl Fn(Arc<Pool<P>>, usize) -> T,
+    ) -> Result<Vec<T::Output>> {
+        (0..self.pool.shard_count())
+            .map(|shard_index| self.spawn_task(task(self.pool.clone_arc(), shard_index)))
+            .collect::<FuturesOrdered<_>>()
+            .try_collect()
+            .await
+    }
+
     fn spawn_detached(&self, task: impl PoolTask) {
         self.dedicated_executor.spawn(task.run()).detach()
     }
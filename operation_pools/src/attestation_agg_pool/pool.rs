@@ -30,53 +30,113 @@ use crate::attestation_agg_pool::{
 #[allow(type_alias_bounds)]
 type AttestationsWithSlot<P: Preset> = (ContiguousList<Attestation<P>, P::MaxAttestations>, Slot);
 
-#[derive(Default)]
 pub struct Pool<P: Preset> {
-    aggregates: RwLock<BTreeMap<Epoch, AggregateMap<P>>>,
-    data_root_to_data_map: RwLock<BTreeMap<Epoch, HashMap<H256, AttestationData>>>,
-    // The type of the inner map does not affect the result of attestation packing,
-    // though that may change if the packers are redesigned again.
-    singular_attestations: RwLock<BTreeMap<Epoch, AttestationMap<P>>>,
+    max_aggregates_per_data: usize,
+    // Attestations are sharded by committee index so that insertions for different committees
+    // do not contend for the same locks. This matters on nodes subscribed to all subnets.
+    shards: Box<[Shard<P>]>,
     best_proposable_attestations: Mutex<AttestationsWithSlot<P>>,
     proposer_indices: RwLock<BTreeMap<Slot, ValidatorIndex>>,
     registered_validator_indices: RwLock<HashSet<ValidatorIndex>>,
 }
 
 impl<P: Preset> Pool<P> {
+    #[must_use]
+    pub fn new(max_aggregates_per_data: usize) -> Self {
+        // Committee indices are always lower than `MAX_COMMITTEES_PER_SLOT`,
+        // so every committee in a slot gets a shard of its own.
+        let shard_count = usize::try_from(P::MAX_COMMITTEES_PER_SLOT.get())
+            .expect("MAX_COMMITTEES_PER_SLOT should fit in usize");
+
+        let shards = core::iter::repeat_with(Shard::default)
+            .take(shard_count)
+            .collect();
+
+        Self {
+            max_aggregates_per_data,
+            shards,
+            best_proposable_attestations: Mutex::default(),
+            proposer_indices: RwLock::default(),
+            registered_validator_indices: RwLock::default(),
+        }
+    }
+
+    #[must_use]
+    pub const fn max_aggregates_per_data(&self) -> usize {
+        self.max_aggregates_per_data
+    }
+
+    #[must_use]
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
     pub async fn on_slot(&self, slot: Slot) {
+        let mut proposer_indices = self.proposer_indices.write().await;
+        *proposer_indices = proposer_indices.split_off(&slot);
+    }
+
+    // Shards are pruned separately so that the work can be spread across worker tasks.
+    // Attestations must have the current or previous justified checkpoint as their source,
+    // so ones with a source older than the finalized checkpoint can never be included.
+    pub async fn prune_shard(&self, shard_index: usize, slot: Slot, finalized_epoch: Epoch) {
+        let shard = &self.shards[shard_index];
+
         if misc::is_epoch_start::<P>(slot) {
             let current_epoch = misc::compute_epoch_at_slot::<P>(slot);
             let previous_epoch = current_epoch.saturating_sub(1).max(GENESIS_EPOCH);
 
-            let mut aggregates = self.aggregates.write().await;
-            *aggregates = aggregates.split_off(&previous_epoch);
+            shard.on_epoch(previous_epoch).await;
+        }
+
+        shard.prune_finalized(finalized_epoch).await;
+    }
+
+    pub async fn aggregate_count(&self) -> usize {
+        let mut count = 0;
+
+        for shard in &*self.shards {
+            count += shard.aggregate_count().await;
+        }
 
-            let mut data_root_to_data_map = self.data_root_to_data_map.write().await;
-            *data_root_to_data_map = data_root_to_data_map.split_off(&previous_epoch);
+        count
+    }
 
-            let mut singular_attestations = self.singular_attestations.write().await;
-            *singular_attestations = singular_attestations.split_off(&previous_epoch);
+    pub async fn singular_attestation_count(&self) -> usize {
+        let mut count = 0;
+
+        for shard in &*self.shards {
+            count += shard.singular_attestation_count().await;
         }
 
-        let mut proposer_indices = self.proposer_indices.write().await;
-        *proposer_indices = proposer_indices.split_off(&slot);
+        count
     }
 
     pub async fn add_data_root_to_data_entry(&self, data: AttestationData) {
+        let shard = self.shard(data);
+        let epoch = data.target.epoch;
         let root = data.hash_tree_root();
 
-        self.data_root_to_data_map
+        // Most attestations share data with ones already in the pool.
+        // Checking first avoids taking the write lock for every insertion.
+        if shard.data_by_root(root, epoch).await.is_some() {
+            return;
+        }
+
+        shard
+            .data_root_to_data_map
             .write()
             .await
-            .entry(data.target.epoch)
+            .entry(epoch)
             .or_default()
             .insert(root, data);
     }
 
     pub async fn aggregates(&self, data: AttestationData) -> Arc<Mutex<Vec<Aggregate<P>>>> {
+        let shard = self.shard(data);
         let epoch = data.target.epoch;
 
-        if let Some(aggregates) = self
+        if let Some(aggregates) = shard
             .aggregates
             .read()
             .await
@@ -86,7 +146,8 @@ impl<P: Preset> Pool<P> {
             return aggregates.clone_arc();
         }
 
-        self.aggregates
+        shard
+            .aggregates
             .write()
             .await
             .entry(epoch)
@@ -125,32 +186,9 @@ impl<P: Preset> Pool<P> {
     }
 
     pub async fn aggregate_attestations_by_epoch(&self, epoch: Epoch) -> Vec<Attestation<P>> {
-        self.aggregates
-            .read()
-            .await
-            .get(&epoch)
-            .into_iter()
-            .flatten()
-            .map(|(data, aggregates)| async {
-                aggregates
-                    .lock()
-                    .await
-                    .iter()
-                    .cloned()
-                    .map(|aggregate| {
-                        let Aggregate {
-                            aggregation_bits,
-                            signature,
-                        } = aggregate;
-
-                        Attestation {
-                            aggregation_bits,
-                            data: *data,
-                            signature: signature.into(),
-                        }
-                    })
-                    .collect_vec()
-            })
+        self.shards
+            .iter()
+            .map(|shard| shard.aggregate_attestations_by_epoch(epoch))
             .collect::<FuturesUnordered<_>>()
             .collect::<Vec<_>>()
             .await
@@ -159,6 +197,16 @@ impl<P: Preset> Pool<P> {
             .collect_vec()
     }
 
+    pub async fn shard_aggregate_attestations_by_epoch(
+        &self,
+        shard_index: usize,
+        epoch: Epoch,
+    ) -> Vec<Attestation<P>> {
+        self.shards[shard_index]
+            .aggregate_attestations_by_epoch(epoch)
+            .await
+    }
+
     pub async fn best_aggregate_attestation(
         &self,
         data: AttestationData,
@@ -166,6 +214,7 @@ impl<P: Preset> Pool<P> {
         let epoch = data.target.epoch;
 
         if let Some(aggregates) = self
+            .shard(data)
             .aggregates
             .read()
             .await
@@ -200,14 +249,11 @@ impl<P: Preset> Pool<P> {
         attestation_data_root: H256,
         epoch: Epoch,
     ) -> Option<Attestation<P>> {
-        if let Some(data) = self
-            .data_root_to_data_map
-            .read()
-            .await
-            .get(&epoch)
-            .and_then(|data_map| data_map.get(&attestation_data_root))
-        {
-            return self.best_aggregate_attestation(*data).await;
+        // The root does not identify the committee, so every shard has to be checked.
+        for shard in &*self.shards {
+            if let Some(data) = shard.data_by_root(attestation_data_root, epoch).await {
+                return self.best_aggregate_attestation(data).await;
+            }
         }
 
         self.aggregate_attestations_by_epoch(epoch)
@@ -298,9 +344,10 @@ impl<P: Preset> Pool<P> {
         &self,
         data: AttestationData,
     ) -> Arc<RwLock<AttestationSet<P>>> {
+        let shard = self.shard(data);
         let epoch = data.target.epoch;
 
-        if let Some(attestations) = self
+        if let Some(attestations) = shard
             .singular_attestations
             .read()
             .await
@@ -310,7 +357,8 @@ impl<P: Preset> Pool<P> {
             return attestations.clone_arc();
         }
 
-        self.singular_attestations
+        shard
+            .singular_attestations
             .write()
             .await
             .entry(epoch)
@@ -321,13 +369,9 @@ impl<P: Preset> Pool<P> {
     }
 
     pub async fn singular_attestations_by_epoch(&self, epoch: Epoch) -> Vec<Arc<Attestation<P>>> {
-        self.singular_attestations
-            .read()
-            .await
-            .get(&epoch)
-            .into_iter()
-            .flatten()
-            .map(|(_, attestations)| async { attestations.read().await.clone() })
+        self.shards
+            .iter()
+            .map(|shard| shard.singular_attestations_by_epoch(epoch))
             .collect::<FuturesUnordered<_>>()
             .collect::<Vec<_>>()
             .await
@@ -336,6 +380,16 @@ impl<P: Preset> Pool<P> {
             .collect_vec()
     }
 
+    pub async fn shard_singular_attestations_by_epoch(
+        &self,
+        shard_index: usize,
+        epoch: Epoch,
+    ) -> Vec<Arc<Attestation<P>>> {
+        self.shards[shard_index]
+            .singular_attestations_by_epoch(epoch)
+            .await
+    }
+
     async fn has_precomputed_proposer_indices_in_slots(
         &self,
         range: impl RangeBounds<Slot> + Send,
@@ -347,4 +401,262 @@ impl<P: Preset> Pool<P> {
             .next()
             .is_some()
     }
+
+    fn shard(&self, data: AttestationData) -> &Shard<P> {
+        let index = usize::try_from(data.index).unwrap_or(usize::MAX) % self.shards.len();
+        &self.shards[index]
+    }
+}
+
+#[derive(Default)]
+struct Shard<P: Preset> {
+    aggregates: RwLock<BTreeMap<Epoch, AggregateMap<P>>>,
+    data_root_to_data_map: RwLock<BTreeMap<Epoch, HashMap<H256, AttestationData>>>,
+    // The type of the inner map does not affect the result of attestation packing,
+    // though that may change if the packers are redesigned again.
+    singular_attestations: RwLock<BTreeMap<Epoch, AttestationMap<P>>>,
+}
+
+impl<P: Preset> Shard<P> {
+    async fn on_epoch(&self, previous_epoch: Epoch) {
+        let mut aggregates = self.aggregates.write().await;
+        *aggregates = aggregates.split_off(&previous_epoch);
+        drop(aggregates);
+
+        let mut data_root_to_data_map = self.data_root_to_data_map.write().await;
+        *data_root_to_data_map = data_root_to_data_map.split_off(&previous_epoch);
+        drop(data_root_to_data_map);
+
+        let mut singular_attestations = self.singular_attestations.write().await;
+        *singular_attestations = singular_attestations.split_off(&previous_epoch);
+    }
+
+    async fn prune_finalized(&self, finalized_epoch: Epoch) {
+        for epoch_aggregates in self.aggregates.write().await.values_mut() {
+            epoch_aggregates.retain(|data, _| data.source.epoch >= finalized_epoch);
+        }
+
+        for epoch_attestations in self.singular_attestations.write().await.values_mut() {
+            epoch_attestations.retain(|data, _| data.source.epoch >= finalized_epoch);
+        }
+
+        for data_map in self.data_root_to_data_map.write().await.values_mut() {
+            data_map.retain(|_, data| data.source.epoch >= finalized_epoch);
+        }
+    }
+
+    async fn aggregate_count(&self) -> usize {
+        let aggregates = self.aggregates.read().await;
+        let mut count = 0;
+
+        for aggregates in aggregates.values().flat_map(HashMap::values) {
+            count += aggregates.lock().await.len();
+        }
+
+        count
+    }
+
+    async fn singular_attestation_count(&self) -> usize {
+        let singular_attestations = self.singular_attestations.read().await;
+        let mut count = 0;
+
+        for attestations in singular_attestations.values().flat_map(HashMap::values) {
+            count += attestations.read().await.len();
+        }
+
+        count
+    }
+
+    async fn data_by_root(
+        &self,
+        attestation_data_root: H256,
+        epoch: Epoch,
+    ) -> Option<AttestationData> {
+        self.data_root_to_data_map
+            .read()
+            .await
+            .get(&epoch)
+            .and_then(|data_map| data_map.get(&attestation_data_root))
+            .copied()
+    }
+
+    async fn aggregate_attestations_by_epoch(&self, epoch: Epoch) -> Vec<Attestation<P>> {
+        self.aggregates
+            .read()
+            .await
+            .get(&epoch)
+            .into_iter()
+            .flatten()
+            .map(|(data, aggregates)| async {
+                aggregates
+                    .lock()
+                    .await
+                    .iter()
+                    .cloned()
+                    .map(|aggregate| {
+                        let Aggregate {
+                            aggregation_bits,
+                            signature,
+                        } = aggregate;
+
+                        Attestation {
+                            aggregation_bits,
+                            data: *data,
+                            signature: signature.into(),
+                        }
+                    })
+                    .collect_vec()
+            })
+            .collect::<FuturesUnordered<_>>()
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .flatten()
+            .collect_vec()
+    }
+
+    async fn singular_attestations_by_epoch(&self, epoch: Epoch) -> Vec<Arc<Attestation<P>>> {
+        self.singular_attestations
+            .read()
+            .await
+            .get(&epoch)
+            .into_iter()
+            .flatten()
+            .map(|(_, attestations)| async { attestations.read().await.clone() })
+            .collect::<FuturesUnordered<_>>()
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .flatten()
+            .collect_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ssz::BitList;
+    use types::{
+        phase0::{containers::Checkpoint, primitives::CommitteeIndex},
+        preset::Minimal,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn attestations_are_split_between_shards_by_committee_index() {
+        let pool = Pool::<Minimal>::new(1);
+
+        assert_eq!(pool.shard_count(), 4);
+
+        for index in 0..4 {
+            insert_aggregate(&pool, attestation_data(index, 0, 0)).await;
+        }
+
+        for shard_index in 0..pool.shard_count() {
+            let attestations = pool
+                .shard_aggregate_attestations_by_epoch(shard_index, 0)
+                .await;
+
+            assert_eq!(committee_indices(&attestations), [shard_index as u64]);
+        }
+
+        let attestations = pool.aggregate_attestations_by_epoch(0).await;
+
+        assert_eq!(committee_indices(&attestations), [0, 1, 2, 3]);
+        assert_eq!(pool.aggregate_count().await, 4);
+    }
+
+    #[tokio::test]
+    async fn pruning_a_shard_at_the_start_of_an_epoch_only_affects_that_shard() {
+        let pool = Pool::<Minimal>::new(1);
+
+        insert_aggregate(&pool, attestation_data(0, 0, 0)).await;
+        insert_aggregate(&pool, attestation_data(1, 0, 0)).await;
+
+        let start_of_epoch_2 = misc::compute_start_slot_at_epoch::<Minimal>(2);
+
+        pool.prune_shard(0, start_of_epoch_2, 0).await;
+
+        assert!(pool
+            .shard_aggregate_attestations_by_epoch(0, 0)
+            .await
+            .is_empty());
+
+        let attestations = pool.aggregate_attestations_by_epoch(0).await;
+
+        assert_eq!(committee_indices(&attestations), [1]);
+    }
+
+    #[tokio::test]
+    async fn pruning_a_shard_removes_attestations_with_finalized_sources() {
+        let pool = Pool::<Minimal>::new(1);
+
+        insert_aggregate(&pool, attestation_data(0, 1, 0)).await;
+        insert_aggregate(&pool, attestation_data(0, 1, 1)).await;
+
+        // Pruning in the middle of an epoch should only remove attestations by source.
+        pool.prune_shard(0, misc::compute_start_slot_at_epoch::<Minimal>(1) + 1, 1)
+            .await;
+
+        let attestations = pool.aggregate_attestations_by_epoch(1).await;
+
+        assert_eq!(attestations.len(), 1);
+        assert_eq!(attestations[0].data.source.epoch, 1);
+    }
+
+    #[tokio::test]
+    async fn best_aggregate_attestation_by_data_root_finds_data_in_any_shard() {
+        let pool = Pool::<Minimal>::new(1);
+        let data = attestation_data(3, 0, 0);
+
+        insert_aggregate(&pool, data).await;
+
+        let attestation = pool
+            .best_aggregate_attestation_by_data_root(data.hash_tree_root(), 0)
+            .await
+            .expect("attestation should be found in the shard for committee 3");
+
+        assert_eq!(attestation.data, data);
+        assert!(pool
+            .best_aggregate_attestation_by_data_root(H256::repeat_byte(1), 0)
+            .await
+            .is_none());
+    }
+
+    async fn insert_aggregate(pool: &Pool<Minimal>, data: AttestationData) {
+        pool.add_data_root_to_data_entry(data).await;
+
+        pool.aggregates(data).await.lock().await.push(Aggregate {
+            aggregation_bits: BitList::new(true, 1),
+            signature: Default::default(),
+        });
+    }
+
+    fn attestation_data(
+        index: CommitteeIndex,
+        target_epoch: Epoch,
+        source_epoch: Epoch,
+    ) -> AttestationData {
+        AttestationData {
+            slot: misc::compute_start_slot_at_epoch::<Minimal>(target_epoch),
+            index,
+            source: Checkpoint {
+                epoch: source_epoch,
+                root: H256::zero(),
+            },
+            target: Checkpoint {
+                epoch: target_epoch,
+                root: H256::zero(),
+            },
+            ..AttestationData::default()
+        }
+    }
+
+    fn committee_indices(attestations: &[Attestation<Minimal>]) -> Vec<CommitteeIndex> {
+        attestations
+            .iter()
+            .map(|attestation| attestation.data.index)
+            .sorted()
+            .collect()
+    }
 }
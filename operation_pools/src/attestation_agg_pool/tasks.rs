@@ -3,6 +3,7 @@ use std::sync::Arc;
 
 use anyhow::Result;
 use bls::PublicKeyBytes;
+use clock::Clock;
 use eth1_api::ApiController;
 use fork_choice_control::Wait;
 use helper_functions::accessors;
@@ -10,7 +11,12 @@ use prometheus_metrics::Metrics;
 use ssz::ContiguousList;
 use std_ext::ArcExt as _;
 use types::{
-    combined::BeaconState, phase0::containers::Attestation, preset::Preset,
+    combined::BeaconState,
+    phase0::{
+        containers::Attestation,
+        primitives::{Epoch, Slot},
+    },
+    preset::Preset,
     traits::BeaconState as _,
 };
 
@@ -20,13 +26,14 @@ use crate::{
         pool::Pool,
         types::Aggregate,
     },
-    misc::PoolTask,
+    misc::{insert_aggregate, PoolTask},
 };
 
 pub struct BestProposableAttestationsTask<P: Preset, W: Wait> {
     pub pool: Arc<Pool<P>>,
     pub controller: ApiController<P, W>,
     pub beacon_state: Arc<BeaconState<P>>,
+    pub packing_time_budget: Duration,
 }
 
 impl<P: Preset, W: Wait> PoolTask for BestProposableAttestationsTask<P, W> {
@@ -37,6 +44,7 @@ impl<P: Preset, W: Wait> PoolTask for BestProposableAttestationsTask<P, W> {
             pool,
             controller,
             beacon_state,
+            packing_time_budget,
         } = self;
 
         let attestations = pool.best_proposable_attestations(beacon_state.slot()).await;
@@ -49,14 +57,17 @@ impl<P: Preset, W: Wait> PoolTask for BestProposableAttestationsTask<P, W> {
             controller.chain_config().clone_arc(),
             controller.head_block_root().value,
             beacon_state.clone_arc(),
-            true,
+            None,
         )?;
 
-        Ok(
-            pack_attestations_greedily(&attestation_packer, &pool, &beacon_state)
-                .await
-                .attestations,
+        Ok(pack_attestations_greedily(
+            &attestation_packer,
+            &pool,
+            &beacon_state,
+            packing_time_budget,
         )
+        .await
+        .attestations)
     }
 }
 
@@ -84,6 +95,7 @@ impl<P: Preset> PoolTask for ComputeProposerIndicesTask<P> {
 pub struct PackProposableAttestationsTask<P: Preset, W: Wait> {
     pub pool: Arc<Pool<P>>,
     pub controller: ApiController<P, W>,
+    pub clock: Arc<dyn Clock>,
     pub metrics: Option<Arc<Metrics>>,
 }
 
@@ -94,6 +106,7 @@ impl<P: Preset, W: Wait> PoolTask for PackProposableAttestationsTask<P, W> {
         let Self {
             pool,
             controller,
+            clock,
             metrics,
         } = self;
 
@@ -103,7 +116,7 @@ impl<P: Preset, W: Wait> PoolTask for PackProposableAttestationsTask<P, W> {
             controller.chain_config().clone_arc(),
             controller.head_block_root().value,
             beacon_state.clone_arc(),
-            false,
+            Some(clock),
         )?;
 
         let mut is_empty = true;
@@ -190,7 +203,7 @@ impl<P: Preset, W: Send + 'static> PoolTask for InsertAttestationTask<P, W> {
                 aggregate_attestation(existing_attestation, &mut aggregate)?;
             }
 
-            aggregates.push(aggregate);
+            insert_aggregate(&mut aggregates, aggregate, pool.max_aggregates_per_data());
         } else {
             for aggregate in aggregates.iter_mut() {
                 aggregate_attestation(&attestation, aggregate)?;
@@ -209,6 +222,74 @@ impl<P: Preset, W: Send + 'static> PoolTask for InsertAttestationTask<P, W> {
     }
 }
 
+pub struct PruneShardTask<P: Preset> {
+    pub pool: Arc<Pool<P>>,
+    pub shard_index: usize,
+    pub slot: Slot,
+    pub finalized_epoch: Epoch,
+}
+
+impl<P: Preset> PoolTask for PruneShardTask<P> {
+    type Output = ();
+
+    async fn run(self) -> Result<Self::Output> {
+        let Self {
+            pool,
+            shard_index,
+            slot,
+            finalized_epoch,
+        } = self;
+
+        pool.prune_shard(shard_index, slot, finalized_epoch).await;
+
+        Ok(())
+    }
+}
+
+pub struct ShardAggregatesByEpochTask<P: Preset> {
+    pub pool: Arc<Pool<P>>,
+    pub shard_index: usize,
+    pub epoch: Epoch,
+}
+
+impl<P: Preset> PoolTask for ShardAggregatesByEpochTask<P> {
+    type Output = Vec<Attestation<P>>;
+
+    async fn run(self) -> Result<Self::Output> {
+        let Self {
+            pool,
+            shard_index,
+            epoch,
+        } = self;
+
+        Ok(pool
+            .shard_aggregate_attestations_by_epoch(shard_index, epoch)
+            .await)
+    }
+}
+
+pub struct ShardSingularAttestationsByEpochTask<P: Preset> {
+    pub pool: Arc<Pool<P>>,
+    pub shard_index: usize,
+    pub epoch: Epoch,
+}
+
+impl<P: Preset> PoolTask for ShardSingularAttestationsByEpochTask<P> {
+    type Output = Vec<Arc<Attestation<P>>>;
+
+    async fn run(self) -> Result<Self::Output> {
+        let Self {
+            pool,
+            shard_index,
+            epoch,
+        } = self;
+
+        Ok(pool
+            .shard_singular_attestations_by_epoch(shard_index, epoch)
+            .await)
+    }
+}
+
 pub struct SetRegisteredValidatorsTask<P: Preset, W: Wait> {
     pub pool: Arc<Pool<P>>,
     pub controller: ApiController<P, W>,
@@ -229,7 +310,7 @@ impl<P: Preset, W: Wait> PoolTask for SetRegisteredValidatorsTask<P, W> {
 
         let validator_indices = pubkeys
             .into_iter()
-            .filter_map(|pubkey| accessors::index_of_public_key(&beacon_state, pubkey))
+            .filter_map(|pubkey| controller.validator_index(&beacon_state, pubkey))
             .collect();
 
         pool.set_registered_validator_indices(validator_indices)
@@ -274,6 +355,7 @@ async fn pack_attestations_greedily<P: Preset>(
     attestation_packer: &AttestationPacker<P>,
     pool: &Pool<P>,
     state: &BeaconState<P>,
+    time_budget: Duration,
 ) -> PackOutcome<P> {
     let previous_epoch = accessors::get_previous_epoch(state);
     let current_epoch = accessors::get_current_epoch(state);
@@ -281,5 +363,6 @@ async fn pack_attestations_greedily<P: Preset>(
     attestation_packer.pack_proposable_attestations_greedily(
         &pool.aggregate_attestations_by_epoch(previous_epoch).await,
         &pool.aggregate_attestations_by_epoch(current_epoch).await,
+        time_budget,
     )
 }
@@ -11,6 +11,8 @@ use types::{
     preset::Preset,
 };
 
+use crate::misc::AggregateBits;
+
 // Use `Mutex` instead of `RwLock` to avoid race conditions in `InsertAttestationTask`.
 // Don't let this comment fool you into thinking the locking is well thought out.
 // There may be other bugs.
@@ -27,3 +29,13 @@ pub struct Aggregate<P: Preset> {
     pub aggregation_bits: BitList<P::MaxValidatorsPerCommittee>,
     pub signature: AggregateSignature,
 }
+
+impl<P: Preset> AggregateBits for Aggregate<P> {
+    fn is_covered_by(&self, other: &Self) -> bool {
+        !self.aggregation_bits.any_not_in(&other.aggregation_bits)
+    }
+
+    fn participant_count(&self) -> usize {
+        self.aggregation_bits.count_ones()
+    }
+}
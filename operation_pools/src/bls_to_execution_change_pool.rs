@@ -1,4 +1,7 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 
 use anyhow::Result;
 use eth1_api::ApiController;
@@ -12,7 +15,7 @@ use futures::{
 };
 use helper_functions::predicates;
 use itertools::Itertools as _;
-use log::{debug, warn};
+use log::{debug, info, warn};
 use prometheus_metrics::Metrics;
 use transition_functions::capella;
 use types::{
@@ -21,6 +24,7 @@ use types::{
 };
 
 use crate::{
+    config::PoolConfig,
     messages::{PoolToApiMessage, PoolToP2pMessage},
     misc::{Origin, PoolAdditionOutcome, PoolRejectionReason},
 };
@@ -36,6 +40,7 @@ impl BlsToExecutionChangePool {
         pool_to_api_tx: UnboundedSender<PoolToApiMessage>,
         pool_to_p2p_tx: UnboundedSender<PoolToP2pMessage>,
         metrics: Option<Arc<Metrics>>,
+        pool_config: PoolConfig,
     ) -> (Arc<Self>, Service<P, W>) {
         let (tx, rx) = futures::channel::mpsc::unbounded();
 
@@ -44,6 +49,8 @@ impl BlsToExecutionChangePool {
         let service = Service {
             controller,
             bls_to_execution_changes: HashMap::new(),
+            unpublished_bls_to_execution_changes: HashSet::new(),
+            max_bls_to_execution_changes: pool_config.max_bls_to_execution_changes,
             metrics,
             pool_to_api_tx,
             pool_to_p2p_tx,
@@ -57,6 +64,10 @@ impl BlsToExecutionChangePool {
         PoolMessage::DiscardOldBlsToExecutionChanges.send(&self.tx)
     }
 
+    pub fn publish_pending_bls_to_execution_changes(&self) {
+        PoolMessage::PublishPendingBlsToExecutionChanges.send(&self.tx)
+    }
+
     pub async fn handle_external_signed_bls_to_execution_change(
         &self,
         signed_bls_to_execution_change: Box<SignedBlsToExecutionChange>,
@@ -97,6 +108,10 @@ impl BlsToExecutionChangePool {
 pub struct Service<P: Preset, W: Wait> {
     controller: ApiController<P, W>,
     bls_to_execution_changes: HashMap<ValidatorIndex, SignedBlsToExecutionChange>,
+    // Changes submitted through the API before Capella. They cannot be gossiped until the fork
+    // activates, so they are published the first time the pool sees a post-Capella state.
+    unpublished_bls_to_execution_changes: HashSet<ValidatorIndex>,
+    max_bls_to_execution_changes: usize,
     metrics: Option<Arc<Metrics>>,
     pool_to_api_tx: UnboundedSender<PoolToApiMessage>,
     pool_to_p2p_tx: UnboundedSender<PoolToP2pMessage>,
@@ -114,6 +129,14 @@ impl<P: Preset, W: Wait> Service<P, W> {
                         });
 
                         self.discard_old_bls_to_execution_changes();
+                        self.track_collection_metrics();
+                        true
+                    }
+                    PoolMessage::PublishPendingBlsToExecutionChanges => {
+                        if let Err(error) = self.publish_pending_bls_to_execution_changes() {
+                            warn!("failed to publish pending BLS to execution changes: {error:?}");
+                        }
+
                         true
                     }
                     PoolMessage::HandleExternalBlsToExecutionChange(
@@ -130,6 +153,8 @@ impl<P: Preset, W: Wait> Service<P, W> {
                             origin,
                         );
 
+                        self.track_collection_metrics();
+
                         sender
                             .map(|sender| sender.send(outcome).is_ok())
                             .unwrap_or(true)
@@ -157,16 +182,21 @@ impl<P: Preset, W: Wait> Service<P, W> {
         signed_bls_to_execution_change: SignedBlsToExecutionChange,
         origin: Origin,
     ) -> PoolAdditionOutcome {
-        match self.validate_signed_bls_to_execution_change(signed_bls_to_execution_change) {
-            Ok(outcome) => match outcome {
+        match self.validate_signed_bls_to_execution_change(signed_bls_to_execution_change, &origin)
+        {
+            Ok((outcome, post_capella)) => match outcome {
                 ValidationOutcome::Accept => {
                     match origin {
-                        Origin::Api => {
+                        Origin::Api if post_capella => {
                             PoolToP2pMessage::PublishSignedBlsToExecutionChange(Box::new(
                                 signed_bls_to_execution_change,
                             ))
                             .send(&self.pool_to_p2p_tx);
                         }
+                        Origin::Api => {
+                            self.unpublished_bls_to_execution_changes
+                                .insert(signed_bls_to_execution_change.message.validator_index);
+                        }
                         Origin::Gossip(gossip_id) => {
                             PoolToP2pMessage::Accept(gossip_id).send(&self.pool_to_p2p_tx);
                         }
@@ -208,36 +238,93 @@ impl<P: Preset, W: Wait> Service<P, W> {
     fn validate_signed_bls_to_execution_change(
         &mut self,
         signed_bls_to_execution_change: SignedBlsToExecutionChange,
-    ) -> Result<ValidationOutcome> {
+        origin: &Origin,
+    ) -> Result<(ValidationOutcome, bool)> {
         let state = self.controller.preprocessed_state_at_current_slot()?;
+        let post_capella = state.post_capella().is_some();
 
-        let Some(state) = state.post_capella() else {
+        // There are no BLS to execution change gossip topics before Capella.
+        // Only changes submitted through the API are held until the fork activates.
+        if !post_capella && matches!(origin, Origin::Gossip(_)) {
             warn!(
-                "signed BLS to execution change received before Capella fork \
+                "signed BLS to execution change received from gossip before Capella fork \
                  (signed_bls_to_execution_change: {:?}, slot: {})",
                 signed_bls_to_execution_change,
                 state.slot(),
             );
 
-            return Ok(ValidationOutcome::Ignore);
-        };
+            return Ok((ValidationOutcome::Ignore, post_capella));
+        }
 
         let validator_index = signed_bls_to_execution_change.message.validator_index;
 
         if self.bls_to_execution_changes.contains_key(&validator_index) {
-            return Ok(ValidationOutcome::Ignore);
+            return Ok((ValidationOutcome::Ignore, post_capella));
         }
 
         capella::validate_bls_to_execution_change(
             self.controller.chain_config(),
-            state,
+            &*state,
             signed_bls_to_execution_change,
         )?;
 
+        if self.bls_to_execution_changes.len() >= self.max_bls_to_execution_changes {
+            debug!(
+                "BLS to execution change pool is full; ignoring change \
+                 (validator_index: {validator_index}, capacity: {})",
+                self.max_bls_to_execution_changes,
+            );
+
+            return Ok((ValidationOutcome::Ignore, post_capella));
+        }
+
         self.bls_to_execution_changes
             .insert(validator_index, signed_bls_to_execution_change);
 
-        Ok(ValidationOutcome::Accept)
+        Ok((ValidationOutcome::Accept, post_capella))
+    }
+
+    fn publish_pending_bls_to_execution_changes(&mut self) -> Result<()> {
+        if self.unpublished_bls_to_execution_changes.is_empty() {
+            return Ok(());
+        }
+
+        let state = self.controller.preprocessed_state_at_current_slot()?;
+
+        let Some(state) = state.post_capella() else {
+            return Ok(());
+        };
+
+        let mut published = 0;
+
+        for validator_index in self.unpublished_bls_to_execution_changes.drain() {
+            let Some(change) = self.bls_to_execution_changes.get(&validator_index).copied() else {
+                continue;
+            };
+
+            if let Err(error) = capella::validate_bls_to_execution_change(
+                self.controller.chain_config(),
+                state,
+                change,
+            ) {
+                warn!(
+                    "BLS to execution change submitted before Capella fork is no longer valid \
+                     (error: {error}, message: {change:?})",
+                );
+
+                self.bls_to_execution_changes.remove(&validator_index);
+                continue;
+            }
+
+            PoolToP2pMessage::PublishSignedBlsToExecutionChange(Box::new(change))
+                .send(&self.pool_to_p2p_tx);
+
+            published += 1;
+        }
+
+        info!("published {published} BLS to execution changes submitted before Capella fork");
+
+        Ok(())
     }
 
     fn discard_old_bls_to_execution_changes(&mut self) {
@@ -253,12 +340,27 @@ impl<P: Preset, W: Wait> Service<P, W> {
             };
 
             !predicates::has_eth1_withdrawal_credential(validator)
-        })
+        });
+
+        let bls_to_execution_changes = &self.bls_to_execution_changes;
+
+        self.unpublished_bls_to_execution_changes
+            .retain(|validator_index| bls_to_execution_changes.contains_key(validator_index));
+    }
+
+    fn track_collection_metrics(&self) {
+        if let Some(metrics) = self.metrics.as_ref() {
+            metrics.set_collection_length(
+                &["BlsToExecutionChangePool", "bls_to_execution_changes"],
+                self.bls_to_execution_changes.len(),
+            );
+        }
     }
 }
 
 enum PoolMessage {
     DiscardOldBlsToExecutionChanges,
+    PublishPendingBlsToExecutionChanges,
     HandleExternalBlsToExecutionChange(
         Box<SignedBlsToExecutionChange>,
         Origin,
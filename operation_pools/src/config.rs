@@ -0,0 +1,25 @@
+use core::time::Duration;
+
+pub const DEFAULT_ATTESTATION_PACKING_TIME_BUDGET: Duration = Duration::from_millis(250);
+pub const DEFAULT_MAX_AGGREGATES_PER_ATTESTATION_DATA: usize = 16;
+pub const DEFAULT_MAX_AGGREGATES_PER_CONTRIBUTION_DATA: usize = 16;
+pub const DEFAULT_MAX_BLS_TO_EXECUTION_CHANGES: usize = 1 << 16;
+
+#[derive(Clone, Copy, Debug)]
+pub struct PoolConfig {
+    pub attestation_packing_time_budget: Duration,
+    pub max_aggregates_per_attestation_data: usize,
+    pub max_aggregates_per_contribution_data: usize,
+    pub max_bls_to_execution_changes: usize,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            attestation_packing_time_budget: DEFAULT_ATTESTATION_PACKING_TIME_BUDGET,
+            max_aggregates_per_attestation_data: DEFAULT_MAX_AGGREGATES_PER_ATTESTATION_DATA,
+            max_aggregates_per_contribution_data: DEFAULT_MAX_AGGREGATES_PER_CONTRIBUTION_DATA,
+            max_bls_to_execution_changes: DEFAULT_MAX_BLS_TO_EXECUTION_CHANGES,
+        }
+    }
+}
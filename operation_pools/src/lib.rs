@@ -3,6 +3,11 @@ pub use crate::{
     bls_to_execution_change_pool::{
         BlsToExecutionChangePool, Service as BlsToExecutionChangePoolService,
     },
+    config::{
+        PoolConfig, DEFAULT_ATTESTATION_PACKING_TIME_BUDGET,
+        DEFAULT_MAX_AGGREGATES_PER_ATTESTATION_DATA, DEFAULT_MAX_AGGREGATES_PER_CONTRIBUTION_DATA,
+        DEFAULT_MAX_BLS_TO_EXECUTION_CHANGES,
+    },
     messages::{PoolToApiMessage, PoolToLivenessMessage, PoolToP2pMessage},
     misc::{Origin, PoolAdditionOutcome, PoolRejectionReason},
     sync_committee_agg_pool::Manager as SyncCommitteeAggPool,
@@ -21,6 +26,7 @@ mod attestation_agg_pool {
 }
 
 mod bls_to_execution_change_pool;
+mod config;
 mod messages;
 mod misc;
 
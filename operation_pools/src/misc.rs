@@ -40,3 +40,96 @@ pub trait PoolTask: Send + 'static {
 
     fn run(self) -> impl Future<Output = Result<Self::Output>> + Send;
 }
+
+pub trait AggregateBits {
+    fn is_covered_by(&self, other: &Self) -> bool;
+
+    fn participant_count(&self) -> usize;
+}
+
+// Aggregates whose bits are all covered by another aggregate are superseded by it.
+// Once the cap is reached, the aggregate with the fewest bits is evicted if the new one has more.
+pub fn insert_aggregate<A: AggregateBits>(
+    aggregates: &mut Vec<A>,
+    aggregate: A,
+    max_aggregates: usize,
+) {
+    if aggregates
+        .iter()
+        .any(|existing| aggregate.is_covered_by(existing))
+    {
+        return;
+    }
+
+    aggregates.retain(|existing| !existing.is_covered_by(&aggregate));
+
+    if aggregates.len() >= max_aggregates {
+        let Some((index, smallest)) = aggregates
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, existing)| existing.participant_count())
+        else {
+            return;
+        };
+
+        if smallest.participant_count() >= aggregate.participant_count() {
+            return;
+        }
+
+        aggregates.swap_remove(index);
+    }
+
+    aggregates.push(aggregate);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Bits(u8);
+
+    impl AggregateBits for Bits {
+        fn is_covered_by(&self, other: &Self) -> bool {
+            self.0 & !other.0 == 0
+        }
+
+        fn participant_count(&self) -> usize {
+            self.0.count_ones().try_into().expect("u32 fits in usize")
+        }
+    }
+
+    fn bits(aggregates: &[Bits]) -> Vec<u8> {
+        aggregates.iter().map(|aggregate| aggregate.0).collect()
+    }
+
+    #[test]
+    fn insert_aggregate_skips_covered_aggregates() {
+        let mut aggregates = vec![Bits(0b0111)];
+
+        insert_aggregate(&mut aggregates, Bits(0b0011), 4);
+
+        assert_eq!(bits(&aggregates), [0b0111]);
+    }
+
+    #[test]
+    fn insert_aggregate_removes_superseded_aggregates() {
+        let mut aggregates = vec![Bits(0b0001), Bits(0b0010), Bits(0b1000)];
+
+        insert_aggregate(&mut aggregates, Bits(0b0011), 4);
+
+        assert_eq!(bits(&aggregates), [0b1000, 0b0011]);
+    }
+
+    #[test]
+    fn insert_aggregate_evicts_smallest_aggregate_when_full() {
+        let mut aggregates = vec![Bits(0b0011), Bits(0b0100)];
+
+        insert_aggregate(&mut aggregates, Bits(0b1000), 2);
+
+        assert_eq!(bits(&aggregates), [0b0011, 0b0100]);
+
+        insert_aggregate(&mut aggregates, Bits(0b1100_1000), 2);
+
+        assert_eq!(bits(&aggregates), [0b0011, 0b1100_1000]);
+    }
+}
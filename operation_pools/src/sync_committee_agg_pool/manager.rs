@@ -19,6 +19,7 @@ use types::{
 };
 
 use crate::{
+    config::PoolConfig,
     messages::{PoolToLivenessMessage, PoolToP2pMessage},
     misc::{Origin, PoolTask},
     sync_committee_agg_pool::{
@@ -69,11 +70,12 @@ impl<P: Preset, W: Wait> Manager<P, W> {
         pool_to_liveness_tx: Option<UnboundedSender<PoolToLivenessMessage>>,
         pool_to_p2p_tx: UnboundedSender<PoolToP2pMessage>,
         metrics: Option<Arc<Metrics>>,
+        pool_config: PoolConfig,
     ) -> Arc<Self> {
         Arc::new(Self {
             dedicated_executor,
             controller,
-            pool: Arc::new(Pool::new()),
+            pool: Arc::new(Pool::new(pool_config.max_aggregates_per_contribution_data)),
             pool_to_liveness_tx,
             pool_to_p2p_tx,
             metrics,
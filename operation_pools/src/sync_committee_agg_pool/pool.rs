@@ -1,9 +1,11 @@
+use core::cmp::Reverse;
 use std::{collections::HashSet, sync::Arc};
 
 use anyhow::{anyhow, Result};
 use helper_functions::accessors;
 use itertools::Itertools as _;
 use log::debug;
+use ssz::{BitVector, BitVectorBits};
 use std_ext::ArcExt as _;
 use tokio::sync::RwLock;
 use types::{
@@ -17,11 +19,15 @@ use types::{
     traits::BeaconState as _,
 };
 
-use crate::sync_committee_agg_pool::types::{
-    Aggregate, AggregateMap, ContributionData, SyncCommitteeMessageMap, SyncCommitteeMessageSet,
+use crate::{
+    misc::insert_aggregate,
+    sync_committee_agg_pool::types::{
+        Aggregate, AggregateMap, ContributionData, SyncCommitteeMessageMap, SyncCommitteeMessageSet,
+    },
 };
 
 pub struct Pool<P: Preset> {
+    max_aggregates_per_data: usize,
     aggregates: RwLock<AggregateMap<P>>,
     aggregator_contributions: RwLock<HashSet<(ValidatorIndex, SubcommitteeIndex)>>,
     sync_committee_messages: RwLock<SyncCommitteeMessageMap>,
@@ -29,8 +35,9 @@ pub struct Pool<P: Preset> {
 
 impl<P: Preset> Pool<P> {
     #[must_use]
-    pub fn new() -> Self {
+    pub fn new(max_aggregates_per_data: usize) -> Self {
         Self {
+            max_aggregates_per_data,
             aggregates: RwLock::new(AggregateMap::new()),
             aggregator_contributions: RwLock::new(HashSet::new()),
             sync_committee_messages: RwLock::new(SyncCommitteeMessageMap::new()),
@@ -115,11 +122,14 @@ impl<P: Preset> Pool<P> {
             }
         }
 
-        self.aggregates(contribution_data)
-            .await
-            .write()
-            .await
-            .push(aggregate);
+        let pool_aggregates = self.aggregates(contribution_data).await;
+        let mut pool_aggregates = pool_aggregates.write().await;
+
+        insert_aggregate(
+            &mut pool_aggregates,
+            aggregate,
+            self.max_aggregates_per_data,
+        );
 
         Ok(())
     }
@@ -207,15 +217,7 @@ impl<P: Preset> Pool<P> {
             subcommittee_index,
         };
 
-        let aggregate = self
-            .aggregates(data)
-            .await
-            .read()
-            .await
-            .iter()
-            .max_by_key(|aggregate| aggregate.aggregation_bits.count_ones())
-            .copied()
-            .unwrap_or_default();
+        let aggregate = merge_disjoint_aggregates(self.aggregates(data).await.read().await.iter());
 
         SyncCommitteeContribution {
             slot,
@@ -226,6 +228,28 @@ impl<P: Preset> Pool<P> {
         }
     }
 
+    pub async fn aggregate_count(&self) -> usize {
+        let aggregates = self.aggregates.read().await;
+        let mut count = 0;
+
+        for aggregates in aggregates.values() {
+            count += aggregates.read().await.len();
+        }
+
+        count
+    }
+
+    pub async fn sync_committee_message_count(&self) -> usize {
+        let messages = self.sync_committee_messages.read().await;
+        let mut count = 0;
+
+        for messages in messages.values() {
+            count += messages.read().await.len();
+        }
+
+        count
+    }
+
     pub async fn contribution_and_proof_exists(
         &self,
         contribution_and_proof: ContributionAndProof<P>,
@@ -275,3 +299,41 @@ impl<P: Preset> Pool<P> {
             .clone_arc()
     }
 }
+
+// Aggregates for the same contribution data that share no participants can be combined.
+// Starting from the aggregate with the most participants gives the best result in most cases.
+fn merge_disjoint_aggregates<'aggregates, P: Preset>(
+    aggregates: impl IntoIterator<Item = &'aggregates Aggregate<P>>,
+) -> Aggregate<P> {
+    let mut aggregates = aggregates.into_iter().copied().collect_vec();
+
+    aggregates.sort_by_key(|aggregate| Reverse(aggregate.aggregation_bits.count_ones()));
+
+    let mut aggregates = aggregates.into_iter();
+
+    let Some(mut merged) = aggregates.next() else {
+        return Aggregate::default();
+    };
+
+    for aggregate in aggregates {
+        if !is_disjoint(merged.aggregation_bits, aggregate.aggregation_bits) {
+            continue;
+        }
+
+        for (position, participated) in aggregate.aggregation_bits.into_iter().enumerate() {
+            if participated {
+                merged.aggregation_bits.set(position, true);
+            }
+        }
+
+        merged.signature.aggregate_in_place(aggregate.signature);
+    }
+
+    merged
+}
+
+fn is_disjoint<N: BitVectorBits>(bits: BitVector<N>, other: BitVector<N>) -> bool {
+    bits.into_iter()
+        .zip(other)
+        .all(|(bit, other_bit)| !(bit && other_bit))
+}
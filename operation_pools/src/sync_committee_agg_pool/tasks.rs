@@ -336,6 +336,18 @@ impl<P: Preset> PoolTask for HandleSlotTask<P> {
 
         pool.on_slot(slot).await;
 
+        if let Some(metrics) = metrics.as_ref() {
+            metrics.set_collection_length(
+                &["SyncCommitteeAggPool", "aggregates"],
+                pool.aggregate_count().await,
+            );
+
+            metrics.set_collection_length(
+                &["SyncCommitteeAggPool", "sync_committee_messages"],
+                pool.sync_committee_message_count().await,
+            );
+        }
+
         Ok(())
     }
 }
@@ -15,6 +15,8 @@ use types::{
     preset::Preset,
 };
 
+use crate::misc::AggregateBits;
+
 pub type AggregateMap<P> = HashMap<ContributionData, Arc<RwLock<Vec<Aggregate<P>>>>>;
 pub type SyncCommitteeMessageMap = HashMap<ContributionData, Arc<RwLock<SyncCommitteeMessageSet>>>;
 pub type SyncCommitteeMessageSet = HashSet<SyncCommitteeMessage>;
@@ -67,3 +69,16 @@ pub struct Aggregate<P: Preset> {
     pub aggregation_bits: BitVector<P::SyncSubcommitteeSize>,
     pub signature: AggregateSignature,
 }
+
+impl<P: Preset> AggregateBits for Aggregate<P> {
+    fn is_covered_by(&self, other: &Self) -> bool {
+        self.aggregation_bits
+            .into_iter()
+            .zip(other.aggregation_bits)
+            .all(|(bit, other_bit)| !bit || other_bit)
+    }
+
+    fn participant_count(&self) -> usize {
+        self.aggregation_bits.count_ones()
+    }
+}
@@ -18,7 +18,7 @@ use crate::misc::{AttestationSubnetActions, BeaconCommitteeSubscription, SubnetP
 
 use AttestationSubnetState::{DiscoveringPeers, Irrelevant, Persistent, Subscribed};
 
-const DISCOVER_PEERS_IN_ADVANCE_SLOTS: u64 = 2;
+pub const DISCOVER_PEERS_IN_ADVANCE_SLOTS: u64 = 2;
 const SUBSCRIBE_IN_ADVANCE_SLOTS: u64 = 1;
 
 #[derive(Clone, Copy, Default, PartialEq, Eq)]
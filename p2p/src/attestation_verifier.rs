@@ -1,13 +1,13 @@
-use std::sync::Arc;
+use std::{collections::VecDeque, sync::Arc};
 
 use anyhow::Result;
 use dedicated_executor::DedicatedExecutor;
 use eth1_api::RealController;
 use eth2_libp2p::GossipId;
-use fork_choice_control::{VerifyAggregateAndProofResult, VerifyAttestationResult};
+use fork_choice_control::{P2pMessage, VerifyAggregateAndProofResult, VerifyAttestationResult};
 use fork_choice_store::{AggregateAndProofAction, AttestationAction};
 use futures::{
-    channel::mpsc::{self, UnboundedReceiver, UnboundedSender},
+    channel::mpsc::{self, Receiver, UnboundedReceiver, UnboundedSender},
     select, StreamExt,
 };
 use helper_functions::{
@@ -32,19 +32,23 @@ use types::{
     preset::Preset,
 };
 
-use crate::messages::P2pToAttestationVerifier;
+use crate::messages::{P2pToAttestationVerifier, ATTESTATION_VERIFIER_CHANNEL};
 
 const MAX_BATCH_SIZE: usize = 64;
 
+const MAX_QUEUED_ATTESTATIONS: usize = 1 << 14;
+const MAX_QUEUED_AGGREGATES: usize = 1 << 12;
+
 pub struct AttestationVerifier<P: Preset> {
-    attestations: Vec<AttestationWithOrigin<P>>,
-    aggregates: Vec<AggregateWithOrigin<P>>,
+    attestations: GossipQueue<AttestationWithOrigin<P>>,
+    aggregates: GossipQueue<AggregateWithOrigin<P>>,
     controller: RealController<P>,
     dedicated_executor: DedicatedExecutor,
     active_task_count: usize,
     max_active_tasks: usize,
     metrics: Option<Arc<Metrics>>,
-    p2p_to_verifier_rx: UnboundedReceiver<P2pToAttestationVerifier<P>>,
+    p2p_tx: UnboundedSender<P2pMessage<P>>,
+    p2p_to_verifier_rx: Receiver<P2pToAttestationVerifier<P>>,
     task_to_verifier_rx: UnboundedReceiver<TaskMessage>,
     task_to_verifier_tx: UnboundedSender<TaskMessage>,
 }
@@ -55,18 +59,20 @@ impl<P: Preset> AttestationVerifier<P> {
         controller: RealController<P>,
         dedicated_executor: DedicatedExecutor,
         metrics: Option<Arc<Metrics>>,
-        p2p_to_verifier_rx: UnboundedReceiver<P2pToAttestationVerifier<P>>,
+        p2p_tx: UnboundedSender<P2pMessage<P>>,
+        p2p_to_verifier_rx: Receiver<P2pToAttestationVerifier<P>>,
     ) -> Self {
         let (task_to_verifier_tx, task_to_verifier_rx) = mpsc::unbounded();
 
         Self {
-            attestations: vec![],
-            aggregates: vec![],
+            attestations: GossipQueue::new(MAX_QUEUED_ATTESTATIONS),
+            aggregates: GossipQueue::new(MAX_QUEUED_AGGREGATES),
             controller,
             dedicated_executor,
             active_task_count: 0,
             max_active_tasks: num_cpus::get(),
             metrics,
+            p2p_tx,
             p2p_to_verifier_rx,
             task_to_verifier_rx,
             task_to_verifier_tx,
@@ -89,16 +95,20 @@ impl<P: Preset> AttestationVerifier<P> {
                     }
                 }
                 message = self.p2p_to_verifier_rx.select_next_some() => {
+                    if let Some(metrics) = self.metrics.as_ref() {
+                        metrics.dec_gossip_channel_length(ATTESTATION_VERIFIER_CHANNEL);
+                    }
+
                     match message {
                         P2pToAttestationVerifier::GossipAggregateAndProof(aggregate, gossip_id) => {
-                            self.aggregates.push(AggregateWithOrigin {
+                            self.enqueue_aggregate(AggregateWithOrigin {
                                 aggregate,
                                 gossip_id,
                             });
                             self.spawn_verify_batch_tasks();
                         }
                         P2pToAttestationVerifier::GossipAttestation(attestation, subnet_id, gossip_id) => {
-                            self.attestations.push(AttestationWithOrigin {
+                            self.enqueue_attestation(AttestationWithOrigin {
                                 attestation,
                                 subnet_id,
                                 gossip_id,
@@ -111,9 +121,39 @@ impl<P: Preset> AttestationVerifier<P> {
         }
     }
 
+    fn enqueue_attestation(&mut self, attestation: AttestationWithOrigin<P>) {
+        if let Some(dropped) = self.attestations.push(attestation) {
+            report_dropped_object(
+                &self.p2p_tx,
+                self.metrics.as_deref(),
+                dropped.gossip_id,
+                "attestation",
+            );
+        }
+    }
+
+    fn enqueue_aggregate(&mut self, aggregate: AggregateWithOrigin<P>) {
+        if let Some(dropped) = self.aggregates.push(aggregate) {
+            report_dropped_object(
+                &self.p2p_tx,
+                self.metrics.as_deref(),
+                dropped.gossip_id,
+                "aggregate_and_proof",
+            );
+        }
+    }
+
     fn spawn_verify_batch_tasks(&mut self) {
         self.spawn_verify_attestation_batch_task();
         self.spawn_verify_aggregate_batch_task();
+
+        if let Some(metrics) = self.metrics.as_ref() {
+            metrics.set_attestation_verifier_queue_length("attestation", self.attestations.len());
+            metrics.set_attestation_verifier_queue_length(
+                "aggregate_and_proof",
+                self.aggregates.len(),
+            );
+        }
     }
 
     fn spawn_verify_aggregate_batch_task(&mut self) {
@@ -127,8 +167,7 @@ impl<P: Preset> AttestationVerifier<P> {
             metrics.set_attestation_verifier_active_task_count(self.active_task_count);
         }
 
-        let split_at = self.aggregates.len().saturating_sub(MAX_BATCH_SIZE);
-        let aggregates = self.aggregates.split_off(split_at);
+        let aggregates = self.aggregates.take_batch();
 
         VerifyAggregateBatchTask::spawn(
             aggregates,
@@ -150,8 +189,7 @@ impl<P: Preset> AttestationVerifier<P> {
             metrics.set_attestation_verifier_active_task_count(self.active_task_count);
         }
 
-        let split_at = self.attestations.len().saturating_sub(MAX_BATCH_SIZE);
-        let attestations = self.attestations.split_off(split_at);
+        let attestations = self.attestations.take_batch();
 
         VerifyAttestationBatchTask::spawn(
             attestations,
@@ -453,6 +491,68 @@ fn attestation_batch_triples<'a, P: Preset>(
         .collect()
 }
 
+// Batches are taken from the back of the queue, so the oldest objects are dropped when the
+// verifier falls behind. They are the least likely to still be useful when verified.
+struct GossipQueue<T> {
+    objects: VecDeque<T>,
+    capacity: usize,
+}
+
+impl<T> GossipQueue<T> {
+    const fn new(capacity: usize) -> Self {
+        Self {
+            objects: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.objects.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.objects.is_empty()
+    }
+
+    /// Returns the object dropped to make room for `object`, if any.
+    fn push(&mut self, object: T) -> Option<T> {
+        let dropped = if self.objects.len() >= self.capacity {
+            self.objects.pop_front()
+        } else {
+            None
+        };
+
+        self.objects.push_back(object);
+
+        dropped
+    }
+
+    fn take_batch(&mut self) -> Vec<T> {
+        let split_at = self.objects.len().saturating_sub(MAX_BATCH_SIZE);
+        self.objects.split_off(split_at).into()
+    }
+}
+
+// Dropped objects are reported as ignored. Otherwise gossipsub would keep them in its cache
+// until they expire without forwarding them or penalizing the peers that sent them.
+fn report_dropped_object<P: Preset>(
+    p2p_tx: &UnboundedSender<P2pMessage<P>>,
+    metrics: Option<&Metrics>,
+    gossip_id: GossipId,
+    object: &str,
+) {
+    if p2p_tx
+        .unbounded_send(P2pMessage::Ignore(gossip_id))
+        .is_err()
+    {
+        debug!("send to p2p failed because the receiver was dropped");
+    }
+
+    if let Some(metrics) = metrics {
+        metrics.register_attestation_verifier_dropped_object(object);
+    }
+}
+
 #[derive(Clone)]
 struct AggregateWithOrigin<P: Preset> {
     aggregate: Box<SignedAggregateAndProof<P>>,
@@ -479,3 +579,43 @@ impl TaskMessage {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use types::preset::Minimal;
+
+    use super::*;
+
+    #[test]
+    fn gossip_queue_drops_oldest_objects_when_full() {
+        let mut queue = GossipQueue::new(2);
+
+        assert_eq!(queue.push(1), None);
+        assert_eq!(queue.push(2), None);
+        assert_eq!(queue.push(3), Some(1));
+        assert_eq!(queue.push(4), Some(2));
+        assert_eq!(queue.len(), 2);
+    }
+
+    #[test]
+    fn gossip_queue_takes_newest_objects_first() {
+        let mut queue = GossipQueue::new(MAX_BATCH_SIZE * 2);
+
+        for object in 0..MAX_BATCH_SIZE + 1 {
+            assert_eq!(queue.push(object), None);
+        }
+
+        assert_eq!(queue.take_batch(), (1..=MAX_BATCH_SIZE).collect::<Vec<_>>());
+        assert_eq!(queue.take_batch(), [0]);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn dropped_objects_are_reported_as_ignored() {
+        let (p2p_tx, mut p2p_rx) = mpsc::unbounded::<P2pMessage<Minimal>>();
+
+        report_dropped_object(&p2p_tx, None, GossipId::default(), "attestation");
+
+        assert!(matches!(p2p_rx.try_next(), Ok(Some(P2pMessage::Ignore(_)))));
+    }
+}
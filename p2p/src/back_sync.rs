@@ -1,4 +1,5 @@
-use std::{collections::BTreeMap, sync::Arc, thread::Builder};
+use core::time::Duration;
+use std::{collections::BTreeMap, sync::Arc, thread::Builder, time::Instant};
 
 use anyhow::{ensure, Result};
 use database::Database;
@@ -21,10 +22,15 @@ use types::{
 
 use crate::messages::ArchiverToSync;
 
+// Archiving is postponed while storage is degraded and fails if the database cannot be written to.
+// Neither is likely to be resolved quickly, so there is no point in retrying immediately.
+const ARCHIVER_RETRY_DELAY: Duration = Duration::from_secs(60);
+
 pub struct BackSync<P: Preset> {
     batch: Batch<P>,
     data: Data,
     archiving: bool,
+    archiver_postponed_at: Option<Instant>,
 }
 
 impl<P: Preset> BackSync<P> {
@@ -41,6 +47,7 @@ impl<P: Preset> BackSync<P> {
             data,
             batch: Batch::default(),
             archiving: false,
+            archiver_postponed_at: None,
         }
     }
 
@@ -106,6 +113,26 @@ impl<P: Preset> BackSync<P> {
             return Ok(());
         }
 
+        if self
+            .archiver_postponed_at
+            .is_some_and(|postponed_at| postponed_at.elapsed() < ARCHIVER_RETRY_DELAY)
+        {
+            features::log!(
+                DebugP2p,
+                "not spawning state archiver: state archiver postponed recently",
+            );
+
+            return Ok(());
+        }
+
+        if controller.storage_health().is_degraded() {
+            features::log!(DebugP2p, "not spawning state archiver: storage is degraded");
+
+            self.archiver_postponed_at = Some(Instant::now());
+
+            return Ok(());
+        }
+
         let start_slot = self.low_slot();
         let end_slot = self.high_slot();
 
@@ -117,12 +144,18 @@ impl<P: Preset> BackSync<P> {
                     "archiving back sync states from {start_slot} to {end_slot}",
                 );
 
+                // Back sync data must not be removed unless all states have been archived.
+                // Otherwise the missing states would never be archived.
                 match controller.archive_back_sync_states(start_slot, end_slot, genesis_provider) {
-                    Ok(()) => info!("back sync state archiver thread finished successfully"),
-                    Err(error) => warn!("back sync state archiver thread failed: {error:?}"),
-                };
-
-                ArchiverToSync::BackSyncStatesArchived.send(&sync_tx);
+                    Ok(()) => {
+                        info!("back sync state archiver thread finished successfully");
+                        ArchiverToSync::BackSyncStatesArchived.send(&sync_tx);
+                    }
+                    Err(error) => {
+                        warn!("back sync state archiver thread failed: {error:?}");
+                        ArchiverToSync::BackSyncStatesArchivalFailed.send(&sync_tx);
+                    }
+                }
             })?;
 
         self.archiving = true;
@@ -130,6 +163,19 @@ impl<P: Preset> BackSync<P> {
         Ok(())
     }
 
+    pub fn on_archiver_failed(&mut self) {
+        self.archiving = false;
+        self.archiver_postponed_at = Some(Instant::now());
+    }
+
+    /// Whether a postponed or failed state archiver should be spawned again.
+    pub fn archiver_retry_due(&self) -> bool {
+        !self.archiving
+            && self
+                .archiver_postponed_at
+                .is_some_and(|postponed_at| postponed_at.elapsed() >= ARCHIVER_RETRY_DELAY)
+    }
+
     pub fn verify_blocks(
         &mut self,
         database: &Database,
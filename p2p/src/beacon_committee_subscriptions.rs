@@ -1,46 +1,133 @@
-use std::collections::BTreeMap;
+use core::ops::RangeInclusive;
+use std::collections::{btree_map::Entry, BTreeMap};
 
-use helper_functions::misc;
-use types::{
-    phase0::primitives::{CommitteeIndex, Epoch, ValidatorIndex},
-    preset::Preset,
-};
+use types::phase0::primitives::{CommitteeIndex, Slot, ValidatorIndex};
 
 use crate::misc::BeaconCommitteeSubscription;
 
-type ValidatorCommitteeSubscriptions = BTreeMap<CommitteeIndex, BeaconCommitteeSubscription>;
+type SlotSubscriptions = BTreeMap<(ValidatorIndex, CommitteeIndex), BeaconCommitteeSubscription>;
 
+// Subscriptions are keyed by the slot of the duty they were made for.
+// A subscription expires at the end of that slot, i.e., its expiration is `slot + 1`.
 #[derive(Default, Clone)]
 pub struct BeaconCommitteeSubscriptions {
-    subscriptions: BTreeMap<Epoch, BTreeMap<ValidatorIndex, ValidatorCommitteeSubscriptions>>,
+    subscriptions: BTreeMap<Slot, SlotSubscriptions>,
 }
 
 impl BeaconCommitteeSubscriptions {
-    pub fn discard_old_subscriptions(&mut self, epoch: Epoch) {
-        self.subscriptions = self.subscriptions.split_off(&epoch);
+    pub fn discard_expired_subscriptions(&mut self, current_slot: Slot) {
+        self.subscriptions = self.subscriptions.split_off(&current_slot);
     }
 
-    pub fn all(&self) -> impl Iterator<Item = BeaconCommitteeSubscription> + '_ {
+    /// Returns subscriptions for duties in `slots`.
+    ///
+    /// Subscriptions outside the lookahead window cannot affect subnet states,
+    /// so there is no point in feeding them to `AttestationSubnets` on every slot.
+    pub fn at_slots(
+        &self,
+        slots: RangeInclusive<Slot>,
+    ) -> impl Iterator<Item = BeaconCommitteeSubscription> + '_ {
         self.subscriptions
-            .values()
-            .flat_map(BTreeMap::values)
-            .flat_map(BTreeMap::values)
+            .range(slots)
+            .flat_map(|(_, subscriptions)| subscriptions.values())
             .copied()
     }
 
-    pub fn update<P: Preset>(
+    /// Records `subscriptions` and returns the ones that were not already known.
+    ///
+    /// Validator clients resubmit their subscriptions every epoch (some every slot).
+    /// A resubmitted subscription is only returned if it turned the validator into an aggregator.
+    /// Aggregation duty is never revoked by a later submission for the same committee and slot.
+    pub fn update(
         &mut self,
+        current_slot: Slot,
         subscriptions: impl IntoIterator<Item = BeaconCommitteeSubscription>,
-    ) {
+    ) -> Vec<BeaconCommitteeSubscription> {
+        let mut changed = vec![];
+
         for subscription in subscriptions {
-            let epoch = misc::compute_epoch_at_slot::<P>(subscription.slot);
+            if subscription.slot < current_slot {
+                continue;
+            }
 
-            self.subscriptions
-                .entry(epoch)
-                .or_default()
-                .entry(subscription.validator_index)
+            let key = (subscription.validator_index, subscription.committee_index);
+
+            match self
+                .subscriptions
+                .entry(subscription.slot)
                 .or_default()
-                .insert(subscription.committee_index, subscription);
+                .entry(key)
+            {
+                Entry::Vacant(vacant) => {
+                    vacant.insert(subscription);
+                    changed.push(subscription);
+                }
+                Entry::Occupied(mut occupied) => {
+                    let existing = occupied.get_mut();
+
+                    let merged = BeaconCommitteeSubscription {
+                        is_aggregator: existing.is_aggregator || subscription.is_aggregator,
+                        ..subscription
+                    };
+
+                    if *existing != merged {
+                        *existing = merged;
+                        changed.push(merged);
+                    }
+                }
+            }
         }
+
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subscription(slot: Slot, is_aggregator: bool) -> BeaconCommitteeSubscription {
+        BeaconCommitteeSubscription {
+            validator_index: 1,
+            committee_index: 2,
+            committees_at_slot: 4,
+            slot,
+            is_aggregator,
+        }
+    }
+
+    #[test]
+    fn update_returns_only_new_or_changed_subscriptions() {
+        let mut subscriptions = BeaconCommitteeSubscriptions::default();
+
+        assert_eq!(
+            subscriptions.update(10, [subscription(12, false)]),
+            [subscription(12, false)],
+        );
+        assert_eq!(subscriptions.update(10, [subscription(12, false)]), []);
+        assert_eq!(
+            subscriptions.update(10, [subscription(12, true)]),
+            [subscription(12, true)],
+        );
+        assert_eq!(subscriptions.update(11, [subscription(12, false)]), []);
+        assert_eq!(subscriptions.update(11, [subscription(10, true)]), []);
+
+        assert_eq!(
+            subscriptions.at_slots(12..=12).collect::<Vec<_>>(),
+            [subscription(12, true)],
+        );
+    }
+
+    #[test]
+    fn expired_subscriptions_are_discarded() {
+        let mut subscriptions = BeaconCommitteeSubscriptions::default();
+
+        subscriptions.update(0, [subscription(3, false), subscription(5, true)]);
+        subscriptions.discard_expired_subscriptions(4);
+
+        assert_eq!(
+            subscriptions.at_slots(0..=10).collect::<Vec<_>>(),
+            [subscription(5, true)],
+        );
     }
 }
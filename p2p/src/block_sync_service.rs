@@ -184,6 +184,10 @@ impl<P: Preset> BlockSyncService<P> {
             select! {
                 _ = interval.select_next_some() => {
                     self.request_blobs_and_blocks_if_ready()?;
+
+                    if self.back_sync.as_ref().is_some_and(BackSync::archiver_retry_due) {
+                        self.try_to_spawn_back_sync_states_archiver()?;
+                    }
                 },
 
                 message = match self.archiver_to_sync_rx.as_mut() {
@@ -212,6 +216,16 @@ impl<P: Preset> BlockSyncService<P> {
                             }
                         }
                     }
+                    ArchiverToSync::BackSyncStatesArchivalFailed => {
+                        features::log!(
+                            DebugP2p,
+                            "received back sync states archival failed message",
+                        );
+
+                        if let Some(back_sync) = self.back_sync.as_mut() {
+                            back_sync.on_archiver_failed();
+                        }
+                    }
                 },
 
                 message = match self.fork_choice_to_sync_rx.as_mut() {
@@ -276,8 +290,8 @@ impl<P: Preset> BlockSyncService<P> {
                         P2pToSync::BlockNeeded(block_root, peer_id) => {
                             self.request_needed_block(block_root, peer_id)?;
                         }
-                        P2pToSync::RequestedBlobSidecar(blob_sidecar, block_seen, peer_id) => {
-                            self.controller.on_requested_blob_sidecar(blob_sidecar, block_seen, peer_id);
+                        P2pToSync::RequestedBlobSidecars(blob_sidecars, peer_id) => {
+                            self.controller.on_requested_blob_sidecars(blob_sidecars, peer_id);
                         }
                         P2pToSync::RequestedBlock((block, peer_id, request_id)) => {
                             match self
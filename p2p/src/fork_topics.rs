@@ -0,0 +1,171 @@
+use enum_iterator::Sequence as _;
+use helper_functions::misc;
+use types::{config::Config, nonstandard::Phase, phase0::primitives::Slot, preset::Preset};
+
+/// Number of epochs before a new phase to subscribe to its topics.
+///
+/// The behavior is specified in the [Networking specification] but the exact number is not:
+/// > In advance of the fork, a node SHOULD subscribe to the post-fork variants of the topics.
+///
+/// A whole epoch gives the gossip mesh for the new topics time to form
+/// even if some slots before the fork are missed.
+///
+/// [Networking specification]: https://github.com/ethereum/consensus-specs/blob/9839ed49346a85f95af4f8b0cb9c4d98b2308af8/specs/altair/p2p-interface.md#transitioning-the-gossip
+const NEW_PHASE_TOPICS_LOOKAHEAD_EPOCHS: u64 = 1;
+
+/// Number of epochs to remain subscribed to the topics of previous phases as defined in:
+/// <https://github.com/ethereum/consensus-specs/blob/9839ed49346a85f95af4f8b0cb9c4d98b2308af8/specs/altair/p2p-interface.md#transitioning-the-gossip>
+const OLD_PHASE_TOPICS_REMAIN_EPOCHS: u64 = 2;
+
+/// Decides when to switch gossip topics around forks.
+///
+/// Transitions are tracked per phase instead of being tied to exact slots so that they are not
+/// missed when the node is started or skips slots shortly before or after a fork.
+pub struct ForkTopics {
+    // The last phase whose topics were subscribed to in advance.
+    next_phase_topics_subscribed: Option<Phase>,
+    // The last phase for which topics of previous phases were unsubscribed from.
+    old_phase_topics_unsubscribed: Option<Phase>,
+}
+
+impl ForkTopics {
+    // Topics of previous phases are not subscribed to on startup.
+    // There is nothing to unsubscribe from unless the node was started before a fork.
+    #[must_use]
+    pub const fn new(current_phase: Phase) -> Self {
+        Self {
+            next_phase_topics_subscribed: None,
+            old_phase_topics_unsubscribed: Some(current_phase),
+        }
+    }
+
+    /// Returns the next phase if its topics should be subscribed to at `slot`.
+    ///
+    /// Each phase is returned at most once.
+    pub fn phase_to_subscribe<P: Preset>(&mut self, config: &Config, slot: Slot) -> Option<Phase> {
+        let next_phase = config.next_phase_at_slot::<P>(slot)?;
+        let epoch = misc::compute_epoch_at_slot::<P>(slot);
+
+        if epoch + NEW_PHASE_TOPICS_LOOKAHEAD_EPOCHS < config.fork_epoch(next_phase)
+            || self.next_phase_topics_subscribed >= Some(next_phase)
+        {
+            return None;
+        }
+
+        self.next_phase_topics_subscribed = Some(next_phase);
+
+        Some(next_phase)
+    }
+
+    /// Returns the current phase if topics of all other phases should be unsubscribed from at
+    /// `slot`.
+    ///
+    /// Each phase is returned at most once.
+    pub fn phase_to_keep<P: Preset>(&mut self, config: &Config, slot: Slot) -> Option<Phase> {
+        let phase = config.phase_at_slot::<P>(slot);
+        let epoch = misc::compute_epoch_at_slot::<P>(slot);
+
+        if Some(phase) <= Phase::first()
+            || self.old_phase_topics_unsubscribed >= Some(phase)
+            || config.fork_epoch(phase) + OLD_PHASE_TOPICS_REMAIN_EPOCHS > epoch
+        {
+            return None;
+        }
+
+        self.old_phase_topics_unsubscribed = Some(phase);
+
+        Some(phase)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use types::{phase0::consts::FAR_FUTURE_EPOCH, preset::Minimal};
+
+    use super::*;
+
+    // Slots per epoch in `Minimal` is 8.
+    fn config() -> Config {
+        Config {
+            altair_fork_epoch: 4,
+            bellatrix_fork_epoch: 6,
+            capella_fork_epoch: FAR_FUTURE_EPOCH,
+            deneb_fork_epoch: FAR_FUTURE_EPOCH,
+            ..Config::minimal()
+        }
+    }
+
+    fn transitions(
+        fork_topics: &mut ForkTopics,
+        slots: impl IntoIterator<Item = Slot>,
+    ) -> Vec<(Slot, Option<Phase>, Option<Phase>)> {
+        let config = config();
+
+        slots
+            .into_iter()
+            .filter_map(|slot| {
+                let subscribe = fork_topics.phase_to_subscribe::<Minimal>(&config, slot);
+                let keep = fork_topics.phase_to_keep::<Minimal>(&config, slot);
+                (subscribe.is_some() || keep.is_some()).then_some((slot, subscribe, keep))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn topics_switch_once_per_fork_when_every_slot_is_processed() {
+        let mut fork_topics = ForkTopics::new(Phase::Phase0);
+
+        assert_eq!(
+            transitions(&mut fork_topics, 0..100),
+            [
+                // Epoch 3 is one epoch before Altair.
+                (24, Some(Phase::Altair), None),
+                // Epoch 5 is one epoch before Bellatrix.
+                (40, Some(Phase::Bellatrix), None),
+                // Epoch 6 is 2 epochs after Altair, but Bellatrix has started by then.
+                // Epoch 8 is 2 epochs after Bellatrix.
+                (64, None, Some(Phase::Bellatrix)),
+            ],
+        );
+    }
+
+    #[test]
+    fn topics_switch_when_slots_at_boundaries_are_skipped() {
+        let mut fork_topics = ForkTopics::new(Phase::Phase0);
+
+        assert_eq!(
+            transitions(&mut fork_topics, [20, 27, 33, 47, 50, 70, 71]),
+            [
+                (27, Some(Phase::Altair), None),
+                (47, Some(Phase::Bellatrix), None),
+                (70, None, Some(Phase::Bellatrix)),
+            ],
+        );
+    }
+
+    #[test]
+    fn node_started_after_fork_does_not_unsubscribe_from_current_topics() {
+        let mut fork_topics = ForkTopics::new(Phase::Altair);
+
+        assert_eq!(
+            transitions(&mut fork_topics, 33..100),
+            [
+                (40, Some(Phase::Bellatrix), None),
+                (64, None, Some(Phase::Bellatrix)),
+            ],
+        );
+    }
+
+    #[test]
+    fn node_started_in_lookahead_subscribes_immediately() {
+        let mut fork_topics = ForkTopics::new(Phase::Phase0);
+
+        assert_eq!(
+            transitions(&mut fork_topics, 30..48),
+            [
+                (30, Some(Phase::Altair), None),
+                (40, Some(Phase::Bellatrix), None),
+            ],
+        );
+    }
+}
@@ -0,0 +1,133 @@
+use core::time::Duration;
+use std::{collections::VecDeque, time::Instant};
+
+// The lowest number of mesh peers gossipsub tries to keep on each subscribed topic (`mesh_n_low`).
+// Own messages published to smaller meshes rely on fanout and flood publishing to reach the network.
+pub const MIN_PUBLISH_MESH_PEERS: usize = 6;
+
+pub const PUBLISH_RETRY_INTERVAL: Duration = Duration::from_millis(500);
+
+// Attestations and blocks are only useful for a few seconds after they are produced.
+// 8 attempts at 500 ms intervals keep retrying for most of the first third of a mainnet slot.
+const MAX_PUBLISH_ATTEMPTS: usize = 8;
+
+pub enum PublishReadiness {
+    Ready,
+    // The mesh is too small, but other peers subscribed to the topic can still receive the message.
+    SparseMesh,
+    // No peers are subscribed to the topic. Publishing would fail with `InsufficientPeers`.
+    NoPeers,
+}
+
+impl PublishReadiness {
+    #[must_use]
+    pub const fn new(mesh_peers: usize, topic_peers: usize) -> Self {
+        if mesh_peers >= MIN_PUBLISH_MESH_PEERS {
+            Self::Ready
+        } else if topic_peers > 0 {
+            Self::SparseMesh
+        } else {
+            Self::NoPeers
+        }
+    }
+}
+
+/// Own messages that could not be published because no peers were subscribed to their topics.
+///
+/// Freshly started nodes may not have formed meshes yet when their validators start performing
+/// duties. Messages are retried every [`PUBLISH_RETRY_INTERVAL`] until peers appear on the topic or
+/// `MAX_PUBLISH_ATTEMPTS` attempts have been made.
+pub struct PublishRetries<T> {
+    pending: VecDeque<PendingPublish<T>>,
+}
+
+struct PendingPublish<T> {
+    message: T,
+    attempts: usize,
+    retry_at: Instant,
+}
+
+impl<T> Default for PublishRetries<T> {
+    fn default() -> Self {
+        Self {
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+impl<T> PublishRetries<T> {
+    /// Schedules another attempt to publish `message`.
+    ///
+    /// `attempts` is the number of attempts already made.
+    /// Returns the message back if no more attempts should be scheduled.
+    pub fn schedule(&mut self, message: T, attempts: usize, now: Instant) -> Result<(), T> {
+        if attempts >= MAX_PUBLISH_ATTEMPTS {
+            return Err(message);
+        }
+
+        self.pending.push_back(PendingPublish {
+            message,
+            attempts,
+            retry_at: now + PUBLISH_RETRY_INTERVAL,
+        });
+
+        Ok(())
+    }
+
+    /// Removes messages due for another attempt along with the number of attempts already made.
+    pub fn take_due(&mut self, now: Instant) -> Vec<(T, usize)> {
+        let mut due = vec![];
+
+        // Messages are scheduled with the same delay, so `pending` is ordered by `retry_at`.
+        while self
+            .pending
+            .front()
+            .is_some_and(|pending| pending.retry_at <= now)
+        {
+            if let Some(PendingPublish {
+                message, attempts, ..
+            }) = self.pending.pop_front()
+            {
+                due.push((message, attempts));
+            }
+        }
+
+        due
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retries_messages_after_interval_until_attempts_run_out() {
+        let mut retries = PublishRetries::default();
+        let start = Instant::now();
+
+        assert_eq!(retries.schedule("block", 1, start), Ok(()));
+        assert_eq!(
+            retries.schedule("attestation", 2, start + PUBLISH_RETRY_INTERVAL / 2),
+            Ok(()),
+        );
+
+        assert_eq!(retries.take_due(start), []);
+        assert_eq!(
+            retries.take_due(start + PUBLISH_RETRY_INTERVAL),
+            [("block", 1)]
+        );
+        assert_eq!(retries.pending.len(), 1);
+
+        assert_eq!(
+            retries.take_due(start + PUBLISH_RETRY_INTERVAL * 2),
+            [("attestation", 2)],
+        );
+        assert!(retries.pending.is_empty());
+
+        assert_eq!(
+            retries.schedule("block", MAX_PUBLISH_ATTEMPTS, start),
+            Err("block"),
+        );
+        assert!(retries.pending.is_empty());
+    }
+}
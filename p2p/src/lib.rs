@@ -6,7 +6,7 @@ pub use crate::{
     block_verification_pool::BlockVerificationPool,
     messages::{
         ApiToP2p, P2pToSlasher, P2pToValidator, SubnetServiceToP2p, SyncToApi, SyncToMetrics,
-        ToSubnetService, ValidatorToP2p,
+        ToSubnetService, ValidatorToP2p, SLASHER_CHANNEL,
     },
     misc::{BeaconCommitteeSubscription, SyncCommitteeSubscription},
     network::{Channels, Network},
@@ -20,11 +20,14 @@ mod back_sync;
 mod beacon_committee_subscriptions;
 mod block_sync_service;
 mod block_verification_pool;
+mod fork_topics;
+mod gossip_publish;
 mod messages;
 mod misc;
 mod network;
 mod network_api;
 mod range_and_root_requests;
+mod subnet_peers;
 mod subnet_service;
 mod sync_committee_subnets;
 mod sync_manager;
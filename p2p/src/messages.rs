@@ -3,7 +3,7 @@ use std::{collections::BTreeMap, sync::Arc};
 use anyhow::Result;
 use bls::PublicKeyBytes;
 use eth2_libp2p::{
-    rpc::{GoodbyeReason, StatusMessage},
+    rpc::{GoodbyeReason, RPCResponseErrorCode, StatusMessage},
     types::{EnrForkId, GossipKind},
     GossipId, GossipTopic, MessageAcceptance, NetworkEvent, PeerAction, PeerId, PeerRequestId,
     PubsubMessage, ReportSource, Request, Response, Subnet, SubnetDiscovery,
@@ -35,15 +35,21 @@ use crate::{
     network_api::{NodeIdentity, NodePeer, NodePeerCount, NodePeersQuery},
 };
 
+// Labels of the gossip channel metrics.
+pub const ATTESTATION_VERIFIER_CHANNEL: &str = "attestation_verifier";
+pub const SLASHER_CHANNEL: &str = "slasher";
+
 pub enum P2pToAttestationVerifier<P: Preset> {
     GossipAggregateAndProof(Box<SignedAggregateAndProof<P>>, GossipId),
     GossipAttestation(Arc<Attestation<P>>, SubnetId, GossipId),
 }
 
 impl<P: Preset> P2pToAttestationVerifier<P> {
-    pub fn send(self, tx: &UnboundedSender<Self>) {
-        if tx.unbounded_send(self).is_err() {
-            debug!("send to attestation verifier failed because the receiver was dropped");
+    #[must_use]
+    pub fn into_gossip_id(self) -> GossipId {
+        match self {
+            Self::GossipAggregateAndProof(_, gossip_id)
+            | Self::GossipAttestation(_, _, gossip_id) => gossip_id,
         }
     }
 }
@@ -57,7 +63,7 @@ pub enum P2pToSync<P: Preset> {
     StatusPeer(PeerId),
     BlobsNeeded(Vec<BlobIdentifier>, Slot, Option<PeerId>),
     BlockNeeded(H256, Option<PeerId>),
-    RequestedBlobSidecar(Arc<BlobSidecar<P>>, bool, PeerId),
+    RequestedBlobSidecars(Vec<(Arc<BlobSidecar<P>>, bool)>, PeerId),
     RequestedBlock((Arc<SignedBeaconBlock<P>>, PeerId, RequestId)),
     BlobsByRangeRequestFinished(RequestId),
     BlobsByRootChunkReceived(BlobIdentifier, PeerId, RequestId),
@@ -141,6 +147,7 @@ impl SyncToP2p {
 
 pub enum ArchiverToSync {
     BackSyncStatesArchived,
+    BackSyncStatesArchivalFailed,
 }
 
 impl ArchiverToSync {
@@ -195,20 +202,13 @@ pub enum P2pToSlasher<P: Preset> {
     Block(Arc<SignedBeaconBlock<P>>),
 }
 
-impl<P: Preset> P2pToSlasher<P> {
-    pub fn send(self, tx: &UnboundedSender<Self>) {
-        if tx.unbounded_send(self).is_err() {
-            debug!("send to slasher failed because the receiver was dropped");
-        }
-    }
-}
-
 pub enum ServiceInboundMessage<P: Preset> {
     DiscoverSubnetPeers(Vec<SubnetDiscovery>),
     GoodbyePeer(PeerId, GoodbyeReason, ReportSource),
     Publish(PubsubMessage<P>),
     ReportPeer(PeerId, PeerAction, ReportSource, &'static str),
     ReportMessageValidationResult(GossipId, MessageAcceptance),
+    SendErrorResponse(PeerId, PeerRequestId, RPCResponseErrorCode, String),
     SendRequest(PeerId, RequestId, Request),
     SendResponse(PeerId, PeerRequestId, Box<Response<P>>),
     Subscribe(GossipTopic),
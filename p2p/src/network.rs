@@ -6,25 +6,29 @@ use std::{
 };
 
 use anyhow::{bail, Result};
+use clock::{ClockDrift, ClockSkew};
 use dedicated_executor::DedicatedExecutor;
-use enum_iterator::Sequence as _;
 use eth1_api::RealController;
 use eth2_libp2p::{
     rpc::{
         methods::{
             BlobsByRangeRequest, BlobsByRootRequest, BlocksByRangeRequest, BlocksByRootRequest,
         },
-        GoodbyeReason, StatusMessage,
+        GoodbyeReason, RPCResponseErrorCode, StatusMessage,
     },
     service::Network as Service,
     types::{core_topics_to_subscribe, EnrForkId, ForkContext, GossipEncoding},
-    Context, GossipId, GossipTopic, MessageAcceptance, MessageId, NetworkConfig, NetworkEvent,
-    NetworkGlobals, PeerAction, PeerId, PeerRequestId, PubsubMessage, ReportSource, Request,
-    Response, ShutdownReason, Subnet, SubnetDiscovery, SyncInfo, SyncStatus, TaskExecutor,
+    Context, GossipId, GossipTopic, IdentTopic, MessageAcceptance, MessageId, NetworkConfig,
+    NetworkEvent, NetworkGlobals, PeerAction, PeerId, PeerRequestId, PubsubMessage, ReportSource,
+    Request, Response, ShutdownReason, Subnet, SubnetDiscovery, SyncInfo, SyncStatus, TaskExecutor,
+    TopicHash,
 };
 use fork_choice_control::P2pMessage;
 use futures::{
-    channel::mpsc::{self, Receiver, UnboundedReceiver, UnboundedSender},
+    channel::{
+        mpsc::{self, Receiver, Sender, UnboundedReceiver, UnboundedSender},
+        oneshot::Receiver as OneshotReceiver,
+    },
     future::FutureExt as _,
     select,
     stream::StreamExt as _,
@@ -38,63 +42,63 @@ use slog::{o, Drain as _, Logger};
 use slog_stdlog::StdLog;
 use std_ext::ArcExt as _;
 use thiserror::Error;
+use typenum::Unsigned as _;
 use types::{
-    altair::containers::{SignedContributionAndProof, SyncCommitteeMessage},
+    altair::{
+        consts::SyncCommitteeSubnetCount,
+        containers::{SignedContributionAndProof, SyncCommitteeMessage},
+    },
     capella::containers::SignedBlsToExecutionChange,
     combined::SignedBeaconBlock,
     deneb::containers::{BlobIdentifier, BlobSidecar},
-    nonstandard::{Phase, WithStatus},
+    nonstandard::WithStatus,
     phase0::{
-        consts::{FAR_FUTURE_EPOCH, GENESIS_EPOCH},
+        consts::{AttestationSubnetCount, FAR_FUTURE_EPOCH, GENESIS_EPOCH, GENESIS_SLOT},
         containers::{
             Attestation, AttesterSlashing, ProposerSlashing, SignedAggregateAndProof,
             SignedVoluntaryExit,
         },
-        primitives::{Epoch, ForkDigest, NodeId, Slot, SubnetId, H256},
+        primitives::{Epoch, ForkDigest, NodeId, Slot, SubnetId, UnixSeconds, H256},
     },
     preset::Preset,
     traits::{BeaconState as _, SignedBeaconBlock as _},
 };
 
 use crate::{
+    fork_topics::ForkTopics,
+    gossip_publish::{PublishReadiness, PublishRetries, PUBLISH_RETRY_INTERVAL},
     messages::{
         ApiToP2p, P2pToAttestationVerifier, P2pToSlasher, P2pToSync, P2pToValidator,
         ServiceInboundMessage, ServiceOutboundMessage, SubnetServiceToP2p, SyncToP2p,
-        ValidatorToP2p,
+        ValidatorToP2p, ATTESTATION_VERIFIER_CHANNEL, SLASHER_CHANNEL,
     },
     misc::{AttestationSubnetActions, RequestId, SubnetPeerDiscovery, SyncCommitteeSubnetAction},
+    subnet_peers::{SubnetPeers, TARGET_SUBNET_PEERS},
     upnp::PortMappings,
 };
 
 const MAX_FOR_DOS_PREVENTION: u64 = 64;
 
-/// Number of slots before a new phase to subscribe to its topics.
-///
-/// The number 5 was chosen arbitrarily.
-/// The behavior is specified in the [Networking specification] but the exact number is not:
-/// > In advance of the fork, a node SHOULD subscribe to the post-fork variants of the topics.
-///
-/// Lighthouse uses the number 2 with no explanation why:
-/// - <https://github.com/sigp/lighthouse/blob/bf533c8e42cc73c35730e285c21df8add0195369/beacon_node/network/src/service.rs#L42>
-/// - <https://github.com/sigp/lighthouse/pull/2532>
-///
-/// [Networking specification]: https://github.com/ethereum/consensus-specs/blob/9839ed49346a85f95af4f8b0cb9c4d98b2308af8/specs/altair/p2p-interface.md#transitioning-the-gossip
-const NEW_PHASE_TOPICS_ADVANCE_SLOTS: u64 = 5;
-
-/// Number of epochs to remain subscribed to the topics of previous phases as defined in:
-/// <https://github.com/ethereum/consensus-specs/blob/9839ed49346a85f95af4f8b0cb9c4d98b2308af8/specs/altair/p2p-interface.md#transitioning-the-gossip>
-const OLD_PHASE_TOPICS_REMAIN_EPOCHS: u64 = 2;
+// Batches larger than this are split to limit how long sidecars are held back and how much memory
+// they take up while a long `BlobsByRange` response is being received.
+const REQUESTED_BLOB_SIDECARS_BATCH_SIZE: usize = 32;
+
+struct RequestedBlobSidecars<P: Preset> {
+    blob_sidecars: Vec<(Arc<BlobSidecar<P>>, bool)>,
+    peer_id: PeerId,
+}
 
 pub struct Channels<P: Preset> {
     pub api_to_p2p_rx: UnboundedReceiver<ApiToP2p<P>>,
     pub fork_choice_to_p2p_rx: UnboundedReceiver<P2pMessage<P>>,
+    pub graceful_shutdown_rx: OneshotReceiver<()>,
     pub pool_to_p2p_rx: UnboundedReceiver<PoolToP2pMessage>,
-    pub p2p_to_attestation_verifier_tx: UnboundedSender<P2pToAttestationVerifier<P>>,
+    pub p2p_to_attestation_verifier_tx: Sender<P2pToAttestationVerifier<P>>,
     pub p2p_to_sync_tx: UnboundedSender<P2pToSync<P>>,
     pub p2p_to_validator_tx: UnboundedSender<P2pToValidator<P>>,
     pub sync_to_p2p_rx: UnboundedReceiver<SyncToP2p>,
     pub validator_to_p2p_rx: UnboundedReceiver<ValidatorToP2p<P>>,
-    pub network_to_slasher_tx: Option<UnboundedSender<P2pToSlasher<P>>>,
+    pub network_to_slasher_tx: Option<Sender<P2pToSlasher<P>>>,
     pub subnet_service_to_p2p_rx: UnboundedReceiver<SubnetServiceToP2p>,
 }
 
@@ -103,6 +107,9 @@ pub struct Network<P: Preset> {
     network_globals: Arc<NetworkGlobals>,
     received_blob_sidecars: HashMap<BlobIdentifier, Slot>,
     received_block_roots: HashMap<H256, Slot>,
+    // Sidecars from `BlobsByRange` and `BlobsByRoot` responses are passed to fork choice in batches
+    // so that their KZG proofs can be verified together.
+    requested_blob_sidecars: HashMap<RequestId, RequestedBlobSidecars<P>>,
     controller: RealController<P>,
     channels: Channels<P>,
     dedicated_executor: Arc<DedicatedExecutor>,
@@ -113,7 +120,10 @@ pub struct Network<P: Preset> {
     //                      (or whatever replaces it). Fork digests can easily be computed from a
     //                      state obtained from one of the controllers.
     fork_context: Arc<ForkContext>,
+    fork_topics: ForkTopics,
     metrics: Option<Arc<Metrics>>,
+    clock_drift: Arc<ClockDrift>,
+    subnet_peers: SubnetPeers,
     network_to_service_tx: UnboundedSender<ServiceInboundMessage<P>>,
     service_to_network_rx: UnboundedReceiver<ServiceOutboundMessage<P>>,
     shutdown_rx: Receiver<ShutdownReason>,
@@ -138,6 +148,8 @@ impl<P: Preset> Network<P> {
         sync_committee_agg_pool: Arc<SyncCommitteeAggPool<P>>,
         bls_to_execution_change_pool: Arc<BlsToExecutionChangePool>,
         metrics: Option<Arc<Metrics>>,
+        clock_drift: Arc<ClockDrift>,
+        subnet_peer_discovery_delay: Duration,
         libp2p_registry: Option<&mut Registry>,
     ) -> Result<Self> {
         let chain_config = controller.chain_config().as_ref();
@@ -177,19 +189,32 @@ impl<P: Preset> Network<P> {
         let (network_to_service_tx, network_to_service_rx) = mpsc::unbounded();
         let (service_to_network_tx, service_to_network_rx) = mpsc::unbounded();
 
-        run_network_service(service, network_to_service_rx, service_to_network_tx);
+        run_network_service(
+            service,
+            network_globals.clone_arc(),
+            controller.clone_arc(),
+            metrics.clone(),
+            network_to_service_rx,
+            service_to_network_tx,
+        );
+
+        let fork_topics = ForkTopics::new(fork_context.current_fork());
 
         let network = Self {
             network_globals,
             received_blob_sidecars: HashMap::new(),
             received_block_roots: HashMap::new(),
+            requested_blob_sidecars: HashMap::new(),
             controller,
             channels,
             dedicated_executor,
             sync_committee_agg_pool,
             bls_to_execution_change_pool,
             fork_context,
+            fork_topics,
             metrics,
+            clock_drift,
+            subnet_peers: SubnetPeers::new(subnet_peer_discovery_delay),
             network_to_service_tx,
             service_to_network_rx,
             shutdown_rx,
@@ -280,6 +305,7 @@ impl<P: Preset> Network<P> {
                     match message {
                         P2pMessage::Slot(slot) => {
                             self.on_slot(slot);
+                            self.track_subnet_peers();
                             self.track_collection_metrics();
                         }
                         P2pMessage::Accept(gossip_id) => {
@@ -343,8 +369,9 @@ impl<P: Preset> Network<P> {
                             P2pToSync::HeadState(state).send(&self.channels.p2p_to_sync_tx);
                         }
                         P2pMessage::ReverifyGossipAttestation(attestation, subnet_id, gossip_id) => {
-                            P2pToAttestationVerifier::GossipAttestation(attestation, subnet_id, gossip_id)
-                                .send(&self.channels.p2p_to_attestation_verifier_tx);
+                            self.send_to_attestation_verifier(
+                                P2pToAttestationVerifier::GossipAttestation(attestation, subnet_id, gossip_id),
+                            );
                         }
                     }
                 },
@@ -433,6 +460,13 @@ impl<P: Preset> Network<P> {
                     }
                 },
 
+                result = &mut self.channels.graceful_shutdown_rx => {
+                    // The sender being dropped without sending means no shutdown was requested.
+                    if result.is_ok() {
+                        self.disconnect_before_shutdown();
+                    }
+                },
+
                 shutdown_reason = self.shutdown_rx.select_next_some() => match shutdown_reason {
                     ShutdownReason::Failure(message) => {
                         bail!("eth2_libp2p initiated shutdown: {message}");
@@ -442,7 +476,50 @@ impl<P: Preset> Network<P> {
         }
     }
 
-    fn on_slot(&self, slot: Slot) {
+    // Keep running after this so that the Goodbye messages are actually delivered.
+    // The runtime exits once the rest of the application has shut down.
+    fn disconnect_before_shutdown(&self) {
+        // `unsubscribe` locks `gossipsub_subscriptions` for writing.
+        // Read current subscriptions before unsubscribing to avoid a deadlock.
+        let subscribed_topics = self
+            .network_globals
+            .gossipsub_subscriptions
+            .read()
+            .iter()
+            .cloned()
+            .collect::<Vec<_>>();
+
+        for topic in subscribed_topics {
+            ServiceInboundMessage::Unsubscribe(topic).send(&self.network_to_service_tx);
+        }
+
+        let connected_peer_ids = self
+            .network_globals
+            .peers
+            .read()
+            .connected_peer_ids()
+            .copied()
+            .collect::<Vec<_>>();
+
+        self.log(
+            Level::Info,
+            format_args!(
+                "sending Goodbye to {} peers before shutdown",
+                connected_peer_ids.len(),
+            ),
+        );
+
+        for peer_id in connected_peer_ids {
+            ServiceInboundMessage::GoodbyePeer(
+                peer_id,
+                GoodbyeReason::ClientShutdown,
+                ReportSource::Processor,
+            )
+            .send(&self.network_to_service_tx);
+        }
+    }
+
+    fn on_slot(&mut self, slot: Slot) {
         P2pToSync::Slot(slot).send(&self.channels.p2p_to_sync_tx);
 
         let chain_config = self.controller.chain_config();
@@ -464,35 +541,28 @@ impl<P: Preset> Network<P> {
         }
 
         // Subscribe to the topics of the next phase.
-        if let Some(next_phase) = chain_config.next_phase_at_slot::<P>(slot) {
-            let next_phase_slot = chain_config
-                .fork_slot::<P>(next_phase)
-                .expect("Config::next_phase_at_slot ensures that the phase is enabled");
-
-            if slot + NEW_PHASE_TOPICS_ADVANCE_SLOTS == next_phase_slot {
-                if let Some(fork_digest) = self.fork_context.to_context_bytes(next_phase) {
-                    self.log(
-                        Level::Info,
-                        format_args!("subscribing to new topics from {next_phase}"),
-                    );
+        if let Some(next_phase) = self.fork_topics.phase_to_subscribe::<P>(chain_config, slot) {
+            if let Some(fork_digest) = self.fork_context.to_context_bytes(next_phase) {
+                self.log(
+                    Level::Info,
+                    format_args!(
+                        "subscribing to new topics from {next_phase} (fork epoch: {})",
+                        chain_config.fork_epoch(next_phase),
+                    ),
+                );
 
-                    ServiceInboundMessage::SubscribeNewForkTopics(next_phase, fork_digest)
-                        .send(&self.network_to_service_tx);
-                }
+                ServiceInboundMessage::SubscribeNewForkTopics(next_phase, fork_digest)
+                    .send(&self.network_to_service_tx);
             }
         }
 
-        if Some(phase_by_slot) > Phase::first() && misc::is_epoch_start::<P>(slot) {
-            let epoch = misc::compute_epoch_at_slot::<P>(slot);
-
-            // Unsubscribe from the topics of previous phases.
-            if chain_config.fork_epoch(phase_by_slot) + OLD_PHASE_TOPICS_REMAIN_EPOCHS == epoch {
-                if let Some(fork_digest) = self.fork_context.to_context_bytes(phase_by_slot) {
-                    self.log(Level::Info, "unsubscribing from old topics");
+        // Unsubscribe from the topics of previous phases.
+        if let Some(phase) = self.fork_topics.phase_to_keep::<P>(chain_config, slot) {
+            if let Some(fork_digest) = self.fork_context.to_context_bytes(phase) {
+                self.log(Level::Info, "unsubscribing from old topics");
 
-                    ServiceInboundMessage::UnsubscribeFromForkTopicsExcept(fork_digest)
-                        .send(&self.network_to_service_tx);
-                }
+                ServiceInboundMessage::UnsubscribeFromForkTopicsExcept(fork_digest)
+                    .send(&self.network_to_service_tx);
             }
         }
     }
@@ -545,6 +615,42 @@ impl<P: Preset> Network<P> {
         self.publish(PubsubMessage::BeaconBlock(beacon_block));
     }
 
+    fn buffer_requested_blob_sidecar(
+        &mut self,
+        request_id: RequestId,
+        peer_id: PeerId,
+        blob_sidecar: Arc<BlobSidecar<P>>,
+        block_seen: bool,
+    ) {
+        let RequestedBlobSidecars {
+            blob_sidecars,
+            peer_id: _,
+        } = self
+            .requested_blob_sidecars
+            .entry(request_id)
+            .or_insert_with(|| RequestedBlobSidecars {
+                blob_sidecars: vec![],
+                peer_id,
+            });
+
+        blob_sidecars.push((blob_sidecar, block_seen));
+
+        if blob_sidecars.len() >= REQUESTED_BLOB_SIDECARS_BATCH_SIZE {
+            self.flush_requested_blob_sidecars(request_id);
+        }
+    }
+
+    fn flush_requested_blob_sidecars(&mut self, request_id: RequestId) {
+        if let Some(RequestedBlobSidecars {
+            blob_sidecars,
+            peer_id,
+        }) = self.requested_blob_sidecars.remove(&request_id)
+        {
+            P2pToSync::RequestedBlobSidecars(blob_sidecars, peer_id)
+                .send(&self.channels.p2p_to_sync_tx);
+        }
+    }
+
     fn publish_blob_sidecar(&self, blob_sidecar: Arc<BlobSidecar<P>>) {
         let subnet_id = misc::compute_subnet_for_blob_sidecar(blob_sidecar.index);
         let blob_identifier: BlobIdentifier = blob_sidecar.as_ref().into();
@@ -862,6 +968,10 @@ impl<P: Preset> Network<P> {
                     Level::Warn,
                     format_args!("request {id:?} to peer {peer_id} failed: {error}"),
                 );
+
+                // Sidecars received before the failure are still worth validating.
+                self.flush_requested_blob_sidecars(id);
+
                 P2pToSync::RequestFailed(peer_id).send(&self.channels.p2p_to_sync_tx);
             }
             NetworkEvent::RequestReceived {
@@ -983,6 +1093,36 @@ impl<P: Preset> Network<P> {
 
         self.dedicated_executor
             .spawn(async move {
+                let earliest_available_slot = controller.earliest_available_slot()?;
+
+                // Respond the same way as other clients do while they are backfilling.
+                // An empty response would make the peer think the range contains no blocks.
+                if end_slot <= earliest_available_slot {
+                    log(
+                        Level::Debug,
+                        connected_peers,
+                        target_peers,
+                        format_args!(
+                            "requested BeaconBlocksByRange range is not available \
+                             (peer_request_id: {peer_request_id:?}, peer_id: {peer_id}, \
+                             start_slot: {start_slot}, earliest_available_slot: {earliest_available_slot})",
+                        ),
+                    );
+
+                    ServiceInboundMessage::SendErrorResponse(
+                        peer_id,
+                        peer_request_id,
+                        RPCResponseErrorCode::ResourceUnavailable,
+                        format!("blocks before slot {earliest_available_slot} are not available"),
+                    )
+                    .send(&network_to_service_tx);
+
+                    return Ok(());
+                }
+
+                // Serve the part of the range that is available if the request only overlaps it.
+                let start_slot = start_slot.max(earliest_available_slot);
+
                 let blocks = controller.blocks_by_range(start_slot..end_slot)?;
 
                 for block_with_root in blocks {
@@ -1276,8 +1416,12 @@ impl<P: Preset> Network<P> {
                         .received_block_roots
                         .contains_key(&blob_identifier.block_root);
 
-                    P2pToSync::RequestedBlobSidecar(blob_sidecar, block_seen, peer_id)
-                        .send(&self.channels.p2p_to_sync_tx);
+                    self.buffer_requested_blob_sidecar(
+                        request_id,
+                        peer_id,
+                        blob_sidecar,
+                        block_seen,
+                    );
                 }
             }
             Response::BlobsByRange(None) => {
@@ -1285,6 +1429,8 @@ impl<P: Preset> Network<P> {
                     "peer {peer_id} terminated BlobsByRange response stream for request_id: {request_id}",
                 ));
 
+                self.flush_requested_blob_sidecars(request_id);
+
                 P2pToSync::BlobsByRangeRequestFinished(request_id)
                     .send(&self.channels.p2p_to_sync_tx);
             }
@@ -1313,8 +1459,12 @@ impl<P: Preset> Network<P> {
                         .received_block_roots
                         .contains_key(&blob_identifier.block_root);
 
-                    P2pToSync::RequestedBlobSidecar(blob_sidecar, block_seen, peer_id)
-                        .send(&self.channels.p2p_to_sync_tx);
+                    self.buffer_requested_blob_sidecar(
+                        request_id,
+                        peer_id,
+                        blob_sidecar,
+                        block_seen,
+                    );
                 }
 
                 P2pToSync::BlobsByRootChunkReceived(blob_identifier, peer_id, request_id)
@@ -1324,6 +1474,8 @@ impl<P: Preset> Network<P> {
                 self.log_with_feature(format_args!(
                     "peer {peer_id} terminated BlobsByRoot response stream for request_id: {request_id}",
                 ));
+
+                self.flush_requested_blob_sidecars(request_id);
             }
             Response::BlocksByRange(Some(block)) => {
                 self.log(
@@ -1378,9 +1530,7 @@ impl<P: Preset> Network<P> {
                     self.controller
                         .on_requested_block(block.clone_arc(), Some(peer_id));
 
-                    if let Some(network_to_slasher_tx) = &self.channels.network_to_slasher_tx {
-                        P2pToSlasher::Block(block).send(network_to_slasher_tx);
-                    }
+                    self.send_to_slasher(P2pToSlasher::Block(block));
                 }
             }
             Response::BlocksByRoot(None) => {
@@ -1430,6 +1580,8 @@ impl<P: Preset> Network<P> {
                     metrics.observe_block_duration_to_slot(block_slot_timestamp);
                 }
 
+                self.observe_block_arrival(block_slot_timestamp);
+
                 self.log(
                     Level::Info,
                     format_args!(
@@ -1437,9 +1589,7 @@ impl<P: Preset> Network<P> {
                     ),
                 );
 
-                if let Some(network_to_slasher_tx) = &self.channels.network_to_slasher_tx {
-                    P2pToSlasher::Block(beacon_block.clone_arc()).send(network_to_slasher_tx);
-                }
+                self.send_to_slasher(P2pToSlasher::Block(beacon_block.clone_arc()));
 
                 self.controller
                     .on_gossip_block(beacon_block, GossipId { source, message_id });
@@ -1483,15 +1633,19 @@ impl<P: Preset> Network<P> {
                     ),
                 );
 
-                if let Some(network_to_slasher_tx) = &self.channels.network_to_slasher_tx {
+                if self.channels.network_to_slasher_tx.is_some() {
                     let attestation = Arc::new(aggregate_and_proof.message.aggregate.clone());
-                    P2pToSlasher::Attestation(attestation).send(network_to_slasher_tx);
+                    self.send_to_slasher(P2pToSlasher::Attestation(attestation));
                 }
 
                 let gossip_id = GossipId { source, message_id };
 
-                P2pToAttestationVerifier::GossipAggregateAndProof(aggregate_and_proof, gossip_id)
-                    .send(&self.channels.p2p_to_attestation_verifier_tx);
+                self.send_to_attestation_verifier(
+                    P2pToAttestationVerifier::GossipAggregateAndProof(
+                        aggregate_and_proof,
+                        gossip_id,
+                    ),
+                );
             }
             PubsubMessage::Attestation(subnet_id, attestation) => {
                 if let Some(metrics) = self.metrics.as_ref() {
@@ -1506,14 +1660,15 @@ impl<P: Preset> Network<P> {
                     ),
                 );
 
-                if let Some(network_to_slasher_tx) = &self.channels.network_to_slasher_tx {
-                    P2pToSlasher::Attestation(attestation.clone_arc()).send(network_to_slasher_tx);
-                }
+                self.send_to_slasher(P2pToSlasher::Attestation(attestation.clone_arc()));
 
                 let gossip_id = GossipId { source, message_id };
 
-                P2pToAttestationVerifier::GossipAttestation(attestation, subnet_id, gossip_id)
-                    .send(&self.channels.p2p_to_attestation_verifier_tx);
+                self.send_to_attestation_verifier(P2pToAttestationVerifier::GossipAttestation(
+                    attestation,
+                    subnet_id,
+                    gossip_id,
+                ));
             }
             PubsubMessage::VoluntaryExit(signed_voluntary_exit) => {
                 if let Some(metrics) = self.metrics.as_ref() {
@@ -1877,6 +2032,59 @@ impl<P: Preset> Network<P> {
         }
     }
 
+    // Gossip is dropped rather than queued without bound if the attestation verifier falls behind.
+    // Dropped messages are ignored so that gossipsub does not wait for a validation result.
+    fn send_to_attestation_verifier(&mut self, message: P2pToAttestationVerifier<P>) {
+        match self
+            .channels
+            .p2p_to_attestation_verifier_tx
+            .try_send(message)
+        {
+            Ok(()) => {
+                if let Some(metrics) = self.metrics.as_ref() {
+                    metrics.inc_gossip_channel_length(ATTESTATION_VERIFIER_CHANNEL);
+                }
+            }
+            Err(error) if error.is_full() => {
+                debug!("dropping gossip message because attestation verifier channel is full");
+
+                if let Some(metrics) = self.metrics.as_ref() {
+                    metrics.register_gossip_channel_dropped_message(ATTESTATION_VERIFIER_CHANNEL);
+                }
+
+                self.report_outcome(
+                    error.into_inner().into_gossip_id(),
+                    MessageAcceptance::Ignore,
+                );
+            }
+            Err(_) => {
+                debug!("send to attestation verifier failed because the receiver was dropped");
+            }
+        }
+    }
+
+    fn send_to_slasher(&mut self, message: P2pToSlasher<P>) {
+        let Some(network_to_slasher_tx) = self.channels.network_to_slasher_tx.as_mut() else {
+            return;
+        };
+
+        match network_to_slasher_tx.try_send(message) {
+            Ok(()) => {
+                if let Some(metrics) = self.metrics.as_ref() {
+                    metrics.inc_gossip_channel_length(SLASHER_CHANNEL);
+                }
+            }
+            Err(error) if error.is_full() => {
+                debug!("dropping gossip message because slasher channel is full");
+
+                if let Some(metrics) = self.metrics.as_ref() {
+                    metrics.register_gossip_channel_dropped_message(SLASHER_CHANNEL);
+                }
+            }
+            Err(_) => debug!("send to slasher failed because the receiver was dropped"),
+        }
+    }
+
     fn report_outcome(&self, gossip_id: GossipId, message_acceptance: MessageAcceptance) {
         ServiceInboundMessage::ReportMessageValidationResult(gossip_id, message_acceptance)
             .send(&self.network_to_service_tx);
@@ -1933,6 +2141,40 @@ impl<P: Preset> Network<P> {
         self.received_block_roots.insert(block_root, slot).is_none()
     }
 
+    fn observe_block_arrival(&self, block_slot_timestamp: UnixSeconds) {
+        if let Err(error) = self.clock_drift.observe_block_arrival(block_slot_timestamp) {
+            warn!("unable to observe block arrival time: {error:?}");
+            return;
+        }
+
+        let skew = self
+            .clock_drift
+            .estimated_skew(self.controller.chain_config());
+
+        if let Some(metrics) = self.metrics.as_ref() {
+            metrics.set_clock_drift(skew.map_or(0, ClockSkew::as_millis));
+        }
+
+        let Some(skew) = skew else {
+            return;
+        };
+
+        if !self.clock_drift.should_warn(Instant::now()) {
+            return;
+        }
+
+        match skew {
+            ClockSkew::Behind(duration) => warn!(
+                "local clock appears to be behind the network by at least {duration:?} \
+                 judging by arrival times of recent blocks; check NTP configuration",
+            ),
+            ClockSkew::Ahead(duration) => warn!(
+                "local clock appears to be ahead of the network by at least {duration:?} \
+                 judging by arrival times of recent blocks; check NTP configuration",
+            ),
+        }
+    }
+
     fn register_new_received_blob_sidecar(
         &mut self,
         blob_identifier: BlobIdentifier,
@@ -1976,6 +2218,70 @@ impl<P: Preset> Network<P> {
             })
     }
 
+    fn track_subnet_peers(&mut self) {
+        let attestation_subnets = (0..AttestationSubnetCount::U64).map(Subnet::Attestation);
+        let sync_committee_subnets = (0..SyncCommitteeSubnetCount::U64).map(Subnet::SyncCommittee);
+
+        let peer_counts = {
+            let peers = self.network_globals.peers.read();
+
+            attestation_subnets
+                .chain(sync_committee_subnets)
+                .map(|subnet| (subnet, peers.good_peers_on_subnet(subnet).count()))
+                .collect::<Vec<_>>()
+        };
+
+        if let Some(metrics) = self.metrics.as_ref() {
+            metrics.set_subnet_peers_target(TARGET_SUBNET_PEERS);
+
+            for (subnet, peer_count) in &peer_counts {
+                let (subnet_type, subnet_id) = match subnet {
+                    Subnet::Attestation(subnet_id) => ("attestation", subnet_id),
+                    Subnet::SyncCommittee(subnet_id) => ("sync_committee", subnet_id),
+                };
+
+                metrics.set_subnet_peers(&[subnet_type, &subnet_id.to_string()], *peer_count);
+            }
+        }
+
+        // Peers are only needed on subnets the node participates in.
+        let subscribed_peer_counts = {
+            let subscribed_topics = self.network_globals.gossipsub_subscriptions.read();
+
+            peer_counts
+                .into_iter()
+                .filter(|(subnet, _)| {
+                    self.subnet_gossip_topic(*subnet)
+                        .is_some_and(|topic| subscribed_topics.contains(&topic))
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let subnets = self
+            .subnet_peers
+            .update(subscribed_peer_counts, Instant::now());
+
+        if subnets.is_empty() {
+            return;
+        }
+
+        self.log(
+            Level::Debug,
+            format_args!("discovering peers for subnets with too few peers: {subnets:?}"),
+        );
+
+        let subnet_discoveries = subnets
+            .into_iter()
+            .map(|subnet| SubnetDiscovery {
+                subnet,
+                min_ttl: None,
+            })
+            .collect();
+
+        ServiceInboundMessage::DiscoverSubnetPeers(subnet_discoveries)
+            .send(&self.network_to_service_tx);
+    }
+
     fn track_collection_metrics(&self) {
         if let Some(metrics) = self.metrics.as_ref() {
             let type_name = tynm::type_name::<Self>();
@@ -2011,16 +2317,36 @@ fn fork_digest(fork_context: &ForkContext) -> ForkDigest {
 
 fn run_network_service<P: Preset>(
     mut service: Service<RequestId, P>,
+    network_globals: Arc<NetworkGlobals>,
+    controller: RealController<P>,
+    metrics: Option<Arc<Metrics>>,
     mut network_to_service_rx: UnboundedReceiver<ServiceInboundMessage<P>>,
     service_to_network_tx: UnboundedSender<ServiceOutboundMessage<P>>,
 ) {
     tokio::spawn(async move {
+        let mut publish_retries = PublishRetries::default();
+        let mut publish_retry_interval = tokio::time::interval(PUBLISH_RETRY_INTERVAL);
+
         loop {
             select! {
                 network_event = service.next_event().fuse() => {
                     ServiceOutboundMessage::NetworkEvent(network_event).send(&service_to_network_tx);
                 }
 
+                _ = publish_retry_interval.tick().fuse() => {
+                    for (message, attempts) in publish_retries.take_due(Instant::now()) {
+                        publish_own_message(
+                            &mut service,
+                            &network_globals,
+                            &controller,
+                            metrics.as_ref(),
+                            &mut publish_retries,
+                            message,
+                            attempts,
+                        );
+                    }
+                }
+
                 message = network_to_service_rx.select_next_some() => {
                     match message {
                         ServiceInboundMessage::DiscoverSubnetPeers(subnet_discoveries) => {
@@ -2030,7 +2356,15 @@ fn run_network_service<P: Preset>(
                             service.goodbye_peer(&peer_id, goodbye_reason, report_source);
                         }
                         ServiceInboundMessage::Publish(message) => {
-                            service.publish(message);
+                            publish_own_message(
+                                &mut service,
+                                &network_globals,
+                                &controller,
+                                metrics.as_ref(),
+                                &mut publish_retries,
+                                message,
+                                0,
+                            );
                         }
                         ServiceInboundMessage::ReportPeer(peer_id, action, source, msg) => {
                             service.report_peer(&peer_id, action, source, msg);
@@ -2042,6 +2376,9 @@ fn run_network_service<P: Preset>(
                                 message_acceptance,
                             );
                         }
+                        ServiceInboundMessage::SendErrorResponse(peer_id, peer_request_id, error, reason) => {
+                            service.send_error_response(peer_id, peer_request_id, error, reason);
+                        }
                         ServiceInboundMessage::SendRequest(peer_id, request_id, request) => {
                             service.send_request(peer_id, request_id, request);
                         }
@@ -2076,6 +2413,124 @@ fn run_network_service<P: Preset>(
     });
 }
 
+// Messages sent through `ServiceInboundMessage::Publish` are all produced by this node or
+// submitted to it through the API. They are checked against the gossip mesh before publishing.
+fn publish_own_message<P: Preset>(
+    service: &mut Service<RequestId, P>,
+    network_globals: &NetworkGlobals,
+    controller: &RealController<P>,
+    metrics: Option<&Arc<Metrics>>,
+    publish_retries: &mut PublishRetries<PubsubMessage<P>>,
+    message: PubsubMessage<P>,
+    attempts: usize,
+) {
+    let kind = message.kind();
+
+    // Count peers across all subscribed topics of the same kind.
+    // There may be more than one around forks.
+    let topic_hashes = network_globals
+        .gossipsub_subscriptions
+        .read()
+        .iter()
+        .filter(|topic| topic.kind() == &kind)
+        .map(|topic| IdentTopic::from(topic.clone()).hash())
+        .collect::<Vec<TopicHash>>();
+
+    let gossipsub = service.gossipsub();
+
+    let mesh_peers = topic_hashes
+        .iter()
+        .map(|topic_hash| gossipsub.mesh_peers(topic_hash).count())
+        .sum::<usize>();
+
+    let topic_peers = gossipsub
+        .all_peers()
+        .filter(|(_, topics)| topics.iter().any(|topic| topic_hashes.contains(topic)))
+        .count();
+
+    let kind_label = kind.to_string();
+
+    if let Some(metrics) = metrics {
+        metrics.observe_gossip_publish_peers(&kind_label, mesh_peers, topic_peers);
+    }
+
+    let outcome = match PublishReadiness::new(mesh_peers, topic_peers) {
+        PublishReadiness::Ready => {
+            observe_publish_delay(controller, metrics, &message);
+            service.publish(message);
+            "published"
+        }
+        PublishReadiness::SparseMesh => {
+            debug!(
+                "publishing {kind_label} message with only {mesh_peers} mesh peers \
+                 to {topic_peers} peers subscribed to the topic",
+            );
+
+            observe_publish_delay(controller, metrics, &message);
+            service.publish(message);
+            "published_to_sparse_mesh"
+        }
+        PublishReadiness::NoPeers => {
+            match publish_retries.schedule(message, attempts + 1, Instant::now()) {
+                Ok(()) => {
+                    debug!(
+                        "no peers subscribed to {kind_label} topic; \
+                         retrying in {PUBLISH_RETRY_INTERVAL:?} (attempt: {})",
+                        attempts + 1,
+                    );
+
+                    "retried"
+                }
+                Err(message) => {
+                    // Let `eth2_libp2p` make a final attempt and report the failure.
+                    // The message is likely to reach no one, but it is not dropped.
+                    warn!(
+                        "no peers subscribed to {kind_label} topic after {} attempts",
+                        attempts + 1,
+                    );
+
+                    observe_publish_delay(controller, metrics, &message);
+                    service.publish(message);
+                    "published_without_peers"
+                }
+            }
+        }
+    };
+
+    if let Some(metrics) = metrics {
+        metrics.inc_gossip_publish_outcome(&kind_label, outcome);
+    }
+}
+
+// Measured when messages are handed to gossipsub rather than when they are produced.
+// This includes the time spent waiting for peers to publish to.
+fn observe_publish_delay<P: Preset>(
+    controller: &RealController<P>,
+    metrics: Option<&Arc<Metrics>>,
+    message: &PubsubMessage<P>,
+) {
+    let Some(metrics) = metrics else {
+        return;
+    };
+
+    let (object, slot) = match message {
+        PubsubMessage::BeaconBlock(beacon_block) => ("beacon_block", beacon_block.message().slot()),
+        PubsubMessage::BlobSidecar(data) => {
+            let (_, blob_sidecar) = data.as_ref();
+            (
+                "blob_sidecar",
+                blob_sidecar.signed_block_header.message.slot,
+            )
+        }
+        _ => return,
+    };
+
+    let slot_timestamp = controller.genesis_time()
+        + (slot - GENESIS_SLOT) * controller.chain_config().seconds_per_slot.get();
+
+    metrics.observe_validator_publish_delay(object, slot_timestamp);
+}
+
 fn log(level: Level, connected_peers: usize, target_peers: usize, message: impl Display) {
     log!(
         level,
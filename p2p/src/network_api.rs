@@ -2,6 +2,7 @@ use eth2_libp2p::{
     types::EnrAttestationBitfield, ConnectionDirection, Enr, EnrExt as _, EnrSyncCommitteeBitfield,
     Multiaddr, PeerConnectionStatus, PeerId, PeerInfo,
 };
+use itertools::Itertools as _;
 use serde::{Deserialize, Serialize};
 use types::preset::Preset;
 
@@ -63,8 +64,11 @@ pub struct NodePeerCount {
 
 #[derive(Serialize)]
 struct NodeMetadata {
+    #[serde(with = "serde_utils::string_or_native")]
     seq_number: u64,
     attnets: EnrAttestationBitfield,
+    // `syncnets` was added to `MetaData` in Altair.
+    #[serde(skip_serializing_if = "Option::is_none")]
     syncnets: Option<EnrSyncCommitteeBitfield>,
 }
 
@@ -110,18 +114,38 @@ impl From<&ConnectionDirection> for PeerDirection {
 impl<P: Preset> Network<P> {
     #[must_use]
     pub fn node_identity(&self) -> NodeIdentity {
-        let metadata = self.network_globals().local_metadata.read();
-        let enr = self.network_globals().local_enr();
-
-        let metadata = NodeMetadata {
-            seq_number: metadata.seq_number(),
-            attnets: metadata.attnets(),
-            syncnets: metadata.syncnets(),
+        let network_globals = self.network_globals();
+        let peer_id = network_globals.local_peer_id();
+        let enr = network_globals.local_enr();
+
+        let metadata = {
+            let metadata = network_globals.local_metadata.read();
+
+            NodeMetadata {
+                seq_number: metadata.seq_number(),
+                attnets: metadata.attnets(),
+                syncnets: metadata.syncnets(),
+            }
         };
 
+        // The ENR only contains addresses that are reachable from outside.
+        // It may have none if the external address has not been discovered yet,
+        // so the addresses the node is actually listening on are reported as well.
+        let p2p_addresses = enr
+            .multiaddr_p2p_tcp()
+            .into_iter()
+            .chain(
+                network_globals
+                    .listen_multiaddrs()
+                    .into_iter()
+                    .filter_map(|address| address.with_p2p(peer_id).ok()),
+            )
+            .unique()
+            .collect();
+
         NodeIdentity {
-            peer_id: self.network_globals().local_peer_id().to_base58(),
-            p2p_addresses: enr.multiaddr_p2p_tcp(),
+            peer_id: peer_id.to_base58(),
+            p2p_addresses,
             discovery_addresses: enr.multiaddr_p2p_udp(),
             enr,
             metadata,
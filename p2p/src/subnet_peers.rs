@@ -0,0 +1,103 @@
+use core::time::Duration;
+use std::{collections::HashMap, time::Instant};
+
+use eth2_libp2p::Subnet;
+
+// The number of peers `eth2_libp2p` tries to keep on each subscribed subnet.
+pub const TARGET_SUBNET_PEERS: usize = 3;
+
+/// Tracks how long subscribed subnets have had fewer peers than [`TARGET_SUBNET_PEERS`].
+///
+/// Discovery queries are already made when subscribing to a subnet, but peers may disconnect
+/// long before the subscription ends. Subnets that stay under-populated for longer than
+/// `discovery_delay` are queried again. The delay avoids queries for peers that are only briefly
+/// disconnected and limits how often the same subnet is queried.
+pub struct SubnetPeers {
+    discovery_delay: Duration,
+    below_target_since: HashMap<Subnet, Instant>,
+}
+
+impl SubnetPeers {
+    #[must_use]
+    pub fn new(discovery_delay: Duration) -> Self {
+        Self {
+            discovery_delay,
+            below_target_since: HashMap::new(),
+        }
+    }
+
+    /// Returns subnets that peers should be discovered for.
+    ///
+    /// `peer_counts` should contain all subscribed subnets.
+    /// Subnets missing from it are no longer tracked.
+    pub fn update(
+        &mut self,
+        peer_counts: impl IntoIterator<Item = (Subnet, usize)>,
+        now: Instant,
+    ) -> Vec<Subnet> {
+        let below_target = peer_counts
+            .into_iter()
+            .filter(|(_, peer_count)| *peer_count < TARGET_SUBNET_PEERS)
+            .map(|(subnet, _)| subnet)
+            .collect::<Vec<_>>();
+
+        self.below_target_since
+            .retain(|subnet, _| below_target.contains(subnet));
+
+        let mut subnets_to_discover = vec![];
+
+        for subnet in below_target {
+            let since = self.below_target_since.entry(subnet).or_insert(now);
+
+            if now.saturating_duration_since(*since) >= self.discovery_delay {
+                subnets_to_discover.push(subnet);
+                *since = now;
+            }
+        }
+
+        subnets_to_discover
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn discovers_peers_for_subnets_below_target_for_longer_than_delay() {
+        let delay = Duration::from_secs(60);
+        let mut subnet_peers = SubnetPeers::new(delay);
+
+        let populated = Subnet::Attestation(0);
+        let recovered = Subnet::Attestation(1);
+        let under_populated = Subnet::SyncCommittee(0);
+
+        let start = Instant::now();
+
+        let peer_counts = [
+            (populated, TARGET_SUBNET_PEERS),
+            (recovered, 0),
+            (under_populated, TARGET_SUBNET_PEERS - 1),
+        ];
+
+        assert_eq!(subnet_peers.update(peer_counts, start), []);
+
+        let peer_counts = [
+            (populated, TARGET_SUBNET_PEERS),
+            (recovered, TARGET_SUBNET_PEERS),
+            (under_populated, TARGET_SUBNET_PEERS - 1),
+        ];
+
+        assert_eq!(
+            subnet_peers.update(peer_counts, start + delay),
+            [under_populated]
+        );
+
+        // Queries are repeated only after another delay.
+        assert_eq!(subnet_peers.update(peer_counts, start + delay * 3 / 2), []);
+        assert_eq!(
+            subnet_peers.update(peer_counts, start + delay * 2),
+            [under_populated]
+        );
+    }
+}
@@ -8,7 +8,6 @@ use futures::{
     select,
     stream::StreamExt as _,
 };
-use helper_functions::misc;
 use log::{debug, warn};
 use operation_pools::AttestationAggPool;
 use types::{
@@ -17,7 +16,7 @@ use types::{
 };
 
 use crate::{
-    attestation_subnets::AttestationSubnets,
+    attestation_subnets::{AttestationSubnets, DISCOVER_PEERS_IN_ADVANCE_SLOTS},
     beacon_committee_subscriptions::BeaconCommitteeSubscriptions,
     messages::{SubnetServiceToP2p, ToSubnetService},
     misc::{BeaconCommitteeSubscription, SyncCommitteeSubscription},
@@ -106,15 +105,14 @@ impl<P: Preset, W: Wait> SubnetService<P, W> {
     }
 
     fn on_slot(&mut self, slot: Slot) -> Result<()> {
-        let current_epoch = misc::compute_epoch_at_slot::<P>(slot);
-
         self.beacon_committee_subscriptions
-            .discard_old_subscriptions(current_epoch);
+            .discard_expired_subscriptions(slot);
 
         let actions = self.attestation_subnets.on_slot(
             self.attestation_agg_pool.config(),
             slot,
-            self.beacon_committee_subscriptions.all(),
+            self.beacon_committee_subscriptions
+                .at_slots(slot..=slot + DISCOVER_PEERS_IN_ADVANCE_SLOTS),
         )?;
 
         if !actions.is_empty() {
@@ -139,14 +137,21 @@ impl<P: Preset, W: Wait> SubnetService<P, W> {
         current_slot: Slot,
         subscriptions: Vec<BeaconCommitteeSubscription>,
     ) -> Result<()> {
-        self.beacon_committee_subscriptions
-            .update::<P>(subscriptions);
+        let new_subscriptions = self
+            .beacon_committee_subscriptions
+            .update(current_slot, subscriptions);
+
+        if new_subscriptions.is_empty() {
+            return Ok(());
+        }
 
         let actions = self
             .attestation_subnets
-            .update(current_slot, self.beacon_committee_subscriptions.all())?;
+            .update(current_slot, new_subscriptions)?;
 
-        SubnetServiceToP2p::UpdateAttestationSubnets(actions).send(&self.p2p_tx);
+        if !actions.is_empty() {
+            SubnetServiceToP2p::UpdateAttestationSubnets(actions).send(&self.p2p_tx);
+        }
 
         Ok(())
     }
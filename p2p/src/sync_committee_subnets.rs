@@ -5,6 +5,7 @@ use arithmetic::UsizeExt;
 use features::Feature;
 use helper_functions::misc;
 use itertools::izip;
+use log::warn;
 use typenum::Unsigned as _;
 use types::{
     altair::consts::SyncCommitteeSubnetCount,
@@ -100,7 +101,13 @@ impl<P: Preset> SyncCommitteeSubnets<P> {
                 .into_iter()
                 .map(UsizeExt::div_typenum::<P::SyncSubcommitteeSize>)
             {
-                let subnet_state = &mut self.states[subnet_id];
+                // Indices are validated in the HTTP API, but the service should not panic
+                // if an invalid subscription gets through some other way.
+                let Some(subnet_state) = self.states.get_mut(subnet_id) else {
+                    warn!("ignoring subscription to nonexistent sync committee subnet {subnet_id}");
+                    continue;
+                };
+
                 let expiration = subnet_state.max_expiration(until_epoch);
 
                 *subnet_state = Subscribed { expiration };
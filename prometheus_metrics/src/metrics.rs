@@ -18,6 +18,12 @@ pub struct Metrics {
     // System stats
     cores: IntGauge,
     disk_usage: IntGauge,
+    database_disk_usage: IntGauge,
+    data_directory_free_space: IntGauge,
+    database_write_latency: Gauge,
+    storage_degraded: IntGauge,
+    open_file_descriptors: IntGauge,
+    runtime_scheduling_delays: Histogram,
     used_memory: IntGauge,
     rx_bytes: IntGauge,
     tx_bytes: IntGauge,
@@ -44,6 +50,11 @@ pub struct Metrics {
 
     // Extra Network stats
     gossip_block_slot_start_delay_time: Histogram,
+    clock_drift: IntGauge,
+    subnet_peers: IntGaugeVec,
+    subnet_peers_target: IntGauge,
+    gossip_publish_peers: HistogramVec,
+    gossip_publish_outcomes: IntCounterVec,
 
     // Mutator
     mutator_attestations: IntCounterVec,
@@ -51,9 +62,16 @@ pub struct Metrics {
 
     pub block_processing_times: Histogram,
     pub block_post_processing_times: Histogram,
+    pub block_blob_availability_times: Histogram,
 
     // Attestation Verifier
     attestation_verifier_active_task_count: IntGauge,
+    attestation_verifier_queue_lengths: IntGaugeVec,
+    attestation_verifier_dropped_objects: IntCounterVec,
+
+    // Gossip channels
+    gossip_channel_lengths: IntGaugeVec,
+    gossip_channel_dropped_messages: IntCounterVec,
 
     pub attestation_verifier_process_attestation_batch_times: Histogram,
     pub attestation_verifier_processs_aggregate_batch_times: Histogram,
@@ -69,6 +87,7 @@ pub struct Metrics {
     pub validator_own_attestations_init_times: Histogram,
     pub validator_attest_times: Histogram,
     pub validator_attest_slashing_protector_times: Histogram,
+    pub validator_attestation_rebroadcasts: IntCounter,
 
     // eth/v1/validator/attestation_data
     pub validator_api_attestation_data_times: Histogram,
@@ -77,14 +96,24 @@ pub struct Metrics {
     pub validator_propose_times: Histogram,
     pub validator_propose_successes: IntCounter,
     pub validator_proposal_slashing_protector_times: Histogram,
+    validator_publish_delay_times: HistogramVec,
+    pub validator_published_blob_sidecars: IntCounter,
+
+    // Withdrawal sweep
+    validator_withdrawal_sweep_validators_ahead: IntGaugeVec,
+    validator_withdrawal_sweep_estimated_time: IntGaugeVec,
 
     // Build beacon block times
     pub build_beacon_block_times: Histogram,
     pub local_execution_payload_times: Histogram,
+    fee_recipient_mismatches: IntCounterVec,
     pub process_sync_committee_contribution_times: Histogram,
+    produced_sync_aggregate_participation: Histogram,
+    produced_sync_aggregate_participants: IntGauge,
     pub prepare_bls_to_execution_changes_times: Histogram,
     pub eth1_vote_times: Histogram,
     pub eth1_pending_deposits_times: Histogram,
+    eth1_votes: IntCounterVec,
     pub prepare_attester_slashings_times: Histogram,
     pub prepare_proposer_slashings_times: Histogram,
     pub prepare_voluntary_exits_times: Histogram,
@@ -108,6 +137,7 @@ pub struct Metrics {
     pub fc_attestation_task_times: HistogramVec,
 
     pub fc_blob_sidecar_task_times: Histogram,
+    pub fc_blob_sidecars_task_times: Histogram,
     pub fc_blob_sidecar_persist_task_times: Histogram,
     pub fc_block_attestation_task_times: Histogram,
     pub fc_attester_slashing_task_times: Histogram,
@@ -130,6 +160,7 @@ pub struct Metrics {
     beacon_processed_deposits_total: IntGauge,
 
     pub beacon_reorgs_total: IntCounter,
+    beacon_reorg_depth: Histogram,
 
     beacon_participation_prev_epoch_active_gwei_total: IntGauge,
     beacon_participation_prev_epoch_target_attesting_gwei_total: IntGauge,
@@ -139,10 +170,16 @@ pub struct Metrics {
     pub builder_register_validator_times: Histogram,
     pub builder_post_blinded_block_times: Histogram,
     pub builder_get_execution_payload_header_times: Histogram,
+    pub builder_relay_get_header_times: HistogramVec,
+    pub builder_relay_post_blinded_block_times: HistogramVec,
+    builder_relay_faults: IntCounterVec,
 
     // WebSigner
     pub web3signer_load_keys_times: Histogram,
     pub web3signer_sign_times: Histogram,
+    pub web3signer_endpoint_sign_times: HistogramVec,
+    web3signer_endpoint_errors_count: IntCounterVec,
+    web3signer_endpoint_healthy: IntGaugeVec,
 
     // Eth1 API
     pub eth1_api_request_times: HistogramVec,
@@ -167,6 +204,31 @@ impl Metrics {
             // System stats
             cores: IntGauge::new("CORE_COUNT", "Number of core in the node")?,
             disk_usage: IntGauge::new("GRANDINE_DISK_USAGE", "Grandine disk usage")?,
+            database_disk_usage: IntGauge::new(
+                "GRANDINE_DATABASE_DISK_USAGE",
+                "Grandine beacon database disk usage",
+            )?,
+            data_directory_free_space: IntGauge::new(
+                "GRANDINE_DATA_DIRECTORY_FREE_SPACE",
+                "Free space on the disk containing the Grandine beacon database",
+            )?,
+            database_write_latency: Gauge::new(
+                "GRANDINE_DATABASE_WRITE_LATENCY",
+                "Longest write to the Grandine beacon database since the last check in seconds",
+            )?,
+            storage_degraded: IntGauge::new(
+                "GRANDINE_STORAGE_DEGRADED",
+                "Whether archival of states is stopped because of low disk space",
+            )?,
+            open_file_descriptors: IntGauge::new(
+                "GRANDINE_OPEN_FILE_DESCRIPTORS",
+                "Number of file descriptors opened by Grandine",
+            )?,
+            runtime_scheduling_delays: Histogram::with_opts(histogram_opts!(
+                "GRANDINE_RUNTIME_SCHEDULING_DELAYS",
+                "Time it takes for the async runtime to poll a task again after it yields",
+                vec![0.000_01, 0.000_1, 0.001, 0.01, 0.1, 1.0, 10.0],
+            ))?,
             used_memory: IntGauge::new("GRANDINE_USED_MEMORY", "Grandine memory usage")?,
             rx_bytes: IntGauge::new("NODE_RX_BYTES", "Node total bytes received")?,
             tx_bytes: IntGauge::new("NODE_TX_BYTES", "Node total bytes sent")?,
@@ -240,6 +302,38 @@ impl Metrics {
                 "Duration between when the block is received and the start of the slot it belongs to.",
             ))?,
 
+            clock_drift: IntGauge::new(
+                "CLOCK_DRIFT",
+                "Estimated difference between the local clock and the network in milliseconds",
+            )?,
+
+            subnet_peers: IntGaugeVec::new(
+                opts!("SUBNET_PEERS", "Number of good peers on each gossip subnet"),
+                &["type", "subnet_id"],
+            )?,
+
+            subnet_peers_target: IntGauge::new(
+                "SUBNET_PEERS_TARGET",
+                "Number of peers on subscribed subnets below which more peers are discovered",
+            )?,
+
+            gossip_publish_peers: HistogramVec::new(
+                histogram_opts!(
+                    "GOSSIP_PUBLISH_PEERS",
+                    "Number of peers on the topic of own messages at the time they are published",
+                    vec![0.0, 1.0, 2.0, 4.0, 6.0, 8.0, 12.0, 16.0, 32.0, 64.0],
+                ),
+                &["kind", "peers"],
+            )?,
+
+            gossip_publish_outcomes: IntCounterVec::new(
+                opts!(
+                    "GOSSIP_PUBLISH_OUTCOMES",
+                    "Outcomes of attempts to publish own messages over gossip",
+                ),
+                &["kind", "outcome"],
+            )?,
+
             // Mutator
             mutator_attestations: IntCounterVec::new(
                 opts!(
@@ -267,12 +361,33 @@ impl Metrics {
                 "Mutator Block post processing times",
             ))?,
 
+            block_blob_availability_times: Histogram::with_opts(histogram_opts!(
+                "MUTATOR_BLOCK_BLOB_AVAILABILITY_TIMES",
+                "Time blocks spent waiting for blob sidecars to become available",
+            ))?,
+
             // Attestation Verifier
             attestation_verifier_active_task_count: IntGauge::new(
                 "ATTESTATION_VERIFIER_ACTIVE_TASK_COUNT",
                 "Attestation verifier active task count",
             )?,
 
+            attestation_verifier_queue_lengths: IntGaugeVec::new(
+                opts!(
+                    "ATTESTATION_VERIFIER_QUEUE_LENGTHS",
+                    "Number of gossip objects waiting for attestation verifier",
+                ),
+                &["type"],
+            )?,
+
+            attestation_verifier_dropped_objects: IntCounterVec::new(
+                opts!(
+                    "ATTESTATION_VERIFIER_DROPPED_OBJECTS",
+                    "Number of gossip objects dropped because attestation verifier queue was full",
+                ),
+                &["type"],
+            )?,
+
             attestation_verifier_process_attestation_batch_times: Histogram::with_opts(
                 histogram_opts!(
                     "ATTESTATION_VERIFIER_PROCESS_ATTESTATION_BATCH_TIMES",
@@ -294,6 +409,23 @@ impl Metrics {
                 )
             )?,
 
+            // Gossip channels
+            gossip_channel_lengths: IntGaugeVec::new(
+                opts!(
+                    "GOSSIP_CHANNEL_LENGTHS",
+                    "Number of gossip messages sent by p2p but not yet received by the consumer",
+                ),
+                &["channel"],
+            )?,
+
+            gossip_channel_dropped_messages: IntCounterVec::new(
+                opts!(
+                    "GOSSIP_CHANNEL_DROPPED_MESSAGES",
+                    "Number of gossip messages dropped because the channel to the consumer was full",
+                ),
+                &["channel"],
+            )?,
+
             // Validator ticks + Epoch processing
             validator_propose_tick_times: Histogram::with_opts(histogram_opts!(
                 "VALIDATOR_PROPOSE_TICK_TIMES",
@@ -331,6 +463,11 @@ impl Metrics {
                 "Slashing protection times when attesting",
             ))?,
 
+            validator_attestation_rebroadcasts: IntCounter::new(
+                "VALIDATOR_ATTESTATION_REBROADCASTS",
+                "Number of own attestations published again after not being seen in aggregates",
+            )?,
+
             // eth/v1/validator/attestation_data
             validator_api_attestation_data_times: Histogram::with_opts(histogram_opts!(
                 "VALIDATOR_API_ATTESTATION_DATA_TIMES",
@@ -355,6 +492,36 @@ impl Metrics {
                 )
             )?,
 
+            validator_publish_delay_times: HistogramVec::new(
+                histogram_opts!(
+                    "VALIDATOR_PUBLISH_DELAY_TIMES",
+                    "Time from the start of the slot until own blocks and blob sidecars are handed to gossipsub",
+                ),
+                &["object"],
+            )?,
+
+            validator_published_blob_sidecars: IntCounter::new(
+                "VALIDATOR_PUBLISHED_BLOB_SIDECARS",
+                "Number of blob sidecars of own proposals published",
+            )?,
+
+            // Withdrawal sweep
+            validator_withdrawal_sweep_validators_ahead: IntGaugeVec::new(
+                opts!(
+                    "VALIDATOR_WITHDRAWAL_SWEEP_VALIDATORS_AHEAD",
+                    "Number of validators the withdrawal sweep has to go through before reaching a validator",
+                ),
+                &["validator_index"],
+            )?,
+
+            validator_withdrawal_sweep_estimated_time: IntGaugeVec::new(
+                opts!(
+                    "VALIDATOR_WITHDRAWAL_SWEEP_ESTIMATED_TIME",
+                    "Estimated Unix time at which the withdrawal sweep reaches a validator",
+                ),
+                &["validator_index"],
+            )?,
+
             // Build beacon block times
             build_beacon_block_times: Histogram::with_opts(histogram_opts!(
                 "BUILD_BEACON_BLOCK_TIMES",
@@ -366,11 +533,30 @@ impl Metrics {
                 "Local execution payload times",
             ))?,
 
+            fee_recipient_mismatches: IntCounterVec::new(
+                opts!(
+                    "FEE_RECIPIENT_MISMATCHES",
+                    "Number of execution payloads with a fee recipient different from the configured one",
+                ),
+                &["type"],
+            )?,
+
             process_sync_committee_contribution_times: Histogram::with_opts(histogram_opts!(
                 "PROCESS_SYNC_COMMITTEE_CONTRIBUTION_TIMES",
                 "Sync committee contribution processing times",
             ))?,
 
+            produced_sync_aggregate_participation: Histogram::with_opts(histogram_opts!(
+                "PRODUCED_SYNC_AGGREGATE_PARTICIPATION",
+                "Fraction of the sync committee included in sync aggregates of produced blocks",
+                vec![0.0, 0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9, 0.95, 1.0],
+            ))?,
+
+            produced_sync_aggregate_participants: IntGauge::new(
+                "PRODUCED_SYNC_AGGREGATE_PARTICIPANTS",
+                "Number of sync committee participants in the last produced sync aggregate",
+            )?,
+
             prepare_bls_to_execution_changes_times: Histogram::with_opts(histogram_opts!(
                 "PREPARE_BLS_TO_EXECUTION_CHANGES_TIMES",
                 "Prepare BLS to execution changes times",
@@ -386,6 +572,11 @@ impl Metrics {
                 "Eth1 pending deposits times",
             ))?,
 
+            eth1_votes: IntCounterVec::new(
+                opts!("ETH1_VOTES", "Number of Eth1 votes by how they were chosen"),
+                &["type"],
+            )?,
+
             prepare_attester_slashings_times: Histogram::with_opts(histogram_opts!(
                 "PREPARE_ATTESTER_SLASHINGS_TIMES",
                 "Prepare attester slashing times",
@@ -477,6 +668,11 @@ impl Metrics {
                 "Forkchoice BlobSidecar times",
             ))?,
 
+            fc_blob_sidecars_task_times: Histogram::with_opts(histogram_opts!(
+                "FC_BLOB_SIDECARS_TASK_TIMES",
+                "Forkchoice batched BlobSidecars times",
+            ))?,
+
             fc_blob_sidecar_persist_task_times: Histogram::with_opts(histogram_opts!(
                 "FC_BLOB_SIDECAR_PERSIST_TASK_TIMES",
                 "Forkchoice BlobSidecar persist task times",
@@ -564,6 +760,12 @@ impl Metrics {
                 "Total number of reorgs",
             )?,
 
+            beacon_reorg_depth: Histogram::with_opts(histogram_opts!(
+                "beacon_reorg_depth",
+                "Number of slots between the old head and the common ancestor of reorgs",
+                vec![1.0, 2.0, 3.0, 4.0, 8.0, 16.0, 32.0, 64.0],
+            ))?,
+
             beacon_participation_prev_epoch_active_gwei_total: IntGauge::new(
                 "beacon_participation_prev_epoch_active_gwei_total",
                 "Total effective balance of previous epoch active validators",
@@ -595,6 +797,30 @@ impl Metrics {
                 "Builder get execution payload header times",
             ))?,
 
+            builder_relay_get_header_times: HistogramVec::new(
+                histogram_opts!(
+                    "BUILDER_RELAY_GET_HEADER_TIMES",
+                    "Execution payload header request times per relay",
+                ),
+                &["url"],
+            )?,
+
+            builder_relay_post_blinded_block_times: HistogramVec::new(
+                histogram_opts!(
+                    "BUILDER_RELAY_POST_BLINDED_BLOCK_TIMES",
+                    "Blinded block submission times per relay",
+                ),
+                &["url"],
+            )?,
+
+            builder_relay_faults: IntCounterVec::new(
+                opts!(
+                    "BUILDER_RELAY_FAULTS",
+                    "Number of failed or invalid relay responses per relay and fault type",
+                ),
+                &["url", "type"],
+            )?,
+
             // WebSigner
             web3signer_load_keys_times: Histogram::with_opts(histogram_opts!(
                 "WEB3SIGNER_LOAD_KEYS_TIMES",
@@ -606,6 +832,30 @@ impl Metrics {
                 "Web3Signer sign times",
             ))?,
 
+            web3signer_endpoint_sign_times: HistogramVec::new(
+                histogram_opts!(
+                    "WEB3SIGNER_ENDPOINT_SIGN_TIMES",
+                    "Web3Signer sign times per endpoint",
+                ),
+                &["url"],
+            )?,
+
+            web3signer_endpoint_errors_count: IntCounterVec::new(
+                opts!(
+                    "WEB3SIGNER_ENDPOINT_ERRORS_COUNT",
+                    "Number of failed signing requests per Web3Signer endpoint",
+                ),
+                &["url"],
+            )?,
+
+            web3signer_endpoint_healthy: IntGaugeVec::new(
+                opts!(
+                    "WEB3SIGNER_ENDPOINT_HEALTHY",
+                    "Whether the last health check of a Web3Signer endpoint succeeded",
+                ),
+                &["url"],
+            )?,
+
             // Eth1 API
             eth1_api_request_times: HistogramVec::new(
                 histogram_opts!(
@@ -664,6 +914,12 @@ impl Metrics {
         default_registry.register(Box::new(self.live.clone()))?;
         default_registry.register(Box::new(self.cores.clone()))?;
         default_registry.register(Box::new(self.disk_usage.clone()))?;
+        default_registry.register(Box::new(self.database_disk_usage.clone()))?;
+        default_registry.register(Box::new(self.data_directory_free_space.clone()))?;
+        default_registry.register(Box::new(self.database_write_latency.clone()))?;
+        default_registry.register(Box::new(self.storage_degraded.clone()))?;
+        default_registry.register(Box::new(self.open_file_descriptors.clone()))?;
+        default_registry.register(Box::new(self.runtime_scheduling_delays.clone()))?;
         default_registry.register(Box::new(self.used_memory.clone()))?;
         default_registry.register(Box::new(self.rx_bytes.clone()))?;
         default_registry.register(Box::new(self.tx_bytes.clone()))?;
@@ -681,13 +937,23 @@ impl Metrics {
             self.received_aggregated_attestation_subsets.clone(),
         ))?;
         default_registry.register(Box::new(self.gossip_block_slot_start_delay_time.clone()))?;
+        default_registry.register(Box::new(self.clock_drift.clone()))?;
+        default_registry.register(Box::new(self.subnet_peers.clone()))?;
+        default_registry.register(Box::new(self.subnet_peers_target.clone()))?;
+        default_registry.register(Box::new(self.gossip_publish_peers.clone()))?;
+        default_registry.register(Box::new(self.gossip_publish_outcomes.clone()))?;
         default_registry.register(Box::new(self.mutator_attestations.clone()))?;
         default_registry.register(Box::new(self.mutator_aggregate_and_proofs.clone()))?;
         default_registry.register(Box::new(self.block_processing_times.clone()))?;
         default_registry.register(Box::new(self.block_post_processing_times.clone()))?;
+        default_registry.register(Box::new(self.block_blob_availability_times.clone()))?;
         default_registry.register(Box::new(
             self.attestation_verifier_active_task_count.clone(),
         ))?;
+        default_registry.register(Box::new(self.attestation_verifier_queue_lengths.clone()))?;
+        default_registry.register(Box::new(self.attestation_verifier_dropped_objects.clone()))?;
+        default_registry.register(Box::new(self.gossip_channel_lengths.clone()))?;
+        default_registry.register(Box::new(self.gossip_channel_dropped_messages.clone()))?;
         default_registry.register(Box::new(
             self.attestation_verifier_process_attestation_batch_times
                 .clone(),
@@ -709,22 +975,35 @@ impl Metrics {
         default_registry.register(Box::new(
             self.validator_attest_slashing_protector_times.clone(),
         ))?;
+        default_registry.register(Box::new(self.validator_attestation_rebroadcasts.clone()))?;
         default_registry.register(Box::new(self.validator_api_attestation_data_times.clone()))?;
         default_registry.register(Box::new(self.validator_propose_times.clone()))?;
         default_registry.register(Box::new(self.validator_propose_successes.clone()))?;
+        default_registry.register(Box::new(self.validator_publish_delay_times.clone()))?;
+        default_registry.register(Box::new(self.validator_published_blob_sidecars.clone()))?;
+        default_registry.register(Box::new(
+            self.validator_withdrawal_sweep_validators_ahead.clone(),
+        ))?;
+        default_registry.register(Box::new(
+            self.validator_withdrawal_sweep_estimated_time.clone(),
+        ))?;
         default_registry.register(Box::new(
             self.validator_proposal_slashing_protector_times.clone(),
         ))?;
         default_registry.register(Box::new(self.build_beacon_block_times.clone()))?;
         default_registry.register(Box::new(self.local_execution_payload_times.clone()))?;
+        default_registry.register(Box::new(self.fee_recipient_mismatches.clone()))?;
         default_registry.register(Box::new(
             self.process_sync_committee_contribution_times.clone(),
         ))?;
+        default_registry.register(Box::new(self.produced_sync_aggregate_participation.clone()))?;
+        default_registry.register(Box::new(self.produced_sync_aggregate_participants.clone()))?;
         default_registry.register(Box::new(
             self.prepare_bls_to_execution_changes_times.clone(),
         ))?;
         default_registry.register(Box::new(self.eth1_vote_times.clone()))?;
         default_registry.register(Box::new(self.eth1_pending_deposits_times.clone()))?;
+        default_registry.register(Box::new(self.eth1_votes.clone()))?;
         default_registry.register(Box::new(self.prepare_attester_slashings_times.clone()))?;
         default_registry.register(Box::new(self.prepare_proposer_slashings_times.clone()))?;
         default_registry.register(Box::new(self.prepare_voluntary_exits_times.clone()))?;
@@ -751,6 +1030,7 @@ impl Metrics {
         default_registry.register(Box::new(self.fc_aggregate_and_proof_task_times.clone()))?;
         default_registry.register(Box::new(self.fc_attestation_task_times.clone()))?;
         default_registry.register(Box::new(self.fc_blob_sidecar_task_times.clone()))?;
+        default_registry.register(Box::new(self.fc_blob_sidecars_task_times.clone()))?;
         default_registry.register(Box::new(self.fc_blob_sidecar_persist_task_times.clone()))?;
         default_registry.register(Box::new(self.fc_block_attestation_task_times.clone()))?;
         default_registry.register(Box::new(self.fc_attester_slashing_task_times.clone()))?;
@@ -772,6 +1052,7 @@ impl Metrics {
         default_registry.register(Box::new(self.beacon_slot.clone()))?;
         default_registry.register(Box::new(self.beacon_processed_deposits_total.clone()))?;
         default_registry.register(Box::new(self.beacon_reorgs_total.clone()))?;
+        default_registry.register(Box::new(self.beacon_reorg_depth.clone()))?;
         default_registry.register(Box::new(
             self.beacon_participation_prev_epoch_active_gwei_total
                 .clone(),
@@ -786,8 +1067,16 @@ impl Metrics {
         default_registry.register(Box::new(
             self.builder_get_execution_payload_header_times.clone(),
         ))?;
+        default_registry.register(Box::new(self.builder_relay_get_header_times.clone()))?;
+        default_registry.register(Box::new(
+            self.builder_relay_post_blinded_block_times.clone(),
+        ))?;
+        default_registry.register(Box::new(self.builder_relay_faults.clone()))?;
         default_registry.register(Box::new(self.web3signer_load_keys_times.clone()))?;
         default_registry.register(Box::new(self.web3signer_sign_times.clone()))?;
+        default_registry.register(Box::new(self.web3signer_endpoint_sign_times.clone()))?;
+        default_registry.register(Box::new(self.web3signer_endpoint_errors_count.clone()))?;
+        default_registry.register(Box::new(self.web3signer_endpoint_healthy.clone()))?;
         default_registry.register(Box::new(self.eth1_api_request_times.clone()))?;
         default_registry.register(Box::new(self.eth1_api_errors_count.clone()))?;
         default_registry.register(Box::new(self.eth1_api_reset_count.clone()))?;
@@ -815,6 +1104,30 @@ impl Metrics {
         self.disk_usage.set(disk_usage as i64)
     }
 
+    pub fn set_database_disk_usage(&self, disk_usage: u64) {
+        self.database_disk_usage.set(disk_usage as i64)
+    }
+
+    pub fn set_data_directory_free_space(&self, free_space: u64) {
+        self.data_directory_free_space.set(free_space as i64)
+    }
+
+    pub fn set_database_write_latency(&self, latency: Duration) {
+        self.database_write_latency.set(latency.as_secs_f64())
+    }
+
+    pub fn set_storage_degraded(&self, degraded: bool) {
+        self.storage_degraded.set(degraded.into())
+    }
+
+    pub fn set_open_file_descriptors(&self, count: usize) {
+        self.open_file_descriptors.set(count as i64)
+    }
+
+    pub fn observe_runtime_scheduling_delay(&self, delay: Duration) {
+        self.runtime_scheduling_delays.observe(delay.as_secs_f64())
+    }
+
     pub fn set_used_memory(&self, used_memory: u64) {
         self.used_memory.set(used_memory as i64)
     }
@@ -892,6 +1205,159 @@ impl Metrics {
         }
     }
 
+    pub fn observe_validator_publish_delay(&self, object: &str, slot_timestamp: UnixSeconds) {
+        let duration = match helpers::duration_from_now_to(slot_timestamp) {
+            Ok(duration) => duration,
+            Err(error) => {
+                warn!("unable to observe publish delay of {object}: {error:?}");
+                return;
+            }
+        };
+
+        match self
+            .validator_publish_delay_times
+            .get_metric_with_label_values(&[object])
+        {
+            Ok(histogram) => histogram.observe(duration.as_secs_f64()),
+            Err(error) => warn!("unable to observe publish delay of {object}: {error:?}"),
+        }
+    }
+
+    pub fn set_withdrawal_sweep_position(
+        &self,
+        validator_index: u64,
+        validators_ahead: u64,
+        estimated_time: u64,
+    ) {
+        let validator_index = validator_index.to_string();
+
+        match self
+            .validator_withdrawal_sweep_validators_ahead
+            .get_metric_with_label_values(&[validator_index.as_str()])
+        {
+            Ok(gauge) => gauge.set(validators_ahead as i64),
+            Err(error) => warn!(
+                "unable to set withdrawal sweep position of validator {validator_index}: {error:?}",
+            ),
+        }
+
+        match self
+            .validator_withdrawal_sweep_estimated_time
+            .get_metric_with_label_values(&[validator_index.as_str()])
+        {
+            Ok(gauge) => gauge.set(estimated_time as i64),
+            Err(error) => warn!(
+                "unable to set withdrawal sweep estimate of validator {validator_index}: {error:?}",
+            ),
+        }
+    }
+
+    pub fn set_clock_drift(&self, millis: i64) {
+        self.clock_drift.set(millis)
+    }
+
+    pub fn observe_reorg_depth(&self, depth: u64) {
+        self.beacon_reorg_depth.observe(depth as f64)
+    }
+
+    pub fn set_subnet_peers(&self, labels: &[&str], peer_count: usize) {
+        match self.subnet_peers.get_metric_with_label_values(labels) {
+            Ok(gauge) => gauge.set(peer_count as i64),
+            Err(error) => warn!("unable to track subnet peers for {labels:?}: {error:?}"),
+        }
+    }
+
+    pub fn set_subnet_peers_target(&self, target: usize) {
+        self.subnet_peers_target.set(target as i64)
+    }
+
+    pub fn observe_gossip_publish_peers(&self, kind: &str, mesh_peers: usize, topic_peers: usize) {
+        for (peers, count) in [("mesh", mesh_peers), ("topic", topic_peers)] {
+            match self
+                .gossip_publish_peers
+                .get_metric_with_label_values(&[kind, peers])
+            {
+                Ok(histogram) => histogram.observe(count as f64),
+                Err(error) => warn!("unable to observe gossip publish peers for {kind}: {error:?}"),
+            }
+        }
+    }
+
+    pub fn inc_gossip_publish_outcome(&self, kind: &str, outcome: &str) {
+        match self
+            .gossip_publish_outcomes
+            .get_metric_with_label_values(&[kind, outcome])
+        {
+            Ok(counter) => counter.inc(),
+            Err(error) => {
+                warn!("unable to track gossip publish outcome {outcome} for {kind}: {error:?}")
+            }
+        }
+    }
+
+    // Block production
+    pub fn observe_produced_sync_aggregate_participation(
+        &self,
+        participants: usize,
+        committee_size: usize,
+    ) {
+        if committee_size == 0 {
+            return;
+        }
+
+        self.produced_sync_aggregate_participation
+            .observe(participants as f64 / committee_size as f64);
+
+        self.produced_sync_aggregate_participants
+            .set(participants as i64);
+    }
+
+    // Builder API
+    pub fn register_builder_relay_fault(&self, url: &str, fault_type: &str) {
+        match self
+            .builder_relay_faults
+            .get_metric_with_label_values(&[url, fault_type])
+        {
+            Ok(counter) => counter.inc(),
+            Err(error) => {
+                warn!("unable to register builder relay fault {fault_type} for {url}: {error:?}")
+            }
+        }
+    }
+
+    // Web3Signer
+    pub fn register_web3signer_endpoint_error(&self, url: &str) {
+        match self
+            .web3signer_endpoint_errors_count
+            .get_metric_with_label_values(&[url])
+        {
+            Ok(counter) => counter.inc(),
+            Err(error) => {
+                warn!("unable to register Web3Signer endpoint error for {url}: {error:?}")
+            }
+        }
+    }
+
+    pub fn set_web3signer_endpoint_healthy(&self, url: &str, healthy: bool) {
+        match self
+            .web3signer_endpoint_healthy
+            .get_metric_with_label_values(&[url])
+        {
+            Ok(gauge) => gauge.set(healthy.into()),
+            Err(error) => {
+                warn!("unable to set Web3Signer endpoint health for {url}: {error:?}")
+            }
+        }
+    }
+
+    // Eth1
+    pub fn register_eth1_vote(&self, vote_type: &str) {
+        match self.eth1_votes.get_metric_with_label_values(&[vote_type]) {
+            Ok(counter) => counter.inc(),
+            Err(error) => warn!("unable to register Eth1 vote of type {vote_type}: {error:?}"),
+        }
+    }
+
     // Mutator
     pub fn register_mutator_attestation(&self, labels: &[&str]) {
         match self
@@ -923,6 +1389,81 @@ impl Metrics {
             .set(task_count as i64)
     }
 
+    pub fn set_attestation_verifier_queue_length(&self, object_type: &str, length: usize) {
+        match self
+            .attestation_verifier_queue_lengths
+            .get_metric_with_label_values(&[object_type])
+        {
+            Ok(gauge) => gauge.set(length as i64),
+            Err(error) => {
+                warn!(
+                    "unable to set attestation verifier queue length for {object_type}: {error:?}"
+                )
+            }
+        }
+    }
+
+    pub fn register_attestation_verifier_dropped_object(&self, object_type: &str) {
+        match self
+            .attestation_verifier_dropped_objects
+            .get_metric_with_label_values(&[object_type])
+        {
+            Ok(counter) => counter.inc(),
+            Err(error) => {
+                warn!("unable to register dropped attestation verifier object for {object_type}: {error:?}")
+            }
+        }
+    }
+
+    // Gossip channels
+    pub fn inc_gossip_channel_length(&self, channel: &str) {
+        match self
+            .gossip_channel_lengths
+            .get_metric_with_label_values(&[channel])
+        {
+            Ok(gauge) => gauge.inc(),
+            Err(error) => {
+                warn!("unable to increment gossip channel length for {channel}: {error:?}")
+            }
+        }
+    }
+
+    pub fn dec_gossip_channel_length(&self, channel: &str) {
+        match self
+            .gossip_channel_lengths
+            .get_metric_with_label_values(&[channel])
+        {
+            Ok(gauge) => gauge.dec(),
+            Err(error) => {
+                warn!("unable to decrement gossip channel length for {channel}: {error:?}")
+            }
+        }
+    }
+
+    pub fn register_gossip_channel_dropped_message(&self, channel: &str) {
+        match self
+            .gossip_channel_dropped_messages
+            .get_metric_with_label_values(&[channel])
+        {
+            Ok(counter) => counter.inc(),
+            Err(error) => {
+                warn!("unable to register dropped gossip message for {channel}: {error:?}")
+            }
+        }
+    }
+
+    pub fn register_fee_recipient_mismatch(&self, payload_type: &str) {
+        match self
+            .fee_recipient_mismatches
+            .get_metric_with_label_values(&[payload_type])
+        {
+            Ok(counter) => counter.inc(),
+            Err(error) => {
+                warn!("unable to register fee recipient mismatch for {payload_type}: {error:?}")
+            }
+        }
+    }
+
     // EF interop metrics
     pub fn set_active_validators(&self, validator_count: usize) {
         self.beacon_current_active_validators
@@ -12,6 +12,7 @@ pub const DEFAULT_LIBP2P_IPV6_PORT: NonZeroU16 = nonzero!(9050_u16);
 pub const DEFAULT_LIBP2P_QUIC_IPV4_PORT: NonZeroU16 = nonzero!(9001_u16);
 pub const DEFAULT_LIBP2P_QUIC_IPV6_PORT: NonZeroU16 = nonzero!(9051_u16);
 pub const DEFAULT_REQUEST_TIMEOUT: u64 = 30000;
+pub const DEFAULT_SUBNET_PEER_DISCOVERY_DELAY: u64 = 60000;
 pub const DEFAULT_TARGET_PEERS: usize = 100;
 pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
 
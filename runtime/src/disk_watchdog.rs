@@ -0,0 +1,192 @@
+use core::{convert::Infallible as Never, time::Duration};
+use std::{path::Path, sync::Arc};
+
+use anyhow::{Context as _, Result};
+use bytesize::ByteSize;
+use directories::Directories;
+use fork_choice_control::Storage;
+use log::{error, info, warn};
+use prometheus_metrics::Metrics;
+use sysinfo::Disks;
+use types::preset::Preset;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+// The database grows in steps of 256 MiB and a single batch of finalized blocks and states can
+// take several more. Stopping archival with this much space left leaves room for everything else.
+const DEGRADED_FREE_SPACE: ByteSize = ByteSize::gib(5);
+const LOW_FREE_SPACE: ByteSize = ByteSize::gib(20);
+
+// Writes this slow usually mean the disk is failing or shared with something much busier.
+const SLOW_WRITE_LATENCY: Duration = Duration::from_secs(10);
+
+// Growth is only reported if the disk would fill up faster than this.
+const MIN_TIME_UNTIL_FULL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Monitors the disk the beacon database is stored on.
+///
+/// Running out of space in the middle of a write may leave the database unusable.
+/// Once free space drops below [`DEGRADED_FREE_SPACE`], storage is put into degraded mode until
+/// at least [`LOW_FREE_SPACE`] is available again. See [`StorageHealth::is_degraded`].
+///
+/// [`StorageHealth::is_degraded`]: fork_choice_control::StorageHealth::is_degraded
+pub struct DiskWatchdog<P: Preset> {
+    storage: Arc<Storage<P>>,
+    directories: Arc<Directories>,
+    metrics: Option<Arc<Metrics>>,
+    previous_database_size: Option<u64>,
+}
+
+impl<P: Preset> DiskWatchdog<P> {
+    #[must_use]
+    pub const fn new(
+        storage: Arc<Storage<P>>,
+        directories: Arc<Directories>,
+        metrics: Option<Arc<Metrics>>,
+    ) -> Self {
+        Self {
+            storage,
+            directories,
+            metrics,
+            previous_database_size: None,
+        }
+    }
+
+    pub async fn run(mut self) -> Result<Never> {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            if let Err(error) = self.check() {
+                warn!("unable to check disk health: {error:?}");
+            }
+        }
+    }
+
+    fn check(&mut self) -> Result<()> {
+        let health = self.storage.health();
+
+        let write_latency = health.take_max_write_latency();
+
+        if let Some(metrics) = self.metrics.as_ref() {
+            metrics.set_database_write_latency(write_latency);
+        }
+
+        if write_latency >= SLOW_WRITE_LATENCY {
+            warn!(
+                "writing to the beacon database took {write_latency:?}; \
+                 the disk may be failing or overloaded",
+            );
+        }
+
+        let store_directory = self
+            .directories
+            .store_directory
+            .as_deref()
+            .context("store directory is not set")?;
+
+        let free_space = free_space(store_directory)?;
+        let database_size = self.directories.store_disk_usage()?;
+
+        if let Some(metrics) = self.metrics.as_ref() {
+            metrics.set_data_directory_free_space(free_space);
+        }
+
+        let growth = self
+            .previous_database_size
+            .replace(database_size)
+            .map(|previous_size| database_size.saturating_sub(previous_size))
+            .filter(|growth| *growth > 0);
+
+        if let Some(growth) = growth {
+            let intervals_until_full = free_space / growth;
+            let time_until_full = CHECK_INTERVAL
+                .saturating_mul(u32::try_from(intervals_until_full).unwrap_or(u32::MAX));
+
+            if time_until_full < MIN_TIME_UNTIL_FULL {
+                warn!(
+                    "beacon database grew by {} in the last {CHECK_INTERVAL:?}; \
+                     at this rate the disk will be full in about {time_until_full:?}",
+                    ByteSize(growth),
+                );
+            }
+        }
+
+        let was_degraded = health.is_degraded();
+        let degraded = is_degraded(was_degraded, free_space);
+
+        match (was_degraded, degraded) {
+            (false, true) => error!(
+                "only {} of disk space left for the beacon database; \
+                 archival of states is stopped until at least {LOW_FREE_SPACE} is available",
+                ByteSize(free_space),
+            ),
+            (true, false) => info!(
+                "{} of disk space available for the beacon database; resuming archival of states",
+                ByteSize(free_space),
+            ),
+            (true, true) => warn!(
+                "storage is degraded because only {} of disk space is left",
+                ByteSize(free_space),
+            ),
+            (false, false) if free_space < LOW_FREE_SPACE.as_u64() => warn!(
+                "only {} of disk space left for the beacon database",
+                ByteSize(free_space),
+            ),
+            (false, false) => {}
+        }
+
+        health.set_degraded(degraded);
+
+        if let Some(metrics) = self.metrics.as_ref() {
+            metrics.set_storage_degraded(degraded);
+        }
+
+        Ok(())
+    }
+}
+
+// Degraded mode is left at a higher threshold than it is entered at.
+// Otherwise archiving a single state could be enough to enter it again.
+const fn is_degraded(was_degraded: bool, free_space: u64) -> bool {
+    if was_degraded {
+        free_space < LOW_FREE_SPACE.as_u64()
+    } else {
+        free_space < DEGRADED_FREE_SPACE.as_u64()
+    }
+}
+
+fn free_space(directory: &Path) -> Result<u64> {
+    let directory = fs_err::canonicalize(directory)?;
+    let disks = Disks::new_with_refreshed_list();
+
+    // Mount points may be nested. The one closest to the directory is the one it is stored on.
+    let disk = disks
+        .list()
+        .iter()
+        .filter(|disk| directory.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().components().count())
+        .with_context(|| format!("no disk found for {}", directory.display()))?;
+
+    Ok(disk.available_space())
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    #[test_case(false, ByteSize::gib(30) => false; "healthy with plenty of space")]
+    #[test_case(false, ByteSize::gib(10) => false; "healthy between thresholds")]
+    #[test_case(false, ByteSize::gib(5) => false; "healthy at lower threshold")]
+    #[test_case(false, ByteSize::gib(4) => true; "healthy below lower threshold")]
+    #[test_case(true, ByteSize::gib(4) => true; "degraded below lower threshold")]
+    #[test_case(true, ByteSize::gib(10) => true; "degraded between thresholds")]
+    #[test_case(true, ByteSize::gib(20) => false; "degraded at upper threshold")]
+    #[test_case(true, ByteSize::gib(30) => false; "degraded with plenty of space")]
+    fn degraded_mode_has_hysteresis(was_degraded: bool, free_space: ByteSize) -> bool {
+        is_degraded(was_degraded, free_space.as_u64())
+    }
+}
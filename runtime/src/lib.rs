@@ -3,7 +3,7 @@ pub use crate::{
         default_network_config, DEFAULT_ETH1_DB_SIZE, DEFAULT_ETH2_DB_SIZE,
         DEFAULT_LIBP2P_IPV4_PORT, DEFAULT_LIBP2P_IPV6_PORT, DEFAULT_LIBP2P_QUIC_IPV4_PORT,
         DEFAULT_LIBP2P_QUIC_IPV6_PORT, DEFAULT_METRICS_PORT, DEFAULT_REQUEST_TIMEOUT,
-        DEFAULT_TARGET_PEERS, DEFAULT_TIMEOUT,
+        DEFAULT_SUBNET_PEER_DISCOVERY_DELAY, DEFAULT_TARGET_PEERS, DEFAULT_TIMEOUT,
     },
     misc::{MetricsConfig, StorageConfig},
     runtime::run_after_genesis,
@@ -11,6 +11,7 @@ pub use crate::{
 };
 
 mod defaults;
+mod disk_watchdog;
 mod misc;
 mod runtime;
 mod schema;
@@ -1,10 +1,10 @@
-use core::{convert::Infallible as Never, future::Future};
-use std::{collections::HashSet, sync::Arc};
+use core::{convert::Infallible as Never, future::Future, time::Duration};
+use std::{collections::HashSet, sync::Arc, time::Instant};
 
 use anyhow::Result;
 use builder_api::{BuilderApi, BuilderConfig};
 use bytesize::ByteSize;
-use clock::Tick;
+use clock::{Clock, ClockDrift};
 use database::Database;
 use dedicated_executor::DedicatedExecutor;
 use eth1::{Eth1Chain, Eth1Config};
@@ -15,7 +15,10 @@ use eth1_api::{
 use fork_choice_control::{Controller, StateLoadStrategy, Storage};
 use fork_choice_store::StoreConfig;
 use futures::{
-    channel::mpsc::{self, UnboundedReceiver, UnboundedSender},
+    channel::{
+        mpsc::{self, UnboundedReceiver, UnboundedSender},
+        oneshot,
+    },
     future::Either,
     lock::Mutex,
     stream::TryStreamExt as _,
@@ -24,9 +27,11 @@ use genesis::GenesisProvider;
 use http_api::{Channels as HttpApiChannels, HttpApi, HttpApiConfig};
 use keymanager::KeyManager;
 use liveness_tracker::LivenessTracker;
-use log::info;
+use log::{debug, info, warn};
 use metrics::{run_metrics_server, MetricsChannels, MetricsService};
-use operation_pools::{AttestationAggPool, BlsToExecutionChangePool, SyncCommitteeAggPool};
+use operation_pools::{
+    AttestationAggPool, BlsToExecutionChangePool, PoolConfig, SyncCommitteeAggPool,
+};
 use p2p::{
     AttestationVerifier, BlockSyncService, BlockSyncServiceChannels, Channels, Network,
     NetworkConfig, SubnetService,
@@ -37,13 +42,30 @@ use slashing_protection::SlashingProtector;
 use std_ext::ArcExt as _;
 use tokio::{select, sync::RwLock};
 use types::{config::Config as ChainConfig, preset::Preset, traits::BeaconState as _};
-use validator::{Validator, ValidatorChannels, ValidatorConfig};
+use validator::{
+    DistributedMiddleware, DutiesCache, ProposalReports, Validator, ValidatorChannels,
+    ValidatorConfig,
+};
 
-use crate::misc::{MetricsConfig, StorageConfig};
+use crate::{
+    disk_watchdog::DiskWatchdog,
+    misc::{MetricsConfig, StorageConfig},
+};
 
 #[cfg(unix)]
 use tokio::signal::unix::SignalKind;
 
+// Event streams are never completed by the server, so connections serving them would otherwise
+// keep the HTTP API from shutting down until the overall timeout.
+const HTTP_API_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Saving the chain may take a while if many blocks have not been persisted yet.
+const GRACEFUL_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(60);
+
+// Channels carrying gossip from the network are bounded so that a stalled consumer cannot make
+// memory usage grow without limit. Messages that do not fit are dropped by `Network`.
+const GOSSIP_CHANNEL_CAPACITY: usize = 1 << 12;
+
 #[allow(clippy::too_many_arguments)]
 #[allow(clippy::too_many_lines)]
 pub async fn run_after_genesis<P: Preset>(
@@ -63,10 +85,21 @@ pub async fn run_after_genesis<P: Preset>(
     back_sync_enabled: bool,
     metrics_config: MetricsConfig,
     track_liveness: bool,
+    subnet_peer_discovery_delay: Duration,
     eth1_api_to_metrics_tx: Option<UnboundedSender<Eth1ApiToMetrics>>,
     eth1_api_to_metrics_rx: Option<UnboundedReceiver<Eth1ApiToMetrics>>,
     slashing_protection_history_limit: u64,
+    pool_config: PoolConfig,
+    clock: Arc<dyn Clock>,
 ) -> Result<()> {
+    // Load the KZG trusted setup in the background so that it does not delay validation of the
+    // first blob sidecars received after startup.
+    tokio::task::spawn_blocking(|| {
+        let started_at = Instant::now();
+        kzg_utils::settings();
+        debug!("loaded KZG trusted setup in {:?}", started_at.elapsed());
+    });
+
     let MetricsConfig {
         metrics,
         metrics_server_config,
@@ -94,7 +127,8 @@ pub async fn run_after_genesis<P: Preset>(
     let (fork_choice_to_p2p_tx, fork_choice_to_p2p_rx) = mpsc::unbounded();
     let (fork_choice_to_subnet_tx, fork_choice_to_subnet_rx) = mpsc::unbounded();
     let (fork_choice_to_validator_tx, fork_choice_to_validator_rx) = mpsc::unbounded();
-    let (p2p_to_attestation_verifier_tx, p2p_to_attestation_verifier_rx) = mpsc::unbounded();
+    let (p2p_to_attestation_verifier_tx, p2p_to_attestation_verifier_rx) =
+        mpsc::channel(GOSSIP_CHANNEL_CAPACITY);
     let (p2p_to_sync_tx, p2p_to_sync_rx) = mpsc::unbounded();
     let (p2p_to_validator_tx, p2p_to_validator_rx) = mpsc::unbounded();
     let (sync_to_p2p_tx, sync_to_p2p_rx) = mpsc::unbounded();
@@ -108,6 +142,9 @@ pub async fn run_after_genesis<P: Preset>(
     let (pool_to_p2p_tx, pool_to_p2p_rx) = mpsc::unbounded();
     let (subnet_service_to_p2p_tx, subnet_service_to_p2p_rx) = mpsc::unbounded();
     let (subnet_service_tx, subnet_service_rx) = mpsc::unbounded();
+    let (http_api_shutdown_tx, http_api_shutdown_rx) = oneshot::channel();
+    let (p2p_shutdown_tx, p2p_shutdown_rx) = oneshot::channel();
+    let (validator_shutdown_tx, validator_shutdown_rx) = oneshot::channel();
 
     let (fork_choice_to_sync_tx, fork_choice_to_sync_rx) =
         back_sync_enabled.then(mpsc::unbounded).unzip();
@@ -181,7 +218,7 @@ pub async fn run_after_genesis<P: Preset>(
 
     let slashing_protector = Arc::new(Mutex::new(slashing_protector));
 
-    let current_tick = Tick::current(&chain_config, anchor_state.genesis_time())?;
+    let current_tick = clock.current_tick(&chain_config, anchor_state.genesis_time())?;
 
     let (controller, mutator_handle) = Controller::new(
         chain_config.clone_arc(),
@@ -192,7 +229,7 @@ pub async fn run_after_genesis<P: Preset>(
         execution_engine.clone_arc(),
         metrics.clone(),
         fc_to_api_tx,
-        fork_choice_to_p2p_tx,
+        fork_choice_to_p2p_tx.clone(),
         fork_choice_to_subnet_tx,
         fork_choice_to_sync_tx,
         fork_choice_to_validator_tx,
@@ -200,8 +237,11 @@ pub async fn run_after_genesis<P: Preset>(
         unfinalized_blocks,
     )?;
 
-    let execution_service =
-        ExecutionService::new(eth1_api, controller.clone_arc(), execution_service_rx);
+    let execution_service = ExecutionService::new(
+        eth1_api.clone_arc(),
+        controller.clone_arc(),
+        execution_service_rx,
+    );
 
     let validator_keys = Arc::new(signer.keys().copied().collect::<HashSet<_>>());
 
@@ -225,6 +265,7 @@ pub async fn run_after_genesis<P: Preset>(
         controller.clone_arc(),
         dedicated_executor_low_priority,
         metrics.clone(),
+        fork_choice_to_p2p_tx,
         p2p_to_attestation_verifier_rx,
     );
 
@@ -321,6 +362,11 @@ pub async fn run_after_genesis<P: Preset>(
         ))
     });
 
+    let distributed_middleware = validator_config
+        .distributed_middleware_url
+        .clone()
+        .map(|url| Arc::new(DistributedMiddleware::new(signer.client().clone(), url)));
+
     let slasher = slasher_config
         .map(|slasher_config| -> Result<_> {
             let fork_version = chain_config.genesis_fork_version;
@@ -383,7 +429,7 @@ pub async fn run_after_genesis<P: Preset>(
                 blocks_db,
             };
 
-            let (network_tx, network_to_slasher_rx) = mpsc::unbounded();
+            let (network_tx, network_to_slasher_rx) = mpsc::channel(GOSSIP_CHANNEL_CAPACITY);
             let (slasher_to_validator_tx, validator_rx) = mpsc::unbounded();
             let (validator_tx, validator_to_slasher_rx) = mpsc::unbounded();
 
@@ -396,6 +442,7 @@ pub async fn run_after_genesis<P: Preset>(
                 controller.clone_arc(),
                 fork_version,
                 databases,
+                metrics.clone(),
                 slasher_to_validator_tx,
                 network_to_slasher_rx,
                 validator_to_slasher_rx,
@@ -403,6 +450,7 @@ pub async fn run_after_genesis<P: Preset>(
         })
         .transpose()?;
 
+    let run_web3signer_health_checks = signer.web3signer().clone().run_health_checks();
     let signer = Arc::new(RwLock::new(signer));
 
     let graffiti = validator_config
@@ -418,6 +466,7 @@ pub async fn run_after_genesis<P: Preset>(
             anchor_state.genesis_validators_root(),
             validator_config.suggested_fee_recipient,
             graffiti,
+            validator_config.keymanager_web3signer_proxy,
         ))
     } else {
         Arc::new(KeyManager::new_persistent(
@@ -428,13 +477,16 @@ pub async fn run_after_genesis<P: Preset>(
             validator_config.keystore_storage_password_file.as_deref(),
             validator_config.suggested_fee_recipient,
             graffiti,
+            validator_config.keymanager_web3signer_proxy,
         )?)
     };
 
     let attestation_agg_pool = AttestationAggPool::new(
         controller.clone_arc(),
+        clock.clone_arc(),
         dedicated_executor_normal_priority.clone_arc(),
         metrics.clone(),
+        pool_config,
     );
 
     let sync_committee_agg_pool = SyncCommitteeAggPool::new(
@@ -443,6 +495,7 @@ pub async fn run_after_genesis<P: Preset>(
         pool_to_liveness_tx,
         pool_to_p2p_tx.clone(),
         metrics.clone(),
+        pool_config,
     );
 
     let (bls_to_execution_change_pool, bls_to_execution_change_pool_service) =
@@ -451,11 +504,13 @@ pub async fn run_after_genesis<P: Preset>(
             pool_to_api_tx,
             pool_to_p2p_tx,
             metrics.clone(),
+            pool_config,
         );
 
     let validator_channels = ValidatorChannels {
         api_to_validator_rx,
         fork_choice_rx: fork_choice_to_validator_rx,
+        graceful_shutdown_rx: validator_shutdown_rx,
         p2p_tx: validator_to_p2p_tx,
         p2p_to_validator_rx,
         slasher_to_validator_rx,
@@ -465,25 +520,49 @@ pub async fn run_after_genesis<P: Preset>(
         validator_to_slasher_tx,
     };
 
+    let operation_pool_database = if in_memory {
+        Database::in_memory()
+    } else {
+        Database::persistent(
+            "operation_pools",
+            directories
+                .store_directory
+                .clone()
+                .unwrap_or_default()
+                .join("operation_pools"),
+            db_size,
+        )?
+    };
+
+    let duties_cache = Arc::new(DutiesCache::default());
+    let clock_drift = Arc::new(ClockDrift::default());
+    let proposal_reports = Arc::new(ProposalReports::default());
+
     let validator = Validator::new(
         eth1_chain,
         validator_config.clone_arc(),
         controller.clone_arc(),
         execution_engine,
         attestation_agg_pool.clone_arc(),
-        builder_api,
+        duties_cache.clone_arc(),
+        builder_api.clone(),
+        distributed_middleware,
         keymanager.proposer_configs().clone_arc(),
         signer,
         slashing_protector,
         sync_committee_agg_pool.clone_arc(),
         bls_to_execution_change_pool.clone_arc(),
+        operation_pool_database,
         metrics.clone(),
+        clock_drift.clone_arc(),
+        proposal_reports.clone_arc(),
         validator_channels,
     );
 
     let p2p_channels = Channels {
         api_to_p2p_rx,
         fork_choice_to_p2p_rx,
+        graceful_shutdown_rx: p2p_shutdown_rx,
         pool_to_p2p_rx,
         p2p_to_attestation_verifier_tx,
         p2p_to_sync_tx,
@@ -509,6 +588,8 @@ pub async fn run_after_genesis<P: Preset>(
         sync_committee_agg_pool.clone_arc(),
         bls_to_execution_change_pool.clone_arc(),
         metrics.clone(),
+        clock_drift,
+        subnet_peer_discovery_delay,
         registry.as_mut(),
     )
     .await?;
@@ -527,6 +608,7 @@ pub async fn run_after_genesis<P: Preset>(
         api_to_p2p_tx,
         api_to_validator_tx,
         fc_to_api_rx,
+        graceful_shutdown_rx: http_api_shutdown_rx,
         pool_to_api_rx,
         subnet_service_tx,
         sync_to_api_rx,
@@ -536,6 +618,7 @@ pub async fn run_after_genesis<P: Preset>(
     let http_api = HttpApi {
         controller: controller.clone_arc(),
         genesis_provider,
+        eth1_api,
         keymanager,
         validator_keys,
         validator_config,
@@ -544,18 +627,32 @@ pub async fn run_after_genesis<P: Preset>(
         attestation_agg_pool,
         sync_committee_agg_pool,
         bls_to_execution_change_pool,
+        duties_cache,
+        proposal_reports,
         channels: http_api_channels,
         metrics: metrics.clone(),
+        builder_api,
     };
 
-    let join_mutator = async { tokio::task::spawn_blocking(|| mutator_handle.join()).await? };
-    let run_clock = run_clock(controller.clone_arc());
+    // These are spawned in advance to be able to wait for them during graceful shutdown.
+    let mut join_mutator = tokio::task::spawn_blocking(|| mutator_handle.join());
+    let mut run_validator = tokio::spawn(validator.run());
+    let mut run_http_api = tokio::spawn(http_api.run());
+
+    let run_clock = run_clock(clock, controller.clone_arc());
+    let controller_for_shutdown = controller.clone_arc();
 
     let run_slasher = match slasher {
         Some(slasher) => Either::Left(slasher.run()),
         None => Either::Right(core::future::pending()),
     };
 
+    let run_disk_watchdog = if in_memory {
+        Either::Right(core::future::pending())
+    } else {
+        Either::Left(DiskWatchdog::new(storage, directories.clone_arc(), metrics.clone()).run())
+    };
+
     let run_metrics_server = match metrics_server_config {
         Some(config) => Either::Left(run_metrics_server(
             config,
@@ -579,30 +676,70 @@ pub async fn run_after_genesis<P: Preset>(
     };
 
     select! {
-        result = join_mutator => result,
+        result = &mut join_mutator => result?,
         result = spawn_fallible(execution_service.run()) => result,
-        result = spawn_fallible(validator.run()) => result,
+        result = &mut run_validator => result?,
         result = spawn_fallible(attestation_verifier.run()) => result,
         result = spawn_fallible(block_sync_service.run()) => result.map(from_never),
         result = spawn_fallible(network.run()) => result.map(from_never),
-        result = spawn_fallible(http_api.run()) => result,
+        result = &mut run_http_api => result?,
         result = spawn_fallible(run_clock) => result,
         result = spawn_fallible(run_slasher) => result.map(from_never),
         result = spawn_fallible(bls_to_execution_change_pool_service.run()) => result,
         result = spawn_fallible(run_metrics_server) => result,
         result = spawn_fallible(run_metrics_service) => result,
         result = spawn_fallible(run_liveness_tracker) => result,
+        result = spawn_fallible(run_disk_watchdog) => result.map(from_never),
         result = spawn_fallible(subnet_service.run()) => result,
+        result = spawn_fallible(run_web3signer_health_checks) => result.map(from_never),
         result = wait_for_signal() => result,
     }?;
 
-    info!("saving current chain before exit…");
+    info!("shutting down…");
 
-    Ok(())
+    let shut_down = async {
+        // Stop accepting gossip and tell peers we are leaving.
+        // The network service keeps running until the runtime exits to deliver Goodbye messages.
+        p2p_shutdown_tx.send(()).unwrap_or_default();
+
+        // Stop accepting HTTP connections and complete responses to requests already received.
+        http_api_shutdown_tx.send(()).unwrap_or_default();
+
+        if !run_http_api.is_finished() {
+            match tokio::time::timeout(HTTP_API_SHUTDOWN_TIMEOUT, run_http_api).await {
+                Ok(result) => result??,
+                Err(_) => warn!("HTTP API did not finish serving requests before shutdown"),
+            }
+        }
+
+        // Persist operation pools.
+        validator_shutdown_tx.send(()).unwrap_or_default();
+
+        if !run_validator.is_finished() {
+            run_validator.await??;
+        }
+
+        info!("saving current chain before exit…");
+
+        controller_for_shutdown.stop();
+
+        if !join_mutator.is_finished() {
+            join_mutator.await??;
+        }
+
+        anyhow::Ok(())
+    };
+
+    tokio::time::timeout(GRACEFUL_SHUTDOWN_TIMEOUT, shut_down)
+        .await
+        .unwrap_or_else(|_| {
+            warn!("graceful shutdown did not complete in {GRACEFUL_SHUTDOWN_TIMEOUT:?}");
+            Ok(())
+        })
 }
 
-async fn run_clock<P: Preset>(controller: RealController<P>) -> Result<()> {
-    let mut ticks = clock::ticks(controller.chain_config(), controller.genesis_time())?;
+async fn run_clock<P: Preset>(clock: Arc<dyn Clock>, controller: RealController<P>) -> Result<()> {
+    let mut ticks = clock.ticks(controller.chain_config(), controller.genesis_time())?;
 
     while let Some(tick) = ticks.try_next().await? {
         controller.on_tick(tick);
@@ -1,14 +1,19 @@
 pub use crate::{
     signer::{KeyOrigin, Signer},
     types::{ForkInfo, SigningMessage, SigningTriple},
-    web3signer::Config as Web3SignerConfig,
+    web3signer::{
+        ClientIdentity as Web3SignerClientIdentity, Config as Web3SignerConfig,
+        TlsConfig as Web3SignerTlsConfig, Web3Signer,
+    },
 };
 
 mod signer;
 mod types;
 mod web3signer {
     pub use api::{Config, Web3Signer};
+    pub use tls::{ClientIdentity, TlsConfig};
 
     mod api;
+    mod tls;
     mod types;
 }
@@ -6,7 +6,7 @@ use std::{
 use anyhow::Result;
 use bls::{PublicKeyBytes, SecretKey, Signature};
 use futures::{
-    stream::{FuturesUnordered, TryStreamExt as _},
+    stream::{StreamExt as _, TryStreamExt as _},
     try_join,
 };
 use itertools::Itertools as _;
@@ -39,12 +39,17 @@ pub enum KeyOrigin {
 #[derive(Clone)]
 enum SignMethod {
     SecretKey(Arc<SecretKey>, KeyOrigin),
-    Web3Signer(Url),
+    // Endpoints are tried in order until one of them succeeds.
+    Web3Signer(Vec<Url>),
 }
 
 #[derive(Clone)]
 pub struct Signer {
     sign_methods: HashMap<PublicKeyBytes, SignMethod>,
+    // Keys imported at runtime are not used for signing until they are activated.
+    // This gives the validator client a chance to check that they are not in use elsewhere.
+    pending_sign_methods: HashMap<PublicKeyBytes, SignMethod>,
+    client: Client,
     web3signer: Web3Signer,
 }
 
@@ -52,6 +57,7 @@ impl Signer {
     pub fn new(
         validator_keys: impl IntoIterator<Item = (PublicKeyBytes, Arc<SecretKey>, KeyOrigin)>,
         client: Client,
+        web3signer_client: Client,
         web3signer_config: Web3SignerConfig,
         metrics: Option<Arc<Metrics>>,
     ) -> Self {
@@ -64,7 +70,9 @@ impl Signer {
 
         Self {
             sign_methods,
-            web3signer: Web3Signer::new(client, web3signer_config, metrics),
+            pending_sign_methods: HashMap::new(),
+            client,
+            web3signer: Web3Signer::new(web3signer_client, web3signer_config, metrics),
         }
     }
 
@@ -75,7 +83,7 @@ impl Signer {
 
     #[must_use]
     pub fn is_empty(&self) -> bool {
-        self.sign_methods.is_empty()
+        self.sign_methods.is_empty() && self.pending_sign_methods.is_empty()
     }
 
     // The `#[must_use]` is redundant starting with Rust 1.66.0, but Clippy hasn't caught up yet.
@@ -86,9 +94,15 @@ impl Signer {
         self.sign_methods.keys()
     }
 
+    #[must_use]
+    pub fn pending_keys(&self) -> impl ExactSizeIterator<Item = &PublicKeyBytes> {
+        self.pending_sign_methods.keys()
+    }
+
     pub fn keys_with_origin(&self) -> impl Iterator<Item = (PublicKeyBytes, KeyOrigin)> + '_ {
         self.sign_methods
             .iter()
+            .chain(&self.pending_sign_methods)
             .map(|(pubkey, sign_method)| match sign_method {
                 SignMethod::SecretKey(_, origin) => (*pubkey, *origin),
                 SignMethod::Web3Signer(_) => (*pubkey, KeyOrigin::Web3Signer),
@@ -98,48 +112,96 @@ impl Signer {
     pub fn web3signer_keys(&self) -> impl Iterator<Item = (PublicKeyBytes, Url)> + '_ {
         self.sign_methods
             .iter()
+            .chain(&self.pending_sign_methods)
             .filter_map(|(pubkey, sign_method)| match sign_method {
                 SignMethod::SecretKey(_, _) => None,
-                SignMethod::Web3Signer(url) => Some((*pubkey, url.clone())),
+                SignMethod::Web3Signer(urls) => Some((*pubkey, urls.first()?.clone())),
             })
     }
 
+    #[must_use]
+    pub const fn web3signer(&self) -> &Web3Signer {
+        &self.web3signer
+    }
+
     #[must_use]
     pub const fn client(&self) -> &Client {
-        self.web3signer.client()
+        &self.client
     }
 
+    /// Adds keys as pending. They are not used for signing until [`Self::activate_pending_keys`].
     pub fn append_keys(
         &mut self,
         keys: impl IntoIterator<Item = (PublicKeyBytes, Arc<SecretKey>)>,
     ) {
         for (public_key, secret_key) in keys {
-            self.sign_methods
+            if self.sign_methods.contains_key(&public_key) {
+                continue;
+            }
+
+            self.pending_sign_methods
                 .entry(public_key)
                 .or_insert(SignMethod::SecretKey(secret_key, KeyOrigin::KeymanagerAPI));
         }
     }
 
+    /// Adds a key as pending. It is not used for signing until [`Self::activate_pending_keys`].
     pub fn append_remote_key(&mut self, public_key: PublicKeyBytes, url: Url) -> bool {
-        match self.sign_methods.entry(public_key) {
+        if self.sign_methods.contains_key(&public_key) {
+            return false;
+        }
+
+        match self.pending_sign_methods.entry(public_key) {
             Entry::Occupied(_) => false,
             Entry::Vacant(vacant) => {
-                vacant.insert(SignMethod::Web3Signer(url));
+                vacant.insert(SignMethod::Web3Signer(vec![url]));
                 true
             }
         }
     }
 
+    pub fn activate_pending_keys(&mut self, public_keys: impl IntoIterator<Item = PublicKeyBytes>) {
+        for public_key in public_keys {
+            if let Some(sign_method) = self.pending_sign_methods.remove(&public_key) {
+                self.sign_methods.insert(public_key, sign_method);
+            }
+        }
+    }
+
+    /// Makes keys pending again. Keys imported through the Keymanager API are loaded as active on
+    /// startup, so this is needed to keep them pending across restarts.
+    pub fn deactivate_keys(&mut self, public_keys: impl IntoIterator<Item = PublicKeyBytes>) {
+        for public_key in public_keys {
+            if let Some(sign_method) = self.sign_methods.remove(&public_key) {
+                self.pending_sign_methods.insert(public_key, sign_method);
+            }
+        }
+    }
+
     pub fn delete_key(&mut self, public_key: PublicKeyBytes) {
         self.sign_methods.remove(&public_key);
+        self.pending_sign_methods.remove(&public_key);
     }
 
     pub async fn load_keys_from_web3signer(&mut self) -> Result<()> {
         for (url, remote_keys) in self.web3signer.load_public_keys().await {
             for public_key in remote_keys {
-                self.sign_methods
+                // Keys imported through the Keymanager API stay pending until activated.
+                if self.pending_sign_methods.contains_key(&public_key) {
+                    continue;
+                }
+
+                let sign_method = self
+                    .sign_methods
                     .entry(public_key)
-                    .or_insert_with(|| SignMethod::Web3Signer(url.clone()));
+                    .or_insert_with(|| SignMethod::Web3Signer(vec![]));
+
+                // Keys served by multiple endpoints can fail over between them.
+                if let SignMethod::Web3Signer(urls) = sign_method {
+                    if !urls.contains(url) {
+                        urls.push(url.clone());
+                    }
+                }
             }
         }
 
@@ -148,7 +210,7 @@ impl Signer {
 
     #[must_use]
     pub fn no_keys(&self) -> bool {
-        self.sign_methods.is_empty()
+        self.sign_methods.is_empty() && self.pending_sign_methods.is_empty()
     }
 
     pub async fn sign<'block, P: Preset>(
@@ -160,9 +222,9 @@ impl Signer {
     ) -> Result<Signature> {
         let signature = match self.sign_method(public_key)? {
             SignMethod::SecretKey(secret_key, _) => secret_key.sign(signing_root),
-            SignMethod::Web3Signer(url) => self
+            SignMethod::Web3Signer(urls) => self
                 .web3signer
-                .sign(url, message, signing_root, fork_info, public_key)
+                .sign(urls, message, signing_root, fork_info, public_key)
                 .await?
                 .try_into()?,
         };
@@ -207,14 +269,13 @@ impl Signer {
         };
 
         let sign_remotely_future = async {
-            sign_remotely
-                .into_iter()
+            futures::stream::iter(sign_remotely)
                 .map(|(index, message, signing_root, public_key)| async move {
                     self.sign(message, signing_root, fork_info, public_key)
                         .await
                         .map(|signature| (index, signature))
                 })
-                .collect::<FuturesUnordered<_>>()
+                .buffer_unordered(self.web3signer.max_concurrent_requests().get())
                 .try_collect::<Vec<_>>()
                 .await
         };
@@ -235,3 +296,96 @@ impl Signer {
             .map_err(Into::into)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use hex_literal::hex;
+    use httpmock::{Method, MockServer};
+    use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+    use serde_json::json;
+
+    use super::*;
+
+    const PUBLIC_KEY: PublicKeyBytes = PublicKeyBytes(hex!(
+        "93247f2209abcacf57b75a51dafae777f9dd38bc7053d1af526f220a7489a6d3a2753e5f3e8b1cfe39b56f43611df74a"
+    ));
+
+    fn client_with_marker(marker: &'static str) -> Result<Client> {
+        let headers = HeaderMap::from_iter([(
+            HeaderName::from_static("x-client"),
+            HeaderValue::from_static(marker),
+        )]);
+        Ok(Client::builder().default_headers(headers).build()?)
+    }
+
+    #[tokio::test]
+    async fn web3signer_requests_use_web3signer_client() -> Result<()> {
+        let server = MockServer::start();
+
+        let web3signer_mock = server.mock(|when, then| {
+            when.method(Method::GET)
+                .path("/api/v1/eth2/publicKeys")
+                .header("x-client", "web3signer");
+            then.status(200).body(json!([PUBLIC_KEY]).to_string());
+        });
+
+        let shared_mock = server.mock(|when, then| {
+            when.method(Method::GET)
+                .path("/other")
+                .header("x-client", "shared");
+            then.status(200);
+        });
+
+        let mut signer = Signer::new(
+            core::iter::empty(),
+            client_with_marker("shared")?,
+            client_with_marker("web3signer")?,
+            Web3SignerConfig {
+                urls: vec![Url::parse(&server.url("/"))?],
+                ..Web3SignerConfig::default()
+            },
+            None,
+        );
+
+        signer.load_keys_from_web3signer().await?;
+
+        web3signer_mock.assert();
+        assert!(signer.has_key(PUBLIC_KEY));
+
+        signer
+            .client()
+            .get(server.url("/other"))
+            .send()
+            .await?
+            .error_for_status()?;
+
+        shared_mock.assert();
+
+        Ok(())
+    }
+
+    #[test]
+    fn deactivated_keys_become_pending_again() -> Result<()> {
+        let mut signer = Signer::new(
+            core::iter::empty(),
+            Client::new(),
+            Client::new(),
+            Web3SignerConfig::default(),
+            None,
+        );
+
+        assert!(signer.append_remote_key(PUBLIC_KEY, Url::parse("http://localhost:9000")?));
+
+        signer.activate_pending_keys([PUBLIC_KEY]);
+
+        assert!(signer.has_key(PUBLIC_KEY));
+        assert_eq!(signer.pending_keys().len(), 0);
+
+        signer.deactivate_keys([PUBLIC_KEY]);
+
+        assert!(!signer.has_key(PUBLIC_KEY));
+        itertools::assert_equal(signer.pending_keys().copied(), [PUBLIC_KEY]);
+
+        Ok(())
+    }
+}
@@ -2,6 +2,7 @@ use core::marker::PhantomData;
 
 use bls::PublicKeyBytes;
 use builder_api::unphased::containers::ValidatorRegistrationV1;
+use helper_functions::signing_domains::SigningDomains;
 use serde::Serialize;
 use types::{
     altair::containers::{
@@ -18,7 +19,7 @@ use types::{
     phase0::{
         containers::{
             AggregateAndProof, AttestationData, BeaconBlock as Phase0BeaconBlock,
-            BeaconBlockHeader, Fork,
+            BeaconBlockHeader, Fork, VoluntaryExit,
         },
         primitives::{Epoch, Slot, H256},
     },
@@ -34,6 +35,26 @@ pub struct ForkInfo<P: Preset> {
     pub phantom: PhantomData<P>,
 }
 
+impl<P: Preset> ForkInfo<P> {
+    #[must_use]
+    pub const fn new(fork: Fork, genesis_validators_root: H256) -> Self {
+        Self {
+            fork,
+            genesis_validators_root,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Builds [`ForkInfo`] for messages in `epoch` from the fork schedule.
+    #[must_use]
+    pub fn scheduled(signing_domains: SigningDomains<'_, P>, epoch: Epoch) -> Self {
+        Self::new(
+            signing_domains.fork(epoch),
+            signing_domains.genesis_validators_root(),
+        )
+    }
+}
+
 impl<P: Preset, BS: BeaconState<P>> From<&BS> for ForkInfo<P> {
     fn from(state: &BS) -> Self {
         Self {
@@ -73,6 +94,7 @@ pub enum SigningMessage<'block, P: Preset> {
     SyncAggregatorSelectionData(SyncAggregatorSelectionData),
     ContributionAndProof(ContributionAndProof<P>),
     ValidatorRegistration(ValidatorRegistrationV1),
+    VoluntaryExit(VoluntaryExit),
 }
 
 impl<'block, P: Preset> From<&'block Phase0BeaconBlock<P>> for SigningMessage<'block, P> {
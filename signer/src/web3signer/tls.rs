@@ -0,0 +1,135 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{ensure, Result};
+use reqwest::{Certificate, ClientBuilder, Identity};
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+const PEM_CERTIFICATE_END: &str = "-----END CERTIFICATE-----";
+
+#[derive(Debug, Error)]
+enum Error {
+    #[error("CA certificate file {path:?} contains no PEM certificates")]
+    NoCertificates { path: PathBuf },
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct TlsConfig {
+    /// PEM file with one or more certificates to trust in addition to the system ones.
+    pub ca_certificate_file: Option<PathBuf>,
+    /// Identity to present to signers that require mutual TLS.
+    pub client_identity: Option<ClientIdentity>,
+}
+
+#[derive(Clone, Debug)]
+pub enum ClientIdentity {
+    Pkcs12 {
+        identity_file: PathBuf,
+        password_file: Option<PathBuf>,
+    },
+    Pem {
+        certificate_file: PathBuf,
+        key_file: PathBuf,
+    },
+}
+
+impl TlsConfig {
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.ca_certificate_file.is_none() && self.client_identity.is_none()
+    }
+
+    pub fn configure(&self, mut builder: ClientBuilder) -> Result<ClientBuilder> {
+        if let Some(path) = &self.ca_certificate_file {
+            for certificate in load_certificates(path)? {
+                builder = builder.add_root_certificate(certificate);
+            }
+        }
+
+        if let Some(client_identity) = &self.client_identity {
+            builder = builder.identity(client_identity.load()?);
+        }
+
+        Ok(builder)
+    }
+}
+
+impl ClientIdentity {
+    fn load(&self) -> Result<Identity> {
+        let identity = match self {
+            Self::Pkcs12 {
+                identity_file,
+                password_file,
+            } => {
+                let der = fs_err::read(identity_file)?;
+
+                let password = match password_file {
+                    Some(path) => Zeroizing::new(fs_err::read_to_string(path)?),
+                    None => Zeroizing::default(),
+                };
+
+                Identity::from_pkcs12_der(&der, password.trim_end_matches(['\r', '\n']))?
+            }
+            Self::Pem {
+                certificate_file,
+                key_file,
+            } => {
+                let certificate = fs_err::read(certificate_file)?;
+                let key = Zeroizing::new(fs_err::read(key_file)?);
+
+                Identity::from_pkcs8_pem(&certificate, &key)?
+            }
+        };
+
+        Ok(identity)
+    }
+}
+
+// `Certificate::from_pem` only reads the first certificate in a file.
+// CA bundles commonly contain a chain of them.
+fn load_certificates(path: &Path) -> Result<Vec<Certificate>> {
+    let bundle = fs_err::read_to_string(path)?;
+
+    let certificates = split_pem_bundle(&bundle)
+        .map(|pem| Certificate::from_pem(pem.as_bytes()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    ensure!(
+        !certificates.is_empty(),
+        Error::NoCertificates {
+            path: path.to_path_buf(),
+        },
+    );
+
+    Ok(certificates)
+}
+
+fn split_pem_bundle(bundle: &str) -> impl Iterator<Item = &str> {
+    bundle
+        .split_inclusive(PEM_CERTIFICATE_END)
+        .filter(|pem| pem.ends_with(PEM_CERTIFICATE_END))
+        .map(str::trim_start)
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools as _;
+
+    use super::*;
+
+    #[test]
+    fn split_pem_bundle_returns_each_certificate() {
+        let bundle = "\
+            -----BEGIN CERTIFICATE-----\nAAAA\n-----END CERTIFICATE-----\n\
+            -----BEGIN CERTIFICATE-----\nBBBB\n-----END CERTIFICATE-----\n\
+            trailing garbage\n";
+
+        assert_eq!(
+            split_pem_bundle(bundle).collect_vec(),
+            [
+                "-----BEGIN CERTIFICATE-----\nAAAA\n-----END CERTIFICATE-----",
+                "-----BEGIN CERTIFICATE-----\nBBBB\n-----END CERTIFICATE-----",
+            ],
+        );
+    }
+}
@@ -0,0 +1,10 @@
+//! Harness for running several in-process nodes connected to each other over localhost.
+//!
+//! Interop validator keys are split among the nodes, so the network can only finalize if blocks
+//! and attestations propagate between them. This makes it possible to test p2p and validator
+//! behavior without any external infrastructure.
+
+pub use crate::simulation::{run, SimulationConfig};
+
+mod node;
+mod simulation;
@@ -0,0 +1,177 @@
+use core::time::Duration;
+use std::{
+    net::{Ipv4Addr, SocketAddr, TcpListener},
+    sync::Arc,
+};
+
+use anyhow::Result;
+use bls::{PublicKeyBytes, SecretKey};
+use bytesize::ByteSize;
+use clock::Clock;
+use database::Database;
+use deposit_tree::DepositTree;
+use directories::Directories;
+use eth1::{Eth1Chain, Eth1Config};
+use fork_choice_control::{StateLoadStrategy, DEFAULT_ARCHIVAL_EPOCH_INTERVAL};
+use fork_choice_store::StoreConfig;
+use genesis::GenesisProvider;
+use http_api::HttpApiConfig;
+use operation_pools::PoolConfig;
+use p2p::{Multiaddr, NetworkConfig};
+use reqwest::Client;
+use runtime::{MetricsConfig, StorageConfig, DEFAULT_SUBNET_PEER_DISCOVERY_DELAY};
+use signer::{KeyOrigin, Signer, Web3SignerConfig};
+use slashing_protection::DEFAULT_SLASHING_PROTECTION_HISTORY_LIMIT;
+use std_ext::ArcExt as _;
+use types::{config::Config as ChainConfig, phase0::primitives::ValidatorIndex, preset::Preset};
+use validator::ValidatorConfig;
+
+pub struct Node {
+    pub index: usize,
+    pub http_address: SocketAddr,
+    pub libp2p_address: Multiaddr,
+    libp2p_port: u16,
+    validator_indices: Vec<ValidatorIndex>,
+}
+
+impl Node {
+    pub fn new(index: usize, validator_indices: Vec<ValidatorIndex>) -> Result<Self> {
+        let libp2p_port = unused_port()?;
+
+        Ok(Self {
+            index,
+            http_address: (Ipv4Addr::LOCALHOST, unused_port()?).into(),
+            libp2p_address: format!("/ip4/{}/tcp/{libp2p_port}", Ipv4Addr::LOCALHOST).parse()?,
+            libp2p_port,
+            validator_indices,
+        })
+    }
+
+    pub async fn run<P: Preset>(
+        &self,
+        chain_config: Arc<ChainConfig>,
+        genesis_provider: GenesisProvider<P>,
+        deposit_tree: DepositTree,
+        peers: Vec<Multiaddr>,
+        clock: Arc<dyn Clock>,
+    ) -> Result<()> {
+        let client = Client::new();
+
+        let eth1_config = Arc::new(Eth1Config {
+            default_deposit_tree: Some(deposit_tree),
+            ..Eth1Config::default()
+        });
+
+        let eth1_chain = Eth1Chain::new(
+            chain_config.clone_arc(),
+            eth1_config.clone_arc(),
+            client.clone(),
+            Database::in_memory(),
+            None,
+            None,
+        )?;
+
+        eth1_chain.spawn_unfinalized_blocks_tracker_task()?;
+
+        let state_load_strategy = StateLoadStrategy::Anchor {
+            block: genesis_provider.block(),
+            state: genesis_provider.state(),
+        };
+
+        let storage_config = StorageConfig {
+            in_memory: true,
+            db_size: ByteSize::mb(0),
+            directories: Arc::new(Directories {
+                data_dir: None,
+                store_directory: None,
+                network_dir: None,
+                validator_dir: None,
+            }),
+            eth1_db_size: ByteSize::mb(0),
+            archival_epoch_interval: DEFAULT_ARCHIVAL_EPOCH_INTERVAL,
+            prune_storage: false,
+        };
+
+        let metrics_config = MetricsConfig {
+            metrics: None,
+            metrics_server_config: None,
+            metrics_service_config: None,
+        };
+
+        let signer = Signer::new(
+            self.validator_keys(),
+            client.clone(),
+            client,
+            Web3SignerConfig::default(),
+            None,
+        );
+
+        runtime::run_after_genesis(
+            chain_config.clone_arc(),
+            StoreConfig::minimal(&chain_config),
+            Arc::new(ValidatorConfig::default()),
+            self.network_config(peers)?,
+            genesis_provider,
+            state_load_strategy,
+            eth1_chain,
+            eth1_config,
+            storage_config,
+            None,
+            signer,
+            None,
+            HttpApiConfig::with_address(self.http_address.ip(), self.http_address.port()),
+            false,
+            metrics_config,
+            true,
+            Duration::from_millis(DEFAULT_SUBNET_PEER_DISCOVERY_DELAY),
+            None,
+            None,
+            DEFAULT_SLASHING_PROTECTION_HISTORY_LIMIT,
+            PoolConfig::default(),
+            clock,
+        )
+        .await
+    }
+
+    fn network_config(&self, peers: Vec<Multiaddr>) -> Result<NetworkConfig> {
+        let mut network_config = runtime::default_network_config();
+
+        network_config.set_ipv4_listening_address(
+            Ipv4Addr::LOCALHOST,
+            self.libp2p_port,
+            unused_port()?,
+            unused_port()?,
+        );
+
+        // Nodes find each other through `libp2p_nodes`. Discovery would only find nodes on
+        // other networks running on the same machine.
+        network_config.disable_discovery = true;
+        network_config.disable_quic_support = true;
+        network_config.upnp_enabled = false;
+        network_config.network_dir = None;
+        network_config.libp2p_nodes = peers;
+        network_config.subscribe_all_subnets = true;
+
+        Ok(network_config)
+    }
+
+    fn validator_keys(&self) -> Vec<(PublicKeyBytes, Arc<SecretKey>, KeyOrigin)> {
+        self.validator_indices
+            .iter()
+            .copied()
+            .map(interop::secret_key)
+            .map(|secret_key| {
+                let secret_key = Arc::new(secret_key);
+                let public_key = secret_key.to_public_key().into();
+                (public_key, secret_key, KeyOrigin::LocalFileSystem)
+            })
+            .collect()
+    }
+}
+
+// The port is released before the node binds it. This is a TOCTOU race condition,
+// but the only consequence of it is a failed simulation run.
+fn unused_port() -> Result<u16> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+    Ok(listener.local_addr()?.port())
+}
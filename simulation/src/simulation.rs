@@ -0,0 +1,431 @@
+use core::{
+    num::{NonZeroU64, NonZeroUsize},
+    time::Duration,
+};
+use std::{sync::Arc, time::SystemTime};
+
+use anyhow::{bail, ensure, Result};
+use clock::Clock;
+use educe::Educe;
+use futures::{future::try_join_all, TryStreamExt as _};
+use genesis::GenesisProvider;
+use log::info;
+use nonzero_ext::nonzero;
+use reqwest::Client;
+use serde::Deserialize;
+use std_ext::ArcExt as _;
+use thiserror::Error;
+use tokio::select;
+use typenum::Unsigned as _;
+use types::{
+    config::Config as ChainConfig,
+    phase0::{
+        containers::Checkpoint,
+        primitives::{Epoch, UnixSeconds, ValidatorIndex},
+    },
+    preset::Preset,
+};
+
+use crate::node::Node;
+
+// The genesis epoch is finalized from the start. Epoch 1 is the earliest one that can be finalized
+// through attestations, which happens at the start of epoch 3 with full participation.
+const MIN_EPOCHS: u64 = 3;
+
+#[derive(Clone, Copy, Debug, Educe)]
+#[educe(Default)]
+pub struct SimulationConfig {
+    #[educe(Default(expression = "nonzero!(4_usize)"))]
+    pub node_count: NonZeroUsize,
+    #[educe(Default(expression = "nonzero!(64_u64)"))]
+    pub validator_count: NonZeroU64,
+    #[educe(Default = 4)]
+    pub epochs: u64,
+    /// Time between starting the nodes and genesis. Nodes need it to connect to each other.
+    #[educe(Default(expression = "Duration::from_secs(10)"))]
+    pub genesis_delay: Duration,
+    /// Minimum percentage of validators that must be seen attesting in the last completed epoch.
+    #[educe(Default = 95)]
+    pub min_participation_percent: u64,
+}
+
+/// Runs `config.node_count` nodes until epoch `config.epochs` and checks their views of the chain.
+///
+/// Every node must have finalized the epoch 2 epochs before the last one and seen enough
+/// validators attesting in the epoch before the last one.
+/// All nodes and the checks follow `clock`.
+pub async fn run<P: Preset>(
+    chain_config: ChainConfig,
+    config: SimulationConfig,
+    clock: Arc<dyn Clock>,
+) -> Result<()> {
+    let SimulationConfig {
+        node_count,
+        validator_count,
+        epochs,
+        genesis_delay,
+        min_participation_percent,
+    } = config;
+
+    ensure!(epochs >= MIN_EPOCHS, Error::TooFewEpochs { epochs });
+
+    let genesis_time = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)?
+        .saturating_add(genesis_delay)
+        .as_secs();
+
+    let (genesis_state, deposit_tree) =
+        interop::quick_start_beacon_state::<P>(&chain_config, genesis_time, validator_count)?;
+
+    let chain_config = Arc::new(chain_config);
+    let genesis_provider = GenesisProvider::Custom(Arc::new(genesis_state));
+
+    let nodes = (0..node_count.get())
+        .map(|node_index| {
+            // Distribute keys round-robin so that every node has proposers in every epoch.
+            let validator_indices = (0..validator_count.get())
+                .skip(node_index)
+                .step_by(node_count.get())
+                .collect();
+
+            Node::new(node_index, validator_indices)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    info!(
+        "starting simulation with {node_count} nodes and {validator_count} validators \
+         (genesis time: {genesis_time})",
+    );
+
+    // Each node dials the ones started before it, which connects every pair of nodes.
+    let run_nodes = nodes.iter().enumerate().map(|(position, node)| {
+        let peers = nodes[..position]
+            .iter()
+            .map(|peer| peer.libp2p_address.clone())
+            .collect();
+
+        node.run(
+            chain_config.clone_arc(),
+            genesis_provider.clone(),
+            deposit_tree,
+            peers,
+            clock.clone_arc(),
+        )
+    });
+
+    let check_nodes = check_nodes::<P>(
+        &chain_config,
+        clock.as_ref(),
+        &nodes,
+        genesis_time,
+        epochs,
+        validator_count,
+        min_participation_percent,
+    );
+
+    select! {
+        result = try_join_all(run_nodes) => {
+            result?;
+            bail!(Error::NodeStopped)
+        }
+        result = check_nodes => result,
+    }
+}
+
+async fn check_nodes<P: Preset>(
+    chain_config: &ChainConfig,
+    clock: &dyn Clock,
+    nodes: &[Node],
+    genesis_time: UnixSeconds,
+    epochs: u64,
+    validator_count: NonZeroU64,
+    min_participation_percent: u64,
+) -> Result<()> {
+    // Wait until a slot into the last epoch to give nodes time to process the epoch transition.
+    // Subscribe to ticks before checking the current one to avoid missing the slot.
+    let check_slot = epochs * P::SlotsPerEpoch::U64 + 1;
+    let mut ticks = clock.ticks(chain_config, genesis_time)?;
+
+    if clock.current_tick(chain_config, genesis_time)?.slot < check_slot {
+        while let Some(tick) = ticks.try_next().await? {
+            if tick.slot >= check_slot {
+                break;
+            }
+        }
+    }
+
+    let client = Client::new();
+    let expected_finalized_epoch = epochs - 2;
+    let participation_epoch = epochs - 1;
+    let validator_indices = (0..validator_count.get()).collect::<Vec<_>>();
+
+    for node in nodes {
+        let finalized_epoch = finalized_epoch(&client, node).await?;
+
+        info!("node {} finalized epoch {finalized_epoch}", node.index);
+
+        ensure!(
+            finalized_epoch >= expected_finalized_epoch,
+            Error::NotFinalized {
+                node: node.index,
+                finalized_epoch,
+                expected_finalized_epoch,
+            },
+        );
+
+        let live_count = live_validator_count(
+            &client,
+            node,
+            participation_epoch,
+            validator_indices.as_slice(),
+        )
+        .await?;
+
+        info!(
+            "node {} saw {live_count}/{validator_count} validators \
+             attesting in epoch {participation_epoch}",
+            node.index,
+        );
+
+        ensure!(
+            live_count * 100 >= validator_count.get() * min_participation_percent,
+            Error::LowParticipation {
+                node: node.index,
+                epoch: participation_epoch,
+                live_count,
+                validator_count,
+            },
+        );
+    }
+
+    Ok(())
+}
+
+async fn finalized_epoch(client: &Client, node: &Node) -> Result<Epoch> {
+    let url = format!(
+        "http://{}/eth/v1/beacon/states/head/finality_checkpoints",
+        node.http_address,
+    );
+
+    let response = client
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<DataResponse<FinalityCheckpoints>>()
+        .await?;
+
+    Ok(response.data.finalized.epoch)
+}
+
+async fn live_validator_count(
+    client: &Client,
+    node: &Node,
+    epoch: Epoch,
+    validator_indices: &[ValidatorIndex],
+) -> Result<u64> {
+    let url = format!(
+        "http://{}/eth/v1/validator/liveness/{epoch}",
+        node.http_address,
+    );
+
+    let body = validator_indices
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+
+    let response = client
+        .post(url)
+        .json(&body)
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<DataResponse<Vec<ValidatorLiveness>>>()
+        .await?;
+
+    Ok(response
+        .data
+        .into_iter()
+        .filter(|liveness| liveness.is_live)
+        .count()
+        .try_into()?)
+}
+
+#[derive(Deserialize)]
+struct DataResponse<T> {
+    data: T,
+}
+
+#[derive(Deserialize)]
+struct FinalityCheckpoints {
+    finalized: Checkpoint,
+}
+
+#[derive(Deserialize)]
+struct ValidatorLiveness {
+    is_live: bool,
+}
+
+#[derive(Debug, Error)]
+enum Error {
+    #[error("simulation must run for at least {MIN_EPOCHS} epochs (epochs: {epochs})")]
+    TooFewEpochs { epochs: u64 },
+    #[error("nodes stopped before the simulation ended")]
+    NodeStopped,
+    #[error(
+        "node {node} has not finalized epoch {expected_finalized_epoch} \
+         (finalized epoch: {finalized_epoch})"
+    )]
+    NotFinalized {
+        node: usize,
+        finalized_epoch: Epoch,
+        expected_finalized_epoch: Epoch,
+    },
+    #[error(
+        "node {node} saw only {live_count}/{validator_count} validators attesting in epoch {epoch}"
+    )]
+    LowParticipation {
+        node: usize,
+        epoch: Epoch,
+        live_count: u64,
+        validator_count: NonZeroU64,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use clock::SystemClock;
+    use httpmock::{Method, MockServer};
+    use types::{phase0::primitives::H256, preset::Minimal};
+
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[ignore = "runs for several minutes and binds ports on localhost"]
+    async fn minimal_network_finalizes_with_keys_split_among_nodes() -> Result<()> {
+        run::<Minimal>(
+            ChainConfig::minimal(),
+            SimulationConfig::default(),
+            Arc::new(SystemClock),
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn simulation_must_run_for_minimum_number_of_epochs() {
+        let config = SimulationConfig {
+            epochs: MIN_EPOCHS - 1,
+            ..SimulationConfig::default()
+        };
+
+        let error = run::<Minimal>(ChainConfig::minimal(), config, Arc::new(SystemClock))
+            .await
+            .expect_err("simulation should not start");
+
+        assert!(matches!(
+            error.downcast_ref::<Error>(),
+            Some(Error::TooFewEpochs { .. }),
+        ));
+    }
+
+    #[tokio::test]
+    async fn checks_pass_when_node_finalized_with_enough_participation() -> Result<()> {
+        let server = MockServer::start();
+        let node = mock_node(&server, 2, [true, true, true, false])?;
+
+        check_nodes::<Minimal>(
+            &ChainConfig::minimal(),
+            &SystemClock,
+            &[node],
+            0,
+            4,
+            nonzero!(4_u64),
+            75,
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn checks_fail_when_node_has_not_finalized() -> Result<()> {
+        let server = MockServer::start();
+        let node = mock_node(&server, 1, [true; 4])?;
+
+        let error = check_nodes::<Minimal>(
+            &ChainConfig::minimal(),
+            &SystemClock,
+            &[node],
+            0,
+            4,
+            nonzero!(4_u64),
+            75,
+        )
+        .await
+        .expect_err("checks should fail");
+
+        assert!(matches!(
+            error.downcast_ref::<Error>(),
+            Some(Error::NotFinalized {
+                finalized_epoch: 1,
+                expected_finalized_epoch: 2,
+                ..
+            }),
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn checks_fail_when_participation_is_low() -> Result<()> {
+        let server = MockServer::start();
+        let node = mock_node(&server, 2, [true, true, false, false])?;
+
+        let error = check_nodes::<Minimal>(
+            &ChainConfig::minimal(),
+            &SystemClock,
+            &[node],
+            0,
+            4,
+            nonzero!(4_u64),
+            75,
+        )
+        .await
+        .expect_err("checks should fail");
+
+        assert!(matches!(
+            error.downcast_ref::<Error>(),
+            Some(Error::LowParticipation { live_count: 2, .. }),
+        ));
+
+        Ok(())
+    }
+
+    fn mock_node(server: &MockServer, finalized_epoch: Epoch, liveness: [bool; 4]) -> Result<Node> {
+        server.mock(|when, then| {
+            when.method(Method::GET)
+                .path("/eth/v1/beacon/states/head/finality_checkpoints");
+            then.status(200).body(format!(
+                r#"{{"data": {{"finalized": {{"epoch": "{finalized_epoch}", "root": "{:?}"}}}}}}"#,
+                H256::zero(),
+            ));
+        });
+
+        let liveness = liveness
+            .iter()
+            .enumerate()
+            .map(|(index, is_live)| format!(r#"{{"index": "{index}", "is_live": {is_live}}}"#))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        server.mock(|when, then| {
+            when.method(Method::POST)
+                .path("/eth/v1/validator/liveness/3");
+            then.status(200)
+                .body(format!(r#"{{"data": [{liveness}]}}"#));
+        });
+
+        let mut node = Node::new(0, vec![])?;
+        node.http_address = *server.address();
+
+        Ok(node)
+    }
+}
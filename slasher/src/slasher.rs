@@ -6,13 +6,14 @@ use database::Database;
 use eth1_api::RealController;
 use features::Feature;
 use futures::{
-    channel::mpsc::{UnboundedReceiver, UnboundedSender},
+    channel::mpsc::{Receiver, UnboundedReceiver, UnboundedSender},
     select,
     stream::StreamExt,
 };
 use helper_functions::{accessors, misc};
 use log::{debug, info, warn};
-use p2p::P2pToSlasher;
+use p2p::{P2pToSlasher, SLASHER_CHANNEL};
+use prometheus_metrics::Metrics;
 use thiserror::Error;
 use types::{
     combined::SignedBeaconBlock,
@@ -61,8 +62,9 @@ pub struct Slasher<P: Preset> {
     fork_version: Version,
     attestations: Attestations<P>,
     blocks: Blocks,
+    metrics: Option<Arc<Metrics>>,
     slasher_to_validator_tx: UnboundedSender<SlasherToValidator<P>>,
-    network_to_slasher_rx: UnboundedReceiver<P2pToSlasher<P>>,
+    network_to_slasher_rx: Receiver<P2pToSlasher<P>>,
     validator_to_slasher_rx: UnboundedReceiver<ValidatorToSlasher>,
 }
 
@@ -73,8 +75,9 @@ impl<P: Preset> Slasher<P> {
         controller: RealController<P>,
         fork_version: Version,
         databases: Databases,
+        metrics: Option<Arc<Metrics>>,
         slasher_to_validator_tx: UnboundedSender<SlasherToValidator<P>>,
-        network_to_slasher_rx: UnboundedReceiver<P2pToSlasher<P>>,
+        network_to_slasher_rx: Receiver<P2pToSlasher<P>>,
         validator_to_slasher_rx: UnboundedReceiver<ValidatorToSlasher>,
     ) -> Self {
         let Databases {
@@ -96,6 +99,7 @@ impl<P: Preset> Slasher<P> {
                 max_targets_db,
             ),
             blocks: Blocks::new(config, blocks_db),
+            metrics,
             slasher_to_validator_tx,
             network_to_slasher_rx,
             validator_to_slasher_rx,
@@ -106,6 +110,10 @@ impl<P: Preset> Slasher<P> {
         loop {
             select! {
                 network_message = self.network_to_slasher_rx.select_next_some() => {
+                    if let Some(metrics) = self.metrics.as_ref() {
+                        metrics.dec_gossip_channel_length(SLASHER_CHANNEL);
+                    }
+
                     let result = match network_message {
                         P2pToSlasher::Attestation(attestation) => self
                             .process_attestation(&attestation)
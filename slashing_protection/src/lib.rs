@@ -4,7 +4,7 @@ use anyhow::Result;
 use bls::PublicKeyBytes;
 use educe::Educe;
 use fs_err::File;
-use helper_functions::{accessors, misc, signing::SignForSingleFork};
+use helper_functions::{accessors, misc, signing_domains::SigningDomains};
 use itertools::Itertools as _;
 use log::{debug, info, warn};
 use rusqlite::{Connection, OptionalExtension, Rows, Transaction, TransactionBehavior};
@@ -639,6 +639,8 @@ impl SlashingProtector {
             return Ok(vec![]);
         }
 
+        let signing_domains = SigningDomains::for_state(config, state);
+
         let proposals = attestations
             .clone()
             .into_iter()
@@ -648,7 +650,7 @@ impl SlashingProtector {
                 let proposal = AttestationProposal {
                     source_epoch: data.source.epoch,
                     target_epoch: data.target.epoch,
-                    signing_root: Some(data.signing_root(config, state)),
+                    signing_root: Some(signing_domains.signing_root(&data)),
                 };
 
                 (proposal, pubkey)
@@ -10,7 +10,10 @@ use bstr::ByteSlice as _;
 use derive_more::From;
 use fs_err::tokio::{File, OpenOptions};
 use futures::stream::{StreamExt as _, TryStreamExt as _};
-use http::{header::DATE, Version};
+use http::{
+    header::{HeaderName, CONTENT_LENGTH, DATE},
+    Version,
+};
 use httparse::{Header, Request, Response, Status, EMPTY_HEADER};
 use itertools::Itertools as _;
 use serde_json::Value;
@@ -35,6 +38,15 @@ const EMPTY_HEADERS: [Header; MAX_HEADERS] = [EMPTY_HEADER; MAX_HEADERS];
 // We use a valid date to retain compatibility with other tools.
 const NORMALIZED_DATE: &str = "Thu, 01 Jan 1970 00:00:00 GMT";
 
+// Test cases with names ending with this can be recorded from a live node with `Case::record`.
+// Responses in them are recorded from a node synced to a real network and replayed against
+// a node started from cached data, so some fields in them are expected to differ.
+const RECORDED_SUFFIX: &str = "recorded";
+
+// Top-level fields that depend on the view of the chain of the node serving the request.
+// Their values are replaced with `null` in `*recorded` test cases.
+const NONDETERMINISTIC_FIELDS: &[&str] = &["execution_optimistic", "finalized"];
+
 #[derive(Clone, Copy, From)]
 pub struct Case<'path> {
     case_path_relative_to_workspace_root: &'path str,
@@ -47,6 +59,40 @@ impl<'path> Case<'path> {
             .with_context(|| format!("test case {} failed", self.file_name()))
     }
 
+    /// Submits requests in the test case to a live node and saves its responses.
+    ///
+    /// This is meant for generating new test cases from real data.
+    /// The responses are normalized the same way as in [`Case::run`].
+    pub async fn record(self, live_address: SocketAddr) -> Result<()> {
+        ensure!(
+            self.is_recorded(),
+            "only test cases with names ending with {RECORDED_SUFFIX:?} can be recorded",
+        );
+
+        self.glob("*.request")
+            .pipe(futures::stream::iter)
+            .then(|request_path| async move {
+                let response_bytes = self
+                    .fetch_normalized_response(live_address, request_path.as_path())
+                    .await
+                    .with_context(|| format!("recording request {request_path:?} failed"))?;
+
+                self.file_for_writing(request_path.with_extension("response"))
+                    .await
+                    .write_all(response_bytes.as_slice())
+                    .await
+                    .map_err(Error::from)
+            })
+            .try_collect()
+            .await
+            .with_context(|| format!("recording test case {} failed", self.file_name()))
+    }
+
+    #[must_use]
+    pub fn is_recorded(self) -> bool {
+        self.ends_with(RECORDED_SUFFIX)
+    }
+
     async fn submit_requests(self, update_responses: bool, address: SocketAddr) -> Result<()> {
         let results =
             self.glob("*.request")
@@ -96,6 +142,37 @@ impl<'path> Case<'path> {
         address: SocketAddr,
         request_path: &Path,
     ) -> Result<()> {
+        let actual_bytes = self
+            .fetch_normalized_response(address, request_path)
+            .await?;
+
+        let response_path = request_path.with_extension("response");
+
+        if update_responses {
+            self.file_for_writing(response_path)
+                .await
+                .write_all(actual_bytes.as_slice())
+                .await?;
+
+            return Ok(());
+        }
+
+        let mut response_file = self.file_for_reading(response_path).await;
+
+        let expected_response_length = response_file.metadata().await?.len().try_into()?;
+
+        let mut expected_bytes = Vec::with_capacity(expected_response_length);
+
+        response_file.read_to_end(&mut expected_bytes).await?;
+
+        compare_responses(actual_bytes.as_slice(), expected_bytes.as_slice())
+    }
+
+    async fn fetch_normalized_response(
+        self,
+        address: SocketAddr,
+        request_path: &Path,
+    ) -> Result<Vec<u8>> {
         let mut request_file = self.file_for_reading(request_path).await;
 
         let request_length = request_file.metadata().await?.len().try_into()?;
@@ -110,35 +187,16 @@ impl<'path> Case<'path> {
 
         stream.write_all(request_bytes.as_slice()).await?;
 
-        let response_path = request_path.with_extension("response");
-
-        let mut response_file = if update_responses {
-            self.file_for_writing(response_path).await
-        } else {
-            self.file_for_reading(response_path).await
-        };
-
-        let expected_response_length = response_file.metadata().await?.len().try_into()?;
-
-        let mut actual_bytes = Vec::with_capacity(expected_response_length);
+        let mut response_bytes = vec![];
 
         // Do not make assertions about the actual length of the response.
         // It only gets in the way of debugging.
-        stream.read_to_end(&mut actual_bytes).await?;
+        stream.read_to_end(&mut response_bytes).await?;
 
-        normalize_response_headers(&mut actual_bytes)?;
-        normalize_response_body(&mut actual_bytes)?;
-
-        if update_responses {
-            response_file.write_all(actual_bytes.as_slice()).await?;
-            return Ok(());
-        }
-
-        let mut expected_bytes = Vec::with_capacity(expected_response_length);
-
-        response_file.read_to_end(&mut expected_bytes).await?;
+        normalize_response_headers(&mut response_bytes)?;
+        normalize_response_body(&mut response_bytes, self.is_recorded())?;
 
-        compare_responses(actual_bytes.as_slice(), expected_bytes.as_slice())
+        Ok(response_bytes)
     }
 
     fn file_name(self) -> &'path str {
@@ -220,6 +278,53 @@ fn validate_request(bytes: &[u8]) -> Result<()> {
 }
 
 fn normalize_response_headers(bytes: &mut Vec<u8>) -> Result<()> {
+    replace_header_values(bytes, &DATE, NORMALIZED_DATE)
+}
+
+fn normalize_response_body(bytes: &mut Vec<u8>, recorded: bool) -> Result<()> {
+    let mut headers = EMPTY_HEADERS;
+
+    let (_, body) = parse_response(bytes, &mut headers)?;
+
+    // If the response body is valid JSON, pretty-print it.
+    if let Ok(mut json) = serde_json::from_slice::<Value>(body) {
+        if recorded {
+            normalize_nondeterministic_fields(&mut json);
+        }
+
+        let body_offset = bytes.len() - body.len();
+        let pretty_printed = serde_json::to_string_pretty(&json)?;
+
+        bytes.truncate(body_offset);
+        bytes.extend_from_slice(pretty_printed.as_bytes());
+
+        // Responses from live nodes may differ in length before normalization.
+        // Make `Content-Length` match the normalized body so that it does not cause mismatches.
+        if recorded {
+            let content_length = pretty_printed.len().to_string();
+            replace_header_values(bytes, &CONTENT_LENGTH, content_length.as_str())?;
+        }
+    }
+
+    Ok(())
+}
+
+// Only top-level metadata is normalized.
+// Fields with the same names inside `data` (like `finalized` in state finality checkpoints)
+// are part of the response proper and must still match.
+fn normalize_nondeterministic_fields(json: &mut Value) {
+    let Value::Object(object) = json else {
+        return;
+    };
+
+    for (key, value) in object {
+        if NONDETERMINISTIC_FIELDS.contains(&key.as_str()) {
+            *value = Value::Null;
+        }
+    }
+}
+
+fn replace_header_values(bytes: &mut Vec<u8>, name: &HeaderName, value: &str) -> Result<()> {
     // Loop for 2 reasons:
     // - Modifying `bytes` invalidates the references in `response`.
     // - A header may occur in the response multiple times.
@@ -230,12 +335,12 @@ fn normalize_response_headers(bytes: &mut Vec<u8>) -> Result<()> {
 
         for header in response.headers.iter() {
             // The name comparison ignores case, just like `str::eq_ignore_ascii_case`.
-            if header.name == DATE && header.value != NORMALIZED_DATE.as_bytes() {
+            if header.name == *name && header.value != value.as_bytes() {
                 let Range { start, end } = header.value.as_ptr_range();
                 let start_offset = start as usize - bytes.as_slice().as_ptr() as usize;
                 let end_offset = end as usize - bytes.as_slice().as_ptr() as usize;
 
-                bytes.splice(start_offset..end_offset, NORMALIZED_DATE.bytes());
+                bytes.splice(start_offset..end_offset, value.bytes());
 
                 continue 'outer;
             }
@@ -247,23 +352,6 @@ fn normalize_response_headers(bytes: &mut Vec<u8>) -> Result<()> {
     Ok(())
 }
 
-fn normalize_response_body(bytes: &mut Vec<u8>) -> Result<()> {
-    let mut headers = EMPTY_HEADERS;
-
-    let (_, body) = parse_response(bytes, &mut headers)?;
-
-    // If the response body is valid JSON, pretty-print it.
-    if let Ok(json) = serde_json::from_slice::<Value>(body) {
-        let body_offset = bytes.len() - body.len();
-        let pretty_printed = serde_json::to_string_pretty(&json)?;
-
-        bytes.truncate(body_offset);
-        bytes.extend_from_slice(pretty_printed.as_bytes());
-    }
-
-    Ok(())
-}
-
 fn compare_responses(actual_bytes: &[u8], expected_bytes: &[u8]) -> Result<()> {
     // Compare bytes first to speed up the successful path.
     // If that fails, compare the responses in a finer-grained manner for better error messages.
@@ -438,3 +526,40 @@ fn workspace_root_relative_to_tested_crate_root() -> PathBuf {
     pathdiff::diff_paths(workspace_root, tested_crate_root)
         .expect("snapshot_test_utils should only be used by crates inside the same workspace")
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn only_top_level_metadata_is_normalized() {
+        let mut json = json!({
+            "execution_optimistic": false,
+            "finalized": true,
+            "data": {
+                "finalized": {
+                    "epoch": "1",
+                    "root": "0x00",
+                },
+            },
+        });
+
+        normalize_nondeterministic_fields(&mut json);
+
+        assert_eq!(
+            json,
+            json!({
+                "execution_optimistic": null,
+                "finalized": null,
+                "data": {
+                    "finalized": {
+                        "epoch": "1",
+                        "root": "0x00",
+                    },
+                },
+            }),
+        );
+    }
+}
@@ -266,6 +266,11 @@ impl Uint256 {
         ]))
     }
 
+    #[must_use]
+    pub fn saturating_mul(self, other: Self) -> Self {
+        Self(self.into_raw().saturating_mul(other.into_raw()))
+    }
+
     const fn into_raw(self) -> RawUint256 {
         self.0
     }
@@ -1,3 +1,5 @@
+use core::ops::AddAssign;
+
 use helper_functions::{
     accessors::{
         combined_participation, compute_base_reward, get_base_reward_per_increment,
@@ -6,7 +8,11 @@ use helper_functions::{
     mutators::clamp_balance,
     predicates::{is_active_validator, is_eligible_for_penalties, is_in_inactivity_leak},
 };
-use itertools::izip;
+use itertools::{izip, Itertools as _};
+use rayon::{
+    iter::{IndexedParallelIterator as _, IntoParallelIterator as _, ParallelIterator as _},
+    slice::ParallelSlice as _,
+};
 use serde::Serialize;
 use static_assertions::assert_eq_size;
 use types::{
@@ -28,7 +34,11 @@ use types::{
 
 use crate::unphased::{EpochDeltas, ValidatorSummary};
 
-pub trait AltairEpochDeltas: Default {
+// Large enough to make scheduling overhead negligible, small enough to spread mainnet validators
+// over all cores.
+const STATISTICS_CHUNK_SIZE: usize = 1 << 12;
+
+pub trait AltairEpochDeltas: Default + Send {
     fn add_source_reward(&mut self, value: Gwei);
     fn add_source_penalty(&mut self, value: Gwei);
     fn add_target_reward(&mut self, value: Gwei);
@@ -71,6 +81,19 @@ pub struct Statistics {
     pub current_epoch_target_participating_balance: Gwei,
 }
 
+impl AddAssign for Statistics {
+    fn add_assign(&mut self, other: Self) {
+        self.previous_epoch_source_participating_balance +=
+            other.previous_epoch_source_participating_balance;
+        self.previous_epoch_target_participating_balance +=
+            other.previous_epoch_target_participating_balance;
+        self.previous_epoch_head_participating_balance +=
+            other.previous_epoch_head_participating_balance;
+        self.current_epoch_target_participating_balance +=
+            other.current_epoch_target_participating_balance;
+    }
+}
+
 impl Statistics {
     fn clamp_balances<P: Preset>(&mut self) {
         clamp_balance::<P>(&mut self.previous_epoch_source_participating_balance);
@@ -174,63 +197,97 @@ pub fn statistics<P: Preset, S: PostAltairBeaconState<P>>(
     let current_epoch = get_current_epoch(state);
     let previous_epoch = get_previous_epoch(state);
     let participation = combined_participation(state);
+    let validators = state.validators().into_iter().collect_vec();
+
+    // Summarize validators in chunks to avoid storing statistics for every validator.
+    // Chunk results are combined in order, so the output does not depend on scheduling.
+    let chunk_results = validators
+        .par_chunks(STATISTICS_CHUNK_SIZE)
+        .zip(participation.par_chunks(STATISTICS_CHUNK_SIZE))
+        .map(|(validators, participation)| {
+            let mut statistics = Statistics::default();
+
+            let summaries = validators
+                .iter()
+                .zip(participation.iter().copied())
+                .map(|(validator, participation)| {
+                    summarize_validator(
+                        validator,
+                        participation,
+                        previous_epoch,
+                        current_epoch,
+                        &mut statistics,
+                    )
+                })
+                .collect_vec();
+
+            (statistics, summaries)
+        })
+        .collect::<Vec<_>>();
 
     let mut statistics = Statistics::default();
+    let mut summaries = Vec::with_capacity(validators.len());
 
-    let summaries = state
-        .validators()
-        .into_iter()
-        .zip(participation.iter().copied())
-        .map(|(validator, participation)| {
-            let Validator {
-                effective_balance,
-                slashed,
-                withdrawable_epoch,
-                ..
-            } = *validator;
-
-            let active_in_previous_epoch = is_active_validator(validator, previous_epoch);
-            let active_in_current_epoch = is_active_validator(validator, current_epoch);
-            let eligible_for_penalties = is_eligible_for_penalties(validator, previous_epoch);
-
-            if !slashed {
-                // Unlike `get_unslashed_attesting_indices` in Phase 0,
-                // `get_unslashed_participating_indices` in Altair checks if validators were active.
-                // There doesn't seem to be a way for a validator that's not active to attest in
-                // normal operation, but some test cases in `consensus-spec-tests` cover the check.
-
-                if active_in_previous_epoch {
-                    if participation.previous_epoch_matching_source() {
-                        statistics.previous_epoch_source_participating_balance += effective_balance;
-                    }
-
-                    if participation.previous_epoch_matching_target() {
-                        statistics.previous_epoch_target_participating_balance += effective_balance;
-                    }
-
-                    if participation.previous_epoch_matching_head() {
-                        statistics.previous_epoch_head_participating_balance += effective_balance;
-                    }
-                }
+    for (chunk_statistics, chunk_summaries) in chunk_results {
+        statistics += chunk_statistics;
+        summaries.extend(chunk_summaries);
+    }
 
-                if active_in_current_epoch && participation.current_epoch_matching_target() {
-                    statistics.current_epoch_target_participating_balance += effective_balance;
-                }
+    statistics.clamp_balances::<P>();
+
+    (statistics, summaries, participation)
+}
+
+fn summarize_validator(
+    validator: &Validator,
+    participation: Participation,
+    previous_epoch: Epoch,
+    current_epoch: Epoch,
+    statistics: &mut Statistics,
+) -> AltairValidatorSummary {
+    let Validator {
+        effective_balance,
+        slashed,
+        withdrawable_epoch,
+        ..
+    } = *validator;
+
+    let active_in_previous_epoch = is_active_validator(validator, previous_epoch);
+    let active_in_current_epoch = is_active_validator(validator, current_epoch);
+    let eligible_for_penalties = is_eligible_for_penalties(validator, previous_epoch);
+
+    if !slashed {
+        // Unlike `get_unslashed_attesting_indices` in Phase 0,
+        // `get_unslashed_participating_indices` in Altair checks if validators were active.
+        // There doesn't seem to be a way for a validator that's not active to attest in
+        // normal operation, but some test cases in `consensus-spec-tests` cover the check.
+
+        if active_in_previous_epoch {
+            if participation.previous_epoch_matching_source() {
+                statistics.previous_epoch_source_participating_balance += effective_balance;
             }
 
-            AltairValidatorSummary {
-                effective_balance,
-                slashed,
-                withdrawable_epoch,
-                active_in_previous_epoch,
-                eligible_for_penalties,
+            if participation.previous_epoch_matching_target() {
+                statistics.previous_epoch_target_participating_balance += effective_balance;
             }
-        })
-        .collect();
 
-    statistics.clamp_balances::<P>();
+            if participation.previous_epoch_matching_head() {
+                statistics.previous_epoch_head_participating_balance += effective_balance;
+            }
+        }
 
-    (statistics, summaries, participation)
+        if active_in_current_epoch && participation.current_epoch_matching_target() {
+            statistics.current_epoch_target_participating_balance += effective_balance;
+        }
+    }
+
+    AltairValidatorSummary {
+        effective_balance,
+        slashed,
+        withdrawable_epoch,
+        active_in_previous_epoch,
+        eligible_for_penalties,
+    }
 }
 
 pub fn epoch_deltas<P: Preset, D: AltairEpochDeltas>(
@@ -249,7 +306,11 @@ pub fn epoch_deltas<P: Preset, D: AltairEpochDeltas>(
     let head_increments = statistics.previous_epoch_head_participating_balance / increment;
     let active_increments = total_active_balance(state) / increment;
 
+    // The inputs are collected first so that deltas can be computed in parallel.
+    // Collecting an indexed parallel iterator preserves the order of validators.
     izip!(summaries, participation, &state.inactivity_scores)
+        .collect_vec()
+        .into_par_iter()
         .map(|(summary, participation, inactivity_score)| {
             let mut deltas = D::default();
 
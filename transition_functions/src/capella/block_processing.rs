@@ -28,7 +28,10 @@ use types::{
     config::Config,
     phase0::primitives::{ExecutionAddress, H256},
     preset::Preset,
-    traits::{PostCapellaBeaconBlockBody, PostCapellaBeaconState, PostCapellaExecutionPayload},
+    traits::{
+        BeaconState as AnyBeaconState, PostCapellaBeaconBlockBody, PostCapellaBeaconState,
+        PostCapellaExecutionPayload,
+    },
 };
 
 use crate::{
@@ -287,9 +290,11 @@ pub fn process_bls_to_execution_change<P: Preset>(
     Ok(())
 }
 
+// The signing domain of `BlsToExecutionChange` is fork-agnostic, so changes can be validated
+// against states from phases before Capella as well.
 pub fn validate_bls_to_execution_change<P: Preset>(
     config: &Config,
-    state: &(impl PostCapellaBeaconState<P> + ?Sized),
+    state: &(impl AnyBeaconState<P> + ?Sized),
     bls_to_execution_change: SignedBlsToExecutionChange,
 ) -> Result<()> {
     validate_bls_to_execution_change_with_verifier(
@@ -302,7 +307,7 @@ pub fn validate_bls_to_execution_change<P: Preset>(
 
 fn validate_bls_to_execution_change_with_verifier<P: Preset>(
     config: &Config,
-    state: &(impl PostCapellaBeaconState<P> + ?Sized),
+    state: &(impl AnyBeaconState<P> + ?Sized),
     bls_to_execution_change: SignedBlsToExecutionChange,
     mut verifier: impl Verifier,
 ) -> Result<()> {
@@ -2,7 +2,8 @@ use helper_functions::{
     accessors::{compute_base_reward, get_base_reward_per_increment, total_active_balance},
     predicates::is_in_inactivity_leak,
 };
-use itertools::izip;
+use itertools::{izip, Itertools as _};
+use rayon::iter::{IntoParallelIterator as _, ParallelIterator as _};
 use types::{
     altair::consts::{
         TIMELY_HEAD_WEIGHT, TIMELY_SOURCE_WEIGHT, TIMELY_TARGET_WEIGHT, WEIGHT_DENOMINATOR,
@@ -32,6 +33,8 @@ pub fn epoch_deltas<P: Preset, D: EpochDeltas>(
     let active_increments = total_active_balance(state) / increment;
 
     izip!(summaries, participation, &state.inactivity_scores)
+        .collect_vec()
+        .into_par_iter()
         .map(|(summary, participation, inactivity_score)| {
             let mut deltas = D::default();
 
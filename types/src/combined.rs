@@ -66,7 +66,9 @@ use crate::{
             BeaconBlock as Phase0BeaconBlock, SignedBeaconBlock as Phase0SignedBeaconBlock,
             SignedBeaconBlockHeader,
         },
-        primitives::{ExecutionBlockHash, ExecutionBlockNumber, Slot, UnixSeconds},
+        primitives::{
+            ExecutionAddress, ExecutionBlockHash, ExecutionBlockNumber, Slot, UnixSeconds,
+        },
     },
     preset::{Mainnet, Preset},
     traits::{
@@ -866,6 +868,14 @@ impl<P: Preset> ExecutionPayload<P> {
             Self::Deneb(payload) => payload.block_hash,
         }
     }
+
+    pub const fn fee_recipient(&self) -> ExecutionAddress {
+        match self {
+            Self::Bellatrix(payload) => payload.fee_recipient,
+            Self::Capella(payload) => payload.fee_recipient,
+            Self::Deneb(payload) => payload.fee_recipient,
+        }
+    }
 }
 
 #[derive(From, Deserialize)]
@@ -884,6 +894,14 @@ impl<P: Preset> ExecutionPayloadHeader<P> {
             Self::Deneb(_) => Phase::Deneb,
         }
     }
+
+    pub const fn fee_recipient(&self) -> ExecutionAddress {
+        match self {
+            Self::Bellatrix(header) => header.fee_recipient,
+            Self::Capella(header) => header.fee_recipient,
+            Self::Deneb(header) => header.fee_recipient,
+        }
+    }
 }
 
 // TODO(feature/deneb): `ExecutionPayloadParams` seems to correspond to `NewPayloadRequest` from
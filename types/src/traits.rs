@@ -50,8 +50,8 @@ use crate::{
         primitives::WithdrawalIndex,
     },
     collections::{
-        Balances, EpochParticipation, Eth1DataVotes, HistoricalRoots, InactivityScores,
-        RandaoMixes, RecentRoots, Slashings, Validators,
+        Balances, EpochParticipation, Eth1DataVotes, HistoricalRoots, HistoricalSummaries,
+        InactivityScores, RandaoMixes, RecentRoots, Slashings, Validators,
     },
     combined::{
         BeaconBlock as CombinedBeaconBlock, BeaconState as CombinedBeaconState,
@@ -339,6 +339,7 @@ impl<parameters> BeaconState<P> for implementor {
 pub trait PostAltairBeaconState<P: Preset>: BeaconState<P> {
     fn previous_epoch_participation(&self) -> &EpochParticipation<P>;
     fn current_epoch_participation(&self) -> &EpochParticipation<P>;
+    fn inactivity_scores(&self) -> &InactivityScores<P>;
     fn current_sync_committee(&self) -> &Arc<Hc<SyncCommittee<P>>>;
     fn next_sync_committee(&self) -> &Arc<Hc<SyncCommittee<P>>>;
 
@@ -385,6 +386,7 @@ impl<parameters> PostAltairBeaconState<P> for implementor {
         field                          return_type;
         [previous_epoch_participation] [EpochParticipation<P>];
         [current_epoch_participation]  [EpochParticipation<P>];
+        [inactivity_scores]            [InactivityScores<P>];
         [current_sync_committee]       [Arc<Hc<SyncCommittee<P>>>];
         [next_sync_committee]          [Arc<Hc<SyncCommittee<P>>>];
     )]
@@ -456,6 +458,8 @@ pub trait PostCapellaBeaconState<P: Preset>: PostBellatrixBeaconState<P> {
 
     fn next_withdrawal_validator_index(&self) -> ValidatorIndex;
     fn next_withdrawal_validator_index_mut(&mut self) -> &mut ValidatorIndex;
+
+    fn historical_summaries(&self) -> &HistoricalSummaries<P>;
 }
 
 impl<P: Preset, S: PostCapellaBeaconState<P>> PostCapellaBeaconState<P> for Hc<S> {
@@ -474,6 +478,10 @@ impl<P: Preset, S: PostCapellaBeaconState<P>> PostCapellaBeaconState<P> for Hc<S
     fn next_withdrawal_validator_index_mut(&mut self) -> &mut ValidatorIndex {
         self.as_mut().next_withdrawal_validator_index_mut()
     }
+
+    fn historical_summaries(&self) -> &HistoricalSummaries<P> {
+        self.as_ref().historical_summaries()
+    }
 }
 
 impl<P: Preset> PostCapellaBeaconState<P> for CapellaBeaconState<P> {
@@ -492,6 +500,10 @@ impl<P: Preset> PostCapellaBeaconState<P> for CapellaBeaconState<P> {
     fn next_withdrawal_validator_index_mut(&mut self) -> &mut ValidatorIndex {
         &mut self.next_withdrawal_validator_index
     }
+
+    fn historical_summaries(&self) -> &HistoricalSummaries<P> {
+        &self.historical_summaries
+    }
 }
 
 impl<P: Preset> PostCapellaBeaconState<P> for DenebBeaconState<P> {
@@ -510,6 +522,10 @@ impl<P: Preset> PostCapellaBeaconState<P> for DenebBeaconState<P> {
     fn next_withdrawal_validator_index_mut(&mut self) -> &mut ValidatorIndex {
         &mut self.next_withdrawal_validator_index
     }
+
+    fn historical_summaries(&self) -> &HistoricalSummaries<P> {
+        &self.historical_summaries
+    }
 }
 
 pub trait SignedBeaconBlock<P: Preset>: Debug + Send + Sync {
@@ -0,0 +1,293 @@
+//! Client for the selection endpoints of distributed validator middleware (Charon, SSV, Diva).
+//!
+//! Key shares of a distributed validator produce partial selection proofs.
+//! Whether the distributed validator is an aggregator depends on the selection proof of the whole
+//! validator, which only the middleware can assemble from the partial proofs of all operators.
+
+use core::time::Duration;
+use std::collections::HashMap;
+
+use anyhow::Result;
+use bls::SignatureBytes;
+use reqwest::{Client, Url};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use thiserror::Error;
+use types::{
+    altair::primitives::SubcommitteeIndex,
+    phase0::primitives::{Slot, ValidatorIndex},
+};
+
+// Selection proofs are needed within a third of a slot.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+const BEACON_COMMITTEE_SELECTIONS_PATH: &str = "/eth/v1/validator/beacon_committee_selections";
+const SYNC_COMMITTEE_SELECTIONS_PATH: &str = "/eth/v1/validator/sync_committee_selections";
+
+#[derive(Clone, Copy, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct BeaconCommitteeSelection {
+    #[serde(with = "serde_utils::string_or_native")]
+    pub validator_index: ValidatorIndex,
+    #[serde(with = "serde_utils::string_or_native")]
+    pub slot: Slot,
+    pub selection_proof: SignatureBytes,
+}
+
+#[derive(Clone, Copy, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct SyncCommitteeSelection {
+    #[serde(with = "serde_utils::string_or_native")]
+    pub validator_index: ValidatorIndex,
+    #[serde(with = "serde_utils::string_or_native")]
+    pub slot: Slot,
+    #[serde(with = "serde_utils::string_or_native")]
+    pub subcommittee_index: SubcommitteeIndex,
+    pub selection_proof: SignatureBytes,
+}
+
+#[derive(Debug, Error)]
+enum Error {
+    #[error(
+        "distributed validator middleware returned no selection proof \
+         for validator {validator_index} at slot {slot}"
+    )]
+    MissingSelectionProof {
+        validator_index: ValidatorIndex,
+        slot: Slot,
+    },
+}
+
+#[derive(Deserialize)]
+struct SelectionsResponse<T> {
+    data: Vec<T>,
+}
+
+pub struct DistributedMiddleware {
+    client: Client,
+    url: Url,
+}
+
+impl DistributedMiddleware {
+    #[must_use]
+    pub const fn new(client: Client, url: Url) -> Self {
+        Self { client, url }
+    }
+
+    /// Exchanges partial selection proofs for the combined ones.
+    /// The returned proofs are in the same order as `selections`.
+    pub async fn beacon_committee_selection_proofs(
+        &self,
+        selections: Vec<BeaconCommitteeSelection>,
+    ) -> Result<Vec<SignatureBytes>> {
+        let combined = self
+            .post_selections(BEACON_COMMITTEE_SELECTIONS_PATH, &selections)
+            .await?
+            .into_iter()
+            .map(|selection| {
+                let key = (selection.validator_index, selection.slot);
+                (key, selection.selection_proof)
+            })
+            .collect::<HashMap<_, _>>();
+
+        selections
+            .into_iter()
+            .map(|selection| {
+                let BeaconCommitteeSelection {
+                    validator_index,
+                    slot,
+                    ..
+                } = selection;
+
+                combined
+                    .get(&(validator_index, slot))
+                    .copied()
+                    .ok_or(Error::MissingSelectionProof {
+                        validator_index,
+                        slot,
+                    })
+                    .map_err(Into::into)
+            })
+            .collect()
+    }
+
+    /// Exchanges partial selection proofs for the combined ones.
+    /// The returned proofs are in the same order as `selections`.
+    pub async fn sync_committee_selection_proofs(
+        &self,
+        selections: Vec<SyncCommitteeSelection>,
+    ) -> Result<Vec<SignatureBytes>> {
+        let combined = self
+            .post_selections(SYNC_COMMITTEE_SELECTIONS_PATH, &selections)
+            .await?
+            .into_iter()
+            .map(|selection| {
+                let key = (
+                    selection.validator_index,
+                    selection.slot,
+                    selection.subcommittee_index,
+                );
+
+                (key, selection.selection_proof)
+            })
+            .collect::<HashMap<_, _>>();
+
+        selections
+            .into_iter()
+            .map(|selection| {
+                let SyncCommitteeSelection {
+                    validator_index,
+                    slot,
+                    subcommittee_index,
+                    ..
+                } = selection;
+
+                combined
+                    .get(&(validator_index, slot, subcommittee_index))
+                    .copied()
+                    .ok_or(Error::MissingSelectionProof {
+                        validator_index,
+                        slot,
+                    })
+                    .map_err(Into::into)
+            })
+            .collect()
+    }
+
+    async fn post_selections<T: Serialize + DeserializeOwned + Sync>(
+        &self,
+        path: &str,
+        selections: &[T],
+    ) -> Result<Vec<T>> {
+        if selections.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let url = self.url.join(path)?;
+
+        let response = self
+            .client
+            .post(url)
+            .json(selections)
+            .timeout(REQUEST_TIMEOUT)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(reqwest::Error::without_url)?
+            .json::<SelectionsResponse<T>>()
+            .await?;
+
+        Ok(response.data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use httpmock::{Method, MockServer};
+    use serde_json::json;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_beacon_committee_selection_proofs_are_matched_to_requests() -> Result<()> {
+        let partial_proof = SignatureBytes::repeat_byte(1);
+        let combined_proof_1 = SignatureBytes::repeat_byte(2);
+        let combined_proof_2 = SignatureBytes::repeat_byte(3);
+
+        let server = MockServer::start();
+
+        let mock = server.mock(|when, then| {
+            when.method(Method::POST)
+                .path(BEACON_COMMITTEE_SELECTIONS_PATH)
+                .json_body(json!([
+                    {
+                        "validator_index": "1",
+                        "slot": "10",
+                        "selection_proof": partial_proof,
+                    },
+                    {
+                        "validator_index": "2",
+                        "slot": "10",
+                        "selection_proof": partial_proof,
+                    },
+                ]));
+
+            // The middleware may return selections in any order.
+            then.status(200).json_body(json!({
+                "data": [
+                    {
+                        "validator_index": "2",
+                        "slot": "10",
+                        "selection_proof": combined_proof_2,
+                    },
+                    {
+                        "validator_index": "1",
+                        "slot": "10",
+                        "selection_proof": combined_proof_1,
+                    },
+                ],
+            }));
+        });
+
+        let middleware = DistributedMiddleware::new(Client::new(), server.base_url().parse()?);
+
+        let selections = [1, 2]
+            .into_iter()
+            .map(|validator_index| BeaconCommitteeSelection {
+                validator_index,
+                slot: 10,
+                selection_proof: partial_proof,
+            })
+            .collect();
+
+        assert_eq!(
+            middleware
+                .beacon_committee_selection_proofs(selections)
+                .await?,
+            [combined_proof_1, combined_proof_2],
+        );
+
+        mock.assert();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sync_committee_selection_proofs_fail_if_one_is_missing() -> Result<()> {
+        let server = MockServer::start();
+
+        server.mock(|when, then| {
+            when.method(Method::POST)
+                .path(SYNC_COMMITTEE_SELECTIONS_PATH);
+
+            then.status(200).json_body(json!({
+                "data": [
+                    {
+                        "validator_index": "1",
+                        "slot": "10",
+                        "subcommittee_index": "0",
+                        "selection_proof": SignatureBytes::repeat_byte(2),
+                    },
+                ],
+            }));
+        });
+
+        let middleware = DistributedMiddleware::new(Client::new(), server.base_url().parse()?);
+
+        let selections = [0, 1]
+            .into_iter()
+            .map(|subcommittee_index| SyncCommitteeSelection {
+                validator_index: 1,
+                slot: 10,
+                subcommittee_index,
+                selection_proof: SignatureBytes::repeat_byte(1),
+            })
+            .collect();
+
+        middleware
+            .sync_committee_selection_proofs(selections)
+            .await
+            .expect_err("middleware returned no selection proof for subcommittee 1");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,316 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+use anyhow::Result;
+use bls::PublicKeyBytes;
+use eth1_api::ApiController;
+use fork_choice_control::Wait;
+use helper_functions::{accessors, misc};
+use parking_lot::Mutex;
+use ssz::SszHash as _;
+use std_ext::ArcExt;
+use types::{
+    altair::primitives::SyncCommitteePeriod,
+    nonstandard::{RelativeEpoch, WithStatus},
+    phase0::{
+        consts::GENESIS_EPOCH,
+        primitives::{CommitteeIndex, Epoch, Slot, ValidatorIndex, H256},
+    },
+    preset::Preset,
+    traits::PostAltairBeaconState,
+};
+
+pub type ProposerDuties = Duties<Vec<ProposerDuty>>;
+pub type AttesterDuties = Duties<HashMap<ValidatorIndex, AttesterDuty>>;
+
+#[derive(Clone, Copy)]
+pub struct ProposerDuty {
+    pub pubkey: PublicKeyBytes,
+    pub validator_index: ValidatorIndex,
+    pub slot: Slot,
+}
+
+#[derive(Clone, Copy)]
+pub struct AttesterDuty {
+    pub committee_index: CommitteeIndex,
+    pub committee_length: usize,
+    pub committees_at_slot: u64,
+    pub slot: Slot,
+    pub validator_committee_index: usize,
+}
+
+pub struct Duties<T> {
+    pub dependent_root: H256,
+    pub duties: T,
+}
+
+pub struct SyncDuties {
+    // Sync committees are known a whole period in advance and change very rarely when reorganized,
+    // so sync duties are checked against the committee itself rather than a dependent root.
+    committee_root: H256,
+    sync_committee_indices: HashMap<ValidatorIndex, Vec<usize>>,
+}
+
+impl SyncDuties {
+    #[must_use]
+    pub fn sync_committee_indices(&self, validator_index: ValidatorIndex) -> Option<&[usize]> {
+        self.sync_committee_indices
+            .get(&validator_index)
+            .map(Vec::as_slice)
+    }
+}
+
+/// Duties for the current and next epoch kept after they are first computed.
+///
+/// Shared between the internal validator and the HTTP API so that duties for many validators
+/// can be looked up without computing committees every time they are requested.
+///
+/// Entries are only used if their dependent root matches the one derived from the current head.
+/// Entries made stale by reorganizations are ignored and replaced when duties are recomputed.
+#[derive(Default)]
+pub struct DutiesCache {
+    proposer_duties: Mutex<BTreeMap<Epoch, Arc<ProposerDuties>>>,
+    attester_duties: Mutex<BTreeMap<Epoch, Arc<AttesterDuties>>>,
+    sync_duties: Mutex<BTreeMap<SyncCommitteePeriod, Arc<SyncDuties>>>,
+}
+
+impl DutiesCache {
+    pub fn proposer_duties<P: Preset, W: Wait>(
+        &self,
+        controller: &ApiController<P, W>,
+        epoch: Epoch,
+    ) -> Result<WithStatus<Arc<ProposerDuties>>> {
+        cached_or_computed(
+            &self.proposer_duties,
+            controller,
+            epoch,
+            epoch,
+            compute_proposer_duties,
+        )
+    }
+
+    pub fn attester_duties<P: Preset, W: Wait>(
+        &self,
+        controller: &ApiController<P, W>,
+        epoch: Epoch,
+    ) -> Result<WithStatus<Arc<AttesterDuties>>> {
+        // Unlike proposer duties, attester duties depend on the block before the previous epoch.
+        let previous_epoch = epoch.saturating_sub(1).max(GENESIS_EPOCH);
+
+        cached_or_computed(
+            &self.attester_duties,
+            controller,
+            epoch,
+            previous_epoch,
+            compute_attester_duties,
+        )
+    }
+
+    /// Returns `None` if `period` is neither the current nor the next period of `state`.
+    pub fn sync_duties<P: Preset, W: Wait>(
+        &self,
+        controller: &ApiController<P, W>,
+        state: &(impl PostAltairBeaconState<P> + ?Sized),
+        period: SyncCommitteePeriod,
+    ) -> Option<Arc<SyncDuties>> {
+        let state_epoch = accessors::get_current_epoch(state);
+        let state_period = misc::sync_committee_period::<P>(state_epoch);
+
+        let committee = if period == state_period {
+            state.current_sync_committee()
+        } else if period == state_period + 1 {
+            state.next_sync_committee()
+        } else {
+            return None;
+        };
+
+        let committee_root = committee.hash_tree_root();
+
+        if let Some(sync_duties) = self.sync_duties.lock().get(&period) {
+            if sync_duties.committee_root == committee_root {
+                return Some(sync_duties.clone_arc());
+            }
+        }
+
+        let mut sync_committee_indices = HashMap::<_, Vec<_>>::new();
+
+        for (index, pubkey) in committee.pubkeys.iter().enumerate() {
+            if let Some(validator_index) = controller.validator_index(state, pubkey.to_bytes()) {
+                sync_committee_indices
+                    .entry(validator_index)
+                    .or_default()
+                    .push(index);
+            }
+        }
+
+        let sync_duties = Arc::new(SyncDuties {
+            committee_root,
+            sync_committee_indices,
+        });
+
+        let mut cache = self.sync_duties.lock();
+        cache.retain(|cached_period, _| state_period <= *cached_period);
+        cache.insert(period, sync_duties.clone_arc());
+
+        Some(sync_duties)
+    }
+}
+
+fn cached_or_computed<P: Preset, W: Wait, T>(
+    cache: &Mutex<BTreeMap<Epoch, Arc<Duties<T>>>>,
+    controller: &ApiController<P, W>,
+    epoch: Epoch,
+    dependent_epoch: Epoch,
+    compute: impl FnOnce(&ApiController<P, W>, Epoch) -> Result<WithStatus<Duties<T>>>,
+) -> Result<WithStatus<Arc<Duties<T>>>> {
+    let current_epoch = misc::compute_epoch_at_slot::<P>(controller.slot());
+
+    // Duties for other epochs are requested too rarely to be worth keeping.
+    if epoch != current_epoch && epoch != current_epoch + 1 {
+        return Ok(compute(controller, epoch)?.map(Arc::new));
+    }
+
+    let cached = cache.lock().get(&epoch).map(ArcExt::clone_arc);
+
+    if let Some(duties) = cached {
+        let WithStatus {
+            value: dependent_root,
+            optimistic,
+            finalized,
+        } = dependent_root_from_head(controller, dependent_epoch)?;
+
+        if duties.dependent_root == dependent_root {
+            return Ok(WithStatus {
+                value: duties,
+                optimistic,
+                finalized,
+            });
+        }
+    }
+
+    let with_status = compute(controller, epoch)?.map(Arc::new);
+
+    let mut cache = cache.lock();
+    cache.retain(|cached_epoch, _| current_epoch <= *cached_epoch);
+    cache.insert(epoch, with_status.value.clone_arc());
+
+    Ok(with_status)
+}
+
+fn compute_proposer_duties<P: Preset, W: Wait>(
+    controller: &ApiController<P, W>,
+    epoch: Epoch,
+) -> Result<WithStatus<ProposerDuties>> {
+    let WithStatus {
+        value: state,
+        optimistic,
+        finalized,
+    } = controller.preprocessed_state_at_epoch(epoch)?;
+
+    let dependent_root = controller.dependent_root(&state, epoch)?;
+
+    let proposer_indices = controller.proposer_indices(&state)?;
+
+    let duties = misc::slots_in_epoch::<P>(epoch)
+        .zip(proposer_indices.iter().copied())
+        .map(|(slot, validator_index)| {
+            let pubkey = accessors::public_key(&state, validator_index)?.to_bytes();
+
+            Ok(ProposerDuty {
+                pubkey,
+                validator_index,
+                slot,
+            })
+        })
+        .collect::<Result<_>>()?;
+
+    Ok(WithStatus {
+        value: Duties {
+            dependent_root,
+            duties,
+        },
+        optimistic,
+        finalized,
+    })
+}
+
+fn compute_attester_duties<P: Preset, W: Wait>(
+    controller: &ApiController<P, W>,
+    epoch: Epoch,
+) -> Result<WithStatus<AttesterDuties>> {
+    let head_state = controller.head_state();
+
+    let (state, relative_epoch) = match accessors::relative_epoch(&head_state.value, epoch) {
+        Ok(relative_epoch) => (head_state, relative_epoch),
+        Err(_) => (
+            controller.preprocessed_state_at_epoch(epoch)?,
+            RelativeEpoch::Current,
+        ),
+    };
+
+    let WithStatus {
+        value: state,
+        optimistic,
+        finalized,
+    } = state;
+
+    let previous_epoch = epoch.saturating_sub(1).max(GENESIS_EPOCH);
+    let dependent_root = controller.dependent_root(&state, previous_epoch)?;
+    let committees_at_slot = accessors::get_committee_count_per_slot(&state, relative_epoch);
+
+    let mut duties = HashMap::new();
+
+    for slot in misc::slots_in_epoch::<P>(epoch) {
+        for (committee, committee_index) in accessors::beacon_committees(&state, slot)?.zip(0..) {
+            let committee_length = committee.len();
+
+            for (validator_committee_index, validator_index) in committee.into_iter().enumerate() {
+                let duty = AttesterDuty {
+                    committee_index,
+                    committee_length,
+                    committees_at_slot,
+                    slot,
+                    validator_committee_index,
+                };
+
+                duties.insert(validator_index, duty);
+            }
+        }
+    }
+
+    Ok(WithStatus {
+        value: Duties {
+            dependent_root,
+            duties,
+        },
+        optimistic,
+        finalized,
+    })
+}
+
+// This avoids processing the head state up to `epoch` just to find out the cached duties are valid.
+// Slots after the head are empty, so their block roots are the same as that of the head.
+fn dependent_root_from_head<P: Preset, W: Wait>(
+    controller: &ApiController<P, W>,
+    epoch: Epoch,
+) -> Result<WithStatus<H256>> {
+    let WithStatus {
+        value: head,
+        optimistic,
+        finalized,
+    } = controller.head();
+
+    let dependent_root = if head.slot() < misc::compute_start_slot_at_epoch::<P>(epoch) {
+        head.block_root
+    } else {
+        controller.dependent_root(&controller.state_by_chain_link(&head), epoch)?
+    };
+
+    Ok(WithStatus {
+        value: dependent_root,
+        optimistic,
+        finalized,
+    })
+}
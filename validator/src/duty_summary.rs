@@ -0,0 +1,149 @@
+use core::ops::AddAssign;
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::Result;
+use helper_functions::{accessors, predicates};
+use log::{debug, info};
+use types::{
+    combined::BeaconState,
+    phase0::primitives::{Epoch, Gwei, ValidatorIndex},
+    preset::Preset,
+    traits::BeaconState as _,
+};
+
+#[derive(Clone, Copy, Default)]
+pub struct DutyCounts {
+    pub attestations_published: u64,
+    pub aggregates_published: u64,
+    pub sync_committee_messages_published: u64,
+    pub proposals_attempted: u64,
+    pub proposals_published: u64,
+}
+
+impl AddAssign for DutyCounts {
+    fn add_assign(&mut self, other: Self) {
+        self.attestations_published += other.attestations_published;
+        self.aggregates_published += other.aggregates_published;
+        self.sync_committee_messages_published += other.sync_committee_messages_published;
+        self.proposals_attempted += other.proposals_attempted;
+        self.proposals_published += other.proposals_published;
+    }
+}
+
+/// Duties performed by own validators, logged once per epoch.
+#[derive(Default)]
+pub struct DutySummary {
+    counts: BTreeMap<Epoch, HashMap<ValidatorIndex, DutyCounts>>,
+    // Balances at the start of the last summarized epoch.
+    balances: HashMap<ValidatorIndex, Gwei>,
+}
+
+impl DutySummary {
+    pub fn counts_mut(&mut self, epoch: Epoch, validator_index: ValidatorIndex) -> &mut DutyCounts {
+        self.counts
+            .entry(epoch)
+            .or_default()
+            .entry(validator_index)
+            .or_default()
+    }
+
+    /// Logs duties of `own_validator_indices` in the epoch preceding the current epoch of `state`.
+    ///
+    /// Attestations are considered included if the validator has the timely source flag set.
+    /// Participation flags do not exist before Altair, so inclusion is not reported for Phase 0.
+    pub fn log_previous_epoch<P: Preset>(
+        &mut self,
+        state: &BeaconState<P>,
+        own_validator_indices: impl IntoIterator<Item = ValidatorIndex>,
+    ) -> Result<()> {
+        let current_epoch = accessors::get_current_epoch(state);
+
+        let Some(epoch) = current_epoch.checked_sub(1) else {
+            return Ok(());
+        };
+
+        let mut epoch_counts = self.counts.remove(&epoch).unwrap_or_default();
+
+        self.counts = self.counts.split_off(&current_epoch);
+
+        let participation = state.post_altair().map(accessors::combined_participation);
+
+        let mut total = DutyCounts::default();
+        let mut validator_count = 0_u64;
+        let mut expected_attestations = 0_u64;
+        let mut included_attestations = 0_u64;
+        let mut balance_change = None;
+        let mut balances = HashMap::new();
+
+        for validator_index in own_validator_indices {
+            let validator = state.validators().get(validator_index)?;
+            let balance = *state.balances().get(validator_index)?;
+            let counts = epoch_counts.remove(&validator_index).unwrap_or_default();
+
+            let expected = u64::from(predicates::is_active_validator(validator, epoch));
+
+            let included = participation.as_ref().map(|participation| {
+                usize::try_from(validator_index)
+                    .ok()
+                    .and_then(|index| participation.get(index))
+                    .is_some_and(|participation| participation.previous_epoch_matching_source())
+            });
+
+            let validator_balance_change = self
+                .balances
+                .get(&validator_index)
+                .map(|previous| i128::from(balance) - i128::from(*previous));
+
+            debug!(
+                "validator {validator_index} duties in epoch {epoch}: \
+                 attestations published: {}/{expected}, attestation included: {}, \
+                 aggregates published: {}, sync committee messages published: {}, \
+                 proposals published: {}/{}, balance change: {} Gwei",
+                counts.attestations_published,
+                display_option(included),
+                counts.aggregates_published,
+                counts.sync_committee_messages_published,
+                counts.proposals_published,
+                counts.proposals_attempted,
+                display_option(validator_balance_change),
+            );
+
+            total += counts;
+            validator_count += 1;
+            expected_attestations += expected;
+            included_attestations += u64::from(included.unwrap_or_default());
+
+            if let Some(change) = validator_balance_change {
+                *balance_change.get_or_insert(0) += change;
+            }
+
+            balances.insert(validator_index, balance);
+        }
+
+        self.balances = balances;
+
+        if validator_count == 0 {
+            return Ok(());
+        }
+
+        info!(
+            "duties of {validator_count} validators in epoch {epoch}: \
+             attestations published: {}/{expected_attestations}, attestations included: {}, \
+             aggregates published: {}, sync committee messages published: {}, \
+             proposals published: {}/{}, balance change: {} Gwei",
+            total.attestations_published,
+            display_option(participation.is_some().then_some(included_attestations)),
+            total.aggregates_published,
+            total.sync_committee_messages_published,
+            total.proposals_published,
+            total.proposals_attempted,
+            display_option(balance_change),
+        );
+
+        Ok(())
+    }
+}
+
+fn display_option(value: Option<impl ToString>) -> String {
+    value.map_or_else(|| "unknown".to_owned(), |value| value.to_string())
+}
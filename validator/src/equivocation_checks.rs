@@ -0,0 +1,158 @@
+use eth1_api::ApiController;
+use fork_choice_control::Wait;
+use types::{
+    phase0::primitives::{Epoch, Slot, ValidatorIndex, H256},
+    preset::Preset,
+};
+
+use crate::validator_config::ValidatorConfig;
+
+/// Messages from own validators that fork choice has seen from other sources.
+///
+/// Slashing protection only knows about messages signed by this node. Another message from the
+/// same key means the key is in use elsewhere, so signing a conflicting one is likely slashable.
+pub trait SeenMessages {
+    fn proposed_block_root(&self, slot: Slot, proposer_index: ValidatorIndex) -> Option<H256>;
+
+    fn subnet_attester_seen(&self, target_epoch: Epoch, validator_index: ValidatorIndex) -> bool;
+}
+
+impl<P: Preset, W: Wait> SeenMessages for ApiController<P, W> {
+    fn proposed_block_root(&self, slot: Slot, proposer_index: ValidatorIndex) -> Option<H256> {
+        self.as_ref().proposed_block_root(slot, proposer_index)
+    }
+
+    fn subnet_attester_seen(&self, target_epoch: Epoch, validator_index: ValidatorIndex) -> bool {
+        self.as_ref()
+            .subnet_attester_seen(target_epoch, validator_index)
+    }
+}
+
+/// Returns the root of a block from `proposer_index` in `slot` that prevents signing another one.
+pub fn conflicting_block_root(
+    validator_config: &ValidatorConfig,
+    seen_messages: &impl SeenMessages,
+    slot: Slot,
+    proposer_index: ValidatorIndex,
+) -> Option<H256> {
+    if !validator_config.block_equivocation_check {
+        return None;
+    }
+
+    seen_messages.proposed_block_root(slot, proposer_index)
+}
+
+/// Returns `true` if an attestation from `validator_index` for `target_epoch` seen on an
+/// attestation subnet prevents signing another one.
+pub fn attestation_seen(
+    validator_config: &ValidatorConfig,
+    seen_messages: &impl SeenMessages,
+    target_epoch: Epoch,
+    validator_index: ValidatorIndex,
+) -> bool {
+    if !validator_config.attestation_equivocation_check {
+        return false;
+    }
+
+    seen_messages.subnet_attester_seen(target_epoch, validator_index)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+
+    use super::*;
+
+    #[derive(Default)]
+    struct FakeSeenMessages {
+        block_roots: HashMap<(Slot, ValidatorIndex), H256>,
+        subnet_attesters: HashSet<(Epoch, ValidatorIndex)>,
+    }
+
+    impl SeenMessages for FakeSeenMessages {
+        fn proposed_block_root(&self, slot: Slot, proposer_index: ValidatorIndex) -> Option<H256> {
+            self.block_roots.get(&(slot, proposer_index)).copied()
+        }
+
+        fn subnet_attester_seen(
+            &self,
+            target_epoch: Epoch,
+            validator_index: ValidatorIndex,
+        ) -> bool {
+            self.subnet_attesters
+                .contains(&(target_epoch, validator_index))
+        }
+    }
+
+    #[test]
+    fn block_is_not_signed_if_another_one_from_same_proposer_and_slot_was_seen() {
+        let validator_config = ValidatorConfig::default();
+
+        let seen_messages = FakeSeenMessages {
+            block_roots: HashMap::from([((1, 2), H256::repeat_byte(1))]),
+            ..FakeSeenMessages::default()
+        };
+
+        assert_eq!(
+            conflicting_block_root(&validator_config, &seen_messages, 1, 2),
+            Some(H256::repeat_byte(1)),
+        );
+
+        assert_eq!(
+            conflicting_block_root(&validator_config, &seen_messages, 1, 3),
+            None,
+        );
+
+        assert_eq!(
+            conflicting_block_root(&validator_config, &seen_messages, 2, 2),
+            None,
+        );
+    }
+
+    #[test]
+    fn seen_blocks_are_ignored_if_check_is_disabled() {
+        let validator_config = ValidatorConfig {
+            block_equivocation_check: false,
+            ..ValidatorConfig::default()
+        };
+
+        let seen_messages = FakeSeenMessages {
+            block_roots: HashMap::from([((1, 2), H256::repeat_byte(1))]),
+            ..FakeSeenMessages::default()
+        };
+
+        assert_eq!(
+            conflicting_block_root(&validator_config, &seen_messages, 1, 2),
+            None,
+        );
+    }
+
+    #[test]
+    fn attestation_is_not_signed_if_another_one_for_same_target_was_seen() {
+        let validator_config = ValidatorConfig::default();
+
+        let seen_messages = FakeSeenMessages {
+            subnet_attesters: HashSet::from([(1, 2)]),
+            ..FakeSeenMessages::default()
+        };
+
+        assert!(attestation_seen(&validator_config, &seen_messages, 1, 2));
+        assert!(!attestation_seen(&validator_config, &seen_messages, 1, 3));
+        assert!(!attestation_seen(&validator_config, &seen_messages, 2, 2));
+    }
+
+    #[test]
+    fn seen_attestations_are_ignored_if_check_is_disabled() {
+        let validator_config = ValidatorConfig {
+            attestation_equivocation_check: false,
+            ..ValidatorConfig::default()
+        };
+
+        let seen_messages = FakeSeenMessages {
+            subnet_attesters: HashSet::from([(1, 2)]),
+            ..FakeSeenMessages::default()
+        };
+
+        assert!(!attestation_seen(&validator_config, &seen_messages, 1, 2));
+    }
+}
@@ -3,7 +3,7 @@ use core::{
     ops::{Deref, DerefMut},
 };
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     sync::{Arc, RwLockReadGuard, RwLockWriteGuard},
 };
 
@@ -61,67 +61,83 @@ pub trait Eth1Storage {
 
         let eth1_data = state_at_slot.eth1_data();
         let period_start = voting_period_start_time(config, state_at_slot);
-
-        let mut finalized_deposit_tree = self.finalized_deposit_tree()?;
-        let mut valid_votes = HashMap::new();
+        let votes_to_consider = self.votes_to_consider(config, eth1_data, period_start)?;
 
         features::log!(DebugEth1, "Eth1 Vote Eth1 Data: {eth1_data:?}");
         features::log!(
             DebugEth1,
-            "Finalized deposit tree: deposit_count {}, last_added_block_number: {}",
-            finalized_deposit_tree.deposit_count,
-            finalized_deposit_tree.last_added_block_number,
+            "Eth1 votes to consider: {}",
+            votes_to_consider.len(),
         );
 
-        for (position, vote) in state_at_slot.eth1_data_votes().into_iter().enumerate() {
-            let unfinalized_blocks = self.unfinalized_blocks();
+        // > Valid votes already cast during this period
+        //
+        // Votes are compared in full rather than by block hash alone. Votes for known blocks with
+        // incorrect deposit data would otherwise be repeated in our own blocks.
+        let votes_to_consider_set = votes_to_consider.iter().collect::<HashSet<_>>();
 
-            let Some(block_position) = unfinalized_blocks
-                .iter()
-                .position(|block| block.hash == vote.block_hash)
-            else {
-                continue;
-            };
-
-            features::log!(DebugEth1, "Block position: {block_position}");
-
-            if !is_candidate_block(config, &unfinalized_blocks[block_position], period_start) {
-                continue;
-            }
-
-            let deposit_count = unfinalized_blocks
-                .iter()
-                .take(block_position + 1)
-                .rev()
-                .find_map(|block| block.deposit_events.last())
-                .map(|deposit_event| deposit_event.index)
-                .unwrap_or(finalized_deposit_tree.deposit_count);
-
-            features::log!(DebugEth1, "Deposit count: {deposit_count}");
-            features::log!(
-                DebugEth1,
-                "Eth1 data deposit count: {:?}",
-                eth1_data.deposit_count,
-            );
+        let valid_votes = state_at_slot
+            .eth1_data_votes()
+            .into_iter()
+            .filter(|vote| votes_to_consider_set.contains(vote));
 
-            if deposit_count < eth1_data.deposit_count {
-                // > Ensure cannot move back to earlier deposit contract states
-                continue;
-            }
+        let mut vote_counts = HashMap::new();
 
-            let (count, _) = valid_votes.entry(vote).or_insert((0, position));
+        for (position, vote) in valid_votes.enumerate() {
+            let (count, _) = vote_counts.entry(vote).or_insert((0, position));
             *count += 1;
         }
 
-        if let Some(vote) = valid_votes
+        // > Tiebreak by smallest distance
+        let majority_vote = vote_counts
             .into_iter()
             .max_by_key(|(_, (count, position))| (*count, Reverse(*position)))
-            .map(|(vote, _)| vote)
-        {
-            features::log!(DebugEth1, "Eth1 Vote: {vote:?}");
-            return Ok(*vote);
+            .map(|(vote, _)| *vote);
+
+        // > Default vote on latest eth1 block data in the period range unless eth1 chain is not live
+        let (vote, vote_type) = if let Some(vote) = majority_vote {
+            (vote, "majority")
+        } else if let Some(vote) = votes_to_consider.last().copied() {
+            (vote, "default")
+        } else {
+            (eth1_data, "state")
+        };
+
+        features::log!(DebugEth1, "Eth1 Vote: {vote:?} ({vote_type})");
+
+        if let Some(metrics) = metrics {
+            metrics.register_eth1_vote(vote_type);
         }
 
+        Ok(vote)
+    }
+
+    /// Computes `Eth1Data` for all candidate blocks that do not move back to earlier deposit
+    /// contract states than the one in `state_eth1_data`.
+    ///
+    /// Corresponds to `votes_to_consider` in [`get_eth1_vote`].
+    ///
+    /// [`get_eth1_vote`]: https://github.com/ethereum/consensus-specs/blob/v1.3.0/specs/phase0/validator.md#eth1-data
+    fn votes_to_consider(
+        &self,
+        config: &Config,
+        state_eth1_data: Eth1Data,
+        period_start: UnixSeconds,
+    ) -> Result<Vec<Eth1Data>> {
+        let mut deposit_tree = self.finalized_deposit_tree()?;
+
+        features::log!(
+            DebugEth1,
+            "Finalized deposit tree: deposit_count {}, last_added_block_number: {}",
+            deposit_tree.deposit_count,
+            deposit_tree.last_added_block_number,
+        );
+
+        // `DepositTree` cannot compute its root without adding a deposit.
+        // The root of the finalized deposit tree is only known if it matches the one in the state.
+        let mut deposit_root = (deposit_tree.deposit_count == state_eth1_data.deposit_count)
+            .then_some(state_eth1_data.deposit_root);
+
         let unfinalized_blocks = self.unfinalized_blocks();
 
         features::log!(
@@ -132,49 +148,40 @@ pub trait Eth1Storage {
             unfinalized_blocks.last().map(|block| block.number),
         );
 
-        let candidate_eth1_blocks = unfinalized_blocks
-            .iter()
-            .filter(|eth1_block| is_candidate_block(config, eth1_block, period_start));
+        let follow_distance_width = config.seconds_per_eth1_block * config.eth1_follow_distance;
+        let mut votes = vec![];
 
-        if let Some(eth1_block) = candidate_eth1_blocks.last() {
-            let eth1_blocks_to_catch_up = match unfinalized_blocks
-                .iter()
-                .position(|block| block.hash == eth1_block.hash)
-            {
-                Some(block_position) => {
-                    unfinalized_blocks.iter().take(block_position + 1).collect()
-                }
-                None => vec![],
-            };
+        for block in unfinalized_blocks.iter() {
+            // Blocks are ordered by number, so none of the remaining ones can be candidates.
+            if block.timestamp + follow_distance_width > period_start {
+                break;
+            }
 
-            features::log!(
-                DebugEth1,
-                "Candidate eth1 block: {} - {}",
-                eth1_block.hash,
-                eth1_block.number,
-            );
+            for DepositEvent { data, index } in block.deposit_events.iter().copied() {
+                // It's possible for download manager to add deposits to finalized deposit tree on grandine restart
+                // while deposits are not yet finalized in eth2
+                if deposit_tree.deposit_count <= index {
+                    deposit_root = Some(deposit_tree.push_and_compute_root(index, data)?);
+                }
+            }
 
-            let mut eth1_data = Eth1Data {
-                block_hash: eth1_block.hash,
-                ..eth1_data
+            let Some(deposit_root) = deposit_root else {
+                continue;
             };
 
-            for block in eth1_blocks_to_catch_up {
-                for DepositEvent { data, index } in block.deposit_events.iter().copied() {
-                    // It's possible for download manager to add deposits to finalized deposit tree on grandine restart
-                    // while deposits are not yet finalized in eth2
-                    if finalized_deposit_tree.deposit_count <= index {
-                        eth1_data.deposit_root =
-                            finalized_deposit_tree.push_and_compute_root(index, data)?;
-                        eth1_data.deposit_count = finalized_deposit_tree.deposit_count;
-                    }
-                }
+            // > Ensure cannot move back to earlier deposit contract states
+            if is_candidate_block(config, block, period_start)
+                && deposit_tree.deposit_count >= state_eth1_data.deposit_count
+            {
+                votes.push(Eth1Data {
+                    deposit_root,
+                    deposit_count: deposit_tree.deposit_count,
+                    block_hash: block.hash,
+                });
             }
-
-            return Ok(eth1_data);
         }
 
-        Ok(eth1_data)
+        Ok(votes)
     }
 
     fn pending_deposits<P: Preset>(
@@ -395,7 +402,10 @@ mod tests {
     use tap::Pipe as _;
     use try_from_iterator::TryFromIterator as _;
     use types::{
-        phase0::{consts::GENESIS_SLOT, primitives::ExecutionBlockHash},
+        phase0::{
+            consts::GENESIS_SLOT,
+            primitives::{ExecutionBlockHash, H256},
+        },
         preset::Minimal,
     };
 
@@ -720,4 +730,56 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn eth1_vote_ignores_votes_with_incorrect_deposit_data() -> Result<()> {
+        let config = Config::minimal();
+
+        let (mut state, deposit_tree) = factory::min_genesis_state::<Minimal>(&config)?;
+
+        let genesis_trigger_time = state.genesis_time() - config.genesis_delay;
+        let eth1_block_time = genesis_trigger_time + config.seconds_per_eth1_block;
+        let block_hash = ExecutionBlockHash::repeat_byte(1);
+
+        let secret_key = interop::secret_key(64);
+        let new_deposit_data = interop::quick_start_deposit_data::<Minimal>(&config, &secret_key);
+
+        let eth1_storage = TestEth1Storage {
+            finalized_deposit_tree: deposit_tree,
+            unfinalized_blocks: vec![Eth1Block {
+                hash: block_hash,
+                timestamp: eth1_block_time,
+                deposit_events: vec![DepositEvent {
+                    data: new_deposit_data,
+                    index: 64,
+                }]
+                .try_into()?,
+                ..Eth1Block::default()
+            }],
+        };
+
+        let expected_vote = eth1_storage.eth1_vote(&config, None, &state)?;
+
+        assert_eq!(expected_vote.block_hash, block_hash);
+        assert_eq!(expected_vote.deposit_count, 65);
+
+        let incorrect_vote = Eth1Data {
+            deposit_root: H256::repeat_byte(2),
+            ..expected_vote
+        };
+
+        for _ in 0..4 {
+            state
+                .make_mut()
+                .eth1_data_votes_mut()
+                .push(incorrect_vote)?;
+        }
+
+        assert_eq!(
+            eth1_storage.eth1_vote(&config, None, &state)?,
+            expected_vote,
+        );
+
+        Ok(())
+    }
 }
@@ -0,0 +1,341 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Result;
+use bls::PublicKeyBytes;
+use database::Database;
+use helper_functions::accessors;
+use itertools::Itertools as _;
+use log::{error, info};
+use ssz::Ssz;
+use types::{
+    combined::BeaconState,
+    phase0::primitives::{Epoch, ValidatorIndex},
+    preset::Preset,
+};
+
+use crate::persisted_operations;
+
+// Activity is only checked in epochs after the import epoch. The node the keys were moved from
+// may legitimately have performed duties in it. Attestations for an epoch can be included until
+// the end of the next one, so waiting 3 epochs watches the first epoch after the import in full.
+const LIVENESS_CHECK_EPOCHS: u64 = 3;
+
+#[derive(Clone, Copy, Debug, Ssz)]
+pub struct ImportedKey {
+    pub public_key: PublicKeyBytes,
+    pub import_epoch: Epoch,
+    pub active_elsewhere: bool,
+}
+
+impl ImportedKey {
+    const fn activation_epoch(self) -> Epoch {
+        self.import_epoch + LIVENESS_CHECK_EPOCHS
+    }
+}
+
+/// Keys imported through the Keymanager API that have not been used for signing yet.
+///
+/// Moving keys between nodes quickly risks both nodes signing messages for the same epoch.
+/// Imported keys only start performing duties after a few epochs in which the validators are
+/// checked for activity. Any attestation from them for an epoch after the import included in the
+/// chain in the meantime means the keys are still in use elsewhere.
+///
+/// The state is persisted because imported keys are loaded as active after a restart.
+#[derive(Default)]
+pub struct ImportedKeys {
+    keys: HashMap<PublicKeyBytes, ImportedKey>,
+}
+
+impl ImportedKeys {
+    pub fn load(database: &Database) -> Result<Self> {
+        let keys = persisted_operations::load_imported_keys(database)?
+            .into_iter()
+            .map(|key| (key.public_key, key))
+            .collect();
+
+        Ok(Self { keys })
+    }
+
+    pub fn save(&self, database: &Database) -> Result<()> {
+        let keys = self.keys.values().copied().collect_vec();
+        persisted_operations::save_imported_keys(database, &keys)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn public_keys(&self) -> impl Iterator<Item = PublicKeyBytes> + '_ {
+        self.keys.keys().copied()
+    }
+
+    /// Checks `pending_keys` for activity and returns the ones that can be used for signing.
+    ///
+    /// Should be called at the start of every slot with the state at that slot.
+    /// `validator_index` looks up validator indices in `state`.
+    pub fn update<P: Preset>(
+        &mut self,
+        state: &BeaconState<P>,
+        pending_keys: impl IntoIterator<Item = PublicKeyBytes>,
+        validator_index: impl Fn(PublicKeyBytes) -> Option<ValidatorIndex>,
+    ) -> Vec<PublicKeyBytes> {
+        let previous_epoch = accessors::get_previous_epoch(state);
+        let current_epoch = accessors::get_current_epoch(state);
+        let pending_keys = pending_keys.into_iter().collect::<HashSet<_>>();
+
+        // Keys may be deleted before they are activated.
+        self.keys
+            .retain(|public_key, _| pending_keys.contains(public_key));
+
+        for public_key in pending_keys {
+            self.keys.entry(public_key).or_insert_with(|| {
+                let imported_key = ImportedKey {
+                    public_key,
+                    import_epoch: current_epoch,
+                    active_elsewhere: false,
+                };
+
+                info!(
+                    "validator {public_key:?} imported through the Keymanager API \
+                     will start performing duties in epoch {} \
+                     if it is not active elsewhere",
+                    imported_key.activation_epoch(),
+                );
+
+                imported_key
+            });
+        }
+
+        // Participation flags do not exist before Altair, so activity cannot be checked there.
+        if let Some(post_altair_state) = state.post_altair() {
+            for imported_key in self.keys.values_mut() {
+                if imported_key.active_elsewhere {
+                    continue;
+                }
+
+                let Some(validator_index) = validator_index(imported_key.public_key) else {
+                    continue;
+                };
+
+                let is_live = [
+                    (
+                        previous_epoch,
+                        post_altair_state.previous_epoch_participation(),
+                    ),
+                    (
+                        current_epoch,
+                        post_altair_state.current_epoch_participation(),
+                    ),
+                ]
+                .into_iter()
+                .filter(|(epoch, _)| *epoch > imported_key.import_epoch)
+                .any(|(_, participation)| {
+                    participation
+                        .get(validator_index)
+                        .is_ok_and(|flags| *flags != 0)
+                });
+
+                if is_live {
+                    error!(
+                        "validator {validator_index} ({:?}) imported through \
+                         the Keymanager API is active elsewhere; it will not perform any duties \
+                         unless it is deleted and imported again",
+                        imported_key.public_key,
+                    );
+
+                    imported_key.active_elsewhere = true;
+                }
+            }
+        }
+
+        let activated_keys = self
+            .keys
+            .values()
+            .filter(|imported_key| {
+                !imported_key.active_elsewhere && imported_key.activation_epoch() <= current_epoch
+            })
+            .map(|imported_key| imported_key.public_key)
+            .collect::<Vec<_>>();
+
+        for public_key in &activated_keys {
+            self.keys.remove(public_key);
+        }
+
+        activated_keys
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std_ext::ArcExt as _;
+    use typenum::Unsigned as _;
+    use types::{config::Config, nonstandard::Phase, preset::Minimal, traits::BeaconState as _};
+
+    use super::*;
+
+    const SLOTS_PER_EPOCH: u64 = <Minimal as Preset>::SlotsPerEpoch::U64;
+
+    #[test]
+    fn imported_keys_are_activated_after_liveness_check() -> anyhow::Result<()> {
+        let config = Config::minimal();
+        let (mut state, _) = factory::min_genesis_state::<Minimal>(&config)?;
+        let mut imported_keys = ImportedKeys::default();
+
+        let kept = PublicKeyBytes::repeat_byte(1);
+        let deleted = PublicKeyBytes::repeat_byte(2);
+
+        assert!(imported_keys
+            .update(&*state, [kept, deleted], |_| None)
+            .is_empty());
+
+        for epoch in 1..LIVENESS_CHECK_EPOCHS {
+            *state.make_mut().slot_mut() = SLOTS_PER_EPOCH * epoch;
+
+            assert!(imported_keys
+                .update(&*state, [kept, deleted], |_| None)
+                .is_empty());
+        }
+
+        *state.make_mut().slot_mut() = SLOTS_PER_EPOCH * LIVENESS_CHECK_EPOCHS;
+
+        assert_eq!(imported_keys.update(&*state, [kept], |_| None), [kept]);
+        assert!(imported_keys.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn activity_in_import_epoch_does_not_block_activation() -> anyhow::Result<()> {
+        let config = Config::minimal().start_and_stay_in(Phase::Altair);
+        let (mut state, _) = factory::min_genesis_state::<Minimal>(&config)?;
+        let mut imported_keys = ImportedKeys::default();
+
+        let public_key = PublicKeyBytes::repeat_byte(1);
+
+        // The validator attests in epoch 1 on the node the key is being moved from.
+        *state.make_mut().slot_mut() = SLOTS_PER_EPOCH;
+        set_participation(&mut state, Participation::Current)?;
+
+        assert!(imported_keys
+            .update(&*state, [public_key], |_| Some(0))
+            .is_empty());
+
+        // The attestation is still visible as previous epoch participation in epoch 2.
+        *state.make_mut().slot_mut() = SLOTS_PER_EPOCH * 2;
+        reset_participation(&mut state, Participation::Current)?;
+
+        assert!(imported_keys
+            .update(&*state, [public_key], |_| Some(0))
+            .is_empty());
+
+        *state.make_mut().slot_mut() = SLOTS_PER_EPOCH * (1 + LIVENESS_CHECK_EPOCHS);
+        reset_participation(&mut state, Participation::Previous)?;
+
+        assert_eq!(
+            imported_keys.update(&*state, [public_key], |_| Some(0)),
+            [public_key],
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn activity_after_import_epoch_blocks_activation() -> anyhow::Result<()> {
+        let config = Config::minimal().start_and_stay_in(Phase::Altair);
+        let (mut state, _) = factory::min_genesis_state::<Minimal>(&config)?;
+        let mut imported_keys = ImportedKeys::default();
+
+        let public_key = PublicKeyBytes::repeat_byte(1);
+
+        *state.make_mut().slot_mut() = SLOTS_PER_EPOCH;
+
+        assert!(imported_keys
+            .update(&*state, [public_key], |_| Some(0))
+            .is_empty());
+
+        *state.make_mut().slot_mut() = SLOTS_PER_EPOCH * 2;
+        set_participation(&mut state, Participation::Current)?;
+
+        assert!(imported_keys
+            .update(&*state, [public_key], |_| Some(0))
+            .is_empty());
+
+        *state.make_mut().slot_mut() = SLOTS_PER_EPOCH * (1 + LIVENESS_CHECK_EPOCHS);
+        reset_participation(&mut state, Participation::Current)?;
+
+        assert!(imported_keys
+            .update(&*state, [public_key], |_| Some(0))
+            .is_empty());
+        assert!(!imported_keys.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn imported_keys_round_trip() -> anyhow::Result<()> {
+        let config = Config::minimal();
+        let (mut state, _) = factory::min_genesis_state::<Minimal>(&config)?;
+        let database = Database::in_memory();
+        let mut imported_keys = ImportedKeys::default();
+
+        let public_key = PublicKeyBytes::repeat_byte(1);
+
+        *state.make_mut().slot_mut() = SLOTS_PER_EPOCH;
+
+        imported_keys.update(&*state, [public_key], |_| None);
+        imported_keys.save(&database)?;
+
+        let mut imported_keys = ImportedKeys::load(&database)?;
+
+        itertools::assert_equal(imported_keys.public_keys(), [public_key]);
+
+        // The import epoch is kept, so the restart does not delay activation.
+        *state.make_mut().slot_mut() = SLOTS_PER_EPOCH * (1 + LIVENESS_CHECK_EPOCHS);
+
+        assert_eq!(
+            imported_keys.update(&*state, [public_key], |_| None),
+            [public_key],
+        );
+
+        Ok(())
+    }
+
+    #[derive(Clone, Copy)]
+    enum Participation {
+        Previous,
+        Current,
+    }
+
+    fn set_participation(
+        state: &mut std::sync::Arc<BeaconState<Minimal>>,
+        participation: Participation,
+    ) -> anyhow::Result<()> {
+        *participation_flags(state, participation)? = 1;
+        Ok(())
+    }
+
+    fn reset_participation(
+        state: &mut std::sync::Arc<BeaconState<Minimal>>,
+        participation: Participation,
+    ) -> anyhow::Result<()> {
+        *participation_flags(state, participation)? = 0;
+        Ok(())
+    }
+
+    fn participation_flags(
+        state: &mut std::sync::Arc<BeaconState<Minimal>>,
+        participation: Participation,
+    ) -> anyhow::Result<&mut u8> {
+        let state = state
+            .make_mut()
+            .post_altair_mut()
+            .ok_or_else(|| anyhow::anyhow!("state should be post-Altair"))?;
+
+        let epoch_participation = match participation {
+            Participation::Previous => state.previous_epoch_participation_mut(),
+            Participation::Current => state.current_epoch_participation_mut(),
+        };
+
+        epoch_participation.get_mut(0).map_err(Into::into)
+    }
+}
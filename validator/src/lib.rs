@@ -1,15 +1,32 @@
 pub use crate::{
+    distributed_middleware::{
+        BeaconCommitteeSelection, DistributedMiddleware, SyncCommitteeSelection,
+    },
+    duties_cache::{
+        AttesterDuties, AttesterDuty, DutiesCache, ProposerDuties, ProposerDuty, SyncDuties,
+    },
     messages::{ApiToValidator, ValidatorToApi, ValidatorToLiveness},
     misc::{ProposerData as ValidatorProposerData, ValidatorBlindedBlock},
+    proposal_reports::{ProposalReport, ProposalReports},
     validator::{Channels as ValidatorChannels, Validator},
     validator_config::ValidatorConfig,
+    withdrawal_sweep::{positions as withdrawal_sweep_positions, WithdrawalSweepPosition},
 };
 
+mod distributed_middleware;
+mod duties_cache;
+mod duty_summary;
+mod equivocation_checks;
 mod eth1_storage;
+mod imported_keys;
 mod messages;
 mod misc;
 mod own_beacon_committee_subscriptions;
 mod own_sync_committee_subscriptions;
+mod persisted_operations;
+mod proposal_reports;
+mod published_attestations;
 mod slot_head;
 mod validator;
 mod validator_config;
+mod withdrawal_sweep;
@@ -1,16 +1,20 @@
 use bls::{PublicKeyBytes, SignatureBytes};
+use clock::ClockDrift;
 use serde::{Deserialize, Serialize};
 use ssz::{BitVector, Size, SszHash, SszSize, SszWrite, WriteError, H256};
 use typenum::U1;
 use types::{
     altair::consts::SyncCommitteeSubnetCount,
     combined::{BeaconBlock, BlindedBeaconBlock},
+    config::Config,
     nonstandard::Phase,
     phase0::primitives::{ValidatorIndex, H160},
     preset::Preset,
     traits::BeaconBlock as _,
 };
 
+use crate::validator_config::ValidatorConfig;
+
 #[allow(clippy::struct_field_names)]
 pub struct Aggregator {
     pub aggregator_index: ValidatorIndex,
@@ -98,3 +102,56 @@ impl<P: Preset> ValidatorBlindedBlock<P> {
         matches!(self, Self::BlindedBeaconBlock(_))
     }
 }
+
+// Blocks produced with a grossly wrong clock would be ignored as coming from the future or
+// published too late to become canonical. The estimate of the skew may be wrong, so proposals are
+// only refused if the user opted in.
+pub fn proposals_refused_due_to_clock_skew(
+    validator_config: &ValidatorConfig,
+    clock_drift: &ClockDrift,
+    config: &Config,
+) -> bool {
+    validator_config.refuse_proposals_on_clock_skew && clock_drift.is_grossly_skewed(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::SystemTime;
+
+    use test_case::test_case;
+
+    use super::*;
+
+    #[test_case(false, false => false; "disabled with accurate clock")]
+    #[test_case(false, true => false; "disabled with skewed clock")]
+    #[test_case(true, false => false; "enabled with accurate clock")]
+    #[test_case(true, true => true; "enabled with skewed clock")]
+    fn proposals_are_only_refused_if_enabled_and_clock_is_skewed(
+        refuse_proposals_on_clock_skew: bool,
+        skewed: bool,
+    ) -> bool {
+        let config = Config::mainnet();
+
+        let validator_config = ValidatorConfig {
+            refuse_proposals_on_clock_skew,
+            ..ValidatorConfig::default()
+        };
+
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("system time should be after the Unix epoch")
+            .as_secs();
+
+        // Blocks arriving a minute before their slot starts mean the local clock is far behind.
+        let slot_timestamp = if skewed { now + 60 } else { now };
+        let clock_drift = ClockDrift::default();
+
+        for _ in 0..32 {
+            clock_drift
+                .observe_block_arrival(slot_timestamp)
+                .expect("arrival time should be observed");
+        }
+
+        proposals_refused_due_to_clock_skew(&validator_config, &clock_drift, &config)
+    }
+}
@@ -1,25 +1,35 @@
-use std::collections::HashMap;
-
 use anyhow::Result;
-use helper_functions::{accessors, misc, predicates, signing::SignForSingleFork as _};
+use bls::SignatureBytes;
+use eth1_api::ApiController;
+use fork_choice_control::Wait;
+use helper_functions::{predicates, signing_domains::SigningDomains};
+use itertools::Itertools as _;
 use log::warn;
 use p2p::BeaconCommitteeSubscription;
-use signer::{Signer, SigningMessage, SigningTriple};
+use signer::{ForkInfo, Signer, SigningMessage, SigningTriple};
 use tokio::sync::RwLock;
 use types::{config::Config, phase0::primitives::Epoch, preset::Preset, traits::BeaconState};
 
+use crate::{
+    distributed_middleware::{BeaconCommitteeSelection, DistributedMiddleware},
+    duties_cache::{AttesterDuties, AttesterDuty},
+};
+
 #[derive(Default)]
 pub struct OwnBeaconCommitteeSubscriptions {
     latest_computed_epoch: Option<Epoch>,
 }
 
 impl OwnBeaconCommitteeSubscriptions {
-    pub async fn compute_for_epoch<P: Preset>(
+    pub async fn compute_for_epoch<P: Preset, W: Wait>(
         &mut self,
         config: &Config,
+        controller: &ApiController<P, W>,
         epoch: Epoch,
         state: &impl BeaconState<P>,
+        attester_duties: &AttesterDuties,
         signer: &RwLock<Signer>,
+        distributed_middleware: Option<&DistributedMiddleware>,
     ) -> Result<Vec<BeaconCommitteeSubscription>> {
         if self
             .latest_computed_epoch
@@ -29,57 +39,97 @@ impl OwnBeaconCommitteeSubscriptions {
             return Ok(vec![]);
         }
 
-        let own_public_keys = signer
+        let own_duties = signer
             .read()
             .await
             .keys()
             .copied()
             .filter_map(|public_key| {
-                let validator_index = accessors::index_of_public_key(state, public_key)?;
-                Some((validator_index, public_key))
+                let validator_index = controller.validator_index(state, public_key)?;
+                let duty = attester_duties.duties.get(&validator_index).copied()?;
+                Some((duty, validator_index, public_key))
             })
-            .collect::<HashMap<_, _>>();
+            .sorted_by_key(|(duty, _, _)| {
+                (
+                    duty.slot,
+                    duty.committee_index,
+                    duty.validator_committee_index,
+                )
+            })
+            .collect_vec();
 
-        if own_public_keys.is_empty() {
+        if own_duties.is_empty() {
             return Ok(vec![]);
         }
 
         let mut subscriptions = vec![];
         let mut triples = vec![];
 
-        for slot in misc::slots_in_epoch::<P>(epoch) {
-            let beacon_committees = (0..).zip(accessors::beacon_committees(state, slot)?);
-            let relative_epoch = accessors::relative_epoch(state, epoch)?;
-            let committees_at_slot = accessors::get_committee_count_per_slot(state, relative_epoch);
-
-            for (committee_index, committee) in beacon_committees {
-                for validator_index in committee {
-                    let Some(public_key) = own_public_keys.get(&validator_index).copied() else {
-                        continue;
-                    };
-
-                    subscriptions.push(BeaconCommitteeSubscription {
-                        validator_index,
-                        committee_index,
-                        committees_at_slot,
-                        slot,
-                        is_aggregator: false,
-                    });
-
-                    triples.push(SigningTriple::<P> {
-                        message: SigningMessage::AggregationSlot { slot },
-                        signing_root: slot.signing_root(config, state),
-                        public_key,
-                    });
-                }
-            }
+        // `state` may be from the epoch before a fork while `epoch` is the first one after it.
+        let signing_domains = SigningDomains::for_state(config, state);
+
+        for (duty, validator_index, public_key) in own_duties {
+            let AttesterDuty {
+                committee_index,
+                committees_at_slot,
+                slot,
+                ..
+            } = duty;
+
+            subscriptions.push(BeaconCommitteeSubscription {
+                validator_index,
+                committee_index,
+                committees_at_slot,
+                slot,
+                is_aggregator: false,
+            });
+
+            triples.push(SigningTriple::<P> {
+                message: SigningMessage::AggregationSlot { slot },
+                signing_root: signing_domains.signing_root(&slot),
+                public_key,
+            });
         }
 
         let slot_signatures = signer
             .read()
             .await
-            .sign_triples(triples, Some(state.into()))
-            .await?;
+            .sign_triples(triples, Some(ForkInfo::scheduled(signing_domains, epoch)))
+            .await?
+            .map(SignatureBytes::from)
+            .collect_vec();
+
+        let slot_signatures = match distributed_middleware {
+            Some(middleware) => {
+                let selections = subscriptions
+                    .iter()
+                    .zip(slot_signatures)
+                    .map(|(subscription, selection_proof)| BeaconCommitteeSelection {
+                        validator_index: subscription.validator_index,
+                        slot: subscription.slot,
+                        selection_proof,
+                    })
+                    .collect();
+
+                match middleware
+                    .beacon_committee_selection_proofs(selections)
+                    .await
+                {
+                    Ok(selection_proofs) => selection_proofs,
+                    Err(error) => {
+                        warn!(
+                            "failed to combine selection proofs through \
+                             distributed validator middleware: {error:?}",
+                        );
+
+                        // Subscribe without aggregation duties rather than not at all.
+                        self.latest_computed_epoch = Some(epoch);
+                        return Ok(subscriptions);
+                    }
+                }
+            }
+            None => slot_signatures,
+        };
 
         let result = subscriptions
             .into_iter()
@@ -94,7 +144,7 @@ impl OwnBeaconCommitteeSubscriptions {
                 } = subscription;
 
                 let is_aggregator =
-                    predicates::is_aggregator(state, slot, committee_index, slot_signature.into())?;
+                    predicates::is_aggregator(state, slot, committee_index, slot_signature)?;
 
                 Ok(BeaconCommitteeSubscription {
                     validator_index,
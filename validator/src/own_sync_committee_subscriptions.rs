@@ -2,6 +2,8 @@ use core::marker::PhantomData;
 use std::collections::{BTreeMap, HashMap, HashSet};
 
 use bls::PublicKeyBytes;
+use eth1_api::ApiController;
+use fork_choice_control::Wait;
 use helper_functions::{accessors, misc};
 use itertools::Itertools as _;
 use p2p::SyncCommitteeSubscription;
@@ -21,8 +23,9 @@ pub struct OwnSyncCommitteeSubscriptions<P: Preset> {
 }
 
 impl<P: Preset> OwnSyncCommitteeSubscriptions<P> {
-    pub fn build(
+    pub fn build<W: Wait>(
         &mut self,
+        controller: &ApiController<P, W>,
         state: &(impl PostAltairBeaconState<P> + ?Sized),
         own_public_keys: &HashSet<PublicKeyBytes>,
     ) {
@@ -34,6 +37,7 @@ impl<P: Preset> OwnSyncCommitteeSubscriptions<P> {
         if self.subscriptions.get(&current_period).is_none() {
             let subscriptions = core::iter::repeat(current_epoch)
                 .zip(sync_committee_subscriptions(
+                    controller,
                     state,
                     own_public_keys,
                     state.current_sync_committee(),
@@ -50,6 +54,7 @@ impl<P: Preset> OwnSyncCommitteeSubscriptions<P> {
             let mut rng = rand::thread_rng();
 
             let subscriptions = sync_committee_subscriptions(
+                controller,
                 state,
                 own_public_keys,
                 state.next_sync_committee(),
@@ -95,7 +100,8 @@ impl<P: Preset> OwnSyncCommitteeSubscriptions<P> {
     }
 }
 
-fn sync_committee_subscriptions<P: Preset>(
+fn sync_committee_subscriptions<P: Preset, W: Wait>(
+    controller: &ApiController<P, W>,
     state: &(impl PostAltairBeaconState<P> + ?Sized),
     own_public_keys: &HashSet<PublicKeyBytes>,
     sync_committee: &SyncCommittee<P>,
@@ -108,7 +114,7 @@ fn sync_committee_subscriptions<P: Preset>(
         .filter(|(_, public_key)| own_public_keys.contains(&public_key.to_bytes()))
         .filter_map(|(position, public_key)| {
             Some((
-                accessors::index_of_public_key(state, public_key.to_bytes())?,
+                controller.validator_index(state, public_key.to_bytes())?,
                 position,
             ))
         })
@@ -0,0 +1,226 @@
+use anyhow::Result;
+use builder_api::unphased::containers::SignedValidatorRegistrationV1;
+use database::Database;
+use educe::Educe;
+use ssz::{ContiguousList, Ssz, SszRead, SszReadDefault as _, SszWrite};
+use try_from_iterator::TryFromIterator as _;
+use typenum::{Unsigned as _, U65536};
+use types::{
+    capella::containers::SignedBlsToExecutionChange,
+    phase0::{
+        containers::{AttesterSlashing, ProposerSlashing, SignedVoluntaryExit},
+        primitives::Epoch,
+    },
+    preset::Preset,
+};
+
+use crate::imported_keys::ImportedKey;
+
+const PROPOSER_SLASHINGS_KEY: &str = "proposer_slashings";
+const ATTESTER_SLASHINGS_KEY: &str = "attester_slashings";
+const VOLUNTARY_EXITS_KEY: &str = "voluntary_exits";
+const BLS_TO_EXECUTION_CHANGES_KEY: &str = "bls_to_execution_changes";
+const VALIDATOR_REGISTRATIONS_KEY: &str = "validator_registrations";
+const IMPORTED_KEYS_KEY: &str = "imported_keys";
+
+type MaxPersistedOperations = U65536;
+
+/// Operations that were submitted to the node but not yet included in a block.
+/// They are stored between restarts and revalidated when loaded.
+#[derive(Educe)]
+#[educe(Default)]
+pub struct PersistedOperations<P: Preset> {
+    pub proposer_slashings: Vec<ProposerSlashing>,
+    pub attester_slashings: Vec<AttesterSlashing<P>>,
+    pub voluntary_exits: Vec<SignedVoluntaryExit>,
+    pub bls_to_execution_changes: Vec<SignedBlsToExecutionChange>,
+}
+
+impl<P: Preset> PersistedOperations<P> {
+    pub fn load(database: &Database) -> Result<Self> {
+        Ok(Self {
+            proposer_slashings: load_operations(database, PROPOSER_SLASHINGS_KEY)?,
+            attester_slashings: load_operations(database, ATTESTER_SLASHINGS_KEY)?,
+            voluntary_exits: load_operations(database, VOLUNTARY_EXITS_KEY)?,
+            bls_to_execution_changes: load_operations(database, BLS_TO_EXECUTION_CHANGES_KEY)?,
+        })
+    }
+
+    pub fn save(&self, database: &Database) -> Result<()> {
+        database.put_batch([
+            (
+                PROPOSER_SLASHINGS_KEY,
+                encode_operations(&self.proposer_slashings)?,
+            ),
+            (
+                ATTESTER_SLASHINGS_KEY,
+                encode_operations(&self.attester_slashings)?,
+            ),
+            (
+                VOLUNTARY_EXITS_KEY,
+                encode_operations(&self.voluntary_exits)?,
+            ),
+            (
+                BLS_TO_EXECUTION_CHANGES_KEY,
+                encode_operations(&self.bls_to_execution_changes)?,
+            ),
+        ])
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.proposer_slashings.is_empty()
+            && self.attester_slashings.is_empty()
+            && self.voluntary_exits.is_empty()
+            && self.bls_to_execution_changes.is_empty()
+    }
+}
+
+/// A validator registration along with the epoch it was received in.
+///
+/// The epoch is stored so that restored registrations are pruned at the same time as they would
+/// have been without a restart.
+#[derive(Clone, Copy, Debug, Ssz)]
+pub struct PersistedValidatorRegistration {
+    pub epoch: Epoch,
+    pub registration: SignedValidatorRegistrationV1,
+}
+
+// Validator registrations are stored under a separate key because they only need to be saved
+// when they change rather than every slot.
+pub fn load_validator_registrations(
+    database: &Database,
+) -> Result<Vec<PersistedValidatorRegistration>> {
+    load_operations(database, VALIDATOR_REGISTRATIONS_KEY)
+}
+
+pub fn save_validator_registrations(
+    database: &Database,
+    registrations: &[PersistedValidatorRegistration],
+) -> Result<()> {
+    database.put(
+        VALIDATOR_REGISTRATIONS_KEY,
+        encode_operations(registrations)?,
+    )
+}
+
+pub fn load_imported_keys(database: &Database) -> Result<Vec<ImportedKey>> {
+    load_operations(database, IMPORTED_KEYS_KEY)
+}
+
+pub fn save_imported_keys(database: &Database, imported_keys: &[ImportedKey]) -> Result<()> {
+    database.put(IMPORTED_KEYS_KEY, encode_operations(imported_keys)?)
+}
+
+fn load_operations<T: SszRead<()>>(database: &Database, key: &str) -> Result<Vec<T>> {
+    let Some(bytes) = database.get(key)? else {
+        return Ok(vec![]);
+    };
+
+    let operations = ContiguousList::<T, MaxPersistedOperations>::from_ssz_default(bytes)?;
+
+    Ok(operations.into_iter().collect())
+}
+
+fn encode_operations<T: Clone + SszWrite>(operations: &[T]) -> Result<Vec<u8>> {
+    let operations = ContiguousList::<T, MaxPersistedOperations>::try_from_iter(
+        operations
+            .iter()
+            .take(MaxPersistedOperations::USIZE)
+            .cloned(),
+    )?;
+
+    operations.to_ssz().map_err(Into::into)
+}
+
+#[cfg(test)]
+mod tests {
+    use bls::{PublicKeyBytes, SignatureBytes};
+    use builder_api::unphased::containers::ValidatorRegistrationV1;
+    use types::{
+        capella::containers::BlsToExecutionChange, phase0::primitives::ExecutionAddress,
+        preset::Minimal,
+    };
+
+    use super::*;
+
+    #[test]
+    fn persisted_operations_round_trip() -> Result<()> {
+        let database = Database::in_memory();
+
+        let operations = PersistedOperations::<Minimal> {
+            voluntary_exits: vec![SignedVoluntaryExit::default(); 2],
+            bls_to_execution_changes: vec![SignedBlsToExecutionChange {
+                message: BlsToExecutionChange {
+                    validator_index: 7,
+                    from_bls_pubkey: PublicKeyBytes::default(),
+                    to_execution_address: ExecutionAddress::default(),
+                },
+                signature: SignatureBytes::default(),
+            }],
+            ..PersistedOperations::default()
+        };
+
+        operations.save(&database)?;
+
+        let loaded = PersistedOperations::<Minimal>::load(&database)?;
+
+        assert_eq!(loaded.voluntary_exits, operations.voluntary_exits);
+        assert_eq!(
+            loaded.bls_to_execution_changes,
+            operations.bls_to_execution_changes,
+        );
+        assert!(loaded.proposer_slashings.is_empty());
+        assert!(loaded.attester_slashings.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn validator_registrations_round_trip() -> Result<()> {
+        let database = Database::in_memory();
+
+        assert!(load_validator_registrations(&database)?.is_empty());
+
+        let registration = SignedValidatorRegistrationV1 {
+            message: ValidatorRegistrationV1 {
+                fee_recipient: ExecutionAddress::repeat_byte(1),
+                gas_limit: 30_000_000,
+                timestamp: 1_700_000_000,
+                pubkey: PublicKeyBytes::default(),
+            },
+            signature: SignatureBytes::default(),
+        };
+
+        save_validator_registrations(
+            &database,
+            &[PersistedValidatorRegistration {
+                epoch: 3,
+                registration,
+            }],
+        )?;
+
+        let loaded = load_validator_registrations(&database)?;
+
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].epoch, 3);
+        assert_eq!(
+            loaded[0].registration.message.timestamp,
+            registration.message.timestamp,
+        );
+        assert_eq!(
+            loaded[0].registration.message.gas_limit,
+            registration.message.gas_limit,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn missing_operations_load_as_empty() -> Result<()> {
+        let operations = PersistedOperations::<Minimal>::load(&Database::in_memory())?;
+
+        assert!(operations.is_empty());
+
+        Ok(())
+    }
+}
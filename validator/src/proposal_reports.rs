@@ -0,0 +1,87 @@
+use core::time::Duration;
+use std::{collections::VecDeque, time::SystemTime};
+
+use bls::PublicKeyBytes;
+use parking_lot::Mutex;
+use serde::Serialize;
+use types::phase0::primitives::{Slot, UnixSeconds, ValidatorIndex, H256};
+
+const MAX_REPORTS: usize = 32;
+
+/// Information collected while attempting a proposal.
+///
+/// Durations are in milliseconds. Fields are left empty if the attempt ended before reaching
+/// the corresponding step.
+#[derive(Clone, Default, Debug, Serialize)]
+pub struct ProposalReport {
+    pub slot: Slot,
+    pub validator_index: ValidatorIndex,
+    pub pubkey: PublicKeyBytes,
+    pub reason: String,
+    pub head_block_root: H256,
+    pub head_slot: Slot,
+    pub head_optimistic: bool,
+    /// Time from the start of the slot to the start of the attempt.
+    pub start_delay_ms: Option<u64>,
+    pub randao_signing_ms: Option<u64>,
+    pub execution_payload_ms: Option<u64>,
+    pub execution_payload_error: Option<String>,
+    pub builder_response: Option<String>,
+    pub block_signing_ms: Option<u64>,
+    pub total_ms: Option<u64>,
+}
+
+/// Reports of the most recent proposals that own validators failed to publish.
+#[derive(Default)]
+pub struct ProposalReports {
+    reports: Mutex<VecDeque<ProposalReport>>,
+}
+
+impl ProposalReports {
+    #[must_use]
+    pub fn recent(&self) -> Vec<ProposalReport> {
+        self.reports.lock().iter().cloned().collect()
+    }
+
+    pub(crate) fn push(&self, report: ProposalReport) {
+        let mut reports = self.reports.lock();
+
+        if reports.len() == MAX_REPORTS {
+            reports.pop_front();
+        }
+
+        reports.push_back(report);
+    }
+}
+
+pub fn millis(duration: Duration) -> u64 {
+    duration.as_millis().try_into().unwrap_or(u64::MAX)
+}
+
+pub fn millis_since(timestamp: UnixSeconds) -> Option<u64> {
+    let start = SystemTime::UNIX_EPOCH + Duration::from_secs(timestamp);
+    SystemTime::now().duration_since(start).ok().map(millis)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_most_recent_reports_are_kept() {
+        let reports = ProposalReports::default();
+
+        for slot in 0..64 {
+            reports.push(ProposalReport {
+                slot,
+                ..ProposalReport::default()
+            });
+        }
+
+        let recent = reports.recent();
+
+        assert_eq!(recent.len(), MAX_REPORTS);
+        assert_eq!(recent.first().map(|report| report.slot), Some(32));
+        assert_eq!(recent.last().map(|report| report.slot), Some(63));
+    }
+}
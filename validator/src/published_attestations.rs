@@ -0,0 +1,211 @@
+use core::time::Duration;
+use std::sync::Arc;
+
+use anyhow::Result;
+use clock::{Tick, TickKind};
+use educe::Educe;
+use types::{
+    config::Config,
+    phase0::{
+        consts::INTERVALS_PER_SLOT,
+        containers::Attestation,
+        primitives::{SubnetId, ValidatorIndex},
+    },
+    preset::Preset,
+};
+
+pub struct PublishedAttestation<P: Preset> {
+    pub validator_index: ValidatorIndex,
+    pub position_in_committee: usize,
+    pub attestation: Arc<Attestation<P>>,
+    pub subnet_id: SubnetId,
+    pub seen_in_aggregate: bool,
+}
+
+/// Own attestations published in the current slot along with whether they have been aggregated.
+#[derive(Educe)]
+#[educe(Default)]
+pub struct PublishedAttestations<P: Preset> {
+    attestations: Vec<PublishedAttestation<P>>,
+}
+
+impl<P: Preset> PublishedAttestations<P> {
+    pub fn insert(
+        &mut self,
+        validator_index: ValidatorIndex,
+        attestation: Arc<Attestation<P>>,
+        subnet_id: SubnetId,
+    ) {
+        let Some(position_in_committee) = attestation.aggregation_bits.first_one() else {
+            return;
+        };
+
+        self.attestations.push(PublishedAttestation {
+            validator_index,
+            position_in_committee,
+            attestation,
+            subnet_id,
+            seen_in_aggregate: false,
+        });
+    }
+
+    pub fn mark_in_aggregate(&mut self, aggregate: &Attestation<P>) {
+        // Singular attestations may be our own ones relayed back to us.
+        if aggregate.aggregation_bits.count_ones() < 2 {
+            return;
+        }
+
+        for published in &mut self.attestations {
+            if published.attestation.data == aggregate.data
+                && aggregate
+                    .aggregation_bits
+                    .get(published.position_in_committee)
+                    .is_some_and(|bit| *bit)
+            {
+                published.seen_in_aggregate = true;
+            }
+        }
+    }
+
+    pub fn unaggregated(&self) -> impl Iterator<Item = &PublishedAttestation<P>> {
+        self.attestations
+            .iter()
+            .filter(|published| !published.seen_in_aggregate)
+    }
+
+    pub fn clear(&mut self) {
+        self.attestations.clear();
+    }
+}
+
+/// Returns `true` if own attestations missing from aggregates should be published again at `tick`.
+///
+/// Aggregates are due at the start of the last interval of a slot, so checking earlier would find
+/// every attestation missing. Attestations are published again at the first tick at least `delay`
+/// after that point and no later than the last tick of the slot.
+pub fn rebroadcast_due(
+    config: &Config,
+    delay: Duration,
+    tick: Tick,
+    last_tick: Option<Tick>,
+) -> Result<bool> {
+    if !is_past_rebroadcast_time(config, delay, tick)? {
+        return Ok(false);
+    }
+
+    match last_tick {
+        Some(last_tick) if last_tick.slot == tick.slot => {
+            Ok(!is_past_rebroadcast_time(config, delay, last_tick)?)
+        }
+        _ => Ok(true),
+    }
+}
+
+fn is_past_rebroadcast_time(config: &Config, delay: Duration, tick: Tick) -> Result<bool> {
+    if tick.kind == TickKind::AggregateFourth {
+        return Ok(true);
+    }
+
+    let slot_duration = Duration::from_secs(config.seconds_per_slot.get());
+    let aggregation_interval = slot_duration / u32::try_from(INTERVALS_PER_SLOT.get())?;
+
+    Ok(tick.duration_until_next_slot(config)? + delay <= aggregation_interval)
+}
+
+#[cfg(test)]
+mod tests {
+    use ssz::BitList;
+    use test_case::test_case;
+    use types::{
+        phase0::{containers::AttestationData, primitives::H256},
+        preset::Minimal,
+    };
+
+    use super::*;
+
+    #[test]
+    fn mark_in_aggregate_ignores_singular_attestations_and_other_data() {
+        let mut published = PublishedAttestations::<Minimal>::default();
+
+        published.insert(1, attestation(H256::zero(), &[0]), 0);
+        published.insert(2, attestation(H256::zero(), &[3]), 0);
+        published.insert(3, attestation(H256::repeat_byte(1), &[1]), 0);
+
+        published.mark_in_aggregate(&attestation(H256::zero(), &[0]));
+        published.mark_in_aggregate(&attestation(H256::repeat_byte(1), &[1, 2]));
+
+        assert_eq!(unaggregated_validators(&published), [1, 2]);
+
+        published.mark_in_aggregate(&attestation(H256::zero(), &[0, 1, 2]));
+
+        assert_eq!(unaggregated_validators(&published), [2]);
+
+        published.mark_in_aggregate(&attestation(H256::repeat_byte(2), &[2, 3]));
+
+        assert_eq!(unaggregated_validators(&published), [2]);
+
+        published.clear();
+
+        assert!(unaggregated_validators(&published).is_empty());
+    }
+
+    // Ticks in mainnet are 1 second long and aggregates are due 8 seconds into a slot.
+    #[test_case(Duration::ZERO, TickKind::AggregateFourth, None => true; "last tick of slot")]
+    #[test_case(Duration::ZERO, TickKind::AttestFourth, None => false; "before aggregation point")]
+    #[test_case(Duration::ZERO, TickKind::Aggregate, None => true; "at aggregation point")]
+    #[test_case(
+        Duration::from_secs(2), TickKind::AggregateSecond, None => false;
+        "before delay passes"
+    )]
+    #[test_case(
+        Duration::from_secs(2), TickKind::AggregateThird, Some(TickKind::AggregateSecond) => true;
+        "when delay passes"
+    )]
+    #[test_case(
+        Duration::from_secs(2), TickKind::AggregateFourth, Some(TickKind::AggregateThird) => false;
+        "after delay passed"
+    )]
+    #[test_case(
+        Duration::from_millis(1500), TickKind::AggregateThird, Some(TickKind::AggregateSecond) => true;
+        "first tick after delay"
+    )]
+    #[test_case(
+        Duration::from_secs(10), TickKind::AggregateFourth, Some(TickKind::AggregateThird) => true;
+        "delay longer than aggregation interval"
+    )]
+    fn rebroadcast_is_due_once_per_slot(
+        delay: Duration,
+        kind: TickKind,
+        last_kind: Option<TickKind>,
+    ) -> bool {
+        let config = Config::mainnet();
+        let tick = Tick { slot: 1, kind };
+        let last_tick = last_kind.map(|kind| Tick { slot: 1, kind });
+
+        rebroadcast_due(&config, delay, tick, last_tick).expect("mainnet ticks are valid")
+    }
+
+    fn attestation(beacon_block_root: H256, positions: &[usize]) -> Arc<Attestation<Minimal>> {
+        let mut aggregation_bits = BitList::with_length(4);
+
+        for position in positions {
+            aggregation_bits.set(*position, true);
+        }
+
+        Arc::new(Attestation {
+            aggregation_bits,
+            data: AttestationData {
+                beacon_block_root,
+                ..AttestationData::default()
+            },
+            ..Attestation::default()
+        })
+    }
+
+    fn unaggregated_validators(published: &PublishedAttestations<Minimal>) -> Vec<ValidatorIndex> {
+        published
+            .unaggregated()
+            .map(|published| published.validator_index)
+            .collect()
+    }
+}
@@ -4,11 +4,10 @@ use std::{collections::HashSet, sync::Arc};
 use anyhow::Result;
 use bls::{CachedPublicKey, PublicKeyBytes, SignatureBytes};
 use helper_functions::{
-    accessors, misc, predicates,
-    signing::{SignForSingleFork, SignForSingleForkAtSlot as _},
+    accessors, misc, predicates, signing::SignForSingleFork, signing_domains::SigningDomains,
 };
 use log::warn;
-use signer::{Signer, SigningMessage, SigningTriple};
+use signer::{ForkInfo, Signer, SigningMessage, SigningTriple};
 use tokio::sync::RwLock;
 use types::{
     altair::{
@@ -24,6 +23,10 @@ use types::{
     traits::BeaconState as _,
 };
 
+use crate::distributed_middleware::{
+    BeaconCommitteeSelection, DistributedMiddleware, SyncCommitteeSelection,
+};
+
 pub struct SlotHead<P: Preset> {
     pub config: Arc<Config>,
     pub beacon_block_root: H256,
@@ -42,6 +45,18 @@ impl<P: Preset> SlotHead<P> {
         accessors::get_current_epoch(&self.beacon_state)
     }
 
+    /// Domains used to sign messages on top of this head.
+    #[must_use]
+    pub fn signing_domains(&self) -> SigningDomains<'_, P> {
+        SigningDomains::for_state(&self.config, &self.beacon_state)
+    }
+
+    /// [`ForkInfo`] matching [`Self::signing_domains`] for messages in the current epoch.
+    #[must_use]
+    pub fn fork_info(&self) -> ForkInfo<P> {
+        ForkInfo::scheduled(self.signing_domains(), self.current_epoch())
+    }
+
     #[must_use]
     pub fn public_key(&self, validator_index: ValidatorIndex) -> &CachedPublicKey {
         &self
@@ -64,10 +79,6 @@ impl<P: Preset> SlotHead<P> {
         own_public_keys.contains(&self.public_key(validator_index).to_bytes())
     }
 
-    pub fn proposer_index(&self) -> Result<ValidatorIndex> {
-        accessors::get_beacon_proposer_index(&self.beacon_state)
-    }
-
     pub fn beacon_committee(&self, committee_index: CommitteeIndex) -> Result<IndexSlice> {
         accessors::beacon_committee(&self.beacon_state, self.slot(), committee_index)
     }
@@ -92,41 +103,69 @@ impl<P: Preset> SlotHead<P> {
         misc::compute_subnet_for_attestation::<P>(committees_per_slot, slot, committee_index)
     }
 
+    /// Partial selection proofs of distributed validators are exchanged for combined ones through
+    /// `distributed_middleware` before deciding whether the validators are aggregators.
     pub async fn selection_proofs<I>(
         &self,
-        committee_indices_with_pubkeys: I,
+        validator_and_committee_indices_with_pubkeys: I,
         signer: &RwLock<Signer>,
+        distributed_middleware: Option<&DistributedMiddleware>,
     ) -> Result<Vec<Option<SignatureBytes>>>
     where
-        I: IntoIterator<Item = (CommitteeIndex, PublicKeyBytes)> + Send,
+        I: IntoIterator<Item = (ValidatorIndex, CommitteeIndex, PublicKeyBytes)> + Send,
     {
         let slot = self.slot();
+        let signing_domains = self.signing_domains();
 
-        let (triples, committee_indices): (Vec<_>, Vec<_>) = committee_indices_with_pubkeys
+        let (triples, indices): (Vec<_>, Vec<_>) = validator_and_committee_indices_with_pubkeys
             .into_iter()
-            .map(|(committee_index, public_key)| {
+            .map(|(validator_index, committee_index, public_key)| {
                 let triple = SigningTriple {
                     message: SigningMessage::AggregationSlot { slot },
-                    signing_root: slot.signing_root(&self.config, &self.beacon_state),
+                    signing_root: signing_domains.signing_root(&slot),
                     public_key,
                 };
 
-                (triple, committee_index)
+                (triple, (validator_index, committee_index))
             })
             .unzip();
 
-        signer
+        let slot_signatures = signer
             .read()
             .await
-            .sign_triples(triples, Some(self.beacon_state.as_ref().into()))
+            .sign_triples(triples, Some(self.fork_info()))
             .await?
-            .zip(committee_indices)
-            .map(|(signature, committee_index)| {
-                let slot_signature = signature.into();
+            .map(SignatureBytes::from)
+            .collect::<Vec<_>>();
+
+        let slot_signatures = match distributed_middleware {
+            Some(middleware) => {
+                let selections = slot_signatures
+                    .into_iter()
+                    .zip(&indices)
+                    .map(
+                        |(selection_proof, (validator_index, _))| BeaconCommitteeSelection {
+                            validator_index: *validator_index,
+                            slot,
+                            selection_proof,
+                        },
+                    )
+                    .collect();
 
+                middleware
+                    .beacon_committee_selection_proofs(selections)
+                    .await?
+            }
+            None => slot_signatures,
+        };
+
+        slot_signatures
+            .into_iter()
+            .zip(indices)
+            .map(|(slot_signature, (_, committee_index))| {
                 let aggregator = predicates::is_aggregator(
                     &self.beacon_state,
-                    self.slot(),
+                    slot,
                     committee_index,
                     slot_signature,
                 )?;
@@ -146,6 +185,8 @@ impl<P: Preset> SlotHead<P> {
     where
         I: IntoIterator<Item = (ValidatorIndex, PublicKeyBytes)> + Send,
     {
+        let signing_domains = self.signing_domains();
+
         let (triples, validator_indices): (Vec<_>, Vec<_>) = validator_indices_with_pubkeys
             .into_iter()
             .map(|(validator_index, public_key)| {
@@ -154,11 +195,8 @@ impl<P: Preset> SlotHead<P> {
                         beacon_block_root: self.beacon_block_root,
                         slot,
                     },
-                    signing_root: self.beacon_block_root.signing_root(
-                        &self.config,
-                        &self.beacon_state,
-                        self.slot(),
-                    ),
+                    signing_root: signing_domains
+                        .signing_root_at_slot(&self.beacon_block_root, self.slot()),
                     public_key,
                 };
 
@@ -169,7 +207,7 @@ impl<P: Preset> SlotHead<P> {
         let messages = signer
             .read()
             .await
-            .sign_triples(triples, Some(self.beacon_state.as_ref().into()))
+            .sign_triples(triples, Some(self.fork_info()))
             .await?
             .zip(validator_indices)
             .map(move |(signature, validator_index)| SyncCommitteeMessage {
@@ -183,36 +221,74 @@ impl<P: Preset> SlotHead<P> {
     }
 
     /// <https://github.com/ethereum/consensus-specs/blob/dc14b79a521fb621f0d2b9da9410f6e7ffaa7df5/specs/altair/validator.md#aggregation-selection>
-    pub async fn sync_committee_selection_proofs(
+    ///
+    /// See [`Self::selection_proofs`] for how `distributed_middleware` is used.
+    pub async fn sync_committee_selection_proofs<I>(
         &self,
-        subcommittee_indices_with_pubkeys: impl Iterator<Item = (SubcommitteeIndex, PublicKeyBytes)>
-            + Send,
+        indices_with_pubkeys: I,
         signer: &RwLock<Signer>,
-    ) -> Result<Vec<Option<SignatureBytes>>> {
-        let triples = subcommittee_indices_with_pubkeys.map(|(subcommittee_index, public_key)| {
-            let selection_data = SyncAggregatorSelectionData {
-                slot: self.slot(),
-                subcommittee_index,
-            };
-
-            SigningTriple {
-                message: SigningMessage::SyncAggregatorSelectionData(selection_data),
-                signing_root: selection_data.signing_root(&self.config, &self.beacon_state),
-                public_key,
-            }
-        });
+        distributed_middleware: Option<&DistributedMiddleware>,
+    ) -> Result<Vec<Option<SignatureBytes>>>
+    where
+        I: Iterator<Item = (ValidatorIndex, SubcommitteeIndex, PublicKeyBytes)> + Send,
+    {
+        let slot = self.slot();
+        let signing_domains = self.signing_domains();
+
+        let (triples, indices): (Vec<_>, Vec<_>) = indices_with_pubkeys
+            .map(|(validator_index, subcommittee_index, public_key)| {
+                let selection_data = SyncAggregatorSelectionData {
+                    slot,
+                    subcommittee_index,
+                };
+
+                let triple = SigningTriple {
+                    message: SigningMessage::SyncAggregatorSelectionData(selection_data),
+                    signing_root: signing_domains.signing_root(&selection_data),
+                    public_key,
+                };
 
-        signer
+                (triple, (validator_index, subcommittee_index))
+            })
+            .unzip();
+
+        let selection_proofs = signer
             .read()
             .await
-            .sign_triples(triples, Some(self.beacon_state.as_ref().into()))
+            .sign_triples(triples, Some(self.fork_info()))
             .await?
-            .map(|signature| {
-                let selection_proof = signature.into();
+            .map(SignatureBytes::from)
+            .collect::<Vec<_>>();
+
+        let selection_proofs = match distributed_middleware {
+            Some(middleware) => {
+                let selections = selection_proofs
+                    .into_iter()
+                    .zip(indices)
+                    .map(|(selection_proof, (validator_index, subcommittee_index))| {
+                        SyncCommitteeSelection {
+                            validator_index,
+                            slot,
+                            subcommittee_index,
+                            selection_proof,
+                        }
+                    })
+                    .collect();
+
+                middleware
+                    .sync_committee_selection_proofs(selections)
+                    .await?
+            }
+            None => selection_proofs,
+        };
+
+        Ok(selection_proofs
+            .into_iter()
+            .map(|selection_proof| {
                 let aggregator = predicates::is_sync_committee_aggregator::<P>(selection_proof);
-                Ok(aggregator.then_some(selection_proof))
+                aggregator.then_some(selection_proof)
             })
-            .collect()
+            .collect())
     }
 
     pub async fn sign_beacon_block(
@@ -229,8 +305,8 @@ impl<P: Preset> SlotHead<P> {
             .await
             .sign(
                 message,
-                block.signing_root(&self.config, &self.beacon_state),
-                Some(self.beacon_state.as_ref().into()),
+                self.signing_domains().signing_root(block),
+                Some(self.fork_info()),
                 public_key,
             )
             .await
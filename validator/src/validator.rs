@@ -1,23 +1,24 @@
 //! <https://github.com/ethereum/consensus-specs/blob/b2f42bf4d79432ee21e2f2b3912ff4bbf7898ada/specs/phase0/validator.md>
 
-use core::ops::ControlFlow;
+use core::{ops::ControlFlow, time::Duration};
 use std::{
     collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     error::Error as StdError,
     sync::Arc,
-    time::SystemTime,
+    time::{Instant, SystemTime},
 };
 
 use anyhow::{ensure, Error as AnyhowError, Result};
-use bls::{AggregateSignature, PublicKeyBytes, Signature, SignatureBytes};
+use bls::{AggregateSignature, CachedPublicKey, PublicKeyBytes, Signature, SignatureBytes};
 use builder_api::{
     combined::SignedBuilderBid,
     consts::EPOCHS_PER_VALIDATOR_REGISTRATION_SUBMISSION,
     unphased::containers::{SignedValidatorRegistrationV1, ValidatorRegistrationV1},
-    BuilderApi,
+    BuilderApi, BuilderApiError,
 };
 use cached::{Cached as _, SizedCache};
-use clock::{Tick, TickKind};
+use clock::{ClockDrift, Tick, TickKind};
+use database::Database;
 use derive_more::Display;
 use eth1::Eth1Chain;
 use eth1_api::{ApiController, Eth1ExecutionEngine};
@@ -29,7 +30,10 @@ use features::Feature;
 use fork_choice_control::{StateCacheError, ValidatorMessage, Wait};
 use fork_choice_store::ChainLink;
 use futures::{
-    channel::mpsc::{UnboundedReceiver, UnboundedSender},
+    channel::{
+        mpsc::{UnboundedReceiver, UnboundedSender},
+        oneshot::Receiver as OneshotReceiver,
+    },
     future::{Either as EitherFuture, OptionFuture},
     lock::Mutex,
     select,
@@ -37,7 +41,8 @@ use futures::{
 };
 use helper_functions::{
     accessors, misc, predicates,
-    signing::{RandaoEpoch, SignForAllForks, SignForSingleFork},
+    signing::{RandaoEpoch, SignForAllForks},
+    signing_domains::SigningDomains,
 };
 use itertools::{Either, Itertools as _};
 use keymanager::ProposerConfigs;
@@ -111,15 +116,27 @@ use types::{
 };
 
 use crate::{
+    distributed_middleware::DistributedMiddleware,
+    duties_cache::DutiesCache,
+    duty_summary::DutySummary,
+    equivocation_checks,
     eth1_storage::Eth1Storage as _,
+    imported_keys::ImportedKeys,
     messages::{
         ApiToValidator, BeaconBlockSender, BlindedBlockSender, ValidatorToApi, ValidatorToLiveness,
     },
-    misc::{Aggregator, ProposerData, SyncCommitteeMember, ValidatorBlindedBlock},
+    misc::{
+        proposals_refused_due_to_clock_skew, Aggregator, ProposerData, SyncCommitteeMember,
+        ValidatorBlindedBlock,
+    },
     own_beacon_committee_subscriptions::OwnBeaconCommitteeSubscriptions,
     own_sync_committee_subscriptions::OwnSyncCommitteeSubscriptions,
+    persisted_operations::{self, PersistedOperations, PersistedValidatorRegistration},
+    proposal_reports::{self, ProposalReport, ProposalReports},
+    published_attestations::{self, PublishedAttestations},
     slot_head::SlotHead,
     validator_config::ValidatorConfig,
+    withdrawal_sweep,
 };
 
 const EPOCHS_TO_KEEP_REGISTERED_VALIDATORS: u64 = 2;
@@ -143,6 +160,15 @@ enum Error<P: Preset> {
     },
     #[error("self-incriminating proposer slashing: {proposer_slashing:?}")]
     SelfIncriminatingProposerSlashing { proposer_slashing: ProposerSlashing },
+    #[error(
+        "{payload_type} execution payload fee recipient {actual} \
+         does not match configured fee recipient {expected}"
+    )]
+    FeeRecipientMismatch {
+        payload_type: &'static str,
+        expected: ExecutionAddress,
+        actual: ExecutionAddress,
+    },
 }
 
 #[derive(Display)]
@@ -160,6 +186,7 @@ assert_not_impl_any!(HeadFarBehind: StdError);
 pub struct Channels<P: Preset, W> {
     pub api_to_validator_rx: UnboundedReceiver<ApiToValidator<P>>,
     pub fork_choice_rx: UnboundedReceiver<ValidatorMessage<P, W>>,
+    pub graceful_shutdown_rx: OneshotReceiver<()>,
     pub p2p_tx: UnboundedSender<ValidatorToP2p<P>>,
     pub p2p_to_validator_rx: UnboundedReceiver<P2pToValidator<P>>,
     pub slasher_to_validator_rx: Option<UnboundedReceiver<SlasherToValidator<P>>>,
@@ -178,20 +205,27 @@ pub struct Validator<P: Preset, W: Wait> {
     execution_engine: Arc<Eth1ExecutionEngine<P>>,
     api_to_validator_rx: UnboundedReceiver<ApiToValidator<P>>,
     fork_choice_rx: UnboundedReceiver<ValidatorMessage<P, W>>,
+    graceful_shutdown_rx: OneshotReceiver<()>,
     p2p_tx: UnboundedSender<ValidatorToP2p<P>>,
     p2p_to_validator_rx: UnboundedReceiver<P2pToValidator<P>>,
     last_tick: Option<Tick>,
     next_graffiti_index: usize,
     attestation_agg_pool: Arc<AttestationAggPool<P, W>>,
+    duties_cache: Arc<DutiesCache>,
     own_beacon_committee_subscriptions: OwnBeaconCommitteeSubscriptions,
     own_singular_attestations: OnceCell<Vec<OwnAttestation<P>>>,
+    published_own_attestations: PublishedAttestations<P>,
+    duty_summary: DutySummary,
+    imported_keys: ImportedKeys,
     own_sync_committee_members: TokioOnceCell<Vec<SyncCommitteeMember>>,
     own_sync_committee_subscriptions: OwnSyncCommitteeSubscriptions<P>,
     published_own_sync_committee_messages: bool,
     own_aggregators: BTreeMap<AttestationData, Vec<Aggregator>>,
     validator_votes: HashMap<Epoch, Vec<ValidatorVote>>,
     builder_api: Option<Arc<BuilderApi>>,
+    distributed_middleware: Option<Arc<DistributedMiddleware>>,
     last_registration_epoch: Option<Epoch>,
+    last_registration_version: u64,
     proposer_configs: Arc<ProposerConfigs>,
     signer: Arc<RwLock<Signer>>,
     slashing_protector: Arc<Mutex<SlashingProtector>>,
@@ -203,11 +237,16 @@ pub struct Validator<P: Preset, W: Wait> {
         BTreeMap<Epoch, BTreeMap<PublicKeyBytes, (ValidatorRegistrationV1, Signature)>>,
     attester_slashings: Vec<AttesterSlashing<P>>,
     voluntary_exits: Vec<SignedVoluntaryExit>,
+    operation_pool_database: Database,
     sync_committee_agg_pool: Arc<SyncCommitteeAggPool<P, W>>,
     bls_to_execution_change_pool: Arc<BlsToExecutionChangePool>,
     payload_cache: SizedCache<H256, WithBlobsAndMev<ExecutionPayload<P>, P>>,
     payload_id_cache: SizedCache<(H256, Slot), PayloadId>,
     metrics: Option<Arc<Metrics>>,
+    clock_drift: Arc<ClockDrift>,
+    proposal_reports: Arc<ProposalReports>,
+    // Present only while `Validator::propose` is running.
+    proposal_report: Option<ProposalReport>,
     validator_to_api_tx: UnboundedSender<ValidatorToApi<P>>,
     validator_to_liveness_tx: Option<UnboundedSender<ValidatorToLiveness<P>>>,
     validator_to_slasher_tx: Option<UnboundedSender<ValidatorToSlasher>>,
@@ -222,18 +261,24 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
         controller: ApiController<P, W>,
         execution_engine: Arc<Eth1ExecutionEngine<P>>,
         attestation_agg_pool: Arc<AttestationAggPool<P, W>>,
+        duties_cache: Arc<DutiesCache>,
         builder_api: Option<Arc<BuilderApi>>,
+        distributed_middleware: Option<Arc<DistributedMiddleware>>,
         proposer_configs: Arc<ProposerConfigs>,
         signer: Arc<RwLock<Signer>>,
         slashing_protector: Arc<Mutex<SlashingProtector>>,
         sync_committee_agg_pool: Arc<SyncCommitteeAggPool<P, W>>,
         bls_to_execution_change_pool: Arc<BlsToExecutionChangePool>,
+        operation_pool_database: Database,
         metrics: Option<Arc<Metrics>>,
+        clock_drift: Arc<ClockDrift>,
+        proposal_reports: Arc<ProposalReports>,
         channels: Channels<P, W>,
     ) -> Self {
         let Channels {
             api_to_validator_rx,
             fork_choice_rx,
+            graceful_shutdown_rx,
             p2p_tx,
             p2p_to_validator_rx,
             slasher_to_validator_rx,
@@ -251,20 +296,27 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
             execution_engine,
             api_to_validator_rx,
             fork_choice_rx,
+            graceful_shutdown_rx,
             p2p_tx,
             p2p_to_validator_rx,
             last_tick: None,
             next_graffiti_index: 0,
             attestation_agg_pool,
+            duties_cache,
             own_beacon_committee_subscriptions: OwnBeaconCommitteeSubscriptions::default(),
             own_singular_attestations: OnceCell::new(),
+            published_own_attestations: PublishedAttestations::default(),
+            duty_summary: DutySummary::default(),
+            imported_keys: ImportedKeys::default(),
             own_sync_committee_members: TokioOnceCell::new(),
             own_sync_committee_subscriptions: OwnSyncCommitteeSubscriptions::default(),
             published_own_sync_committee_messages: false,
             own_aggregators: BTreeMap::new(),
             validator_votes: HashMap::new(),
             builder_api,
+            distributed_middleware,
             last_registration_epoch: None,
+            last_registration_version: 0,
             proposer_configs,
             signer,
             slashing_protector,
@@ -277,9 +329,13 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
             registered_validators: BTreeMap::new(),
             attester_slashings: vec![],
             voluntary_exits: vec![],
+            operation_pool_database,
             payload_cache: SizedCache::with_size(PAYLOAD_CACHE_SIZE),
             payload_id_cache: SizedCache::with_size(PAYLOAD_ID_CACHE_SIZE),
             metrics,
+            clock_drift,
+            proposal_reports,
+            proposal_report: None,
             validator_to_api_tx,
             validator_to_liveness_tx,
             validator_to_slasher_tx,
@@ -288,6 +344,10 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
 
     #[allow(clippy::too_many_lines)]
     pub async fn run(mut self) -> Result<()> {
+        self.restore_persisted_operations();
+        self.restore_validator_registrations();
+        self.restore_imported_keys().await;
+
         loop {
             let mut slasher_to_validator_rx = self
                 .slasher_to_validator_rx
@@ -296,6 +356,14 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
                 .unwrap_or_else(|| EitherFuture::Right(futures::stream::pending()));
 
             select! {
+                result = &mut self.graceful_shutdown_rx => {
+                    // The sender being dropped without sending means no shutdown was requested.
+                    if result.is_ok() {
+                        self.persist_operations().await;
+                        return Ok(());
+                    }
+                },
+
                 message = self.fork_choice_rx.select_next_some() => match message {
                     ValidatorMessage::Tick(wait_group, tick) => {
                         self.handle_tick(wait_group, tick).await?;
@@ -312,6 +380,9 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
                         self.attest_gossip_block(&wait_group, head).await?;
                     }
                     ValidatorMessage::ValidAttestation(wait_group, attestation) => {
+                        self.published_own_attestations
+                            .mark_in_aggregate(&attestation);
+
                         self.attestation_agg_pool
                             .insert_attestation(wait_group, attestation.clone_arc());
 
@@ -321,38 +392,23 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
                         }
                     },
                     ValidatorMessage::PrepareExecutionPayload(slot, safe_execution_payload_hash, finalized_execution_payload_hash) => {
-                        let slot_head = self.safe_slot_head(slot).await;
-
-                        if let Some(slot_head) = slot_head {
-                            let proposer_index = slot_head.proposer_index()?;
-                            let head_root = slot_head.beacon_block_root;
-                            let head_slot = slot_head.slot();
-
-                            let payload_id = self
-                                .prepare_execution_payload(
-                                    &slot_head.beacon_state,
-                                    safe_execution_payload_hash,
-                                    finalized_execution_payload_hash,
-                                    proposer_index,
-                                )
-                                .await;
-
-                            match payload_id {
-                                Ok(payload_id_option) => {
-                                    match payload_id_option {
-                                        Some(payload_id) => {
-                                            info!(
-                                                "started work on execution payload with id {payload_id:?} \
-                                                 for head {head_root:?} at slot {head_slot}",
-                                            );
-                                            self.payload_id_cache.cache_set((head_root, head_slot), payload_id);
-                                        }
-                                        None => warn!("could not prepare execution payload: payload_id is None"),
-                                    }
-                                }
-                                Err(error) => warn!("error while preparing execution payload: {error:?}"),
+                        // Head changes are reported for every slot.
+                        // Only act on them once the lookahead window before the slot is open.
+                        let window_open = match self.last_tick {
+                            Some(tick) if tick.slot + 1 == slot => {
+                                self.is_in_payload_preparation_window(tick)?
                             }
+                            _ => false,
                         };
+
+                        if window_open {
+                            self.prepare_execution_payload_for_local_proposer(
+                                slot,
+                                safe_execution_payload_hash,
+                                finalized_execution_payload_hash,
+                            )
+                            .await?;
+                        }
                     }
                 },
 
@@ -450,36 +506,16 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
                             sender.send(self.voluntary_exits.clone()).is_ok()
                         }
                         ApiToValidator::SignedValidatorRegistrations(sender, registrations) => {
-                            let (registered_validators, errors): (Vec<_>, Vec<_>) = registrations
-                                .into_iter()
-                                .enumerate()
-                                .map(|(index, registration)| {
-                                    let SignedValidatorRegistrationV1 {
-                                        message,
-                                        signature,
-                                    } = registration;
-
-                                    match signature.try_into() {
-                                        Ok(signature) => Ok((message, signature)),
-                                        Err(error) => Err((index, AnyhowError::new(error))),
-                                    }
-                                })
-                                .partition_result();
+                            let current_epoch =
+                                misc::compute_epoch_at_slot::<P>(self.controller.slot());
 
+                            let errors = self.handle_external_validator_registrations(
+                                registrations,
+                                current_epoch,
+                            );
 
                             if errors.is_empty() {
-                                let current_slot = self.controller.slot();
-                                let current_epoch = misc::compute_epoch_at_slot::<P>(current_slot);
-
-                                let registrations = registered_validators
-                                    .into_iter()
-                                    .map(|registration| (registration.0.pubkey, registration))
-                                    .collect();
-
-                                self.registered_validators
-                                    .entry(current_epoch)
-                                    .and_modify(|map| map.extend(&registrations))
-                                    .or_insert(registrations);
+                                self.persist_validator_registrations();
                             }
 
                             sender.send(errors).is_ok()
@@ -544,6 +580,189 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
         message.send(&self.p2p_tx);
     }
 
+    // Operations are persisted on graceful shutdown and at the start of every slot.
+    // The latter limits the number of operations lost if the process is killed.
+    async fn persist_operations(&self) {
+        let bls_to_execution_changes = match self
+            .bls_to_execution_change_pool
+            .signed_bls_to_execution_changes()
+            .await
+        {
+            Ok(changes) => changes,
+            Err(error) => {
+                warn!("failed to request BLS to execution changes to persist: {error:?}");
+                return;
+            }
+        };
+
+        let operations = PersistedOperations::<P> {
+            proposer_slashings: self.proposer_slashings.clone(),
+            attester_slashings: self.attester_slashings.clone(),
+            voluntary_exits: self.voluntary_exits.clone(),
+            bls_to_execution_changes,
+        };
+
+        if let Err(error) = operations.save(&self.operation_pool_database) {
+            warn!("failed to persist operation pools: {error:?}");
+        }
+    }
+
+    fn restore_persisted_operations(&mut self) {
+        let operations = match PersistedOperations::<P>::load(&self.operation_pool_database) {
+            Ok(operations) => operations,
+            Err(error) => {
+                warn!("failed to load persisted operation pools: {error:?}");
+                return;
+            }
+        };
+
+        if operations.is_empty() {
+            return;
+        }
+
+        let PersistedOperations {
+            proposer_slashings,
+            attester_slashings,
+            voluntary_exits,
+            bls_to_execution_changes,
+        } = operations;
+
+        info!(
+            "restoring persisted operations (proposer slashings: {}, attester slashings: {}, \
+             voluntary exits: {}, BLS to execution changes: {})",
+            proposer_slashings.len(),
+            attester_slashings.len(),
+            voluntary_exits.len(),
+            bls_to_execution_changes.len(),
+        );
+
+        // Restored operations go through the same validation as new ones.
+        // Operations that were included or became invalid while the node was offline are dropped.
+        for slashing in proposer_slashings {
+            if let Err(error) = self.handle_external_proposer_slashing(slashing) {
+                warn!("failed to restore proposer slashing: {error:?}");
+            }
+        }
+
+        for slashing in attester_slashings {
+            if let Err(error) = self.handle_external_attester_slashing(slashing) {
+                warn!("failed to restore attester slashing: {error:?}");
+            }
+        }
+
+        for exit in voluntary_exits {
+            if let Err(error) = self.handle_external_voluntary_exit(Box::new(exit)) {
+                warn!("failed to restore voluntary exit: {error:?}");
+            }
+        }
+
+        // Peers may have lost the changes as well, so they are published again.
+        for change in bls_to_execution_changes {
+            self.bls_to_execution_change_pool
+                .notify_external_signed_bls_to_execution_change(Box::new(change), Origin::Api);
+        }
+    }
+
+    // Registrations from external validator clients are persisted so that they can be
+    // resubmitted to relays right after a restart instead of when the clients next send them.
+    fn persist_validator_registrations(&self) {
+        let registrations = self
+            .registered_validators
+            .iter()
+            .flat_map(|(epoch, registrations)| {
+                registrations
+                    .values()
+                    .map(|(message, signature)| PersistedValidatorRegistration {
+                        epoch: *epoch,
+                        registration: SignedValidatorRegistrationV1 {
+                            message: *message,
+                            signature: (*signature).into(),
+                        },
+                    })
+            })
+            .collect_vec();
+
+        if let Err(error) = persisted_operations::save_validator_registrations(
+            &self.operation_pool_database,
+            &registrations,
+        ) {
+            warn!("failed to persist validator registrations: {error:?}");
+        }
+    }
+
+    // Restored registrations keep the epoch they were received in.
+    // Otherwise every restart would postpone their pruning.
+    fn restore_validator_registrations(&mut self) {
+        let registrations =
+            match persisted_operations::load_validator_registrations(&self.operation_pool_database)
+            {
+                Ok(registrations) => registrations,
+                Err(error) => {
+                    warn!("failed to load persisted validator registrations: {error:?}");
+                    return;
+                }
+            };
+
+        if registrations.is_empty() {
+            return;
+        }
+
+        info!(
+            "restoring persisted validator registrations (count: {})",
+            registrations.len(),
+        );
+
+        let registrations_by_epoch = group_into_btreemap(registrations.into_iter().map(
+            |PersistedValidatorRegistration {
+                 epoch,
+                 registration,
+             }| (epoch, registration),
+        ));
+
+        for (epoch, registrations) in registrations_by_epoch {
+            for (index, error) in self.handle_external_validator_registrations(registrations, epoch)
+            {
+                warn!(
+                    "failed to restore validator registration {index} from epoch {epoch}: \
+                     {error:?}",
+                );
+            }
+        }
+    }
+
+    fn handle_external_validator_registrations(
+        &mut self,
+        registrations: Vec<SignedValidatorRegistrationV1>,
+        epoch: Epoch,
+    ) -> Vec<(usize, AnyhowError)> {
+        let (registered_validators, errors): (Vec<_>, Vec<_>) = registrations
+            .into_iter()
+            .enumerate()
+            .map(|(index, registration)| {
+                let SignedValidatorRegistrationV1 { message, signature } = registration;
+
+                match signature.try_into() {
+                    Ok(signature) => Ok((message, signature)),
+                    Err(error) => Err((index, AnyhowError::new(error))),
+                }
+            })
+            .partition_result();
+
+        if errors.is_empty() {
+            let registrations = registered_validators
+                .into_iter()
+                .map(|registration| (registration.0.pubkey, registration))
+                .collect();
+
+            self.registered_validators
+                .entry(epoch)
+                .and_modify(|map| map.extend(&registrations))
+                .or_insert(registrations);
+        }
+
+        errors
+    }
+
     fn handle_external_voluntary_exit(
         &mut self,
         exit: Box<SignedVoluntaryExit>,
@@ -678,21 +897,37 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
             self.process_validator_votes(current_epoch)?;
             self.discard_old_proposer_slashings(current_epoch);
             self.discard_old_registered_validators(current_epoch);
+            self.persist_validator_registrations();
             self.discard_old_attester_slashings(current_epoch);
             self.discard_old_voluntary_exits();
             self.bls_to_execution_change_pool
                 .discard_old_bls_to_execution_changes();
+            self.bls_to_execution_change_pool
+                .publish_pending_bls_to_execution_changes();
             self.own_sync_committee_subscriptions
                 .discard_old_subscriptions(current_epoch);
+            self.track_withdrawal_sweep_metrics();
         }
 
-        if self.last_registration_epoch.is_none() {
+        // Fee recipients and gas limits can be changed at runtime through the Keymanager API.
+        // Resubmit registrations as soon as that happens rather than waiting for the next epoch.
+        if self.last_registration_epoch.is_none()
+            || self.last_registration_version != self.proposer_configs.registration_version()
+        {
             self.register_validators(current_epoch);
         }
 
         self.attestation_agg_pool.on_tick(tick).await;
         self.track_collection_metrics();
 
+        if tick.is_start_of_slot() {
+            self.persist_operations().await;
+        }
+
+        if self.is_attestation_rebroadcast_due(tick)? {
+            self.rebroadcast_unaggregated_attestations(&wait_group);
+        }
+
         let slot_head = if no_validators {
             None
         } else {
@@ -711,6 +946,14 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
         self.attestation_agg_pool
             .compute_proposer_indices(slot_head.beacon_state.clone_arc());
 
+        if tick.is_start_of_slot() {
+            self.activate_imported_keys(&slot_head).await;
+        }
+
+        if tick.is_start_of_epoch::<P>() {
+            self.log_duty_summary(&slot_head).await;
+        }
+
         if let Some(state) = slot_head.beacon_state.post_altair() {
             if misc::is_epoch_start::<P>(state.slot() + 1) {
                 self.own_sync_committee_members.take();
@@ -775,11 +1018,125 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
             _ => {}
         }
 
+        if self.payload_preparation_window_opens_at(tick)? {
+            let snapshot = self.controller.snapshot();
+
+            self.prepare_execution_payload_for_local_proposer(
+                slot + 1,
+                snapshot.safe_execution_payload_hash(),
+                snapshot.finalized_execution_payload_hash(),
+            )
+            .await?;
+        }
+
         self.last_tick = Some(tick);
 
         Ok(())
     }
 
+    fn is_in_payload_preparation_window(&self, tick: Tick) -> Result<bool> {
+        let slot_duration = Duration::from_secs(self.chain_config.seconds_per_slot.get());
+        let lookahead = self
+            .validator_config
+            .prepare_payload_lookahead
+            .min(slot_duration);
+
+        Ok(tick.duration_until_next_slot(&self.chain_config)? <= lookahead)
+    }
+
+    fn payload_preparation_window_opens_at(&self, tick: Tick) -> Result<bool> {
+        if !self.is_in_payload_preparation_window(tick)? {
+            return Ok(false);
+        }
+
+        match self.last_tick {
+            Some(last_tick) if last_tick.slot == tick.slot => {
+                Ok(!self.is_in_payload_preparation_window(last_tick)?)
+            }
+            _ => Ok(true),
+        }
+    }
+
+    fn is_attestation_rebroadcast_due(&self, tick: Tick) -> Result<bool> {
+        let Some(delay) = self.validator_config.attestation_rebroadcast_delay else {
+            return Ok(false);
+        };
+
+        published_attestations::rebroadcast_due(&self.chain_config, delay, tick, self.last_tick)
+    }
+
+    async fn is_local_proposer(
+        &self,
+        state: &BeaconState<P>,
+        proposer_index: ValidatorIndex,
+    ) -> Result<bool> {
+        if self.prepared_proposers.contains_key(&proposer_index) {
+            return Ok(true);
+        }
+
+        let public_key = accessors::public_key(state, proposer_index)?;
+
+        Ok(self.signer.read().await.has_key(public_key.to_bytes()))
+    }
+
+    // Sends `engine_forkchoiceUpdated` with payload attributes so that the execution client
+    // can start building a payload for `slot` on top of the current head.
+    async fn prepare_execution_payload_for_local_proposer(
+        &mut self,
+        slot: Slot,
+        safe_execution_payload_hash: ExecutionBlockHash,
+        finalized_execution_payload_hash: ExecutionBlockHash,
+    ) -> Result<()> {
+        let Some(slot_head) = self.safe_slot_head(slot).await else {
+            return Ok(());
+        };
+
+        let head_root = slot_head.beacon_block_root;
+        let head_slot = slot_head.slot();
+
+        if self
+            .payload_id_cache
+            .cache_get(&(head_root, head_slot))
+            .is_some()
+        {
+            return Ok(());
+        }
+
+        let proposer_index = self.controller.proposer_index(&slot_head.beacon_state)?;
+
+        if !self
+            .is_local_proposer(&slot_head.beacon_state, proposer_index)
+            .await?
+        {
+            return Ok(());
+        }
+
+        let payload_id = self
+            .prepare_execution_payload(
+                &slot_head.beacon_state,
+                safe_execution_payload_hash,
+                finalized_execution_payload_hash,
+                proposer_index,
+            )
+            .await;
+
+        match payload_id {
+            Ok(Some(payload_id)) => {
+                info!(
+                    "started work on execution payload with id {payload_id:?} \
+                     for head {head_root:?} at slot {head_slot}",
+                );
+
+                self.payload_id_cache
+                    .cache_set((head_root, head_slot), payload_id);
+            }
+            Ok(None) => warn!("could not prepare execution payload: payload_id is None"),
+            Err(error) => warn!("error while preparing execution payload: {error:?}"),
+        }
+
+        Ok(())
+    }
+
     async fn safe_slot_head(&self, slot: Slot) -> Option<SlotHead<P>> {
         self.slot_head(slot)
             .await
@@ -864,6 +1221,13 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
             .get_execution_payload(payload_id)
             .await?;
 
+        self.check_fee_recipient(
+            state,
+            proposer_index,
+            "local",
+            payload.value.fee_recipient(),
+        )?;
+
         let payload_root = payload.value.hash_tree_root();
 
         self.payload_cache.cache_set(payload_root, payload.clone());
@@ -884,15 +1248,32 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
             .as_ref()
             .map(|metrics| metrics.local_execution_payload_times.start_timer());
 
-        self.local_execution_payload_result(
-            &slot_head.beacon_state,
-            slot_head.beacon_block_root,
-            proposer_index,
-        )
-        .await
-        .map_err(|error| warn!("execution engine failed to produce payload: {error:?}"))
-        .ok()
-        .flatten()
+        let started_at = Instant::now();
+
+        let result = self
+            .local_execution_payload_result(
+                &slot_head.beacon_state,
+                slot_head.beacon_block_root,
+                proposer_index,
+            )
+            .await;
+
+        self.update_proposal_report(|report| {
+            report.execution_payload_ms = Some(proposal_reports::millis(started_at.elapsed()));
+
+            match &result {
+                Ok(Some(_)) => {}
+                Ok(None) => {
+                    report.execution_payload_error = Some("no payload ID".to_owned());
+                }
+                Err(error) => report.execution_payload_error = Some(format!("{error:#}")),
+            }
+        });
+
+        result
+            .map_err(|error| warn!("execution engine failed to produce payload: {error:?}"))
+            .ok()
+            .flatten()
     }
 
     fn blinded_block_from_beacon_block(
@@ -974,15 +1355,57 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
                         let blob_kzg_commitments = response.blob_kzg_commitments().cloned();
                         let mev = response.mev();
 
+                        let builder_api = self.builder_api.as_ref().expect(
+                            "Builder API should be present as it was used to query \
+                             ExecutionPayloadHeader",
+                        );
+
+                        if let Err(error) = builder_api.can_use_builder_bid(beacon_block.mev, mev) {
+                            // There is no local payload to fall back to.
+                            if matches!(error, BuilderApiError::ZeroBoostFactorWithoutLocalPayload)
+                            {
+                                return Err(error.into());
+                            }
+
+                            info!("using local execution payload instead of builder bid: {error}");
+
+                            self.update_proposal_report(|report| {
+                                report.builder_response = Some(format!("bid not used: {error}"));
+                            });
+
+                            return Ok(Some(beacon_block.map(ValidatorBlindedBlock::BeaconBlock)));
+                        }
+
+                        let payload_header = response.execution_payload_header();
+
+                        if let Err(error) = self.check_fee_recipient(
+                            &slot_head.beacon_state,
+                            proposer_index,
+                            "builder",
+                            payload_header.fee_recipient(),
+                        ) {
+                            info!("using local execution payload instead of builder bid: {error}");
+
+                            self.update_proposal_report(|report| {
+                                report.builder_response = Some(format!("bid not used: {error}"));
+                            });
+
+                            return Ok(Some(beacon_block.map(ValidatorBlindedBlock::BeaconBlock)));
+                        }
+
                         if let Some(blinded_block) = self.blinded_block_from_beacon_block(
                             slot_head,
                             beacon_block.value.clone(),
-                            response.execution_payload_header(),
+                            payload_header,
                             blob_kzg_commitments,
                             skip_randao_verification,
                         ) {
                             let block = ValidatorBlindedBlock::BlindedBeaconBlock(blinded_block);
 
+                            self.update_proposal_report(|report| {
+                                report.builder_response = Some("bid used".to_owned());
+                            });
+
                             return Ok(Some(WithBlobsAndMev::new(
                                 block,
                                 None,
@@ -992,9 +1415,17 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
                             )));
                         }
                     }
-                    Ok(None) => {}
+                    Ok(None) => {
+                        self.update_proposal_report(|report| {
+                            report.builder_response = Some("no bid".to_owned());
+                        });
+                    }
                     Err(error) => {
                         warn!("failed to get execution payload header: {error}");
+
+                        self.update_proposal_report(|report| {
+                            report.builder_response = Some(format!("failed: {error}"));
+                        });
                     }
                 };
             }
@@ -1017,7 +1448,10 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
             .as_ref()
             .map(|metrics| metrics.build_beacon_block_times.start_timer());
 
-        let proposer_index = proposer_index.map_or_else(|| slot_head.proposer_index(), Ok)?;
+        let proposer_index = proposer_index.map_or_else(
+            || self.controller.proposer_index(&slot_head.beacon_state),
+            Ok,
+        )?;
 
         // TODO(Grandine Team): Move this to a separate task so it prepares the execution payload
         //                      before it is time to propose a block.
@@ -1238,6 +1672,15 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
             return sender.send(Ok(None)).is_ok();
         };
 
+        // The head may have changed since the request was checked.
+        if slot_head.optimistic {
+            warn!(
+                "validator cannot produce a block because \
+                 chain head has not been fully verified by an execution engine",
+            );
+            return sender.send(Ok(None)).is_ok();
+        }
+
         let result = self
             .build_beacon_block(
                 &slot_head,
@@ -1263,8 +1706,16 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
             return sender.send(Ok(None)).is_ok();
         };
 
-        let Ok(proposer_index) = slot_head.proposer_index() else {
-            // Slot_head::proposer index can only fail if head state has no active validators.
+        if slot_head.optimistic {
+            warn!(
+                "validator cannot produce a blinded block because \
+                 chain head has not been fully verified by an execution engine",
+            );
+            return sender.send(Ok(None)).is_ok();
+        }
+
+        let Ok(proposer_index) = self.controller.proposer_index(&slot_head.beacon_state) else {
+            // Controller::proposer_index can only fail if head state has no active validators.
             warn!("failed to produce blinded beacon block: head state has no active validators");
             return sender.send(Ok(None)).is_ok();
         };
@@ -1288,7 +1739,6 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
     }
 
     /// <https://github.com/ethereum/consensus-specs/blob/b2f42bf4d79432ee21e2f2b3912ff4bbf7898ada/specs/phase0/validator.md#block-proposal>
-    #[allow(clippy::too_many_lines)]
     async fn propose(&mut self, wait_group: W, slot_head: &SlotHead<P>) -> Result<()> {
         if slot_head.slot() == GENESIS_SLOT {
             // All peers should already have the genesis block.
@@ -1296,19 +1746,97 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
             return Ok(());
         }
 
+        let proposer_index = tokio::task::block_in_place(|| {
+            self.controller.proposer_index(&slot_head.beacon_state)
+        })?;
+        let public_key = slot_head.public_key(proposer_index);
+
+        if !self.signer.read().await.has_key(public_key.to_bytes()) {
+            return Ok(());
+        }
+
+        let epoch = slot_head.current_epoch();
+
+        self.duty_summary
+            .counts_mut(epoch, proposer_index)
+            .proposals_attempted += 1;
+
+        let slot_timestamp = misc::compute_timestamp_at_slot(
+            &self.chain_config,
+            &slot_head.beacon_state,
+            slot_head.slot(),
+        );
+
+        let started_at = Instant::now();
+
+        self.proposal_report = Some(ProposalReport {
+            slot: slot_head.slot(),
+            validator_index: proposer_index,
+            pubkey: public_key.to_bytes(),
+            head_block_root: slot_head.beacon_block_root,
+            head_slot: slot_head.beacon_state.latest_block_header().slot,
+            head_optimistic: slot_head.optimistic,
+            start_delay_ms: proposal_reports::millis_since(slot_timestamp),
+            ..ProposalReport::default()
+        });
+
+        let result = self
+            .try_propose(wait_group, slot_head, proposer_index, public_key)
+            .await;
+
+        let report = self.proposal_report.take();
+
+        let (reason, result) = match result {
+            Ok(None) => return Ok(()),
+            Ok(Some(reason)) => (reason, Ok(())),
+            Err(error) => (format!("{error:#}"), Err(error)),
+        };
+
+        if let Some(mut report) = report {
+            report.reason = reason;
+            report.total_ms = Some(proposal_reports::millis(started_at.elapsed()));
+
+            warn!(
+                "validator {proposer_index} missed proposal in slot {}: {} (report: {report:?})",
+                report.slot, report.reason,
+            );
+
+            self.proposal_reports.push(report);
+        }
+
+        result
+    }
+
+    /// Returns the reason the block was not published if it was not.
+    #[allow(clippy::too_many_lines)]
+    async fn try_propose(
+        &mut self,
+        wait_group: W,
+        slot_head: &SlotHead<P>,
+        proposer_index: ValidatorIndex,
+        public_key: &CachedPublicKey,
+    ) -> Result<Option<String>> {
+        let epoch = slot_head.current_epoch();
+
         if slot_head.optimistic {
             warn!(
                 "validator cannot produce a block because \
                  chain head has not been fully verified by an execution engine",
             );
-            return Ok(());
+            return Ok(Some("chain head is optimistic".to_owned()));
         }
 
-        let proposer_index = tokio::task::block_in_place(|| slot_head.proposer_index())?;
-        let public_key = slot_head.public_key(proposer_index);
-
-        if !self.signer.read().await.has_key(public_key.to_bytes()) {
-            return Ok(());
+        if proposals_refused_due_to_clock_skew(
+            &self.validator_config,
+            &self.clock_drift,
+            &self.chain_config,
+        ) {
+            error!(
+                "validator {proposer_index} cannot produce a block at slot {} \
+                 because the local clock differs too much from the network",
+                slot_head.slot(),
+            );
+            return Ok(Some("local clock is grossly skewed".to_owned()));
         }
 
         let _propose_timer = self
@@ -1319,7 +1847,7 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
         let execution_payload_header_handle =
             self.get_execution_payload_header(slot_head, public_key.to_bytes());
 
-        let epoch = slot_head.current_epoch();
+        let randao_signing_started_at = Instant::now();
 
         let result = self
             .signer
@@ -1327,12 +1855,20 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
             .await
             .sign(
                 SigningMessage::RandaoReveal { epoch },
-                RandaoEpoch::from(epoch).signing_root(&self.chain_config, &slot_head.beacon_state),
-                Some(slot_head.beacon_state.as_ref().into()),
+                slot_head
+                    .signing_domains()
+                    .signing_root(&RandaoEpoch::from(epoch)),
+                Some(slot_head.fork_info()),
                 public_key.to_bytes(),
             )
             .await;
 
+        self.update_proposal_report(|report| {
+            report.randao_signing_ms = Some(proposal_reports::millis(
+                randao_signing_started_at.elapsed(),
+            ));
+        });
+
         let randao_reveal = match result {
             Ok(signature) => signature.into(),
             Err(error) => {
@@ -1342,7 +1878,7 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
                     public_key.to_bytes(),
                     error,
                 );
-                return Ok(());
+                return Ok(Some(format!("failed to sign RANDAO reveal: {error:#}")));
             }
         };
 
@@ -1374,18 +1910,44 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
                 proposer_index,
                 slot_head.slot(),
             );
-            return Ok(());
+            return Ok(Some("no beacon block could be built".to_owned()));
         };
 
+        // Check before signing because signed blinded blocks are published by builders.
+        if let Some(block_root) = equivocation_checks::conflicting_block_root(
+            &self.validator_config,
+            &self.controller,
+            slot_head.slot(),
+            proposer_index,
+        ) {
+            warn!(
+                "validator {} not proposing beacon block in slot {} because another block \
+                 from it has already been seen (block root: {block_root:?}); \
+                 the same validator key may be in use elsewhere",
+                proposer_index,
+                slot_head.slot(),
+            );
+            return Ok(Some(format!(
+                "another block from the same proposer has been seen (block root: {block_root:?})",
+            )));
+        }
+
+        let block_signing_started_at = Instant::now();
+
         let beacon_block = match validator_blinded_block {
             ValidatorBlindedBlock::BlindedBeaconBlock(message) => {
                 let Some(signature) = slot_head
                     .sign_beacon_block(&self.signer, &message, (&message).into(), public_key)
                     .await
                 else {
-                    return Ok(());
+                    return Ok(Some("failed to sign blinded beacon block".to_owned()));
                 };
 
+                self.update_proposal_report(|report| {
+                    report.block_signing_ms =
+                        Some(proposal_reports::millis(block_signing_started_at.elapsed()));
+                });
+
                 let signed_blinded_block = message.with_signature(signature);
 
                 let builder_api = self.builder_api.as_ref().expect(
@@ -1408,7 +1970,9 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
                     Ok(response) => response,
                     Err(error) => {
                         warn!("failed to post blinded block to the builder node: {error:?}");
-                        return Ok(());
+                        return Ok(Some(format!(
+                            "failed to post blinded block to the builder node: {error:#}",
+                        )));
                     }
                 };
 
@@ -1424,13 +1988,19 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
                     .with_signature(signature)
             }
             ValidatorBlindedBlock::BeaconBlock(block) => {
-                match slot_head
+                let Some(signature) = slot_head
                     .sign_beacon_block(&self.signer, &block, (&block).into(), public_key)
                     .await
-                {
-                    Some(signature) => block.with_signature(signature),
-                    None => return Ok(()),
-                }
+                else {
+                    return Ok(Some("failed to sign beacon block".to_owned()));
+                };
+
+                self.update_proposal_report(|report| {
+                    report.block_signing_ms =
+                        Some(proposal_reports::millis(block_signing_started_at.elapsed()));
+                });
+
+                block.with_signature(signature)
             }
         };
 
@@ -1446,7 +2016,7 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
             .await?;
 
         if control_flow.is_break() {
-            return Ok(());
+            return Ok(Some("block rejected by slashing protection".to_owned()));
         }
 
         info!(
@@ -1460,29 +2030,62 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
 
         let block = Arc::new(beacon_block.clone());
 
-        for blob_sidecar in misc::construct_blob_sidecars(
+        // Construct sidecars before publishing anything.
+        // If construction failed after the block was published, peers would be left with a block
+        // whose blobs never arrive.
+        let blob_sidecars = misc::construct_blob_sidecars(
             &block,
             block_blobs.unwrap_or_default().into_iter(),
             block_proofs.unwrap_or_default().into_iter(),
-        )? {
-            let blob_sidecar = Arc::new(blob_sidecar);
+        )?
+        .into_iter()
+        .map(Arc::new)
+        .collect_vec();
 
-            self.controller
-                .on_own_blob_sidecar(wait_group.clone(), blob_sidecar.clone_arc());
+        ValidatorToP2p::PublishBeaconBlock(block.clone_arc()).send(&self.p2p_tx);
 
-            ValidatorToP2p::PublishBlobSidecar(blob_sidecar).send(&self.p2p_tx);
-        }
+        // Pass sidecars to the controller first so that the block does not wait for them.
+        self.controller
+            .on_own_blob_sidecars(&wait_group, blob_sidecars.iter().cloned());
 
         self.controller
             .on_own_block(wait_group.clone(), block.clone_arc());
 
-        ValidatorToP2p::PublishBeaconBlock(block).send(&self.p2p_tx);
+        // TODO(feature/peerdas): Publish data column sidecars with KZG cell proofs computed on the
+        //                        dedicated executor once `types` has PeerDAS containers,
+        //                        `eth2_libp2p` has data column topics and `kzg_utils` can compute
+        //                        cell proofs.
+        if !blob_sidecars.is_empty() {
+            debug!(
+                "publishing {} blob sidecars of beacon block in slot {}",
+                blob_sidecars.len(),
+                slot_head.slot(),
+            );
+        }
+
+        for blob_sidecar in blob_sidecars {
+            ValidatorToP2p::PublishBlobSidecar(blob_sidecar).send(&self.p2p_tx);
+
+            if let Some(metrics) = self.metrics.as_ref() {
+                metrics.validator_published_blob_sidecars.inc();
+            }
+        }
 
         if let Some(metrics) = self.metrics.as_ref() {
             metrics.validator_propose_successes.inc();
         }
 
-        Ok(())
+        self.duty_summary
+            .counts_mut(epoch, proposer_index)
+            .proposals_published += 1;
+
+        Ok(None)
+    }
+
+    fn update_proposal_report(&mut self, update: impl FnOnce(&mut ProposalReport)) {
+        if let Some(report) = self.proposal_report.as_mut() {
+            update(report);
+        }
     }
 
     /// See:
@@ -1541,19 +2144,23 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
 
             let mut protector = self.slashing_protector.lock().await;
 
-            protector.validate_and_store_own_attestations(
-                &self.chain_config,
-                &slot_head.beacon_state,
-                own_singular_attestations.iter().map(|own_attestation| {
-                    let OwnAttestation {
-                        validator_index, ..
-                    } = own_attestation;
+            protector
+                .validate_and_store_own_attestations(
+                    &self.chain_config,
+                    &slot_head.beacon_state,
+                    own_singular_attestations.iter().map(|own_attestation| {
+                        let OwnAttestation {
+                            validator_index, ..
+                        } = own_attestation;
 
-                    let public_key = slot_head.public_key(*validator_index).to_bytes();
+                        let public_key = slot_head.public_key(*validator_index).to_bytes();
 
-                    (own_attestation, public_key)
-                }),
-            )?
+                        (own_attestation, public_key)
+                    }),
+                )?
+                .into_iter()
+                .cloned()
+                .collect_vec()
         };
 
         for own_attestation in &accepted_attestations {
@@ -1590,13 +2197,21 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
                 .send(&self.p2p_tx);
 
             self.attestation_agg_pool
-                .insert_attestation(wait_group.clone(), attestation);
+                .insert_attestation(wait_group.clone(), attestation.clone_arc());
+
+            self.duty_summary
+                .counts_mut(attestation.data.target.epoch, *validator_index)
+                .attestations_published += 1;
+
+            self.published_own_attestations
+                .insert(*validator_index, attestation, subnet_id);
         }
 
         prometheus_metrics::stop_and_record(timer);
 
-        let committee_indices_with_pubkeys = accepted_attestations.iter().map(|own_attestation| {
+        let indices_with_pubkeys = accepted_attestations.iter().map(|own_attestation| {
             (
+                own_attestation.validator_index,
                 own_attestation.attestation.data.index,
                 slot_head
                     .public_key(own_attestation.validator_index)
@@ -1605,7 +2220,11 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
         });
 
         let selection_proofs = match slot_head
-            .selection_proofs(committee_indices_with_pubkeys, &self.signer)
+            .selection_proofs(
+                indices_with_pubkeys,
+                &self.signer,
+                self.distributed_middleware.as_deref(),
+            )
             .await
         {
             Ok(signature) => signature,
@@ -1621,7 +2240,7 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
 
         self.own_aggregators = selection_proofs
             .into_iter()
-            .zip(accepted_attestations.into_iter().cloned())
+            .zip(accepted_attestations)
             .filter_map(|(selection_proof, own_attestation)| {
                 let public_key = slot_head.public_key(own_attestation.validator_index);
 
@@ -1644,7 +2263,7 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
     }
 
     async fn publish_aggregates_and_proofs(&mut self, wait_group: &W, slot_head: &SlotHead<P>) {
-        let config = &self.chain_config;
+        let signing_domains = slot_head.signing_domains();
 
         let (triples, proofs): (Vec<_>, Vec<_>) = self
             .own_aggregators
@@ -1677,8 +2296,7 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
                                 message: SigningMessage::AggregateAndProof(Box::new(
                                     aggregate_and_proof.clone(),
                                 )),
-                                signing_root: aggregate_and_proof
-                                    .signing_root(config, &slot_head.beacon_state),
+                                signing_root: signing_domains.signing_root(&aggregate_and_proof),
                                 public_key,
                             };
 
@@ -1698,7 +2316,7 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
             .signer
             .read()
             .await
-            .sign_triples(triples, Some(slot_head.beacon_state.as_ref().into()))
+            .sign_triples(triples, Some(slot_head.fork_info()))
             .await;
 
         let signatures = match sign_result {
@@ -1738,8 +2356,19 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
 
         for aggregate_and_proof in aggregates_and_proofs {
             let attestation = Arc::new(aggregate_and_proof.message.aggregate.clone());
+
+            self.duty_summary
+                .counts_mut(
+                    attestation.data.target.epoch,
+                    aggregate_and_proof.message.aggregator_index,
+                )
+                .aggregates_published += 1;
+
             let aggregate_and_proof = Box::new(aggregate_and_proof);
 
+            self.published_own_attestations
+                .mark_in_aggregate(&attestation);
+
             self.attestation_agg_pool
                 .insert_attestation(wait_group.clone(), attestation);
 
@@ -1775,6 +2404,19 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
 
         let own_messages = self.own_sync_committee_messages(slot_head).await?;
 
+        // Members of multiple subcommittees publish the same message in each of their subnets.
+        let publishing_validators = own_messages
+            .values()
+            .flatten()
+            .map(|message| message.validator_index)
+            .collect::<HashSet<_>>();
+
+        for validator_index in publishing_validators {
+            self.duty_summary
+                .counts_mut(slot_head.current_epoch(), validator_index)
+                .sync_committee_messages_published += 1;
+        }
+
         for (sync_subnet_id, messages) in own_messages {
             for sync_committee_message in &messages {
                 debug!(
@@ -1848,7 +2490,9 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
     ) -> Result<ControlFlow<()>> {
         let proposal = BlockProposal {
             slot: block.message().slot(),
-            signing_root: Some(block.message().signing_root(&self.chain_config, state)),
+            signing_root: Some(
+                SigningDomains::for_state(&self.chain_config, state).signing_root(block.message()),
+            ),
         };
 
         debug!("validating beacon block proposal: {block:?}");
@@ -1953,6 +2597,7 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
         }
 
         let own_public_keys = self.own_public_keys().await;
+        let signing_domains = slot_head.signing_domains();
 
         let (triples, other_data) = tokio::task::block_in_place(|| {
             let target = Checkpoint {
@@ -1966,16 +2611,20 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
             let (triples, other_data): (Vec<_>, Vec<_>) = slot_head
                 .beacon_committees(slot_head.slot())?
                 .map(|(committee_index, committee)| {
-                    let members = committee.into_iter().enumerate().filter_map(
-                        |(member_position, validator_index)| {
+                    let members = committee
+                        .into_iter()
+                        .enumerate()
+                        .filter_map(|(member_position, validator_index)| {
                             let public_key = slot_head.public_key(validator_index);
                             own_public_keys.contains(&public_key.to_bytes()).then_some((
                                 member_position,
                                 validator_index,
                                 public_key,
                             ))
-                        },
-                    );
+                        })
+                        .filter(|(_, validator_index, _)| {
+                            !self.attestation_seen_on_gossip(target.epoch, *validator_index)
+                        });
 
                     (committee_index, committee.len(), members)
                 })
@@ -1992,8 +2641,7 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
                         move |(member_position, validator_index, public_key)| {
                             let triple = SigningTriple {
                                 message: SigningMessage::<P>::Attestation(data),
-                                signing_root: data
-                                    .signing_root(&self.chain_config, &slot_head.beacon_state),
+                                signing_root: signing_domains.signing_root(&data),
                                 public_key: public_key.to_bytes(),
                             };
 
@@ -2010,7 +2658,7 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
             .signer
             .read()
             .await
-            .sign_triples(triples, Some(slot_head.beacon_state.as_ref().into()))
+            .sign_triples(triples, Some(slot_head.fork_info()))
             .await;
 
         let signatures = match result {
@@ -2083,7 +2731,7 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
                         return None;
                     }
 
-                    let validator_index = accessors::index_of_public_key(state, public_key)?;
+                    let validator_index = self.controller.validator_index(state, public_key)?;
                     Some((validator_index, public_key))
                 })
                 .sorted_by_key(|(validator_index, _)| *validator_index)
@@ -2140,6 +2788,7 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
         slot_head: &SlotHead<P>,
     ) -> Result<Vec<SignedContributionAndProof<P>>> {
         let subcommittee_aggregators = self.own_subcommittee_aggregators(slot_head).await?;
+        let signing_domains = slot_head.signing_domains();
 
         // TODO(Grandine Team): Parallelize.
         //                      This used `into_par_iter` before, however, `build_sync_committee_contribution`
@@ -2170,8 +2819,7 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
 
                         let triple = SigningTriple {
                             message: SigningMessage::ContributionAndProof(contribution_and_proof),
-                            signing_root: contribution_and_proof
-                                .signing_root(&self.chain_config, &slot_head.beacon_state),
+                            signing_root: signing_domains.signing_root(&contribution_and_proof),
                             public_key: aggregator.public_key,
                         };
 
@@ -2186,7 +2834,7 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
             .signer
             .read()
             .await
-            .sign_triples(triples, Some(slot_head.beacon_state.as_ref().into()))
+            .sign_triples(triples, Some(slot_head.fork_info()))
             .await;
 
         let signatures = match result {
@@ -2223,13 +2871,24 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
             })
             .collect_vec();
 
-        let indices_with_pubkeys = subcommittee_members
-            .iter()
-            .copied()
-            .map(|(subcommittee_index, member)| (subcommittee_index, member.public_key));
+        let indices_with_pubkeys =
+            subcommittee_members
+                .iter()
+                .copied()
+                .map(|(subcommittee_index, member)| {
+                    (
+                        member.validator_index,
+                        subcommittee_index,
+                        member.public_key,
+                    )
+                });
 
         let proofs = match slot_head
-            .sync_committee_selection_proofs(indices_with_pubkeys, &self.signer)
+            .sync_committee_selection_proofs(
+                indices_with_pubkeys,
+                &self.signer,
+                self.distributed_middleware.as_deref(),
+            )
             .await
         {
             Ok(proofs) => proofs,
@@ -2253,11 +2912,40 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
             .pipe(group_into_btreemap))
     }
 
+    // Slashing protection only knows about attestations signed by this node.
+    // An attestation from the same key for the same target epoch seen on gossip means the key is
+    // in use elsewhere, so signing another one is likely to be slashable.
+    // Only attestations that passed validation are recorded, so forged ones cannot prevent signing.
+    fn attestation_seen_on_gossip(
+        &self,
+        target_epoch: Epoch,
+        validator_index: ValidatorIndex,
+    ) -> bool {
+        let seen = equivocation_checks::attestation_seen(
+            &self.validator_config,
+            &self.controller,
+            target_epoch,
+            validator_index,
+        );
+
+        if seen {
+            warn!(
+                "validator {validator_index} not attesting in epoch {target_epoch} because \
+                 another attestation from it has already been seen; \
+                 the same validator key may be in use elsewhere",
+            );
+        }
+
+        seen
+    }
+
     fn attested_in_current_slot(&self) -> bool {
         self.own_singular_attestations.get().is_some()
     }
 
     fn discard_previous_slot_attestations(&mut self) {
+        self.published_own_attestations.clear();
+
         if let Some(own_attestations) = self.own_singular_attestations.take() {
             for own_attestation in own_attestations {
                 let AttestationData {
@@ -2280,6 +2968,125 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
         }
     }
 
+    async fn log_duty_summary(&mut self, slot_head: &SlotHead<P>) {
+        let state = slot_head.beacon_state.as_ref();
+
+        let own_validator_indices = self
+            .own_public_keys()
+            .await
+            .into_iter()
+            .filter_map(|public_key| self.controller.validator_index(state, public_key))
+            .sorted();
+
+        if let Err(error) = self
+            .duty_summary
+            .log_previous_epoch(state, own_validator_indices)
+        {
+            warn!("failed to summarize validator duties: {error:?}");
+        }
+    }
+
+    // Keys still pending when the node stopped are loaded as active by the signer.
+    // They stay pending until the liveness check started before the restart completes.
+    async fn restore_imported_keys(&mut self) {
+        let imported_keys = match ImportedKeys::load(&self.operation_pool_database) {
+            Ok(imported_keys) => imported_keys,
+            Err(error) => {
+                warn!("failed to load persisted imported keys: {error:?}");
+                return;
+            }
+        };
+
+        if imported_keys.is_empty() {
+            return;
+        }
+
+        self.signer
+            .write()
+            .await
+            .deactivate_keys(imported_keys.public_keys());
+
+        self.imported_keys = imported_keys;
+    }
+
+    async fn activate_imported_keys(&mut self, slot_head: &SlotHead<P>) {
+        let pending_keys = self
+            .signer
+            .read()
+            .await
+            .pending_keys()
+            .copied()
+            .collect_vec();
+
+        if pending_keys.is_empty() && self.imported_keys.is_empty() {
+            return;
+        }
+
+        let activated_keys = self.imported_keys.update(
+            slot_head.beacon_state.as_ref(),
+            pending_keys,
+            |public_key| {
+                self.controller
+                    .validator_index(slot_head.beacon_state.as_ref(), public_key)
+            },
+        );
+
+        if let Err(error) = self.imported_keys.save(&self.operation_pool_database) {
+            warn!("failed to persist imported keys: {error:?}");
+        }
+
+        if activated_keys.is_empty() {
+            return;
+        }
+
+        info!(
+            "validators imported through the Keymanager API start performing duties: [{}]",
+            activated_keys.iter().format(", "),
+        );
+
+        self.signer
+            .write()
+            .await
+            .activate_pending_keys(activated_keys);
+    }
+
+    // Validators in subnets with few well-connected peers often have their attestations left out
+    // of aggregates. Publishing them again gives aggregators another chance to pick them up.
+    // Only attestations that already passed slashing protection are published again.
+    fn rebroadcast_unaggregated_attestations(&self, wait_group: &W) {
+        let unaggregated = self.published_own_attestations.unaggregated().collect_vec();
+
+        let Some(first) = unaggregated.first() else {
+            return;
+        };
+
+        info!(
+            "attestations of validators [{}] in slot {} not seen in any aggregate; publishing again",
+            unaggregated
+                .iter()
+                .map(|published| published.validator_index)
+                .format(", "),
+            first.attestation.data.slot,
+        );
+
+        for published in unaggregated {
+            if let Some(metrics) = self.metrics.as_ref() {
+                metrics.validator_attestation_rebroadcasts.inc();
+            }
+
+            ValidatorToP2p::PublishSingularAttestation(
+                published.attestation.clone_arc(),
+                published.subnet_id,
+            )
+            .send(&self.p2p_tx);
+
+            // The pool may have dropped the attestation in favor of better aggregates.
+            // Inserting it again makes it available to aggregators using this node.
+            self.attestation_agg_pool
+                .insert_attestation(wait_group.clone(), published.attestation.clone_arc());
+        }
+    }
+
     fn discard_old_attester_slashings(&mut self, current_epoch: Epoch) {
         let finalized_state = self.controller.last_finalized_state().value;
 
@@ -2487,9 +3294,29 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
         epoch: Epoch,
         beacon_state: &BeaconState<P>,
     ) {
+        let attester_duties = tokio::task::block_in_place(|| {
+            self.duties_cache.attester_duties(&self.controller, epoch)
+        });
+
+        let attester_duties = match attester_duties {
+            Ok(attester_duties) => attester_duties.value,
+            Err(error) => {
+                warn!("failed to compute attester duties for epoch {epoch}: {error:?}");
+                return;
+            }
+        };
+
         let subscriptions = match self
             .own_beacon_committee_subscriptions
-            .compute_for_epoch(&self.chain_config, epoch, beacon_state, &self.signer)
+            .compute_for_epoch(
+                &self.chain_config,
+                &self.controller,
+                epoch,
+                beacon_state,
+                &attester_duties,
+                &self.signer,
+                self.distributed_middleware.as_deref(),
+            )
             .await
         {
             Ok(subscriptions) => subscriptions,
@@ -2517,8 +3344,11 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
         if let Some(post_altair_state) = beacon_state.post_altair() {
             let own_public_keys = self.own_public_keys().await;
 
-            self.own_sync_committee_subscriptions
-                .build(post_altair_state, &own_public_keys);
+            self.own_sync_committee_subscriptions.build(
+                &self.controller,
+                post_altair_state,
+                &own_public_keys,
+            );
 
             let current_epoch = accessors::get_current_epoch(beacon_state);
 
@@ -2767,12 +3597,20 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
 
         let prev_randao = accessors::get_randao_mix(state, epoch);
 
+        let gas_limit = if self.validator_config.payload_attributes_gas_limit {
+            let proposer_pubkey = accessors::public_key(state, proposer_index)?.to_bytes();
+            Some(self.proposer_configs.gas_limit(proposer_pubkey)?)
+        } else {
+            None
+        };
+
         let payload_attributes = match state {
             BeaconState::Phase0(_) | BeaconState::Altair(_) => return Ok(None),
             BeaconState::Bellatrix(_) => PayloadAttributesV1 {
                 timestamp,
                 prev_randao,
                 suggested_fee_recipient,
+                gas_limit,
             }
             .into(),
             BeaconState::Capella(state) => {
@@ -2786,6 +3624,7 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
                     prev_randao,
                     suggested_fee_recipient,
                     withdrawals,
+                    gas_limit,
                 }
                 .into()
             }
@@ -2804,6 +3643,7 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
                     suggested_fee_recipient,
                     withdrawals,
                     parent_beacon_block_root,
+                    gas_limit,
                 }
                 .into()
             }
@@ -2937,6 +3777,13 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
             sync_committee_signature.aggregate_in_place(contribution.signature.try_into()?);
         }
 
+        if let Some(metrics) = self.metrics.as_ref() {
+            metrics.observe_produced_sync_aggregate_participation(
+                sync_committee_bits.count_ones(),
+                P::SyncCommitteeSize::USIZE,
+            );
+        }
+
         Ok(SyncAggregate {
             sync_committee_bits,
             sync_committee_signature: sync_committee_signature.into(),
@@ -2987,7 +3834,12 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
     }
 
     fn register_validators(&mut self, current_epoch: Epoch) {
-        if let Some(last_registration_epoch) = self.last_registration_epoch {
+        let registration_version = self.proposer_configs.registration_version();
+
+        if let Some(last_registration_epoch) = self
+            .last_registration_epoch
+            .filter(|_| self.last_registration_version == registration_version)
+        {
             let next_registration_epoch =
                 last_registration_epoch + EPOCHS_PER_VALIDATOR_REGISTRATION_SUBMISSION;
 
@@ -3000,7 +3852,7 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
         let chain_config = self.chain_config.clone_arc();
         let proposer_configs = self.proposer_configs.clone_arc();
         let signer = self.signer.clone_arc();
-        let registered_validators = self.registered_validators.clone();
+        let registered_validators = latest_validator_registrations(&self.registered_validators);
         let subnet_service_tx = self.subnet_service_tx.clone();
 
         tokio::spawn(async move {
@@ -3008,8 +3860,7 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
 
             ToSubnetService::SetRegisteredValidators(
                 registered_validators
-                    .values()
-                    .flat_map(BTreeMap::keys)
+                    .keys()
                     .copied()
                     .chain(pubkeys.iter().copied())
                     .collect(),
@@ -3048,11 +3899,7 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
             let signed_registrations = registrations
                 .into_iter()
                 .zip(signatures)
-                .chain(
-                    registered_validators
-                        .into_values()
-                        .flat_map(BTreeMap::into_values),
-                )
+                .chain(registered_validators.into_values())
                 .map(|(message, signature)| SignedValidatorRegistrationV1 {
                     message,
                     signature: signature.into(),
@@ -3070,6 +3917,7 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
         });
 
         self.last_registration_epoch = Some(current_epoch);
+        self.last_registration_version = registration_version;
     }
 
     fn fee_recipient(
@@ -3088,6 +3936,42 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
             })
     }
 
+    // Builders commonly set their own address as the fee recipient and pay the proposer with
+    // a transaction at the end of the payload, so mismatches in builder payloads are expected with
+    // some relays. They are reported the same way so that users can tell them apart in metrics.
+    fn check_fee_recipient(
+        &self,
+        state: &BeaconState<P>,
+        proposer_index: ValidatorIndex,
+        payload_type: &'static str,
+        actual: ExecutionAddress,
+    ) -> Result<()> {
+        let expected = self.fee_recipient(state, proposer_index)?;
+
+        if actual == expected {
+            return Ok(());
+        }
+
+        let error = Error::<P>::FeeRecipientMismatch {
+            payload_type,
+            expected,
+            actual,
+        };
+
+        warn!(
+            "{error} (proposer index: {proposer_index}, slot: {})",
+            state.slot()
+        );
+
+        if let Some(metrics) = self.metrics.as_ref() {
+            metrics.register_fee_recipient_mismatch(payload_type);
+        }
+
+        ensure!(!self.validator_config.strict_fee_recipient, error);
+
+        Ok(())
+    }
+
     async fn publish_signed_blinded_block(
         &mut self,
         block: &SignedBlindedBeaconBlock<P>,
@@ -3174,6 +4058,32 @@ impl<P: Preset, W: Wait + Sync> Validator<P, W> {
             self.eth1_chain.track_collection_metrics(metrics);
         }
     }
+
+    fn track_withdrawal_sweep_metrics(&self) {
+        let validator_indices = &self.validator_config.withdrawal_sweep_validator_indices;
+
+        if validator_indices.is_empty() {
+            return;
+        }
+
+        let Some(metrics) = self.metrics.as_ref() else {
+            return;
+        };
+
+        let state = self.controller.head_state().value;
+
+        let positions = tokio::task::block_in_place(|| {
+            withdrawal_sweep::positions(&self.chain_config, &state, validator_indices)
+        });
+
+        for position in positions {
+            metrics.set_withdrawal_sweep_position(
+                position.validator_index,
+                position.validators_ahead,
+                position.estimated_time,
+            );
+        }
+    }
 }
 
 struct ValidatorVote {
@@ -3202,6 +4112,30 @@ enum VoteSummary {
     },
 }
 
+// The same validator may be registered in multiple epochs.
+// Only the registration with the latest timestamp should be submitted to relays.
+fn latest_validator_registrations(
+    registered_validators: &BTreeMap<
+        Epoch,
+        BTreeMap<PublicKeyBytes, (ValidatorRegistrationV1, Signature)>,
+    >,
+) -> BTreeMap<PublicKeyBytes, (ValidatorRegistrationV1, Signature)> {
+    let mut latest = BTreeMap::<_, (ValidatorRegistrationV1, Signature)>::new();
+
+    for (pubkey, registration) in registered_validators.values().flatten() {
+        latest
+            .entry(*pubkey)
+            .and_modify(|existing| {
+                if existing.0.timestamp <= registration.0.timestamp {
+                    *existing = *registration;
+                }
+            })
+            .or_insert(*registration);
+    }
+
+    latest
+}
+
 // Use `BTreeMap` to make grouping deterministic for snapshot testing.
 // There is no equivalent of `Itertools::into_group_map` that collects into a `BTreeMap`.
 // See <https://github.com/rust-itertools/itertools/issues/520>.
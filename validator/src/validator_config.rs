@@ -1,14 +1,55 @@
+use core::time::Duration;
 use std::path::PathBuf;
 
 use educe::Educe;
-use types::phase0::primitives::{ExecutionAddress, H256};
+use reqwest::Url;
+use types::phase0::primitives::{ExecutionAddress, ValidatorIndex, H256};
 
 #[derive(Clone, Debug, Educe)]
 #[educe(Default)]
 pub struct ValidatorConfig {
+    /// URL of distributed validator middleware (Charon, SSV, Diva) if validator keys are shares
+    /// of distributed validators.
+    ///
+    /// Signatures produced with key shares are partial. Partial selection proofs are exchanged
+    /// for combined ones through the selection endpoints of the middleware.
+    /// Aggregation duties are decided using the combined selection proofs.
+    pub distributed_middleware_url: Option<Url>,
     pub graffiti: Vec<H256>,
     #[educe(Default = 32)]
     pub max_empty_slots: u64,
+    /// How long before the start of a local proposal slot to send `engine_forkchoiceUpdated`
+    /// with payload attributes. Clamped to the duration of a slot.
+    #[educe(Default(expression = "Duration::from_secs(12)"))]
+    pub prepare_payload_lookahead: Duration,
+    /// How long after aggregates are due to publish own attestations again if they have not been
+    /// seen in any aggregate by then. Attestations are republished no later than the last tick of
+    /// the slot. Disabled if [`None`].
+    pub attestation_rebroadcast_delay: Option<Duration>,
     pub suggested_fee_recipient: ExecutionAddress,
+    /// Whether to discard execution payloads whose fee recipient differs from the configured one.
+    /// Mismatches are always logged and counted in metrics.
+    pub strict_fee_recipient: bool,
+    /// Whether to include gas limits from proposer configs in payload attributes.
+    /// The field is not part of the Engine API and may be rejected by execution clients.
+    pub payload_attributes_gas_limit: bool,
+    /// Whether to refuse to publish blocks if fork choice already contains another block from
+    /// the same proposer in the same slot. Protects against slashing by duplicate validator setups.
+    #[educe(Default = true)]
+    pub block_equivocation_check: bool,
+    /// Whether to refuse to sign attestations for validators that already had a subnet attestation
+    /// accepted for the same target epoch. Complements slashing protection, which only knows about
+    /// attestations signed by this node.
+    #[educe(Default = true)]
+    pub attestation_equivocation_check: bool,
+    /// Whether to refuse to propose blocks while the local clock appears to be grossly skewed.
+    /// Disabled by default because the estimate is based on arrival times of gossip blocks.
+    pub refuse_proposals_on_clock_skew: bool,
     pub keystore_storage_password_file: Option<PathBuf>,
+    /// Whether to forward keystore operations of the Keymanager API to the keymanager APIs of
+    /// Web3Signer instances instead of performing them locally.
+    pub keymanager_web3signer_proxy: bool,
+    /// Validators whose positions in the withdrawal sweep are tracked in metrics and reported by
+    /// `/grandine/validator/withdrawal_sweep`.
+    pub withdrawal_sweep_validator_indices: Vec<ValidatorIndex>,
 }
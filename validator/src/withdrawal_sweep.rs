@@ -0,0 +1,154 @@
+use helper_functions::{accessors, misc, predicates};
+use itertools::Itertools as _;
+use serde::Serialize;
+use typenum::Unsigned as _;
+use types::{
+    combined::BeaconState,
+    config::Config,
+    phase0::primitives::{Slot, UnixSeconds, ValidatorIndex},
+    preset::Preset,
+    traits::BeaconState as _,
+};
+
+/// Where a validator is in the withdrawal sweep and when the sweep is expected to reach it.
+///
+/// Estimates assume a block in every slot and no changes to balances.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
+pub struct WithdrawalSweepPosition {
+    pub validator_index: ValidatorIndex,
+    /// Whether the validator has a balance to withdraw in the current state.
+    /// The sweep passes over validators without one.
+    pub withdrawable: bool,
+    /// Number of validators the sweep has to go through before reaching this one.
+    pub validators_ahead: u64,
+    /// Number of blocks that will be proposed before the one whose sweep reaches this validator.
+    pub blocks_ahead: u64,
+    pub estimated_slot: Slot,
+    pub estimated_time: UnixSeconds,
+}
+
+/// Computes sweep positions of `validator_indices` from `next_withdrawal_validator_index` in `state`.
+///
+/// Returns nothing for states before Capella. Indices of nonexistent validators are skipped.
+/// This iterates over the whole validator registry, so it should not be called in `async` tasks.
+#[must_use]
+pub fn positions<P: Preset>(
+    config: &Config,
+    state: &BeaconState<P>,
+    validator_indices: &[ValidatorIndex],
+) -> Vec<WithdrawalSweepPosition> {
+    let Some(post_capella_state) = state.post_capella() else {
+        return vec![];
+    };
+
+    let epoch = accessors::get_current_epoch(state);
+
+    let withdrawable = state
+        .validators()
+        .into_iter()
+        .zip(state.balances().into_iter().copied())
+        .map(|(validator, balance)| {
+            predicates::is_fully_withdrawable_validator(validator, balance, epoch)
+                || predicates::is_partially_withdrawable_validator::<P>(validator, balance)
+        })
+        .collect_vec();
+
+    sweep::<P>(
+        post_capella_state.next_withdrawal_validator_index(),
+        &withdrawable,
+        validator_indices,
+    )
+    .into_iter()
+    .map(|(validator_index, validators_ahead, blocks_ahead)| {
+        let estimated_slot = state.slot() + 1 + blocks_ahead;
+
+        WithdrawalSweepPosition {
+            validator_index,
+            withdrawable: withdrawable[usize::try_from(validator_index)
+                .expect("sweep only returns indices of validators in the registry")],
+            validators_ahead,
+            blocks_ahead,
+            estimated_slot,
+            estimated_time: misc::compute_timestamp_at_slot(config, state, estimated_slot),
+        }
+    })
+    .collect()
+}
+
+// Follows `get_expected_withdrawals` block by block. Each block goes through at most
+// `MAX_VALIDATORS_PER_WITHDRAWALS_SWEEP` validators and stops early once it has
+// `MAX_WITHDRAWALS_PER_PAYLOAD` withdrawals.
+fn sweep<P: Preset>(
+    next_withdrawal_validator_index: ValidatorIndex,
+    withdrawable: &[bool],
+    validator_indices: &[ValidatorIndex],
+) -> Vec<(ValidatorIndex, u64, u64)> {
+    let total_validators = withdrawable.len();
+
+    let Some(start) = usize::try_from(next_withdrawal_validator_index)
+        .ok()
+        .filter(|start| *start < total_validators)
+    else {
+        return vec![];
+    };
+
+    let mut positions = vec![None; validator_indices.len()];
+    let mut blocks_ahead = 0;
+    let mut validators_in_block = 0;
+    let mut withdrawals_in_block = 0;
+
+    for (index, validators_ahead) in (start..total_validators).chain(0..start).zip(0..) {
+        for (position, validator_index) in positions.iter_mut().zip(validator_indices) {
+            if usize::try_from(*validator_index) == Ok(index) {
+                *position = Some((*validator_index, validators_ahead, blocks_ahead));
+            }
+        }
+
+        if positions.iter().all(Option::is_some) {
+            break;
+        }
+
+        validators_in_block += 1;
+
+        if withdrawable[index] {
+            withdrawals_in_block += 1;
+        }
+
+        if validators_in_block == P::MAX_VALIDATORS_PER_WITHDRAWALS_SWEEP
+            || withdrawals_in_block == P::MaxWithdrawalsPerPayload::U64
+        {
+            blocks_ahead += 1;
+            validators_in_block = 0;
+            withdrawals_in_block = 0;
+        }
+    }
+
+    positions.into_iter().flatten().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use types::preset::Minimal;
+
+    use super::*;
+
+    #[test]
+    fn sweep_counts_blocks_until_validators_are_reached() {
+        // `MAX_WITHDRAWALS_PER_PAYLOAD` and `MAX_VALIDATORS_PER_WITHDRAWALS_SWEEP` are 4 and 16
+        // in the minimal preset.
+        let mut withdrawable = vec![true; 40];
+        withdrawable[20..36].fill(false);
+
+        assert_eq!(
+            sweep::<Minimal>(10, &withdrawable, &[10, 13, 14, 30, 37, 5, 100]),
+            [
+                (10, 0, 0),
+                (13, 3, 0),
+                (14, 4, 1),
+                (30, 20, 2),
+                (37, 27, 3),
+                (5, 35, 5),
+            ],
+        );
+    }
+}